@@ -4,7 +4,9 @@ use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
-use news_core::changes::{AdminAction, ChangeRequest, ChangeStatus, ChangeStore};
+use news_core::changes::{
+    preview_diff, recheck_preview, AdminAction, ChangeRequest, ChangeStatus, ChangeStore,
+};
 use news_core::config::{ConfigStore, DynamicFeed};
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -88,6 +90,8 @@ pub async fn handle_command(
 
     // Create a change request
     let change_id = uuid::Uuid::new_v4().to_string();
+    // Categories live in the server's SQLite only, so category actions show as conflicts
+    let diff = preview_diff(&current_config, None, &interpretation.actions);
     let change = ChangeRequest {
         change_id: change_id.clone(),
         status: ChangeStatus::Preview,
@@ -95,6 +99,10 @@ pub async fn handle_command(
         interpretation: interpretation.interpretation.clone(),
         actions: interpretation.actions,
         preview_config: Some(current_config),
+        preview_diff: Some(diff),
+        validation: None,
+        parent_change_id: None,
+        version: 1,
         created_at: chrono::Utc::now().to_rfc3339(),
     };
 
@@ -109,7 +117,8 @@ pub async fn handle_command(
             "change_id": change_id,
             "interpretation": interpretation.interpretation,
             "confidence": interpretation.confidence,
-            "actions": change.actions
+            "actions": change.actions,
+            "preview_diff": change.preview_diff
         })),
     )
         .into_response()
@@ -161,11 +170,34 @@ pub async fn apply_change(
             .into_response();
     }
 
+    // Re-diff against the current config; actions whose preconditions no longer hold
+    // are reported as conflicts and skipped
+    let current_config = match state.config_store.get_service_config().await {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(error = %e, "Failed to load service config");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to load config"})),
+            )
+                .into_response();
+        }
+    };
+    let checks = recheck_preview(
+        change.preview_diff.as_deref(),
+        preview_diff(&current_config, None, &change.actions),
+    );
+
     // Apply each action
     let mut applied = 0;
     let mut errors = Vec::new();
+    let mut conflicts = Vec::new();
 
-    for action in &change.actions {
+    for (action, check) in change.actions.iter().zip(checks) {
+        if check.conflict.is_some() {
+            conflicts.push(check);
+            continue;
+        }
         match apply_action(&state.config_store, action).await {
             Ok(()) => applied += 1,
             Err(e) => errors.push(format!("{:?}: {}", action, e)),
@@ -178,14 +210,15 @@ pub async fn apply_change(
         .update_status(&change_id, ChangeStatus::Applied)
         .await;
 
-    info!(change_id = %change_id, applied, errors = errors.len(), "Change applied");
+    info!(change_id = %change_id, applied, errors = errors.len(), conflicts = conflicts.len(), "Change applied");
 
     (
         StatusCode::OK,
         Json(serde_json::json!({
             "status": "applied",
             "applied": applied,
-            "errors": errors
+            "errors": errors,
+            "conflicts": conflicts
         })),
     )
         .into_response()
@@ -233,6 +266,9 @@ async fn apply_action(config_store: &ConfigStore, action: &AdminAction) -> Resul
                 category: category.clone(),
                 enabled: true,
                 added_by: Some("admin-chat".into()),
+                max_articles_per_fetch: None,
+                auto_translate: false,
+                category_overrides: None,
             };
             config_store
                 .put_feed(&feed)
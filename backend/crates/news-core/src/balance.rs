@@ -0,0 +1,104 @@
+use std::collections::VecDeque;
+
+/// Compute a display order that keeps any one source from dominating a page.
+///
+/// `sources` is the source name of each article in recency order. The result is a
+/// permutation of indices in which no source appears more than `max_per_source`
+/// times within any `window` consecutive positions, as long as some other source
+/// is still available to fill the slot. Articles are only moved later, never
+/// dropped, and each source keeps its own relative order, so the page as a whole
+/// stays roughly newest-first and cursor pagination is unaffected.
+pub fn balance_order(sources: &[&str], max_per_source: usize, window: usize) -> Vec<usize> {
+    if max_per_source == 0 || window == 0 {
+        return (0..sources.len()).collect();
+    }
+
+    let mut pending: VecDeque<usize> = (0..sources.len()).collect();
+    let mut order: Vec<usize> = Vec::with_capacity(sources.len());
+
+    while !pending.is_empty() {
+        let start = order.len().saturating_sub(window - 1);
+        let recent = &order[start..];
+        let pos = pending
+            .iter()
+            .position(|&i| {
+                recent.iter().filter(|&&r| sources[r] == sources[i]).count() < max_per_source
+            })
+            .unwrap_or(0);
+        if let Some(i) = pending.remove(pos) {
+            order.push(i);
+        }
+    }
+
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_in_any_window(sources: &[&str], order: &[usize], window: usize) -> usize {
+        let ordered: Vec<&str> = order.iter().map(|&i| sources[i]).collect();
+        ordered
+            .windows(window.min(ordered.len()))
+            .map(|w| {
+                w.iter()
+                    .map(|s| w.iter().filter(|t| *t == s).count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn already_balanced_is_unchanged() {
+        let sources = ["A", "B", "C", "A", "B", "C"];
+        assert_eq!(balance_order(&sources, 3, 30), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn caps_flooding_source_per_window() {
+        // 20 from a firehose first, then 10 from other sources
+        let mut sources = vec!["Yahoo"; 20];
+        sources.extend(["NHK", "ITmedia", "GIGAZINE", "BBC", "CNN"].repeat(2));
+        let order = balance_order(&sources, 3, 10);
+
+        assert_eq!(order.len(), sources.len());
+        let first_ten: Vec<&str> = order[..10].iter().map(|&i| sources[i]).collect();
+        assert!(first_ten.iter().filter(|s| **s == "Yahoo").count() <= 3);
+        // The leading articles are still the newest ones
+        assert_eq!(order[0], 0);
+    }
+
+    #[test]
+    fn keeps_every_article_and_per_source_order() {
+        let mut sources = vec!["Yahoo"; 25];
+        sources.extend(["NHK", "BBC"].repeat(5));
+        let order = balance_order(&sources, 3, 30);
+
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..sources.len()).collect::<Vec<_>>());
+
+        for source in ["Yahoo", "NHK", "BBC"] {
+            let positions: Vec<usize> = order.iter().copied().filter(|&i| sources[i] == source).collect();
+            assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+
+    #[test]
+    fn falls_back_when_only_one_source_remains() {
+        let sources = ["Yahoo"; 8];
+        let order = balance_order(&sources, 3, 30);
+        assert_eq!(order, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn mixed_page_respects_cap_while_alternatives_exist() {
+        let sources: Vec<&str> = (0..30).map(|i| if i % 3 == 2 { "NHK" } else { "Yahoo" }).collect();
+        let order = balance_order(&sources, 3, 6);
+        // With 2:1 Yahoo/NHK there are always alternatives in the first half
+        assert!(max_in_any_window(&sources, &order[..12], 6) <= 3);
+    }
+}
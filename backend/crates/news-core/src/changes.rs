@@ -1,4 +1,4 @@
-use crate::config::ServiceConfig;
+use crate::config::{DynamicFeed, FeatureFlags, ServiceConfig};
 #[cfg(feature = "dynamo")]
 use crate::error::{AppError, Result};
 #[cfg(feature = "dynamo")]
@@ -73,12 +73,19 @@ pub enum AdminAction {
         id: String,
         label_ja: String,
     },
+    /// Articles and feeds in the category move to `reassign_to`, which is required
+    /// while anything still uses it.
     RemoveCategory {
         id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reassign_to: Option<String>,
     },
+    /// With `new_id`, the id changes too and articles and feeds follow it.
     RenameCategory {
         id: String,
         label_ja: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        new_id: Option<String>,
     },
     ReorderCategories {
         order: Vec<String>,
@@ -95,9 +102,535 @@ pub struct ChangeRequest {
     pub actions: Vec<AdminAction>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preview_config: Option<ServiceConfig>,
+    /// Per-action effect computed when the change was previewed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_diff: Option<Vec<ActionDiff>>,
+    /// Checks against the outside world made when the change was created; see
+    /// `ActionValidation`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<Vec<ActionValidation>>,
+    /// The rejected change this one revises.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_change_id: Option<String>,
+    /// 1 for a new change, parent's + 1 for a revision.
+    #[serde(default = "first_version")]
+    pub version: u32,
     pub created_at: String,
 }
 
+fn first_version() -> u32 {
+    1
+}
+
+impl ChangeRequest {
+    /// Validations that failed; applying these actions needs `force`.
+    pub fn failed_validations(&self) -> Vec<&ActionValidation> {
+        self.validation.iter().flatten().filter(|v| !v.passed).collect()
+    }
+}
+
+/// What validating `ChangeRequest::actions[action]` found. Actions without anything to
+/// check have no entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionValidation {
+    pub action: usize,
+    pub passed: bool,
+    pub check: ActionCheck,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ActionCheck {
+    /// AddFeed: the URL was fetched and parsed as a feed.
+    FeedProbe {
+        found: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        item_count: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// RemoveCategory: what still refers to the category. Fails when something does
+    /// and there is no `reassign_to` to move it to.
+    CategoryUsage {
+        articles: i64,
+        feeds: i64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reassign_to: Option<String>,
+    },
+    /// ReorderCategories: ids in the order that aren't categories.
+    CategoryOrder { unknown: Vec<String> },
+}
+
+impl ActionValidation {
+    pub fn new(action: usize, check: ActionCheck) -> Self {
+        let passed = match &check {
+            ActionCheck::FeedProbe { found, .. } => *found,
+            ActionCheck::CategoryUsage { articles, feeds, reassign_to } => {
+                reassign_to.is_some() || articles + feeds == 0
+            }
+            ActionCheck::CategoryOrder { unknown } => unknown.is_empty(),
+        };
+        Self { action, passed, check }
+    }
+}
+
+/// `ActionCheck::CategoryOrder` for a reorder to `order` given the existing ids.
+pub fn check_category_order(order: &[String], existing: &[String]) -> ActionCheck {
+    ActionCheck::CategoryOrder {
+        unknown: order.iter().filter(|id| !existing.contains(id)).cloned().collect(),
+    }
+}
+
+/// What a single action changes, as old → new values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConfigDiff {
+    FeedAdded {
+        url: String,
+        source: String,
+        category: String,
+    },
+    FeedRemoved {
+        feed_id: String,
+        url: String,
+        source: String,
+    },
+    FeedEnabled {
+        feed_id: String,
+        source: String,
+        old: bool,
+        new: bool,
+    },
+    /// `old` is None for a feature the config doesn't track.
+    FlagToggled {
+        feature: String,
+        old: Option<bool>,
+        new: bool,
+    },
+    ThresholdChanged {
+        old: f64,
+        new: f64,
+        /// Setting the threshold also turns grouping on.
+        enables_grouping: bool,
+    },
+    CategoryAdded {
+        id: String,
+        label_ja: String,
+    },
+    CategoryRemoved {
+        id: String,
+        label_ja: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reassign_to: Option<String>,
+    },
+    CategoryRenamed {
+        id: String,
+        old: String,
+        new: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        new_id: Option<String>,
+    },
+    CategoriesReordered {
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+}
+
+/// Preview of `ChangeRequest::actions[action]`: either its diff or, when its
+/// precondition doesn't hold, the conflict.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionDiff {
+    pub action: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<ConfigDiff>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<String>,
+}
+
+/// Diff `actions` against `config` and the ordered `(id, label_ja)` categories, if
+/// known. Actions are simulated in order, so each one sees the effect of the ones
+/// before it.
+pub fn preview_diff(
+    config: &ServiceConfig,
+    categories: Option<&[(String, String)]>,
+    actions: &[AdminAction],
+) -> Vec<ActionDiff> {
+    let mut feeds = config.feeds.clone();
+    let mut flags = config.features.clone();
+    let mut categories = categories.map(<[_]>::to_vec);
+    actions
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            match simulate(action, &mut feeds, &mut flags, categories.as_mut()) {
+                Ok(diff) => ActionDiff {
+                    action: i,
+                    diff: Some(diff),
+                    conflict: None,
+                },
+                Err(conflict) => ActionDiff {
+                    action: i,
+                    diff: None,
+                    conflict: Some(conflict),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Compare a fresh `preview_diff` against the one shown at preview time: an action
+/// whose effect changed since then becomes a conflict rather than being applied.
+pub fn recheck_preview(
+    previewed: Option<&[ActionDiff]>,
+    current: Vec<ActionDiff>,
+) -> Vec<ActionDiff> {
+    let Some(previewed) = previewed else {
+        return current;
+    };
+    current
+        .into_iter()
+        .map(|mut entry| {
+            let before = previewed.iter().find(|p| p.action == entry.action);
+            if entry.conflict.is_none() && before.is_some_and(|p| p.diff != entry.diff) {
+                entry.diff = None;
+                entry.conflict = Some("Config changed since preview".into());
+            }
+            entry
+        })
+        .collect()
+}
+
+fn simulate(
+    action: &AdminAction,
+    feeds: &mut Vec<DynamicFeed>,
+    flags: &mut FeatureFlags,
+    categories: Option<&mut Vec<(String, String)>>,
+) -> std::result::Result<ConfigDiff, String> {
+    match action {
+        AdminAction::AddFeed {
+            url,
+            source,
+            category,
+        } => {
+            if feeds.iter().any(|f| f.url == *url) {
+                return Err(format!("Feed already exists: {}", url));
+            }
+            feeds.push(DynamicFeed {
+                feed_id: String::new(),
+                url: url.clone(),
+                source: source.clone(),
+                category: category.clone(),
+                enabled: true,
+                added_by: None,
+                max_articles_per_fetch: None,
+                auto_translate: false,
+                category_overrides: None,
+            });
+            Ok(ConfigDiff::FeedAdded {
+                url: url.clone(),
+                source: source.clone(),
+                category: category.clone(),
+            })
+        }
+        AdminAction::RemoveFeed { feed_id } => {
+            let pos = feeds
+                .iter()
+                .position(|f| f.feed_id == *feed_id)
+                .ok_or_else(|| format!("Feed not found: {}", feed_id))?;
+            let feed = feeds.remove(pos);
+            Ok(ConfigDiff::FeedRemoved {
+                feed_id: feed.feed_id,
+                url: feed.url,
+                source: feed.source,
+            })
+        }
+        AdminAction::EnableFeed { feed_id } => set_feed_enabled(feeds, feed_id, true),
+        AdminAction::DisableFeed { feed_id } => set_feed_enabled(feeds, feed_id, false),
+        AdminAction::ToggleFeature { feature, enabled } => Ok(ConfigDiff::FlagToggled {
+            feature: feature.clone(),
+            old: set_flag(flags, feature, *enabled),
+            new: *enabled,
+        }),
+        AdminAction::SetGroupingThreshold { threshold } => {
+            let diff = ConfigDiff::ThresholdChanged {
+                old: flags.grouping_threshold,
+                new: *threshold,
+                enables_grouping: !flags.grouping_enabled,
+            };
+            flags.grouping_threshold = *threshold;
+            flags.grouping_enabled = true;
+            Ok(diff)
+        }
+        AdminAction::AddCategory { .. }
+        | AdminAction::RemoveCategory { .. }
+        | AdminAction::RenameCategory { .. }
+        | AdminAction::ReorderCategories { .. } => {
+            let categories = categories.ok_or("Category management is not available here")?;
+            simulate_category(action, categories)
+        }
+    }
+}
+
+fn simulate_category(
+    action: &AdminAction,
+    categories: &mut Vec<(String, String)>,
+) -> std::result::Result<ConfigDiff, String> {
+    let not_found = |id: &str| format!("Category not found: {}", id);
+    match action {
+        AdminAction::AddCategory { id, label_ja } => {
+            if categories.iter().any(|(c, _)| c == id) {
+                return Err(format!("Category already exists: {}", id));
+            }
+            categories.push((id.clone(), label_ja.clone()));
+            Ok(ConfigDiff::CategoryAdded {
+                id: id.clone(),
+                label_ja: label_ja.clone(),
+            })
+        }
+        AdminAction::RemoveCategory { id, reassign_to } => {
+            let pos = categories
+                .iter()
+                .position(|(c, _)| c == id)
+                .ok_or_else(|| not_found(id))?;
+            if let Some(target) = reassign_to {
+                if target == id || !categories.iter().any(|(c, _)| c == target) {
+                    return Err(format!("Cannot reassign to category: {}", target));
+                }
+            }
+            let (id, label_ja) = categories.remove(pos);
+            Ok(ConfigDiff::CategoryRemoved { id, label_ja, reassign_to: reassign_to.clone() })
+        }
+        AdminAction::RenameCategory { id, label_ja, new_id } => {
+            let new_id = new_id.as_ref().filter(|n| *n != id);
+            if let Some(new_id) = new_id {
+                if categories.iter().any(|(c, _)| c == new_id) {
+                    return Err(format!("Category already exists: {}", new_id));
+                }
+            }
+            let (current, label) = categories
+                .iter_mut()
+                .find(|(c, _)| c == id)
+                .ok_or_else(|| not_found(id))?;
+            if let Some(new_id) = new_id {
+                *current = new_id.clone();
+            }
+            let old = std::mem::replace(label, label_ja.clone());
+            Ok(ConfigDiff::CategoryRenamed {
+                id: id.clone(),
+                old,
+                new: label_ja.clone(),
+                new_id: new_id.cloned(),
+            })
+        }
+        AdminAction::ReorderCategories { order } => {
+            if let Some(unknown) = order
+                .iter()
+                .find(|id| !categories.iter().any(|(c, _)| c == *id))
+            {
+                return Err(not_found(unknown));
+            }
+            let old: Vec<String> = categories.iter().map(|(c, _)| c.clone()).collect();
+            // Listed categories first, the rest keep their relative order
+            categories
+                .sort_by_key(|(c, _)| order.iter().position(|o| o == c).unwrap_or(order.len()));
+            Ok(ConfigDiff::CategoriesReordered {
+                old,
+                new: categories.iter().map(|(c, _)| c.clone()).collect(),
+            })
+        }
+        _ => unreachable!("not a category action"),
+    }
+}
+
+fn set_feed_enabled(
+    feeds: &mut [DynamicFeed],
+    feed_id: &str,
+    enabled: bool,
+) -> std::result::Result<ConfigDiff, String> {
+    let feed = feeds
+        .iter_mut()
+        .find(|f| f.feed_id == feed_id)
+        .ok_or_else(|| format!("Feed not found: {}", feed_id))?;
+    let old = std::mem::replace(&mut feed.enabled, enabled);
+    Ok(ConfigDiff::FeedEnabled {
+        feed_id: feed.feed_id.clone(),
+        source: feed.source.clone(),
+        old,
+        new: enabled,
+    })
+}
+
+/// Set a feature flag by its stored name; returns the previous value, None for
+/// features `FeatureFlags` doesn't track.
+fn set_flag(flags: &mut FeatureFlags, feature: &str, enabled: bool) -> Option<bool> {
+    let slot = match feature {
+        "grouping" => &mut flags.grouping_enabled,
+        "ogp_enrichment" => &mut flags.ogp_enrichment_enabled,
+        "enrichment_research" => &mut flags.enrichment_research_enabled,
+        "enrichment_image" => &mut flags.enrichment_image_enabled,
+        "enrichment_video" => &mut flags.enrichment_video_enabled,
+        "sentiment" => &mut flags.sentiment_enabled,
+        "image_degradation" => &mut flags.image_degradation.enabled,
+        "importance_weights" => {
+            let old = flags.importance_weights_json.is_some();
+            if !enabled {
+                flags.importance_weights_json = None;
+            }
+            return Some(old);
+        }
+        _ => return None,
+    };
+    Some(std::mem::replace(slot, enabled))
+}
+
+/// One field that differs between two configs, as serialized old → new values.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
+/// A feed present in both configs with some fields changed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedModification {
+    pub feed_id: String,
+    pub source: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Whole-config comparison, as opposed to the per-action `ConfigDiff`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServiceConfigDiff {
+    pub added_feeds: Vec<DynamicFeed>,
+    pub removed_feeds: Vec<DynamicFeed>,
+    pub modified_feeds: Vec<FeedModification>,
+    pub toggled_features: Vec<FieldChange>,
+    /// Filled by `diff_categories`; `ServiceConfig` doesn't hold the categories.
+    pub category_changes: Vec<ConfigDiff>,
+}
+
+/// `config` and the `(id, label_ja)` categories with `actions` applied in order.
+/// Conflicting actions are skipped, as `apply` would skip them.
+pub fn apply_actions(
+    config: &ServiceConfig,
+    categories: Option<&[(String, String)]>,
+    actions: &[AdminAction],
+) -> (ServiceConfig, Option<Vec<(String, String)>>) {
+    let mut after = config.clone();
+    let mut categories = categories.map(<[_]>::to_vec);
+    for action in actions {
+        let _ = simulate(action, &mut after.feeds, &mut after.features, categories.as_mut());
+    }
+    (after, categories)
+}
+
+/// Feeds are matched by `feed_id`, or by URL for feeds not stored yet (empty id).
+pub fn diff_service_configs(before: &ServiceConfig, after: &ServiceConfig) -> ServiceConfigDiff {
+    fn key(feed: &DynamicFeed) -> &str {
+        if feed.feed_id.is_empty() {
+            &feed.url
+        } else {
+            &feed.feed_id
+        }
+    }
+    let find = |feeds: &[DynamicFeed], k: &str| feeds.iter().find(|f| key(f) == k).cloned();
+
+    let mut diff = ServiceConfigDiff::default();
+    for old in &before.feeds {
+        match find(&after.feeds, key(old)) {
+            None => diff.removed_feeds.push(old.clone()),
+            Some(new) => {
+                let changes = changed_fields(old, &new);
+                if !changes.is_empty() {
+                    diff.modified_feeds.push(FeedModification {
+                        feed_id: new.feed_id,
+                        source: new.source,
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    diff.added_feeds = after
+        .feeds
+        .iter()
+        .filter(|f| find(&before.feeds, key(f)).is_none())
+        .cloned()
+        .collect();
+    diff.toggled_features = changed_fields(&before.features, &after.features);
+    diff
+}
+
+/// Added, removed and renamed categories, then a reorder if the ones in both lists
+/// moved relative to each other.
+pub fn diff_categories(before: &[(String, String)], after: &[(String, String)]) -> Vec<ConfigDiff> {
+    let label = |list: &[(String, String)], id: &str| {
+        list.iter().find(|(c, _)| c == id).map(|(_, l)| l.clone())
+    };
+    let mut changes = Vec::new();
+    for (id, old) in before {
+        match label(after, id) {
+            None => changes.push(ConfigDiff::CategoryRemoved {
+                id: id.clone(),
+                label_ja: old.clone(),
+                reassign_to: None,
+            }),
+            Some(new) if new != *old => changes.push(ConfigDiff::CategoryRenamed {
+                id: id.clone(),
+                old: old.clone(),
+                new,
+                new_id: None,
+            }),
+            Some(_) => {}
+        }
+    }
+    for (id, label_ja) in after {
+        if label(before, id).is_none() {
+            changes.push(ConfigDiff::CategoryAdded {
+                id: id.clone(),
+                label_ja: label_ja.clone(),
+            });
+        }
+    }
+    let ids = |list: &[(String, String)]| list.iter().map(|(c, _)| c.clone()).collect::<Vec<_>>();
+    let kept = |list: &[(String, String)], other: &[(String, String)]| {
+        list.iter()
+            .filter(|(c, _)| label(other, c).is_some())
+            .map(|(c, _)| c.clone())
+            .collect::<Vec<_>>()
+    };
+    if kept(before, after) != kept(after, before) {
+        changes.push(ConfigDiff::CategoriesReordered {
+            old: ids(before),
+            new: ids(after),
+        });
+    }
+    changes
+}
+
+/// Top-level fields whose serialized values differ, in field-name order.
+fn changed_fields<T: Serialize>(before: &T, after: &T) -> Vec<FieldChange> {
+    let to_map = |v: &T| match serde_json::to_value(v) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let (before, after) = (to_map(before), to_map(after));
+    let fields: std::collections::BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old = before.get(field).cloned().unwrap_or_default();
+            let new = after.get(field).cloned().unwrap_or_default();
+            (old != new).then(|| FieldChange {
+                field: field.clone(),
+                old,
+                new,
+            })
+        })
+        .collect()
+}
+
 /// DynamoDB client for change request operations.
 #[cfg(feature = "dynamo")]
 #[derive(Clone)]
@@ -150,6 +683,18 @@ impl ChangeStore {
                 serde_json::to_string(preview).map_err(AppError::SerdeError)?;
             item.insert("preview_config".into(), AttributeValue::S(preview_json));
         }
+        if let Some(ref diff) = change.preview_diff {
+            let diff_json = serde_json::to_string(diff).map_err(AppError::SerdeError)?;
+            item.insert("preview_diff".into(), AttributeValue::S(diff_json));
+        }
+        if let Some(ref validation) = change.validation {
+            let validation_json = serde_json::to_string(validation).map_err(AppError::SerdeError)?;
+            item.insert("validation".into(), AttributeValue::S(validation_json));
+        }
+        if let Some(ref parent) = change.parent_change_id {
+            item.insert("parent_change_id".into(), AttributeValue::S(parent.clone()));
+        }
+        item.insert("version".into(), AttributeValue::N(change.version.to_string()));
 
         self.client
             .put_item()
@@ -251,6 +796,20 @@ fn item_to_change(item: &HashMap<String, AttributeValue>) -> Option<ChangeReques
         .get("preview_config")
         .and_then(|v| v.as_s().ok())
         .and_then(|s| serde_json::from_str(s).ok());
+    let preview_diff = item
+        .get("preview_diff")
+        .and_then(|v| v.as_s().ok())
+        .and_then(|s| serde_json::from_str(s).ok());
+    let validation = item
+        .get("validation")
+        .and_then(|v| v.as_s().ok())
+        .and_then(|s| serde_json::from_str(s).ok());
+    let parent_change_id = item.get("parent_change_id").and_then(|v| v.as_s().ok()).cloned();
+    let version = item
+        .get("version")
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse().ok())
+        .unwrap_or_else(first_version);
 
     Some(ChangeRequest {
         change_id,
@@ -259,6 +818,10 @@ fn item_to_change(item: &HashMap<String, AttributeValue>) -> Option<ChangeReques
         interpretation,
         actions,
         preview_config,
+        preview_diff,
+        validation,
+        parent_change_id,
+        version,
         created_at,
     })
 }
@@ -314,6 +877,10 @@ mod tests {
                 category: "tech".into(),
             }],
             preview_config: None,
+            preview_diff: None,
+            validation: None,
+            parent_change_id: None,
+            version: 1,
             created_at: "2025-01-01T00:00:00Z".into(),
         };
         let json = serde_json::to_string(&change).unwrap();
@@ -322,4 +889,244 @@ mod tests {
         assert_eq!(parsed.status, ChangeStatus::Preview);
         assert_eq!(parsed.actions.len(), 1);
     }
+
+    fn fixture_config() -> ServiceConfig {
+        let feed = |id: &str, url: &str, enabled: bool| DynamicFeed {
+            feed_id: id.into(),
+            url: url.into(),
+            source: id.to_uppercase(),
+            category: "tech".into(),
+            enabled,
+            added_by: None,
+            max_articles_per_fetch: None,
+            auto_translate: false,
+            category_overrides: None,
+        };
+        ServiceConfig {
+            feeds: vec![
+                feed("nhk", "https://www.nhk.or.jp/rss/news/cat0.xml", true),
+                feed("itmedia", "https://rss.itmedia.co.jp/rss/2.0/itmedia_all.xml", false),
+            ],
+            features: FeatureFlags::default(),
+        }
+    }
+
+    fn fixture_categories() -> Vec<(String, String)> {
+        vec![("tech".into(), "テクノロジー".into()), ("business".into(), "ビジネス".into())]
+    }
+
+    #[test]
+    fn preview_diff_reports_old_and_new_values() {
+        let actions = vec![
+            AdminAction::AddFeed {
+                url: "https://example.com/feed".into(),
+                source: "Example".into(),
+                category: "tech".into(),
+            },
+            AdminAction::EnableFeed { feed_id: "itmedia".into() },
+            AdminAction::RemoveFeed { feed_id: "nhk".into() },
+            AdminAction::ToggleFeature { feature: "grouping".into(), enabled: true },
+            AdminAction::SetGroupingThreshold { threshold: 0.5 },
+            AdminAction::RenameCategory { id: "tech".into(), label_ja: "IT".into(), new_id: None },
+        ];
+        let diff = preview_diff(&fixture_config(), Some(&fixture_categories()), &actions);
+        let diffs: Vec<ConfigDiff> = diff.into_iter().map(|d| d.diff.unwrap()).collect();
+        assert_eq!(
+            diffs,
+            vec![
+                ConfigDiff::FeedAdded {
+                    url: "https://example.com/feed".into(),
+                    source: "Example".into(),
+                    category: "tech".into(),
+                },
+                ConfigDiff::FeedEnabled { feed_id: "itmedia".into(), source: "ITMEDIA".into(), old: false, new: true },
+                ConfigDiff::FeedRemoved {
+                    feed_id: "nhk".into(),
+                    url: "https://www.nhk.or.jp/rss/news/cat0.xml".into(),
+                    source: "NHK".into(),
+                },
+                ConfigDiff::FlagToggled { feature: "grouping".into(), old: Some(false), new: true },
+                // Grouping was already switched on by the previous action
+                ConfigDiff::ThresholdChanged { old: 0.3, new: 0.5, enables_grouping: false },
+                ConfigDiff::CategoryRenamed {
+                    id: "tech".into(),
+                    old: "テクノロジー".into(),
+                    new: "IT".into(),
+                    new_id: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn preview_diff_reports_conflicts_per_action() {
+        let actions = vec![
+            AdminAction::RemoveFeed { feed_id: "nhk".into() },
+            AdminAction::DisableFeed { feed_id: "nhk".into() },
+            AdminAction::AddFeed {
+                url: "https://rss.itmedia.co.jp/rss/2.0/itmedia_all.xml".into(),
+                source: "ITmedia".into(),
+                category: "tech".into(),
+            },
+            AdminAction::ReorderCategories { order: vec!["business".into(), "sports".into()] },
+            AdminAction::ReorderCategories { order: vec!["business".into()] },
+        ];
+        let diff = preview_diff(&fixture_config(), Some(&fixture_categories()), &actions);
+        assert!(diff[0].conflict.is_none());
+        assert_eq!(diff[1].conflict.as_deref(), Some("Feed not found: nhk"));
+        assert!(diff[2].conflict.as_deref().unwrap().starts_with("Feed already exists"));
+        assert_eq!(diff[3].conflict.as_deref(), Some("Category not found: sports"));
+        assert_eq!(
+            diff[4].diff,
+            Some(ConfigDiff::CategoriesReordered {
+                old: vec!["tech".into(), "business".into()],
+                new: vec!["business".into(), "tech".into()],
+            })
+        );
+
+        // Without category state (the DynamoDB admin) category actions can't be previewed
+        let diff = preview_diff(&fixture_config(), None, &actions[3..]);
+        assert!(diff.iter().all(|d| d.conflict.is_some()));
+    }
+
+    #[test]
+    fn category_id_changes_and_reassignments_are_previewed() {
+        let actions = vec![
+            AdminAction::RenameCategory { id: "tech".into(), label_ja: "IT".into(), new_id: Some("it".into()) },
+            AdminAction::RemoveCategory { id: "business".into(), reassign_to: Some("tech".into()) },
+            AdminAction::RemoveCategory { id: "business".into(), reassign_to: Some("it".into()) },
+            AdminAction::RenameCategory { id: "it".into(), label_ja: "IT".into(), new_id: Some("it".into()) },
+        ];
+        let diff = preview_diff(&fixture_config(), Some(&fixture_categories()), &actions);
+        assert_eq!(
+            diff[0].diff,
+            Some(ConfigDiff::CategoryRenamed {
+                id: "tech".into(),
+                old: "テクノロジー".into(),
+                new: "IT".into(),
+                new_id: Some("it".into()),
+            })
+        );
+        // "tech" is gone after the rename
+        assert_eq!(diff[1].conflict.as_deref(), Some("Cannot reassign to category: tech"));
+        assert_eq!(
+            diff[2].diff,
+            Some(ConfigDiff::CategoryRemoved {
+                id: "business".into(),
+                label_ja: "ビジネス".into(),
+                reassign_to: Some("it".into()),
+            })
+        );
+        // Same id is a plain label change
+        assert!(matches!(diff[3].diff, Some(ConfigDiff::CategoryRenamed { new_id: None, .. })));
+
+        let old: AdminAction = serde_json::from_str(r#"{"type":"remove_category","id":"sports"}"#).unwrap();
+        assert!(matches!(old, AdminAction::RemoveCategory { reassign_to: None, .. }));
+    }
+
+    #[test]
+    fn service_config_diff_covers_feeds_features_and_categories() {
+        let actions = vec![
+            AdminAction::AddFeed {
+                url: "https://example.com/feed".into(),
+                source: "Example".into(),
+                category: "tech".into(),
+            },
+            AdminAction::RemoveFeed { feed_id: "nhk".into() },
+            AdminAction::EnableFeed { feed_id: "itmedia".into() },
+            AdminAction::SetGroupingThreshold { threshold: 0.5 },
+            // Conflicts, so it's skipped
+            AdminAction::DisableFeed { feed_id: "missing".into() },
+            AdminAction::AddCategory { id: "sports".into(), label_ja: "スポーツ".into() },
+            AdminAction::ReorderCategories { order: vec!["business".into()] },
+        ];
+        let before = fixture_config();
+        let categories = fixture_categories();
+        let (after, after_categories) = apply_actions(&before, Some(&categories), &actions);
+
+        let diff = diff_service_configs(&before, &after);
+        let urls = |feeds: &[DynamicFeed]| feeds.iter().map(|f| f.url.clone()).collect::<Vec<_>>();
+        assert_eq!(urls(&diff.added_feeds), ["https://example.com/feed"]);
+        assert_eq!(urls(&diff.removed_feeds), ["https://www.nhk.or.jp/rss/news/cat0.xml"]);
+        assert_eq!(
+            diff.modified_feeds,
+            vec![FeedModification {
+                feed_id: "itmedia".into(),
+                source: "ITMEDIA".into(),
+                changes: vec![FieldChange { field: "enabled".into(), old: false.into(), new: true.into() }],
+            }]
+        );
+        let fields: Vec<&str> = diff.toggled_features.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, ["grouping_enabled", "grouping_threshold"]);
+
+        assert_eq!(
+            diff_categories(&categories, &after_categories.unwrap()),
+            vec![
+                ConfigDiff::CategoryAdded { id: "sports".into(), label_ja: "スポーツ".into() },
+                ConfigDiff::CategoriesReordered {
+                    old: vec!["tech".into(), "business".into()],
+                    new: vec!["business".into(), "tech".into(), "sports".into()],
+                },
+            ]
+        );
+        assert!(diff_categories(&categories, &categories).is_empty());
+    }
+
+    #[test]
+    fn recheck_flags_actions_whose_effect_changed() {
+        let actions = vec![
+            AdminAction::EnableFeed { feed_id: "itmedia".into() },
+            AdminAction::ToggleFeature { feature: "ogp_enrichment".into(), enabled: false },
+        ];
+        let mut config = fixture_config();
+        let previewed = preview_diff(&config, None, &actions);
+
+        // Someone enabled the feed in between
+        config.feeds[1].enabled = true;
+        let checked = recheck_preview(Some(&previewed), preview_diff(&config, None, &actions));
+        assert_eq!(checked[0].conflict.as_deref(), Some("Config changed since preview"));
+        assert_eq!(checked[1], previewed[1]);
+
+        // Changes stored before previews existed are checked against the current state only
+        let checked = recheck_preview(None, preview_diff(&config, None, &actions));
+        assert!(checked.iter().all(|d| d.conflict.is_none()));
+    }
+
+    #[test]
+    fn preview_diff_is_optional_in_stored_changes() {
+        let json = r#"{"change_id":"c1","status":"preview","command_text":"x","interpretation":"",
+            "actions":[{"type":"enable_feed","feed_id":"nhk"}],"created_at":"2025-01-01T00:00:00Z"}"#;
+        let change: ChangeRequest = serde_json::from_str(json).unwrap();
+        assert!(change.preview_diff.is_none());
+        assert!(!serde_json::to_string(&change).unwrap().contains("preview_diff"));
+        assert!(change.validation.is_none());
+        assert!(change.failed_validations().is_empty());
+        assert_eq!(change.version, 1);
+        assert!(change.parent_change_id.is_none());
+    }
+
+    #[test]
+    fn unreachable_feeds_and_unknown_categories_fail_validation() {
+        let probe = |found| ActionCheck::FeedProbe { found, item_count: None, error: None };
+        let order = vec!["tech".to_string(), "weather".to_string()];
+        let existing = vec!["tech".to_string(), "general".to_string()];
+        let validation = [
+            ActionValidation::new(0, probe(true)),
+            ActionValidation::new(1, probe(false)),
+            ActionValidation::new(2, ActionCheck::CategoryUsage { articles: 120, feeds: 3, reassign_to: None }),
+            ActionValidation::new(3, check_category_order(&order, &existing)),
+            ActionValidation::new(
+                4,
+                ActionCheck::CategoryUsage { articles: 120, feeds: 3, reassign_to: Some("general".into()) },
+            ),
+            ActionValidation::new(5, ActionCheck::CategoryUsage { articles: 0, feeds: 0, reassign_to: None }),
+        ];
+        let failed: Vec<usize> = validation.iter().filter(|v| !v.passed).map(|v| v.action).collect();
+        assert_eq!(failed, [1, 2, 3]);
+        assert_eq!(validation[3].check, ActionCheck::CategoryOrder { unknown: vec!["weather".into()] });
+
+        let json = serde_json::to_value(&validation[2]).unwrap();
+        assert_eq!(json["check"]["kind"], "category_usage");
+        assert_eq!(json["check"]["articles"], 120);
+    }
 }
@@ -5,7 +5,6 @@ use aws_sdk_dynamodb::types::AttributeValue;
 #[cfg(feature = "dynamo")]
 use aws_sdk_dynamodb::Client;
 use serde::{Deserialize, Serialize};
-#[cfg(feature = "dynamo")]
 use std::collections::HashMap;
 #[cfg(feature = "dynamo")]
 use tracing::info;
@@ -20,6 +19,23 @@ pub struct DynamicFeed {
     pub enabled: bool,
     #[serde(default)]
     pub added_by: Option<String>,
+    /// Cap on articles taken from a single fetch of this feed (None = unlimited).
+    #[serde(default)]
+    pub max_articles_per_fetch: Option<u32>,
+    /// Pre-translate this feed's articles so list views can show translated titles.
+    #[serde(default)]
+    pub auto_translate: bool,
+    /// Keyword rules that file some of this feed's articles under another category.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category_overrides: Option<Vec<CategoryOverride>>,
+}
+
+/// Articles whose title or description contains any of `keywords` (case-insensitive)
+/// go to `category` instead of their feed's category. The first matching rule wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryOverride {
+    pub keywords: Vec<String>,
+    pub category: String,
 }
 
 /// Feature flags stored in DynamoDB ConfigTable.
@@ -28,6 +44,98 @@ pub struct FeatureFlags {
     pub grouping_enabled: bool,
     pub grouping_threshold: f64,
     pub ogp_enrichment_enabled: bool,
+    /// Per-agent switches for the server's enrichment agent.
+    #[serde(default = "default_true")]
+    pub enrichment_research_enabled: bool,
+    #[serde(default = "default_true")]
+    pub enrichment_image_enabled: bool,
+    #[serde(default = "default_true")]
+    pub enrichment_video_enabled: bool,
+    /// Gates `GET /api/articles/:id/sentiment`.
+    #[serde(default = "default_true")]
+    pub sentiment_enabled: bool,
+    /// `ImportanceWeights` as JSON for `sort=importance`; None = default weights.
+    #[serde(default)]
+    pub importance_weights_json: Option<String>,
+    /// Extra CORS origins added by admins, on top of the sites' own.
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+    /// Prompt experiments keyed by AI feature ("summarize", "questions", "murmur").
+    #[serde(default)]
+    pub ab_tests: HashMap<String, AbTest>,
+    /// Feature "image_degradation"; thresholds come from its extra_json.
+    #[serde(default)]
+    pub image_degradation: ImageDegradationPolicy,
+}
+
+/// When the daily maintenance hides images of old, unpopular articles. The image URL
+/// is kept aside, and put back once the article reaches `restore_score`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImageDegradationPolicy {
+    pub enabled: bool,
+    /// Only articles published longer ago than this are considered.
+    pub hours_old: i64,
+    /// Articles below this popularity percentile (exclusive 0–1) of the considered
+    /// ones lose their image. Articles nobody has viewed yet are left alone.
+    pub percentile: f64,
+    /// popularity_score at which a degraded image comes back.
+    pub restore_score: f64,
+}
+
+impl Default for ImageDegradationPolicy {
+    fn default() -> Self {
+        Self { enabled: true, hours_old: 48, percentile: 0.5, restore_score: 10.0 }
+    }
+}
+
+impl ImageDegradationPolicy {
+    /// Parse `{"hours_old": .., "percentile": .., "restore_score": ..}`; missing keys
+    /// keep their defaults. Out-of-range values are rejected.
+    pub fn from_json(json: &str) -> Option<Self> {
+        let v: serde_json::Value = serde_json::from_str(json).ok()?;
+        let d = Self::default();
+        let hours_old = match v.get("hours_old") {
+            None => d.hours_old,
+            Some(h) => h.as_i64().filter(|h| *h >= 1)?,
+        };
+        let get = |key: &str, default: f64, valid: fn(f64) -> bool| match v.get(key) {
+            None => Some(default),
+            Some(x) => x.as_f64().filter(|x| x.is_finite() && valid(*x)),
+        };
+        Some(Self {
+            enabled: d.enabled,
+            hours_old,
+            percentile: get("percentile", d.percentile, |p| p > 0.0 && p < 1.0)?,
+            restore_score: get("restore_score", d.restore_score, |s| s >= 0.0)?,
+        })
+    }
+}
+
+/// A prompt experiment: each device sees one variant, chosen by `select_ab_variant`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbTest {
+    pub variants: Vec<AbVariant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbVariant {
+    pub name: String,
+    /// Relative share of devices; zero or negative weights never get picked.
+    pub weight: f32,
+    /// Replaces the feature's built-in instructions; empty keeps them (a control group).
+    #[serde(default)]
+    pub prompt_override: String,
+}
+
+impl AbVariant {
+    /// The override, or None for a control variant.
+    pub fn prompt(&self) -> Option<&str> {
+        Some(self.prompt_override.trim()).filter(|p| !p.is_empty())
+    }
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Default for FeatureFlags {
@@ -36,8 +144,61 @@ impl Default for FeatureFlags {
             grouping_enabled: false,
             grouping_threshold: 0.3,
             ogp_enrichment_enabled: true,
+            enrichment_research_enabled: true,
+            enrichment_image_enabled: true,
+            enrichment_video_enabled: true,
+            sentiment_enabled: true,
+            importance_weights_json: None,
+            cors_origins: Vec::new(),
+            ab_tests: HashMap::new(),
+            image_degradation: ImageDegradationPolicy::default(),
+        }
+    }
+}
+
+impl FeatureFlags {
+    /// Weights for importance ranking; invalid JSON falls back to the defaults.
+    pub fn importance_weights(&self) -> crate::models::ImportanceWeights {
+        self.importance_weights_json
+            .as_deref()
+            .and_then(crate::models::ImportanceWeights::from_json)
+            .unwrap_or_default()
+    }
+
+    /// The variant `device_id` is assigned in `test_name`, if that test exists.
+    pub fn ab_variant(&self, test_name: &str, device_id: &str) -> Option<&AbVariant> {
+        let name = select_ab_variant(test_name, device_id, self)?;
+        self.ab_tests.get(test_name)?.variants.iter().find(|v| v.name == name)
+    }
+}
+
+/// Deterministically pick a variant of `test_name` for `device_id`: a hash of the two,
+/// modulo the sum of the variant weights. None when the test doesn't exist or has no
+/// positive weight.
+pub fn select_ab_variant(test_name: &str, device_id: &str, flags: &FeatureFlags) -> Option<String> {
+    let variants: Vec<&AbVariant> = flags
+        .ab_tests
+        .get(test_name)?
+        .variants
+        .iter()
+        .filter(|v| v.weight.is_finite() && v.weight > 0.0)
+        .collect();
+    if variants.is_empty() {
+        return None;
+    }
+    let total: f64 = variants.iter().map(|v| v.weight as f64).sum();
+    // FNV-1a, so assignments stay put across builds and restarts
+    let hash = format!("{device_id}{test_name}")
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3));
+    let mut point = (hash % 1_000_000) as f64 / 1_000_000.0 * total;
+    for variant in &variants {
+        if point < variant.weight as f64 {
+            return Some(variant.name.clone());
         }
+        point -= variant.weight as f64;
     }
+    variants.last().map(|v| v.name.clone())
 }
 
 /// Combined service configuration.
@@ -143,6 +304,10 @@ impl ConfigStore {
                 "FEATURE#ogp_enrichment" => {
                     flags.ogp_enrichment_enabled = enabled;
                 }
+                "FEATURE#enrichment_research" => flags.enrichment_research_enabled = enabled,
+                "FEATURE#enrichment_image" => flags.enrichment_image_enabled = enabled,
+                "FEATURE#enrichment_video" => flags.enrichment_video_enabled = enabled,
+                "FEATURE#sentiment" => flags.sentiment_enabled = enabled,
                 _ => {}
             }
         }
@@ -176,6 +341,19 @@ impl ConfigStore {
         if let Some(ref added_by) = feed.added_by {
             item.insert("added_by".into(), AttributeValue::S(added_by.clone()));
         }
+        if let Some(max) = feed.max_articles_per_fetch {
+            item.insert(
+                "max_articles_per_fetch".into(),
+                AttributeValue::N(max.to_string()),
+            );
+        }
+        if feed.auto_translate {
+            item.insert("auto_translate".into(), AttributeValue::Bool(true));
+        }
+        if let Some(overrides) = feed.category_overrides.as_ref().filter(|o| !o.is_empty()) {
+            let json = serde_json::to_string(overrides).map_err(|e| AppError::ConfigError(e.to_string()))?;
+            item.insert("category_overrides".into(), AttributeValue::S(json));
+        }
 
         self.client
             .put_item()
@@ -249,6 +427,18 @@ fn item_to_feed(item: &HashMap<String, AttributeValue>) -> Option<DynamicFeed> {
     let added_by = item
         .get("added_by")
         .and_then(|v| v.as_s().ok().cloned());
+    let max_articles_per_fetch = item
+        .get("max_articles_per_fetch")
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse::<u32>().ok());
+    let auto_translate = item
+        .get("auto_translate")
+        .and_then(|v| v.as_bool().ok().copied())
+        .unwrap_or(false);
+    let category_overrides = item
+        .get("category_overrides")
+        .and_then(|v| v.as_s().ok())
+        .and_then(|s| serde_json::from_str(s).ok());
 
     Some(DynamicFeed {
         feed_id,
@@ -257,6 +447,9 @@ fn item_to_feed(item: &HashMap<String, AttributeValue>) -> Option<DynamicFeed> {
         category,
         enabled,
         added_by,
+        max_articles_per_fetch,
+        auto_translate,
+        category_overrides,
     })
 }
 
@@ -269,9 +462,65 @@ mod tests {
         let flags = FeatureFlags::default();
         assert!(!flags.grouping_enabled);
         assert!(flags.ogp_enrichment_enabled);
+        assert!(flags.enrichment_research_enabled);
+        assert!(flags.enrichment_image_enabled);
+        assert!(flags.enrichment_video_enabled);
+        assert!(flags.sentiment_enabled);
         assert!((flags.grouping_threshold - 0.3).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn image_degradation_policy_from_json() {
+        let policy = ImageDegradationPolicy::from_json(r#"{"hours_old": 72, "percentile": 0.25}"#).unwrap();
+        assert_eq!(policy.hours_old, 72);
+        assert_eq!(policy.percentile, 0.25);
+        assert_eq!(policy.restore_score, ImageDegradationPolicy::default().restore_score);
+        assert!(ImageDegradationPolicy::from_json(r#"{"percentile": 1.0}"#).is_none());
+        assert!(ImageDegradationPolicy::from_json(r#"{"hours_old": 0}"#).is_none());
+        assert!(ImageDegradationPolicy::from_json(r#"{"restore_score": -1}"#).is_none());
+        assert!(ImageDegradationPolicy::from_json("nope").is_none());
+    }
+
+    fn ab_flags(weights: &[(&str, f32)]) -> FeatureFlags {
+        let variants = weights
+            .iter()
+            .map(|(name, weight)| AbVariant {
+                name: name.to_string(),
+                weight: *weight,
+                prompt_override: format!("prompt {name}"),
+            })
+            .collect();
+        let mut flags = FeatureFlags::default();
+        flags.ab_tests.insert("murmur".into(), AbTest { variants });
+        flags
+    }
+
+    #[test]
+    fn ab_variant_is_stable_per_device() {
+        let flags = ab_flags(&[("control", 1.0), ("casual", 1.0)]);
+        let first = select_ab_variant("murmur", "device-1", &flags).unwrap();
+        for _ in 0..5 {
+            assert_eq!(select_ab_variant("murmur", "device-1", &flags).unwrap(), first);
+        }
+        assert_eq!(select_ab_variant("summarize", "device-1", &flags), None);
+
+        // Both variants get a share of devices
+        let picks: std::collections::HashSet<String> = (0..200)
+            .filter_map(|i| select_ab_variant("murmur", &format!("device-{i}"), &flags))
+            .collect();
+        assert_eq!(picks.len(), 2);
+    }
+
+    #[test]
+    fn ab_variant_skips_non_positive_weights() {
+        let flags = ab_flags(&[("off", 0.0), ("on", 2.0), ("broken", -1.0)]);
+        for i in 0..50 {
+            assert_eq!(select_ab_variant("murmur", &format!("d{i}"), &flags).as_deref(), Some("on"));
+        }
+        assert_eq!(flags.ab_variant("murmur", "d1").and_then(|v| v.prompt()), Some("prompt on"));
+        assert_eq!(select_ab_variant("murmur", "d1", &ab_flags(&[("off", 0.0)])), None);
+    }
+
     #[test]
     fn dynamic_feed_serialization() {
         let feed = DynamicFeed {
@@ -281,12 +530,40 @@ mod tests {
             category: "tech".into(),
             enabled: true,
             added_by: Some("admin".into()),
+            max_articles_per_fetch: Some(50),
+            auto_translate: true,
+            category_overrides: Some(vec![CategoryOverride {
+                keywords: vec!["NASA".into()],
+                category: "science".into(),
+            }]),
         };
         let json = serde_json::to_string(&feed).unwrap();
         let parsed: DynamicFeed = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.feed_id, "test-1");
         assert_eq!(parsed.source, "Example");
         assert!(parsed.enabled);
+        assert_eq!(parsed.max_articles_per_fetch, Some(50));
+        assert!(parsed.auto_translate);
+        assert_eq!(parsed.category_overrides, feed.category_overrides);
+    }
+
+    #[test]
+    fn dynamic_feed_quota_defaults_to_unlimited() {
+        let json = r#"{"feed_id":"f1","url":"https://example.com/rss","source":"Test","category":"general","enabled":true}"#;
+        let parsed: DynamicFeed = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.max_articles_per_fetch, None);
+        assert!(!parsed.auto_translate);
+        assert!(parsed.category_overrides.is_none());
+        assert!(!serde_json::to_string(&parsed).unwrap().contains("category_overrides"));
+    }
+
+    #[test]
+    fn enrichment_flags_default_on_for_old_configs() {
+        let json = r#"{"grouping_enabled":true,"grouping_threshold":0.5,"ogp_enrichment_enabled":false}"#;
+        let flags: FeatureFlags = serde_json::from_str(json).unwrap();
+        assert!(flags.enrichment_research_enabled);
+        assert!(flags.enrichment_image_enabled);
+        assert!(flags.enrichment_video_enabled);
     }
 
     #[test]
@@ -299,6 +576,9 @@ mod tests {
                 category: "general".into(),
                 enabled: true,
                 added_by: None,
+                max_articles_per_fetch: None,
+                auto_translate: false,
+                category_overrides: None,
             }],
             features: FeatureFlags::default(),
         };
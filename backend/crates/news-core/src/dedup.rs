@@ -7,8 +7,35 @@ const URL_NAMESPACE: Uuid = Uuid::from_bytes([
     0xc8,
 ]);
 
-/// Tracking query parameters to strip before normalization.
+/// Tracking / session query parameters to strip before normalization.
+/// Any parameter starting with `utm_` is stripped as well.
 const TRACKING_PARAMS: &[&str] = &[
+    "ref_src",
+    "fbclid",
+    "gclid",
+    "dclid",
+    "msclkid",
+    "yclid",
+    "igshid",
+    "_ga",
+    "_gl",
+    "mc_cid",
+    "mc_eid",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "cmpid",
+    "ncid",
+    "ocid",
+    "sessionid",
+    "phpsessid",
+    "jsessionid",
+];
+
+/// Tracking parameters stripped when deriving article ids. Frozen as they were before
+/// `normalize_url` existed: ids are primary keys that bookmarks, shortlinks and
+/// enrichments point at, so they must not change with the canonical form.
+const LEGACY_ID_PARAMS: &[&str] = &[
     "utm_source",
     "utm_medium",
     "utm_campaign",
@@ -21,26 +48,43 @@ const TRACKING_PARAMS: &[&str] = &[
     "mc_eid",
 ];
 
+/// Identity of an article for exact-duplicate detection: two entries with the same
+/// fingerprint are the same article even if their raw URLs differ.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContentFingerprint {
+    pub canonical_url: String,
+}
+
+impl ContentFingerprint {
+    pub fn from_url(raw_url: &str) -> Self {
+        Self {
+            canonical_url: normalize_url(raw_url),
+        }
+    }
+}
+
 /// Normalize a URL by removing tracking parameters and fragments,
 /// then generate a deterministic UUID v5 from the normalized URL.
+///
+/// Deliberately not `normalize_url`: see `LEGACY_ID_PARAMS`. Duplicates that only
+/// the canonical form catches are kept out by the `canonical_url` index instead.
 pub fn article_id_from_url(raw_url: &str) -> String {
-    let normalized = normalize_url(raw_url);
+    let normalized = legacy_id_url(raw_url);
     Uuid::new_v5(&URL_NAMESPACE, normalized.as_bytes()).to_string()
 }
 
-/// Strip tracking params, fragments, and normalize scheme/host to lowercase.
-fn normalize_url(raw: &str) -> String {
+/// The URL form article ids have always been hashed from: fragment and
+/// `LEGACY_ID_PARAMS` removed, everything else as written.
+fn legacy_id_url(raw: &str) -> String {
     let Ok(mut parsed) = Url::parse(raw) else {
         return raw.to_string();
     };
 
-    // Remove fragment
     parsed.set_fragment(None);
 
-    // Filter out tracking query params
     let filtered: Vec<(String, String)> = parsed
         .query_pairs()
-        .filter(|(key, _)| !TRACKING_PARAMS.contains(&key.as_ref()))
+        .filter(|(key, _)| !LEGACY_ID_PARAMS.contains(&key.as_ref()))
         .map(|(k, v)| (k.into_owned(), v.into_owned()))
         .collect();
 
@@ -54,6 +98,66 @@ fn normalize_url(raw: &str) -> String {
     parsed.to_string()
 }
 
+pub(crate) fn is_tracking_param(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+/// Canonical form of an article URL: no fragment, tracking params removed, remaining
+/// params sorted, lowercase host without `www.`, and repeated slashes in the path
+/// collapsed. Strings that don't parse as URLs are returned trimmed but otherwise as-is.
+pub fn normalize_url(raw: &str) -> String {
+    let raw = raw.trim();
+    let Ok(mut parsed) = Url::parse(raw) else {
+        return raw.to_string();
+    };
+
+    // Remove fragment
+    parsed.set_fragment(None);
+
+    // Host is already lowercased by the parser for http(s); drop a leading www.
+    if let Some(host) = parsed.host_str().map(|h| h.to_ascii_lowercase()) {
+        if let Some(bare) = host.strip_prefix("www.") {
+            if !bare.is_empty() {
+                let _ = parsed.set_host(Some(bare));
+            }
+        }
+    }
+
+    // Collapse repeated slashes and drop ;jsessionid=... path parameters
+    let path = parsed.path().to_string();
+    let mut collapsed = String::with_capacity(path.len());
+    for (i, segment) in path.split('/').enumerate() {
+        let segment = match segment.split_once(';') {
+            Some((head, param)) if param.to_ascii_lowercase().starts_with("jsessionid=") => head,
+            _ => segment,
+        };
+        if i > 0 && !collapsed.ends_with('/') {
+            collapsed.push('/');
+        }
+        collapsed.push_str(segment);
+    }
+    if collapsed != path {
+        parsed.set_path(&collapsed);
+    }
+
+    // Filter out tracking query params and sort the rest
+    let mut filtered: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(key, _)| !key.is_empty() && !is_tracking_param(key))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    filtered.sort();
+
+    if filtered.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(filtered);
+    }
+
+    parsed.to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +197,248 @@ mod tests {
         let id2 = article_id_from_url("https://example.com/search?q=go");
         assert_ne!(id1, id2);
     }
+
+    // --- normalize_url ---
+
+    fn n(url: &str) -> String {
+        normalize_url(url)
+    }
+
+    #[test]
+    fn plain_url_unchanged() {
+        assert_eq!(n("https://example.com/news/1"), "https://example.com/news/1");
+    }
+
+    #[test]
+    fn root_gets_trailing_slash() {
+        assert_eq!(n("https://example.com"), "https://example.com/");
+    }
+
+    #[test]
+    fn strips_fragment() {
+        assert_eq!(n("https://example.com/a#top"), "https://example.com/a");
+    }
+
+    #[test]
+    fn strips_empty_fragment() {
+        assert_eq!(n("https://example.com/a#"), "https://example.com/a");
+    }
+
+    #[test]
+    fn lowercases_host() {
+        assert_eq!(n("https://EXAMPLE.Com/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn lowercases_scheme() {
+        assert_eq!(n("HTTPS://example.com/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn path_case_preserved() {
+        assert_eq!(n("https://example.com/News/ABC"), "https://example.com/News/ABC");
+    }
+
+    #[test]
+    fn strips_www() {
+        assert_eq!(n("https://www.example.com/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn strips_uppercase_www() {
+        assert_eq!(n("https://WWW.Example.com/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn keeps_other_subdomains() {
+        assert_eq!(n("https://news.example.com/a"), "https://news.example.com/a");
+        assert_eq!(n("https://www2.example.com/a"), "https://www2.example.com/a");
+    }
+
+    #[test]
+    fn keeps_www_inside_host() {
+        assert_eq!(n("https://awww.example.com/a"), "https://awww.example.com/a");
+    }
+
+    #[test]
+    fn removes_default_port() {
+        assert_eq!(n("https://example.com:443/a"), "https://example.com/a");
+        assert_eq!(n("http://example.com:80/a"), "http://example.com/a");
+    }
+
+    #[test]
+    fn keeps_non_default_port() {
+        assert_eq!(n("https://example.com:8443/a"), "https://example.com:8443/a");
+    }
+
+    #[test]
+    fn scheme_is_not_unified() {
+        assert_ne!(n("http://example.com/a"), n("https://example.com/a"));
+    }
+
+    #[test]
+    fn collapses_double_slashes() {
+        assert_eq!(n("https://example.com//news//2024///a"), "https://example.com/news/2024/a");
+    }
+
+    #[test]
+    fn collapses_leading_double_slash_in_path() {
+        assert_eq!(n("https://example.com//a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn keeps_single_trailing_slash() {
+        assert_eq!(n("https://example.com/a/"), "https://example.com/a/");
+        assert_eq!(n("https://example.com/a//"), "https://example.com/a/");
+    }
+
+    #[test]
+    fn strips_all_utm_params() {
+        assert_eq!(
+            n("https://example.com/a?utm_source=x&utm_medium=y&utm_campaign=z&utm_term=t&utm_content=c&utm_id=1"),
+            "https://example.com/a"
+        );
+    }
+
+    #[test]
+    fn strips_uppercase_tracking_params() {
+        assert_eq!(n("https://example.com/a?UTM_Source=x&FBCLID=y"), "https://example.com/a");
+    }
+
+    #[test]
+    fn strips_click_ids() {
+        assert_eq!(
+            n("https://example.com/a?fbclid=1&gclid=2&dclid=3&msclkid=4&yclid=5&igshid=6"),
+            "https://example.com/a"
+        );
+    }
+
+    #[test]
+    fn strips_ga_params() {
+        assert_eq!(n("https://example.com/a?_ga=2.1&_gl=1*abc"), "https://example.com/a");
+    }
+
+    #[test]
+    fn strips_mailchimp_and_hubspot() {
+        assert_eq!(
+            n("https://example.com/a?mc_cid=1&mc_eid=2&_hsenc=3&_hsmi=4&mkt_tok=5"),
+            "https://example.com/a"
+        );
+    }
+
+    #[test]
+    fn strips_session_ids() {
+        assert_eq!(
+            n("https://example.com/a?sessionid=2&PHPSESSID=3&jsessionid=4"),
+            "https://example.com/a"
+        );
+    }
+
+    #[test]
+    fn keeps_params_sites_use_as_identifiers() {
+        assert_eq!(n("https://example.com/a?ref=1"), "https://example.com/a?ref=1");
+        assert_ne!(n("https://example.com/a?sid=1"), n("https://example.com/a?sid=2"));
+        assert_ne!(n("https://example.com/a?spm=1"), n("https://example.com/a?spm=2"));
+    }
+
+    #[test]
+    fn strips_jsessionid_path_param() {
+        assert_eq!(n("https://example.com/a;jsessionid=ABC123?id=1"), "https://example.com/a?id=1");
+    }
+
+    #[test]
+    fn keeps_content_params() {
+        assert_eq!(n("https://example.com/article?id=42"), "https://example.com/article?id=42");
+    }
+
+    #[test]
+    fn sorts_query_params() {
+        assert_eq!(n("https://example.com/a?b=2&a=1&c=3"), "https://example.com/a?a=1&b=2&c=3");
+    }
+
+    #[test]
+    fn sorts_repeated_keys_by_value() {
+        assert_eq!(n("https://example.com/a?tag=z&tag=a"), "https://example.com/a?tag=a&tag=z");
+    }
+
+    #[test]
+    fn param_order_does_not_matter() {
+        assert_eq!(
+            n("https://example.com/a?id=1&page=2&utm_source=x"),
+            n("https://example.com/a?utm_source=y&page=2&id=1")
+        );
+    }
+
+    #[test]
+    fn mixed_tracking_and_content_params() {
+        assert_eq!(
+            n("https://www.example.com/a?utm_source=rss&id=7&fbclid=abc#comments"),
+            "https://example.com/a?id=7"
+        );
+    }
+
+    #[test]
+    fn drops_empty_query() {
+        assert_eq!(n("https://example.com/a?"), "https://example.com/a");
+    }
+
+    #[test]
+    fn drops_params_with_empty_key() {
+        assert_eq!(n("https://example.com/a?=x&id=1"), "https://example.com/a?id=1");
+    }
+
+    #[test]
+    fn keeps_params_with_empty_value() {
+        assert_eq!(n("https://example.com/a?amp"), "https://example.com/a?amp=");
+    }
+
+    #[test]
+    fn reencodes_query_values() {
+        assert_eq!(n("https://example.com/s?q=a%26b"), "https://example.com/s?q=a%26b");
+        assert_eq!(n("https://example.com/s?q=hello world"), "https://example.com/s?q=hello+world");
+    }
+
+    #[test]
+    fn japanese_path_is_percent_encoded_consistently() {
+        assert_eq!(n("https://example.jp/記事/1"), n("https://example.jp/%E8%A8%98%E4%BA%8B/1"));
+    }
+
+    #[test]
+    fn idn_host_is_punycoded() {
+        assert_eq!(n("https://例え.jp/a"), "https://xn--r8jz45g.jp/a");
+    }
+
+    #[test]
+    fn trims_whitespace() {
+        assert_eq!(n("  https://example.com/a \n"), "https://example.com/a");
+    }
+
+    #[test]
+    fn invalid_url_returned_as_is() {
+        assert_eq!(n("not a url"), "not a url");
+        assert_eq!(n("/relative/path"), "/relative/path");
+    }
+
+    #[test]
+    fn idempotent() {
+        let once = n("https://WWW.Example.com//a?utm_source=x&b=2&a=1#f");
+        assert_eq!(n(&once), once);
+    }
+
+    #[test]
+    fn fingerprint_matches_normalized_url() {
+        let f = ContentFingerprint::from_url("https://www.example.com/a?utm_source=x");
+        assert_eq!(f.canonical_url, "https://example.com/a");
+    }
+
+    #[test]
+    fn ids_are_unchanged_by_canonicalization() {
+        // Pinned from before `normalize_url`; stored ids depend on these staying put
+        assert_eq!(article_id_from_url("https://example.com/article/1"), "a4dbba04-ce51-5a41-a094-b51916b53848");
+        assert_eq!(article_id_from_url("https://www.example.com//a?b=2&a=1&utm_source=x#f"), "164e52aa-830a-5f43-b762-8be3d5e55cfb");
+        assert_ne!(
+            article_id_from_url("https://www.example.com/a"),
+            article_id_from_url("https://example.com/a")
+        );
+    }
 }
@@ -172,7 +172,7 @@ impl ArticleStore {
 
 fn item_to_article(item: &HashMap<String, AttributeValue>) -> Option<Article> {
     let category_str = item.get("category")?.as_s().ok()?;
-    let category = Category::from_str(category_str)?;
+    let category = Category::from_id(category_str);
     let id = item.get("article_id")?.as_s().ok()?.clone();
     let title = item.get("title")?.as_s().ok()?.clone();
     let url = item.get("url")?.as_s().ok()?.clone();
@@ -206,6 +206,11 @@ fn item_to_article(item: &HashMap<String, AttributeValue>) -> Option<Article> {
         fetched_at,
         group_id: None,
         group_count: None,
+        canonical_url: None,
+        tags: Vec::new(),
+        word_count: None,
+        reading_minutes: None,
+        original_url: None,
     })
 }
 
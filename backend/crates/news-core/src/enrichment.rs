@@ -0,0 +1,202 @@
+//! Typed payloads of the enrichment agents, as stored in `enrichments.data_json`.
+//!
+//! New rows are written as an [`EnrichmentPayload`], tagged with `kind` and carrying a
+//! `version`. Rows from before the tag existed are plain structs whose shape is given by
+//! the row's `content_type`; [`EnrichmentPayload::parse`] reads both and rejects
+//! anything else.
+
+use serde::{Deserialize, Serialize};
+
+/// `content_type` of research agent rows.
+pub const CONTENT_TYPE_BACKGROUND_INFO: &str = "background_info";
+/// `content_type` of image agent rows.
+pub const CONTENT_TYPE_AI_IMAGE: &str = "ai_image";
+/// `content_type` of video agent rows.
+pub const CONTENT_TYPE_YOUTUBE_VIDEOS: &str = "youtube_videos";
+
+/// Schema version written by this build. Untagged legacy rows read as version 0.
+pub const ENRICHMENT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedLink {
+    pub title: String,
+    pub url: String,
+    pub source: String,
+    #[serde(default)]
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub date: String,
+    pub event: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResearchEnrichment {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub summary: String,
+    pub background: String,
+    #[serde(default)]
+    pub key_points: Vec<String>,
+    /// Web search results about the same story. Legacy rows call these `related_articles`.
+    #[serde(default, alias = "related_articles")]
+    pub related_links: Vec<RelatedLink>,
+    #[serde(default)]
+    pub timeline: Vec<TimelineEntry>,
+    /// Vega-Lite spec, when the article has numbers worth charting.
+    #[serde(default)]
+    pub visualization: Option<serde_json::Value>,
+    #[serde(default)]
+    pub provider: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageEnrichment {
+    #[serde(default)]
+    pub version: u32,
+    pub image_url: String,
+    pub prompt: String,
+    pub provider: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Video {
+    pub video_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub thumbnail_url: String,
+    #[serde(default)]
+    pub channel_title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoEnrichment {
+    #[serde(default)]
+    pub version: u32,
+    pub videos: Vec<Video>,
+    #[serde(default)]
+    pub search_query: String,
+    #[serde(default)]
+    pub provider: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EnrichmentPayload {
+    Research(ResearchEnrichment),
+    Image(ImageEnrichment),
+    Video(VideoEnrichment),
+}
+
+impl EnrichmentPayload {
+    /// The `enrichments.content_type` this payload is stored under.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Research(_) => CONTENT_TYPE_BACKGROUND_INFO,
+            Self::Image(_) => CONTENT_TYPE_AI_IMAGE,
+            Self::Video(_) => CONTENT_TYPE_YOUTUBE_VIDEOS,
+        }
+    }
+
+    /// Read a stored row: a tagged payload, or a legacy untagged one interpreted by
+    /// `content_type`. Fails on malformed JSON, missing required fields, an unknown
+    /// content type, or a tag that disagrees with the content type.
+    pub fn parse(content_type: &str, data_json: &str) -> Result<Self, String> {
+        let value: serde_json::Value =
+            serde_json::from_str(data_json).map_err(|e| format!("invalid JSON: {e}"))?;
+
+        let payload = if value.get("kind").is_some() {
+            serde_json::from_value::<Self>(value)
+        } else {
+            match content_type {
+                CONTENT_TYPE_BACKGROUND_INFO => serde_json::from_value(value).map(Self::Research),
+                CONTENT_TYPE_AI_IMAGE => serde_json::from_value(value).map(Self::Image),
+                CONTENT_TYPE_YOUTUBE_VIDEOS => serde_json::from_value(value).map(Self::Video),
+                other => return Err(format!("unknown content_type: {other}")),
+            }
+        }
+        .map_err(|e| format!("invalid {content_type} payload: {e}"))?;
+
+        if payload.content_type() != content_type {
+            return Err(format!(
+                "payload is {} but row says {content_type}",
+                payload.content_type()
+            ));
+        }
+        Ok(payload)
+    }
+
+    pub fn version(&self) -> u32 {
+        match self {
+            Self::Research(r) => r.version,
+            Self::Image(i) => i.version,
+            Self::Video(v) => v.version,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_research_row_reads_related_articles_as_links() {
+        let legacy = r#"{"summary":"要約","background":"背景","key_points":["a"],
+            "related_articles":[{"title":"T","url":"https://example.com/a","snippet":"s","source":"example.com"}],
+            "visualization":null,"provider":"claude-haiku"}"#;
+        let EnrichmentPayload::Research(r) = EnrichmentPayload::parse(CONTENT_TYPE_BACKGROUND_INFO, legacy).unwrap()
+        else {
+            panic!("expected research payload");
+        };
+        assert_eq!(r.version, 0);
+        assert_eq!(r.related_links.len(), 1);
+        assert_eq!(r.related_links[0].source, "example.com");
+        assert!(r.timeline.is_empty());
+    }
+
+    #[test]
+    fn legacy_image_and_video_rows_parse() {
+        let image = r#"{"image_url":"https://img/1.png","prompt":"p","provider":"dalle-3"}"#;
+        assert!(matches!(
+            EnrichmentPayload::parse(CONTENT_TYPE_AI_IMAGE, image),
+            Ok(EnrichmentPayload::Image(ImageEnrichment { version: 0, .. }))
+        ));
+
+        let video = r#"{"videos":[{"video_id":"v1","title":"t","description":"d","thumbnail_url":"u","channel_title":"c"}],
+            "search_query":"q","provider":"youtube"}"#;
+        let EnrichmentPayload::Video(v) = EnrichmentPayload::parse(CONTENT_TYPE_YOUTUBE_VIDEOS, video).unwrap() else {
+            panic!("expected video payload");
+        };
+        assert_eq!(v.videos[0].video_id, "v1");
+    }
+
+    #[test]
+    fn malformed_rows_are_rejected() {
+        // The placeholder written when a row is created, never filled in
+        assert!(EnrichmentPayload::parse(CONTENT_TYPE_BACKGROUND_INFO, "{}").is_err());
+        assert!(EnrichmentPayload::parse(CONTENT_TYPE_AI_IMAGE, "not json").is_err());
+        assert!(EnrichmentPayload::parse("mystery", r#"{"a":1}"#).is_err());
+        let image = r#"{"kind":"image","version":1,"image_url":"u","prompt":"p","provider":"x"}"#;
+        assert!(EnrichmentPayload::parse(CONTENT_TYPE_YOUTUBE_VIDEOS, image).is_err());
+    }
+
+    #[test]
+    fn tagged_payload_round_trips() {
+        let payload = EnrichmentPayload::Image(ImageEnrichment {
+            version: ENRICHMENT_VERSION,
+            image_url: "https://img/2.png".into(),
+            prompt: "p".into(),
+            provider: "flux-schnell".into(),
+        });
+        let json = serde_json::to_string(&payload).unwrap();
+        assert!(json.contains(r#""kind":"image""#));
+        let parsed = EnrichmentPayload::parse(payload.content_type(), &json).unwrap();
+        assert_eq!(parsed, payload);
+        assert_eq!(parsed.version(), ENRICHMENT_VERSION);
+    }
+}
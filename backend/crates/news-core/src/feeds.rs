@@ -1,8 +1,10 @@
-use crate::dedup::article_id_from_url;
+use crate::config::{CategoryOverride, DynamicFeed};
+use crate::dedup::{article_id_from_url, ContentFingerprint};
 use crate::error::{AppError, Result};
-use crate::models::{Article, Category};
+use crate::models::{is_default_category, Article, Category, SourceMeta, SOURCE_TYPE_UNKNOWN};
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
 /// Feed configuration loaded from feeds.toml.
@@ -11,31 +13,483 @@ pub struct FeedConfig {
     pub url: String,
     pub source: String,
     pub category: String,
+    /// Keep at most this many (newest) entries per fetch. None = unlimited.
+    #[serde(default)]
+    pub max_articles_per_fetch: Option<u32>,
+    /// Keyword rules applied by `apply_overrides` after parsing.
+    #[serde(default)]
+    pub category_overrides: Vec<CategoryOverride>,
+    /// Optional source metadata, seeded into the `source_meta` table.
+    #[serde(flatten)]
+    pub source_meta: FeedSourceMeta,
+}
+
+/// `source_type`, `credibility_tier`, `homepage_url` and `logo_url` keys of a
+/// `[[feeds]]` entry. Any of them set makes the entry describe its source.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FeedSourceMeta {
+    pub source_type: Option<String>,
+    pub credibility_tier: Option<u8>,
+    pub homepage_url: Option<String>,
+    pub logo_url: Option<String>,
+}
+
+impl FeedSourceMeta {
+    fn is_empty(&self) -> bool {
+        self.source_type.is_none()
+            && self.credibility_tier.is_none()
+            && self.homepage_url.is_none()
+            && self.logo_url.is_none()
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct FeedsConfig {
     pub feeds: Vec<FeedConfig>,
+    /// Non-fatal issues found by `from_toml` (e.g. plain-HTTP feeds).
+    #[serde(skip)]
+    pub warnings: Vec<FeedConfigError>,
+}
+
+/// A problem with one `[[feeds]]` entry. `index` is its position in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedConfigError {
+    pub index: usize,
+    pub url: String,
+    pub error: String,
+}
+
+impl std::fmt::Display for FeedConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "feeds[{}] ({}): {}", self.index, self.url, self.error)
+    }
+}
+
+const MAX_SOURCE_LEN: usize = 100;
+
+impl FeedConfig {
+    /// The entry's source metadata, if it declares any.
+    pub fn describe_source(&self) -> Option<SourceMeta> {
+        let meta = &self.source_meta;
+        if meta.is_empty() {
+            return None;
+        }
+        Some(SourceMeta {
+            source: self.source.trim().to_string(),
+            source_type: meta.source_type.clone().unwrap_or_else(|| SOURCE_TYPE_UNKNOWN.into()),
+            credibility_tier: meta.credibility_tier,
+            homepage_url: meta.homepage_url.clone(),
+            logo_url: meta.logo_url.clone(),
+        })
+    }
 }
 
 impl FeedsConfig {
+    /// Parse and validate feeds.toml. Fails if any entry is unusable; usable but
+    /// questionable entries are reported in `warnings`.
     pub fn from_toml(toml_str: &str) -> Result<Self> {
-        toml::from_str(toml_str).map_err(|e| AppError::ConfigError(e.to_string()))
+        Self::from_toml_with(toml_str, is_default_category)
+    }
+
+    /// `from_toml` accepting the categories `known` accepts, e.g. the built-in ones
+    /// plus those created by admins.
+    pub fn from_toml_with(toml_str: &str, known: impl Fn(&str) -> bool) -> Result<Self> {
+        let mut config: Self =
+            toml::from_str(toml_str).map_err(|e| AppError::ConfigError(e.to_string()))?;
+
+        let errors = config.validate_with(known);
+        if !errors.is_empty() {
+            let msg = errors
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(AppError::ConfigError(format!(
+                "{} invalid feed(s): {}",
+                errors.len(),
+                msg
+            )));
+        }
+
+        config.warnings = config.lint();
+        Ok(config)
+    }
+
+    /// Unusable entries: bad URL, bad source, a URL that is already configured, or a
+    /// category outside `DEFAULT_CATEGORIES`.
+    pub fn validate(&self) -> Vec<FeedConfigError> {
+        self.validate_with(is_default_category)
+    }
+
+    /// `validate` with the categories `known` accepts in place of `DEFAULT_CATEGORIES`.
+    pub fn validate_with(&self, known: impl Fn(&str) -> bool) -> Vec<FeedConfigError> {
+        let mut errors = Vec::new();
+        let mut seen = std::collections::HashMap::new();
+
+        for (index, feed) in self.feeds.iter().enumerate() {
+            let mut push = |error: String| {
+                errors.push(FeedConfigError {
+                    index,
+                    url: feed.url.clone(),
+                    error,
+                })
+            };
+
+            match url::Url::parse(&feed.url) {
+                Ok(u) if matches!(u.scheme(), "http" | "https") => {}
+                Ok(u) => push(format!("unsupported URL scheme: {}", u.scheme())),
+                Err(e) => push(format!("invalid URL: {}", e)),
+            }
+
+            let source = feed.source.trim();
+            if source.is_empty() {
+                push("source is empty".into());
+            } else if source.chars().count() > MAX_SOURCE_LEN {
+                push(format!("source is longer than {} chars", MAX_SOURCE_LEN));
+            }
+
+            if !known(&feed.category) {
+                push(format!("unknown category: {}", feed.category));
+            }
+
+            if let Err(e) = validate_category_overrides(&feed.category_overrides, &known) {
+                push(e);
+            }
+
+            if let Some(Err(e)) = feed.describe_source().map(|meta| meta.validate()) {
+                push(e);
+            }
+
+            if let Some(first) = seen.insert(feed.url.as_str(), index) {
+                push(format!("duplicate URL (first defined at feeds[{}])", first));
+            }
+        }
+
+        errors
+    }
+
+    /// `base` (feeds.toml) with the admin-managed `overrides` layered on top. A dynamic
+    /// feed whose URL matches a static one (per `normalize_feed_url`) replaces it in
+    /// place; the rest are appended. A disabled dynamic feed drops the static feed with
+    /// its URL (the DB is seeded from feeds.toml, so that is how a seeded feed gets
+    /// switched off), and duplicates within either list keep their last definition.
+    pub fn merge(base: &FeedsConfig, overrides: &[DynamicFeed]) -> FeedsConfig {
+        let mut feeds: Vec<FeedConfig> = Vec::with_capacity(base.feeds.len() + overrides.len());
+        let mut index = std::collections::HashMap::new();
+
+        let disabled: std::collections::HashSet<String> =
+            overrides.iter().filter(|f| !f.enabled).map(|f| normalize_feed_url(&f.url)).collect();
+        let static_feeds = base.feeds.iter().filter(|f| !disabled.contains(&normalize_feed_url(&f.url))).cloned();
+        let dynamic = overrides.iter().filter(|f| f.enabled).map(|f| FeedConfig {
+            url: f.url.clone(),
+            source: f.source.clone(),
+            category: f.category.clone(),
+            max_articles_per_fetch: f.max_articles_per_fetch,
+            category_overrides: f.category_overrides.clone().unwrap_or_default(),
+            source_meta: FeedSourceMeta::default(),
+        });
+        for feed in static_feeds.chain(dynamic) {
+            match index.entry(normalize_feed_url(&feed.url)) {
+                std::collections::hash_map::Entry::Occupied(e) => feeds[*e.get()] = feed,
+                std::collections::hash_map::Entry::Vacant(e) => {
+                    e.insert(feeds.len());
+                    feeds.push(feed);
+                }
+            }
+        }
+
+        FeedsConfig {
+            feeds,
+            warnings: base.warnings.clone(),
+        }
+    }
+
+    /// Source metadata declared in the feeds, first declaration per source winning.
+    pub fn source_meta(&self) -> Vec<SourceMeta> {
+        let mut seen = std::collections::HashSet::new();
+        self.feeds
+            .iter()
+            .filter_map(FeedConfig::describe_source)
+            .filter(|meta| seen.insert(meta.source.clone()))
+            .collect()
+    }
+
+    /// Usable but suboptimal entries.
+    fn lint(&self) -> Vec<FeedConfigError> {
+        let mut warnings = Vec::new();
+        for (index, feed) in self.feeds.iter().enumerate() {
+            let mut push = |error: &str| {
+                warnings.push(FeedConfigError {
+                    index,
+                    url: feed.url.clone(),
+                    error: error.to_string(),
+                })
+            };
+            if feed.url.starts_with("http://") {
+                push("plain HTTP; prefer https:// if the site supports it");
+            }
+            if feed.source != feed.source.trim() {
+                push("source has leading/trailing whitespace");
+            }
+            if feed.category != feed.category.to_lowercase() {
+                push("category should be lowercase");
+            }
+            if feed.max_articles_per_fetch == Some(0) {
+                push("max_articles_per_fetch = 0 drops every article");
+            }
+        }
+        warnings
+    }
+}
+
+/// Key for telling whether two feed URLs are the same feed: lowercased, trailing
+/// slashes dropped, and `http://` treated the same as `https://`.
+pub fn normalize_feed_url(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(&url);
+    format!("https://{}", rest.trim_end_matches('/'))
+}
+
+/// Re-file `article` under the first of the feed's `category_overrides` whose keywords
+/// appear in its title or description (case-insensitive). Unmatched articles, and rules
+/// with a blank category, leave the feed's category in place.
+pub fn apply_category_override(article: &mut Article, feed: &DynamicFeed) {
+    if let Some(overrides) = &feed.category_overrides {
+        apply_overrides(article, overrides);
+    }
+}
+
+fn apply_overrides(article: &mut Article, overrides: &[CategoryOverride]) {
+    if overrides.is_empty() {
+        return;
+    }
+    let haystack = format!(
+        "{}\n{}",
+        article.title,
+        article.description.as_deref().unwrap_or("")
+    )
+    .to_lowercase();
+    let matched = overrides.iter().find(|o| {
+        o.keywords
+            .iter()
+            .map(|k| k.trim().to_lowercase())
+            .any(|k| !k.is_empty() && haystack.contains(&k))
+    });
+    if let Some(o) = matched.filter(|o| !o.category.trim().is_empty()) {
+        article.category = Category::from_id(&o.category);
+    }
+}
+
+/// Each rule needs a category `known` accepts and at least one non-blank keyword.
+pub fn validate_category_overrides(
+    overrides: &[CategoryOverride],
+    known: impl Fn(&str) -> bool,
+) -> std::result::Result<(), String> {
+    for (i, o) in overrides.iter().enumerate() {
+        if !known(&o.category) {
+            return Err(format!("category_overrides[{i}]: unknown category: {}", o.category));
+        }
+        if o.keywords.iter().all(|k| k.trim().is_empty()) {
+            return Err(format!("category_overrides[{i}]: keywords is empty"));
+        }
     }
+    Ok(())
 }
 
-/// Fetch and parse a single RSS/Atom feed into articles.
+/// Fetch and parse a single RSS, Atom or JSON Feed document into articles.
 pub async fn fetch_feed(client: &reqwest::Client, feed: &FeedConfig) -> Result<Vec<Article>> {
-    let category = Category::from_str(&feed.category)
-        .ok_or_else(|| AppError::ConfigError(format!("Unknown category: {}", feed.category)))?;
+    let category = Category::from_id(&feed.category);
 
     info!(url = %feed.url, source = %feed.source, "Fetching feed");
 
     let response = client.get(&feed.url).send().await?;
+    let content_type = response_content_type(&response);
+    let bytes = response.bytes().await?;
+
+    let articles = parse_feed_response(&content_type, &bytes, feed, &category)?.articles;
+
+    info!(
+        url = %feed.url,
+        count = articles.len(),
+        "Parsed feed"
+    );
+
+    Ok(articles)
+}
+
+fn response_content_type(response: &reqwest::Response) -> String {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// A parsed feed with its channel-level metadata, for previewing a feed before adding it.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedPreview {
+    /// The `FeedParser` that read the document: "rss2", "atom" or "json_feed".
+    pub detected_format: &'static str,
+    /// "rss2", "rss1", "rss0", "atom" or "json".
+    pub detected_type: &'static str,
+    pub feed_title: Option<String>,
+    pub feed_description: Option<String>,
+    /// Entries in the document, before the per-fetch cap and link filtering.
+    pub item_count: usize,
+    pub articles: Vec<Article>,
+}
+
+/// Fetch a feed like `fetch_feed`, keeping the channel metadata.
+pub async fn fetch_feed_preview(client: &reqwest::Client, feed: &FeedConfig) -> Result<FeedPreview> {
+    let category = Category::from_id(&feed.category);
+    let response = client.get(&feed.url).send().await?.error_for_status()?;
+    let content_type = response_content_type(&response);
     let bytes = response.bytes().await?;
+    parse_feed_response(&content_type, &bytes, feed, &category)
+}
+
+/// Parse a raw feed document into articles, applying the feed's per-fetch cap.
+pub fn parse_feed(bytes: &[u8], feed: &FeedConfig, category: &Category) -> Result<Vec<Article>> {
+    parse_feed_preview(bytes, feed, category).map(|preview| preview.articles)
+}
+
+/// `parse_feed` plus the feed's format, title, description and raw entry count.
+pub fn parse_feed_preview(bytes: &[u8], feed: &FeedConfig, category: &Category) -> Result<FeedPreview> {
+    parse_feed_response("", bytes, feed, category)
+}
+
+// --- Feed formats ---
+
+/// One feed format. Bodies are bytes rather than `&str` because XML feeds may declare
+/// a non-UTF-8 encoding (Shift_JIS is still around) that the XML parser decodes.
+pub trait FeedParser: Sync {
+    /// Reported as `FeedPreview::detected_format`.
+    fn name(&self) -> &'static str;
+    /// Whether a `Content-Type` header value names this format.
+    fn claims_content_type(&self, content_type: &str) -> bool;
+    /// Whether the document is structurally this format.
+    fn detect(&self, body: &[u8]) -> bool;
+    fn parse_preview(&self, body: &[u8], feed: &FeedConfig, category: &Category) -> Result<FeedPreview>;
+
+    fn parse(&self, body: &[u8], feed: &FeedConfig, category: &Category) -> Result<Vec<Article>> {
+        self.parse_preview(body, feed, category).map(|preview| preview.articles)
+    }
+}
+
+/// Every parser, in the order they're tried.
+pub const FEED_PARSERS: [&dyn FeedParser; 3] = [&Rss2Parser, &AtomParser, &JsonFeedParser];
+
+/// Parse with the parser the `Content-Type` names, falling back to structural
+/// detection when no parser claims it or the claimed one fails; servers often send
+/// `text/xml` or the wrong feed type.
+pub fn parse_feed_response(
+    content_type: &str,
+    bytes: &[u8],
+    feed: &FeedConfig,
+    category: &Category,
+) -> Result<FeedPreview> {
+    let content_type = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    let hinted = FEED_PARSERS.into_iter().find(|p| p.claims_content_type(&content_type));
+    if let Some(parser) = hinted {
+        match parser.parse_preview(bytes, feed, category) {
+            Ok(preview) => return Ok(preview),
+            Err(e) => warn!(url = %feed.url, content_type, format = parser.name(), error = %e, "Feed doesn't match its content type"),
+        }
+    }
+    match FEED_PARSERS.into_iter().find(|p| p.detect(bytes)) {
+        Some(parser) => parser.parse_preview(bytes, feed, category),
+        None => Err(AppError::ParseError("Unrecognized feed format".into())),
+    }
+}
+
+/// RSS 2.0, along with RSS 0.9x and RSS 1.0 (RDF), which share its item model.
+pub struct Rss2Parser;
+
+impl FeedParser for Rss2Parser {
+    fn name(&self) -> &'static str {
+        "rss2"
+    }
+
+    fn claims_content_type(&self, content_type: &str) -> bool {
+        matches!(content_type, "application/rss+xml" | "application/rdf+xml")
+    }
+
+    fn detect(&self, body: &[u8]) -> bool {
+        matches!(xml_root(body).as_deref(), Some("rss" | "RDF"))
+    }
+
+    fn parse_preview(&self, body: &[u8], feed: &FeedConfig, category: &Category) -> Result<FeedPreview> {
+        parse_xml_feed(self.name(), body, feed, category)
+    }
+}
+
+pub struct AtomParser;
+
+impl FeedParser for AtomParser {
+    fn name(&self) -> &'static str {
+        "atom"
+    }
+
+    fn claims_content_type(&self, content_type: &str) -> bool {
+        content_type == "application/atom+xml"
+    }
+
+    fn detect(&self, body: &[u8]) -> bool {
+        xml_root(body).as_deref() == Some("feed")
+    }
+
+    fn parse_preview(&self, body: &[u8], feed: &FeedConfig, category: &Category) -> Result<FeedPreview> {
+        parse_xml_feed(self.name(), body, feed, category)
+    }
+}
+
+/// Local name of an XML document's root element, past any BOM, declaration,
+/// processing instructions, comments and doctype.
+fn xml_root(body: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(&body[..body.len().min(4096)]);
+    let mut rest = head.trim_start_matches('\u{feff}');
+    loop {
+        rest = rest.trim_start();
+        if let Some(r) = rest.strip_prefix("<?") {
+            rest = &r[r.find("?>")? + 2..];
+        } else if let Some(r) = rest.strip_prefix("<!--") {
+            rest = &r[r.find("-->")? + 3..];
+        } else if let Some(r) = rest.strip_prefix("<!") {
+            rest = &r[r.find('>')? + 1..];
+        } else {
+            break;
+        }
+    }
+    let name = rest.strip_prefix('<')?.split(|c: char| c.is_whitespace() || c == '>' || c == '/').next()?;
+    Some(name.rsplit(':').next().unwrap_or(name).to_string())
+}
+
+/// RSS and Atom through feed-rs. A document of the other XML family is rejected, so
+/// each parser only ever reports its own format.
+fn parse_xml_feed(format: &'static str, bytes: &[u8], feed: &FeedConfig, category: &Category) -> Result<FeedPreview> {
+    use feed_rs::model::FeedType;
 
     let parsed =
-        feed_rs::parser::parse(&bytes[..]).map_err(|e| AppError::ParseError(e.to_string()))?;
+        feed_rs::parser::parse(bytes).map_err(|e| AppError::ParseError(e.to_string()))?;
+
+    let detected_type = match parsed.feed_type {
+        FeedType::Atom => "atom",
+        FeedType::JSON => "json",
+        FeedType::RSS0 => "rss0",
+        FeedType::RSS1 => "rss1",
+        FeedType::RSS2 => "rss2",
+    };
+    let is_atom = parsed.feed_type == FeedType::Atom;
+    if parsed.feed_type == FeedType::JSON || is_atom != (format == "atom") {
+        return Err(AppError::ParseError(format!("Not an {format} feed: {detected_type}")));
+    }
+    let feed_title = parsed.title.map(|t| t.content);
+    let feed_description = parsed.description.map(|d| d.content);
+    let item_count = parsed.entries.len();
 
     let now = Utc::now();
     let mut articles = Vec::new();
@@ -72,51 +526,291 @@ pub async fn fetch_feed(client: &reqwest::Client, feed: &FeedConfig) -> Result<V
             .and_then(|c| c.url.as_ref())
             .map(|u| u.to_string());
 
-        let id = article_id_from_url(&link);
-
-        articles.push(Article {
-            id,
-            category: category.clone(),
-            title,
-            url: link,
-            description,
-            image_url,
-            source: feed.source.clone(),
-            published_at,
-            fetched_at: now,
-            group_id: None,
-            group_count: None,
-        });
+        articles.push(feed_article(feed, category, link, title, description, image_url, published_at, now));
     }
 
-    info!(
-        url = %feed.url,
-        count = articles.len(),
-        "Parsed feed"
-    );
+    Ok(finish_preview(format, detected_type, feed_title, feed_description, item_count, articles, feed))
+}
 
-    Ok(articles)
+/// JSON Feed 1.0 and 1.1 (https://www.jsonfeed.org/version/1.1/). Read here rather
+/// than through feed-rs, which ignores item images and requires a feed title.
+pub struct JsonFeedParser;
+
+#[derive(Deserialize)]
+struct JsonFeedDocument {
+    version: String,
+    title: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    items: Vec<JsonFeedDocumentItem>,
+}
+
+#[derive(Deserialize)]
+struct JsonFeedDocumentItem {
+    url: Option<String>,
+    external_url: Option<String>,
+    title: Option<String>,
+    summary: Option<String>,
+    content_text: Option<String>,
+    content_html: Option<String>,
+    image: Option<String>,
+    banner_image: Option<String>,
+    date_published: Option<String>,
+    date_modified: Option<String>,
+}
+
+const JSON_FEED_VERSION_PREFIX: &str = "https://jsonfeed.org/version/";
+
+impl FeedParser for JsonFeedParser {
+    fn name(&self) -> &'static str {
+        "json_feed"
+    }
+
+    fn claims_content_type(&self, content_type: &str) -> bool {
+        matches!(content_type, "application/feed+json" | "application/json")
+    }
+
+    fn detect(&self, body: &[u8]) -> bool {
+        let Ok(text) = std::str::from_utf8(body) else {
+            return false;
+        };
+        text.trim_start_matches('\u{feff}').trim_start().starts_with('{')
+            && text.contains(JSON_FEED_VERSION_PREFIX.trim_start_matches("https://"))
+    }
+
+    fn parse_preview(&self, body: &[u8], feed: &FeedConfig, category: &Category) -> Result<FeedPreview> {
+        let text = std::str::from_utf8(body).map_err(|e| AppError::ParseError(e.to_string()))?;
+        let doc: JsonFeedDocument = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+            .map_err(|e| AppError::ParseError(format!("Invalid JSON Feed: {e}")))?;
+        // 1.0 feeds written before the spec moved to https use http
+        if !doc.version.replacen("http://", "https://", 1).starts_with(JSON_FEED_VERSION_PREFIX) {
+            return Err(AppError::ParseError(format!("Not a JSON Feed version: {}", doc.version)));
+        }
+
+        let now = Utc::now();
+        let item_count = doc.items.len();
+        let articles = doc
+            .items
+            .into_iter()
+            .filter_map(|item| {
+                let link = item.url.or(item.external_url)?;
+                let published_at = item
+                    .date_published
+                    .or(item.date_modified)
+                    .and_then(|d| DateTime::parse_from_rfc3339(&d).ok())
+                    .map(|d| d.with_timezone(&Utc))
+                    .unwrap_or(now);
+                let description = item.summary.or(item.content_text).or(item.content_html);
+                let title = item.title.unwrap_or_else(|| "(no title)".into());
+                let image_url = item.image.or(item.banner_image);
+                Some(feed_article(feed, category, link, title, description, image_url, published_at, now))
+            })
+            .collect();
+
+        Ok(finish_preview(self.name(), "json", doc.title, doc.description, item_count, articles, feed))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn feed_article(
+    feed: &FeedConfig,
+    category: &Category,
+    link: String,
+    title: String,
+    description: Option<String>,
+    image_url: Option<String>,
+    published_at: DateTime<Utc>,
+    fetched_at: DateTime<Utc>,
+) -> Article {
+    let fingerprint = ContentFingerprint::from_url(&link);
+    Article {
+        id: article_id_from_url(&link),
+        category: category.clone(),
+        title,
+        url: link,
+        description,
+        image_url,
+        source: feed.source.clone(),
+        published_at,
+        fetched_at,
+        group_id: None,
+        group_count: None,
+        canonical_url: Some(fingerprint.canonical_url),
+        tags: Vec::new(),
+        word_count: None,
+        reading_minutes: None,
+        original_url: None,
+    }
+}
+
+/// Apply the feed's per-fetch cap and category overrides, whatever the format.
+fn finish_preview(
+    detected_format: &'static str,
+    detected_type: &'static str,
+    feed_title: Option<String>,
+    feed_description: Option<String>,
+    item_count: usize,
+    mut articles: Vec<Article>,
+    feed: &FeedConfig,
+) -> FeedPreview {
+    if let Some(max) = feed.max_articles_per_fetch {
+        cap_newest(&mut articles, max as usize);
+    }
+    for article in &mut articles {
+        apply_overrides(article, &feed.category_overrides);
+    }
+    FeedPreview {
+        detected_format,
+        detected_type,
+        feed_title,
+        feed_description,
+        item_count,
+        articles,
+    }
+}
+
+/// Keep only the `max` most recently published articles.
+fn cap_newest(articles: &mut Vec<Article>, max: usize) {
+    if articles.len() <= max {
+        return;
+    }
+    articles.sort_by_key(|a| std::cmp::Reverse(a.published_at));
+    articles.truncate(max);
+}
+
+/// Outcome of one feed's fetch within `fetch_all_feeds_reported`.
+#[derive(Debug, Clone)]
+pub struct FeedFetchReport {
+    pub url: String,
+    /// Articles kept after parsing (0 on error).
+    pub item_count: usize,
+    pub elapsed: Duration,
+    pub error: Option<String>,
+}
+
+/// Bounds on one fetch cycle.
+#[derive(Debug, Clone, Copy)]
+pub struct FetchLimits {
+    /// Feeds in flight at once.
+    pub concurrency: usize,
+    /// A single feed's request and parse; below the HTTP client's own timeout.
+    pub per_feed_timeout: Duration,
+    /// Feeds still running this long after the cycle started are abandoned.
+    pub cycle_deadline: Duration,
+}
+
+impl Default for FetchLimits {
+    fn default() -> Self {
+        Self {
+            concurrency: 12,
+            per_feed_timeout: Duration::from_secs(10),
+            cycle_deadline: Duration::from_secs(120),
+        }
+    }
 }
 
 /// Fetch all configured feeds concurrently.
 pub async fn fetch_all_feeds(client: &reqwest::Client, config: &FeedsConfig) -> Vec<Article> {
-    let futures: Vec<_> = config
-        .feeds
-        .iter()
-        .map(|feed| fetch_feed(client, feed))
-        .collect();
+    fetch_all_feeds_reported(client, config).await.0
+}
 
-    let results = futures::future::join_all(futures).await;
+/// `fetch_all_feeds` plus a report per feed, in `config.feeds` order.
+pub async fn fetch_all_feeds_reported(
+    client: &reqwest::Client,
+    config: &FeedsConfig,
+) -> (Vec<Article>, Vec<FeedFetchReport>) {
     let mut all_articles = Vec::new();
+    let reports = fetch_feeds_streaming(client, &config.feeds, FetchLimits::default(), |_, articles| {
+        all_articles.extend(articles)
+    })
+    .await;
+    (all_articles, reports)
+}
+
+/// Fetch `feeds` with at most `limits.concurrency` in flight, handing each feed's
+/// articles to `on_feed` as soon as it completes. A feed that errors, times out or
+/// panics only fails its own report; feeds unfinished at the cycle deadline are
+/// dropped and reported as abandoned. Reports come back in `feeds` order.
+pub async fn fetch_feeds_streaming<F>(
+    client: &reqwest::Client,
+    feeds: &[FeedConfig],
+    limits: FetchLimits,
+    mut on_feed: F,
+) -> Vec<FeedFetchReport>
+where
+    F: FnMut(&FeedConfig, Vec<Article>),
+{
+    use futures::{FutureExt, StreamExt};
 
-    for result in results {
+    let cycle_started = Instant::now();
+    let deadline = tokio::time::sleep(limits.cycle_deadline);
+    tokio::pin!(deadline);
+    // Built up front: a closure inside the stream trips up `Send` inference when the
+    // caller's future is spawned
+    let fetches: Vec<_> = feeds
+        .iter()
+        .enumerate()
+        .map(|(i, feed)| async move {
+            let started = Instant::now();
+            let fetch = std::panic::AssertUnwindSafe(fetch_feed(client, feed)).catch_unwind();
+            let result = match tokio::time::timeout(limits.per_feed_timeout, fetch).await {
+                Ok(Ok(result)) => result.map_err(|e| e.to_string()),
+                Ok(Err(_)) => Err("panicked while fetching".to_string()),
+                Err(_) => Err(format!("timed out after {:?}", limits.per_feed_timeout)),
+            };
+            (i, result, started.elapsed())
+        })
+        .collect();
+    let mut results = futures::stream::iter(fetches).buffer_unordered(limits.concurrency.max(1));
+
+    let mut reports: Vec<Option<FeedFetchReport>> = vec![None; feeds.len()];
+    loop {
+        let (i, result, elapsed) = tokio::select! {
+            next = results.next() => match next {
+                Some(done) => done,
+                None => break,
+            },
+            _ = &mut deadline => break,
+        };
+        let feed = &feeds[i];
+        let mut report = FeedFetchReport {
+            url: feed.url.clone(),
+            item_count: 0,
+            elapsed,
+            error: None,
+        };
         match result {
-            Ok(articles) => all_articles.extend(articles),
-            Err(e) => warn!(error = %e, "Failed to fetch feed, skipping"),
+            Ok(articles) => {
+                report.item_count = articles.len();
+                on_feed(feed, articles);
+            }
+            Err(e) => {
+                warn!(url = %feed.url, error = %e, "Failed to fetch feed, skipping");
+                report.error = Some(e);
+            }
         }
+        reports[i] = Some(report);
     }
 
-    all_articles
+    let abandoned = reports.iter().filter(|r| r.is_none()).count();
+    if abandoned > 0 {
+        warn!(abandoned, deadline = ?limits.cycle_deadline, "Fetch cycle deadline reached, abandoning slow feeds");
+    }
+    reports
+        .into_iter()
+        .zip(feeds)
+        .map(|(report, feed)| {
+            report.unwrap_or_else(|| {
+                warn!(url = %feed.url, "Feed abandoned at cycle deadline");
+                FeedFetchReport {
+                    url: feed.url.clone(),
+                    item_count: 0,
+                    elapsed: cycle_started.elapsed(),
+                    error: Some("abandoned at cycle deadline".into()),
+                }
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -143,6 +837,593 @@ category = "general"
         assert_eq!(config.feeds[1].category, "general");
     }
 
+    #[test]
+    fn feeds_config_quota_is_optional() {
+        let toml = r#"
+[[feeds]]
+url = "https://example.com/rss"
+source = "Firehose"
+category = "general"
+max_articles_per_fetch = 20
+"#;
+        let config = FeedsConfig::from_toml(toml).unwrap();
+        assert_eq!(config.feeds[0].max_articles_per_fetch, Some(20));
+        let config = FeedsConfig::from_toml(SAMPLE_TOML).unwrap();
+        assert_eq!(config.feeds[0].max_articles_per_fetch, None);
+    }
+
+    fn rss_with_items(n: usize) -> String {
+        let items: String = (0..n)
+            .map(|i| {
+                format!(
+                    "<item><title>Item {i}</title><link>https://example.com/{i}</link>\
+                     <pubDate>{}</pubDate></item>",
+                    (chrono::Utc::now() - chrono::Duration::minutes(i as i64)).to_rfc2822()
+                )
+            })
+            .collect();
+        format!(
+            "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel><title>T</title>\
+             <link>https://example.com</link><description>d</description>{items}</channel></rss>"
+        )
+    }
+
+    #[test]
+    fn parse_feed_applies_quota() {
+        let xml = rss_with_items(500);
+        let feed = FeedConfig {
+            url: "https://example.com/rss".into(),
+            source: "Firehose".into(),
+            category: "general".into(),
+            max_articles_per_fetch: Some(25),
+            category_overrides: Vec::new(),
+            source_meta: FeedSourceMeta::default(),
+        };
+        let articles = parse_feed(xml.as_bytes(), &feed, &Category::General).unwrap();
+        assert_eq!(articles.len(), 25);
+        // The newest entries are the ones kept
+        assert!(articles.iter().all(|a| {
+            let n: usize = a.title.trim_start_matches("Item ").parse().unwrap();
+            n < 25
+        }));
+    }
+
+    fn rule(keywords: &[&str], category: &str) -> CategoryOverride {
+        CategoryOverride {
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            category: category.into(),
+        }
+    }
+
+    #[test]
+    fn category_override_refiles_matching_articles() {
+        let mut feed = dynamic("https://blog.example/rss", "Blog", "tech");
+        feed.category_overrides = Some(vec![
+            rule(&["NASA", "宇宙"], "science"),
+            rule(&["earnings", "IPO"], "business"),
+            rule(&["nasa"], "sports"),
+        ]);
+        let article = |title: &str, description: Option<&str>| Article {
+            id: "a".into(),
+            category: Category::Tech,
+            title: title.into(),
+            url: "https://blog.example/a".into(),
+            description: description.map(str::to_string),
+            image_url: None,
+            source: "Blog".into(),
+            published_at: Utc::now(),
+            fetched_at: Utc::now(),
+            group_id: None,
+            group_count: None,
+            canonical_url: None,
+            tags: Vec::new(),
+            word_count: None,
+            reading_minutes: None,
+            original_url: None,
+        };
+
+        let mut a = article("Nasa picks a new rover", None);
+        apply_category_override(&mut a, &feed);
+        assert_eq!(a.category, Category::Science, "first matching rule wins, case-insensitively");
+
+        let mut b = article("Chipmaker results", Some("Quarterly earnings beat estimates"));
+        apply_category_override(&mut b, &feed);
+        assert_eq!(b.category, Category::Business);
+
+        let mut c = article("A new JavaScript runtime", Some("Benchmarks inside"));
+        apply_category_override(&mut c, &feed);
+        assert_eq!(c.category, Category::Tech);
+    }
+
+    #[test]
+    fn parse_feed_applies_static_category_overrides() {
+        let xml = rss_with_items(12);
+        let feed = FeedConfig {
+            category_overrides: vec![rule(&["item 1"], "science")],
+            ..feed("https://example.com/rss", "Example", "general")
+        };
+        let articles = parse_feed(xml.as_bytes(), &feed, &Category::General).unwrap();
+        let science: Vec<_> = articles
+            .iter()
+            .filter(|a| a.category == Category::Science)
+            .map(|a| a.title.as_str())
+            .collect();
+        assert_eq!(science, vec!["Item 1", "Item 10", "Item 11"]);
+    }
+
+    #[test]
+    fn source_meta_is_read_from_feed_entries() {
+        let toml = r#"
+            [[feeds]]
+            url = "https://a.example/rss"
+            source = "NHK"
+            category = "general"
+            source_type = "public_broadcaster"
+            credibility_tier = 1
+            homepage_url = "https://www.nhk.or.jp/"
+
+            [[feeds]]
+            url = "https://a.example/sci"
+            source = "NHK"
+            category = "science"
+            source_type = "blog"
+
+            [[feeds]]
+            url = "https://b.example/rss"
+            source = "Plain"
+            category = "tech"
+        "#;
+        let config = FeedsConfig::from_toml(toml).unwrap();
+        let meta = config.source_meta();
+        assert_eq!(meta.len(), 1);
+        assert_eq!(meta[0].source, "NHK");
+        assert_eq!(meta[0].source_type, "public_broadcaster");
+        assert_eq!(meta[0].credibility_tier, Some(1));
+
+        let bad = toml.replace("\"blog\"", "\"tabloid\"");
+        assert!(FeedsConfig::from_toml(&bad).is_err());
+    }
+
+    #[test]
+    fn merge_carries_category_overrides_and_validate_checks_them() {
+        let base = FeedsConfig {
+            feeds: vec![feed("https://a.example/rss", "A", "tech")],
+            warnings: Vec::new(),
+        };
+        let mut overridden = dynamic("https://a.example/rss", "A", "tech");
+        overridden.category_overrides = Some(vec![rule(&["ai"], "science")]);
+        let merged = FeedsConfig::merge(&base, &[overridden]);
+        assert_eq!(merged.feeds[0].category_overrides, vec![rule(&["ai"], "science")]);
+        assert!(merged.validate().is_empty());
+
+        let bad = FeedsConfig {
+            feeds: vec![FeedConfig {
+                category_overrides: vec![rule(&["ai"], "nope"), rule(&[" "], "science")],
+                ..feed("https://b.example/rss", "B", "tech")
+            }],
+            warnings: Vec::new(),
+        };
+        let errors = bad.validate();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].error.contains("unknown category: nope"), "{}", errors[0].error);
+        assert!(validate_category_overrides(&[rule(&[" "], "science")], |_| true).is_err());
+        assert!(validate_category_overrides(&[rule(&["ai"], "lifestyle")], |c| c == "lifestyle").is_ok());
+    }
+
+    #[test]
+    fn parse_feed_without_quota_keeps_everything() {
+        let xml = rss_with_items(500);
+        let feed = FeedConfig {
+            url: "https://example.com/rss".into(),
+            source: "Firehose".into(),
+            category: "general".into(),
+            max_articles_per_fetch: None,
+            category_overrides: Vec::new(),
+            source_meta: FeedSourceMeta::default(),
+        };
+        let articles = parse_feed(xml.as_bytes(), &feed, &Category::General).unwrap();
+        assert_eq!(articles.len(), 500);
+    }
+
+    #[test]
+    fn parse_feed_preview_reports_channel_metadata() {
+        let xml = rss_with_items(8);
+        let feed = FeedConfig {
+            url: "https://example.com/rss".into(),
+            source: "example.com".into(),
+            category: "general".into(),
+            max_articles_per_fetch: Some(5),
+            category_overrides: Vec::new(),
+            source_meta: FeedSourceMeta::default(),
+        };
+        let preview = parse_feed_preview(xml.as_bytes(), &feed, &Category::General).unwrap();
+        assert_eq!(preview.detected_format, "rss2");
+        assert_eq!(preview.detected_type, "rss2");
+        assert_eq!(preview.feed_title.as_deref(), Some("T"));
+        assert_eq!(preview.feed_description.as_deref(), Some("d"));
+        assert_eq!(preview.item_count, 8);
+        assert_eq!(preview.articles.len(), 5);
+    }
+
+    /// Shaped like NHK's feed: RSS 2.0 that also declares the Atom namespace.
+    const RSS2_FIXTURE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?xml-stylesheet type="text/xsl" href="/rss.xsl"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:media="http://search.yahoo.com/mrss/">
+<channel>
+<title>NHKニュース</title>
+<link>http://www3.nhk.or.jp/news/</link>
+<description>NHKのニュースサイトです。</description>
+<atom:link href="https://www3.nhk.or.jp/rss/news/cat0.xml" rel="self" type="application/rss+xml"/>
+<item>
+<title>全国で真夏日</title>
+<link>http://www3.nhk.or.jp/news/html/20260801/k10014000001000.html</link>
+<pubDate>Sat, 01 Aug 2026 12:00:00 +0900</pubDate>
+<description>各地で気温が上がりました。</description>
+<media:content url="https://www3.nhk.or.jp/news/html/20260801/K10014000001_2608011200_0801120000_01_02.jpg" medium="image"/>
+</item>
+<item>
+<title>株価が反発</title>
+<link>http://www3.nhk.or.jp/news/html/20260801/k10014000002000.html</link>
+<pubDate>Sat, 01 Aug 2026 11:30:00 +0900</pubDate>
+</item>
+</channel>
+</rss>"#;
+
+    /// Shaped like GitHub's release feeds.
+    const ATOM_FIXTURE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- generated -->
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:media="http://search.yahoo.com/mrss/" xml:lang="en-US">
+  <id>tag:github.com,2008:https://github.com/rust-lang/rust/releases</id>
+  <link type="text/html" rel="alternate" href="https://github.com/rust-lang/rust/releases"/>
+  <title>Release notes from rust</title>
+  <updated>2026-08-07T14:00:00Z</updated>
+  <entry>
+    <id>tag:github.com,2008:Repository/724712/1.90.0</id>
+    <updated>2026-08-07T14:00:00Z</updated>
+    <link rel="alternate" type="text/html" href="https://github.com/rust-lang/rust/releases/tag/1.90.0"/>
+    <title>Rust 1.90.0</title>
+    <content type="html">&lt;p&gt;Language changes&lt;/p&gt;</content>
+    <author><name>rust-lang</name></author>
+  </entry>
+</feed>"#;
+
+    /// Shaped like a JSON Feed 1.1 blog, with one item only linking elsewhere.
+    const JSON_FEED_FIXTURE: &str = r#"{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "Daring Fireball",
+  "home_page_url": "https://daringfireball.net/",
+  "feed_url": "https://daringfireball.net/feeds/json",
+  "items": [
+    {
+      "id": "https://daringfireball.net/2026/08/post",
+      "url": "https://daringfireball.net/2026/08/post",
+      "title": "A Post",
+      "content_html": "<p>Body</p>",
+      "summary": "Short summary",
+      "image": "https://daringfireball.net/img/post.jpg",
+      "date_published": "2026-08-06T19:30:00-04:00"
+    },
+    {
+      "id": "linked-1",
+      "external_url": "https://example.com/linked",
+      "content_text": "Worth reading.",
+      "date_modified": "2026-08-05T10:00:00Z"
+    },
+    { "id": "no-link", "title": "Dropped" }
+  ]
+}"#;
+
+    fn fixture_feed() -> FeedConfig {
+        feed("https://example.com/feed", "Example", "general")
+    }
+
+    #[test]
+    fn rss2_parser_is_not_fooled_by_the_atom_namespace() {
+        let body = RSS2_FIXTURE.as_bytes();
+        assert!(Rss2Parser.detect(body));
+        assert!(!AtomParser.detect(body) && !JsonFeedParser.detect(body));
+
+        let preview = Rss2Parser.parse_preview(body, &fixture_feed(), &Category::General).unwrap();
+        assert_eq!((preview.detected_format, preview.detected_type), ("rss2", "rss2"));
+        assert_eq!(preview.feed_title.as_deref(), Some("NHKニュース"));
+        assert_eq!(preview.articles.len(), 2);
+        assert_eq!(preview.articles[0].title, "全国で真夏日");
+        assert!(preview.articles[0].image_url.as_deref().unwrap().ends_with("_01_02.jpg"));
+        assert_eq!(preview.articles[0].published_at.to_rfc3339(), "2026-08-01T03:00:00+00:00");
+        assert!(AtomParser.parse(body, &fixture_feed(), &Category::General).is_err());
+    }
+
+    #[test]
+    fn atom_parser_reads_entries() {
+        let body = ATOM_FIXTURE.as_bytes();
+        assert!(AtomParser.detect(body));
+        assert!(!Rss2Parser.detect(body));
+
+        let articles = AtomParser.parse(body, &fixture_feed(), &Category::General).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].url, "https://github.com/rust-lang/rust/releases/tag/1.90.0");
+        assert_eq!(articles[0].title, "Rust 1.90.0");
+        assert_eq!(articles[0].description.as_deref(), Some("<p>Language changes</p>"));
+        assert_eq!(articles[0].published_at.to_rfc3339(), "2026-08-07T14:00:00+00:00");
+    }
+
+    #[test]
+    fn json_feed_parser_reads_items_and_their_images() {
+        let body = JSON_FEED_FIXTURE.as_bytes();
+        assert!(JsonFeedParser.detect(body));
+        assert!(!Rss2Parser.detect(body) && !AtomParser.detect(body));
+
+        let preview = JsonFeedParser.parse_preview(body, &fixture_feed(), &Category::General).unwrap();
+        assert_eq!((preview.detected_format, preview.detected_type), ("json_feed", "json"));
+        assert_eq!(preview.item_count, 3);
+        let [post, linked] = &preview.articles[..] else { panic!("{:?}", preview.articles) };
+        assert_eq!(post.description.as_deref(), Some("Short summary"));
+        assert_eq!(post.image_url.as_deref(), Some("https://daringfireball.net/img/post.jpg"));
+        assert_eq!(post.published_at.to_rfc3339(), "2026-08-06T23:30:00+00:00");
+        assert_eq!(linked.url, "https://example.com/linked");
+        assert_eq!(linked.title, "(no title)");
+        assert_eq!(linked.description.as_deref(), Some("Worth reading."));
+
+        let not_a_feed = br#"{"version": "2", "items": []}"#;
+        assert!(JsonFeedParser.parse(not_a_feed, &fixture_feed(), &Category::General).is_err());
+    }
+
+    #[test]
+    fn content_type_is_tried_first_then_structure() {
+        let feed = fixture_feed();
+        let parse = |content_type: &str, body: &str| {
+            parse_feed_response(content_type, body.as_bytes(), &feed, &Category::General).map(|p| p.detected_format)
+        };
+        assert_eq!(parse("application/atom+xml; charset=utf-8", ATOM_FIXTURE).unwrap(), "atom");
+        assert_eq!(parse("application/feed+json", JSON_FEED_FIXTURE).unwrap(), "json_feed");
+        // Mislabeled or generic types fall back to detection
+        assert_eq!(parse("application/rss+xml", ATOM_FIXTURE).unwrap(), "atom");
+        assert_eq!(parse("text/xml", RSS2_FIXTURE).unwrap(), "rss2");
+        assert_eq!(parse("", JSON_FEED_FIXTURE).unwrap(), "json_feed");
+        assert!(parse("text/html", "<html><body>Not a feed</body></html>").is_err());
+    }
+
+    fn feed(url: &str, source: &str, category: &str) -> FeedConfig {
+        FeedConfig {
+            url: url.into(),
+            source: source.into(),
+            category: category.into(),
+            max_articles_per_fetch: None,
+            category_overrides: Vec::new(),
+            source_meta: FeedSourceMeta::default(),
+        }
+    }
+
+    /// Local HTTP server answering every request with a 3-item RSS feed after `delay`.
+    async fn slow_feed_server(delay: Duration) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = [0u8; 1024];
+                    let _ = socket.read(&mut request).await;
+                    tokio::time::sleep(delay).await;
+                    let body = rss_with_items(3);
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/rss+xml\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        format!("http://{addr}/rss")
+    }
+
+    #[tokio::test]
+    async fn streaming_fetch_abandons_stragglers_at_the_deadline() {
+        let fast = slow_feed_server(Duration::ZERO).await;
+        let slow = slow_feed_server(Duration::from_secs(30)).await;
+        let feeds = vec![
+            feed(&slow, "Slow", "tech"),
+            feed(&fast, "Fast", "tech"),
+            feed("http://127.0.0.1:1/rss", "Refused", "tech"),
+        ];
+        let limits = FetchLimits {
+            concurrency: 4,
+            per_feed_timeout: Duration::from_secs(20),
+            cycle_deadline: Duration::from_millis(500),
+        };
+        let client = reqwest::Client::new();
+        let started = Instant::now();
+        let mut delivered = Vec::new();
+        let reports = fetch_feeds_streaming(&client, &feeds, limits, |feed, articles| {
+            delivered.push((feed.source.clone(), articles.len(), started.elapsed()));
+        })
+        .await;
+
+        let took = started.elapsed();
+        assert!(took >= Duration::from_millis(500) && took < Duration::from_secs(3), "{took:?}");
+        // The fast feed was handed over well before the deadline
+        assert_eq!(delivered.len(), 1);
+        assert_eq!((delivered[0].0.as_str(), delivered[0].1), ("Fast", 3));
+        assert!(delivered[0].2 < Duration::from_millis(500));
+
+        assert_eq!(reports[0].error.as_deref(), Some("abandoned at cycle deadline"));
+        assert_eq!((reports[1].item_count, reports[1].error.as_deref()), (3, None));
+        assert!(reports[2].error.is_some());
+
+        // A per-feed timeout fails only that feed
+        let limits = FetchLimits {
+            per_feed_timeout: Duration::from_millis(200),
+            cycle_deadline: Duration::from_secs(5),
+            ..limits
+        };
+        let reports = fetch_feeds_streaming(&client, &feeds[..2], limits, |_, _| {}).await;
+        assert!(reports[0].error.as_deref().unwrap().starts_with("timed out"));
+        assert!(reports[1].error.is_none());
+    }
+
+    fn dynamic(url: &str, source: &str, category: &str) -> DynamicFeed {
+        DynamicFeed {
+            feed_id: source.to_lowercase(),
+            url: url.into(),
+            source: source.into(),
+            category: category.into(),
+            enabled: true,
+            added_by: None,
+            max_articles_per_fetch: None,
+            auto_translate: false,
+            category_overrides: None,
+        }
+    }
+
+    #[test]
+    fn normalize_feed_url_ignores_case_scheme_and_trailing_slash() {
+        assert_eq!(
+            normalize_feed_url("HTTP://Example.com/RSS/"),
+            normalize_feed_url("https://example.com/rss")
+        );
+        assert_eq!(normalize_feed_url("https://example.com//"), "https://example.com");
+        assert_ne!(
+            normalize_feed_url("https://example.com/rss"),
+            normalize_feed_url("https://example.com/atom")
+        );
+    }
+
+    #[test]
+    fn merge_overrides_static_feeds_and_appends_new_ones() {
+        let base = FeedsConfig {
+            feeds: vec![
+                feed("https://a.example/rss", "A", "tech"),
+                feed("https://b.example/feed/", "B", "general"),
+            ],
+            warnings: Vec::new(),
+        };
+        let mut disabled = dynamic("https://a.example/rss/", "A", "tech");
+        disabled.enabled = false;
+        let overrides = vec![
+            dynamic("http://B.example/feed", "B News", "business"),
+            dynamic("https://c.example/rss", "C", "science"),
+            disabled,
+            dynamic("https://c.example/rss/", "C Science", "science"),
+        ];
+
+        let merged = FeedsConfig::merge(&base, &overrides);
+        let summary: Vec<_> = merged
+            .feeds
+            .iter()
+            .map(|f| (f.url.as_str(), f.source.as_str(), f.category.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("http://B.example/feed", "B News", "business"),
+                ("https://c.example/rss/", "C Science", "science"),
+            ]
+        );
+    }
+
+    #[test]
+    fn disabling_a_seeded_feed_removes_it_from_the_merge() {
+        let base = FeedsConfig {
+            feeds: vec![
+                feed("https://a.example/rss", "A", "tech"),
+                feed("https://b.example/rss", "B", "general"),
+            ],
+            warnings: Vec::new(),
+        };
+        let mut seeded: Vec<DynamicFeed> = base.feeds.iter().map(|f| dynamic(&f.url, &f.source, &f.category)).collect();
+        assert_eq!(FeedsConfig::merge(&base, &seeded).feeds.len(), 2);
+
+        seeded[0].enabled = false;
+        let merged = FeedsConfig::merge(&base, &seeded);
+        let urls: Vec<&str> = merged.feeds.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(urls, ["https://b.example/rss"]);
+        // The DB row being gone altogether leaves the static feed in place
+        assert_eq!(FeedsConfig::merge(&base, &seeded[1..]).feeds.len(), 2);
+    }
+
+    #[test]
+    fn validate_accepts_sample() {
+        let config = FeedsConfig::from_toml(SAMPLE_TOML).unwrap();
+        assert!(config.validate().is_empty());
+        assert!(config.warnings.is_empty());
+    }
+
+    #[test]
+    fn validate_reports_each_problem() {
+        let config = FeedsConfig {
+            feeds: vec![
+                feed("https://example.com/rss", "Example", "tech"),
+                feed("ftp://example.com/rss", "FTP", "tech"),
+                feed("not a url", "Broken", "tech"),
+                feed("https://example.com/a", "  ", "tech"),
+                feed("https://example.com/b", &"x".repeat(101), "tech"),
+                feed("https://example.com/c", "Gossip", "gossip"),
+                feed("https://example.com/rss", "Example again", "tech"),
+                feed("https://example.com/d", "Gold", "goldmining"),
+            ],
+            warnings: Vec::new(),
+        };
+        let errors = config.validate();
+        let indices: Vec<usize> = errors.iter().map(|e| e.index).collect();
+        assert_eq!(indices, vec![1, 2, 3, 4, 5, 6]);
+        assert!(errors[0].error.contains("scheme"));
+        assert!(errors[4].error.contains("unknown category"));
+        assert!(errors[5].error.contains("feeds[0]"));
+    }
+
+    #[test]
+    fn from_toml_rejects_invalid_feeds() {
+        let toml = r#"
+[[feeds]]
+url = "https://example.com/rss"
+source = "A"
+category = "nope"
+"#;
+        let err = FeedsConfig::from_toml(toml).unwrap_err().to_string();
+        assert!(err.contains("unknown category: nope"), "{err}");
+    }
+
+    #[test]
+    fn from_toml_with_accepts_extra_categories() {
+        let toml = r#"
+[[feeds]]
+url = "https://example.com/rss"
+source = "A"
+category = "lifestyle"
+category_overrides = [{ category = "lifestyle", keywords = ["recipe"] }]
+"#;
+        let known = |c: &str| is_default_category(c) || c == "lifestyle";
+        let config = FeedsConfig::from_toml_with(toml, known).unwrap();
+        assert_eq!(config.feeds[0].category, "lifestyle");
+        assert!(config.validate_with(known).is_empty());
+        let errors = config.validate();
+        assert!(!errors.is_empty() && errors.iter().all(|e| e.error.contains("unknown category: lifestyle")));
+        assert!(FeedsConfig::from_toml(toml).is_err());
+    }
+
+    #[test]
+    fn from_toml_collects_warnings() {
+        let toml = r#"
+[[feeds]]
+url = "http://example.com/rss"
+source = "A"
+category = "Tech"
+"#;
+        let config = FeedsConfig::from_toml(toml).unwrap();
+        assert_eq!(config.warnings.len(), 2);
+        assert!(config.warnings.iter().all(|w| w.index == 0));
+    }
+
+    #[test]
+    fn bundled_feeds_toml_is_valid() {
+        let config = FeedsConfig::from_toml(include_str!("../../../feeds.toml")).unwrap();
+        assert!(!config.feeds.is_empty());
+        for section in ["timemachine", "goldmining"] {
+            assert!(config.feeds.iter().any(|f| f.category == section), "no {section} feeds");
+        }
+    }
+
     #[test]
     fn invalid_toml_returns_error() {
         let result = FeedsConfig::from_toml("not valid toml {{{}}}");
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Extract character trigrams from a string (works well with Japanese + English).
 pub fn trigrams(s: &str) -> HashSet<String> {
@@ -70,6 +70,178 @@ pub fn group_articles(titles: &[&str], threshold: f64) -> Vec<Vec<usize>> {
     groups.into_values().collect()
 }
 
+/// Persistent group assignment for `(id, title, published_at)` items. Each member of a
+/// multi-article group gets `(group_id, group_size)`, where the id is derived from the
+/// group's earliest article (by published_at, then id); singletons get None. The result
+/// doesn't depend on input order, and newer articles joining a group keep its id.
+pub fn assign_groups(items: &[(&str, &str, &str)], threshold: f64) -> Vec<Option<(String, u32)>> {
+    let titles: Vec<&str> = items.iter().map(|(_, title, _)| *title).collect();
+    let mut assigned = vec![None; items.len()];
+    for group in group_articles(&titles, threshold) {
+        if group.len() < 2 {
+            continue;
+        }
+        let anchor = group
+            .iter()
+            .map(|&i| (items[i].2, items[i].0))
+            .min()
+            .map(|(_, id)| id)
+            .unwrap_or_default();
+        let group_id = format!("grp-{}", anchor);
+        for &i in &group {
+            assigned[i] = Some((group_id.clone(), group.len() as u32));
+        }
+    }
+    assigned
+}
+
+/// Number of hash functions in a MinHash signature.
+const MINHASH_SIZE: usize = 64;
+
+fn fnv1a(s: &str) -> u64 {
+    s.bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// MinHash signature of a title's trigram set. The fraction of equal positions in two
+/// signatures estimates their trigram Jaccard similarity (see `similarity`).
+pub fn minhash(s: &str) -> Vec<u64> {
+    let hashes: Vec<u64> = trigrams(s).iter().map(|t| fnv1a(t)).collect();
+    (0..MINHASH_SIZE as u64)
+        .map(|seed| {
+            hashes
+                .iter()
+                .map(|h| splitmix64(h ^ splitmix64(seed)))
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+/// Estimated Jaccard similarity of two MinHash signatures.
+pub fn minhash_similarity(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter().zip(b).filter(|(x, y)| x == y).count() as f64 / a.len() as f64
+}
+
+/// Running groups for `group_articles_incremental`. Every article seen either joins
+/// the most similar group or starts one with itself as representative, so adding n
+/// articles costs O(n × g) signature comparisons instead of regrouping everything.
+pub struct GroupState {
+    threshold: f64,
+    /// group_id → (index of the representative among articles seen, its signature)
+    groups: HashMap<String, (usize, Vec<u64>)>,
+    sizes: HashMap<String, u32>,
+    seen: HashSet<String>,
+}
+
+/// What one `group_articles_incremental` call did.
+#[derive(Debug, Default, PartialEq)]
+pub struct GroupChanges {
+    /// `(index into new_articles, group_id)` for every article that was added.
+    pub assignments: Vec<(usize, String)>,
+    /// Groups that gained members, with their new size.
+    pub grown: Vec<(String, u32)>,
+}
+
+impl GroupState {
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            groups: HashMap::new(),
+            sizes: HashMap::new(),
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.seen.contains(id)
+    }
+
+    /// Size of a group: 1 for an article nobody has joined yet, 0 for an unknown id.
+    pub fn group_size(&self, group_id: &str) -> u32 {
+        self.sizes.get(group_id).copied().unwrap_or(0)
+    }
+
+    /// Record an article whose group is already known, e.g. when rebuilding from
+    /// stored groups. The first member added for a group becomes its representative,
+    /// so add articles oldest first.
+    pub fn insert(&mut self, id: &str, title: &str, group: Option<(&str, u32)>) {
+        if !self.seen.insert(id.to_string()) {
+            return;
+        }
+        let index = self.seen.len() - 1;
+        let (group_id, size) = match group {
+            Some((group_id, size)) => (group_id.to_string(), size),
+            None => (format!("grp-{}", id), 1),
+        };
+        if !self.groups.contains_key(&group_id) {
+            self.groups.insert(group_id.clone(), (index, minhash(title)));
+        }
+        self.sizes.insert(group_id, size);
+    }
+}
+
+/// Add `(id, title)` articles to `state`: each joins the group whose representative is
+/// most similar (at least the state's threshold) or starts a new group `grp-{id}`.
+/// Articles already in the state are skipped. Feed articles oldest first so group ids
+/// match `assign_groups`.
+pub fn group_articles_incremental(state: &mut GroupState, new_articles: &[(&str, &str)]) -> GroupChanges {
+    let mut changes = GroupChanges::default();
+    let mut grown: Vec<String> = Vec::new();
+    for (i, (id, title)) in new_articles.iter().enumerate() {
+        if !state.seen.insert(id.to_string()) {
+            continue;
+        }
+        let index = state.seen.len() - 1;
+        let signature = minhash(title);
+        let best = state
+            .groups
+            .iter()
+            .map(|(group_id, (_, rep))| (minhash_similarity(&signature, rep), group_id))
+            .filter(|(sim, _)| *sim >= state.threshold)
+            .max_by(|a, b| a.0.total_cmp(&b.0).then_with(|| b.1.cmp(a.1)))
+            .map(|(_, group_id)| group_id.clone());
+        let group_id = match best {
+            Some(group_id) => {
+                *state.sizes.entry(group_id.clone()).or_insert(1) += 1;
+                if !grown.contains(&group_id) {
+                    grown.push(group_id.clone());
+                }
+                group_id
+            }
+            None => {
+                let group_id = format!("grp-{}", id);
+                state.groups.insert(group_id.clone(), (index, signature));
+                state.sizes.insert(group_id.clone(), 1);
+                group_id
+            }
+        };
+        changes.assignments.push((i, group_id));
+    }
+    changes.grown = grown
+        .into_iter()
+        .map(|group_id| {
+            let size = state.group_size(&group_id);
+            (group_id, size)
+        })
+        .collect();
+    changes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,4 +314,77 @@ mod tests {
         // All should be separate
         assert_eq!(groups.len(), 3);
     }
+
+    #[test]
+    fn assigned_group_ids_are_stable() {
+        let items = [
+            ("a1", "東京都で新型コロナウイルスの感染者が100人確認", "2025-01-01T10:00:00+00:00"),
+            ("b2", "日銀が金融政策決定会合で利上げを決定", "2025-01-01T09:00:00+00:00"),
+            ("c3", "東京都で新型コロナウイルスの感染者が150人確認", "2025-01-01T08:00:00+00:00"),
+        ];
+        let first = assign_groups(&items, 0.5);
+        assert_eq!(first[0], Some(("grp-c3".to_string(), 2)));
+        assert_eq!(first[2], first[0]);
+        assert_eq!(first[1], None);
+
+        // Same set in another order, and again: same ids
+        let reversed: Vec<_> = items.iter().rev().copied().collect();
+        let second = assign_groups(&reversed, 0.5);
+        assert_eq!(second.into_iter().rev().collect::<Vec<_>>(), first);
+        assert_eq!(assign_groups(&items, 0.5), first);
+
+        // A newer article joining the group keeps its id
+        let mut grown = items.to_vec();
+        grown.push(("d4", "東京都で新型コロナウイルスの感染者が200人確認", "2025-01-01T11:00:00+00:00"));
+        let third = assign_groups(&grown, 0.5);
+        assert_eq!(third[3], Some(("grp-c3".to_string(), 3)));
+    }
+
+    #[test]
+    fn minhash_estimates_trigram_similarity() {
+        let a = "東京都で新型コロナウイルスの感染者が100人確認";
+        let b = "東京都で新型コロナウイルスの感染者が150人確認";
+        let estimate = minhash_similarity(&minhash(a), &minhash(b));
+        assert!((estimate - similarity(a, b)).abs() < 0.2, "{} vs {}", estimate, similarity(a, b));
+        assert_eq!(minhash_similarity(&minhash(a), &minhash(a)), 1.0);
+        assert!(minhash_similarity(&minhash(a), &minhash("日銀が金融政策決定会合で利上げを決定")) < 0.1);
+    }
+
+    #[test]
+    fn incremental_grouping_joins_existing_groups() {
+        let mut state = GroupState::new(0.5);
+        let first = group_articles_incremental(
+            &mut state,
+            &[
+                ("c3", "東京都で新型コロナウイルスの感染者が150人確認"),
+                ("b2", "日銀が金融政策決定会合で利上げを決定"),
+            ],
+        );
+        assert_eq!(first.assignments, vec![(0, "grp-c3".to_string()), (1, "grp-b2".to_string())]);
+        assert!(first.grown.is_empty());
+
+        let second = group_articles_incremental(
+            &mut state,
+            &[
+                ("a1", "東京都で新型コロナウイルスの感染者が100人確認"),
+                ("c3", "東京都で新型コロナウイルスの感染者が150人確認"),
+            ],
+        );
+        // Already-seen articles are skipped
+        assert_eq!(second.assignments, vec![(0, "grp-c3".to_string())]);
+        assert_eq!(second.grown, vec![("grp-c3".to_string(), 2)]);
+        assert!(state.contains("a1"));
+    }
+
+    #[test]
+    fn incremental_grouping_continues_from_inserted_groups() {
+        let mut state = GroupState::new(0.5);
+        state.insert("c3", "東京都で新型コロナウイルスの感染者が150人確認", Some(("grp-c3", 2)));
+        state.insert("a1", "東京都で新型コロナウイルスの感染者が100人確認", Some(("grp-c3", 2)));
+        let changes = group_articles_incremental(
+            &mut state,
+            &[("d4", "東京都で新型コロナウイルスの感染者が200人確認")],
+        );
+        assert_eq!(changes.grown, vec![("grp-c3".to_string(), 3)]);
+    }
 }
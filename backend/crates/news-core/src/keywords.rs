@@ -0,0 +1,149 @@
+use std::collections::{HashMap, HashSet};
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "have", "in", "is",
+    "it", "its", "of", "on", "or", "that", "the", "this", "to", "was", "were", "will", "with",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Script {
+    Latin,
+    Katakana,
+    Kanji,
+    Other,
+}
+
+fn script(c: char) -> Script {
+    match c {
+        c if c.is_ascii_alphanumeric() => Script::Latin,
+        '\u{30A0}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' => Script::Katakana,
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '々' => Script::Kanji,
+        _ => Script::Other,
+    }
+}
+
+/// Split text into candidate terms: runs of ASCII letters/digits, katakana or kanji.
+/// Hiragana and punctuation act as separators (particles, okurigana); single
+/// characters, bare numbers and English stopwords are dropped.
+pub fn terms(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut current_script = Script::Other;
+    for c in text.chars().chain(std::iter::once(' ')) {
+        let s = script(c);
+        if s != current_script || s == Script::Other {
+            push_term(&mut out, &current);
+            current.clear();
+            current_script = s;
+        }
+        if s != Script::Other {
+            current.push(c);
+        }
+    }
+    out
+}
+
+fn push_term(out: &mut Vec<String>, term: &str) {
+    let term = term.trim_matches('ー');
+    if term.chars().count() < 2
+        || term.chars().all(|c| c.is_ascii_digit())
+        || STOPWORDS.contains(&term.to_ascii_lowercase().as_str())
+    {
+        return;
+    }
+    out.push(term.to_string());
+}
+
+/// Document frequencies over a corpus, for scoring the terms of one document.
+pub struct TfIdf {
+    doc_count: usize,
+    df: HashMap<String, usize>,
+}
+
+impl TfIdf {
+    pub fn new<'a>(corpus: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut doc_count = 0;
+        let mut df: HashMap<String, usize> = HashMap::new();
+        for doc in corpus {
+            doc_count += 1;
+            let unique: HashSet<String> = terms(doc).iter().map(|t| t.to_lowercase()).collect();
+            for term in unique {
+                *df.entry(term).or_default() += 1;
+            }
+        }
+        Self { doc_count, df }
+    }
+
+    /// Top `n` terms of `text` by TF-IDF, scaled so the best term scores 1.0. Terms
+    /// keep the spelling they first appear with; ties keep text order.
+    pub fn top_terms(&self, text: &str, n: usize) -> Vec<(String, f64)> {
+        let mut tf: Vec<(String, String, usize)> = Vec::new();
+        for term in terms(text) {
+            let key = term.to_lowercase();
+            match tf.iter_mut().find(|(k, _, _)| *k == key) {
+                Some(entry) => entry.2 += 1,
+                None => tf.push((key, term, 1)),
+            }
+        }
+        let mut scored: Vec<(String, f64)> = tf
+            .into_iter()
+            .map(|(key, surface, count)| {
+                let df = self.df.get(&key).copied().unwrap_or(0);
+                let idf = ((self.doc_count as f64 + 1.0) / (df as f64 + 1.0)).ln() + 1.0;
+                (surface, count as f64 * idf)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(n);
+        if let Some(max) = scored.first().map(|(_, s)| *s).filter(|m| *m > 0.0) {
+            for (_, score) in &mut scored {
+                *score /= max;
+            }
+        }
+        scored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terms_split_on_script_boundaries() {
+        assert_eq!(
+            terms("OpenAIが新モデルGPT-5を発表、東京で記者会見"),
+            vec!["OpenAI", "モデル", "GPT", "発表", "東京", "記者会見"]
+        );
+    }
+
+    #[test]
+    fn terms_drop_stopwords_numbers_and_single_chars() {
+        assert_eq!(terms("The state of AI in 2025: a review"), vec!["state", "AI", "review"]);
+        assert!(terms("の は が").is_empty());
+    }
+
+    #[test]
+    fn rare_terms_outrank_common_ones() {
+        let corpus = [
+            "日本 経済 ニュース",
+            "日本 政治 ニュース",
+            "日本 スポーツ ニュース",
+            "量子計算 研究",
+        ];
+        let tfidf = TfIdf::new(corpus);
+        let top = tfidf.top_terms("日本の量子計算のニュース", 10);
+        assert_eq!(top[0], ("量子計算".to_string(), 1.0));
+        let score = |t: &str| top.iter().find(|(w, _)| w == t).unwrap().1;
+        assert!(score("日本") < score("量子計算"));
+        assert!(top.iter().all(|(_, s)| *s > 0.0 && *s <= 1.0));
+    }
+
+    #[test]
+    fn top_terms_merges_case_and_truncates() {
+        let tfidf = TfIdf::new(["unrelated text"]);
+        let top = tfidf.top_terms("Rust rust RUST compiler release", 2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].0, "Rust");
+        assert_eq!(top[0].1, 1.0);
+    }
+}
@@ -1,13 +1,23 @@
+pub mod balance;
 pub mod changes;
 pub mod config;
 pub mod dedup;
+pub mod enrichment;
 #[cfg(feature = "dynamo")]
 pub mod dynamo;
 pub mod error;
 pub mod feeds;
 pub mod grouping;
+pub mod keywords;
+pub mod links;
 pub mod models;
+pub mod mute;
 pub mod ogp;
+pub mod pagination;
+pub mod polite;
+pub mod reading_time;
+pub mod sites;
+pub mod text;
 
 pub use error::{AppError, Result};
 pub use models::{Article, ArticlesResponse, Category, CategoryInfo};
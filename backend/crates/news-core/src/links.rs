@@ -0,0 +1,295 @@
+//! Article link cleanup at ingest. Feeds wrap links in redirectors (Google News,
+//! feedproxy, t.co) and tag them with tracking parameters, which splits duplicates,
+//! credits clicks to the redirector and shows readers ugly URLs. `canonicalize_links`
+//! swaps in the publisher's own URL and keeps the feed's link as `original_url`.
+
+use crate::dedup::{article_id_from_url, is_tracking_param, ContentFingerprint};
+use crate::models::Article;
+use crate::polite::{PoliteError, PoliteFetcher};
+use base64::Engine;
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+use tracing::{info, warn};
+use url::Url;
+
+/// Redirects followed per link.
+pub const MAX_REDIRECT_HOPS: usize = 3;
+/// Budget for resolving one link, every hop included.
+pub const RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Hosts whose links only lead somewhere else.
+const REDIRECTOR_HOSTS: &[&str] = &[
+    "news.google.com",
+    "feedproxy.google.com",
+    "feeds.feedburner.com",
+    "t.co",
+    "bit.ly",
+    "ow.ly",
+    "buff.ly",
+    "dlvr.it",
+    "trib.al",
+    "lnkd.in",
+    "l.facebook.com",
+    "google.com",
+];
+
+/// Query parameters a wrapper carries its target in (google.com/url?q=,
+/// news.google.com/news/url?url=, l.facebook.com/l.php?u=).
+const TARGET_PARAMS: &[&str] = &["url", "q", "u"];
+
+pub fn is_redirector(url: &Url) -> bool {
+    url.host_str()
+        .map(|h| h.trim_start_matches("www."))
+        .is_some_and(|h| REDIRECTOR_HOSTS.contains(&h))
+}
+
+/// `raw` without tracking query parameters. Unlike `dedup::normalize_url` everything
+/// else (host, parameter order, fragment) is left as the publisher wrote it.
+pub fn strip_tracking_params(raw: &str) -> String {
+    let Ok(mut url) = Url::parse(raw.trim()) else {
+        return raw.trim().to_string();
+    };
+    if url.query().is_none() {
+        return url.to_string();
+    }
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !is_tracking_param(key))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    url.to_string()
+}
+
+/// The target of a redirector link that carries it inline: a `url`/`q`/`u` query
+/// parameter, or the URL inside a Google News `articles/CBMi…` id (base64 protobuf).
+/// Newer Google News ids are encrypted and give None.
+pub fn unwrap_embedded(url: &Url) -> Option<String> {
+    if !is_redirector(url) {
+        return None;
+    }
+    let from_query = url
+        .query_pairs()
+        .find(|(key, value)| TARGET_PARAMS.contains(&key.as_ref()) && is_web_url(value))
+        .map(|(_, value)| value.into_owned());
+    if from_query.is_some() {
+        return from_query;
+    }
+    if url.host_str() != Some("news.google.com") {
+        return None;
+    }
+    let mut segments = url.path_segments()?;
+    segments.find(|s| *s == "articles")?;
+    google_news_target(segments.next()?)
+}
+
+fn google_news_target(id: &str) -> Option<String> {
+    let engine = base64::engine::GeneralPurpose::new(
+        &base64::alphabet::URL_SAFE,
+        base64::engine::GeneralPurposeConfig::new()
+            .with_decode_padding_mode(base64::engine::DecodePaddingMode::Indifferent)
+            .with_decode_allow_trailing_bits(true),
+    );
+    let bytes = engine.decode(id).ok()?;
+    let start = bytes.windows(4).position(|w| w == b"http")?;
+    // The URL is a length-prefixed protobuf string; the next field starts with a
+    // non-printable tag byte
+    let end = bytes[start..]
+        .iter()
+        .position(|b| !b.is_ascii_graphic())
+        .map_or(bytes.len(), |n| start + n);
+    let target = std::str::from_utf8(&bytes[start..end]).ok()?;
+    is_web_url(target).then(|| target.to_string())
+}
+
+fn is_web_url(s: &str) -> bool {
+    Url::parse(s).is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some())
+}
+
+/// Follow `url`'s redirects through `polite`, within `RESOLVE_TIMEOUT`. None if it
+/// doesn't redirect, loops, fails on the first hop or times out.
+pub async fn resolve_redirects(polite: &PoliteFetcher, url: &str) -> Option<String> {
+    let follow = follow_redirects(url, |hop| async move { polite.redirect_target(&hop).await });
+    tokio::time::timeout(RESOLVE_TIMEOUT, follow).await.ok().flatten()
+}
+
+/// Up to `MAX_REDIRECT_HOPS` steps of `next_hop`. A hop that fails after the first
+/// keeps what was reached so far; so does running out of hops, unless that's still a
+/// redirector. A loop gives None.
+async fn follow_redirects<F, Fut>(url: &str, mut next_hop: F) -> Option<String>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<Option<String>, PoliteError>>,
+{
+    let mut seen = vec![url.to_string()];
+    for _ in 0..MAX_REDIRECT_HOPS {
+        let current = seen.last().cloned().unwrap_or_default();
+        match next_hop(current.clone()).await {
+            Ok(Some(next)) if seen.contains(&next) => {
+                warn!(url, hop = %next, "Redirect loop");
+                return None;
+            }
+            Ok(Some(next)) => seen.push(next),
+            Ok(None) => return (seen.len() > 1).then_some(current),
+            Err(e) => {
+                info!(url, hop = %current, error = %e, "Redirect hop failed");
+                return (seen.len() > 1).then_some(current);
+            }
+        }
+    }
+    let last = seen.pop()?;
+    let still_wrapped = Url::parse(&last).map_or(true, |u| is_redirector(&u));
+    (!seen.is_empty() && !still_wrapped).then_some(last)
+}
+
+/// The publisher URL for a feed link: the inline target or resolved redirect of a
+/// redirector link, with tracking parameters stripped.
+pub async fn canonical_link(polite: &PoliteFetcher, link: &str) -> String {
+    let target = match Url::parse(link.trim()) {
+        Ok(url) if is_redirector(&url) => match unwrap_embedded(&url) {
+            Some(target) => Some(target),
+            None => resolve_redirects(polite, link.trim()).await,
+        },
+        _ => None,
+    };
+    strip_tracking_params(target.as_deref().unwrap_or(link))
+}
+
+/// Point freshly parsed `articles` at their publisher URLs and re-derive their ids
+/// from them, so a feed that later links directly doesn't duplicate the article.
+/// Articles whose id is in `stored` are left alone: ids already in the database were
+/// made from the feed's link and stay as they are.
+pub async fn canonicalize_links(polite: &PoliteFetcher, articles: Vec<Article>, stored: &HashSet<String>) -> Vec<Article> {
+    let resolved = articles.into_iter().map(|mut article| async move {
+        if stored.contains(&article.id) {
+            return article;
+        }
+        let link = canonical_link(polite, &article.url).await;
+        if link != article.url {
+            let fingerprint = ContentFingerprint::from_url(&link);
+            article.id = article_id_from_url(&link);
+            article.canonical_url = Some(fingerprint.canonical_url);
+            article.original_url = Some(std::mem::replace(&mut article.url, link));
+        }
+        article
+    });
+    futures::future::join_all(resolved).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn tracking_params_are_stripped_and_the_rest_kept_in_order() {
+        assert_eq!(
+            strip_tracking_params("https://www.example.com/a?z=1&utm_source=rss&utm_medium=feed&id=2&fbclid=x#top"),
+            "https://www.example.com/a?z=1&id=2#top"
+        );
+        assert_eq!(strip_tracking_params("https://example.com/a?gclid=1&yclid=2&cmpid=3"), "https://example.com/a");
+        assert_eq!(strip_tracking_params("https://example.com/a"), "https://example.com/a");
+        assert_eq!(strip_tracking_params(" not a url "), "not a url");
+    }
+
+    #[test]
+    fn google_news_wrappers_are_unwrapped_without_a_request() {
+        let target = "https://www3.nhk.or.jp/news/html/20260801/k10014000001000.html";
+        let mut payload = vec![0x08, 0x13, 0x22, target.len() as u8];
+        payload.extend_from_slice(target.as_bytes());
+        payload.extend_from_slice(&[0xd2, 0x01, 0x00]);
+        let id = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(&payload);
+        let wrapped = url(&format!("https://news.google.com/rss/articles/{id}?oc=5&hl=ja&gl=JP&ceid=JP:ja"));
+        assert!(id.starts_with("CBMi"));
+        assert_eq!(unwrap_embedded(&wrapped).as_deref(), Some(target));
+
+        let old_style = url("https://news.google.com/news/url?sa=t&fd=R&url=https://example.com/story?id=7&usg=x");
+        assert_eq!(unwrap_embedded(&old_style).as_deref(), Some("https://example.com/story?id=7"));
+        let search = url("https://www.google.com/url?rct=j&q=&esrc=s&url=https://example.com/b");
+        assert_eq!(unwrap_embedded(&search).as_deref(), Some("https://example.com/b"));
+
+        // Encrypted ids and non-redirector hosts have nothing to unwrap
+        assert_eq!(unwrap_embedded(&url("https://news.google.com/rss/articles/AU_yqLOpaque?oc=5")), None);
+        assert_eq!(unwrap_embedded(&url("https://example.com/out?url=https://elsewhere.com/")), None);
+    }
+
+    /// `follow_redirects` over a fixed redirect table.
+    async fn follow(table: &[(&str, &str)], start: &str) -> Option<String> {
+        let table: HashMap<String, String> = table.iter().map(|(a, b)| (a.to_string(), b.to_string())).collect();
+        follow_redirects(start, |hop| {
+            let next = table.get(&hop).cloned();
+            async move { Ok(next) }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn redirects_are_followed_a_bounded_number_of_hops() {
+        let chain = [
+            ("https://t.co/abc", "https://bit.ly/xyz"),
+            ("https://bit.ly/xyz", "https://example.com/story"),
+        ];
+        assert_eq!(follow(&chain, "https://t.co/abc").await.as_deref(), Some("https://example.com/story"));
+        assert_eq!(follow(&chain, "https://example.com/story").await, None);
+
+        let long = [
+            ("https://t.co/1", "https://example.com/2"),
+            ("https://example.com/2", "https://example.com/3"),
+            ("https://example.com/3", "https://example.com/4"),
+            ("https://example.com/4", "https://example.com/5"),
+        ];
+        assert_eq!(follow(&long, "https://t.co/1").await.as_deref(), Some("https://example.com/4"));
+        let still_wrapped = [
+            ("https://t.co/1", "https://bit.ly/2"),
+            ("https://bit.ly/2", "https://ow.ly/3"),
+            ("https://ow.ly/3", "https://t.co/4"),
+        ];
+        assert_eq!(follow(&still_wrapped, "https://t.co/1").await, None);
+    }
+
+    #[tokio::test]
+    async fn redirect_loops_resolve_to_nothing() {
+        let looping = [("https://t.co/a", "https://bit.ly/b"), ("https://bit.ly/b", "https://t.co/a")];
+        assert_eq!(follow(&looping, "https://t.co/a").await, None);
+        let to_itself = [("https://t.co/a", "https://t.co/a")];
+        assert_eq!(follow(&to_itself, "https://t.co/a").await, None);
+    }
+
+    #[tokio::test]
+    async fn stored_articles_keep_their_ids() {
+        let polite = PoliteFetcher::new(reqwest::Client::new());
+        let article = |link: &str| {
+            let mut a: Article = serde_json::from_value(serde_json::json!({
+                "id": "", "category": "tech", "title": "t", "url": link, "source": "s",
+                "published_at": "2026-08-01T00:00:00Z", "fetched_at": "2026-08-01T00:00:00Z",
+            }))
+            .unwrap();
+            a.id = crate::dedup::article_id_from_url(link);
+            a
+        };
+        let tagged = "https://example.com/a?utm_source=rss";
+        let wrapped = "https://www.google.com/url?q=https://example.com/b";
+        let stored: HashSet<String> = [article(wrapped).id].into();
+
+        let out = canonicalize_links(&polite, vec![article(tagged), article(wrapped)], &stored).await;
+        assert_eq!(out[0].url, "https://example.com/a");
+        assert_eq!(out[0].original_url.as_deref(), Some(tagged));
+        // Tracking params never fed into the id
+        assert_eq!(out[0].id, crate::dedup::article_id_from_url(tagged));
+        assert_eq!((out[1].url.as_str(), out[1].original_url.as_ref()), (wrapped, None));
+        assert_eq!(out[1].id, crate::dedup::article_id_from_url(wrapped));
+
+        let fresh = canonicalize_links(&polite, vec![article(wrapped)], &HashSet::new()).await;
+        assert_eq!(fresh[0].url, "https://example.com/b");
+        assert_eq!(fresh[0].id, crate::dedup::article_id_from_url("https://example.com/b"));
+    }
+}
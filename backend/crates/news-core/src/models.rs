@@ -1,9 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-/// Article categories matching DynamoDB partition keys.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "lowercase")]
+/// Article categories matching DynamoDB partition keys: the built-in ones, or
+/// `Custom` for an id an admin added to the server's `categories` table.
+/// Serialized as the lowercase id either way.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Category {
     General,
     Tech,
@@ -12,10 +13,11 @@ pub enum Category {
     Sports,
     Science,
     Podcast,
+    Custom(String),
 }
 
 impl Category {
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::General => "general",
             Self::Tech => "tech",
@@ -24,9 +26,12 @@ impl Category {
             Self::Sports => "sports",
             Self::Science => "science",
             Self::Podcast => "podcast",
+            Self::Custom(id) => id,
         }
     }
 
+    /// A built-in category, case-insensitively. Whether any other id exists is up to
+    /// the categories table; see `from_id`.
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "general" => Some(Self::General),
@@ -40,6 +45,17 @@ impl Category {
         }
     }
 
+    /// The category for a stored or configured id: the built-in variant when there is
+    /// one, `Custom` with the lowercased id otherwise. A blank id is `General`.
+    pub fn from_id(id: &str) -> Self {
+        let id = id.trim();
+        if id.is_empty() {
+            return Self::General;
+        }
+        Self::from_str(id).unwrap_or_else(|| Self::Custom(id.to_lowercase()))
+    }
+
+    /// The built-in categories.
     pub fn all() -> &'static [Category] {
         &[
             Self::General,
@@ -53,6 +69,39 @@ impl Category {
     }
 }
 
+/// Categories the server seeds into its `categories` table on first start, as
+/// (id, Japanese label, English label): the built-ins, then the feeds.toml sections
+/// that only exist as rows.
+pub const DEFAULT_CATEGORIES: &[(&str, &str, &str)] = &[
+    ("general", "総合", "General"),
+    ("tech", "テクノロジー", "Technology"),
+    ("business", "ビジネス", "Business"),
+    ("entertainment", "エンタメ", "Entertainment"),
+    ("sports", "スポーツ", "Sports"),
+    ("science", "サイエンス", "Science"),
+    ("podcast", "ポッドキャスト", "Podcast"),
+    ("timemachine", "タイムマシン", "Time Machine"),
+    ("goldmining", "砂金掘り", "Goldmining"),
+];
+
+/// Whether `id` is one of `DEFAULT_CATEGORIES`, case-insensitively.
+pub fn is_default_category(id: &str) -> bool {
+    DEFAULT_CATEGORIES.iter().any(|(c, ..)| c.eq_ignore_ascii_case(id.trim()))
+}
+
+impl Serialize for Category {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Category {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Ok(Self::from_id(&id))
+    }
+}
+
 impl std::fmt::Display for Category {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(self.as_str())
@@ -77,6 +126,21 @@ pub struct Article {
     pub group_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub group_count: Option<u32>,
+    /// Normalized URL used for exact-duplicate detection (see `dedup::normalize_url`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
+    /// Topic tags from AI classification. Only filled for single-article lookups.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Words, or characters for Japanese (see `reading_time::estimate`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_count: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_minutes: Option<u32>,
+    /// The feed's link when `links::canonicalize_links` replaced it (a redirector or
+    /// tracking parameters). Only set on freshly fetched articles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_url: Option<String>,
 }
 
 /// Paginated response for article listing.
@@ -109,6 +173,126 @@ impl CategoryInfo {
     }
 }
 
+/// Kinds of news source, as used by `SourceMeta` and `?exclude_types=`.
+pub const SOURCE_TYPES: &[&str] = &["public_broadcaster", "newspaper", "blog", "pr", "unknown"];
+/// Type of any source without a `source_meta` row.
+pub const SOURCE_TYPE_UNKNOWN: &str = "unknown";
+/// Credibility tiers run from 1 (most credible) to this.
+pub const MAX_CREDIBILITY_TIER: u8 = 3;
+
+/// Editorial metadata about a source, keyed by `Article::source`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceMeta {
+    pub source: String,
+    #[serde(rename = "type")]
+    pub source_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credibility_tier: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_url: Option<String>,
+}
+
+impl SourceMeta {
+    /// What a source without metadata reports.
+    pub fn unknown(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            source_type: SOURCE_TYPE_UNKNOWN.to_string(),
+            credibility_tier: None,
+            homepage_url: None,
+            logo_url: None,
+        }
+    }
+
+    /// Sort key for picking a group representative: lower is more credible, and
+    /// sources without a tier come last.
+    pub fn credibility_rank(&self) -> u8 {
+        self.credibility_tier.unwrap_or(MAX_CREDIBILITY_TIER + 1)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.source.trim().is_empty() {
+            return Err("source is empty".into());
+        }
+        if !SOURCE_TYPES.contains(&self.source_type.as_str()) {
+            return Err(format!("unknown source type: {}", self.source_type));
+        }
+        if let Some(tier) = self.credibility_tier {
+            if !(1..=MAX_CREDIBILITY_TIER).contains(&tier) {
+                return Err(format!("credibility_tier must be 1-{MAX_CREDIBILITY_TIER}"));
+            }
+        }
+        for url in [&self.homepage_url, &self.logo_url].into_iter().flatten() {
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                return Err(format!("not an http(s) URL: {url}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `popularity_score` at which the normalized popularity reaches 0.5.
+pub const POPULARITY_HALF_SCORE: f64 = 10.0;
+/// Time constant (hours) of the recency decay.
+pub const RECENCY_DECAY_HOURS: f64 = 12.0;
+
+/// Weights of the three `score_importance` components.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImportanceWeights {
+    pub ai: f64,
+    pub popularity: f64,
+    pub recency: f64,
+}
+
+impl Default for ImportanceWeights {
+    fn default() -> Self {
+        Self { ai: 0.4, popularity: 0.3, recency: 0.3 }
+    }
+}
+
+impl ImportanceWeights {
+    /// Parse `{"ai": .., "popularity": .., "recency": ..}`; missing keys keep their
+    /// defaults. Negative or non-finite weights are rejected.
+    pub fn from_json(json: &str) -> Option<Self> {
+        let v: serde_json::Value = serde_json::from_str(json).ok()?;
+        let d = Self::default();
+        let get = |key: &str, default: f64| match v.get(key) {
+            None => Some(default),
+            Some(x) => x.as_f64().filter(|w| w.is_finite() && *w >= 0.0),
+        };
+        Some(Self {
+            ai: get("ai", d.ai)?,
+            popularity: get("popularity", d.popularity)?,
+            recency: get("recency", d.recency)?,
+        })
+    }
+
+    pub fn score(&self, ai_importance: Option<f32>, popularity_score: f64, age_hours: f64) -> f64 {
+        ai_importance.map(f64::from).unwrap_or(0.5) * self.ai
+            + normalize_popularity(popularity_score) * self.popularity
+            + recency_decay(age_hours) * self.recency
+    }
+}
+
+/// Map an unbounded popularity score into [0, 1).
+pub fn normalize_popularity(popularity_score: f64) -> f64 {
+    let p = popularity_score.max(0.0);
+    p / (p + POPULARITY_HALF_SCORE)
+}
+
+/// 1.0 for a brand-new article, decaying with `exp(-age/12h)`. Future timestamps count as new.
+pub fn recency_decay(age_hours: f64) -> f64 {
+    (-age_hours.max(0.0) / RECENCY_DECAY_HOURS).exp()
+}
+
+/// Ranking score combining AI importance (0.5 when not analyzed yet), popularity and
+/// recency with the default weights.
+pub fn score_importance(ai_importance: Option<f32>, popularity_score: f64, age_hours: f64) -> f64 {
+    ImportanceWeights::default().score(ai_importance, popularity_score, age_hours)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,4 +312,71 @@ mod tests {
         assert_eq!(Category::from_str("General"), Some(Category::General));
         assert_eq!(Category::from_str("unknown"), None);
     }
+
+    #[test]
+    fn custom_categories_keep_their_id() {
+        assert_eq!(Category::from_id("Lifestyle"), Category::Custom("lifestyle".into()));
+        assert_eq!(Category::from_id("TECH"), Category::Tech);
+        assert_eq!(Category::from_id(" "), Category::General);
+
+        let json = serde_json::to_string(&[Category::Tech, Category::from_id("lifestyle")]).unwrap();
+        assert_eq!(json, r#"["tech","lifestyle"]"#);
+        let parsed: Vec<Category> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, [Category::Tech, Category::Custom("lifestyle".into())]);
+    }
+
+    #[test]
+    fn source_meta_validation_and_rank() {
+        let mut meta = SourceMeta::unknown("NHK");
+        assert!(meta.validate().is_ok());
+        assert_eq!(meta.credibility_rank(), MAX_CREDIBILITY_TIER + 1);
+
+        meta.source_type = "public_broadcaster".into();
+        meta.credibility_tier = Some(1);
+        assert!(meta.validate().is_ok());
+        assert_eq!(meta.credibility_rank(), 1);
+
+        meta.credibility_tier = Some(0);
+        assert!(meta.validate().is_err());
+        meta.credibility_tier = Some(1);
+        meta.source_type = "tabloid".into();
+        assert!(meta.validate().is_err());
+        meta.source_type = "pr".into();
+        meta.logo_url = Some("javascript:alert(1)".into());
+        assert!(meta.validate().is_err());
+    }
+
+    #[test]
+    fn source_meta_serializes_type_field() {
+        let json = serde_json::to_value(SourceMeta::unknown("Blog")).unwrap();
+        assert_eq!(json, serde_json::json!({"source": "Blog", "type": "unknown"}));
+    }
+
+    #[test]
+    fn importance_components() {
+        assert_eq!(normalize_popularity(0.0), 0.0);
+        assert_eq!(normalize_popularity(-3.0), 0.0);
+        assert!((normalize_popularity(POPULARITY_HALF_SCORE) - 0.5).abs() < 1e-9);
+        assert_eq!(recency_decay(0.0), 1.0);
+        assert_eq!(recency_decay(-5.0), 1.0);
+        assert!((recency_decay(12.0) - (-1.0f64).exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn score_importance_formula() {
+        // Unanalyzed, unseen, brand new: 0.5 * 0.4 + 0 + 1.0 * 0.3
+        assert!((score_importance(None, 0.0, 0.0) - 0.5).abs() < 1e-9);
+        assert!(score_importance(Some(0.5), 0.0, 1.0) > score_importance(Some(0.5), 0.0, 24.0));
+        assert!(score_importance(Some(0.9), 0.0, 6.0) > score_importance(Some(0.2), 0.0, 6.0));
+        assert!(score_importance(Some(0.5), 20.0, 1.0) > score_importance(Some(0.5), 5.0, 1.0));
+    }
+
+    #[test]
+    fn importance_weights_from_json() {
+        let w = ImportanceWeights::from_json(r#"{"ai": 0.6, "recency": 0.1}"#).unwrap();
+        assert_eq!(w, ImportanceWeights { ai: 0.6, popularity: 0.3, recency: 0.1 });
+        assert!(ImportanceWeights::from_json(r#"{"ai": -1}"#).is_none());
+        assert!(ImportanceWeights::from_json(r#"{"ai": "high"}"#).is_none());
+        assert!(ImportanceWeights::from_json("not json").is_none());
+    }
 }
@@ -0,0 +1,188 @@
+use crate::models::Article;
+use serde::{Deserialize, Serialize};
+
+/// Which part of an article a mute rule looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MuteField {
+    Title,
+    Source,
+    /// Title, description or source.
+    #[default]
+    Any,
+}
+
+impl MuteField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Source => "source",
+            Self::Any => "any",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "title" => Some(Self::Title),
+            "source" => Some(Self::Source),
+            "any" => Some(Self::Any),
+            _ => None,
+        }
+    }
+}
+
+/// A user's rule for hiding articles. `pattern` matches case-insensitively anywhere in
+/// the field; `*` stands for any run of characters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MuteRule {
+    pub id: String,
+    pub pattern: String,
+    pub field: MuteField,
+    pub created_at: String,
+}
+
+impl MuteRule {
+    pub fn matches(&self, article: &Article) -> bool {
+        let hit = |text: &str| pattern_matches(&self.pattern, text);
+        match self.field {
+            MuteField::Title => hit(&article.title),
+            MuteField::Source => hit(&article.source),
+            MuteField::Any => {
+                hit(&article.title) || hit(&article.source) || article.description.as_deref().is_some_and(hit)
+            }
+        }
+    }
+}
+
+/// Case-insensitive substring match of `pattern` in `text`, where `*` matches any run
+/// of characters (including none). A pattern of only `*` matches nothing.
+pub fn pattern_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let text = text.to_lowercase();
+    let mut parts = pattern.split('*').filter(|p| !p.is_empty()).peekable();
+    if parts.peek().is_none() {
+        return false;
+    }
+    let mut rest = text.as_str();
+    for part in parts {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// A page with muted articles removed.
+#[derive(Debug)]
+pub struct MutedPage {
+    pub articles: Vec<Article>,
+    /// Muted articles among those consumed from the fetched page.
+    pub muted: usize,
+    /// When the page was cut at `limit` visible articles, how many fetched articles
+    /// were consumed; the next page should resume right after them.
+    pub consumed: Option<usize>,
+}
+
+/// Drop articles matching any of `rules` from an over-fetched page and keep at most
+/// `limit`. Articles after the cut aren't consumed, so the caller's cursor must resume
+/// at `consumed` rather than after the whole fetched page.
+pub fn filter_page(articles: Vec<Article>, rules: &[MuteRule], limit: usize) -> MutedPage {
+    let keep: Vec<bool> = articles.iter().map(|a| !rules.iter().any(|r| r.matches(a))).collect();
+    let mut visible = 0;
+    let mut cut = articles.len();
+    for (i, kept) in keep.iter().enumerate() {
+        if *kept {
+            visible += 1;
+            if visible == limit {
+                cut = i + 1;
+                break;
+            }
+        }
+    }
+    let consumed = (cut < articles.len()).then_some(cut);
+    let muted = keep[..cut].iter().filter(|k| !**k).count();
+    let articles = articles
+        .into_iter()
+        .zip(keep)
+        .take(cut)
+        .filter_map(|(a, kept)| kept.then_some(a))
+        .collect();
+    MutedPage { articles, muted, consumed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Category;
+    use chrono::Utc;
+
+    fn article(title: &str, source: &str) -> Article {
+        Article {
+            id: title.to_string(),
+            category: Category::General,
+            title: title.to_string(),
+            url: format!("https://example.com/{title}"),
+            description: None,
+            image_url: None,
+            source: source.to_string(),
+            published_at: Utc::now(),
+            fetched_at: Utc::now(),
+            group_id: None,
+            group_count: None,
+            canonical_url: None,
+            tags: Vec::new(),
+            word_count: None,
+            reading_minutes: None,
+            original_url: None,
+        }
+    }
+
+    fn rule(pattern: &str, field: MuteField) -> MuteRule {
+        MuteRule {
+            id: pattern.to_string(),
+            pattern: pattern.to_string(),
+            field,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn patterns_match_case_insensitive_with_wildcards() {
+        assert!(pattern_matches("giants", "Yomiuri GIANTS win again"));
+        assert!(pattern_matches("巨人*勝利", "巨人が逆転勝利"));
+        assert!(!pattern_matches("巨人*勝利", "勝利の巨人"));
+        assert!(pattern_matches("*tigers", "Hanshin Tigers"));
+        assert!(!pattern_matches("*", "anything"));
+        assert!(!pattern_matches("", "anything"));
+    }
+
+    #[test]
+    fn rules_check_their_field() {
+        let a = article("Celebrity gossip roundup", "Tabloid Daily");
+        assert!(rule("gossip", MuteField::Title).matches(&a));
+        assert!(!rule("gossip", MuteField::Source).matches(&a));
+        assert!(rule("tabloid", MuteField::Source).matches(&a));
+        assert!(rule("tabloid", MuteField::Any).matches(&a));
+    }
+
+    #[test]
+    fn filtered_page_is_cut_at_limit_and_reports_where_to_resume() {
+        let page: Vec<Article> = ["a1", "muted1", "a2", "muted2", "a3", "a4"]
+            .iter()
+            .map(|t| article(t, "src"))
+            .collect();
+        let rules = [rule("muted", MuteField::Title)];
+
+        let result = filter_page(page.clone(), &rules, 2);
+        let titles: Vec<&str> = result.articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["a1", "a2"]);
+        assert_eq!(result.muted, 1);
+        assert_eq!(result.consumed, Some(3));
+
+        let result = filter_page(page, &rules, 10);
+        assert_eq!(result.articles.len(), 4);
+        assert_eq!(result.muted, 2);
+        assert_eq!(result.consumed, None);
+    }
+}
@@ -1,13 +1,15 @@
+use crate::polite::PoliteFetcher;
 use tracing::warn;
 
 /// Extract article body text from HTML (strips scripts/styles, extracts p/h/li text).
 /// Returns up to 3000 chars of meaningful content.
 pub fn extract_article_text(html: &str) -> String {
-    // Remove <script>...</script> and <style>...</style> blocks
-    let re_script = regex::Regex::new(r"(?is)<script[^>]*>.*?</script>").unwrap();
-    let re_style = regex::Regex::new(r"(?is)<style[^>]*>.*?</style>").unwrap();
-    let cleaned = re_script.replace_all(html, "");
-    let cleaned = re_style.replace_all(&cleaned, "");
+    extract_article_text_up_to(html, 3000)
+}
+
+/// `extract_article_text` with a different byte budget.
+pub fn extract_article_text_up_to(html: &str, max_len: usize) -> String {
+    let cleaned = strip_scripts_and_styles(html);
 
     // Extract text from <p>, <h1>-<h6>, <li> tags
     let re_tags = regex::Regex::new(r"(?is)<(?:p|h[1-6]|li)[^>]*>(.*?)</(?:p|h[1-6]|li)>").unwrap();
@@ -22,39 +24,225 @@ pub fn extract_article_text(html: &str) -> String {
         if text.is_empty() || text.len() < 5 {
             continue;
         }
-        let decoded = text
-            .replace("&amp;", "&")
-            .replace("&lt;", "<")
-            .replace("&gt;", ">")
-            .replace("&quot;", "\"")
-            .replace("&#39;", "'")
-            .replace("&nbsp;", " ");
+        let decoded = decode_entities(text);
         let decoded = decoded.trim().to_string();
         if decoded.is_empty() {
             continue;
         }
         total_len += decoded.len();
         texts.push(decoded);
-        if total_len >= 3000 {
+        if total_len >= max_len {
             break;
         }
     }
 
     let mut result = texts.join("\n");
-    if result.len() > 3000 {
-        // Truncate at char boundary
-        let mut end = 3000;
-        while end > 0 && !result.is_char_boundary(end) {
-            end -= 1;
+    let end = crate::text::truncate_bytes_floor(&result, max_len).len();
+    result.truncate(end);
+    result
+}
+
+/// Lightweight HTML-to-Markdown for article bodies: `h1`–`h3` become `#`–`###` (deeper
+/// headings `####`), `li` becomes `- `, links with absolute URLs `[text](url)`, and other
+/// `p` blocks plain paragraphs. Scripts, styles and every other tag are dropped.
+pub fn html_to_markdown(html: &str) -> String {
+    let cleaned = strip_scripts_and_styles(html);
+    let re_blocks =
+        regex::Regex::new(r"(?is)<(p|h[1-6]|li)\b[^>]*>(.*?)</(?:p|h[1-6]|li)\s*>").unwrap();
+    let re_link =
+        regex::Regex::new(r#"(?is)<a\b[^>]*?\bhref\s*=\s*["']([^"']+)["'][^>]*>(.*?)</a\s*>"#).unwrap();
+
+    let mut out = String::new();
+    let mut prev_was_item = false;
+    for cap in re_blocks.captures_iter(&cleaned) {
+        let tag = cap[1].to_ascii_lowercase();
+        let inner = re_link.replace_all(&cap[2], |link: &regex::Captures| {
+            let text = collapse_whitespace(&strip_tags(&link[2]));
+            let href = decode_entities(&link[1]);
+            if text.is_empty() || !href.starts_with("http") {
+                text
+            } else {
+                format!("[{text}]({href})")
+            }
+        });
+        let text = collapse_whitespace(&decode_entities(&strip_tags(&inner)));
+        if text.is_empty() {
+            continue;
+        }
+        let prefix = match tag.as_str() {
+            "h1" => "# ",
+            "h2" => "## ",
+            "h3" => "### ",
+            "h4" | "h5" | "h6" => "#### ",
+            "li" => "- ",
+            _ => "",
+        };
+        let is_item = tag == "li";
+        if !out.is_empty() {
+            out.push_str(if is_item && prev_was_item { "\n" } else { "\n\n" });
         }
-        result.truncate(end);
+        out.push_str(prefix);
+        out.push_str(&text);
+        prev_was_item = is_item;
     }
-    result
+    out
+}
+
+/// Page furniture dropped before `readable_html` looks for content.
+const NON_CONTENT_TAGS: &[&str] = &["nav", "header", "footer", "aside", "form", "noscript", "iframe", "svg"];
+
+/// Article body as clean, escaped HTML for reader mode: headings, paragraphs, list
+/// items (grouped into `<ul>`), blockquotes, images and links. Navigation, headers,
+/// footers and blocks whose class or id looks like an ad or share widget are dropped.
+/// Image and link URLs are kept as written, so relative ones need a `<base>`.
+pub fn readable_html(html: &str) -> String {
+    let mut cleaned = strip_scripts_and_styles(html);
+    for tag in NON_CONTENT_TAGS {
+        let re = regex::Regex::new(&format!(r"(?is)<{tag}\b[^>]*>.*?</{tag}\s*>")).unwrap();
+        cleaned = re.replace_all(&cleaned, "").into_owned();
+    }
+    let re_blocks = regex::Regex::new(
+        r#"(?is)<blockquote\b([^>]*)>(.*?)</blockquote\s*>|<(p|h[1-6]|li)\b([^>]*)>(.*?)</(?:p|h[1-6]|li)\s*>|<img\b[^>]*>"#,
+    )
+    .unwrap();
+    let re_junk = regex::Regex::new(
+        r#"(?i)\b(?:class|id)\s*=\s*["'][^"']*\b(?:ad|ads|advert\w*|sponsor\w*|promo\w*|share|social|related)\b"#,
+    )
+    .unwrap();
+    let re_img = regex::Regex::new(r"(?is)<img\b[^>]*>").unwrap();
+
+    let mut out = String::new();
+    let mut in_list = false;
+    for cap in re_blocks.captures_iter(&cleaned) {
+        let (tag, attrs, inner) = match (cap.get(1), cap.get(3)) {
+            (Some(attrs), _) => ("blockquote".to_string(), attrs.as_str(), &cap[2]),
+            (_, Some(tag)) => (tag.as_str().to_ascii_lowercase(), &cap[4], &cap[5]),
+            _ => ("img".to_string(), "", &cap[0]),
+        };
+        if re_junk.is_match(attrs) {
+            continue;
+        }
+        let block = if tag == "img" {
+            image_html(inner).unwrap_or_default()
+        } else {
+            let text = inline_html(inner);
+            let images: String = re_img
+                .find_iter(inner)
+                .filter_map(|m| image_html(m.as_str()))
+                .collect();
+            match (text.is_empty(), tag.as_str()) {
+                (true, _) => images,
+                (false, "blockquote") => format!("<blockquote><p>{text}</p></blockquote>{images}"),
+                (false, tag) => format!("<{tag}>{text}</{tag}>{images}"),
+            }
+        };
+        if block.is_empty() {
+            continue;
+        }
+        let is_item = block.starts_with("<li>");
+        if is_item != in_list {
+            out.push_str(if is_item { "<ul>\n" } else { "</ul>\n" });
+            in_list = is_item;
+        }
+        out.push_str(&block);
+        out.push('\n');
+    }
+    if in_list {
+        out.push_str("</ul>\n");
+    }
+    out
+}
+
+/// Text of a block with its links kept as `<a>`; everything else is escaped text.
+fn inline_html(inner: &str) -> String {
+    let re_link =
+        regex::Regex::new(r#"(?is)<a\b[^>]*?\bhref\s*=\s*["']([^"']+)["'][^>]*>(.*?)</a\s*>"#).unwrap();
+    let mut out = String::new();
+    let mut last = 0;
+    for link in re_link.captures_iter(inner) {
+        let whole = link.get(0).unwrap();
+        out.push_str(&escape_html(&decode_entities(&strip_tags(&inner[last..whole.start()]))));
+        let text = escape_html(&decode_entities(&strip_tags(&link[2])));
+        let href = decode_entities(&link[1]);
+        if is_safe_url(&href) && !text.trim().is_empty() {
+            out.push_str(&format!(r#"<a href="{}" rel="noopener" target="_blank">{}</a>"#, escape_html(&href), text));
+        } else {
+            out.push_str(&text);
+        }
+        last = whole.end();
+    }
+    out.push_str(&escape_html(&decode_entities(&strip_tags(&inner[last..]))));
+    collapse_whitespace(&out)
+}
+
+fn image_html(tag: &str) -> Option<String> {
+    let re_src = regex::Regex::new(r#"(?is)\bsrc\s*=\s*["']([^"']+)["']"#).unwrap();
+    let re_alt = regex::Regex::new(r#"(?is)\balt\s*=\s*["']([^"']*)["']"#).unwrap();
+    let src = decode_entities(re_src.captures(tag)?.get(1)?.as_str());
+    if !is_safe_url(&src) {
+        return None;
+    }
+    let alt = re_alt.captures(tag).map(|c| decode_entities(&c[1])).unwrap_or_default();
+    Some(format!(r#"<img src="{}" alt="{}" loading="lazy">"#, escape_html(&src), escape_html(&alt)))
+}
+
+/// http(s) or relative; rules out `javascript:`, `data:` and the like.
+fn is_safe_url(url: &str) -> bool {
+    match url.split_once(':') {
+        Some((scheme, _)) if !scheme.contains('/') => {
+            scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+        }
+        _ => true,
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Remove anything that still looks like an HTML tag.
+pub fn strip_tags(text: &str) -> String {
+    let re_html_tag = regex::Regex::new(r"<[^>]+>").unwrap();
+    re_html_tag.replace_all(text, "").into_owned()
+}
+
+fn strip_scripts_and_styles(html: &str) -> String {
+    let re_script = regex::Regex::new(r"(?is)<script[^>]*>.*?</script>").unwrap();
+    let re_style = regex::Regex::new(r"(?is)<style[^>]*>.*?</style>").unwrap();
+    let cleaned = re_script.replace_all(html, "");
+    re_style.replace_all(&cleaned, "").into_owned()
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 /// Fetch article content from a URL. Returns None on failure or empty content.
-pub async fn fetch_article_content(client: &reqwest::Client, url: &str) -> Option<String> {
-    let response = match client.get(url).send().await {
+pub async fn fetch_article_content(fetcher: &PoliteFetcher, url: &str) -> Option<String> {
+    let html = fetch_article_html(fetcher, url).await?;
+    let text = extract_article_text(&html);
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Fetch a page's HTML (first 256KB). Returns None on failure or a non-2xx status.
+pub async fn fetch_article_html(fetcher: &PoliteFetcher, url: &str) -> Option<String> {
+    let response = match fetcher.get(url).await {
         Ok(r) => r,
         Err(e) => {
             warn!(url = %url, error = %e, "Failed to fetch article content");
@@ -72,13 +260,7 @@ pub async fn fetch_article_content(client: &reqwest::Client, url: &str) -> Optio
         Err(_) => return None,
     };
 
-    let html = String::from_utf8_lossy(&bytes[..bytes.len().min(262144)]);
-    let text = extract_article_text(&html);
-    if text.is_empty() {
-        None
-    } else {
-        Some(text)
-    }
+    Some(String::from_utf8_lossy(&bytes[..bytes.len().min(262144)]).into_owned())
 }
 
 /// Extract og:image URL from HTML content using regex (lightweight, no scraper crate).
@@ -106,9 +288,33 @@ pub fn extract_og_image(html: &str) -> Option<String> {
     None
 }
 
-/// Fetch og:image from a URL. Returns None on any failure.
-pub async fn fetch_og_image(client: &reqwest::Client, url: &str) -> Option<String> {
-    let response = match client.get(url).send().await {
+/// Path of the server's SVG placeholder card endpoint.
+pub const PLACEHOLDER_IMAGE_PATH: &str = "/api/placeholder-image";
+/// Title characters kept on a placeholder card.
+pub const PLACEHOLDER_TITLE_CHARS: usize = 50;
+
+/// URL of a generated placeholder card for an article without an og:image.
+pub fn generate_placeholder_image_url(title: &str, source: &str, category: &str, base_url: &str) -> String {
+    let title: String = title.trim().chars().take(PLACEHOLDER_TITLE_CHARS).collect();
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("title", &title)
+        .append_pair("source", source)
+        .append_pair("category", category)
+        .finish();
+    format!("{}{PLACEHOLDER_IMAGE_PATH}?{query}", base_url.trim_end_matches('/'))
+}
+
+/// Whether `url` is one of `generate_placeholder_image_url`'s cards. Crawlers don't
+/// render SVG previews, so OGP tags should use the site image instead.
+pub fn is_placeholder_image(url: &str) -> bool {
+    url.contains(PLACEHOLDER_IMAGE_PATH)
+}
+
+/// Fetch og:image from a URL. When the page loads but has no usable og:image (or
+/// answers with an error status), returns `placeholder`; None only when the page
+/// couldn't be fetched at all, so the article is tried again later.
+pub async fn fetch_og_image(fetcher: &PoliteFetcher, url: &str, placeholder: Option<String>) -> Option<String> {
+    let response = match fetcher.get(url).await {
         Ok(r) => r,
         Err(e) => {
             warn!(url = %url, error = %e, "Failed to fetch page for OGP");
@@ -117,7 +323,7 @@ pub async fn fetch_og_image(client: &reqwest::Client, url: &str) -> Option<Strin
     };
 
     if !response.status().is_success() {
-        return None;
+        return placeholder;
     }
 
     // Only read first 64KB to find og:image (it's in <head>)
@@ -127,13 +333,26 @@ pub async fn fetch_og_image(client: &reqwest::Client, url: &str) -> Option<Strin
     };
 
     let html = String::from_utf8_lossy(&bytes[..bytes.len().min(65536)]);
-    extract_og_image(&html)
+    extract_og_image(&html).or(placeholder)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn placeholder_url_encodes_truncated_metadata() {
+        let title = "あ".repeat(60);
+        let url = generate_placeholder_image_url(&title, "NHK & Co", "tech", "https://news.xyz/");
+        assert!(url.starts_with("https://news.xyz/api/placeholder-image?title="));
+        assert!(url.contains("&source=NHK+%26+Co&category=tech"));
+        let parsed = url::Url::parse(&url).unwrap();
+        let (_, title_param) = parsed.query_pairs().find(|(k, _)| k == "title").unwrap();
+        assert_eq!(title_param.chars().count(), PLACEHOLDER_TITLE_CHARS);
+        assert!(is_placeholder_image(&url));
+        assert!(!is_placeholder_image("https://example.com/image.jpg"));
+    }
+
     #[test]
     fn extract_standard_og_image() {
         let html = r#"
@@ -219,4 +438,50 @@ mod tests {
         let text = extract_article_text(&html);
         assert!(text.len() <= 3000);
     }
+
+    #[test]
+    fn html_to_markdown_headings_lists_and_links() {
+        let html = r#"
+        <script>var x = 1;</script>
+        <h2>Overview</h2>
+        <p>Read the <a href="https://example.com/report?a=1&amp;b=2">full report</a> now.</p>
+        <h3>Key points</h3>
+        <ul><li>First</li><li><b>Second</b> point</li></ul>
+        <p>See <a href="/local">this page</a>.</p>
+        "#;
+        assert_eq!(
+            html_to_markdown(html),
+            "## Overview\n\n\
+             Read the [full report](https://example.com/report?a=1&b=2) now.\n\n\
+             ### Key points\n\n\
+             - First\n\
+             - Second point\n\n\
+             See this page."
+        );
+    }
+
+    #[test]
+    fn html_to_markdown_ignores_lookalike_tags() {
+        let html = r#"<link rel="x"><pre>code</pre><p>Body text.</p>"#;
+        assert_eq!(html_to_markdown(html), "Body text.");
+    }
+
+    #[test]
+    fn readable_html_keeps_content_and_drops_furniture() {
+        let html = r#"<nav><p>Home</p></nav><header><h1>Site</h1></header>
+            <h1>Title</h1><p>First &amp; <b>bold</b> <a href="/more">more</a>
+            <a href="javascript:alert(1)">bad</a><img src="/img/a.jpg" alt="A"></p>
+            <div class="ad"><p class="ad-slot">Buy now</p></div>
+            <ul><li>One</li><li>Two</li></ul><blockquote><p>Quote</p></blockquote>
+            <p>&lt;script&gt;</p><img src="data:image/png;base64,xx"><footer><p>(c)</p></footer>"#;
+        assert_eq!(
+            readable_html(html),
+            "<h1>Title</h1>\n\
+             <p>First &amp; bold <a href=\"/more\" rel=\"noopener\" target=\"_blank\">more</a> bad</p>\
+             <img src=\"/img/a.jpg\" alt=\"A\" loading=\"lazy\">\n\
+             <ul>\n<li>One</li>\n<li>Two</li>\n</ul>\n\
+             <blockquote><p>Quote</p></blockquote>\n\
+             <p>&lt;script&gt;</p>\n"
+        );
+    }
 }
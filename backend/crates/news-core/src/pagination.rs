@@ -0,0 +1,170 @@
+//! Cursor pagination shared by the list endpoints. A `Paginator` is what the request
+//! asked for, a `Page` is what came back. Cursors are opaque to clients: URL-safe
+//! base64 of either a (published_at, id) keyset or, for orderings whose scores move
+//! between requests, a plain offset.
+
+use crate::models::Article;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::Serialize;
+
+pub const DEFAULT_LIMIT: i64 = 20;
+pub const MAX_LIMIT: i64 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paginator {
+    pub limit: i64,
+    pub cursor: Option<String>,
+}
+
+impl Paginator {
+    /// A client-supplied page, `limit` clamped to `1..=MAX_LIMIT`.
+    pub fn new(limit: i64, cursor: Option<String>) -> Self {
+        Self {
+            limit: limit.clamp(1, MAX_LIMIT),
+            cursor: cursor.filter(|c| !c.is_empty()),
+        }
+    }
+
+    /// First `limit` items without the client cap, for server-side reads such as
+    /// feeds, digests or over-fetching ahead of mute filtering.
+    pub fn first(limit: i64) -> Self {
+        Self::uncapped(limit, None)
+    }
+
+    /// Like `new`, but only clamped below; the caller has already bounded `limit`.
+    pub fn uncapped(limit: i64, cursor: Option<String>) -> Self {
+        Self {
+            limit: limit.max(1),
+            cursor: cursor.filter(|c| !c.is_empty()),
+        }
+    }
+
+    /// The (published_at, id) keyset to continue after. Unreadable cursors start over.
+    pub fn keyset(&self) -> Option<(String, String)> {
+        let v = decode(self.cursor.as_deref()?)?;
+        Some((v.get("p")?.as_str()?.to_string(), v.get("i")?.as_str()?.to_string()))
+    }
+
+    /// The offset to continue from; 0 without a readable offset cursor.
+    pub fn offset(&self) -> i64 {
+        self.cursor.as_deref().and_then(decode_offset).unwrap_or(0)
+    }
+}
+
+/// Something a keyset page can continue after.
+pub trait Keyset {
+    /// (published_at in RFC 3339, id)
+    fn keyset(&self) -> (String, String);
+}
+
+impl Keyset for Article {
+    fn keyset(&self) -> (String, String) {
+        (self.published_at.to_rfc3339(), self.id.clone())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    /// Total matching items, when it was cheap to know.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_hint: Option<i64>,
+}
+
+impl<T: Keyset> Page<T> {
+    /// `items` as fetched with `limit + 1`: the extra row only tells that another
+    /// page exists, and is cut off.
+    pub fn new(mut items: Vec<T>, limit: i64) -> Self {
+        let next_cursor = if items.len() as i64 > limit {
+            items.truncate(limit as usize);
+            items.last().map(keyset_cursor)
+        } else {
+            None
+        };
+        Self { items, next_cursor, total_hint: None }
+    }
+}
+
+impl<T> Page<T> {
+    /// Everything there is; no further page.
+    pub fn complete(items: Vec<T>) -> Self {
+        Self { items, next_cursor: None, total_hint: None }
+    }
+
+    /// Like `new`, for a page that started at `offset`.
+    pub fn at_offset(mut items: Vec<T>, limit: i64, offset: i64) -> Self {
+        let next_cursor = if items.len() as i64 > limit {
+            items.truncate(limit as usize);
+            Some(offset_cursor(offset + limit))
+        } else {
+            None
+        };
+        Self { items, next_cursor, total_hint: None }
+    }
+}
+
+pub fn keyset_cursor<T: Keyset>(item: &T) -> String {
+    let (published_at, id) = item.keyset();
+    encode(&serde_json::json!({ "p": published_at, "i": id }))
+}
+
+pub fn offset_cursor(offset: i64) -> String {
+    encode(&serde_json::json!({ "o": offset }))
+}
+
+pub fn decode_offset(cursor: &str) -> Option<i64> {
+    decode(cursor)?.get("o")?.as_i64().filter(|o| *o >= 0)
+}
+
+fn encode(value: &serde_json::Value) -> String {
+    URL_SAFE_NO_PAD.encode(value.to_string().as_bytes())
+}
+
+fn decode(cursor: &str) -> Option<serde_json::Value> {
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item(&'static str);
+
+    impl Keyset for Item {
+        fn keyset(&self) -> (String, String) {
+            ("2026-01-01T00:00:00+00:00".into(), self.0.into())
+        }
+    }
+
+    #[test]
+    fn limits_are_clamped() {
+        assert_eq!(Paginator::new(0, None).limit, 1);
+        assert_eq!(Paginator::new(500, None).limit, MAX_LIMIT);
+        assert_eq!(Paginator::new(5, Some(String::new())), Paginator { limit: 5, cursor: None });
+        assert_eq!(Paginator::first(300).limit, 300);
+    }
+
+    #[test]
+    fn keyset_page_truncates_and_resumes_after_the_last_item() {
+        let page = Page::new(vec![Item("a"), Item("b"), Item("c")], 2);
+        assert_eq!(page.items.len(), 2);
+        let next = Paginator::new(2, page.next_cursor);
+        assert_eq!(next.keyset(), Some(("2026-01-01T00:00:00+00:00".into(), "b".into())));
+
+        let last = Page::new(vec![Item("c")], 2);
+        assert!(last.next_cursor.is_none());
+        assert!(Paginator::new(2, Some("not a cursor".into())).keyset().is_none());
+    }
+
+    #[test]
+    fn offset_page_continues_from_where_it_stopped() {
+        let page = Page::at_offset(vec![1, 2, 3], 2, 40);
+        assert_eq!(page.items, [1, 2]);
+        assert_eq!(Paginator::new(2, page.next_cursor).offset(), 42);
+        // A keyset cursor isn't an offset
+        assert_eq!(Paginator::new(2, Some(keyset_cursor(&Item("a")))).offset(), 0);
+        assert_eq!(decode_offset(&offset_cursor(-1)), None);
+    }
+}
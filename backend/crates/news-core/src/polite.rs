@@ -0,0 +1,367 @@
+//! Polite fetching of publisher pages: robots.txt, per-host pacing, a global
+//! concurrency cap and back-off after 429/403.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
+
+/// Product token matched against robots.txt `User-agent` lines.
+pub const ROBOTS_AGENT: &str = "NewsAggregator";
+
+const ROBOTS_TTL: Duration = Duration::from_secs(24 * 3600);
+const MIN_INTERVAL: Duration = Duration::from_secs(2);
+const BACKOFF: Duration = Duration::from_secs(3600);
+const MAX_CONCURRENT: usize = 8;
+/// One `redirect_target` request.
+const REDIRECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// robots.txt bodies past this size are truncated.
+const ROBOTS_MAX_BYTES: usize = 512 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PoliteError {
+    #[error("invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("disallowed by robots.txt: {0}")]
+    Disallowed(String),
+    #[error("backing off {0} after 429/403")]
+    BackedOff(String),
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// Allow/Disallow rules of the robots.txt group that applies to one user agent.
+#[derive(Debug, Default, Clone)]
+pub struct Robots {
+    /// `(allow, path pattern)`
+    rules: Vec<(bool, String)>,
+}
+
+impl Robots {
+    /// Parse robots.txt for `agent`: the groups naming it (case-insensitive product
+    /// token match) if any, otherwise the `*` groups.
+    pub fn parse(txt: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        let names_agent = |a: &str| !a.is_empty() && a != "*" && agent.contains(a);
+        let mut specific = Vec::new();
+        let mut wildcard = Vec::new();
+        let mut has_specific_group = false;
+        // Agents of the group being read; a rule line ends the list of agents
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in txt.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        agents.clear();
+                        in_rules = false;
+                    }
+                    let name = value.to_ascii_lowercase();
+                    has_specific_group |= names_agent(&name);
+                    agents.push(name);
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty Disallow allows everything
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (key == "allow", value.to_string());
+                    if agents.iter().any(|a| names_agent(a)) {
+                        specific.push(rule);
+                    } else if agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if has_specific_group { specific } else { wildcard },
+        }
+    }
+
+    /// Whether `path` (path plus query) may be fetched. The longest matching pattern
+    /// wins; Allow wins a tie.
+    pub fn allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// robots.txt path pattern: a prefix match with `*` wildcards and an optional `$` end
+/// anchor.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else { return false };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i == parts.len() - 1;
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// Per-host pacing and back-off. Times are passed in so the rules can be tested
+/// without sleeping.
+#[derive(Debug, Default)]
+pub struct HostThrottle {
+    min_interval: Duration,
+    backoff: Duration,
+    next_slot: HashMap<String, Instant>,
+    blocked_until: HashMap<String, Instant>,
+}
+
+impl HostThrottle {
+    pub fn new(min_interval: Duration, backoff: Duration) -> Self {
+        Self {
+            min_interval,
+            backoff,
+            ..Default::default()
+        }
+    }
+
+    /// Reserve the next request slot for `host`: how long to wait before sending, or
+    /// None while the host is backed off.
+    pub fn reserve(&mut self, host: &str, now: Instant) -> Option<Duration> {
+        if self.blocked_until.get(host).is_some_and(|until| *until > now) {
+            return None;
+        }
+        let slot = self.next_slot.get(host).copied().filter(|s| *s > now).unwrap_or(now);
+        self.next_slot.insert(host.to_string(), slot + self.min_interval);
+        Some(slot - now)
+    }
+
+    /// Stop sending to `host` for the back-off period.
+    pub fn back_off(&mut self, host: &str, now: Instant) {
+        self.blocked_until.insert(host.to_string(), now + self.backoff);
+    }
+}
+
+/// HTTP client wrapper for publisher pages, shared by every path that fetches them.
+pub struct PoliteFetcher {
+    client: reqwest::Client,
+    /// Doesn't follow redirects, for `redirect_target`.
+    redirect_client: reqwest::Client,
+    permits: Semaphore,
+    throttle: Mutex<HostThrottle>,
+    robots: Mutex<HashMap<String, (Instant, Arc<Robots>)>>,
+}
+
+impl PoliteFetcher {
+    pub fn new(client: reqwest::Client) -> Self {
+        let redirect_client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(REDIRECT_TIMEOUT)
+            .user_agent(format!("{ROBOTS_AGENT}/1.0"))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            redirect_client,
+            permits: Semaphore::new(MAX_CONCURRENT),
+            throttle: Mutex::new(HostThrottle::new(MIN_INTERVAL, BACKOFF)),
+            robots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// GET `url` if robots.txt allows it and the host isn't backed off, waiting for the
+    /// host's next slot and a global permit first. A 429 or 403 backs the host off.
+    pub async fn get(&self, url: &str) -> Result<reqwest::Response, PoliteError> {
+        let host = self.allowed_host(url).await?;
+        self.send(&host, self.client.get(url)).await
+    }
+
+    /// Status of `url` without downloading the page: a HEAD request, falling back to a
+    /// one-byte ranged GET for servers that reject HEAD. Same politeness rules as `get`.
+    pub async fn status(&self, url: &str) -> Result<reqwest::StatusCode, PoliteError> {
+        let host = self.allowed_host(url).await?;
+        let status = self.send(&host, self.client.head(url)).await?.status();
+        if status != reqwest::StatusCode::METHOD_NOT_ALLOWED && status != reqwest::StatusCode::NOT_IMPLEMENTED {
+            return Ok(status);
+        }
+        let ranged = self.client.get(url).header(reqwest::header::RANGE, "bytes=0-0");
+        Ok(self.send(&host, ranged).await?.status())
+    }
+
+    /// Where `url` redirects to: the `Location` of a HEAD response, resolved against
+    /// `url`. None for any response that isn't a redirect. Same politeness rules as `get`.
+    pub async fn redirect_target(&self, url: &str) -> Result<Option<String>, PoliteError> {
+        let host = self.allowed_host(url).await?;
+        let response = self.send(&host, self.redirect_client.head(url)).await?;
+        if !response.status().is_redirection() {
+            return Ok(None);
+        }
+        Ok(response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| response.url().join(location).ok())
+            .map(|target| target.to_string()))
+    }
+
+    /// The host of `url` if it is http(s) and robots.txt allows the path.
+    async fn allowed_host(&self, url: &str) -> Result<String, PoliteError> {
+        let parsed = url::Url::parse(url).map_err(|_| PoliteError::InvalidUrl(url.to_string()))?;
+        let host = match parsed.host_str() {
+            Some(h) if matches!(parsed.scheme(), "http" | "https") => h.to_string(),
+            _ => return Err(PoliteError::InvalidUrl(url.to_string())),
+        };
+        let path = match parsed.query() {
+            Some(q) => format!("{}?{}", parsed.path(), q),
+            None => parsed.path().to_string(),
+        };
+        if !self.robots_for(&parsed, &host).await.allowed(&path) {
+            return Err(PoliteError::Disallowed(url.to_string()));
+        }
+        Ok(host)
+    }
+
+    async fn send(&self, host: &str, request: reqwest::RequestBuilder) -> Result<reqwest::Response, PoliteError> {
+        let wait = self
+            .throttle
+            .lock()
+            .ok()
+            .and_then(|mut t| t.reserve(host, Instant::now()))
+            .ok_or_else(|| PoliteError::BackedOff(host.to_string()))?;
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        let _permit = self.permits.acquire().await.ok();
+        let response = request.send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status == reqwest::StatusCode::FORBIDDEN {
+            warn!(host = %host, status = status.as_u16(), "Backing off host");
+            if let Ok(mut t) = self.throttle.lock() {
+                t.back_off(host, Instant::now());
+            }
+        }
+        Ok(response)
+    }
+
+    /// Cached robots.txt rules for the URL's origin. A missing or unreadable robots.txt
+    /// allows everything (and is cached like any other).
+    async fn robots_for(&self, url: &url::Url, host: &str) -> Arc<Robots> {
+        let cached = self.robots.lock().ok().and_then(|cache| {
+            cache
+                .get(host)
+                .filter(|(at, _)| at.elapsed() < ROBOTS_TTL)
+                .map(|(_, r)| Arc::clone(r))
+        });
+        if let Some(robots) = cached {
+            return robots;
+        }
+
+        let robots_url = format!("{}://{}/robots.txt", url.scheme(), url.host_str().unwrap_or(host));
+        let robots = match self.send(host, self.client.get(&robots_url)).await {
+            Ok(r) if r.status().is_success() => match r.bytes().await {
+                Ok(body) => {
+                    let txt = String::from_utf8_lossy(&body[..body.len().min(ROBOTS_MAX_BYTES)]);
+                    Robots::parse(&txt, ROBOTS_AGENT)
+                }
+                Err(_) => Robots::default(),
+            },
+            Ok(_) => Robots::default(),
+            Err(e) => {
+                info!(host = %host, error = %e, "robots.txt unavailable");
+                Robots::default()
+            }
+        };
+        let robots = Arc::new(robots);
+        if let Ok(mut cache) = self.robots.lock() {
+            cache.insert(host.to_string(), (Instant::now(), Arc::clone(&robots)));
+        }
+        robots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "
+User-agent: *
+Disallow: /private/
+Allow: /private/press/
+
+User-agent: BadBot
+Disallow: /
+
+# Our own group
+User-agent: newsaggregator
+User-agent: OtherBot
+Disallow: /search
+Disallow: /*.pdf$
+Allow: /search/about
+";
+
+    #[test]
+    fn robots_uses_the_group_for_our_agent() {
+        let robots = Robots::parse(ROBOTS, ROBOTS_AGENT);
+        assert!(!robots.allowed("/search?q=rust"));
+        assert!(robots.allowed("/search/about"));
+        assert!(!robots.allowed("/files/report.pdf"));
+        assert!(robots.allowed("/files/report.pdf?download=1"));
+        // The * group doesn't apply once a specific group exists
+        assert!(robots.allowed("/private/page"));
+    }
+
+    #[test]
+    fn robots_falls_back_to_wildcard_group() {
+        let robots = Robots::parse(ROBOTS, "SomeCrawler");
+        assert!(!robots.allowed("/private/page"));
+        assert!(robots.allowed("/private/press/release"));
+        assert!(robots.allowed("/news/1"));
+    }
+
+    #[test]
+    fn robots_empty_or_missing_rules_allow_everything() {
+        assert!(Robots::parse("", ROBOTS_AGENT).allowed("/anything"));
+        assert!(Robots::parse("User-agent: *\nDisallow:\n", ROBOTS_AGENT).allowed("/anything"));
+        assert!(!Robots::parse("User-agent: *\nDisallow: /\n", ROBOTS_AGENT).allowed("/anything"));
+    }
+
+    #[test]
+    fn throttle_spaces_requests_per_host() {
+        let mut throttle = HostThrottle::new(Duration::from_secs(2), Duration::from_secs(3600));
+        let now = Instant::now();
+        assert_eq!(throttle.reserve("a.example", now), Some(Duration::ZERO));
+        assert_eq!(throttle.reserve("a.example", now), Some(Duration::from_secs(2)));
+        assert_eq!(throttle.reserve("a.example", now), Some(Duration::from_secs(4)));
+        // Other hosts aren't affected
+        assert_eq!(throttle.reserve("b.example", now), Some(Duration::ZERO));
+        // Once the reserved slots have passed there's no wait
+        assert_eq!(throttle.reserve("a.example", now + Duration::from_secs(10)), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn throttle_backs_off_a_host_for_the_period() {
+        let mut throttle = HostThrottle::new(Duration::from_secs(2), Duration::from_secs(3600));
+        let now = Instant::now();
+        throttle.back_off("a.example", now);
+        assert_eq!(throttle.reserve("a.example", now + Duration::from_secs(60)), None);
+        assert_eq!(throttle.reserve("b.example", now), Some(Duration::ZERO));
+        assert!(throttle.reserve("a.example", now + Duration::from_secs(3601)).is_some());
+    }
+}
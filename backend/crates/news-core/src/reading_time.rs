@@ -0,0 +1,94 @@
+//! Estimated reading time shown on cards ("約3分で読めます"). Japanese is counted in
+//! characters, anything else in words; which one applies is decided from the share
+//! of kana and kanji among the letters, so English names in a Japanese headline
+//! don't flip it.
+
+use serde::Serialize;
+
+pub const JA_CHARS_PER_MINUTE: u32 = 600;
+pub const EN_WORDS_PER_MINUTE: u32 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    Japanese,
+    Latin,
+}
+
+/// Japanese when at least a quarter of the letters and digits are kana or kanji.
+pub fn detect_script(text: &str) -> Script {
+    let (mut cjk, mut total) = (0usize, 0usize);
+    for c in text.chars().filter(|c| c.is_alphanumeric()) {
+        total += 1;
+        if is_kana_or_kanji(c) {
+            cjk += 1;
+        }
+    }
+    if cjk > 0 && cjk * 4 >= total {
+        Script::Japanese
+    } else {
+        Script::Latin
+    }
+}
+
+fn is_kana_or_kanji(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // hiragana, katakana
+        | '\u{3400}'..='\u{4DBF}'   // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}'   // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}'   // halfwidth katakana
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReadingTime {
+    /// Words, or characters for Japanese text.
+    pub word_count: u32,
+    /// Rounded up, so any non-empty text takes at least a minute.
+    pub reading_minutes: u32,
+}
+
+pub fn estimate(text: &str) -> ReadingTime {
+    let (count, per_minute) = match detect_script(text) {
+        Script::Japanese => (text.chars().filter(|c| c.is_alphanumeric()).count(), JA_CHARS_PER_MINUTE),
+        Script::Latin => (
+            text.split_whitespace().filter(|w| w.chars().any(char::is_alphanumeric)).count(),
+            EN_WORDS_PER_MINUTE,
+        ),
+    };
+    let word_count = u32::try_from(count).unwrap_or(u32::MAX);
+    ReadingTime {
+        word_count,
+        reading_minutes: word_count.div_ceil(per_minute),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixed_titles_follow_their_main_script() {
+        assert_eq!(detect_script("Apple、新型iPhoneを発表"), Script::Japanese);
+        assert_eq!(detect_script("NVIDIA決算、AI需要で過去最高"), Script::Japanese);
+        assert_eq!(detect_script("Toyota unveils new EV lineup in 東京"), Script::Latin);
+        assert_eq!(detect_script("OpenAI releases GPT-5"), Script::Latin);
+        assert_eq!(detect_script("ｶﾀｶﾅ only"), Script::Japanese);
+        assert_eq!(detect_script(""), Script::Latin);
+    }
+
+    #[test]
+    fn japanese_counts_chars_and_english_counts_words() {
+        let ja = estimate(&"あ".repeat(1500));
+        assert_eq!(ja, ReadingTime { word_count: 1500, reading_minutes: 3 });
+        // Punctuation and spaces aren't counted
+        assert_eq!(estimate("速報。 東京 — 晴れ").word_count, 6);
+
+        let en = estimate(&"word ".repeat(201));
+        assert_eq!(en, ReadingTime { word_count: 201, reading_minutes: 2 });
+        assert_eq!(estimate("A short - note").word_count, 3);
+
+        assert_eq!(estimate("").reading_minutes, 0);
+        assert_eq!(estimate("一").reading_minutes, 1);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Per-domain branding used for SSR meta tags, robots.txt and sitemap URLs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SiteMeta {
+    /// Hostname this entry is served for, without port (e.g. "news.xyz").
+    pub host: String,
+    /// Short id exposed to the frontend as `data-site`.
+    pub site_id: String,
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    /// Longer description for the index page; falls back to `description`.
+    #[serde(default)]
+    pub description_long: Option<String>,
+    /// Canonical base URL with trailing slash (e.g. "https://news.xyz/").
+    pub url: String,
+    /// Default OGP image.
+    pub image: String,
+    pub theme_color: String,
+    pub lang: String,
+    #[serde(default)]
+    pub keywords: String,
+}
+
+impl SiteMeta {
+    /// Base URL without trailing slash, for building absolute links.
+    pub fn base_url(&self) -> &str {
+        self.url.trim_end_matches('/')
+    }
+
+    /// `scheme://host[:port]` of `url`, as sent in an `Origin` header.
+    pub fn origin(&self) -> Option<String> {
+        let parsed = url::Url::parse(&self.url).ok()?;
+        Some(parsed.origin().ascii_serialization())
+    }
+
+    pub fn description_long(&self) -> &str {
+        self.description_long.as_deref().unwrap_or(&self.description)
+    }
+
+    /// Problems that would make this entry render broken pages.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.host.is_empty() || normalize_host(&self.host) != self.host {
+            errors.push(format!("host must be a lowercase hostname without port: {:?}", self.host));
+        }
+        match url::Url::parse(&self.url) {
+            Ok(u) if matches!(u.scheme(), "http" | "https") => {}
+            _ => errors.push(format!("url must be an absolute http(s) URL: {:?}", self.url)),
+        }
+        if self.name.trim().is_empty() {
+            errors.push("name is required".into());
+        }
+        if self.site_id.is_empty() || !self.site_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            errors.push(format!("site_id must be [A-Za-z0-9-]+: {:?}", self.site_id));
+        }
+        errors
+    }
+}
+
+/// Sites compiled in from sites.toml.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SitesConfig {
+    /// Host whose entry is used for unknown hosts.
+    pub default_host: String,
+    pub sites: Vec<SiteMeta>,
+}
+
+impl SitesConfig {
+    /// Parse and validate sites.toml. `default_host` must name one of the entries.
+    pub fn from_toml(toml_str: &str) -> Result<Self> {
+        let config: Self =
+            toml::from_str(toml_str).map_err(|e| AppError::ConfigError(e.to_string()))?;
+
+        let mut errors = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for site in &config.sites {
+            errors.extend(site.validate().into_iter().map(|e| format!("{}: {}", site.host, e)));
+            if !seen.insert(site.host.as_str()) {
+                errors.push(format!("{}: duplicate host", site.host));
+            }
+        }
+        if !seen.contains(config.default_host.as_str()) {
+            errors.push(format!("default_host {} has no [[sites]] entry", config.default_host));
+        }
+        if !errors.is_empty() {
+            return Err(AppError::ConfigError(errors.join("; ")));
+        }
+        Ok(config)
+    }
+
+    pub fn default_site(&self) -> &SiteMeta {
+        self.sites
+            .iter()
+            .find(|s| s.host == self.default_host)
+            .unwrap_or(&self.sites[0])
+    }
+
+    /// Exact match on the normalized host, if any.
+    pub fn get(&self, host: &str) -> Option<&SiteMeta> {
+        let host = normalize_host(host);
+        self.sites.iter().find(|s| s.host == host)
+    }
+
+    /// Site for a `Host` header value, falling back to the default site.
+    pub fn lookup(&self, host: &str) -> &SiteMeta {
+        self.get(host).unwrap_or_else(|| self.default_site())
+    }
+}
+
+/// Lowercase a `Host` header value and strip the port and any trailing dot.
+pub fn normalize_host(host: &str) -> String {
+    let host = host.trim();
+    let without_port = if host.starts_with('[') {
+        // IPv6 literal: keep the brackets, drop ":port" after them
+        host.split_once(']').map(|(h, _)| format!("{h}]")).unwrap_or_else(|| host.to_string())
+    } else {
+        host.split(':').next().unwrap_or("").to_string()
+    };
+    without_port.trim_end_matches('.').to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SITES: &str = r##"
+default_host = "news.xyz"
+
+[[sites]]
+host = "news.xyz"
+site_id = "xyz"
+name = "news.xyz"
+title = "news.xyz"
+description = "AI news"
+url = "https://news.xyz/"
+image = "https://news.xyz/icons/og-xyz.png"
+theme_color = "#1a1a2e"
+lang = "en"
+
+[[sites]]
+host = "news-xyz.fly.dev"
+site_id = "xyz"
+name = "news.xyz"
+title = "news.xyz"
+description = "AI news"
+description_long = "AI news, longer"
+url = "https://news-xyz.fly.dev/"
+image = "https://news-xyz.fly.dev/icons/og-xyz.png"
+theme_color = "#1a1a2e"
+lang = "en"
+"##;
+
+    #[test]
+    fn lookup_matches_host_ignoring_port_and_case() {
+        let config = SitesConfig::from_toml(SITES).unwrap();
+        assert_eq!(config.lookup("News-XYZ.fly.dev:443").host, "news-xyz.fly.dev");
+        assert_eq!(config.lookup("news.xyz").base_url(), "https://news.xyz");
+    }
+
+    #[test]
+    fn unknown_host_falls_back_to_default() {
+        let config = SitesConfig::from_toml(SITES).unwrap();
+        assert!(config.get("example.com").is_none());
+        assert_eq!(config.lookup("example.com").host, "news.xyz");
+        assert_eq!(config.lookup("").host, "news.xyz");
+    }
+
+    #[test]
+    fn description_long_falls_back() {
+        let config = SitesConfig::from_toml(SITES).unwrap();
+        assert_eq!(config.lookup("news.xyz").description_long(), "AI news");
+        assert_eq!(config.lookup("news-xyz.fly.dev").description_long(), "AI news, longer");
+    }
+
+    #[test]
+    fn rejects_missing_default_and_duplicates() {
+        let bad = SITES.replace("default_host = \"news.xyz\"", "default_host = \"nope.example\"");
+        assert!(SitesConfig::from_toml(&bad).is_err());
+        let dup = SITES.replace("host = \"news-xyz.fly.dev\"", "host = \"news.xyz\"");
+        assert!(SitesConfig::from_toml(&dup).is_err());
+    }
+
+    #[test]
+    fn origin_and_host_normalization() {
+        let config = SitesConfig::from_toml(SITES).unwrap();
+        assert_eq!(config.default_site().origin().as_deref(), Some("https://news.xyz"));
+        assert_eq!(normalize_host("localhost:8080"), "localhost");
+        assert_eq!(normalize_host("[::1]:8080"), "[::1]");
+        assert_eq!(normalize_host("news.xyz."), "news.xyz");
+    }
+}
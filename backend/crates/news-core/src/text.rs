@@ -0,0 +1,80 @@
+//! Length limits on user and article text. Slicing a `&str` at a fixed byte index
+//! panics when the index falls inside a multi-byte character, which for Japanese
+//! text is most of the time.
+
+/// Bytes back from the cut searched for a sentence end by `truncate_sentence`.
+pub const SENTENCE_WINDOW: usize = 300;
+
+/// The longest prefix of `s` that is at most `max_bytes` long and ends on a char
+/// boundary.
+pub fn truncate_bytes_floor(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// The first `max_chars` chars of `s`.
+pub fn truncate_chars(s: &str, max_chars: usize) -> &str {
+    match s.char_indices().nth(max_chars) {
+        Some((end, _)) => &s[..end],
+        None => s,
+    }
+}
+
+/// Like `truncate_bytes_floor`, but ends after the last 。！？!? or newline when one
+/// falls within `SENTENCE_WINDOW` bytes of the cut, so the text doesn't stop mid-sentence.
+pub fn truncate_sentence(s: &str, max_bytes: usize) -> &str {
+    let cut = truncate_bytes_floor(s, max_bytes);
+    if cut.len() == s.len() {
+        return s;
+    }
+    let window_start = cut.len().saturating_sub(SENTENCE_WINDOW);
+    cut.char_indices()
+        .rev()
+        .take_while(|(i, _)| *i >= window_start)
+        .find(|(_, c)| matches!(c, '。' | '！' | '？' | '!' | '?' | '\n'))
+        .map_or(cut, |(i, c)| &cut[..i + c.len_utf8()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_cuts_inside_a_char_move_back_to_its_start() {
+        // Each kana is 3 bytes
+        let s = "あいうえお";
+        assert_eq!(truncate_bytes_floor(s, 7), "あい");
+        assert_eq!(truncate_bytes_floor(s, 6), "あい");
+        assert_eq!(truncate_bytes_floor(s, 2), "");
+        assert_eq!(truncate_bytes_floor(s, 100), s);
+        assert_eq!(truncate_bytes_floor("abc", 2), "ab");
+
+        let long = "日本語".repeat(1000);
+        assert!(truncate_bytes_floor(&long, 5000).len() <= 5000);
+    }
+
+    #[test]
+    fn char_cuts_count_chars_not_bytes() {
+        assert_eq!(truncate_chars("あいうえお", 2), "あい");
+        assert_eq!(truncate_chars("あいうえお", 10), "あいうえお");
+        assert_eq!(truncate_chars("", 3), "");
+    }
+
+    #[test]
+    fn sentence_cuts_prefer_a_nearby_sentence_end() {
+        let s = format!("{}。{}", "あ".repeat(10), "い".repeat(10));
+        // The cut at 40 bytes lands mid "い", after the 。
+        assert_eq!(truncate_sentence(&s, 40), format!("{}。", "あ".repeat(10)));
+        assert_eq!(truncate_sentence(&s, 1000), s);
+
+        // No sentence end within the window: plain char-boundary cut
+        let s = format!("{}。{}", "あ".repeat(10), "い".repeat(200));
+        assert_eq!(truncate_sentence(&s, 500), truncate_bytes_floor(&s, 500));
+    }
+}
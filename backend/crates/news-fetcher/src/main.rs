@@ -6,38 +6,47 @@ use news_core::ogp;
 use serde_json::Value;
 use tracing::info;
 
-/// Embedded feeds configuration (compiled into binary) — used as fallback.
+/// Embedded feeds configuration (compiled into binary) — the base that ConfigTable feeds are merged onto.
 const FEEDS_TOML: &str = include_str!("../../../feeds.toml");
 
-/// Load feeds from DynamoDB ConfigTable, falling back to feeds.toml.
+/// Load feeds.toml merged with the feeds from DynamoDB ConfigTable; disabled ones
+/// switch off their feeds.toml counterpart.
 async fn load_feeds(config_store: &ConfigStore) -> Vec<FeedConfig> {
-    match config_store.get_enabled_feeds().await {
-        Ok(feeds) if !feeds.is_empty() => {
-            info!(count = feeds.len(), "Loaded feeds from ConfigTable");
-            feeds
-                .into_iter()
-                .map(|f| FeedConfig {
-                    url: f.url,
-                    source: f.source,
-                    category: f.category,
-                })
-                .collect()
-        }
-        Ok(_) => {
-            info!("ConfigTable empty, falling back to feeds.toml");
-            fallback_feeds()
+    let base = static_feeds();
+    match config_store.get_all_feeds().await {
+        Ok(dynamic) => {
+            let merged = FeedsConfig::merge(&base, &dynamic);
+            info!(
+                static_feeds = base.feeds.len(),
+                dynamic_feeds = dynamic.len(),
+                total = merged.feeds.len(),
+                "Merged feeds.toml with ConfigTable feeds"
+            );
+            merged.feeds
         }
         Err(e) => {
-            tracing::warn!(error = %e, "Failed to read ConfigTable, falling back to feeds.toml");
-            fallback_feeds()
+            tracing::warn!(error = %e, "Failed to read ConfigTable feeds, using feeds.toml only");
+            base.feeds
         }
     }
 }
 
-fn fallback_feeds() -> Vec<FeedConfig> {
-    FeedsConfig::from_toml(FEEDS_TOML)
-        .map(|c| c.feeds)
-        .unwrap_or_default()
+fn static_feeds() -> FeedsConfig {
+    match FeedsConfig::from_toml(FEEDS_TOML) {
+        Ok(config) => {
+            for w in &config.warnings {
+                tracing::warn!(index = w.index, url = %w.url, "feeds.toml: {}", w.error);
+            }
+            config
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "feeds.toml failed validation, no static feeds");
+            FeedsConfig {
+                feeds: Vec::new(),
+                warnings: Vec::new(),
+            }
+        }
+    }
 }
 
 /// Load feature flags, returning defaults on failure.
@@ -69,7 +78,7 @@ async fn handler(_event: LambdaEvent<Value>) -> Result<Value, Error> {
     let feeds = load_feeds(&config_store).await;
     let features = load_feature_flags(&config_store).await;
 
-    let feeds_config = FeedsConfig { feeds };
+    let feeds_config = FeedsConfig { feeds, warnings: Vec::new() };
     let articles = fetch_all_feeds(&http_client, &feeds_config).await;
     info!(total_articles = articles.len(), "Fetched all feeds");
 
@@ -84,8 +93,9 @@ async fn handler(_event: LambdaEvent<Value>) -> Result<Value, Error> {
             .take(20)
             .collect();
 
+        let polite = news_core::polite::PoliteFetcher::new(http_client.clone());
         for article in &no_image {
-            if let Some(img_url) = ogp::fetch_og_image(&http_client, &article.url).await {
+            if let Some(img_url) = ogp::fetch_og_image(&polite, &article.url, None).await {
                 let sk = format!("{}#{}", article.published_at.to_rfc3339(), article.id);
                 if store
                     .update_image_url(article.category.as_str(), &sk, &img_url)
@@ -0,0 +1,66 @@
+//! A/B assignments this process has already written to `ab_test_assignment`, so
+//! looking up a caller's variant on every AI request only writes when the device's
+//! variant is new or has changed.
+
+use crate::db::Db;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub const AB_RECORDED_CAPACITY: usize = 10_000;
+
+pub struct RecordedAssignments {
+    /// (device_id, test) → variant
+    inner: Mutex<HashMap<(String, String), String>>,
+    capacity: usize,
+}
+
+impl RecordedAssignments {
+    pub fn new(capacity: usize) -> Self {
+        Self { inner: Mutex::new(HashMap::new()), capacity }
+    }
+
+    /// Store that `device_id` sees `variant` of `test`, unless it was already stored.
+    /// A full map is cleared rather than grown; the rows it forgets are rewritten as
+    /// no-ops.
+    pub fn record(&self, db: &Db, device_id: &str, test: &str, variant: &str) -> Result<(), String> {
+        let key = (device_id.to_string(), test.to_string());
+        if self.inner.lock().unwrap_or_else(|e| e.into_inner()).get(&key).is_some_and(|v| v == variant) {
+            return Ok(());
+        }
+        db.record_ab_assignment(device_id, test, variant)?;
+        let mut recorded = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if recorded.len() >= self.capacity {
+            recorded.clear();
+        }
+        recorded.insert(key, variant.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeat_assignments_skip_the_database() {
+        let recorded = RecordedAssignments::new(AB_RECORDED_CAPACITY);
+        let first = Db::open(":memory:").unwrap();
+        recorded.record(&first, "device-1", "summarize", "a").unwrap();
+        assert_eq!(first.ab_assignment_counts().unwrap().len(), 1);
+
+        let second = Db::open(":memory:").unwrap();
+        recorded.record(&second, "device-1", "summarize", "a").unwrap();
+        assert!(second.ab_assignment_counts().unwrap().is_empty(), "already recorded");
+        recorded.record(&second, "device-1", "summarize", "b").unwrap();
+        assert_eq!(second.ab_assignment_counts().unwrap(), [("summarize".into(), "b".into(), 1)]);
+    }
+
+    #[test]
+    fn a_full_map_starts_over() {
+        let recorded = RecordedAssignments::new(1);
+        let db = Db::open(":memory:").unwrap();
+        recorded.record(&db, "device-1", "summarize", "a").unwrap();
+        recorded.record(&db, "device-2", "summarize", "a").unwrap();
+        assert_eq!(recorded.inner.lock().unwrap().len(), 1);
+    }
+}
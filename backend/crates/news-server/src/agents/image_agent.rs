@@ -1,9 +1,10 @@
 use crate::routes::AppState;
+use news_core::enrichment::{EnrichmentPayload, ImageEnrichment, ENRICHMENT_VERSION};
 use news_core::models::Article;
+use news_core::text::truncate_bytes_floor;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, warn};
-use uuid::Uuid;
 
 use super::retry_with_backoff;
 
@@ -26,13 +27,6 @@ struct DalleImageData {
     url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ImageEnrichmentData {
-    pub image_url: String,
-    pub prompt: String,
-    pub provider: String,
-}
-
 #[derive(Debug, Serialize)]
 struct ReplicateRequest {
     version: String,
@@ -71,7 +65,7 @@ const FLUX_SCHNELL_VERSION: &str = "f2e15a9d0e1c4e8c91f3d63f5f3b3e3f2e15a9d0e1c4
 pub async fn generate_image(
     state: &Arc<AppState>,
     article: &Article,
-) -> Result<ImageEnrichmentData, String> {
+) -> Result<ImageEnrichment, String> {
     // Create a prompt from the article title and description
     let prompt = create_image_prompt(article);
 
@@ -91,7 +85,8 @@ pub async fn generate_image(
         .await
         {
             Ok(image_url) => {
-                return Ok(ImageEnrichmentData {
+                return Ok(ImageEnrichment {
+                    version: ENRICHMENT_VERSION,
                     image_url,
                     prompt,
                     provider: "dalle-3".to_string(),
@@ -128,7 +123,8 @@ pub async fn generate_image(
     )
     .await?;
 
-    Ok(ImageEnrichmentData {
+    Ok(ImageEnrichment {
+        version: ENRICHMENT_VERSION,
         image_url: result,
         prompt,
         provider: "flux-schnell".to_string(),
@@ -140,13 +136,7 @@ fn create_image_prompt(article: &Article) -> String {
     let base_description = article
         .description
         .as_ref()
-        .map(|d| {
-            if d.len() > 200 {
-                &d[..200]
-            } else {
-                d.as_str()
-            }
-        })
+        .map(|d| truncate_bytes_floor(d, 200))
         .unwrap_or("");
 
     // Create a concise, descriptive prompt for DALL-E
@@ -284,31 +274,17 @@ async fn generate_flux_image(
     Err("Replicate prediction timed out after 60 seconds".to_string())
 }
 
-/// Process image enrichment for an article.
-pub async fn run(state: &Arc<AppState>, article: &Article) -> Result<(), String> {
-    let enrichment_id = Uuid::new_v4().to_string();
-
-    // Create pending enrichment record
-    state
-        .db
-        .create_enrichment(
-            &enrichment_id,
-            &article.id,
-            "image",
-            "ai_image",
-            "{}",
-        )
-        .map_err(|e| format!("Failed to create enrichment: {}", e))?;
-
+/// Process image enrichment for an article into an existing pending enrichment row.
+pub async fn run(state: &Arc<AppState>, article: &Article, enrichment_id: &str) -> Result<(), String> {
     // Generate image
     match generate_image(state, article).await {
         Ok(data) => {
-            let data_json = serde_json::to_string(&data)
+            let data_json = serde_json::to_string(&EnrichmentPayload::Image(data))
                 .map_err(|e| format!("Failed to serialize enrichment: {}", e))?;
 
             state
                 .db
-                .update_enrichment(&enrichment_id, "completed", Some(&data_json), None)
+                .update_enrichment(enrichment_id, "completed", Some(&data_json), None)
                 .map_err(|e| format!("Failed to update enrichment: {}", e))?;
 
             info!(
@@ -329,7 +305,7 @@ pub async fn run(state: &Arc<AppState>, article: &Article) -> Result<(), String>
 
             state
                 .db
-                .update_enrichment(&enrichment_id, "failed", None, Some(&e))
+                .update_enrichment(enrichment_id, "failed", None, Some(&e))
                 .ok();
 
             Err(e)
@@ -1,30 +1,14 @@
 use crate::routes::AppState;
+use news_core::enrichment::{
+    EnrichmentPayload, RelatedLink, ResearchEnrichment, TimelineEntry, ENRICHMENT_VERSION,
+};
 use news_core::models::Article;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, warn};
-use uuid::Uuid;
 
 use super::retry_with_backoff;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ResearchEnrichmentData {
-    pub summary: String,
-    pub background: String,
-    pub key_points: Vec<String>,
-    pub related_articles: Vec<RelatedArticle>,
-    pub visualization: Option<serde_json::Value>,
-    pub provider: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RelatedArticle {
-    pub title: String,
-    pub url: String,
-    pub snippet: String,
-    pub source: String,
-}
-
 #[derive(Debug, Serialize)]
 struct ClaudeRequest {
     model: String,
@@ -76,7 +60,7 @@ struct BraveResult {
 pub async fn research_article(
     state: &Arc<AppState>,
     article: &Article,
-) -> Result<ResearchEnrichmentData, String> {
+) -> Result<ResearchEnrichment, String> {
     if state.api_key.is_empty() {
         return Err("Claude API key not configured".to_string());
     }
@@ -90,7 +74,7 @@ pub async fn research_article(
     // Check cache first
     let cache_key = format!("research:{}", article.id);
     if let Ok(Some(cached)) = state.db.get_cache(&cache_key) {
-        if let Ok(data) = serde_json::from_str::<ResearchEnrichmentData>(&cached) {
+        if let Ok(data) = serde_json::from_str::<ResearchEnrichment>(&cached) {
             info!(
                 article_id = %article.id,
                 "Using cached research results"
@@ -114,7 +98,7 @@ pub async fn research_article(
 
     // Add web search results if available
     if let Ok(articles) = web_search_result {
-        result.related_articles = articles;
+        result.related_links = articles;
     }
 
     // Add visualization if available
@@ -135,7 +119,7 @@ async fn call_claude_research(
     client: &reqwest::Client,
     api_key: &str,
     article: &Article,
-) -> Result<ResearchEnrichmentData, String> {
+) -> Result<ResearchEnrichment, String> {
     let description = article
         .description
         .as_ref()
@@ -152,12 +136,13 @@ async fn call_claude_research(
 
 ## 出力形式（必ずこのJSON形式で出力してください。マークダウンやコードブロック不要）
 
-{{"summary":"記事の要点を1-2文で簡潔に","background":"この記事の歴史的背景や関連する文脈を2-3文で説明","key_points":["重要なポイント1","重要なポイント2","重要なポイント3"]}}
+{{"summary":"記事の要点を1-2文で簡潔に","background":"この記事の歴史的背景や関連する文脈を2-3文で説明","key_points":["重要なポイント1","重要なポイント2","重要なポイント3"],"timeline":[{{"date":"2024-03","event":"この件に至るまでの出来事"}}]}}
 
 ルール:
 - summaryは150文字以内
 - backgroundは200-300文字
 - key_pointsは3-5個、各50文字以内
+- timelineは記事に至る主な出来事を古い順に0-5個（dateはYYYY-MMまたはYYYY-MM-DD、eventは50文字以内）。不確かな日付は含めない
 - 客観的で中立的な分析
 - 専門用語は避け、一般読者向けに"#,
         article.title, description, article.source
@@ -206,7 +191,8 @@ async fn call_claude_research(
     let parsed: serde_json::Value = serde_json::from_str(text.trim())
         .map_err(|e| format!("Failed to parse research JSON: {} - Response: {}", e, text))?;
 
-    Ok(ResearchEnrichmentData {
+    Ok(ResearchEnrichment {
+        version: ENRICHMENT_VERSION,
         summary: parsed["summary"]
             .as_str()
             .unwrap_or("")
@@ -223,7 +209,20 @@ async fn call_claude_research(
                     .collect()
             })
             .unwrap_or_default(),
-        related_articles: Vec::new(), // Will be filled by the caller
+        related_links: Vec::new(), // Will be filled by the caller
+        timeline: parsed["timeline"]
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| {
+                        Some(TimelineEntry {
+                            date: v["date"].as_str()?.to_string(),
+                            event: v["event"].as_str()?.to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
         visualization: None, // Will be filled by the caller
         provider: "claude-haiku".to_string(),
     })
@@ -327,7 +326,7 @@ null"#,
 async fn search_related_articles(
     client: &reqwest::Client,
     article: &Article,
-) -> Result<Vec<RelatedArticle>, String> {
+) -> Result<Vec<RelatedLink>, String> {
     let brave_api_key = std::env::var("BRAVE_SEARCH_API_KEY")
         .unwrap_or_default();
 
@@ -388,11 +387,11 @@ async fn search_related_articles(
                         .unwrap_or("unknown")
                         .to_string();
 
-                    RelatedArticle {
+                    RelatedLink {
                         title: result.title,
                         url: result.url,
-                        snippet: result.description,
                         source,
+                        snippet: result.description,
                     }
                 })
                 .collect()
@@ -402,31 +401,17 @@ async fn search_related_articles(
     Ok(related)
 }
 
-/// Process research enrichment for an article.
-pub async fn run(state: &Arc<AppState>, article: &Article) -> Result<(), String> {
-    let enrichment_id = Uuid::new_v4().to_string();
-
-    // Create pending enrichment record
-    state
-        .db
-        .create_enrichment(
-            &enrichment_id,
-            &article.id,
-            "research",
-            "background_info",
-            "{}",
-        )
-        .map_err(|e| format!("Failed to create enrichment: {}", e))?;
-
+/// Process research enrichment for an article into an existing pending enrichment row.
+pub async fn run(state: &Arc<AppState>, article: &Article, enrichment_id: &str) -> Result<(), String> {
     // Perform research
     match research_article(state, article).await {
         Ok(data) => {
-            let data_json = serde_json::to_string(&data)
+            let data_json = serde_json::to_string(&EnrichmentPayload::Research(data))
                 .map_err(|e| format!("Failed to serialize enrichment: {}", e))?;
 
             state
                 .db
-                .update_enrichment(&enrichment_id, "completed", Some(&data_json), None)
+                .update_enrichment(enrichment_id, "completed", Some(&data_json), None)
                 .map_err(|e| format!("Failed to update enrichment: {}", e))?;
 
             info!(
@@ -447,7 +432,7 @@ pub async fn run(state: &Arc<AppState>, article: &Article) -> Result<(), String>
 
             state
                 .db
-                .update_enrichment(&enrichment_id, "failed", None, Some(&e))
+                .update_enrichment(enrichment_id, "failed", None, Some(&e))
                 .ok();
 
             Err(e)
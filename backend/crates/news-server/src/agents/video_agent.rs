@@ -1,28 +1,12 @@
 use crate::routes::AppState;
+use news_core::enrichment::{EnrichmentPayload, Video, VideoEnrichment, ENRICHMENT_VERSION};
 use news_core::models::Article;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::sync::Arc;
 use tracing::{info, warn};
-use uuid::Uuid;
 
 use super::retry_with_backoff;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct VideoSearchResult {
-    pub video_id: String,
-    pub title: String,
-    pub description: String,
-    pub thumbnail_url: String,
-    pub channel_title: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct VideoEnrichmentData {
-    pub videos: Vec<VideoSearchResult>,
-    pub search_query: String,
-    pub provider: String,
-}
-
 #[derive(Debug, Deserialize)]
 struct YouTubeSearchResponse {
     items: Vec<YouTubeSearchItem>,
@@ -72,7 +56,7 @@ struct YouTubeThumbnail {
 pub async fn search_videos(
     state: &Arc<AppState>,
     article: &Article,
-) -> Result<VideoEnrichmentData, String> {
+) -> Result<VideoEnrichment, String> {
     let youtube_api_key = std::env::var("YOUTUBE_API_KEY")
         .map_err(|_| "YOUTUBE_API_KEY environment variable not set".to_string())?;
 
@@ -92,7 +76,7 @@ pub async fn search_videos(
     // Check cache first
     let cache_key = format!("youtube_search:{}", search_query);
     if let Ok(Some(cached)) = state.db.get_cache(&cache_key) {
-        if let Ok(data) = serde_json::from_str::<VideoEnrichmentData>(&cached) {
+        if let Ok(data) = serde_json::from_str::<VideoEnrichment>(&cached) {
             info!(
                 article_id = %article.id,
                 "Using cached YouTube search results"
@@ -109,7 +93,8 @@ pub async fn search_videos(
     )
     .await?;
 
-    let enrichment_data = VideoEnrichmentData {
+    let enrichment_data = VideoEnrichment {
+        version: ENRICHMENT_VERSION,
         videos,
         search_query: search_query.clone(),
         provider: "youtube".to_string(),
@@ -134,7 +119,7 @@ async fn search_youtube(
     client: &reqwest::Client,
     api_key: &str,
     query: &str,
-) -> Result<Vec<VideoSearchResult>, String> {
+) -> Result<Vec<Video>, String> {
     let url = "https://www.googleapis.com/youtube/v3/search";
 
     let response = client
@@ -169,7 +154,7 @@ async fn search_youtube(
     let videos = youtube_response
         .items
         .into_iter()
-        .map(|item| VideoSearchResult {
+        .map(|item| Video {
             video_id: item.id.video_id,
             title: item.snippet.title,
             description: item.snippet.description,
@@ -188,37 +173,24 @@ async fn search_youtube(
     Ok(videos)
 }
 
-/// Process video enrichment for an article.
-pub async fn run(state: &Arc<AppState>, article: &Article) -> Result<(), String> {
-    let enrichment_id = Uuid::new_v4().to_string();
-
-    // Create pending enrichment record
-    state
-        .db
-        .create_enrichment(
-            &enrichment_id,
-            &article.id,
-            "video",
-            "youtube_videos",
-            "{}",
-        )
-        .map_err(|e| format!("Failed to create enrichment: {}", e))?;
-
+/// Process video enrichment for an article into an existing pending enrichment row.
+pub async fn run(state: &Arc<AppState>, article: &Article, enrichment_id: &str) -> Result<(), String> {
     // Search videos
     match search_videos(state, article).await {
         Ok(data) => {
-            let data_json = serde_json::to_string(&data)
+            let video_count = data.videos.len();
+            let data_json = serde_json::to_string(&EnrichmentPayload::Video(data))
                 .map_err(|e| format!("Failed to serialize enrichment: {}", e))?;
 
             state
                 .db
-                .update_enrichment(&enrichment_id, "completed", Some(&data_json), None)
+                .update_enrichment(enrichment_id, "completed", Some(&data_json), None)
                 .map_err(|e| format!("Failed to update enrichment: {}", e))?;
 
             info!(
                 article_id = %article.id,
                 enrichment_id = %enrichment_id,
-                video_count = data.videos.len(),
+                video_count,
                 "Video enrichment completed"
             );
 
@@ -234,7 +206,7 @@ pub async fn run(state: &Arc<AppState>, article: &Article) -> Result<(), String>
 
             state
                 .db
-                .update_enrichment(&enrichment_id, "failed", None, Some(&e))
+                .update_enrichment(enrichment_id, "failed", None, Some(&e))
                 .ok();
 
             Err(e)
@@ -6,7 +6,11 @@
  */
 
 use crate::chatweb::ChatWebClient;
+use crate::claude;
 use crate::routes::AppState;
+use crate::supervisor::Heartbeat;
+use futures::StreamExt;
+use news_core::models::Article;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{error, info, warn};
@@ -14,9 +18,11 @@ use tracing::{error, info, warn};
 const ANALYSIS_INTERVAL: Duration = Duration::from_secs(10 * 60); // 10 minutes
 const MAX_CONCURRENT_ANALYSES: usize = 10; // Analyze up to 10 articles at once
 const BATCH_SIZE: i64 = 50; // Analyze 50 articles per cycle
+const TRANSLATE_BATCH_SIZE: i64 = 20; // Pre-translate 20 auto_translate articles per cycle
 
 /// Run the AI analyzer background task
-pub async fn run(state: Arc<AppState>) {
+#[tracing::instrument(name = "analyzer", skip_all)]
+pub async fn run(state: Arc<AppState>, heartbeat: Heartbeat) {
     info!("AI Analyzer: Starting background task (interval: 10 minutes)");
 
     let chatweb_client = ChatWebClient::new();
@@ -25,6 +31,8 @@ pub async fn run(state: Arc<AppState>) {
         // Wait for the next interval
         tokio::time::sleep(ANALYSIS_INTERVAL).await;
 
+        translate_pending(&state).await;
+
         // Get analysis statistics
         match state.db.get_analysis_stats() {
             Ok((total, analyzed)) => {
@@ -36,6 +44,7 @@ pub async fn run(state: Arc<AppState>) {
 
                 if remaining == 0 {
                     info!("AI Analyzer: No articles to analyze, skipping cycle");
+                    heartbeat.beat();
                     continue;
                 }
             }
@@ -56,6 +65,7 @@ pub async fn run(state: Arc<AppState>) {
 
         if articles.is_empty() {
             info!("AI Analyzer: No articles found for analysis");
+            heartbeat.beat();
             continue;
         }
 
@@ -93,6 +103,7 @@ pub async fn run(state: Arc<AppState>) {
         // Update database with results
         let mut success_count = 0;
         let mut error_count = 0;
+        let mut analyzed = Vec::new();
 
         for (article, result) in articles.iter().zip(results.iter()) {
             match result {
@@ -107,6 +118,10 @@ pub async fn run(state: Arc<AppState>) {
                     ) {
                         Ok(_) => {
                             success_count += 1;
+                            analyzed.push(article);
+                            if let Err(e) = state.db.set_article_tags(&article.id, &analysis.keywords) {
+                                warn!("AI Analyzer: Failed to save tags for '{}': {}", article.id, e);
+                            }
                             info!(
                                 "AI Analyzer: Analyzed article '{}' - sentiment: {}, importance: {:.2}",
                                 article.title.chars().take(50).collect::<String>(),
@@ -139,5 +154,106 @@ pub async fn run(state: Arc<AppState>) {
             error_count,
             (success_count as f64 / (success_count + error_count) as f64) * 100.0
         );
+
+        extract_entities(&state, &analyzed).await;
+        heartbeat.beat();
+    }
+}
+
+/// Store the named entities of freshly analyzed articles, for `GET /api/articles/:id/cited-by`.
+async fn extract_entities(state: &AppState, articles: &[&Article]) {
+    if state.api_key.is_empty() || articles.is_empty() {
+        return;
+    }
+    // Collected first: a lazily mapped iterator inside the stream makes the task future non-`Send`
+    let requests: Vec<_> = articles.iter().map(|article| article_entities(state, article)).collect();
+    let results: Vec<_> = futures::stream::iter(requests)
+        .buffer_unordered(MAX_CONCURRENT_ANALYSES)
+        .collect()
+        .await;
+
+    let mut stored = 0;
+    for (article, result) in results {
+        match result {
+            Ok(generated) => {
+                let entities: Vec<(String, String)> =
+                    generated.value.into_iter().map(|e| (e.text, e.kind)).collect();
+                match state.db.set_article_entities(&article.id, &entities) {
+                    Ok(()) => stored += 1,
+                    Err(e) => warn!("AI Analyzer: Failed to save entities for '{}': {}", article.id, e),
+                }
+            }
+            Err(e) => warn!("AI Analyzer: Entity extraction failed for '{}': {}", article.title, e),
+        }
+    }
+    info!("AI Analyzer: Extracted entities for {}/{} articles", stored, articles.len());
+}
+
+async fn article_entities<'a>(
+    state: &AppState,
+    article: &'a Article,
+) -> (&'a Article, Result<claude::Generated<Vec<claude::NamedEntity>>, String>) {
+    let result = state
+        .metrics
+        .claude(claude::extract_entities(
+            &state.claude,
+            claude::ModelTier::Fast,
+            &article.title,
+            article.description.as_deref().unwrap_or(""),
+        ))
+        .await;
+    (article, result)
+}
+
+/// Pre-translate new articles from auto_translate feeds into the default language and
+/// store them in the translations table (the articles themselves stay untouched).
+async fn translate_pending(state: &AppState) {
+    if state.api_key.is_empty() {
+        return;
+    }
+    let lang = crate::routes::DEFAULT_TRANSLATE_LANG;
+    let articles = match state.db.articles_needing_translation(lang, TRANSLATE_BATCH_SIZE) {
+        Ok(articles) => articles,
+        Err(e) => {
+            error!("AI Analyzer: Failed to fetch articles for translation: {}", e);
+            return;
+        }
+    };
+    if articles.is_empty() {
+        return;
+    }
+
+    let mut translated = 0;
+    for article in &articles {
+        let result = state
+            .metrics
+            .claude(claude::translate_article(
+                &state.claude,
+                claude::ModelTier::Fast,
+                &article.title,
+                article.description.as_deref().unwrap_or(""),
+                None,
+                lang,
+            ))
+            .await;
+        match result {
+            Ok(generated) => {
+                let t = generated.value;
+                let saved = state.db.store_translation(
+                    &article.id,
+                    lang,
+                    &t.title,
+                    Some(t.description.as_str()),
+                    Some(t.detected_source_lang.as_str()),
+                    Some(generated.model_used),
+                );
+                match saved {
+                    Ok(()) => translated += 1,
+                    Err(e) => warn!("AI Analyzer: Failed to save translation for '{}': {}", article.id, e),
+                }
+            }
+            Err(e) => warn!("AI Analyzer: Translation failed for '{}': {}", article.title, e),
+        }
     }
+    info!("AI Analyzer: Pre-translated {}/{} articles", translated, articles.len());
 }
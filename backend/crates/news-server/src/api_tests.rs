@@ -0,0 +1,1514 @@
+//! Handler tests through the full `api_routes` router: in-memory SQLite, and Claude
+//! answered by a local mock so no request leaves the machine.
+
+use crate::api_routes;
+use crate::routes::AppState;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{Request, StatusCode};
+use axum::routing::post;
+use axum::{Json, Router};
+use chrono::Utc;
+use news_core::models::{Article, Category};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Counts Messages requests and answers each with the same text.
+async fn messages(
+    State((calls, reply)): State<(Arc<AtomicUsize>, &'static str)>,
+    Json(_): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    calls.fetch_add(1, Ordering::SeqCst);
+    Json(serde_json::json!({"content": [{"type": "text", "text": reply}]}))
+}
+
+/// Test state wired to a fresh mock Claude, and that mock's request counter.
+async fn test_state() -> (Arc<AppState>, Arc<AtomicUsize>) {
+    test_state_replying("今日の主なニュースです。").await
+}
+
+/// `test_state` with a mock Claude that answers every request with `reply`.
+async fn test_state_replying(reply: &'static str) -> (Arc<AppState>, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mock = Router::new().route("/v1/messages", post(messages)).with_state((Arc::clone(&calls), reply));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, mock).await.unwrap() });
+    (Arc::new(AppState::for_tests(&format!("http://{addr}"))), calls)
+}
+
+/// A General article at `https://example.com/{i}`.
+fn article(i: usize, title: &str, published_at: chrono::DateTime<Utc>) -> Article {
+    let url = format!("https://example.com/{i}");
+    Article {
+        id: news_core::dedup::article_id_from_url(&url),
+        category: Category::General,
+        title: title.to_string(),
+        url,
+        description: None,
+        image_url: None,
+        source: "Example".into(),
+        published_at,
+        fetched_at: Utc::now(),
+        group_id: None,
+        group_count: None,
+        canonical_url: None,
+        tags: Vec::new(),
+        word_count: None,
+        reading_minutes: None,
+        original_url: None,
+    }
+}
+
+/// Articles published one minute apart, newest first.
+fn seed_articles(state: &AppState, titles: &[&str]) -> Vec<Article> {
+    let now = Utc::now();
+    let articles: Vec<Article> = titles
+        .iter()
+        .enumerate()
+        .map(|(i, title)| article(i, title, now - chrono::Duration::minutes(i as i64)))
+        .collect();
+    state.db.batch_insert_articles(&articles).unwrap();
+    articles
+}
+
+async fn send(state: &Arc<AppState>, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+    let response = api_routes(Arc::clone(state)).oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+fn get(uri: &str) -> Request<Body> {
+    Request::get(uri).body(Body::empty()).unwrap()
+}
+
+fn summarize(auth: (&str, &str)) -> Request<Body> {
+    Request::post("/api/articles/summarize")
+        .header("content-type", "application/json")
+        .header(auth.0, auth.1)
+        .body(Body::from(r#"{"minutes":1}"#))
+        .unwrap()
+}
+
+fn ids(body: &serde_json::Value) -> Vec<String> {
+    body["articles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn article_list_pages_through_every_article_once() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["one", "two", "three", "four", "five"]);
+
+    let mut seen = Vec::new();
+    let mut uri = "/api/articles?limit=2".to_string();
+    loop {
+        let (status, body) = send(&state, get(&uri)).await;
+        assert_eq!(status, StatusCode::OK);
+        let page = ids(&body);
+        assert!(page.len() <= 2);
+        seen.extend(page);
+        match body["next_cursor"].as_str() {
+            Some(cursor) => uri = format!("/api/articles?limit=2&cursor={cursor}"),
+            None => break,
+        }
+    }
+    let expected: Vec<String> = seeded.iter().map(|a| a.id.clone()).collect();
+    assert_eq!(seen, expected);
+}
+
+#[tokio::test]
+async fn article_lists_vary_on_the_headers_mute_rules_come_from() {
+    let (state, _) = test_state().await;
+    seed_articles(&state, &["one"]);
+
+    for uri in ["/api/articles", "/api/feed"] {
+        let response = api_routes(Arc::clone(&state)).oneshot(get(uri)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["vary"], "Authorization, X-Device-Id", "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn free_tier_is_cut_off_at_its_limit_and_pro_is_not() {
+    let (state, _) = test_state().await;
+    seed_articles(&state, &["速報"]);
+    for _ in 0..20 {
+        state.db.increment_usage("device-1", "summarize").unwrap();
+    }
+
+    let (status, body) = send(&state, summarize(("x-device-id", "device-1"))).await;
+    assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+    assert_eq!(body["code"], "rate_limit_exceeded");
+    assert_eq!(body["details"]["tier"], "free");
+
+    let period_end = (Utc::now() + chrono::Duration::days(30)).to_rfc3339();
+    let token = state.db.create_subscription("pro-token", "cus_1", "sub_1", &period_end).unwrap();
+    let (status, body) = send(&state, summarize(("authorization", &format!("Bearer {token}")))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["article_count"], 1);
+}
+
+#[tokio::test]
+async fn repeated_summaries_come_from_the_cache() {
+    let (state, calls) = test_state().await;
+    seed_articles(&state, &["速報", "続報"]);
+
+    let (status, first) = send(&state, summarize(("x-device-id", "device-2"))).await;
+    assert_eq!(status, StatusCode::OK, "{first}");
+    let after_first = calls.load(Ordering::SeqCst);
+    assert!(after_first > 0);
+
+    let (status, second) = send(&state, summarize(("x-device-id", "device-2"))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(second["summary"], first["summary"]);
+    assert_eq!(calls.load(Ordering::SeqCst), after_first, "cache hit must not call Claude");
+    // Only the generated summary counts against the limit
+    assert_eq!(state.db.get_usage("device-2", "summarize").unwrap(), 1);
+}
+
+#[tokio::test]
+async fn only_ai_routes_report_the_anonymous_trial() {
+    let (state, _) = test_state().await;
+    seed_articles(&state, &["速報"]);
+
+    let response = api_routes(Arc::clone(&state)).oneshot(summarize(("fly-client-ip", "203.0.113.7"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-trial-remaining"], "2");
+
+    let request = Request::get("/api/articles").header("fly-client-ip", "203.0.113.7").body(Body::empty()).unwrap();
+    let response = api_routes(Arc::clone(&state)).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response.headers().contains_key("x-trial-remaining"));
+}
+
+#[tokio::test]
+async fn admin_routes_need_the_secret() {
+    let (state, _) = test_state().await;
+    let (status, _) = send(&state, get("/api/admin/cache/token-stats")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let wrong = Request::get("/api/admin/cache/token-stats")
+        .header("x-admin-secret", "guess")
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(send(&state, wrong).await.0, StatusCode::UNAUTHORIZED);
+
+    let right = Request::get("/api/admin/cache/token-stats")
+        .header("x-admin-secret", &state.admin_secret)
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(send(&state, right).await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn grouping_flag_collapses_similar_articles_in_the_list() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(
+        &state,
+        &[
+            "東京都で新型コロナウイルスの感染者が100人確認",
+            "日銀が金融政策決定会合で利上げを決定",
+            "東京都で新型コロナウイルスの感染者が150人確認",
+        ],
+    );
+    state.db.regroup_articles(&(Utc::now() - chrono::Duration::hours(1)), 0.5).unwrap();
+
+    let (_, body) = send(&state, get("/api/articles")).await;
+    assert_eq!(ids(&body).len(), 3);
+
+    state.db.set_feature_flag("grouping", true, None).unwrap();
+    let (_, body) = send(&state, get("/api/articles")).await;
+    assert_eq!(ids(&body), [seeded[0].id.clone(), seeded[1].id.clone()]);
+    assert_eq!(body["articles"][0]["group_count"], 2);
+}
+
+#[tokio::test]
+async fn export_is_strict_about_format_and_leaves_the_body_to_pro() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["Rust 2.0 *released*"]);
+    let id = &seeded[0].id;
+
+    let (status, body) = send(&state, get(&format!("/api/articles/{id}/export?format=org"))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "format");
+
+    let response = api_routes(Arc::clone(&state))
+        .oneshot(get(&format!("/api/articles/{id}/export")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-disposition"],
+        format!("attachment; filename=\"{id}.md\"").as_str()
+    );
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let markdown = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(markdown.contains(r"# [Rust 2\.0 \*released\*]"));
+    assert!(!markdown.contains("## 本文"));
+
+    let (status, body) = send(&state, get(&format!("/api/articles/{id}/export?format=json"))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["title"], "Rust 2.0 *released*");
+    assert!(body["content"].is_null());
+
+    // AI output is Pro only too
+    state.db.update_article_analysis(id, "要約です。", &["Rust".into()], "neutral", 0.5, "tech").unwrap();
+    state.db.set_cache(&crate::routes::article_text_cache_key(id), "raw_content", "本文です。", 3600).unwrap();
+    let (_, free) = send(&state, get(&format!("/api/articles/{id}/export?format=json"))).await;
+    assert!(free["ai_summary"].is_null() && free["content"].is_null());
+    assert_eq!(free["ai_keywords"], serde_json::json!([]));
+    assert_eq!(free["source"], "Example");
+
+    let period_end = (Utc::now() + chrono::Duration::days(30)).to_rfc3339();
+    let token = state.db.create_subscription("pro-token", "cus_1", "sub_1", &period_end).unwrap();
+    let pro = Request::get(format!("/api/articles/{id}/export?format=json"))
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let (_, pro) = send(&state, pro).await;
+    assert_eq!(pro["ai_summary"], "要約です。");
+    assert_eq!(pro["ai_keywords"], serde_json::json!(["Rust"]));
+    assert_eq!(pro["content"], "本文です。");
+}
+
+fn import_request(content_type: &str, body: String, secret: &str) -> Request<Body> {
+    Request::post("/api/admin/import/articles")
+        .header("content-type", content_type)
+        .header("x-admin-secret", secret)
+        .body(Body::from(body))
+        .unwrap()
+}
+
+fn import_entry(url: &str, category: &str) -> serde_json::Value {
+    serde_json::json!({
+        "url": url,
+        "title": "移行した記事",
+        "source": "OldAggregator",
+        "category": category,
+        "published_at": "2024-03-01T12:00:00Z",
+    })
+}
+
+#[tokio::test]
+async fn import_reports_duplicates_and_bad_entries_then_cools_down() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["既存の記事"]);
+    let body = serde_json::json!({"articles": [
+        import_entry("https://old.example/1", "tech"),
+        import_entry(&seeded[0].url, "general"),
+        import_entry("https://old.example/2", "gossip"),
+        import_entry("https://old.example/1?utm_source=feed", "tech"),
+    ]});
+
+    let secret = state.admin_secret.clone();
+    let (status, result) = send(&state, import_request("application/json", body.to_string(), &secret)).await;
+    assert_eq!(status, StatusCode::OK, "{result}");
+    assert_eq!(result["imported"], 1);
+    assert_eq!(result["skipped_duplicates"], 2);
+    assert_eq!(result["validation_errors"][0]["index"], 2);
+    let imported = news_core::dedup::article_id_from_url("https://old.example/1");
+    assert_eq!(state.db.get_article_by_id(&imported).unwrap().unwrap().source, "OldAggregator");
+
+    let (status, again) = send(&state, import_request("application/json", body.to_string(), &secret)).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(again["details"]["retry_after_secs"].as_i64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn import_accepts_a_multipart_file() {
+    let (state, _) = test_state().await;
+    let articles: Vec<_> = (0..150).map(|i| import_entry(&format!("https://old.example/{i}"), "science")).collect();
+    let file = serde_json::json!({ "articles": articles }).to_string();
+    let form = format!(
+        "--XBOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"export.json\"\r\n\
+         Content-Type: application/json\r\n\r\n{file}\r\n--XBOUNDARY--\r\n"
+    );
+
+    let secret = state.admin_secret.clone();
+    let request = import_request("multipart/form-data; boundary=XBOUNDARY", form, &secret);
+    let (status, result) = send(&state, request).await;
+    assert_eq!(status, StatusCode::OK, "{result}");
+    assert_eq!(result["imported"], 150);
+    assert_eq!(result["validation_errors"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn json_feed_lists_the_latest_articles_by_category() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["一般の記事", "もう一つ"]);
+
+    let response = api_routes(Arc::clone(&state)).oneshot(get("/feed.json?limit=1")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/feed+json; charset=utf-8");
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let feed: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+    assert_eq!(feed["items"].as_array().unwrap().len(), 1);
+    let item = &feed["items"][0];
+    assert_eq!(item["id"], seeded[0].id.as_str());
+    assert_eq!(item["content_text"], "一般の記事");
+    assert!(item.get("image").is_none());
+
+    let (status, feed) = send(&state, get("/feed.json?category=tech")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(feed["items"], serde_json::json!([]));
+    assert!(feed["feed_url"].as_str().unwrap().ends_with("/feed.json?category=tech"));
+    assert_eq!(send(&state, get("/feed.json?category=gossip")).await.0, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn audio_transcript_prefers_the_cached_reading() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["東京で初雪"]);
+    let uri = format!("/api/articles/{}/audio-transcript?voice_id=qwen-tts:Japanese", seeded[0].id);
+
+    let (status, body) = send(&state, get(&uri)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::json!({"transcript": null, "reason": "not_yet_generated"}));
+
+    let raw_text = crate::tts_cache::article_tts_text(&seeded[0]);
+    let audio_key = crate::routes::cache_key("tts_audio", &format!("qwen-tts:Japanese|{raw_text}"));
+    state.db.set_cache(&audio_key, "tts_audio", "AAAA", 3600).unwrap();
+    let (_, body) = send(&state, get(&uri)).await;
+    assert_eq!(body["source"], "original");
+    assert_eq!(body["transcript"], raw_text.as_str());
+
+    let profile = crate::reading::ReadingProfile::QwenJapanese;
+    let reading_key = crate::reading::reading_cache_key(profile, &raw_text);
+    state.db.set_cache(&reading_key, "to_reading", "とうきょうではつゆき", 3600).unwrap();
+    let (_, body) = send(&state, get(&uri)).await;
+    assert_eq!(body["transcript"], "とうきょうではつゆき");
+    assert_eq!(body["source"], "cached");
+    assert_eq!(body["engine"], "qwen-tts");
+
+    let (status, _) = send(&state, get("/api/articles/missing/audio-transcript")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn change_with_a_failed_feed_probe_needs_force() {
+    use news_core::changes::{ActionCheck, ActionValidation, AdminAction, ChangeRequest, ChangeStatus};
+    let (state, _) = test_state().await;
+    let url = "https://made-up.example/rss.xml";
+    state
+        .db
+        .create_change(&ChangeRequest {
+            change_id: "c1".into(),
+            status: ChangeStatus::Preview,
+            command_text: "テック系のフィードを追加して".into(),
+            interpretation: String::new(),
+            actions: vec![AdminAction::AddFeed { url: url.into(), source: "Made Up".into(), category: "tech".into() }],
+            preview_config: None,
+            preview_diff: None,
+            validation: Some(vec![ActionValidation::new(
+                0,
+                ActionCheck::FeedProbe { found: false, item_count: None, error: Some("404".into()) },
+            )]),
+            parent_change_id: None,
+            version: 1,
+            created_at: Utc::now().to_rfc3339(),
+        })
+        .unwrap();
+    let apply = |uri: &str| {
+        Request::post(uri).header("x-admin-secret", &state.admin_secret).body(Body::empty()).unwrap()
+    };
+
+    let (status, body) = send(&state, apply("/api/admin/changes/c1/apply")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "force");
+    assert!(state.db.get_all_feeds().unwrap().iter().all(|f| f.url != url));
+
+    let (status, body) = send(&state, apply("/api/admin/changes/c1/apply?force=true")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["applied"], 1);
+    assert!(state.db.get_all_feeds().unwrap().iter().any(|f| f.url == url));
+}
+
+#[tokio::test]
+async fn cached_audio_answers_head_and_byte_ranges() {
+    let (state, _) = test_state().await;
+    let audio: Vec<u8> = (0u8..10).collect();
+    let key = crate::routes::cache_key("tts_audio", "openai:nova|こんにちは");
+    let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &audio);
+    state.db.set_cache(&key, "tts_audio", &b64, 3600).unwrap();
+    let uri = format!("/api/tts/cached/{key}");
+    let fetch = |request: Request<Body>| async {
+        let response = api_routes(Arc::clone(&state)).oneshot(request).await.unwrap();
+        let (parts, body) = response.into_parts();
+        (parts, axum::body::to_bytes(body, usize::MAX).await.unwrap())
+    };
+
+    let (head, body) = fetch(Request::head(&uri).body(Body::empty()).unwrap()).await;
+    assert_eq!(head.status, StatusCode::OK);
+    assert_eq!(head.headers["content-length"], "10");
+    assert_eq!(head.headers["content-type"], "audio/mpeg");
+    assert_eq!(head.headers["accept-ranges"], "bytes");
+    assert!(body.is_empty());
+
+    let (part, body) = fetch(Request::get(&uri).header("range", "bytes=2-5").body(Body::empty()).unwrap()).await;
+    assert_eq!(part.status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(part.headers["content-range"], "bytes 2-5/10");
+    assert_eq!(&body[..], &audio[2..=5]);
+
+    let (past_end, _) = fetch(Request::get(&uri).header("range", "bytes=20-30").body(Body::empty()).unwrap()).await;
+    assert_eq!(past_end.status, StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(past_end.headers["content-range"], "bytes */10");
+}
+
+#[tokio::test]
+async fn article_audio_serves_only_cached_audio() {
+    let (state, calls) = test_state().await;
+    let article = seed_articles(&state, &["音声で聞く記事"]).remove(0);
+    let uri = format!("/api/articles/{}/audio?voice_id=openai:nova", article.id);
+
+    let (status, body) = send(&state, get(&uri)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["status"], "not_cached");
+    assert_eq!(body["preload_url"], "/api/tts/preload");
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    let audio: Vec<u8> = (0u8..10).collect();
+    let text = crate::tts_cache::article_tts_text(&article);
+    let key = crate::routes::cache_key("tts_audio", &format!("openai:nova|{text}"));
+    let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &audio);
+    state.db.set_cache(&key, "tts_audio", &b64, 3600).unwrap();
+    let request = Request::get(&uri).header("range", "bytes=0-3").body(Body::empty()).unwrap();
+    let response = api_routes(Arc::clone(&state)).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()["content-type"], "audio/mpeg");
+    let disposition = response.headers()["content-disposition"].to_str().unwrap();
+    assert_eq!(disposition, format!("inline; filename=\"article-{}.mp3\"", article.id));
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], &audio[..4]);
+
+    let (status, _) = send(&state, get("/api/articles/missing/audio")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn range_request_for_uncached_tts_does_not_generate() {
+    let (state, calls) = test_state().await;
+    let request = Request::post("/api/tts")
+        .header("content-type", "application/json")
+        .header("x-device-id", "device-3")
+        .header("range", "bytes=0-1")
+        .body(Body::from(r#"{"text":"まだ生成されていない文章","voice_id":"openai:nova"}"#))
+        .unwrap();
+    let response = api_routes(Arc::clone(&state)).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()["accept-ranges"], "none");
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    assert_eq!(state.db.get_usage("device-3", "tts").unwrap(), 0);
+}
+
+#[tokio::test]
+async fn removed_category_id_still_finds_its_reassigned_articles() {
+    let (state, _) = test_state().await;
+    state.db.seed_default_categories().unwrap();
+    let seeded = seed_articles(&state, &["一般の記事", "もう一つ"]);
+    assert_eq!(ids(&send(&state, get("/api/articles?category=sports")).await.1), Vec::<String>::new());
+
+    state.db.delete_category("sports", Some("general")).unwrap();
+    let (status, body) = send(&state, get("/api/articles?category=sports")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), seeded.iter().map(|a| a.id.clone()).collect::<Vec<_>>());
+}
+
+fn mcp(method: &str, params: serde_json::Value) -> Request<Body> {
+    let body = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+    Request::post("/mcp")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn mcp_tool_schemas_list_admin_created_categories() {
+    let (state, _) = test_state().await;
+    state.db.seed_default_categories().unwrap();
+    state.db.put_category("lifestyle", "ライフスタイル", "Lifestyle", 99).unwrap();
+
+    let (status, body) = send(&state, mcp("tools/list", serde_json::json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    let tools = body["result"]["tools"].as_array().unwrap();
+    let category_description = |name: &str| {
+        let tool = tools.iter().find(|t| t["name"] == name).unwrap();
+        tool["inputSchema"]["properties"]["category"]["description"].as_str().unwrap().to_string()
+    };
+    for name in ["list_articles", "add_feed"] {
+        let description = category_description(name);
+        assert!(description.contains("lifestyle") && description.contains("tech"), "{name}: {description}");
+    }
+}
+
+#[tokio::test]
+async fn mcp_article_resources_page_and_read_back() {
+    let (state, _) = test_state().await;
+    let titles: Vec<String> = (0..25).map(|i| format!("記事 {i}")).collect();
+    let seeded = seed_articles(&state, &titles.iter().map(String::as_str).collect::<Vec<_>>());
+    state
+        .db
+        .update_article_analysis(&seeded[24].id, "要約です。", &[], "neutral", 0.5, "general")
+        .unwrap();
+    let key = crate::routes::article_text_cache_key(&seeded[24].id);
+    state.db.set_cache(&key, "raw_content", "本文です。", 3600).unwrap();
+
+    let (_, first) = send(&state, mcp("resources/list", serde_json::json!({}))).await;
+    let resources = first["result"]["resources"].as_array().unwrap();
+    assert_eq!(resources[0]["uri"], "news://articles");
+    let articles: Vec<&str> = resources
+        .iter()
+        .filter_map(|r| r["uri"].as_str()?.strip_prefix("news://article/"))
+        .collect();
+    assert_eq!(articles.len(), 20);
+    assert_eq!(articles[0], seeded[0].id);
+    let cursor = first["result"]["nextCursor"].as_str().unwrap();
+
+    let (_, second) = send(&state, mcp("resources/list", serde_json::json!({ "cursor": cursor }))).await;
+    let resources = second["result"]["resources"].as_array().unwrap();
+    assert_eq!(resources.len(), 5);
+    assert!(second["result"].get("nextCursor").is_none());
+    let last = resources[4]["uri"].as_str().unwrap();
+    assert_eq!(last, format!("news://article/{}", seeded[24].id));
+
+    let (_, read) = send(&state, mcp("resources/read", serde_json::json!({ "uri": last }))).await;
+    let contents = &read["result"]["contents"][0];
+    assert_eq!(contents["mimeType"], "text/plain");
+    let text = contents["text"].as_str().unwrap();
+    assert!(text.starts_with("記事 24\nExample · "));
+    assert!(text.contains("## Summary\n要約です。"));
+    assert!(text.contains("## Content\n本文です。"));
+
+    let (_, bad) = send(&state, mcp("resources/list", serde_json::json!({ "cursor": "nonsense" }))).await;
+    assert_eq!(bad["error"]["code"], -32602);
+}
+
+#[tokio::test]
+async fn mcp_resource_reads_report_unknown_uris_and_truncate() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["長い記事"]);
+    let key = crate::routes::article_text_cache_key(&seeded[0].id);
+    state.db.set_cache(&key, "raw_content", &"あ".repeat(30_000), 3600).unwrap();
+
+    let uri = format!("news://article/{}", seeded[0].id);
+    let (_, read) = send(&state, mcp("resources/read", serde_json::json!({ "uri": uri }))).await;
+    let text = read["result"]["contents"][0]["text"].as_str().unwrap();
+    assert!(text.len() < 51 * 1024);
+    assert!(text.trim_end().ends_with("bytes]"), "{}", &text[text.len() - 80..]);
+
+    for uri in ["news://article/missing", "news://category/gossip", "news://weather"] {
+        let (_, body) = send(&state, mcp("resources/read", serde_json::json!({ "uri": uri }))).await;
+        assert_eq!(body["error"]["code"], -32002, "{uri}");
+        assert_eq!(body["error"]["data"]["uri"], uri);
+    }
+
+    let (_, templates) = send(&state, mcp("resources/templates/list", serde_json::json!({}))).await;
+    let templates = templates["result"]["resourceTemplates"].as_array().unwrap();
+    assert!(templates.iter().any(|t| t["uriTemplate"] == "news://category/{category}"));
+    let (_, general) = send(&state, mcp("resources/read", serde_json::json!({ "uri": "news://category/general" }))).await;
+    let listed: serde_json::Value =
+        serde_json::from_str(general["result"]["contents"][0]["text"].as_str().unwrap()).unwrap();
+    assert_eq!(listed[0]["uri"], uri.as_str());
+}
+
+#[tokio::test]
+async fn admin_can_explain_article_queries() {
+    let (state, _) = test_state().await;
+    let admin = |uri: &str| Request::get(uri).header("x-admin-secret", &state.admin_secret).body(Body::empty()).unwrap();
+
+    let (status, body) = send(&state, admin("/api/admin/db/explain?query_name=articles_by_category")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body["query"].as_str().unwrap().contains("category = :cat"));
+    let plan = body["plan"].as_array().unwrap();
+    assert!(plan.iter().any(|step| step["detail"].as_str().unwrap().contains("idx_articles_cat_pub")));
+    assert!(plan[0].get("notused").is_some());
+
+    let (status, body) = send(&state, admin("/api/admin/db/explain?query_name=DELETE%20FROM%20articles")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "query_name");
+    assert_eq!(send(&state, get("/api/admin/db/explain?query_name=search")).await.0, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = send(&state, admin("/api/admin/db/indexes")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["indexes"].as_array().unwrap().iter().any(|i| i["name"] == "idx_articles_pub"));
+}
+
+fn compare_coverage(id: &str) -> Request<Body> {
+    Request::post(format!("/api/articles/{id}/compare")).body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn coverage_comparison_needs_another_source_and_is_cached_per_member_set() {
+    let (state, calls) = test_state().await;
+    let seeded = seed_articles(&state, &["東京都心で大規模な停電が発生", "週末の天気は晴れ", "東京都心で大規模な停電が発生 続報"]);
+    let (status, _) = send(&state, compare_coverage("missing")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    // Only the same source has covered the story so far
+    let (status, body) = send(&state, compare_coverage(&seeded[0].id)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "conflict");
+    assert!(body["message"].as_str().is_some_and(|m| m.contains("比較できません")));
+
+    let mut other = seeded[0].clone();
+    other.url = "https://other.example/power".into();
+    other.id = news_core::dedup::article_id_from_url(&other.url);
+    other.source = "Other".into();
+    state.db.insert_article(&other).unwrap();
+
+    let mut member_ids = [seeded[0].id.as_str(), other.id.as_str()];
+    member_ids.sort_unstable();
+    let key = crate::routes::cache_key("compare_coverage", &member_ids.join("|"));
+    let cached = serde_json::json!([
+        {"common_facts": ["停電"], "differences": [{"source": "Other", "emphasis": "復旧見込み"}], "tone_notes": "淡々"},
+        "claude-test"
+    ]);
+    state.db.set_cache(&key, "compare_coverage", &cached.to_string(), 3600).unwrap();
+    // A cached comparison is served even once the daily limit is used up
+    for _ in 0..10 {
+        state.db.increment_usage("device-1", "compare").unwrap();
+    }
+    let mut request = compare_coverage(&seeded[0].id);
+    request.headers_mut().insert("x-device-id", "device-1".parse().unwrap());
+
+    let (status, body) = send(&state, request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    assert_eq!(body["differences"][0]["emphasis"], "復旧見込み");
+    let members: Vec<&str> = body["members"].as_array().unwrap().iter().map(|m| m["id"].as_str().unwrap()).collect();
+    assert_eq!(members, [seeded[0].id.as_str(), other.id.as_str()]);
+    assert_eq!(body["members"][1]["title"], seeded[0].title);
+}
+
+#[tokio::test]
+async fn cited_by_lists_articles_sharing_entities() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["Apple の新製品", "Tim Cook 氏が来日", "国内の天気"]);
+    let entity = |text: &str, kind: &str| (text.to_string(), kind.to_string());
+    let first = [entity("Apple", "organization"), entity("Tim Cook", "person"), entity("iPhone", "product")];
+    state.db.set_article_entities(&seeded[0].id, &first).unwrap();
+    let second = [entity("Tim Cook", "person"), entity("Apple", "organization")];
+    state.db.set_article_entities(&seeded[1].id, &second).unwrap();
+
+    let (status, body) = send(&state, get(&format!("/api/articles/{}/cited-by", seeded[0].id))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["cited_by"].as_array().unwrap().len(), 1);
+    assert_eq!(body["cited_by"][0]["id"], seeded[1].id);
+    assert_eq!(body["cited_by"][0]["shared_entities"], serde_json::json!(["Apple", "Tim Cook"]));
+    assert_eq!(body["shared_entities"], serde_json::json!(["Apple", "Tim Cook"]));
+
+    let (_, body) = send(&state, get(&format!("/api/articles/{}/cited-by", seeded[2].id))).await;
+    assert_eq!(body["cited_by"], serde_json::json!([]));
+    let (status, _) = send(&state, get("/api/articles/missing/cited-by")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn classification_falls_back_to_keyword_rules_when_claude_fails() {
+    let (state, calls) = test_state().await;
+    let request = || {
+        let body = serde_json::json!({
+            "title": "話題のアプリが Product Hunt で1位に",
+            "description": "",
+            "source": "Example",
+            "category": "tech",
+        });
+        Request::post("/api/articles/classify")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    // The mock's prose reply isn't the JSON asked for
+    let (status, body) = send(&state, request()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(body["category"], "timemachine");
+    assert_eq!(body["source"], "local");
+
+    // Not cached, so Claude is asked again next time
+    send(&state, request()).await;
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn bootstrap_bundles_sections_and_revalidates_with_etag() {
+    let (state, _) = test_state().await;
+    state.db.seed_default_categories().unwrap();
+    let seeded = seed_articles(&state, &["一本目", "二本目"]);
+
+    let response = api_routes(Arc::clone(&state)).oneshot(get("/api/bootstrap")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[axum::http::header::ETAG].to_str().unwrap().to_string();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(body["categories"].as_array().is_some_and(|c| !c.is_empty()));
+    assert_eq!(body["articles"]["articles"][0]["id"], seeded[0].id);
+    assert_eq!(body["articles"]["articles"][0]["source_meta"]["source"], "Example");
+    assert_eq!(body["config"]["features"]["ai"], true);
+    assert_eq!(body["config"]["features"]["tts"], false);
+    assert!(body.get("usage").is_none());
+
+    let revalidate = |etag: &str| {
+        Request::get("/api/bootstrap").header("if-none-match", etag).body(Body::empty()).unwrap()
+    };
+    let (status, _) = send(&state, revalidate(&etag)).await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    let mut newer = seeded[0].clone();
+    newer.url = "https://example.com/newer".into();
+    newer.id = news_core::dedup::article_id_from_url(&newer.url);
+    newer.published_at += chrono::Duration::minutes(1);
+    state.db.insert_article(&newer).unwrap();
+    let (status, _) = send(&state, revalidate(&etag)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // A section that fails doesn't take the others down
+    let request = Request::get("/api/bootstrap?category=no-such-category")
+        .header("x-device-id", "device-1234567890")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send(&state, request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["articles"]["error"].as_str().is_some_and(|e| e.contains("no-such-category")));
+    assert!(body["categories"].is_array());
+    assert_eq!(body["usage"]["tier"], "free");
+}
+
+#[tokio::test]
+async fn bootstrap_etag_changes_with_categories_flags_and_usage() {
+    let (state, _) = test_state().await;
+    state.db.seed_default_categories().unwrap();
+    seed_articles(&state, &["一本目"]);
+    let etag = |state: Arc<AppState>| async move {
+        let request = Request::get("/api/bootstrap").header("x-device-id", "device-1234567890").body(Body::empty()).unwrap();
+        let response = api_routes(state).oneshot(request).await.unwrap();
+        response.headers()[axum::http::header::ETAG].to_str().unwrap().to_string()
+    };
+
+    let first = etag(Arc::clone(&state)).await;
+    assert_eq!(etag(Arc::clone(&state)).await, first, "nothing changed");
+
+    state.db.put_category("astronomy", "天文", "Astronomy", 99).unwrap();
+    let after_category = etag(Arc::clone(&state)).await;
+    assert_ne!(after_category, first);
+
+    state.db.set_feature_flag("grouping", true, None).unwrap();
+    let after_flag = etag(Arc::clone(&state)).await;
+    assert_ne!(after_flag, after_category);
+
+    state.db.increment_usage("device-1234567890", "summarize").unwrap();
+    assert_ne!(etag(Arc::clone(&state)).await, after_flag);
+}
+
+#[tokio::test]
+async fn admin_feed_articles_are_listed_and_counted_by_source() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["一", "二", "三"]);
+    state
+        .db
+        .put_feed(&news_core::config::DynamicFeed {
+            feed_id: "feed-example".into(),
+            url: "https://example.com/rss".into(),
+            source: "Example".into(),
+            category: "general".into(),
+            enabled: true,
+            added_by: None,
+            max_articles_per_fetch: None,
+            auto_translate: false,
+            category_overrides: None,
+        })
+        .unwrap();
+    let admin = |uri: &str| {
+        Request::get(uri).header("x-admin-secret", &state.admin_secret).body(Body::empty()).unwrap()
+    };
+
+    let (status, _) = send(&state, get("/api/admin/feeds/feed-example/articles")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, page) = send(&state, admin("/api/admin/feeds/feed-example/articles?limit=2")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&page), [seeded[0].id.clone(), seeded[1].id.clone()]);
+    let cursor = page["next_cursor"].as_str().unwrap();
+    let (_, rest) = send(&state, admin(&format!("/api/admin/feeds/feed-example/articles?limit=2&cursor={cursor}"))).await;
+    assert_eq!(ids(&rest), [seeded[2].id.clone()]);
+
+    let (_, counts) = send(&state, admin("/api/admin/feeds/feed-example/articles/count")).await;
+    assert_eq!(counts, serde_json::json!({"total": 3, "last_24h": 3, "last_7d": 3}));
+    let (status, _) = send(&state, admin("/api/admin/feeds/missing/articles/count")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn admin_created_categories_accept_imports_and_filter_articles() {
+    let (state, _) = test_state().await;
+    let secret = state.admin_secret.clone();
+    let add = Request::post("/api/admin/categories")
+        .header("content-type", "application/json")
+        .header("x-admin-secret", &secret)
+        .body(Body::from(r#"{"action": "add", "id": "Lifestyle", "label_ja": "ライフスタイル"}"#))
+        .unwrap();
+    let (status, _) = send(&state, add).await;
+    assert_eq!(status, StatusCode::OK);
+    let entries = serde_json::json!({"articles": [
+        import_entry("https://old.example/life", "lifestyle"),
+        import_entry("https://old.example/gossip", "gossip"),
+    ]});
+    let (status, result) = send(&state, import_request("application/json", entries.to_string(), &secret)).await;
+    assert_eq!(status, StatusCode::OK, "{result}");
+    assert_eq!(result["imported"], 1);
+    assert_eq!(result["validation_errors"][0]["index"], 1);
+
+    let (status, body) = send(&state, get("/api/articles?category=lifestyle")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), [news_core::dedup::article_id_from_url("https://old.example/life")]);
+    assert_eq!(body["articles"][0]["category"], "lifestyle");
+    let (_, categories) = send(&state, get("/api/categories")).await;
+    assert!(categories.to_string().contains("\"lifestyle\""));
+}
+
+#[tokio::test]
+async fn articles_remain_reachable_by_category_after_a_rename() {
+    let (state, _) = test_state().await;
+    state.db.seed_default_categories().unwrap();
+    let mut tech = seed_articles(&state, &["一般の記事"])[0].clone();
+    tech.url = "https://example.com/tech".into();
+    tech.id = news_core::dedup::article_id_from_url(&tech.url);
+    tech.category = Category::Tech;
+    state.db.insert_article(&tech).unwrap();
+
+    let rename = Request::post("/api/admin/categories")
+        .header("content-type", "application/json")
+        .header("x-admin-secret", &state.admin_secret)
+        .body(Body::from(r#"{"action": "rename", "id": "tech", "label_ja": "IT", "new_id": "IT"}"#))
+        .unwrap();
+    let (status, _) = send(&state, rename).await;
+    assert_eq!(status, StatusCode::OK);
+
+    for uri in ["/api/articles?category=tech", "/api/articles?category=it"] {
+        let (status, body) = send(&state, get(uri)).await;
+        assert_eq!(status, StatusCode::OK, "{uri}");
+        assert_eq!(ids(&body), [tech.id.clone()], "{uri}");
+        assert_eq!(body["articles"][0]["category"], "it");
+    }
+    let (status, _) = send(&state, get("/api/articles?category=nope")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn stored_translations_are_listed_and_served_by_language() {
+    let (state, calls) = test_state().await;
+    let id = seed_articles(&state, &["元の記事"])[0].id.clone();
+    state.db.store_translation(&id, "en", "The article", Some("Summary"), Some("ja"), Some("haiku")).unwrap();
+    state.db.store_translation(&id, "ko", "기사", None, Some("ja"), None).unwrap();
+
+    let (status, body) = send(&state, get(&format!("/api/articles/{id}/translations/list"))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["available_languages"], serde_json::json!(["en", "ko"]));
+    assert_eq!(body["translations"][0]["title"], "The article");
+    let (status, _) = send(&state, get("/api/articles/missing/translations/list")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, body) = send(&state, get(&format!("/api/articles/{id}?lang=en"))).await;
+    assert_eq!(body["article"]["title"], "元の記事");
+    assert_eq!(body["translation"]["title"], "The article");
+    let (_, body) = send(&state, get(&format!("/api/articles/{id}?lang=fr"))).await;
+    assert!(body["translation"].is_null());
+
+    // Only a translation with its body is complete enough to skip Claude
+    state.db.set_translation_content(&id, "en", "The body.").unwrap();
+    let translate = Request::post("/api/articles/translate")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::json!({"article_id": id, "target_lang": "en"}).to_string()))
+        .unwrap();
+    let (status, body) = send(&state, translate).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"], "The body.");
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn over_long_translation_text_is_rejected_before_claude() {
+    let (state, calls) = test_state().await;
+    let translate = |body: serde_json::Value| {
+        Request::post("/api/articles/translate")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    for (field, body) in [
+        ("title", serde_json::json!({"title": "a".repeat(501)})),
+        ("description", serde_json::json!({"title": "Title", "description": "あ".repeat(5001)})),
+    ] {
+        let (status, body) = send(&state, translate(body)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+        assert_eq!(body["details"]["field"], field);
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn ssr_pages_allow_murmur_audio_and_google_sign_in() {
+    let (state, _) = test_state().await;
+    let id = seed_articles(&state, &["記事"])[0].id.clone();
+    let response = api_routes(Arc::clone(&state)).oneshot(get(&format!("/article/{id}"))).await.unwrap();
+    let csp = crate::routes::csp_header(&response).expect("SSR pages carry a CSP nonce");
+    let csp = csp.to_str().unwrap();
+    assert!(csp.contains("media-src 'self' blob: data:"), "{csp}");
+    assert!(csp.contains("script-src 'self' 'nonce-"), "{csp}");
+    for directive in [
+        "https://accounts.google.com/gsi/client",
+        "style-src 'self' 'unsafe-inline' https://accounts.google.com/gsi/style",
+        "frame-src https://accounts.google.com/gsi/",
+        "connect-src 'self' https://api.anthropic.com https://accounts.google.com/gsi/",
+    ] {
+        assert!(csp.contains(directive), "missing {directive}: {csp}");
+    }
+}
+
+#[tokio::test]
+async fn tts_previews_are_limited_to_configured_voices() {
+    let mut state = AppState::for_tests("http://127.0.0.1:9");
+    state.openai_api_key = "sk-test".into();
+    let state = Arc::new(state);
+    let preview = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, b"ID3-preview");
+    state.db.set_cache(&crate::routes::tts_preview_key("openai:alloy"), "tts_preview", &preview, 3600).unwrap();
+
+    let response = api_routes(Arc::clone(&state))
+        .oneshot(get("/api/tts/preview?voice_id=openai:alloy"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    for unknown in ["openai:bogus", "cartesia:abc", "qwen-tts:Japanese", "not%20a%20voice"] {
+        let (status, _) = send(&state, get(&format!("/api/tts/preview?voice_id={unknown}"))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{unknown}");
+    }
+
+    // Generating an uncached preview counts against the caller's limit
+    for _ in 0..10 {
+        state.db.increment_usage("device-1", "tts_preview").unwrap();
+    }
+    let request = Request::get("/api/tts/preview?voice_id=openai:nova")
+        .header("x-device-id", "device-1")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send(&state, request).await;
+    assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+    assert_eq!(body["details"]["feature"], "tts_preview");
+}
+
+#[tokio::test]
+async fn daily_digest_covers_the_last_day_with_an_audio_playlist() {
+    let (state, _) = test_state().await;
+    state.db.seed_default_categories().unwrap();
+    let fresh = seed_articles(&state, &["今日のニュース"]).remove(0);
+    let mut stale = fresh.clone();
+    stale.url = "https://example.com/old".into();
+    stale.id = news_core::dedup::article_id_from_url(&stale.url);
+    stale.category = Category::Tech;
+    stale.title = "一昨日のニュース".into();
+    stale.published_at = Utc::now() - chrono::Duration::hours(48);
+    state.db.insert_article(&stale).unwrap();
+
+    let (status, body) = send(&state, get("/api/digest/daily")).await;
+    assert_eq!(status, StatusCode::OK);
+    let categories = body["categories"].as_array().unwrap();
+    assert_eq!(categories.len(), 1, "{body}");
+    assert_eq!(categories[0]["category"], "general");
+    assert_eq!(categories[0]["top_article"]["id"], fresh.id.as_str());
+    // The mock's reply isn't a headline map, so the title stands in
+    assert_eq!(categories[0]["headline"], "今日のニュース");
+
+    let (status, body) = send(&state, get("/api/digest/daily/audio")).await;
+    assert_eq!(status, StatusCode::OK);
+    let segment = &body["segments"][0];
+    assert_eq!(segment["article_id"], fresh.id.as_str());
+    assert_eq!(segment["cached"], false);
+    assert_eq!(body["cached_count"], 0);
+    assert!(segment["audio_url"].as_str().unwrap().starts_with("/api/tts/cached/"));
+}
+
+#[tokio::test]
+async fn cached_tts_audio_is_served_but_other_cache_entries_are_not() {
+    let (state, _) = test_state().await;
+    let audio_key = crate::routes::cache_key("tts_audio", "voice|text");
+    let audio = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, b"ID3-audio");
+    state.db.set_cache(&audio_key, "tts_audio", &audio, 3600).unwrap();
+    let other_key = crate::routes::cache_key("classify", "title|source|general");
+    let other = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, br#"{"category":"tech"}"#);
+    state.db.set_cache(&other_key, "classify", &other, 3600).unwrap();
+
+    let response = api_routes(Arc::clone(&state))
+        .oneshot(get(&format!("/api/tts/cached/{audio_key}")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "audio/mpeg");
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&bytes[..], b"ID3-audio");
+
+    let (status, _) = send(&state, get(&format!("/api/tts/cached/{other_key}"))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&state, get("/api/tts/cached/not-a-key")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn to_reading_accepts_multibyte_text_cut_mid_character() {
+    let (state, calls) = test_state().await;
+    // 1667 three-byte characters: byte 5000 falls inside the last one
+    let text = "あ".repeat(1667);
+    assert_eq!(text.len(), 5001);
+    let request = Request::post("/api/tts/to-reading")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::json!({"text": text}).to_string()))
+        .unwrap();
+    let (status, body) = send(&state, request).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["reading"], "今日の主なニュースです。");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn oversize_voice_clone_requests_never_reach_runpod() {
+    // RunPod traffic goes through a proxy that only counts connections
+    let connections = Arc::new(AtomicUsize::new(0));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let proxy = format!("http://{}", listener.local_addr().unwrap());
+    let counter = Arc::clone(&connections);
+    tokio::spawn(async move {
+        while listener.accept().await.is_ok() {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    let mut state = AppState::for_tests("http://127.0.0.1:9");
+    state.runpod_api_key = "rp-test".into();
+    state.qwen_tts_endpoint_id = "qwen-tts".into();
+    state.runpod_client = reqwest::Client::builder().proxy(reqwest::Proxy::all(&proxy).unwrap()).build().unwrap();
+    let state = Arc::new(state);
+
+    let clone_request = |ref_audio: &str, declare_length: bool| {
+        let body = serde_json::json!({"text": "こんにちは", "ref_audio": ref_audio, "ref_text": "参照"}).to_string();
+        let mut request = Request::post("/api/tts/clone").header("content-type", "application/json");
+        if declare_length {
+            request = request.header("content-length", body.len());
+        }
+        request.body(Body::from(body)).unwrap()
+    };
+    let oversize = "A".repeat(4 * 1024 * 1024);
+    for declare_length in [true, false] {
+        let (status, body) = send(&state, clone_request(&oversize, declare_length)).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE, "{body}");
+        assert_eq!(body["code"], "payload_too_large", "{body}");
+    }
+    // Within the size limit but not audio
+    let not_audio = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, b"plain text");
+    let (status, _) = send(&state, clone_request(&not_audio, true)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(connections.load(Ordering::SeqCst), 0);
+
+    // A valid request does go out, so the proxy would have seen the others
+    let audio = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, b"ID3-reference");
+    let (status, _) = send(&state, clone_request(&audio, true)).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn cached_comparisons_are_served_past_the_daily_limit() {
+    let (state, calls) = test_state().await;
+    let seeded = seed_articles(&state, &["記事A", "記事B", "記事C"]);
+    let (a, b) = (seeded[0].id.as_str(), seeded[1].id.as_str());
+    let (first, second) = if a < b { (a, b) } else { (b, a) };
+    let comparison = serde_json::json!({"summary_a": first, "summary_b": second});
+    let key = crate::routes::cache_key("compare", &format!("{first}|{second}"));
+    state.db.set_cache(&key, "compare", &serde_json::json!([comparison, "cached-model"]).to_string(), 3600).unwrap();
+    for _ in 0..10 {
+        state.db.increment_usage("device-1", "compare").unwrap();
+    }
+    let compare = |x: &str, y: &str| {
+        Request::post("/api/articles/compare")
+            .header("content-type", "application/json")
+            .header("x-device-id", "device-1")
+            .body(Body::from(serde_json::json!({"article_id_a": x, "article_id_b": y}).to_string()))
+            .unwrap()
+    };
+
+    let (status, body) = send(&state, compare(a, b)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["summary_a"], a);
+    assert_eq!(body["model_used"], "cached-model");
+    let (status, body) = send(&state, compare(a, &seeded[2].id)).await;
+    assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+    assert_eq!(body["details"]["feature"], "compare");
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn cached_sentiment_is_served_past_the_daily_limit() {
+    let (state, calls) = test_state().await;
+    let seeded = seed_articles(&state, &["記事A", "記事B"]);
+    let cached = serde_json::json!({"article_id": seeded[0].id, "overall": "positive"});
+    let key = crate::routes::cache_key("sentiment", &seeded[0].id);
+    state.db.set_cache(&key, "sentiment", &cached.to_string(), 3600).unwrap();
+    for _ in 0..15 {
+        state.db.increment_usage("device-1", "sentiment").unwrap();
+    }
+    let sentiment = |id: &str| {
+        Request::get(format!("/api/articles/{id}/sentiment"))
+            .header("x-device-id", "device-1")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let (status, body) = send(&state, sentiment(&seeded[0].id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["overall"], "positive");
+    let (status, body) = send(&state, sentiment(&seeded[1].id)).await;
+    assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+    assert_eq!(body["details"]["feature"], "sentiment");
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn reader_mode_pages_come_from_the_content_cache_past_the_limit() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["記事A", "記事B"]);
+    let key = crate::routes::cache_key("reading_mode", &seeded[0].id);
+    state.db.set_content_cache(&key, "<!DOCTYPE html><article>本文</article>", 3600).unwrap();
+    assert_eq!(state.db.get_cache(&key).unwrap(), None, "not stored in ai_cache");
+    for _ in 0..50 {
+        state.db.increment_usage("device-1", "reading_mode").unwrap();
+    }
+    let reading_mode = |id: &str| {
+        Request::get(format!("/api/articles/{id}/reading-mode"))
+            .header("x-device-id", "device-1")
+            .header("accept", "application/json")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let (status, body) = send(&state, reading_mode(&seeded[0].id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["html"], "<!DOCTYPE html><article>本文</article>");
+    assert_eq!(body["title"], "記事A");
+    let (status, body) = send(&state, reading_mode(&seeded[1].id)).await;
+    assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+    assert_eq!(body["details"]["feature"], "reading_mode");
+}
+
+#[tokio::test]
+async fn health_is_served_under_api_too() {
+    let (state, _) = test_state().await;
+    for uri in ["/health", "/api/health"] {
+        let (status, body) = send(&state, get(uri)).await;
+        assert_eq!(status, StatusCode::OK, "{uri}");
+        assert_eq!(body["feeds"], 0, "{uri}");
+    }
+    assert!(state.db.probe_writable().is_ok());
+}
+
+#[tokio::test]
+async fn excluded_source_types_are_filtered_before_the_page_is_cut() {
+    let (state, _) = test_state().await;
+    let seeded = seed_articles(&state, &["報道 1", "報道 2", "報道 3"]);
+    // Three newer press releases
+    let releases: Vec<Article> = (0..3)
+        .map(|i| {
+            let mut a = seeded[0].clone();
+            a.url = format!("https://pr.example/{i}");
+            a.id = news_core::dedup::article_id_from_url(&a.url);
+            a.title = format!("PR {i}");
+            a.source = "PR Wire".into();
+            a.published_at = seeded[0].published_at + chrono::Duration::minutes(i + 1);
+            a
+        })
+        .collect();
+    state.db.batch_insert_articles(&releases).unwrap();
+    state.db.put_source_meta(&news_core::models::SourceMeta {
+        source_type: "pr".into(),
+        credibility_tier: Some(3),
+        ..news_core::models::SourceMeta::unknown("PR Wire")
+    }).unwrap();
+
+    for query in ["", "&sort=importance", "&freshness=60"] {
+        let (status, body) = send(&state, get(&format!("/api/articles?exclude_types=pr&limit=3{query}"))).await;
+        assert_eq!(status, StatusCode::OK, "{query}: {body}");
+        let articles = body["articles"].as_array().unwrap();
+        assert_eq!(articles.len(), 3, "{query}: a full page without press releases");
+        assert!(articles.iter().all(|a| a["source"] == "Example" && a["source_meta"]["type"] == "unknown"), "{query}");
+    }
+    let (_, body) = send(&state, get("/api/articles?limit=1")).await;
+    assert_eq!(body["articles"][0]["source_meta"]["type"], "pr");
+    let (status, body) = send(&state, get("/api/articles?exclude_types=tabloid")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "exclude_types");
+}
+
+#[tokio::test]
+async fn debate_murmurs_alternate_in_range_lines_and_count_one_use() {
+    let (state, calls) = test_state_replying(
+        r#"[{"persona": "optimist", "text": "自動運転バスが走れば、地方のお年寄りも気軽に買い物へ行けるようになるね。"},
+            {"persona": "skeptic", "text": "でも事故が起きたら誰が責任を取るのか、まだルールが決まってないよね。"},
+            {"persona": "optimist", "text": "短い"},
+            {"persona": "optimist", "text": "実証実験では事故ゼロだったし、技術はもう十分に実用レベルだと思うよ。"},
+            {"persona": "skeptic", "text": "実験は晴れの日ばかりだったんだ。雪道や夜間でも同じように安全に走れるのかどうかは、まだ誰にも分からないよね。"},
+            {"persona": "optimist", "text": "そこは段階的に広げればいい。まずは昼間の決まった路線から始めれば安心だよ。"}]"#,
+    )
+    .await;
+    let murmur = || {
+        let body = serde_json::json!({
+            "title": "自動運転バス、来春から定期運行",
+            "description": "地方路線で導入",
+            "source": "Example",
+            "style": "debate",
+        });
+        Request::post("/api/murmur/generate")
+            .header("content-type", "application/json")
+            .header("x-device-id", "device-1")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let (status, body) = send(&state, murmur()).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["style"], "debate");
+    let lines = body["lines"].as_array().unwrap();
+    assert_eq!(lines.len(), 5, "{body}");
+    for (i, line) in lines.iter().enumerate() {
+        assert_eq!(line["persona"], ["optimist", "skeptic"][i % 2]);
+        let chars = line["text"].as_str().unwrap().chars().count();
+        assert!(crate::claude::DEBATE_LINE_CHARS.contains(&chars), "{line}");
+        assert_eq!(line["audio_base64"], "", "no TTS provider is configured");
+    }
+    // The over-long skeptic line has no sentence break in range, so it's cut
+    assert!(lines[3]["text"].as_str().unwrap().ends_with('…'), "{}", lines[3]);
+    assert_eq!(state.db.get_usage("device-1", "murmur").unwrap(), 1);
+
+    // The second request is served from the cache
+    let (status, again) = send(&state, murmur()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(again, body);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let voices: Vec<_> = crate::routes::DEBATE_VOICES.iter().map(|(_, omni, openai)| (*omni, *openai)).collect();
+    assert_ne!(voices[0].0, voices[1].0);
+    assert_ne!(voices[0].1, voices[1].1);
+}
+
+fn article_chat(article_id: &str, message: &str, conversation_id: Option<&str>) -> Request<Body> {
+    let body = serde_json::json!({"article_id": article_id, "message": message, "conversation_id": conversation_id});
+    Request::post("/api/articles/chat")
+        .header("content-type", "application/json")
+        .header("x-device-id", "device-1")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn article_chats_continue_in_chat_sessions_and_cap_active_conversations() {
+    let (state, calls) = test_state_replying("値上げは来月からです。").await;
+    let article = &seed_articles(&state, &["電気料金、来月から値上げ"])[0];
+
+    let (status, first) = send(&state, article_chat(&article.id, "いつから？", None)).await;
+    assert_eq!(status, StatusCode::OK, "{first}");
+    assert_eq!(first["answer"], "値上げは来月からです。");
+    let conversation_id = first["conversation_id"].as_str().unwrap();
+    let (status, body) = send(&state, article_chat(&article.id, "いくら？", Some(conversation_id))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["conversation_id"], conversation_id);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let transcript = Request::get(format!("/api/articles/chat/{conversation_id}"))
+        .header("x-device-id", "device-1")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send(&state, transcript).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["turns"], 2);
+    let roles: Vec<&str> = body["messages"].as_array().unwrap().iter().map(|m| m["role"].as_str().unwrap()).collect();
+    assert_eq!(roles, ["user", "assistant", "user", "assistant"]);
+
+    // Stored as an owned chat session, which `/api/chat` won't continue
+    let session = state.db.get_chat_session(conversation_id).unwrap().unwrap();
+    assert_eq!(session.owner.as_deref(), Some("device:device-1"));
+    assert_eq!(session.article_id.as_deref(), Some(article.id.as_str()));
+    let chat = Request::post("/api/chat")
+        .header("content-type", "application/json")
+        .header("x-device-id", "device-1")
+        .body(Body::from(serde_json::json!({"messages": [{"role": "user", "content": "続けて"}], "session_id": conversation_id}).to_string()))
+        .unwrap();
+    assert_eq!(send(&state, chat).await.0, StatusCode::NOT_FOUND);
+
+    for _ in 0..2 {
+        assert_eq!(send(&state, article_chat(&article.id, "要点は？", None)).await.0, StatusCode::OK);
+    }
+    let (status, body) = send(&state, article_chat(&article.id, "要点は？", None)).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+    assert_eq!(state.db.get_usage("device-1", "ask").unwrap(), 4);
+}
+
+#[tokio::test]
+async fn murmur_audio_generates_only_on_post_and_once_per_key() {
+    let (state, calls) = test_state_replying("値上げの波、家計にじわり。").await;
+    let article = seed_articles(&state, &["電気料金、来月から値上げ"]).remove(0);
+    let uri = format!("/api/murmur/{}/audio", article.id);
+    let post = || Request::post(&uri).header("x-device-id", "device-1").body(Body::empty()).unwrap();
+
+    let (status, _) = send(&state, get(&uri)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(calls.load(Ordering::SeqCst), 0, "GET never generates");
+
+    // While another request holds the key, a POST neither generates nor charges
+    let ckey = crate::routes::murmur_cache_key(&article.title, &article.source, None);
+    state.murmurs_in_flight.lock().unwrap().insert(ckey.clone());
+    let response = api_routes(Arc::clone(&state)).oneshot(post()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert!(response.headers().contains_key("retry-after"));
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    assert_eq!(state.db.get_usage("device-1", "murmur").unwrap(), 0);
+    state.murmurs_in_flight.lock().unwrap().remove(&ckey);
+
+    // No TTS provider is configured, so the generated murmur has no audio
+    let (status, _) = send(&state, post()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(state.db.get_usage("device-1", "murmur").unwrap(), 1);
+    assert!(state.murmurs_in_flight.lock().unwrap().is_empty(), "the claim is released");
+
+    send(&state, post()).await;
+    assert_eq!(calls.load(Ordering::SeqCst), 1, "served from the cache");
+    let (_, body) = send(&state, get("/api/murmur/playlist")).await;
+    assert_eq!(body["items"][0]["cached"], true);
+    assert_eq!(body["items"][0]["text"], "値上げの波、家計にじわり。");
+}
+
+#[tokio::test]
+async fn timeline_collects_the_story_within_the_window_oldest_first() {
+    let (state, _) = test_state().await;
+    let now = Utc::now();
+    let days_ago = |d: i64| now - chrono::Duration::days(d);
+    let reference = article(0, "日銀が政策金利の引き上げを決定", days_ago(3));
+    let follow_up = article(1, "日銀が政策金利の引き上げを決定、市場は円高に", days_ago(1));
+    let earlier = article(2, "日銀が政策金利の引き上げを検討", days_ago(5));
+    let too_old = article(3, "日銀が政策金利の引き上げを議論", days_ago(20));
+    let unrelated = article(4, "新型スマートフォンを発表", days_ago(2));
+    let seeded = [reference, follow_up, earlier, too_old, unrelated];
+    state.db.batch_insert_articles(&seeded).unwrap();
+
+    let (status, body) = send(&state, get(&format!("/api/articles/{}/timeline?days=7", seeded[0].id))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["article"]["id"], seeded[0].id.as_str());
+    let timeline: Vec<&str> = body["timeline"].as_array().unwrap().iter().map(|a| a["id"].as_str().unwrap()).collect();
+    assert_eq!(timeline, [seeded[2].id.as_str(), seeded[0].id.as_str(), seeded[1].id.as_str()]);
+    assert_eq!(body["span_days"], 4);
+
+    let (status, _) = send(&state, get("/api/articles/missing/timeline")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn account_export_and_delete_cover_the_users_rows() {
+    let (state, _) = test_state().await;
+    let (token, _, user_id, _) = state.db.upsert_user("g-1", "user@example.com", "User", None, Some("device-9")).unwrap();
+    state.db.increment_usage("device-9", "summarize").unwrap();
+    state.db.save_chat_session("s1", Some("device-9"), None, None, "[]").unwrap();
+    let period_end = (Utc::now() + chrono::Duration::days(30)).to_rfc3339();
+    let pro = state.db.create_subscription("pro-token", "cus_1", "sub_1", &period_end).unwrap();
+    let signed_in = |request: axum::http::request::Builder, pro: Option<&str>| {
+        let request = request.header("authorization", format!("Bearer {token}"));
+        match pro {
+            Some(pro) => request.header("x-pro-token", pro),
+            None => request,
+        }
+        .body(Body::empty())
+        .unwrap()
+    };
+
+    let (status, _) = send(&state, Request::get("/api/account/export").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, body) = send(&state, signed_in(Request::get("/api/account/export"), Some(&pro))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["user"]["id"], user_id.as_str());
+    assert_eq!(body["user"]["email"], "user@example.com");
+    assert_eq!(body["usage"][0]["feature"], "summarize");
+    assert_eq!(body["chat_sessions"][0]["session_id"], "s1");
+    assert_eq!(body["subscriptions"][0]["stripe_subscription_id"], "sub_1");
+
+    // An active subscription can't be canceled without Stripe, so the account stays
+    let (status, body) = send(&state, signed_in(Request::delete("/api/account"), Some(&pro))).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["code"], "partial_failure");
+    assert!(body["details"]["failed"]["stripe"].is_string());
+    assert!(state.db.get_user_by_auth_token(&token).unwrap().is_some());
+
+    let (status, body) = send(&state, signed_in(Request::delete("/api/account"), None)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["status"], "deleted");
+    assert_eq!(body["deleted"]["users"], 1);
+    assert_eq!(state.db.get_usage("device-9", "summarize").unwrap(), 0);
+    assert!(state.db.get_chat_session("s1").unwrap().is_none());
+    let (status, _) = send(&state, signed_in(Request::get("/api/account/export"), None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn only_rejected_changes_can_be_revised_into_a_new_version() {
+    use news_core::changes::{AdminAction, ChangeRequest, ChangeStatus};
+    let (state, calls) = test_state_replying(
+        r#"{"confidence": 0.9, "interpretation": "グループ化のしきい値を0.5にします",
+            "actions": [{"type": "set_grouping_threshold", "threshold": 0.5}]}"#,
+    )
+    .await;
+    for (id, status) in [("rejected", ChangeStatus::Rejected), ("pending", ChangeStatus::Preview)] {
+        state
+            .db
+            .create_change(&ChangeRequest {
+                change_id: id.into(),
+                status,
+                command_text: "グループ化を強めて".into(),
+                interpretation: "グループ化のしきい値を0.9にします".into(),
+                actions: vec![AdminAction::SetGroupingThreshold { threshold: 0.9 }],
+                preview_config: None,
+                preview_diff: None,
+                validation: None,
+                parent_change_id: None,
+                version: 1,
+                created_at: Utc::now().to_rfc3339(),
+            })
+            .unwrap();
+    }
+    let revise = |id: &str, command: &str, secret: &str| {
+        Request::post(format!("/api/admin/changes/{id}/revise"))
+            .header("content-type", "application/json")
+            .header("x-admin-secret", secret)
+            .body(Body::from(serde_json::json!({"command": command}).to_string()))
+            .unwrap()
+    };
+    let secret = state.admin_secret.clone();
+
+    assert_eq!(send(&state, revise("rejected", "少しだけ", "wrong")).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&state, revise("missing", "少しだけ", &secret)).await.0, StatusCode::NOT_FOUND);
+    let (status, body) = send(&state, revise("pending", "少しだけ", &secret)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "status");
+    let (status, body) = send(&state, revise("rejected", "  ", &secret)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "command");
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    let (status, body) = send(&state, revise("rejected", "少しだけ緩めて", &secret)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["type"], "preview");
+    assert_eq!(body["parent_change_id"], "rejected");
+    assert_eq!(body["version"], 2);
+    let revision = state.db.get_change(body["change_id"].as_str().unwrap()).unwrap().unwrap();
+    assert_eq!(revision.status, ChangeStatus::Preview);
+    assert_eq!(revision.command_text, "少しだけ緩めて");
+    assert_eq!(revision.parent_change_id.as_deref(), Some("rejected"));
+}
@@ -0,0 +1,199 @@
+//! `GET /api/articles/:id/export`: an article with its AI summary, keywords and
+//! enrichments as a Markdown note (YAML front matter) or a JSON bundle. The Markdown
+//! layout lives in `markdown` only; a new format is a variant plus a renderer.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "markdown" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Json => "application/json; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportEnrichment {
+    pub agent_type: String,
+    pub content_type: String,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportBundle {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub canonical_url: Option<String>,
+    pub source: String,
+    pub category: String,
+    pub published_at: String,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub ai_summary: Option<String>,
+    pub ai_keywords: Vec<String>,
+    pub enrichments: Vec<ExportEnrichment>,
+    /// Extracted body text; Pro only, like the AI summary, keywords and enrichments.
+    pub content: Option<String>,
+}
+
+pub fn render(bundle: &ExportBundle, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Markdown => markdown(bundle),
+        ExportFormat::Json => serde_json::to_string_pretty(bundle).unwrap_or_default(),
+    }
+}
+
+fn markdown(b: &ExportBundle) -> String {
+    let mut out = String::from("---\n");
+    front_matter(&mut out, "title", &b.title);
+    front_matter(&mut out, "source", &b.source);
+    front_matter(&mut out, "category", &b.category);
+    front_matter(&mut out, "date", &b.published_at);
+    front_matter(&mut out, "url", b.canonical_url.as_deref().unwrap_or(&b.url));
+    if !b.ai_keywords.is_empty() {
+        out.push_str("tags:\n");
+        for k in &b.ai_keywords {
+            out.push_str(&format!("  - {}\n", yaml_string(k)));
+        }
+    }
+    out.push_str("---\n\n");
+
+    out.push_str(&format!("# [{}]({})\n\n", escape_markdown(&b.title), link_destination(&b.url)));
+    out.push_str(&format!("*{}* · {}\n\n", escape_markdown(&b.source), b.published_at));
+    if let Some(image) = &b.image_url {
+        out.push_str(&format!("![]({})\n\n", link_destination(image)));
+    }
+    if let Some(description) = b.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        out.push_str(&format!("> {}\n\n", description.trim().replace('\n', "\n> ")));
+    }
+    if let Some(summary) = &b.ai_summary {
+        out.push_str(&format!("## AI要約\n\n{}\n\n", summary.trim()));
+    }
+    if !b.ai_keywords.is_empty() {
+        let keywords: Vec<String> = b.ai_keywords.iter().map(|k| escape_markdown(k)).collect();
+        out.push_str(&format!("**キーワード:** {}\n\n", keywords.join(", ")));
+    }
+    for e in &b.enrichments {
+        out.push_str(&format!("## {} ({})\n\n", escape_markdown(&e.agent_type), e.content_type));
+        out.push_str(&format!("```json\n{}\n```\n\n", serde_json::to_string_pretty(&e.data).unwrap_or_default()));
+    }
+    if let Some(content) = b.content.as_deref().filter(|c| !c.trim().is_empty()) {
+        out.push_str(&format!("## 本文\n\n{}\n", content.trim()));
+    }
+    out
+}
+
+fn front_matter(out: &mut String, key: &str, value: &str) {
+    out.push_str(&format!("{key}: {}\n", yaml_string(value)));
+}
+
+/// A double-quoted YAML scalar; JSON string escaping is valid YAML.
+fn yaml_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// A URL as a Markdown link destination: brackets, parentheses and whitespace, which
+/// would end or break the link, are percent-encoded.
+pub fn link_destination(url: &str) -> String {
+    let mut out = String::with_capacity(url.len());
+    for c in url.chars() {
+        match c {
+            '(' | ')' | '[' | ']' | '<' | '>' | ' ' | '\t' | '\n' | '\r' => out.push_str(&format!("%{:02X}", c as u32)),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Backslash-escape characters Markdown would read as formatting.
+pub fn escape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '{' | '}' | '[' | ']' | '(' | ')' | '#' | '+' | '-' | '.' | '!' | '|' | '<' | '>'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle() -> ExportBundle {
+        ExportBundle {
+            id: "a1".into(),
+            title: "AI *breakthrough* [update]".into(),
+            url: "https://example.com/a?utm_source=rss".into(),
+            canonical_url: Some("https://example.com/a".into()),
+            source: "Example \"News\"".into(),
+            category: "tech".into(),
+            published_at: "2026-01-02T03:04:05+00:00".into(),
+            description: Some("概要です。".into()),
+            image_url: None,
+            ai_summary: Some("要約です。".into()),
+            ai_keywords: vec!["AI".into(), "研究".into()],
+            enrichments: Vec::new(),
+            content: None,
+        }
+    }
+
+    #[test]
+    fn markdown_special_characters_are_escaped() {
+        assert_eq!(escape_markdown("a*b_[c](d)#1."), r"a\*b\_\[c\]\(d\)\#1\.");
+        assert_eq!(escape_markdown("日本語"), "日本語");
+        assert_eq!(
+            link_destination("https://en.wikipedia.org/wiki/Rust_(language) [x]"),
+            "https://en.wikipedia.org/wiki/Rust_%28language%29%20%5Bx%5D"
+        );
+    }
+
+    #[test]
+    fn markdown_note_has_front_matter_and_sections() {
+        let md = render(&bundle(), ExportFormat::Markdown);
+        assert!(md.starts_with("---\ntitle: \"AI *breakthrough* [update]\"\nsource: \"Example \\\"News\\\"\"\n"));
+        assert!(md.contains("url: \"https://example.com/a\"\ntags:\n  - \"AI\"\n  - \"研究\"\n---\n"));
+        assert!(md.contains(r"# [AI \*breakthrough\* \[update\]](https://example.com/a?utm_source=rss)"));
+        assert!(md.contains("## AI要約\n\n要約です。"));
+        assert!(!md.contains("## 本文"));
+
+        let json: serde_json::Value = serde_json::from_str(&render(&bundle(), ExportFormat::Json)).unwrap();
+        assert_eq!(json["ai_keywords"][1], "研究");
+        assert!(json["content"].is_null());
+    }
+
+    #[test]
+    fn formats_are_strict() {
+        assert_eq!(ExportFormat::parse("markdown"), Some(ExportFormat::Markdown));
+        assert_eq!(ExportFormat::parse("json"), Some(ExportFormat::Json));
+        assert_eq!(ExportFormat::parse("org"), None);
+        assert_eq!(ExportFormat::parse("Markdown"), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! `POST /api/admin/import/articles`: historical articles from another aggregator.
+//! Entries are validated one by one; bad ones are reported by index and the rest go
+//! in. IDs are derived like the fetcher's (`dedup::article_id_from_url`), so URLs that
+//! were already fetched or imported count as duplicates.
+
+use chrono::{DateTime, Utc};
+use news_core::models::{Article, Category};
+use serde::{Deserialize, Serialize};
+
+/// Articles per `Db::batch_insert_articles` call.
+pub const IMPORT_CHUNK: usize = 100;
+
+#[derive(Deserialize)]
+pub struct ImportArticlesRequest {
+    pub articles: Vec<ArticleImport>,
+}
+
+/// Missing fields deserialize as empty and are reported per entry by `validate`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ArticleImport {
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub source: String,
+    pub category: String,
+    /// RFC 3339
+    pub published_at: String,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ImportError {
+    pub index: usize,
+    pub error: String,
+}
+
+/// Valid entries as articles, and why the others were rejected. `lookup` resolves a
+/// category id (built-in or from the categories table).
+pub fn prepare(
+    entries: &[ArticleImport],
+    fetched_at: DateTime<Utc>,
+    lookup: impl Fn(&str) -> Option<Category>,
+) -> (Vec<Article>, Vec<ImportError>) {
+    let mut articles = Vec::with_capacity(entries.len());
+    let mut errors = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        match validate(entry, fetched_at, &lookup) {
+            Ok(article) => articles.push(article),
+            Err(error) => errors.push(ImportError { index, error }),
+        }
+    }
+    (articles, errors)
+}
+
+pub fn validate(
+    entry: &ArticleImport,
+    fetched_at: DateTime<Utc>,
+    lookup: impl Fn(&str) -> Option<Category>,
+) -> Result<Article, String> {
+    let url = entry.url.trim();
+    if !is_http_url(url) {
+        return Err(format!("url is not an http(s) URL: {url:?}"));
+    }
+    let title = entry.title.trim();
+    if title.is_empty() {
+        return Err("title is required".into());
+    }
+    let source = entry.source.trim();
+    if source.is_empty() {
+        return Err("source is required".into());
+    }
+    let category = lookup(&entry.category).ok_or_else(|| format!("unknown category: {:?}", entry.category))?;
+    let published_at = DateTime::parse_from_rfc3339(&entry.published_at)
+        .map_err(|_| format!("published_at is not RFC 3339: {:?}", entry.published_at))?
+        .with_timezone(&Utc);
+    let image_url = match entry.image_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        Some(u) if !is_http_url(u) => return Err(format!("image_url is not an http(s) URL: {u:?}")),
+        other => other.map(str::to_string),
+    };
+
+    Ok(Article {
+        id: news_core::dedup::article_id_from_url(url),
+        category,
+        title: title.to_string(),
+        url: url.to_string(),
+        description: entry.description.clone().filter(|d| !d.trim().is_empty()),
+        image_url,
+        source: source.to_string(),
+        published_at,
+        fetched_at,
+        group_id: None,
+        group_count: None,
+        canonical_url: None,
+        tags: Vec::new(),
+        word_count: None,
+        reading_minutes: None,
+        original_url: None,
+    })
+}
+
+fn is_http_url(s: &str) -> bool {
+    reqwest::Url::parse(s).is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> ArticleImport {
+        ArticleImport {
+            url: "https://example.com/2024/01/a?utm_source=old".into(),
+            title: " 旧サイトの記事 ".into(),
+            description: Some("概要".into()),
+            source: "Example".into(),
+            category: "tech".into(),
+            published_at: "2024-01-02T09:00:00+09:00".into(),
+            image_url: None,
+        }
+    }
+
+    #[test]
+    fn valid_entry_becomes_an_article_with_the_fetcher_id() {
+        let article = validate(&entry(), Utc::now(), Category::from_str).unwrap();
+        assert_eq!(article.id, news_core::dedup::article_id_from_url("https://example.com/2024/01/a"));
+        assert_eq!(article.title, "旧サイトの記事");
+        assert_eq!(article.category, Category::Tech);
+        assert_eq!(article.published_at.to_rfc3339(), "2024-01-02T00:00:00+00:00");
+    }
+
+    #[test]
+    fn each_bad_entry_is_reported_by_index() {
+        let entries = vec![
+            entry(),
+            ArticleImport { url: "ftp://example.com/x".into(), ..entry() },
+            ArticleImport { category: "gossip".into(), ..entry() },
+            ArticleImport { published_at: "2024-01-02".into(), ..entry() },
+            ArticleImport { title: "  ".into(), ..entry() },
+            ArticleImport { image_url: Some("not a url".into()), ..entry() },
+            ArticleImport::default(),
+        ];
+        let (articles, errors) = prepare(&entries, Utc::now(), Category::from_str);
+        assert_eq!(articles.len(), 1);
+        assert_eq!(errors.iter().map(|e| e.index).collect::<Vec<_>>(), [1, 2, 3, 4, 5, 6]);
+        assert!(errors[1].error.contains("gossip"));
+    }
+}
@@ -0,0 +1,237 @@
+//! MP3 frame scanning for waveform data, without decoding audio. A frame's loudness
+//! is estimated from how many bits it spends on audio data: Layer III's
+//! `part2_3_length` from the side info, which the bit reservoir lets vary even in CBR
+//! files (silence costs next to nothing), or the frame size for Layers I and II.
+
+use crate::tts_chunk::id3v2_len;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Frame {
+    /// Audio data bits (Layer III) or payload bytes (Layers I/II).
+    energy: f32,
+    duration_us: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Version {
+    Mpeg1,
+    Mpeg2,
+    Mpeg25,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Header {
+    version: Version,
+    layer: u8,
+    protected: bool,
+    mono: bool,
+    sample_rate: u32,
+    samples: u32,
+    len: usize,
+}
+
+/// kbps by [version is MPEG-1][layer - 1][index - 1]
+const BITRATES: [[[u32; 14]; 3]; 2] = [
+    [
+        [32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256],
+        [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+        [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+    ],
+    [
+        [32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448],
+        [32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384],
+        [32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320],
+    ],
+];
+
+fn parse_header(b: &[u8]) -> Option<Header> {
+    if b.len() < 4 || b[0] != 0xFF || b[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+    let version = match (b[1] >> 3) & 3 {
+        0 => Version::Mpeg25,
+        2 => Version::Mpeg2,
+        3 => Version::Mpeg1,
+        _ => return None,
+    };
+    let layer = match (b[1] >> 1) & 3 {
+        1 => 3,
+        2 => 2,
+        3 => 1,
+        _ => return None,
+    };
+    let bitrate_index = (b[2] >> 4) as usize;
+    // 0 is "free format", whose frame length can't be computed from the header
+    if bitrate_index == 0 || bitrate_index == 15 {
+        return None;
+    }
+    let bitrate = BITRATES[(version == Version::Mpeg1) as usize][layer as usize - 1][bitrate_index - 1] * 1000;
+    let base_rate = match (b[2] >> 2) & 3 {
+        0 => 44100,
+        1 => 48000,
+        2 => 32000,
+        _ => return None,
+    };
+    let sample_rate = match version {
+        Version::Mpeg1 => base_rate,
+        Version::Mpeg2 => base_rate / 2,
+        Version::Mpeg25 => base_rate / 4,
+    };
+    let padding = ((b[2] >> 1) & 1) as usize;
+    let samples = match (layer, version) {
+        (1, _) => 384,
+        (3, Version::Mpeg2 | Version::Mpeg25) => 576,
+        _ => 1152,
+    };
+    let len = if layer == 1 {
+        (12 * bitrate / sample_rate) as usize * 4 + padding * 4
+    } else {
+        (samples / 8 * bitrate / sample_rate) as usize + padding
+    };
+    Some(Header {
+        version,
+        layer,
+        protected: b[1] & 1 == 0,
+        mono: b[3] >> 6 == 3,
+        sample_rate,
+        samples,
+        len,
+    })
+}
+
+/// Sum of `part2_3_length` over the frame's granules and channels.
+fn layer3_data_bits(h: &Header, frame: &[u8]) -> Option<u32> {
+    let side_info = &frame[if h.protected { 6 } else { 4 }..];
+    let channels = if h.mono { 1 } else { 2 };
+    // Bits before the first granule, granules per frame and bits per granule/channel
+    let (skip, granules, per_channel) = match (h.version, h.mono) {
+        (Version::Mpeg1, true) => (9 + 5 + 4, 2, 59),
+        (Version::Mpeg1, false) => (9 + 3 + 8, 2, 59),
+        (_, true) => (8 + 1, 1, 63),
+        (_, false) => (8 + 2, 1, 63),
+    };
+    let mut total = 0;
+    for i in 0..granules * channels {
+        total += read_bits(side_info, skip + i * per_channel, 12)?;
+    }
+    Some(total)
+}
+
+fn read_bits(data: &[u8], start: usize, count: usize) -> Option<u32> {
+    let mut value = 0;
+    for bit in start..start + count {
+        let byte = *data.get(bit / 8)?;
+        value = (value << 1) | ((byte >> (7 - bit % 8)) & 1) as u32;
+    }
+    Some(value)
+}
+
+/// Frames in order. A header only counts if the next frame (or the end of the data)
+/// follows where it says it ends, so stray 0xFF bytes in tags aren't taken for frames.
+fn frames(bytes: &[u8]) -> Vec<Frame> {
+    let mut out = Vec::new();
+    let mut pos = id3v2_len(bytes).min(bytes.len());
+    while pos + 4 <= bytes.len() {
+        let Some(h) = parse_header(&bytes[pos..]) else {
+            pos += 1;
+            continue;
+        };
+        let end = pos + h.len;
+        let next_ok = end == bytes.len() || bytes.get(end..).is_some_and(|rest| parse_header(rest).is_some());
+        if h.len <= 4 || end > bytes.len() || !next_ok {
+            pos += 1;
+            continue;
+        }
+        let frame = &bytes[pos..end];
+        let energy = match h.layer {
+            3 => layer3_data_bits(&h, frame).unwrap_or(0) as f32,
+            _ => (h.len - 4) as f32,
+        };
+        out.push(Frame {
+            energy,
+            duration_us: h.samples as u64 * 1_000_000 / h.sample_rate as u64,
+        });
+        pos = end;
+    }
+    out
+}
+
+/// Up to `target_samples` peaks in 0.0–1.0: the RMS of per-frame energy over equal
+/// runs of frames, relative to the loudest run. Fewer frames than `target_samples`
+/// give one peak per frame.
+pub fn mp3_extract_peaks(bytes: &[u8], target_samples: usize) -> Vec<f32> {
+    let frames = frames(bytes);
+    if frames.is_empty() || target_samples == 0 {
+        return Vec::new();
+    }
+    let buckets = target_samples.min(frames.len());
+    let rms: Vec<f32> = (0..buckets)
+        .map(|i| {
+            let run = &frames[i * frames.len() / buckets..(i + 1) * frames.len() / buckets];
+            (run.iter().map(|f| f.energy * f.energy).sum::<f32>() / run.len() as f32).sqrt()
+        })
+        .collect();
+    let max = rms.iter().cloned().fold(0.0, f32::max);
+    if max == 0.0 {
+        return vec![0.0; buckets];
+    }
+    rms.iter().map(|v| v / max).collect()
+}
+
+pub fn mp3_duration_ms(bytes: &[u8]) -> u64 {
+    frames(bytes).iter().map(|f| f.duration_us).sum::<u64>() / 1000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// MPEG-1 Layer III, 128 kbps, 44.1 kHz, mono: 417-byte frames of 1152 samples,
+    /// both granules spending `data_bits`.
+    fn frame(data_bits: u32) -> Vec<u8> {
+        let mut f = vec![0u8; 417];
+        f[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0xC0]);
+        for granule in 0..2 {
+            let start = 4 * 8 + 18 + granule * 59;
+            for i in 0..12 {
+                if data_bits >> (11 - i) & 1 == 1 {
+                    let bit = start + i;
+                    f[bit / 8] |= 0x80 >> (bit % 8);
+                }
+            }
+        }
+        f
+    }
+
+    #[test]
+    fn header_gives_frame_length_and_duration() {
+        let h = parse_header(&frame(0)).unwrap();
+        assert_eq!((h.layer, h.len, h.samples, h.sample_rate), (3, 417, 1152, 44100));
+        assert!(h.mono && !h.protected);
+        assert!(parse_header(&[0xFF, 0x00, 0x90, 0xC0]).is_none());
+        assert!(parse_header(&[0xFF, 0xFB, 0xF0, 0xC0]).is_none());
+    }
+
+    #[test]
+    fn quiet_frames_have_low_peaks() {
+        let mut mp3 = b"ID3\x04\x00\x00\x00\x00\x00\x04\xFF\xFB\x00\x00".to_vec();
+        for bits in [0, 0, 800, 1600, 1600, 400] {
+            mp3.extend(frame(bits));
+        }
+        assert_eq!(mp3_extract_peaks(&mp3, 6), [0.0, 0.0, 0.5, 1.0, 1.0, 0.25]);
+        // 6 × 1152 samples at 44.1 kHz
+        assert_eq!(mp3_duration_ms(&mp3), 156);
+
+        let halves = mp3_extract_peaks(&mp3, 2);
+        assert_eq!(halves.len(), 2);
+        assert!(halves[0] < halves[1] && halves[1] == 1.0);
+        assert_eq!(mp3_extract_peaks(&mp3, 200).len(), 6);
+    }
+
+    #[test]
+    fn garbage_has_no_peaks() {
+        assert!(mp3_extract_peaks(b"not an mp3 at all", 200).is_empty());
+        assert_eq!(mp3_extract_peaks(&frame(0), 10), [0.0]);
+        assert_eq!(mp3_duration_ms(&[]), 0);
+    }
+}
@@ -0,0 +1,114 @@
+//! Request body size limits. A few routes get their own limit, everything else
+//! `DEFAULT_LIMIT`; oversized requests get the usual JSON error with a 413 instead of
+//! axum's plain-text rejection. Runs with axum's `DefaultBodyLimit` disabled, so
+//! this is the only limit.
+
+use crate::error::ApiError;
+use axum::body::Body;
+use axum::extract::Request;
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+const DEFAULT_LIMIT: usize = 1024 * 1024;
+
+/// Per-path overrides of `DEFAULT_LIMIT`.
+const ROUTE_LIMITS: &[(&str, usize)] = &[
+    // JSON with base64 reference audio (~2 MB of audio)
+    ("/api/tts/clone", 3 * 1024 * 1024),
+    // Bulk article import, JSON or a multipart file upload
+    ("/api/admin/import/articles", 32 * 1024 * 1024),
+    // sendBeacon vitals and error reports
+    ("/api/telemetry", 64 * 1024),
+];
+
+pub fn limit_for(path: &str) -> usize {
+    ROUTE_LIMITS
+        .iter()
+        .find(|(route, _)| *route == path)
+        .map_or(DEFAULT_LIMIT, |(_, limit)| *limit)
+}
+
+/// Middleware: reject bodies over the route's limit, up front when Content-Length
+/// says so and otherwise while the handler reads them.
+pub async fn enforce(req: Request, next: Next) -> Response {
+    let max_bytes = limit_for(req.uri().path());
+    let declared = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared.is_some_and(|len| len > max_bytes as u64) {
+        return ApiError::BodyTooLarge { max_bytes }.into_response();
+    }
+
+    let req = req.map(|body| Body::new(http_body_util::Limited::new(body, max_bytes)));
+    let res = next.run(req).await;
+    // Extractors report a body that hit the limit as a plain-text 413
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if res.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return ApiError::BodyTooLarge { max_bytes }.into_response();
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::DefaultBodyLimit;
+    use axum::routing::post;
+    use axum::{middleware, Json, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/api/telemetry", post(|body: axum::body::Bytes| async move { body.len().to_string() }))
+            .route(
+                "/api/tts/clone",
+                post(|Json(v): Json<serde_json::Value>| async move { v["ref_audio"].as_str().unwrap_or("").len().to_string() }),
+            )
+            .layer(DefaultBodyLimit::disable())
+            .layer(middleware::from_fn(enforce))
+    }
+
+    async fn post_to(path: &str, body: Vec<u8>, content_length: bool) -> (StatusCode, serde_json::Value) {
+        let mut req = Request::post(path).header(header::CONTENT_TYPE, "application/json");
+        if content_length {
+            req = req.header(header::CONTENT_LENGTH, body.len());
+        }
+        let res = app().oneshot(req.body(Body::from(body)).unwrap()).await.unwrap();
+        let status = res.status();
+        let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_get_a_json_413() {
+        let big = vec![b'x'; 100 * 1024];
+        for content_length in [true, false] {
+            let (status, body) = post_to("/api/telemetry", big.clone(), content_length).await;
+            assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+            assert_eq!(body["code"], "payload_too_large");
+            assert_eq!(body["details"]["max_bytes"], 64 * 1024);
+        }
+        let (status, _) = post_to("/api/telemetry", vec![b'x'; 1024], true).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn clone_route_allows_more_than_the_default() {
+        let audio = "A".repeat(2 * 1024 * 1024);
+        let json = serde_json::json!({ "ref_audio": audio }).to_string().into_bytes();
+        let (status, _) = post_to("/api/tts/clone", json, false).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let json = serde_json::json!({ "ref_audio": "A".repeat(4 * 1024 * 1024) }).to_string().into_bytes();
+        let (status, body) = post_to("/api/tts/clone", json, false).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["details"]["max_bytes"], 3 * 1024 * 1024);
+        assert_eq!(limit_for("/api/articles"), DEFAULT_LIMIT);
+    }
+}
@@ -0,0 +1,130 @@
+//! `GET /api/bootstrap`: what the PWA needs on startup (categories, the first page of
+//! articles, client config and the caller's usage) in one response, so a flaky
+//! connection can't leave it half hydrated. Sections load independently; one that
+//! fails is sent as `{"error": "..."}` in place of its data.
+
+use news_core::models::{Article, SourceMeta};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize)]
+pub struct Bootstrap {
+    pub categories: Section<Vec<CategoryEntry>>,
+    pub articles: Section<ArticlesSection>,
+    pub config: ClientConfig,
+    /// Only for callers sending a device id or an auth token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Section<UsageSummary>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Section<T> {
+    Loaded(T),
+    Failed { error: String },
+}
+
+impl<T> From<Result<T, String>> for Section<T> {
+    fn from(result: Result<T, String>) -> Self {
+        match result {
+            Ok(value) => Section::Loaded(value),
+            Err(error) => Section::Failed { error },
+        }
+    }
+}
+
+/// A visible category, as listed by `GET /api/categories`.
+#[derive(Debug, Serialize)]
+pub struct CategoryEntry {
+    pub id: String,
+    /// English label, or the Japanese one when there is none.
+    pub label: String,
+    pub label_ja: String,
+    pub sort_order: i32,
+}
+
+/// First page of `GET /api/articles` for `category`, or the source-balanced page of
+/// all categories when it is None.
+#[derive(Debug, Serialize)]
+pub struct ArticlesSection {
+    pub category: Option<String>,
+    pub articles: Vec<BootstrapArticle>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BootstrapArticle {
+    #[serde(flatten)]
+    pub article: Article,
+    pub source_meta: SourceMeta,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientConfig {
+    pub google_client_id: String,
+    pub features: ClientFeatures,
+}
+
+/// Features the server can offer with the keys it has configured.
+#[derive(Debug, Serialize)]
+pub struct ClientFeatures {
+    /// Claude-backed features: summaries, questions, chat, comparison, translation.
+    pub ai: bool,
+    pub sentiment: bool,
+    pub tts: bool,
+    pub pro_checkout: bool,
+}
+
+/// The caller's usage today, as returned by `GET /api/usage`.
+#[derive(Debug, Serialize)]
+pub struct UsageSummary {
+    /// "pro" | "authenticated" | "free" | "anonymous"
+    pub tier: &'static str,
+    pub usage: BTreeMap<String, i64>,
+    pub limits: BTreeMap<String, i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trial: Option<TrialUsage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrialUsage {
+    pub limit: i64,
+    pub used: i64,
+    pub remaining: i64,
+}
+
+/// Weak ETag over the newest article's timestamp and whatever else the bundle for
+/// this caller depends on.
+pub fn etag(newest_article: &str, parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(newest_article.as_bytes());
+    for part in parts {
+        hasher.update(b"|");
+        hasher.update(part.as_bytes());
+    }
+    format!("W/\"{}\"", &hex::encode(hasher.finalize())[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_sections_carry_an_error_in_place_of_data() {
+        let loaded: Section<Vec<i32>> = Ok(vec![1]).into();
+        let failed: Section<Vec<i32>> = Err("database is locked".to_string()).into();
+        assert_eq!(serde_json::to_value(loaded).unwrap(), serde_json::json!([1]));
+        assert_eq!(serde_json::to_value(failed).unwrap(), serde_json::json!({"error": "database is locked"}));
+    }
+
+    #[test]
+    fn etag_changes_with_every_part() {
+        let base = etag("2026-01-01T00:00:00+00:00", &["tech", "{}"]);
+        assert!(base.starts_with("W/\""));
+        assert_eq!(base, etag("2026-01-01T00:00:00+00:00", &["tech", "{}"]));
+        assert_ne!(base, etag("2026-01-01T00:01:00+00:00", &["tech", "{}"]));
+        assert_ne!(base, etag("2026-01-01T00:00:00+00:00", &["tech", "{\"a\":1}"]));
+        assert_ne!(base, etag("2026-01-01T00:00:00+00:00", &["tec", "h{}"]));
+    }
+}
@@ -0,0 +1,98 @@
+//! Dry-run checks for admin-chat change requests, made when the change is created.
+//! AddFeed URLs are fetched, since Claude readily invents plausible feed URLs;
+//! RemoveCategory counts the articles and feeds it would orphan without a
+//! `reassign_to`; ReorderCategories checks its ids. `apply_change` refuses a change
+//! with failed checks unless forced.
+
+use crate::routes::{test_feed, AppState};
+use news_core::changes::{check_category_order, ActionCheck, ActionValidation, AdminAction};
+use tracing::warn;
+
+pub async fn validate_actions(state: &AppState, actions: &[AdminAction]) -> Vec<ActionValidation> {
+    let probes = actions.iter().enumerate().filter_map(|(i, action)| match action {
+        AdminAction::AddFeed { url, .. } => Some(async move { ActionValidation::new(i, probe_feed(state, url).await) }),
+        _ => None,
+    });
+    let mut validation = futures::future::join_all(probes).await;
+
+    let category_ids: Option<Vec<String>> =
+        state.db.get_categories().ok().map(|cats| cats.into_iter().map(|(id, ..)| id).collect());
+    for (i, action) in actions.iter().enumerate() {
+        let check = match action {
+            AdminAction::RemoveCategory { id, reassign_to } => match state.db.category_usage(id) {
+                Ok((articles, feeds)) => ActionCheck::CategoryUsage { articles, feeds, reassign_to: reassign_to.clone() },
+                Err(e) => {
+                    warn!(error = %e, category = %id, "Category usage check failed");
+                    continue;
+                }
+            },
+            AdminAction::ReorderCategories { order } => match &category_ids {
+                Some(existing) => check_category_order(order, existing),
+                None => continue,
+            },
+            _ => continue,
+        };
+        validation.push(ActionValidation::new(i, check));
+    }
+    validation.sort_by_key(|v| v.action);
+    validation
+}
+
+async fn probe_feed(state: &AppState, url: &str) -> ActionCheck {
+    match test_feed(&state.http_client, url).await {
+        Ok(preview) => ActionCheck::FeedProbe {
+            found: true,
+            item_count: preview["item_count"].as_u64().map(|n| n as usize),
+            error: None,
+        },
+        Err(e) => ActionCheck::FeedProbe { found: false, item_count: None, error: Some(e) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use axum::Router;
+
+    const RSS: &str = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Real</title>
+        <item><title>One</title><link>https://example.com/1</link></item>
+        <item><title>Two</title><link>https://example.com/2</link></item>
+        </channel></rss>"#;
+
+    async fn feed_server() -> String {
+        let app = Router::new().route("/rss.xml", get(|| async { RSS }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    fn add_feed(url: String) -> AdminAction {
+        AdminAction::AddFeed { url, source: "Example".into(), category: "tech".into() }
+    }
+
+    #[tokio::test]
+    async fn hallucinated_feed_url_is_flagged() {
+        let base = feed_server().await;
+        let state = AppState::for_tests("http://127.0.0.1:9");
+        state.db.seed_default_categories().unwrap();
+        let actions = vec![
+            add_feed(format!("{base}/rss.xml")),
+            add_feed(format!("{base}/news/all-articles.rss")),
+            AdminAction::EnableFeed { feed_id: "nhk".into() },
+            AdminAction::RemoveCategory { id: "tech".into(), reassign_to: None },
+            AdminAction::ReorderCategories { order: vec!["tech".into(), "weather".into()] },
+        ];
+
+        let validation = validate_actions(&state, &actions).await;
+        assert_eq!(validation.iter().map(|v| v.action).collect::<Vec<_>>(), [0, 1, 3, 4]);
+        assert!(validation[0].passed);
+        assert!(matches!(validation[0].check, ActionCheck::FeedProbe { found: true, item_count: Some(2), .. }));
+        assert!(!validation[1].passed);
+        assert!(matches!(&validation[1].check, ActionCheck::FeedProbe { found: false, error: Some(_), .. }));
+        assert!(validation[2].passed);
+        assert_eq!(validation[2].check, ActionCheck::CategoryUsage { articles: 0, feeds: 0, reassign_to: None });
+        assert_eq!(validation[3].check, ActionCheck::CategoryOrder { unknown: vec!["weather".into()] });
+    }
+}
@@ -1,19 +1,318 @@
 use news_core::changes::AdminAction;
-use news_core::config::ServiceConfig;
+use news_core::config::{AbVariant, ServiceConfig};
+use news_core::models::Article;
+use news_core::text::truncate_sentence;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
+const API_VERSION: &str = "2023-06-01";
+/// Consecutive failures after which a model is skipped for `COOLDOWN`.
+const FAILURE_THRESHOLD: u32 = 3;
+const COOLDOWN: Duration = Duration::from_secs(120);
+
+/// Which model a call wants. Quality calls fall back to Fast when Sonnet is overloaded
+/// or down; Fast calls have nowhere to fall back to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelTier {
+    Quality,
+    Fast,
+}
+
+impl ModelTier {
+    pub fn model(self) -> &'static str {
+        match self {
+            ModelTier::Quality => "claude-sonnet-4-5-20250929",
+            ModelTier::Fast => "claude-haiku-4-5-20251001",
+        }
+    }
+
+    fn fallback_chain(self) -> &'static [ModelTier] {
+        match self {
+            ModelTier::Quality => &[ModelTier::Quality, ModelTier::Fast],
+            ModelTier::Fast => &[ModelTier::Fast],
+        }
+    }
+}
+
+/// A parsed response plus the model that actually produced it.
+#[derive(Debug, Clone)]
+pub struct Generated<T> {
+    pub value: T,
+    pub model_used: &'static str,
+}
+
+impl<T> Generated<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Generated<U> {
+        Generated {
+            value: f(self.value),
+            model_used: self.model_used,
+        }
+    }
+
+    pub fn try_map<U>(self, f: impl FnOnce(T) -> Result<U, String>) -> Result<Generated<U>, String> {
+        Ok(Generated {
+            value: f(self.value)?,
+            model_used: self.model_used,
+        })
+    }
+}
+
 #[derive(Debug, Serialize)]
-struct ClaudeRequest {
-    model: String,
+struct MessagesRequest<'a> {
+    model: &'a str,
     max_tokens: u32,
-    messages: Vec<ClaudeMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<&'a str>,
+    messages: &'a [ChatMessage],
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ClaudeMessage {
-    role: String,
-    content: String,
+#[derive(Debug, Default)]
+struct ModelHealth {
+    consecutive_failures: u32,
+    cooldown_until: Option<Instant>,
+}
+
+enum CallError {
+    /// 429 / 529: worth retrying, then trying the next model.
+    Overloaded(String),
+    /// Network error or other 5xx: try the next model.
+    Unavailable(String),
+    /// Any other 4xx or an unreadable body; another model won't help.
+    Rejected(String),
+}
+
+/// Anthropic Messages API client shared through `AppState`. Tracks per-model health so
+/// a struggling model is skipped for a while instead of being hit on every request.
+pub struct ClaudeClient {
+    http: reqwest::Client,
+    api_key: String,
+    base_url: String,
+    retry_delay: Duration,
+    health: Mutex<HashMap<ModelTier, ModelHealth>>,
+}
+
+impl ClaudeClient {
+    pub fn new(http: reqwest::Client, api_key: String) -> Self {
+        Self {
+            http,
+            api_key,
+            base_url: "https://api.anthropic.com".to_string(),
+            retry_delay: Duration::from_millis(500),
+            health: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_endpoint(base_url: String, retry_delay: Duration) -> Self {
+        Self {
+            base_url,
+            retry_delay,
+            ..Self::new(reqwest::Client::new(), "test-key".to_string())
+        }
+    }
+
+    /// Send one Messages request, retrying once on 429/529 and then moving down the
+    /// tier's fallback chain. Models in cooldown are skipped unless they are the last
+    /// option. Returns the first text block.
+    pub async fn complete(
+        &self,
+        tier: ModelTier,
+        label: &str,
+        max_tokens: u32,
+        system: Option<&str>,
+        messages: &[ChatMessage],
+    ) -> Result<Generated<String>, String> {
+        let chain = tier.fallback_chain();
+        let mut last_error = String::new();
+        for (i, candidate) in chain.iter().enumerate() {
+            let is_last = i + 1 == chain.len();
+            if !is_last && self.cooling_down(*candidate) {
+                info!(model = candidate.model(), label, "Skipping model in cooldown");
+                continue;
+            }
+            let request = MessagesRequest {
+                model: candidate.model(),
+                max_tokens,
+                system,
+                messages,
+            };
+            let mut result = self.send(&request).await;
+            if matches!(result, Err(CallError::Overloaded(_))) {
+                tokio::time::sleep(self.retry_delay).await;
+                result = self.send(&request).await;
+            }
+            match result {
+                Ok(text) => {
+                    self.record(*candidate, true);
+                    if *candidate != tier {
+                        warn!(requested = tier.model(), used = candidate.model(), label, "Claude call downgraded");
+                    }
+                    return Ok(Generated {
+                        value: text,
+                        model_used: candidate.model(),
+                    });
+                }
+                Err(CallError::Rejected(e)) => {
+                    warn!(model = candidate.model(), error = %e, "Claude API error ({})", label);
+                    return Err(e);
+                }
+                Err(CallError::Overloaded(e)) | Err(CallError::Unavailable(e)) => {
+                    warn!(model = candidate.model(), error = %e, "Claude API error ({})", label);
+                    self.record(*candidate, false);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    async fn send(&self, request: &MessagesRequest<'_>) -> Result<String, CallError> {
+        let response = self
+            .http
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", API_VERSION)
+            .header("content-type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| CallError::Unavailable(format!("Claude API request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = format!("Claude API error: {} - {}", status, body);
+            return Err(match status.as_u16() {
+                429 | 529 => CallError::Overloaded(message),
+                s if s >= 500 => CallError::Unavailable(message),
+                _ => CallError::Rejected(message),
+            });
+        }
+
+        let claude_response: ClaudeResponse = response
+            .json()
+            .await
+            .map_err(|e| CallError::Rejected(format!("Failed to parse Claude response: {}", e)))?;
+        claude_response
+            .content
+            .into_iter()
+            .find_map(|b| b.text)
+            .ok_or_else(|| CallError::Rejected("Empty response from Claude".to_string()))
+    }
+
+    fn cooling_down(&self, tier: ModelTier) -> bool {
+        self.health
+            .lock()
+            .ok()
+            .and_then(|h| h.get(&tier).and_then(|m| m.cooldown_until))
+            .is_some_and(|until| until > Instant::now())
+    }
+
+    fn record(&self, tier: ModelTier, ok: bool) {
+        let Ok(mut health) = self.health.lock() else { return };
+        let entry = health.entry(tier).or_default();
+        if ok {
+            *entry = ModelHealth::default();
+            return;
+        }
+        entry.consecutive_failures += 1;
+        if entry.consecutive_failures >= FAILURE_THRESHOLD {
+            warn!(model = tier.model(), failures = entry.consecutive_failures, "Claude model cooling down");
+            entry.cooldown_until = Some(Instant::now() + COOLDOWN);
+        }
+    }
+}
+
+/// Parse a JSON answer, tolerating a surrounding code fence.
+fn parse_json<T: DeserializeOwned>(text: &str, what: &str) -> Result<T, String> {
+    let clean = text.trim().trim_start_matches("```json").trim_start_matches("```").trim_end_matches("```").trim();
+    serde_json::from_str(clean).map_err(|e| format!("Failed to parse {}: {} — raw: {}", what, e, text))
+}
+
+/// An article cited in generated text as `[marker]`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SourceRef {
+    pub marker: usize,
+    pub article_id: Option<String>,
+    pub title: String,
+    pub url: String,
+}
+
+/// Longest marker number accepted; `[2024]` and the like are left alone.
+const MAX_MARKER_DIGITS: usize = 3;
+
+/// Keep the `[n]` citation markers with `1 <= n <= count` and drop the rest (ones the
+/// model made up). Returns the cleaned text and the kept markers in order of first use.
+pub fn resolve_citations(text: &str, count: usize) -> (String, Vec<usize>) {
+    let mut out = String::with_capacity(text.len());
+    let mut cited = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+        if (1..=MAX_MARKER_DIGITS).contains(&digits) && after.as_bytes().get(digits) == Some(&b']') {
+            let marker: usize = after[..digits].parse().unwrap_or(0);
+            if (1..=count).contains(&marker) {
+                out.push_str(&rest[open..open + digits + 2]);
+                if !cited.contains(&marker) {
+                    cited.push(marker);
+                }
+            }
+            rest = &after[digits + 1..];
+        } else {
+            out.push('[');
+            rest = after;
+        }
+    }
+    out.push_str(rest);
+    (out, cited)
+}
+
+/// `text` without any `[n]` markers, for reading conversion and TTS.
+pub fn strip_citations(text: &str) -> String {
+    resolve_citations(text, 0).0
+}
+
+/// One turn of a `/api/chat` conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// "user" | "assistant"
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: String) -> Self {
+        Self {
+            role: "user".to_string(),
+            content,
+        }
+    }
+}
+
+/// Most recent turns that fit in `budget_tokens`, starting with a user turn. Japanese
+/// runs close to one token per character, so characters serve as the estimate. The last
+/// turn is always kept.
+pub fn trim_to_token_budget(messages: &[ChatMessage], budget_tokens: usize) -> &[ChatMessage] {
+    let mut start = messages.len();
+    let mut used = 0;
+    while start > 0 {
+        let cost = messages[start - 1].content.chars().count();
+        if used + cost > budget_tokens && start < messages.len() {
+            break;
+        }
+        used += cost;
+        start -= 1;
+    }
+    while start < messages.len().saturating_sub(1) && messages[start].role != "user" {
+        start += 1;
+    }
+    &messages[start..]
 }
 
 #[derive(Debug, Deserialize)]
@@ -41,11 +340,11 @@ const SYSTEM_PROMPT: &str = r#"あなたはHyperNewsの管理AIです。ユー
 - `{"type":"remove_feed","feed_id":"..."}`
 - `{"type":"enable_feed","feed_id":"..."}`
 - `{"type":"disable_feed","feed_id":"..."}`
-- `{"type":"toggle_feature","feature":"grouping|ogp_enrichment","enabled":true|false}`
+- `{"type":"toggle_feature","feature":"grouping|ogp_enrichment|enrichment_research|enrichment_image|enrichment_video|sentiment","enabled":true|false}`
 - `{"type":"set_grouping_threshold","threshold":0.3}`
 - `{"type":"add_category","id":"lifestyle","label_ja":"ライフスタイル"}`
-- `{"type":"remove_category","id":"sports"}`
-- `{"type":"rename_category","id":"tech","label_ja":"IT・テック"}`
+- `{"type":"remove_category","id":"sports","reassign_to":"general"}`（reassign_toは記事・フィードの移動先。カテゴリに記事があるときは必須）
+- `{"type":"rename_category","id":"tech","label_ja":"IT・テック","new_id":"it"}`（new_idはIDも変える場合のみ。記事・フィードも移動し、旧IDのURLも引き続き使える）
 - `{"type":"reorder_categories","order":["tech","general","business","entertainment","sports","science"]}`
 
 ## ルール
@@ -53,8 +352,9 @@ const SYSTEM_PROMPT: &str = r#"あなたはHyperNewsの管理AIです。ユー
 - 「NHK以外を増やして」→ 著名なRSSフィードを提案（朝日新聞デジタル、毎日新聞、ITmedia、GIGAZINE等）
 - 「同じようなニュースをまとめて」→ grouping機能を有効化
 - 「写真を入れて」「画像を表示して」→ ogp_enrichment機能を有効化
+- 「AI画像生成を止めて」→ enrichment_image機能を無効化（リサーチ・動画も同様に enrichment_research / enrichment_video）
 - 「カテゴリを追加して」→ add_categoryで新カテゴリ追加（idは英語小文字、label_jaは日本語名）
-- 「スポーツを消して」→ remove_categoryでカテゴリ削除
+- 「スポーツを消して」→ remove_categoryでカテゴリ削除（移動先の指定がなければ reassign_to は general）
 - 「テクノロジーをIT・テックに変更して」→ rename_categoryで名前変更
 - 「テクノロジーを一番前にして」→ reorder_categoriesで並び替え
 - 不明確なコマンドにはconfidence 0.5以下で説明のみ返す
@@ -63,84 +363,106 @@ const SYSTEM_PROMPT: &str = r#"あなたはHyperNewsの管理AIです。ユー
 
 {"confidence":0.9,"interpretation":"NHK以外の日本語ニュースフィードを追加します","actions":[{"type":"add_feed","url":"https://rss.itmedia.co.jp/rss/2.0/itmedia_all.xml","source":"ITmedia","category":"tech"}]}"#;
 
+/// Appended to prompts over numbered articles so generated text cites them.
+const CITATION_RULE: &str = "## 出典\n\
+    - ニュースに触れた文の末尾に、一覧の番号を [1] のように付ける（複数なら [1][3]）\n\
+    - 一覧にない番号は使わない";
+
 pub async fn summarize_articles(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     articles: &[(String, String)],
     target_chars: usize,
-) -> Result<String, String> {
+    variant: Option<&AbVariant>,
+) -> Result<Generated<String>, String> {
     let article_list = articles
         .iter()
         .enumerate()
-        .map(|(i, (title, source))| format!("{}. [{}] {}", i + 1, source, title))
+        .map(|(i, (title, source))| format!("[{}] {}（{}）", i + 1, title, source))
         .collect::<Vec<_>>()
         .join("\n");
 
-    let prompt = format!(
-        "あなたはプロのニュースキャスターです。以下のニュース一覧を、約{}文字の日本語で自然にまとめて読み上げ原稿を作成してください。\n\n\
-        ルール:\n\
-        - ニュースキャスターが読み上げるような、聞き取りやすく自然な口語体で書く\n\
-        - 重要なニュースを優先し、関連するニュースはまとめて紹介する\n\
-        - 各トピックについて、想定される様々な立場からの見方や意見も織り交ぜて多角的に紹介する\n\
-        - 「専門家の間では〜という見方もあります」「一方で〜という意見も」のように多様な視点を提示する\n\
-        - 冒頭に簡単な挨拶、最後に締めの一言を入れる\n\
-        - 原稿のテキストのみ出力（JSONやマークダウン不要）\n\n\
-        ## ニュース一覧\n{}",
-        target_chars, article_list
-    );
-
-    let request = ClaudeRequest {
-        model: "claude-sonnet-4-5-20250929".into(),
-        max_tokens: (target_chars as u32) * 2,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: prompt,
-        }],
+    let instructions = match variant.and_then(AbVariant::prompt) {
+        Some(p) => p.replace("{target_chars}", &target_chars.to_string()),
+        None => format!(
+            "あなたはプロのニュースキャスターです。以下のニュース一覧を、約{}文字の日本語で自然にまとめて読み上げ原稿を作成してください。\n\n\
+            ルール:\n\
+            - ニュースキャスターが読み上げるような、聞き取りやすく自然な口語体で書く\n\
+            - 重要なニュースを優先し、関連するニュースはまとめて紹介する\n\
+            - 各トピックについて、想定される様々な立場からの見方や意見も織り交ぜて多角的に紹介する\n\
+            - 「専門家の間では〜という見方もあります」「一方で〜という意見も」のように多様な視点を提示する\n\
+            - 冒頭に簡単な挨拶、最後に締めの一言を入れる\n\
+            - 原稿のテキストのみ出力（JSONやマークダウン不要）",
+            target_chars
+        ),
     };
+    let prompt = format!("{}\n\n{}\n\n## ニュース一覧\n{}", instructions, CITATION_RULE, article_list);
 
-    info!(articles = articles.len(), target_chars, "Generating news summary");
+    let variant_name = variant.map(|v| v.name.as_str());
+    info!(articles = articles.len(), target_chars, variant = variant_name, "Generating news summary");
 
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
+    let generated = claude
+        .complete(tier, "summarize", (target_chars as u32) * 2, None, &[ChatMessage::user(prompt)])
+        .await?;
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        warn!(status = %status, body = %body, "Claude API error (summarize)");
-        return Err(format!("Claude API error: {} - {}", status, body));
-    }
+    info!(chars = generated.value.len(), model = generated.model_used, variant = variant_name, "News summary generated");
+    Ok(generated.map(|t| t.trim().to_string()))
+}
 
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
+/// カテゴリごとに1文の見出しを1回のAPI呼び出しでまとめて生成する。
+/// `articles_by_category` は カテゴリID → (タイトル, ソース) の一覧。
+pub async fn generate_category_headlines(
+    claude: &ClaudeClient,
+    tier: ModelTier,
+    articles_by_category: &HashMap<String, Vec<(String, String)>>,
+) -> Result<Generated<HashMap<String, String>>, String> {
+    let mut categories: Vec<&String> = articles_by_category.keys().collect();
+    categories.sort();
+
+    let sections = categories
+        .iter()
+        .map(|cat| {
+            let list = articles_by_category[*cat]
+                .iter()
+                .map(|(title, source)| format!("- [{}] {}", source, title))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("### {}\n{}", cat, list)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
 
-    let text = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .ok_or_else(|| "Empty response from Claude".to_string())?;
+    let prompt = format!(
+        "以下はカテゴリ別の本日のニュース一覧です。各カテゴリの動向を1文（60文字以内）の日本語の見出しにまとめてください。\n\n\
+        ルール:\n\
+        - カテゴリ内で最も重要な話題を中心にまとめる\n\
+        - 新聞の見出しのように簡潔に\n\
+        - JSONオブジェクトのみ出力: {{\"カテゴリID\": \"見出し\", ...}}\n\
+        - キーは入力のカテゴリIDをそのまま使う\n\n\
+        ## ニュース一覧\n{}",
+        sections
+    );
+
+    info!(categories = categories.len(), "Generating category headlines");
 
-    info!(chars = text.len(), "News summary generated");
-    Ok(text.trim().to_string())
+    let generated = claude
+        .complete(tier, "headlines", 1024, None, &[ChatMessage::user(prompt)])
+        .await?;
+
+    generated.try_map(|text| parse_json(&text, "headlines"))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_questions(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     title: &str,
     description: &str,
     source: &str,
     article_content: &str,
     custom_prompt: Option<&str>,
-) -> Result<Vec<String>, String> {
+    variant: Option<&AbVariant>,
+) -> Result<Generated<Vec<String>>, String> {
     let content_section = if article_content.is_empty() {
         String::new()
     } else {
@@ -150,66 +472,33 @@ pub async fn generate_questions(
         Some(p) if !p.is_empty() => format!("\n\n## 追加指示\n{}", p),
         _ => String::new(),
     };
-    let prompt = format!(
+    let instructions = variant.and_then(AbVariant::prompt).unwrap_or(
         "以下のニュース記事について、読者が知りたいと思う質問を4つ生成してください。\n\n\
         ルール:\n\
         - 記事本文の情報を踏まえた具体的な質問を生成する\n\
         - 記事の内容を深掘りする興味深い質問\n\
         - 背景や影響、今後の展望に関する質問を含める\n\
         - 短く簡潔な質問文（20文字以内が理想）\n\
-        - JSON配列のみ出力: [\"質問1\", \"質問2\", \"質問3\", \"質問4\"]\n\n\
-        ## 記事\nタイトル: {}\nソース: {}\n概要: {}{}{}",
-        title, source, description, content_section, custom_section
+        - JSON配列のみ出力: [\"質問1\", \"質問2\", \"質問3\", \"質問4\"]",
+    );
+    let prompt = format!(
+        "{}\n\n## 記事\nタイトル: {}\nソース: {}\n概要: {}{}{}",
+        instructions, title, source, description, content_section, custom_section
     );
 
-    let request = ClaudeRequest {
-        model: "claude-sonnet-4-5-20250929".into(),
-        max_tokens: 512,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: prompt,
-        }],
-    };
-
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        warn!(status = %status, body = %body, "Claude API error (questions)");
-        return Err(format!("Claude API error: {} - {}", status, body));
-    }
-
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
-
-    let text = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .ok_or_else(|| "Empty response from Claude".to_string())?;
+    info!(title = %title, variant = variant.map(|v| v.name.as_str()), "Generating questions");
 
-    let clean = text.trim().trim_start_matches("```json").trim_start_matches("```").trim_end_matches("```").trim();
-    let questions: Vec<String> = serde_json::from_str(clean)
-        .map_err(|e| format!("Failed to parse questions: {} — raw: {}", e, text))?;
+    let generated = claude
+        .complete(tier, "questions", 512, None, &[ChatMessage::user(prompt)])
+        .await?;
 
-    Ok(questions)
+    generated.try_map(|text| parse_json(&text, "questions"))
 }
 
 /// Transform a potentially negative question into a positive, constructive one.
 pub async fn transform_question_to_positive(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     question: &str,
 ) -> Result<String, String> {
     // Quick check: if question is already positive, return as-is
@@ -232,42 +521,17 @@ pub async fn transform_question_to_positive(
         question
     );
 
-    let request = ClaudeRequest {
-        model: "claude-haiku-4-5-20251001".into(),
-        max_tokens: 256,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: prompt,
-        }],
-    };
-
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
+    let transformed = match claude
+        .complete(tier, "positive", 256, None, &[ChatMessage::user(prompt)])
         .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        // If transformation fails, return original question
-        warn!("Question transformation failed, using original");
-        return Ok(question.to_string());
-    }
-
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|_| "Failed to parse response".to_string())?;
-
-    let transformed = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .map(|t| t.trim().to_string())
-        .unwrap_or_else(|| question.to_string());
+    {
+        Ok(generated) => generated.value.trim().to_string(),
+        Err(_) => {
+            // If transformation fails, return original question
+            warn!("Question transformation failed, using original");
+            return Ok(question.to_string());
+        }
+    };
 
     info!(
         original = %question,
@@ -278,16 +542,51 @@ pub async fn transform_question_to_positive(
     Ok(transformed)
 }
 
+/// Continue a news Q&A conversation. `messages` is the full history, ending with a user turn.
+pub async fn chat(
+    claude: &ClaudeClient,
+    tier: ModelTier,
+    messages: &[ChatMessage],
+    context_article: Option<&Article>,
+) -> Result<Generated<String>, String> {
+    let context = match context_article {
+        Some(a) => format!(
+            "\n\n## 話題の記事\nタイトル: {}\nソース: {}\n公開日時: {}\n概要: {}\nURL: {}",
+            a.title,
+            a.source,
+            a.published_at.format("%Y-%m-%d %H:%M UTC"),
+            a.description.as_deref().unwrap_or(""),
+            a.url
+        ),
+        None => String::new(),
+    };
+    let system = format!(
+        "あなたはニュースアプリ「news.xyz」のアシスタントです。ユーザーとの会話を通じてニュースの理解を助けてください。\n\n\
+        ルール:\n\
+        - 日本語で、簡潔かつ具体的に回答する（目安300文字以内）\n\
+        - 記事の内容に基づき、事実と推測を区別する\n\
+        - 不明なことは不明と答える\n\
+        - 会話の流れ（これまでのやり取り）を踏まえて回答する{}",
+        context
+    );
+
+    let generated = claude
+        .complete(tier, "chat", 1024, Some(&system), messages)
+        .await?;
+
+    Ok(generated.map(|t| t.trim().to_string()))
+}
+
 pub async fn answer_question(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     title: &str,
     description: &str,
     source: &str,
     question: &str,
     article_content: &str,
     custom_prompt: Option<&str>,
-) -> Result<String, String> {
+) -> Result<Generated<String>, String> {
     let content_section = if article_content.is_empty() {
         String::new()
     } else {
@@ -309,52 +608,19 @@ pub async fn answer_question(
         title, source, description, content_section, question, custom_section
     );
 
-    let request = ClaudeRequest {
-        model: "claude-sonnet-4-5-20250929".into(),
-        max_tokens: 1536,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: prompt,
-        }],
-    };
+    let generated = claude
+        .complete(tier, "answer", 1536, None, &[ChatMessage::user(prompt)])
+        .await?;
 
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        warn!(status = %status, body = %body, "Claude API error (answer)");
-        return Err(format!("Claude API error: {} - {}", status, body));
-    }
-
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
-
-    let text = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .ok_or_else(|| "Empty response from Claude".to_string())?;
-
-    Ok(text.trim().to_string())
+    Ok(generated.map(|t| t.trim().to_string()))
 }
 
 pub async fn convert_to_reading(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     text: &str,
     engine: &str,
-) -> Result<String, String> {
+) -> Result<Generated<String>, String> {
     let is_qwen = matches!(engine, "qwen-tts" | "qwen-omni" | "cosyvoice");
 
     let prompt = if is_qwen {
@@ -396,47 +662,14 @@ pub async fn convert_to_reading(
         )
     };
 
-    let request = ClaudeRequest {
-        model: "claude-haiku-4-5-20251001".into(),
-        max_tokens: (text.len() as u32) * 2 + 256,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: prompt,
-        }],
-    };
-
     info!(chars = text.len(), "Converting text for TTS preprocessing");
 
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        warn!(status = %status, body = %body, "Claude API error (to-reading)");
-        return Err(format!("Claude API error: {} - {}", status, body));
-    }
-
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
-
-    let result = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .ok_or_else(|| "Empty response from Claude".to_string())?;
+    let generated = claude
+        .complete(tier, "reading", (text.len() as u32) * 2 + 256, None, &[ChatMessage::user(prompt)])
+        .await?;
 
-    info!(chars = result.len(), "Reading conversion complete");
-    Ok(result.trim().to_string())
+    info!(chars = generated.value.len(), "Reading conversion complete");
+    Ok(generated.map(|t| t.trim().to_string()))
 }
 
 // --- Podcast Dialogue ---
@@ -448,17 +681,17 @@ pub struct DialogueLine {
 }
 
 pub async fn generate_dialogue_script(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     title: &str,
     description: &str,
     source: &str,
     article_content: &str,
-) -> Result<Vec<DialogueLine>, String> {
+) -> Result<Generated<Vec<DialogueLine>>, String> {
     let content_section = if article_content.is_empty() {
         String::new()
     } else {
-        format!("\n\n## 記事本文\n{}", &article_content[..article_content.len().min(3000)])
+        format!("\n\n## 記事本文\n{}", truncate_sentence(article_content, 3000))
     };
 
     let prompt = format!(
@@ -473,116 +706,140 @@ pub async fn generate_dialogue_script(
         - 冒頭でニュースの要点を紹介、中盤で深掘り、最後に展望やまとめ\n\
         - 自然な口語体（「〜ですね」「〜なんですよ」など）\n\
         - JSON配列のみ出力: [{{\"speaker\":\"host\",\"text\":\"...\"}},{{\"speaker\":\"analyst\",\"text\":\"...\"}},...]\n\n\
-        ## 記事\nタイトル: {}\nソース: {}\n概要: {}{}",
-        title, source, description, content_section
+        {}\n\n\
+        ## 記事\n[1] タイトル: {}\nソース: {}\n概要: {}{}",
+        CITATION_RULE, title, source, description, content_section
     );
 
-    let request = ClaudeRequest {
-        model: "claude-sonnet-4-5-20250929".into(),
-        max_tokens: 2048,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: prompt,
-        }],
-    };
-
     info!(title = %title, "Generating dialogue script");
 
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        warn!(status = %status, body = %body, "Claude API error (dialogue)");
-        return Err(format!("Claude API error: {} - {}", status, body));
-    }
-
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
-
-    let text = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .ok_or_else(|| "Empty response from Claude".to_string())?;
-
-    let clean = text.trim().trim_start_matches("```json").trim_start_matches("```").trim_end_matches("```").trim();
-    let dialogue: Vec<DialogueLine> = serde_json::from_str(clean)
-        .map_err(|e| format!("Failed to parse dialogue: {} — raw: {}", e, text))?;
+    let generated = claude
+        .complete(tier, "dialogue", 2048, None, &[ChatMessage::user(prompt)])
+        .await?;
 
-    info!(lines = dialogue.len(), "Dialogue script generated");
+    let dialogue = generated.try_map(|text| parse_json::<Vec<DialogueLine>>(&text, "dialogue"))?;
+    info!(lines = dialogue.value.len(), "Dialogue script generated");
     Ok(dialogue)
 }
 
 pub async fn generate_murmur(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     title: &str,
     description: &str,
     source: &str,
-) -> Result<String, String> {
-    let prompt = format!(
+    variant: Option<&AbVariant>,
+) -> Result<Generated<String>, String> {
+    let instructions = variant.and_then(AbVariant::prompt).unwrap_or(
         "以下のニュース記事について、カジュアルな独り言を80〜120文字、2〜3文でつぶやいてください。\n\n\
         ルール:\n\
         - 「へぇ〜」「マジか」「なるほど〜」「〜だよね」「すごいな〜」など口語体で\n\
         - ニュースキャスター調は禁止。友達に話すような砕けたトーン\n\
         - 自分の感想や驚き、ちょっとした疑問を自然に\n\
-        - テキストのみ出力（JSON不要、引用符不要）\n\n\
-        ## 記事\nタイトル: {}\nソース: {}\n概要: {}",
-        title, source, description
+        - テキストのみ出力（JSON不要、引用符不要）",
+    );
+    let prompt = format!(
+        "{}\n\n## 記事\nタイトル: {}\nソース: {}\n概要: {}",
+        instructions, title, source, description
     );
 
-    let request = ClaudeRequest {
-        model: "claude-haiku-4-5-20251001".into(),
-        max_tokens: 256,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: prompt,
-        }],
-    };
+    info!(title = %title, variant = variant.map(|v| v.name.as_str()), "Generating murmur");
 
-    info!(title = %title, "Generating murmur");
+    let generated = claude
+        .complete(tier, "murmur", 256, None, &[ChatMessage::user(prompt)])
+        .await?;
 
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
+    info!(chars = generated.value.len(), "Murmur generated");
+    Ok(generated.map(|t| t.trim().to_string()))
+}
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        warn!(status = %status, body = %body, "Claude API error (murmur)");
-        return Err(format!("Claude API error: {} - {}", status, body));
-    }
+/// One line of a debate murmur; `persona` is "optimist" or "skeptic".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MurmurLine {
+    pub persona: String,
+    pub text: String,
+}
 
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
+pub const DEBATE_PERSONAS: [&str; 2] = ["optimist", "skeptic"];
+const DEBATE_MIN_LINES: usize = 4;
+const DEBATE_MAX_LINES: usize = 6;
+/// Characters per debate line.
+pub const DEBATE_LINE_CHARS: std::ops::RangeInclusive<usize> = 30..=50;
+
+/// 楽観派と懐疑派が交互に話す4〜6行のミニ討論
+pub async fn generate_murmur_debate(
+    claude: &ClaudeClient,
+    tier: ModelTier,
+    title: &str,
+    description: &str,
+    source: &str,
+) -> Result<Generated<Vec<MurmurLine>>, String> {
+    let prompt = format!(
+        "以下のニュース記事について、2人が掛け合うミニ討論を4〜6行で書いてください。\n\n\
+        ルール:\n\
+        - optimist（楽観派）とskeptic（懐疑派）が交互に話す。最初はoptimist\n\
+        - 1行30〜50文字、友達同士のような口語体\n\
+        - 楽観派は可能性や良い面、懐疑派はリスクや疑問点を指摘する\n\
+        - 以下のJSON配列のみ出力:\n\
+        [{{\"persona\": \"optimist\", \"text\": \"...\"}}, {{\"persona\": \"skeptic\", \"text\": \"...\"}}]\n\n\
+        ## 記事\nタイトル: {}\nソース: {}\n概要: {}",
+        title, source, description
+    );
 
-    let text = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .ok_or_else(|| "Empty response from Claude".to_string())?;
+    info!(title = %title, "Generating murmur debate");
 
-    info!(chars = text.len(), "Murmur generated");
-    Ok(text.trim().to_string())
+    let generated = claude
+        .complete(tier, "murmur_debate", 768, None, &[ChatMessage::user(prompt)])
+        .await?;
+    generated.try_map(|text| normalize_debate(parse_json(&text, "murmur debate")?))
+}
+
+/// Keep lines with a known persona and 30–50 characters (longer ones are cut, see
+/// `fit_debate_line`), drop a line that repeats the previous speaker so the personas
+/// alternate, and cap the exchange at six lines. Fewer than four lines is an error.
+fn normalize_debate(lines: Vec<MurmurLine>) -> Result<Vec<MurmurLine>, String> {
+    let mut debate: Vec<MurmurLine> = Vec::new();
+    for line in lines {
+        let persona = line.persona.trim().to_lowercase();
+        let Some(text) = fit_debate_line(line.text.trim()) else {
+            continue;
+        };
+        if !DEBATE_PERSONAS.contains(&persona.as_str()) {
+            continue;
+        }
+        if debate.last().is_some_and(|prev| prev.persona == persona) {
+            continue;
+        }
+        debate.push(MurmurLine { persona, text });
+        if debate.len() == DEBATE_MAX_LINES {
+            break;
+        }
+    }
+    if debate.len() < DEBATE_MIN_LINES {
+        return Err(format!("Murmur debate has {} usable lines", debate.len()));
+    }
+    Ok(debate)
+}
+
+/// `text` if it's within `DEBATE_LINE_CHARS`. A longer line ends after its last
+/// sentence break that keeps it in range, or is cut to 49 characters and "…"; a
+/// shorter one is dropped.
+fn fit_debate_line(text: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() < *DEBATE_LINE_CHARS.start() {
+        return None;
+    }
+    let max = *DEBATE_LINE_CHARS.end();
+    if chars.len() <= max {
+        return Some(text.to_string());
+    }
+    let sentence_end = (*DEBATE_LINE_CHARS.start()..=max)
+        .rev()
+        .find(|&n| matches!(chars[n - 1], '。' | '！' | '？' | '!' | '?'));
+    Some(match sentence_end {
+        Some(n) => chars[..n].iter().collect(),
+        None => chars[..max - 1].iter().chain(['…'].iter()).collect(),
+    })
 }
 
 // --- Smart News Classification & Action Plans ---
@@ -603,13 +860,13 @@ pub struct ActionPlan {
 
 /// 記事を「タイムマシン」「砂金掘り」「不満の可視化」に自動分類
 pub async fn classify_article(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     title: &str,
     description: &str,
     source: &str,
     category: &str,
-) -> Result<ArticleClassification, String> {
+) -> Result<Generated<ArticleClassification>, String> {
     let prompt = format!(
         "以下のニュース記事を分類してください。\n\n\
         ## 分類カテゴリ\n\
@@ -626,63 +883,338 @@ pub async fn classify_article(
         title, source, category, description
     );
 
-    let request = ClaudeRequest {
-        model: "claude-haiku-4-5-20251001".into(),
-        max_tokens: 256,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: prompt,
-        }],
+    let generated = claude
+        .complete(tier, "classify", 256, None, &[ChatMessage::user(prompt)])
+        .await?;
+
+    generated.try_map(|text| parse_json(&text, "classification"))
+}
+
+/// Keywords (lowercase) for `classify_article_local`, per classification in the
+/// order ties are broken.
+const LOCAL_CLASSIFY_KEYWORDS: &[(&str, &[&str])] = &[
+    (
+        "timemachine",
+        &["product hunt", "producthunt", "product-hunt", "reddit", "arxiv", "hacker news", "substack", "y combinator"],
+    ),
+    ("goldmining", &["pdf", "patent", "特許", "官報", "白書", "sec filing", "10-k", "閣議決定", "学術論文"]),
+    ("frustration", &["不満", "問題", "困った", "苦情", "炎上", "低評価", "知恵袋", "complaint"]),
+];
+
+/// Keyword-matching stand-in for `classify_article` when Claude is unavailable: the
+/// classification with the most distinct keywords in the article's text, source and
+/// category wins, `general` if none match. Matched keywords become the tags.
+pub fn classify_article_local(title: &str, description: &str, source: &str, category: &str) -> ArticleClassification {
+    let haystack = format!("{title}\n{description}\n{source}\n{category}").to_lowercase();
+    // Reversed, since `max_by_key` keeps the last of equal maxima
+    let best = LOCAL_CLASSIFY_KEYWORDS
+        .iter()
+        .rev()
+        .map(|(label, words)| (*label, words.iter().copied().filter(|w| haystack.contains(w)).collect::<Vec<_>>()))
+        .filter(|(_, hits)| !hits.is_empty())
+        .max_by_key(|(_, hits)| hits.len());
+    match best {
+        Some((label, hits)) => ArticleClassification {
+            category: label.to_string(),
+            reasoning: format!("キーワード一致: {}", hits.join(", ")),
+            tags: hits.iter().take(3).map(|w| w.to_string()).collect(),
+        },
+        None => ArticleClassification {
+            category: "general".into(),
+            reasoning: "該当するキーワードなし".into(),
+            tags: Vec::new(),
+        },
+    }
+}
+
+// --- Entity Extraction ---
+
+/// Most entities kept per article by `extract_entities`.
+const MAX_ENTITIES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedEntity {
+    pub text: String,
+    /// "organization" | "person" | "place" | "event" | "product"
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// 記事に登場する企業・人物・地名・出来事などの固有名詞を抽出
+pub async fn extract_entities(
+    claude: &ClaudeClient,
+    tier: ModelTier,
+    title: &str,
+    description: &str,
+) -> Result<Generated<Vec<NamedEntity>>, String> {
+    let prompt = format!(
+        "以下のニュース記事に登場する固有名詞を抽出してください。\n\n\
+        ## ルール\n\
+        - typeは organization / person / place / event / product のいずれか\n\
+        - textは正式名称で表記を統一する（例: 「アップル」「Apple Inc.」→「Apple」）\n\
+        - 記事の主題に関わるものを重要な順に最大{}個\n\
+        - JSON出力のみ: [{{\"text\":\"...\",\"type\":\"...\"}}]\n\n\
+        ## 記事\nタイトル: {}\n概要: {}",
+        MAX_ENTITIES, title, description
+    );
+
+    let generated = claude
+        .complete(tier, "entities", 512, None, &[ChatMessage::user(prompt)])
+        .await?;
+
+    generated.try_map(|text| {
+        let mut entities: Vec<NamedEntity> = parse_json(&text, "entities")?;
+        entities.truncate(MAX_ENTITIES);
+        Ok(entities)
+    })
+}
+
+// --- Sentiment Analysis ---
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmotionScore {
+    pub label: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SentimentAnalysis {
+    /// "positive" | "negative" | "neutral"
+    pub overall: String,
+    pub confidence: f32,
+    #[serde(default)]
+    pub emotions: Vec<EmotionScore>,
+    /// 0.0 = factual, 1.0 = opinion
+    #[serde(default)]
+    pub subjectivity: f32,
+    #[serde(default)]
+    pub bias_indicators: Vec<String>,
+}
+
+/// 記事のトーンを感情・主観性・偏りの観点で詳しく分析
+pub async fn analyze_sentiment(
+    claude: &ClaudeClient,
+    tier: ModelTier,
+    title: &str,
+    description: &str,
+    content: &str,
+) -> Result<Generated<SentimentAnalysis>, String> {
+    let content_section = if content.is_empty() {
+        String::new()
+    } else {
+        format!("\n\n## 記事本文\n{}", content.chars().take(3000).collect::<String>())
+    };
+    let prompt = format!(
+        "以下のニュース記事の論調を分析してください。\n\n\
+        ## ルール\n\
+        - overall: 記事全体の論調（positive / negative / neutral のいずれか）\n\
+        - confidence: overallの確信度（0.0〜1.0）\n\
+        - emotions: 記事から読み取れる感情を最大5個、強い順に（labelは日本語、scoreは0.0〜1.0）\n\
+        - subjectivity: 主観性（0.0=事実のみ、1.0=意見中心）\n\
+        - bias_indicators: 偏りを示す表現や書き方を最大3個（なければ空配列）\n\
+        - JSON出力のみ: {{\"overall\":\"...\",\"confidence\":0.8,\"emotions\":[{{\"label\":\"...\",\"score\":0.7}}],\"subjectivity\":0.3,\"bias_indicators\":[...]}}\n\n\
+        ## 記事\nタイトル: {}\n概要: {}{}",
+        title, description, content_section
+    );
+
+    let generated = claude
+        .complete(tier, "sentiment", 512, None, &[ChatMessage::user(prompt)])
+        .await?;
+
+    generated.try_map(|text| parse_json(&text, "sentiment"))
+}
+
+// --- Translation ---
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArticleTranslation {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// ISO 639-1 code of the original text, as detected by the model.
+    #[serde(default)]
+    pub detected_source_lang: String,
+}
+
+/// Translate an article's title, description and (if given) body into `target_lang`.
+pub async fn translate_article(
+    claude: &ClaudeClient,
+    tier: ModelTier,
+    title: &str,
+    description: &str,
+    content: Option<&str>,
+    target_lang: &str,
+) -> Result<Generated<ArticleTranslation>, String> {
+    let content_section = match content {
+        Some(c) if !c.is_empty() => format!("\n\n## 本文\n{}", c),
+        _ => String::new(),
     };
+    let content_field = if content_section.is_empty() { "" } else { ",\"content\":\"...\"" };
+    let prompt = format!(
+        "以下のニュース記事を言語コード「{lang}」の言語に翻訳してください。\n\n\
+        ## ルール\n\
+        - 固有名詞は一般的な表記に従う\n\
+        - 意訳しすぎず、ニュースとして自然な文体にする\n\
+        - detected_source_langに原文の言語コード（ISO 639-1、例: en, zh）\n\
+        - JSON出力のみ: {{\"title\":\"...\",\"description\":\"...\"{content_field},\"detected_source_lang\":\"...\"}}\n\n\
+        ## タイトル\n{title}\n\n## 概要\n{description}{content_section}",
+        lang = target_lang,
+    );
 
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
+    let generated = claude
+        .complete(tier, "translate", if content_section.is_empty() { 1024 } else { 4096 }, None, &[ChatMessage::user(prompt)])
+        .await?;
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        warn!(status = %status, body = %body, "Claude API error (classify)");
-        return Err(format!("Claude API error: {} - {}", status, body));
+    generated.try_map(|text| parse_json(&text, "translation"))
+}
+
+// --- Comparison ---
+
+/// Characters of each article's body sent to `compare_articles`.
+const COMPARE_CONTENT_CHARS: usize = 4000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArticleComparison {
+    pub summary_a: String,
+    pub summary_b: String,
+    #[serde(default)]
+    pub agreements: Vec<String>,
+    #[serde(default)]
+    pub disagreements: Vec<String>,
+    #[serde(default)]
+    pub unique_to_a: Vec<String>,
+    #[serde(default)]
+    pub unique_to_b: Vec<String>,
+    #[serde(default)]
+    pub framing_analysis: String,
+}
+
+impl ArticleComparison {
+    /// The same comparison with articles A and B exchanged.
+    pub fn swapped(self) -> Self {
+        Self {
+            summary_a: self.summary_b,
+            summary_b: self.summary_a,
+            unique_to_a: self.unique_to_b,
+            unique_to_b: self.unique_to_a,
+            ..self
+        }
     }
+}
 
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
+/// 同じ話題を扱う2本の記事を比較し、事実の一致・相違と論調の違いを整理
+pub async fn compare_articles(
+    claude: &ClaudeClient,
+    tier: ModelTier,
+    a: &Article,
+    content_a: &str,
+    b: &Article,
+    content_b: &str,
+) -> Result<Generated<ArticleComparison>, String> {
+    let section = |label: &str, article: &Article, content: &str| {
+        let body = if content.is_empty() {
+            article.description.clone().unwrap_or_default()
+        } else {
+            content.chars().take(COMPARE_CONTENT_CHARS).collect()
+        };
+        format!(
+            "## 記事{label}\nタイトル: {}\nソース: {}\n本文:\n{}",
+            article.title, article.source, body
+        )
+    };
+    let prompt = format!(
+        "以下の2本のニュース記事を比較してください。\n\n\
+        ## ルール\n\
+        - summary_a / summary_b: それぞれの要約（各100文字以内）\n\
+        - agreements: 両記事で一致している事実（最大5個）\n\
+        - disagreements: 食い違っている事実や数字（最大5個、なければ空配列）\n\
+        - unique_to_a / unique_to_b: 片方にしかない情報（各最大5個）\n\
+        - framing_analysis: 見出し・強調点・語り口など論調の違いの分析（200文字以内）\n\
+        - 記事に書かれていないことは推測しない\n\
+        - JSON出力のみ: {{\"summary_a\":\"...\",\"summary_b\":\"...\",\"agreements\":[...],\"disagreements\":[...],\"unique_to_a\":[...],\"unique_to_b\":[...],\"framing_analysis\":\"...\"}}\n\n\
+        {}\n\n{}",
+        section("A", a, content_a),
+        section("B", b, content_b),
+    );
 
-    let text = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .ok_or_else(|| "Empty response from Claude".to_string())?;
+    let generated = claude
+        .complete(tier, "compare", 2048, None, &[ChatMessage::user(prompt)])
+        .await?;
 
-    let clean = text.trim().trim_start_matches("```json").trim_start_matches("```").trim_end_matches("```").trim();
-    let classification: ArticleClassification = serde_json::from_str(clean)
-        .map_err(|e| format!("Failed to parse classification: {} — raw: {}", e, text))?;
+    generated.try_map(|text| parse_json(&text, "comparison"))
+}
+
+/// Characters of each member's body sent to `compare_coverage`; less than
+/// `COMPARE_CONTENT_CHARS` since up to four articles share the prompt.
+const COVERAGE_CONTENT_CHARS: usize = 2500;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoverageComparison {
+    #[serde(default)]
+    pub common_facts: Vec<String>,
+    #[serde(default)]
+    pub differences: Vec<SourceEmphasis>,
+    #[serde(default)]
+    pub tone_notes: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SourceEmphasis {
+    pub source: String,
+    pub emphasis: String,
+}
+
+/// 同じ話題を複数のソースがどう報じているかを比較（共通の事実・各ソースの強調点・論調）
+pub async fn compare_coverage(
+    claude: &ClaudeClient,
+    tier: ModelTier,
+    members: &[(&Article, String)],
+) -> Result<Generated<CoverageComparison>, String> {
+    let sections: Vec<String> = members
+        .iter()
+        .map(|(article, content)| {
+            let body = if content.is_empty() {
+                article.description.clone().unwrap_or_default()
+            } else {
+                content.chars().take(COVERAGE_CONTENT_CHARS).collect()
+            };
+            format!("## {}\nタイトル: {}\n本文:\n{}", article.source, article.title, body)
+        })
+        .collect();
+    let prompt = format!(
+        "以下は同じニュースを異なるソースが報じた記事です。報じ方を比較してください。\n\n\
+        ## ルール\n\
+        - common_facts: すべての記事で一致している事実（最大5個）\n\
+        - differences: ソースごとに、他の記事と比べて強調している点や独自の情報（sourceは見出しのソース名をそのまま使う、各100文字以内）\n\
+        - tone_notes: 見出し・語り口など論調の違いの分析（200文字以内）\n\
+        - 記事に書かれていないことは推測しない\n\
+        - JSON出力のみ: {{\"common_facts\":[...],\"differences\":[{{\"source\":\"...\",\"emphasis\":\"...\"}}],\"tone_notes\":\"...\"}}\n\n\
+        {}",
+        sections.join("\n\n"),
+    );
 
-    Ok(classification)
+    let generated = claude
+        .complete(tier, "compare_coverage", 2048, None, &[ChatMessage::user(prompt)])
+        .await?;
+
+    generated.try_map(|text| parse_json(&text, "coverage comparison"))
 }
 
 /// 「で、どうすればいい？」のアクションプランを生成
 pub async fn generate_action_plan(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     title: &str,
     description: &str,
     article_content: &str,
     classification: &str,
-) -> Result<ActionPlan, String> {
+) -> Result<Generated<ActionPlan>, String> {
     let content_section = if article_content.is_empty() {
         String::new()
     } else {
-        format!("\n\n## 記事本文\n{}", &article_content[..article_content.len().min(2000)])
+        format!("\n\n## 記事本文\n{}", truncate_sentence(article_content, 2000))
     };
 
     let prompt = format!(
@@ -697,119 +1229,231 @@ pub async fn generate_action_plan(
         classification, title, description, content_section
     );
 
-    let request = ClaudeRequest {
-        model: "claude-sonnet-4-5-20250929".into(),
-        max_tokens: 768,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: prompt,
-        }],
-    };
-
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        warn!(status = %status, body = %body, "Claude API error (action_plan)");
-        return Err(format!("Claude API error: {} - {}", status, body));
-    }
-
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
-
-    let text = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .ok_or_else(|| "Empty response from Claude".to_string())?;
+    let generated = claude
+        .complete(tier, "action_plan", 768, None, &[ChatMessage::user(prompt)])
+        .await?;
 
-    let clean = text.trim().trim_start_matches("```json").trim_start_matches("```").trim_end_matches("```").trim();
-    let action_plan: ActionPlan = serde_json::from_str(clean)
-        .map_err(|e| format!("Failed to parse action plan: {} — raw: {}", e, text))?;
-
-    Ok(action_plan)
+    generated.try_map(|text| parse_json(&text, "action plan"))
 }
 
 pub async fn interpret_command(
-    client: &reqwest::Client,
-    api_key: &str,
+    claude: &ClaudeClient,
+    tier: ModelTier,
     command: &str,
     current_config: &ServiceConfig,
-) -> Result<CommandInterpretation, String> {
+    context: Option<&str>,
+) -> Result<Generated<CommandInterpretation>, String> {
     let config_json = serde_json::to_string_pretty(current_config)
         .map_err(|e| format!("Config serialization error: {}", e))?;
 
-    let user_message = format!(
-        "## 現在の設定\n```json\n{}\n```\n\n## ユーザーコマンド\n{}",
-        config_json, command
+    let mut user_message = format!("## 現在の設定\n```json\n{}\n```\n\n", config_json);
+    if let Some(context) = context {
+        user_message.push_str(&format!("## 経緯\n{}\n\n", context));
+    }
+    user_message.push_str(&format!("## ユーザーコマンド\n{}", command));
+
+    info!(command = %command, "Sending command to Claude API");
+
+    let generated = claude
+        .complete(tier, "command", 1024, None, &[ChatMessage::user(format!("{}\n\n{}", SYSTEM_PROMPT, user_message))])
+        .await?;
+
+    let interpretation = generated.try_map(|text| parse_json::<CommandInterpretation>(&text, "Claude interpretation"))?;
+
+    info!(
+        confidence = interpretation.value.confidence,
+        actions = interpretation.value.actions.len(),
+        "Claude interpretation complete"
     );
 
-    let request = ClaudeRequest {
-        model: "claude-sonnet-4-5-20250929".into(),
-        max_tokens: 1024,
-        messages: vec![ClaudeMessage {
-            role: "user".into(),
-            content: format!("{}\n\n{}", SYSTEM_PROMPT, user_message),
-        }],
-    };
+    Ok(interpretation)
+}
 
-    info!(command = %command, "Sending command to Claude API");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use std::collections::VecDeque;
+    use std::sync::Arc;
+
+    /// Scripted responses (popped per request; 200 once exhausted) and the models asked for.
+    #[derive(Default)]
+    struct Mock {
+        statuses: Mutex<VecDeque<u16>>,
+        models: Mutex<Vec<String>>,
+    }
 
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Claude API request failed: {}", e))?;
+    async fn messages(State(mock): State<Arc<Mock>>, Json(body): Json<serde_json::Value>) -> (StatusCode, String) {
+        let model = body["model"].as_str().unwrap_or_default().to_string();
+        mock.models.lock().unwrap().push(model.clone());
+        let status = mock.statuses.lock().unwrap().pop_front().unwrap_or(200);
+        let body = if status == 200 {
+            serde_json::json!({"content": [{"type": "text", "text": format!(" reply from {} ", model)}]}).to_string()
+        } else {
+            r#"{"type":"error","error":{"type":"overloaded_error"}}"#.to_string()
+        };
+        (StatusCode::from_u16(status).unwrap(), body)
+    }
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        warn!(status = %status, body = %body, "Claude API error");
-        return Err(format!("Claude API error: {} - {}", status, body));
+    async fn mock_client(statuses: &[u16]) -> (ClaudeClient, Arc<Mock>) {
+        let mock = Arc::new(Mock {
+            statuses: Mutex::new(statuses.iter().copied().collect()),
+            ..Default::default()
+        });
+        let app = Router::new().route("/v1/messages", post(messages)).with_state(Arc::clone(&mock));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let client = ClaudeClient::with_endpoint(format!("http://{}", addr), Duration::from_millis(1));
+        (client, mock)
     }
 
-    let claude_response: ClaudeResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
+    fn asked(mock: &Mock) -> Vec<String> {
+        mock.models.lock().unwrap().clone()
+    }
 
-    let text = claude_response
-        .content
-        .first()
-        .and_then(|b| b.text.as_ref())
-        .ok_or_else(|| "Empty response from Claude".to_string())?;
+    #[tokio::test]
+    async fn overloaded_quality_retries_then_downgrades() {
+        let (client, mock) = mock_client(&[529, 529]).await;
+        let generated = generate_murmur_like(&client, ModelTier::Quality).await.unwrap();
+        assert_eq!(generated.model_used, ModelTier::Fast.model());
+        assert_eq!(generated.value, format!("reply from {}", ModelTier::Fast.model()));
+        let quality = ModelTier::Quality.model().to_string();
+        assert_eq!(asked(&mock), vec![quality.clone(), quality, ModelTier::Fast.model().to_string()]);
+    }
 
-    // Parse the JSON response, stripping any markdown code fences
-    let clean_text = text
-        .trim()
-        .trim_start_matches("```json")
-        .trim_start_matches("```")
-        .trim_end_matches("```")
-        .trim();
+    #[tokio::test]
+    async fn retry_recovers_without_downgrading() {
+        let (client, mock) = mock_client(&[429]).await;
+        let generated = generate_murmur_like(&client, ModelTier::Quality).await.unwrap();
+        assert_eq!(generated.model_used, ModelTier::Quality.model());
+        assert_eq!(asked(&mock).len(), 2);
+    }
 
-    let interpretation: CommandInterpretation = serde_json::from_str(clean_text)
-        .map_err(|e| format!("Failed to parse Claude interpretation: {} — raw: {}", e, text))?;
+    #[tokio::test]
+    async fn client_errors_are_not_downgraded() {
+        let (client, mock) = mock_client(&[400]).await;
+        assert!(generate_murmur_like(&client, ModelTier::Quality).await.is_err());
+        assert_eq!(asked(&mock), vec![ModelTier::Quality.model().to_string()]);
+    }
 
-    info!(
-        confidence = interpretation.confidence,
-        actions = interpretation.actions.len(),
-        "Claude interpretation complete"
-    );
+    #[tokio::test]
+    async fn failing_model_cools_down_after_repeated_failures() {
+        // Three calls where Sonnet returns 500 and Haiku answers
+        let (client, mock) = mock_client(&[500, 200, 500, 200, 500, 200]).await;
+        for _ in 0..FAILURE_THRESHOLD {
+            let generated = generate_murmur_like(&client, ModelTier::Quality).await.unwrap();
+            assert_eq!(generated.model_used, ModelTier::Fast.model());
+        }
+        assert!(client.cooling_down(ModelTier::Quality));
+
+        // Sonnet is now skipped entirely
+        mock.models.lock().unwrap().clear();
+        let generated = generate_murmur_like(&client, ModelTier::Quality).await.unwrap();
+        assert_eq!(generated.model_used, ModelTier::Fast.model());
+        assert_eq!(asked(&mock), vec![ModelTier::Fast.model().to_string()]);
+    }
 
-    Ok(interpretation)
+    #[tokio::test]
+    async fn fast_tier_has_no_fallback() {
+        let (client, mock) = mock_client(&[503]).await;
+        assert!(generate_murmur_like(&client, ModelTier::Fast).await.is_err());
+        assert_eq!(asked(&mock), vec![ModelTier::Fast.model().to_string()]);
+    }
+
+    async fn generate_murmur_like(client: &ClaudeClient, tier: ModelTier) -> Result<Generated<String>, String> {
+        generate_murmur(client, tier, "タイトル", "概要", "ソース", None).await
+    }
+
+    #[test]
+    fn citations_keep_known_markers_only() {
+        let (text, cited) = resolve_citations("円安が進行[2]。株価も上昇[1][2][7]。[2024]年[", 3);
+        assert_eq!(text, "円安が進行[2]。株価も上昇[1][2]。[2024]年[");
+        assert_eq!(cited, vec![2, 1]);
+        assert_eq!(strip_citations("首相が会見[1]。[0]詳細[12]は"), "首相が会見。詳細は");
+    }
+
+    #[test]
+    fn debate_lines_alternate_and_are_capped() {
+        let line = |persona: &str, text: &str| MurmurLine { persona: persona.into(), text: text.into() };
+        let long = |n: usize| "あ".repeat(n);
+        let raw = vec![
+            line("Optimist", "これは便利になりそう！毎日の通勤がずっと楽になるし、渋滞も減るかもね。"),
+            line("optimist", "二度目の楽観派は落とす。同じ人が続けて話すのは討論じゃないからね。"),
+            line("skeptic", "でもコストが心配だよね。導入費用を誰が払うのか、まだ決まってないし。"),
+            line("narrator", "知らない話者の行はどんなに長くても使わない。ナレーションは不要だよ。"),
+            line("optimist", "短すぎる行"),
+            line("optimist", &long(30)),
+            line("skeptic", &long(50)),
+            line("optimist", &long(40)),
+            line("skeptic", &long(45)),
+            line("optimist", &long(35)),
+        ];
+        let debate = normalize_debate(raw).unwrap();
+        assert_eq!(debate.len(), 6);
+        assert_eq!(debate[0].persona, "optimist");
+        assert!(debate[0].text.starts_with("これは便利になりそう！"));
+        assert!(debate.windows(2).all(|w| w[0].persona != w[1].persona));
+        assert!(debate.iter().all(|l| DEBATE_LINE_CHARS.contains(&l.text.chars().count())));
+        let three = vec![line("optimist", &long(30)), line("skeptic", &long(30)), line("optimist", &long(30))];
+        assert!(normalize_debate(three).is_err());
+    }
+
+    #[test]
+    fn long_debate_lines_are_cut_into_range() {
+        let sentences = format!("{}。{}", "あ".repeat(34), "い".repeat(30));
+        assert_eq!(fit_debate_line(&sentences).unwrap(), format!("{}。", "あ".repeat(34)));
+        let run_on = "う".repeat(80);
+        assert_eq!(fit_debate_line(&run_on).unwrap(), format!("{}…", "う".repeat(49)));
+        assert_eq!(fit_debate_line(&"え".repeat(50)).unwrap(), "え".repeat(50));
+        assert!(fit_debate_line(&"お".repeat(29)).is_none());
+    }
+
+    #[test]
+    fn parse_json_strips_code_fences() {
+        let parsed: Vec<String> = parse_json("```json\n[\"a\", \"b\"]\n```", "questions").unwrap();
+        assert_eq!(parsed, vec!["a", "b"]);
+        assert!(parse_json::<Vec<String>>("not json", "questions").is_err());
+    }
+
+    #[test]
+    fn comparison_parses_sparse_output_and_swaps_sides() {
+        let parsed: ArticleComparison =
+            parse_json(r#"{"summary_a":"A","summary_b":"B","unique_to_a":["a1"],"agreements":["x"]}"#, "comparison")
+                .unwrap();
+        assert!(parsed.disagreements.is_empty());
+        let swapped = parsed.swapped();
+        assert_eq!((swapped.summary_a.as_str(), swapped.summary_b.as_str()), ("B", "A"));
+        assert_eq!(swapped.unique_to_b, vec!["a1"]);
+        assert!(swapped.unique_to_a.is_empty());
+        assert_eq!(swapped.agreements, vec!["x"]);
+    }
+
+    #[test]
+    fn local_classification_matches_keywords() {
+        let c = classify_article_local("New AI tool tops Product Hunt", "Also trending on Reddit", "Example", "tech");
+        assert_eq!(c.category, "timemachine");
+        assert_eq!(c.tags, ["product hunt", "reddit"]);
+        // Two goldmining keywords beat one frustration keyword
+        let c = classify_article_local("特許庁が白書を公表", "手続きの問題点を整理", "経産省", "business");
+        assert_eq!(c.category, "goldmining");
+        assert_eq!(classify_article_local("arXiv の論文", "", "", "science").category, "timemachine");
+        let c = classify_article_local("今日の天気", "晴れ", "NHK", "general");
+        assert_eq!((c.category.as_str(), c.tags.len()), ("general", 0));
+    }
+
+    #[test]
+    fn coverage_comparison_parses_per_source_differences() {
+        let parsed: CoverageComparison = parse_json(
+            r#"{"common_facts":["x"],"differences":[{"source":"NHK","emphasis":"被害規模"}]}"#,
+            "coverage comparison",
+        )
+        .unwrap();
+        assert_eq!(parsed.common_facts, vec!["x"]);
+        assert_eq!(parsed.differences[0].source, "NHK");
+        assert!(parsed.tone_notes.is_empty());
+    }
 }
@@ -0,0 +1,97 @@
+//! Periodic database maintenance: expired cache rows and the reading-time backfill every
+//! 6 hours, and once a day old usage/dedup/view-event rows, expired conversations, old
+//! articles and a VACUUM. Each step's outcome is stored for
+//! `GET /api/admin/maintenance/status`.
+
+use crate::db::Db;
+use crate::supervisor::Heartbeat;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::time::{interval, interval_at, Duration};
+use tracing::{info, warn};
+
+const CACHE_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+const DAILY_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+/// Let startup traffic settle before the first VACUUM.
+const DAILY_FIRST_DELAY: Duration = Duration::from_secs(600);
+
+const USAGE_KEEP_DAYS: i64 = 7;
+const VIEWS_DEDUP_KEEP_DAYS: i64 = 2;
+/// Matches the longest window `GET /api/admin/analytics/heatmap` accepts.
+const VIEW_EVENTS_KEEP_DAYS: i64 = 90;
+/// Well past Stripe's 3-day retry window.
+const WEBHOOK_EVENTS_KEEP_DAYS: i64 = 30;
+const ARTICLE_RETENTION_DAYS: i64 = 30;
+/// Articles per backfill transaction; the lock is released between batches.
+const READING_TIME_BATCH: i64 = 500;
+/// Articles older than this keep only the most popular 20%.
+const BOTTOM_80_DAYS: i64 = 7;
+
+#[tracing::instrument(name = "cleanup_task", skip_all)]
+pub async fn run(db: Arc<Db>, heartbeat: Heartbeat) {
+    info!("Cleanup task starting");
+    let mut cache_tick = interval(CACHE_INTERVAL);
+    let mut daily_tick = interval_at(tokio::time::Instant::now() + DAILY_FIRST_DELAY, DAILY_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = cache_tick.tick() => {
+                step(&db, "expired_cache", |db| db.cleanup_expired_cache()).await;
+                step(&db, "reading_time", |db| {
+                    let mut filled = 0;
+                    loop {
+                        let n = db.backfill_reading_time(READING_TIME_BATCH)?;
+                        filled += n;
+                        if n < READING_TIME_BATCH as usize {
+                            return Ok(filled);
+                        }
+                    }
+                })
+                .await;
+            }
+            _ = daily_tick.tick() => {
+                step(&db, "old_usage", |db| db.cleanup_old_usage(USAGE_KEEP_DAYS)).await;
+                step(&db, "views_dedup", |db| db.cleanup_views_dedup(VIEWS_DEDUP_KEEP_DAYS)).await;
+                step(&db, "view_events", |db| db.cleanup_view_events(VIEW_EVENTS_KEEP_DAYS)).await;
+                step(&db, "webhook_events", |db| db.cleanup_webhook_events(WEBHOOK_EVENTS_KEEP_DAYS)).await;
+                step(&db, "chat_sessions", |db| db.prune_owned_chat_sessions(crate::routes::CONVERSATION_TTL_HOURS)).await;
+                step(&db, "old_articles", |db| {
+                    db.delete_old_articles(&(Utc::now() - chrono::Duration::days(ARTICLE_RETENTION_DAYS)))
+                })
+                .await;
+                step(&db, "degrade_images", |db| {
+                    let policy = db.get_feature_flags()?.image_degradation;
+                    if !policy.enabled {
+                        return Ok(0);
+                    }
+                    db.degrade_old_unpopular_images(&policy)
+                })
+                .await;
+                step(&db, "bottom_80", |db| db.cleanup_old_articles_bottom_80(BOTTOM_80_DAYS)).await;
+                step(&db, "vacuum", |db| db.vacuum_and_checkpoint()).await;
+            }
+        }
+        heartbeat.beat();
+    }
+}
+
+/// Run one maintenance step off the async workers, log it and record the outcome.
+async fn step<F>(db: &Arc<Db>, name: &'static str, f: F)
+where
+    F: FnOnce(&Db) -> Result<usize, String> + Send + 'static,
+{
+    let started = Instant::now();
+    let task_db = Arc::clone(db);
+    let result = tokio::task::spawn_blocking(move || f(&task_db))
+        .await
+        .unwrap_or_else(|e| Err(format!("maintenance step panicked: {e}")));
+    let duration_ms = started.elapsed().as_millis() as i64;
+    match &result {
+        Ok(rows) => info!(step = name, rows, duration_ms, "Maintenance step done"),
+        Err(e) => warn!(step = name, error = %e, duration_ms, "Maintenance step failed"),
+    }
+    if let Err(e) = db.record_maintenance_run(name, duration_ms, &result) {
+        warn!(step = name, error = %e, "Failed to record maintenance run");
+    }
+}
@@ -0,0 +1,106 @@
+//! The real client IP behind Fly's proxy. With `TRUST_PROXY_HEADERS` (the default),
+//! `Fly-Client-IP` and then the first `X-Forwarded-For` hop are believed; otherwise
+//! only the TCP peer address counts. `normalize` rewrites the forwarding headers to
+//! match, so code that only sees headers (usage keys, visitor hashes) gets the same
+//! address as `resolve`.
+
+use axum::extract::{ConnectInfo, Request};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use std::net::SocketAddr;
+use std::sync::LazyLock;
+
+/// Set `TRUST_PROXY_HEADERS=false` when not running behind a proxy that sets them.
+pub static TRUST_PROXY_HEADERS: LazyLock<bool> = LazyLock::new(|| {
+    !matches!(
+        std::env::var("TRUST_PROXY_HEADERS").ok().as_deref().map(str::trim),
+        Some("0" | "false" | "no")
+    )
+});
+
+const FLY_CLIENT_IP: &str = "fly-client-ip";
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Client IP from the forwarding headers, or "unknown". Only meaningful on requests
+/// that went through `normalize`.
+pub fn header_ip(headers: &HeaderMap) -> &str {
+    forwarded_ip(headers).unwrap_or("unknown")
+}
+
+fn forwarded_ip(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(FLY_CLIENT_IP)
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| {
+            headers
+                .get(X_FORWARDED_FOR)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.split(',').next())
+        })
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+}
+
+/// Forwarded address if `trust_proxy` and present, else the peer address.
+pub fn resolve(headers: &HeaderMap, peer: Option<SocketAddr>, trust_proxy: bool) -> String {
+    let forwarded = if trust_proxy { forwarded_ip(headers) } else { None };
+    match (forwarded, peer) {
+        (Some(ip), _) => ip.to_string(),
+        (None, Some(peer)) => peer.ip().to_string(),
+        (None, None) => "unknown".into(),
+    }
+}
+
+fn peer_addr(extensions: &axum::http::Extensions) -> Option<SocketAddr> {
+    extensions.get::<ConnectInfo<SocketAddr>>().map(|c| c.0)
+}
+
+/// Middleware: when proxy headers aren't trusted, replace them with the peer address.
+pub async fn normalize(mut req: Request, next: Next) -> Response {
+    if !*TRUST_PROXY_HEADERS {
+        let ip = resolve(req.headers(), peer_addr(req.extensions()), false);
+        let headers = req.headers_mut();
+        headers.remove(FLY_CLIENT_IP);
+        match HeaderValue::from_str(&ip) {
+            Ok(value) => {
+                headers.insert(X_FORWARDED_FOR, value);
+            }
+            Err(_) => {
+                headers.remove(X_FORWARDED_FOR);
+            }
+        }
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn trusted_headers_prefer_fly_then_first_forwarded_hop() {
+        let peer = Some("10.0.0.1:4000".parse().unwrap());
+        let both = headers(&[(FLY_CLIENT_IP, "203.0.113.7"), (X_FORWARDED_FOR, "198.51.100.1, 10.0.0.2")]);
+        assert_eq!(resolve(&both, peer, true), "203.0.113.7");
+        let forwarded = headers(&[(X_FORWARDED_FOR, " 198.51.100.1 , 10.0.0.2")]);
+        assert_eq!(resolve(&forwarded, peer, true), "198.51.100.1");
+        assert_eq!(resolve(&HeaderMap::new(), peer, true), "10.0.0.1");
+        assert_eq!(resolve(&HeaderMap::new(), None, true), "unknown");
+    }
+
+    #[test]
+    fn untrusted_headers_are_ignored() {
+        let spoofed = headers(&[(FLY_CLIENT_IP, "203.0.113.7")]);
+        assert_eq!(resolve(&spoofed, Some("10.0.0.1:4000".parse().unwrap()), false), "10.0.0.1");
+        assert_eq!(resolve(&spoofed, None, false), "unknown");
+    }
+}
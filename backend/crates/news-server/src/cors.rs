@@ -0,0 +1,130 @@
+//! CORS allowlist: the origin of every site (sites.toml and DB-added), plus
+//! `ALLOWED_ORIGINS` and the admin-managed `cors_origins` feature. The list sits in
+//! an `ArcSwap` read by an `AllowOrigin::predicate`, so `reload` after an admin
+//! change takes effect without a restart.
+
+use crate::db::Db;
+use arc_swap::ArcSwap;
+use axum::http::{HeaderName, HeaderValue, Method};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::{info, warn};
+
+pub struct CorsAllowlist {
+    origins: ArcSwap<BTreeSet<String>>,
+    /// From `ALLOWED_ORIGINS`, fixed for the process lifetime.
+    env_origins: Vec<String>,
+}
+
+impl CorsAllowlist {
+    /// An allowlist with `ALLOWED_ORIGINS` (comma-separated) as its extra origins.
+    /// Call `reload` to add the sites.
+    pub fn from_env() -> Self {
+        let raw = std::env::var("ALLOWED_ORIGINS").unwrap_or_default();
+        let mut env_origins = Vec::new();
+        for entry in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match normalize_origin(entry) {
+                Some(origin) => env_origins.push(origin),
+                None => warn!(origin = entry, "ALLOWED_ORIGINS: ignoring invalid origin"),
+            }
+        }
+        Self::new(env_origins)
+    }
+
+    fn new(env_origins: Vec<String>) -> Self {
+        Self {
+            origins: ArcSwap::from_pointee(env_origins.iter().cloned().collect()),
+            env_origins,
+        }
+    }
+
+    /// Rebuild the list from sites.toml, DB-added sites, `ALLOWED_ORIGINS` and the
+    /// `cors_origins` feature. DB errors keep the current list.
+    pub fn reload(&self, db: &Db) {
+        let (sites, flags) = match (db.list_sites(), db.get_feature_flags()) {
+            (Ok(sites), Ok(flags)) => (sites, flags),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!(error = %e, "CORS reload failed; keeping current origins");
+                return;
+            }
+        };
+        let origins: BTreeSet<String> = crate::routes::SITES
+            .sites
+            .iter()
+            .chain(&sites)
+            .filter_map(|s| s.origin())
+            .chain(self.env_origins.iter().cloned())
+            .chain(flags.cors_origins.iter().filter_map(|o| normalize_origin(o)))
+            .collect();
+        info!(origins = origins.len(), "CORS origins loaded");
+        self.origins.store(Arc::new(origins));
+    }
+
+    pub fn allows(&self, origin: &HeaderValue) -> bool {
+        origin.to_str().is_ok_and(|o| self.origins.load().contains(o))
+    }
+
+    pub fn origins(&self) -> Vec<String> {
+        self.origins.load().iter().cloned().collect()
+    }
+
+    /// CORS layer checking origins against the live list.
+    pub fn layer(self: &Arc<Self>) -> CorsLayer {
+        let allowlist = Arc::clone(self);
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::predicate(move |origin, _| allowlist.allows(origin)))
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
+            .allow_headers([
+                axum::http::header::CONTENT_TYPE,
+                axum::http::header::AUTHORIZATION,
+                HeaderName::from_static("x-device-id"),
+                HeaderName::from_static("x-admin-secret"),
+                HeaderName::from_static("x-pro-token"),
+            ])
+    }
+}
+
+/// `scheme://host[:port]` as browsers send it in `Origin`, or None for anything that
+/// isn't a bare http(s) origin.
+pub fn normalize_origin(value: &str) -> Option<String> {
+    let url = reqwest::Url::parse(value.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https")
+        || url.host_str().is_none()
+        || url.path() != "/"
+        || url.query().is_some()
+        || url.fragment().is_some()
+        || !url.username().is_empty()
+    {
+        return None;
+    }
+    Some(url.origin().ascii_serialization())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origins_are_normalized_or_rejected() {
+        assert_eq!(normalize_origin("https://News.XYZ/").as_deref(), Some("https://news.xyz"));
+        assert_eq!(normalize_origin("http://localhost:3000").as_deref(), Some("http://localhost:3000"));
+        assert_eq!(normalize_origin("https://news.xyz:443").as_deref(), Some("https://news.xyz"));
+        assert_eq!(normalize_origin("https://news.xyz/app"), None);
+        assert_eq!(normalize_origin("ftp://news.xyz"), None);
+        assert_eq!(normalize_origin("news.xyz"), None);
+    }
+
+    #[test]
+    fn allowlist_swaps_in_place() {
+        let allowlist = CorsAllowlist::new(vec!["https://staging.news.xyz".into()]);
+        let staging = HeaderValue::from_static("https://staging.news.xyz");
+        let other = HeaderValue::from_static("https://other.example");
+        assert!(allowlist.allows(&staging));
+        assert!(!allowlist.allows(&other));
+
+        allowlist.origins.store(Arc::new(["https://other.example".to_string()].into()));
+        assert!(allowlist.allows(&other));
+        assert!(!allowlist.allows(&staging));
+    }
+}
@@ -1,25 +1,332 @@
 use chrono::{DateTime, Utc};
-use news_core::changes::{AdminAction, ChangeRequest, ChangeStatus};
-use news_core::config::{DynamicFeed, FeatureFlags, ServiceConfig};
-use news_core::models::{Article, Category};
+use news_core::changes::{ChangeRequest, ChangeStatus};
+use news_core::config::{DynamicFeed, FeatureFlags, ImageDegradationPolicy, ServiceConfig};
+use news_core::models::{
+    recency_decay, Article, Category, ImportanceWeights, SourceMeta, MAX_CREDIBILITY_TIER,
+    POPULARITY_HALF_SCORE,
+};
+use news_core::grouping;
+use news_core::mute::{MuteField, MuteRule};
+use news_core::pagination::{Page, Paginator};
+use news_core::reading_time::{self, ReadingTime};
+use news_core::sites::SiteMeta;
+use rusqlite::functions::FunctionFlags;
 use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
-use tracing::info;
+use tracing::{info, warn};
+
+/// Minimum title similarity for an article to join a story timeline (grouping default).
+const TIMELINE_SIMILARITY: f64 = 0.3;
+
+/// Article columns for `row_to_article` when `source_meta` is joined in.
+const JOINED_ARTICLE_COLUMNS: &str = "articles.id, articles.category, articles.title, articles.url,
+    articles.description, articles.image_url, articles.source, articles.published_at,
+    articles.fetched_at, articles.group_id, articles.group_count, articles.word_count,
+    articles.reading_minutes";
+
+/// Joins each article's source metadata as `sm` (NULLs for sources without any).
+const SOURCE_META_JOIN: &str = "LEFT JOIN source_meta sm ON sm.source = articles.source";
+
+/// Condition leaving out the source types in the JSON array bound to `param`, or
+/// nothing when it's NULL. Needs `SOURCE_META_JOIN`.
+fn exclude_types_condition(param: &str) -> String {
+    format!("({param} IS NULL OR COALESCE(sm.source_type, 'unknown') NOT IN (SELECT value FROM json_each({param})))")
+}
+
+/// `exclude_types` as the JSON array `exclude_types_condition` binds; None for none.
+fn exclude_types_param(exclude_types: &[String]) -> Option<String> {
+    (!exclude_types.is_empty()).then(|| serde_json::to_string(exclude_types).unwrap_or_default())
+}
+
+/// Extra conditions for `Db::query_filtered_articles`.
+#[derive(Debug, Default)]
+pub struct ArticleFilter {
+    /// Published between these, both inclusive.
+    pub range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    /// Leave out sources of these types; sources without metadata are "unknown".
+    pub exclude_types: Vec<String>,
+}
+
+/// Which analyzed articles `Db::reset_analysis_for_articles` sends back to the
+/// analyzer. Conditions combine with AND; at least one of `article_ids` and
+/// `before_analyzed_at` must be set.
+#[derive(Debug, Default)]
+pub struct ReanalyzeFilter {
+    pub article_ids: Option<Vec<String>>,
+    /// Articles analyzed strictly before this.
+    pub before_analyzed_at: Option<DateTime<Utc>>,
+    pub category: Option<String>,
+    /// Newest articles first when capped.
+    pub limit: Option<i64>,
+}
+
+/// Sort order for `Db::query_ranked_articles`.
+pub enum Ranking {
+    /// `score_importance` with the given weights.
+    Importance(ImportanceWeights),
+    Popularity,
+}
 
 pub struct Db {
     conn: Mutex<Connection>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+/// Counters the Db tracks itself; exported by `metrics::Metrics::render`.
+#[derive(Debug, Clone, Copy)]
+pub struct DbStats {
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub busy_waits: u64,
+    pub busy_timeouts: u64,
+}
+
+// SQLite's busy handler is a plain fn pointer, so its counters can't live on Db.
+static BUSY_WAITS: AtomicU64 = AtomicU64::new(0);
+static BUSY_TIMEOUTS: AtomicU64 = AtomicU64::new(0);
+
+/// Same 5 s budget as the old `PRAGMA busy_timeout=5000`, but counted.
+fn busy_handler(attempt: i32) -> bool {
+    if attempt == 0 {
+        BUSY_WAITS.fetch_add(1, Ordering::Relaxed);
+    }
+    if attempt >= 250 {
+        BUSY_TIMEOUTS.fetch_add(1, Ordering::Relaxed);
+        return false;
+    }
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    true
+}
+
+/// Extra attempts for a write that still got SQLITE_BUSY: either the busy handler ran
+/// out of budget, or SQLite refused without calling it (a deferred transaction trying
+/// to upgrade to a write lock after another connection committed).
+const BUSY_RETRIES: u32 = 3;
+
+/// Run `f`, retrying with a short backoff while SQLite reports the database busy or
+/// locked. `f` must be safe to repeat, i.e. do all its writes in one transaction.
+fn with_busy_retry<T>(op: &str, mut f: impl FnMut() -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Err(e) if is_busy(&e) && attempt < BUSY_RETRIES => {
+                attempt += 1;
+                warn!(op, attempt, "SQLite busy, retrying");
+                std::thread::sleep(std::time::Duration::from_millis(50 * u64::from(attempt)));
+            }
+            result => return result.map_err(|e| format!("{op}: {e}")),
+        }
+    }
+}
+
+const SHORTLINK_LEN: usize = 6;
+const SHORTLINK_ATTEMPTS: usize = 5;
+
+/// Random `SHORTLINK_LEN`-character alphanumeric code.
+fn new_short_code() -> String {
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let mut bytes = [0u8; SHORTLINK_LEN];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        bytes.copy_from_slice(&uuid::Uuid::new_v4().as_bytes()[..SHORTLINK_LEN]);
+    }
+    bytes
+        .iter()
+        .map(|b| ALPHABET[*b as usize % ALPHABET.len()] as char)
+        .collect()
+}
+
+fn is_busy(e: &rusqlite::Error) -> bool {
+    matches!(
+        e.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+    )
+}
+
+/// A row of the enrichments table, without the payload.
+#[derive(Debug, serde::Serialize)]
+pub struct EnrichmentRecord {
+    pub enrichment_id: String,
+    pub article_id: String,
+    pub agent_type: String,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+/// Freshness columns of a feed, filled in by the fetcher.
+#[derive(Debug, Default, serde::Serialize)]
+pub struct FeedStats {
+    pub last_fetched_at: Option<String>,
+    pub last_item_count: Option<i64>,
+    /// Mean over the last `FEED_STATS_WINDOW` successful fetches.
+    pub avg_fetch_ms: Option<i64>,
+}
+
+/// A row of `feed_fetch_log`; `error` is set for failed fetches.
+#[derive(Debug, serde::Serialize)]
+pub struct FeedFetchRecord {
+    pub log_id: i64,
+    pub fetched_at: String,
+    pub item_count: i64,
+    pub fetch_ms: i64,
+    pub error: Option<String>,
+}
+
+/// One row of `EXPLAIN QUERY PLAN`.
+#[derive(Debug, serde::Serialize)]
+pub struct QueryPlanStep {
+    pub id: i64,
+    pub parent: i64,
+    pub notused: i64,
+    pub detail: String,
+}
+
+/// An index from `pragma_index_list`. `origin` is "c" (CREATE INDEX), "u" (UNIQUE
+/// constraint) or "pk".
+#[derive(Debug, serde::Serialize)]
+pub struct IndexInfo {
+    pub name: String,
+    pub unique: bool,
+    pub origin: String,
+    pub partial: bool,
+    pub columns: Vec<String>,
+    pub size_bytes: i64,
+}
+
+/// Names `Db::explain_query_plan` accepts.
+pub const EXPLAIN_QUERIES: [&str; 5] = ["articles_by_category", "search", "popular", "fresh", "trending"];
+
+/// The SQL behind each of `EXPLAIN_QUERIES`, in the shape the matching Db method
+/// runs it (article list, search, popular, fresh and trending).
+fn explain_query_sql(query_name: &str) -> Option<String> {
+    const COLUMNS: &str = "id, category, title, url, description, image_url, source,
+                    published_at, fetched_at, group_id, group_count, word_count, reading_minutes";
+    let sql = match query_name {
+        "articles_by_category" => format!(
+            "SELECT {COLUMNS} FROM articles
+             WHERE category = :cat AND dead_link != 1
+             ORDER BY published_at DESC, id DESC LIMIT :lim"
+        ),
+        "search" => format!(
+            "SELECT {COLUMNS} FROM articles
+             WHERE {} ORDER BY published_at DESC LIMIT ?2",
+            search_condition("articles", "?1")
+        ),
+        "popular" => format!(
+            "SELECT {COLUMNS} FROM articles
+             WHERE popularity_score > 0
+             ORDER BY popularity_score DESC, published_at DESC LIMIT ?1 OFFSET ?2"
+        ),
+        "fresh" => format!(
+            "SELECT {COLUMNS} FROM articles
+             WHERE published_at >= ?1 AND (?3 OR dead_link != 1)
+             ORDER BY published_at DESC LIMIT ?2"
+        ),
+        "trending" => format!(
+            "SELECT {COLUMNS} FROM articles
+             WHERE (?1 IS NULL OR category = ?1) AND (?2 IS NULL OR published_at >= ?2)
+               AND (?5 OR dead_link != 1)
+             ORDER BY popularity_score DESC, published_at DESC, id DESC LIMIT ?3 OFFSET ?4"
+        ),
+        _ => return None,
+    };
+    Some(sql)
+}
+
+/// Fetches averaged into `avg_fetch_ms`.
+const FEED_STATS_WINDOW: i64 = 30;
+/// Log rows kept per feed.
+const FEED_LOG_KEEP: i64 = 200;
+
+/// A saved search with the number of matches its owner hasn't opened yet.
+#[derive(Debug, serde::Serialize)]
+pub struct SavedSearch {
+    pub id: String,
+    pub query: String,
+    pub category_filter: Option<String>,
+    pub created_at: String,
+    pub last_notified_at: Option<String>,
+    pub unseen: i64,
+}
+
+/// A stored translation of an article into one language.
+#[derive(Debug, serde::Serialize)]
+pub struct StoredTranslation {
+    pub language: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub source_lang: Option<String>,
+    pub translated_at: String,
+    /// Model that produced it; None for translations stored before this was recorded.
+    pub model: Option<String>,
+    /// Translated body; only `POST /api/articles/translate` stores one. Left out of
+    /// listings, which stay title and description only.
+    #[serde(skip)]
+    pub content: Option<String>,
+}
+
+/// A stored chat. `/api/chat` sessions carry the caller's device; article chats
+/// carry an `owner` key ("user:…" or "device:…") instead.
+#[derive(Debug)]
+pub struct ChatSession {
+    pub device_id: Option<String>,
+    pub owner: Option<String>,
+    pub article_id: Option<String>,
+    pub messages_json: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Article match rule of `/api/search`, shared with saved searches. `pattern` is an SQL
+/// expression for a LIKE pattern escaped with `\` (see `like_pattern`).
+fn search_condition(table: &str, pattern: &str) -> String {
+    format!(r"({table}.title LIKE {pattern} ESCAPE '\' OR {table}.description LIKE {pattern} ESCAPE '\')")
+}
+
+/// `%text%` for `LIKE … ESCAPE '\'`, with `%` and `_` in `text` matched literally.
+fn like_pattern(text: &str) -> String {
+    let escaped = text.replace('\\', r"\\").replace('%', r"\%").replace('_', r"\_");
+    format!("%{escaped}%")
+}
+
+/// SQL counterpart of `like_pattern` for a column or expression.
+fn sql_like_pattern(expr: &str) -> String {
+    format!(r"'%' || replace(replace(replace({expr}, '\', '\\'), '%', '\%'), '_', '\_') || '%'")
 }
 
+/// Time-independent part of `score_importance` with the default weights (mirrors
+/// `ImportanceWeights::default` and `POPULARITY_HALF_SCORE`). Generated
+/// columns can't depend on the current time, so recency is added at query time.
+const COMPOUND_SCORE_EXPR: &str = "COALESCE(ai_importance, 0.5) * 0.4 \
+     + MAX(popularity_score, 0.0) / (MAX(popularity_score, 0.0) + 10.0) * 0.3";
+
+/// Articles used as the document-frequency corpus for TF-IDF keywords.
+pub const KEYWORDS_CORPUS_SIZE: i64 = 500;
+
+/// Rows per multi-row INSERT in `batch_insert_articles` (13 parameters each).
+const INSERT_BATCH_SIZE: usize = 100;
+
+/// Article age in hours, for `recency_decay(...)` in ORDER BY clauses.
+const AGE_HOURS_EXPR: &str = "(julianday('now') - julianday(published_at)) * 24.0";
+
+/// popularity_score as a function of the stored counters.
+const POPULARITY_EXPR: &str = "view_count * 0.7 + click_count * 0.3";
+
 impl Db {
     pub fn open(path: &str) -> Result<Self, String> {
         let conn = Connection::open(path).map_err(|e| format!("SQLite open: {e}"))?;
         conn.execute_batch(
             "PRAGMA journal_mode=WAL;
-             PRAGMA busy_timeout=5000;
              PRAGMA synchronous=NORMAL;
              PRAGMA foreign_keys=ON;",
         )
         .map_err(|e| format!("SQLite pragma: {e}"))?;
+        conn.busy_handler(Some(busy_handler))
+            .map_err(|e| format!("SQLite busy handler: {e}"))?;
 
         // Migration: Add missing columns to existing articles table before schema creation.
         // CREATE TABLE IF NOT EXISTS won't add new columns to an existing table,
@@ -75,7 +382,8 @@ impl Db {
                 ai_sentiment TEXT,
                 ai_importance REAL,
                 ai_category TEXT,
-                analyzed_at TEXT
+                analyzed_at TEXT,
+                canonical_url TEXT
             );
             CREATE INDEX IF NOT EXISTS idx_articles_cat_pub
                 ON articles(category, published_at DESC);
@@ -85,6 +393,10 @@ impl Db {
                 ON articles(popularity_score DESC, published_at DESC);
             CREATE INDEX IF NOT EXISTS idx_articles_enrichment_status
                 ON articles(enrichment_status);
+            CREATE INDEX IF NOT EXISTS idx_articles_source_pub
+                ON articles(source, published_at DESC);
+            CREATE INDEX IF NOT EXISTS idx_articles_group
+                ON articles(group_id, published_at DESC);
 
             CREATE TABLE IF NOT EXISTS feeds (
                 feed_id TEXT PRIMARY KEY,
@@ -92,7 +404,59 @@ impl Db {
                 source TEXT NOT NULL,
                 category TEXT NOT NULL,
                 enabled INTEGER NOT NULL DEFAULT 1,
-                added_by TEXT
+                added_by TEXT,
+                max_articles_per_fetch INTEGER,
+                auto_translate INTEGER NOT NULL DEFAULT 0
+            );
+
+            CREATE TABLE IF NOT EXISTS feed_fetch_log (
+                log_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                feed_id TEXT NOT NULL,
+                fetched_at TEXT NOT NULL,
+                item_count INTEGER NOT NULL DEFAULT 0,
+                fetch_ms INTEGER NOT NULL,
+                error TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_feed_fetch_log_feed
+                ON feed_fetch_log(feed_id, log_id);
+
+            CREATE TABLE IF NOT EXISTS admin_audit_log (
+                log_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                action TEXT NOT NULL,
+                details TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS sites (
+                host TEXT PRIMARY KEY,
+                site_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                title TEXT NOT NULL,
+                description TEXT NOT NULL,
+                description_long TEXT,
+                url TEXT NOT NULL,
+                image TEXT NOT NULL,
+                theme_color TEXT NOT NULL,
+                lang TEXT NOT NULL,
+                keywords TEXT NOT NULL DEFAULT '',
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS source_meta (
+                source TEXT PRIMARY KEY,
+                source_type TEXT NOT NULL DEFAULT 'unknown',
+                credibility_tier INTEGER,
+                homepage_url TEXT,
+                logo_url TEXT,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS ab_test_assignment (
+                device_id TEXT NOT NULL,
+                test_name TEXT NOT NULL,
+                variant TEXT NOT NULL,
+                assigned_at TEXT NOT NULL,
+                PRIMARY KEY (device_id, test_name)
             );
 
             CREATE TABLE IF NOT EXISTS features (
@@ -107,7 +471,8 @@ impl Db {
                 command_text TEXT NOT NULL,
                 interpretation TEXT NOT NULL DEFAULT '',
                 actions_json TEXT NOT NULL DEFAULT '[]',
-                created_at TEXT NOT NULL
+                created_at TEXT NOT NULL,
+                preview_diff_json TEXT
             );
 
             CREATE TABLE IF NOT EXISTS categories (
@@ -118,6 +483,12 @@ impl Db {
                 visible INTEGER NOT NULL DEFAULT 1
             );
 
+            -- Former category ids (renamed or removed) and where they point now
+            CREATE TABLE IF NOT EXISTS category_aliases (
+                alias TEXT PRIMARY KEY,
+                category_id TEXT NOT NULL
+            );
+
             CREATE TABLE IF NOT EXISTS subscriptions (
                 api_token TEXT PRIMARY KEY,
                 stripe_customer_id TEXT NOT NULL,
@@ -131,6 +502,12 @@ impl Db {
             CREATE INDEX IF NOT EXISTS idx_subs_stripe_cust_id
                 ON subscriptions(stripe_customer_id);
 
+            CREATE TABLE IF NOT EXISTS webhook_events (
+                event_id TEXT PRIMARY KEY,
+                event_type TEXT NOT NULL,
+                processed_at TEXT NOT NULL
+            );
+
             CREATE TABLE IF NOT EXISTS usage_limits (
                 device_id TEXT NOT NULL,
                 feature TEXT NOT NULL,
@@ -139,6 +516,39 @@ impl Db {
                 PRIMARY KEY (device_id, feature, used_date)
             );
 
+            CREATE TABLE IF NOT EXISTS chat_sessions (
+                session_id TEXT PRIMARY KEY,
+                device_id TEXT,
+                article_id TEXT,
+                messages_json TEXT NOT NULL DEFAULT '[]',
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS views_dedup (
+                article_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                visitor TEXT NOT NULL,
+                day TEXT NOT NULL,
+                count INTEGER NOT NULL DEFAULT 0,
+                last_at TEXT NOT NULL,
+                PRIMARY KEY (article_id, kind, visitor, day)
+            );
+
+            CREATE TABLE IF NOT EXISTS shortlinks (
+                short_code TEXT PRIMARY KEY,
+                article_id TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                clicks INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_shortlinks_article ON shortlinks(article_id);
+
+            CREATE TABLE IF NOT EXISTS view_events (
+                article_id TEXT NOT NULL,
+                viewed_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_view_events_viewed_at ON view_events(viewed_at);
+
             CREATE TABLE IF NOT EXISTS ai_cache (
                 cache_key TEXT PRIMARY KEY,
                 endpoint TEXT NOT NULL,
@@ -149,6 +559,15 @@ impl Db {
             CREATE INDEX IF NOT EXISTS idx_ai_cache_expires
                 ON ai_cache(expires_at);
 
+            -- Rendered pages built from fetched article content (reader mode)
+            CREATE TABLE IF NOT EXISTS content_cache (
+                cache_key TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                expires_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_content_cache_expires
+                ON content_cache(expires_at);
+
             CREATE TABLE IF NOT EXISTS users (
                 id TEXT PRIMARY KEY,
                 email TEXT NOT NULL UNIQUE,
@@ -159,7 +578,9 @@ impl Db {
                 device_id TEXT,
                 konami_claimed INTEGER NOT NULL DEFAULT 0,
                 created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
+                updated_at TEXT NOT NULL,
+                auth_token_expires_at TEXT NOT NULL
+                    DEFAULT (strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now', '+30 days'))
             );
             CREATE INDEX IF NOT EXISTS idx_users_auth_token ON users(auth_token);
 
@@ -176,7 +597,72 @@ impl Db {
                 FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
             );
             CREATE INDEX IF NOT EXISTS idx_enrichments_article
-                ON enrichments(article_id, status);",
+                ON enrichments(article_id, status);
+
+            CREATE TABLE IF NOT EXISTS article_tags (
+                article_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (article_id, tag),
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_article_tags_tag ON article_tags(tag);
+
+            CREATE TABLE IF NOT EXISTS article_entities (
+                article_id TEXT NOT NULL,
+                entity_text TEXT NOT NULL COLLATE NOCASE,
+                entity_type TEXT NOT NULL,
+                PRIMARY KEY (article_id, entity_text),
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_article_entities_text ON article_entities(entity_text);
+
+            CREATE TABLE IF NOT EXISTS translations (
+                article_id TEXT NOT NULL,
+                lang TEXT NOT NULL,
+                title TEXT NOT NULL,
+                description TEXT,
+                source_lang TEXT,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (article_id, lang),
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS saved_searches (
+                id TEXT PRIMARY KEY,
+                owner_id TEXT NOT NULL,
+                query TEXT NOT NULL,
+                category_filter TEXT,
+                created_at TEXT NOT NULL,
+                last_notified_at TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_saved_searches_owner ON saved_searches(owner_id);
+
+            CREATE TABLE IF NOT EXISTS mute_rules (
+                id TEXT PRIMARY KEY,
+                owner_id TEXT NOT NULL,
+                pattern TEXT NOT NULL,
+                field TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_mute_rules_owner ON mute_rules(owner_id);
+
+            CREATE TABLE IF NOT EXISTS search_matches (
+                search_id TEXT NOT NULL,
+                article_id TEXT NOT NULL,
+                matched_at TEXT NOT NULL,
+                seen INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (search_id, article_id),
+                FOREIGN KEY (search_id) REFERENCES saved_searches(id) ON DELETE CASCADE,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS maintenance_runs (
+                step TEXT PRIMARY KEY,
+                last_run_at TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                rows_affected INTEGER,
+                error TEXT
+            );",
         )
         .map_err(|e| format!("SQLite schema: {e}"))?;
 
@@ -201,20 +687,309 @@ impl Db {
             info!("Migration complete: AI analysis columns added");
         }
 
+        // Migration: Add per-feed fetch quota column
+        let has_quota: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('feeds') WHERE name='max_articles_per_fetch'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_quota {
+            info!("Running migration: Adding max_articles_per_fetch to feeds table");
+            let _ = conn.execute_batch("ALTER TABLE feeds ADD COLUMN max_articles_per_fetch INTEGER;");
+        }
+
+        // Migration: per-feed auto-translation flag
+        let has_auto_translate: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('feeds') WHERE name='auto_translate'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_auto_translate {
+            info!("Running migration: Adding auto_translate to feeds table");
+            let _ = conn.execute_batch("ALTER TABLE feeds ADD COLUMN auto_translate INTEGER NOT NULL DEFAULT 0;");
+        }
+
+        // Migration: which model produced each stored translation
+        let has_translation_model: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('translations') WHERE name='model'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_translation_model {
+            info!("Running migration: Adding model to translations table");
+            let _ = conn.execute_batch("ALTER TABLE translations ADD COLUMN model TEXT;");
+        }
+
+        // Migration: translated article body, kept by POST /api/articles/translate
+        let has_translation_content: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('translations') WHERE name='content'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_translation_content {
+            info!("Running migration: Adding content to translations table");
+            let _ = conn.execute_batch("ALTER TABLE translations ADD COLUMN content TEXT;");
+        }
+
+        // Migration: per-feed keyword → category rules, as JSON
+        let has_category_overrides: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('feeds') WHERE name='category_overrides'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_category_overrides {
+            info!("Running migration: Adding category_overrides to feeds table");
+            let _ = conn.execute_batch("ALTER TABLE feeds ADD COLUMN category_overrides TEXT;");
+        }
+
+        // Migration: per-feed freshness stats
+        let has_feed_stats: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('feeds') WHERE name='last_fetched_at'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_feed_stats {
+            info!("Running migration: Adding fetch stats to feeds table");
+            let _ = conn.execute_batch(
+                "ALTER TABLE feeds ADD COLUMN last_fetched_at TEXT;
+                 ALTER TABLE feeds ADD COLUMN last_item_count INTEGER;
+                 ALTER TABLE feeds ADD COLUMN avg_fetch_ms INTEGER;",
+            );
+        }
+
+        // Migration: preview diff stored with change requests
+        let has_preview_diff: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('changes') WHERE name='preview_diff_json'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_preview_diff {
+            info!("Running migration: Adding preview_diff_json to changes table");
+            let _ = conn.execute_batch("ALTER TABLE changes ADD COLUMN preview_diff_json TEXT;");
+        }
+
+        // Migration: validation results stored with change requests
+        let has_validation: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('changes') WHERE name='validation_json'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_validation {
+            info!("Running migration: Adding validation_json to changes table");
+            let _ = conn.execute_batch("ALTER TABLE changes ADD COLUMN validation_json TEXT;");
+        }
+
+        // Migration: revisions of rejected changes
+        let has_change_version: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('changes') WHERE name='version'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_change_version {
+            info!("Running migration: Adding parent_change_id and version to changes table");
+            let _ = conn.execute_batch(
+                "ALTER TABLE changes ADD COLUMN parent_change_id TEXT;
+                 ALTER TABLE changes ADD COLUMN version INTEGER NOT NULL DEFAULT 1;",
+            );
+        }
+
+        // Migration: auth tokens expire; existing tokens get a fresh 30-day window
+        let has_token_expiry: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('users') WHERE name='auth_token_expires_at'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_token_expiry {
+            info!("Running migration: Adding auth_token_expires_at to users table");
+            let _ = conn.execute_batch(
+                "ALTER TABLE users ADD COLUMN auth_token_expires_at TEXT NOT NULL DEFAULT '';
+                 UPDATE users SET auth_token_expires_at = strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now', '+30 days')
+                    WHERE auth_token_expires_at = '';",
+            );
+        }
+
+        // Migration: canonical_url for exact-duplicate detection, backfilled from url.
+        // Rows whose normalized URL already exists keep NULL (UPDATE OR IGNORE).
+        let has_canonical: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name='canonical_url'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_canonical {
+            info!("Running migration: Adding canonical_url to articles table");
+            let _ = conn.execute_batch("ALTER TABLE articles ADD COLUMN canonical_url TEXT;");
+        }
+        conn.execute_batch(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_articles_canonical_url ON articles(canonical_url);",
+        )
+        .map_err(|e| format!("SQLite canonical_url index: {e}"))?;
+        if !has_canonical {
+            let rows: Vec<(String, String)> = {
+                let mut stmt = conn
+                    .prepare("SELECT id, url FROM articles WHERE canonical_url IS NULL")
+                    .map_err(|e| format!("Backfill canonical_url: {e}"))?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .map_err(|e| format!("Backfill canonical_url: {e}"))?;
+                rows.filter_map(|r| r.ok()).collect()
+            };
+            for (id, url) in &rows {
+                let _ = conn.execute(
+                    "UPDATE OR IGNORE articles SET canonical_url = ?1 WHERE id = ?2",
+                    params![news_core::dedup::normalize_url(url), id],
+                );
+            }
+            info!(rows = rows.len(), "Migration complete: canonical_url backfilled");
+        }
+
+        // Migration: compound_score generated column (also how new databases get it, so
+        // the expression lives in one place). Generated columns are only listed by
+        // pragma_table_xinfo.
+        let has_compound: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_xinfo('articles') WHERE name='compound_score'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_compound {
+            conn.execute_batch(&format!(
+                "ALTER TABLE articles ADD COLUMN compound_score REAL
+                 GENERATED ALWAYS AS ({COMPOUND_SCORE_EXPR}) VIRTUAL;"
+            ))
+            .map_err(|e| format!("Migration compound_score: {e}"))?;
+        }
+
+        // Migration: dead-link flag kept by `link_checker`. 1 = the URL returned 404/410,
+        // -1 = un-flagged by an admin (never re-checked), 0 = fine or not checked yet.
+        let has_dead_link: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name='dead_link'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_dead_link {
+            conn.execute_batch(
+                "ALTER TABLE articles ADD COLUMN dead_link INTEGER NOT NULL DEFAULT 0;
+                 ALTER TABLE articles ADD COLUMN link_checked_at TEXT;",
+            )
+            .map_err(|e| format!("Migration dead_link: {e}"))?;
+        }
+
+        // Migration: reading-time estimate, filled at insert and backfilled by
+        // `backfill_reading_time`
+        let has_reading_time: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name='word_count'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_reading_time {
+            info!("Running migration: Adding word_count and reading_minutes to articles table");
+            conn.execute_batch(
+                "ALTER TABLE articles ADD COLUMN word_count INTEGER;
+                 ALTER TABLE articles ADD COLUMN reading_minutes INTEGER;",
+            )
+            .map_err(|e| format!("Migration reading time: {e}"))?;
+        }
+
+        // Migration: reversible image degradation. A degraded article keeps its image
+        // in original_image_url; image_restored_at is set once popularity brings it back.
+        let has_original_image: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name='original_image_url'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_original_image {
+            info!("Running migration: Adding original_image_url and image_restored_at to articles table");
+            conn.execute_batch(
+                "ALTER TABLE articles ADD COLUMN original_image_url TEXT;
+                 ALTER TABLE articles ADD COLUMN image_restored_at TEXT;",
+            )
+            .map_err(|e| format!("Migration image degradation: {e}"))?;
+        }
+        // Only rows with image_degraded_at are put back, so image-less articles are
+        // never counted as restored
+        let has_degraded_at: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name='image_degraded_at'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_degraded_at {
+            info!("Running migration: Adding image_degraded_at to articles table");
+            conn.execute_batch(
+                "ALTER TABLE articles ADD COLUMN image_degraded_at TEXT;
+                 UPDATE articles SET image_degraded_at = fetched_at WHERE original_image_url IS NOT NULL;",
+            )
+            .map_err(|e| format!("Migration image degraded_at: {e}"))?;
+        }
+
+        // Migration: article chats are chat_sessions with an owner. They used to have
+        // tables of their own; those only held a day of chats and are dropped.
+        let has_chat_owner: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('chat_sessions') WHERE name='owner'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_chat_owner {
+            info!("Running migration: Adding owner to chat_sessions table");
+            conn.execute_batch(
+                "ALTER TABLE chat_sessions ADD COLUMN owner TEXT;
+                 DROP TABLE IF EXISTS conversation_messages;
+                 DROP TABLE IF EXISTS conversations;",
+            )
+            .map_err(|e| format!("Migration chat owner: {e}"))?;
+        }
+        conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_chat_sessions_owner ON chat_sessions(owner, updated_at);")
+            .map_err(|e| format!("Migration chat owner index: {e}"))?;
+
+        // Migration: the feed's own link when ingest replaced it (a redirector or
+        // tracking parameters). Ids of earlier articles stay derived from that link.
+        let has_original_url: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name='original_url'",
+            [],
+            |row| row.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if !has_original_url {
+            info!("Running migration: Adding original_url to articles table");
+            conn.execute_batch(
+                "ALTER TABLE articles ADD COLUMN original_url TEXT;
+                 CREATE INDEX IF NOT EXISTS idx_articles_original_url
+                     ON articles(original_url) WHERE original_url IS NOT NULL;",
+            )
+            .map_err(|e| format!("Migration original_url: {e}"))?;
+        }
+
+        conn.create_scalar_function(
+            "recency_decay",
+            1,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx| Ok(recency_decay(ctx.get::<f64>(0)?)),
+        )
+        .map_err(|e| format!("SQLite recency_decay: {e}"))?;
+
         info!(path, "SQLite database opened");
         Ok(Self {
             conn: Mutex::new(conn),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
         })
     }
 
     // --- Articles ---
 
+    /// Insert one article, ignoring it if the id or normalized URL is already stored.
+    /// Returns false if it already existed. Everything outside tests goes through
+    /// `batch_insert_articles`.
+    #[cfg(test)]
     pub fn insert_article(&self, article: &Article) -> Result<bool, String> {
+        let canonical_url = article
+            .canonical_url
+            .clone()
+            .unwrap_or_else(|| news_core::dedup::normalize_url(&article.url));
+        let reading = description_reading_time(article.description.as_deref());
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let result = conn.execute(
             "INSERT OR IGNORE INTO articles
-                (id, category, title, url, description, image_url, source, published_at, fetched_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                (id, category, title, url, description, image_url, source, published_at, fetched_at, canonical_url,
+                 word_count, reading_minutes, original_url)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
             params![
                 article.id,
                 article.category.as_str(),
@@ -225,6 +1000,10 @@ impl Db {
                 article.source,
                 article.published_at.to_rfc3339(),
                 article.fetched_at.to_rfc3339(),
+                canonical_url,
+                reading.map(|r| r.word_count),
+                reading.map(|r| r.reading_minutes),
+                article.original_url,
             ],
         );
         match result {
@@ -233,40 +1012,372 @@ impl Db {
         }
     }
 
-    pub fn insert_articles(&self, articles: &[Article]) -> Result<usize, String> {
+    /// Insert many articles, 100 per multi-row `INSERT OR IGNORE`, all inside one
+    /// `BEGIN IMMEDIATE` transaction. Returns how many were new.
+    ///
+    /// Versus one statement per row (one implicit commit and one lock round-trip each),
+    /// 1 000 articles on a file-backed WAL database went from ~110 ms to ~15 ms
+    /// (6.5–7.7×) in a release build; see `tests::batch_insert_speedup`. That falls
+    /// short of 10× because with synchronous=NORMAL a WAL commit doesn't fsync, so the
+    /// per-row commits being saved are cheap to begin with.
+    #[tracing::instrument(skip_all, fields(count = articles.len()))]
+    pub fn batch_insert_articles(&self, articles: &[Article]) -> Result<usize, String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn
+            .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+            .map_err(|e| format!("Batch insert: {e}"))?;
+
         let mut inserted = 0;
-        for a in articles {
-            if self.insert_article(a)? {
-                inserted += 1;
+        for chunk in articles.chunks(INSERT_BATCH_SIZE) {
+            let placeholders = vec!["(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"; chunk.len()].join(", ");
+            let sql = format!(
+                "INSERT OR IGNORE INTO articles
+                    (id, category, title, url, description, image_url, source, published_at, fetched_at, canonical_url,
+                     word_count, reading_minutes, original_url)
+                 VALUES {placeholders}"
+            );
+            let mut values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::with_capacity(chunk.len() * 13);
+            for a in chunk {
+                values.push(Box::new(a.id.clone()));
+                values.push(Box::new(a.category.as_str()));
+                values.push(Box::new(a.title.clone()));
+                values.push(Box::new(a.url.clone()));
+                values.push(Box::new(a.description.clone()));
+                values.push(Box::new(a.image_url.clone()));
+                values.push(Box::new(a.source.clone()));
+                values.push(Box::new(a.published_at.to_rfc3339()));
+                values.push(Box::new(a.fetched_at.to_rfc3339()));
+                values.push(Box::new(
+                    a.canonical_url
+                        .clone()
+                        .unwrap_or_else(|| news_core::dedup::normalize_url(&a.url)),
+                ));
+                let reading = description_reading_time(a.description.as_deref());
+                values.push(Box::new(reading.map(|r| r.word_count)));
+                values.push(Box::new(reading.map(|r| r.reading_minutes)));
+                values.push(Box::new(a.original_url.clone()));
             }
+            inserted += tx
+                .execute(&sql, rusqlite::params_from_iter(values.iter()))
+                .map_err(|e| format!("Batch insert: {e}"))?;
         }
+        tx.commit().map_err(|e| format!("Batch insert commit: {e}"))?;
         Ok(inserted)
     }
 
-    pub fn update_image_url(&self, article_id: &str, image_url: &str) -> Result<(), String> {
+    /// Which of freshly parsed `articles` are already stored: (ids stored as they are,
+    /// ids whose feed link was canonicalized into a stored article with another id).
+    /// Ingest leaves the first alone so ids made before canonicalization stay put, and
+    /// drops the second rather than resolving their links again every fetch.
+    pub fn stored_feed_links(&self, articles: &[Article]) -> Result<(HashSet<String>, HashSet<String>), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT EXISTS(SELECT 1 FROM articles WHERE id = ?1),
+                        EXISTS(SELECT 1 FROM articles WHERE original_url = ?2)",
+            )
+            .map_err(|e| format!("Stored feed links: {e}"))?;
+        let mut stored = HashSet::new();
+        let mut replaced = HashSet::new();
+        for a in articles {
+            let (by_id, by_link): (bool, bool) = stmt
+                .query_row(params![a.id, a.url], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(|e| format!("Stored feed links: {e}"))?;
+            if by_id {
+                stored.insert(a.id.clone());
+            } else if by_link {
+                replaced.insert(a.id.clone());
+            }
+        }
+        Ok((stored, replaced))
+    }
+
+    /// Re-estimate an article's reading time from fuller extracted `text`. Only ever
+    /// raises it, so a short or failed extraction doesn't undercut the description.
+    pub fn update_reading_time(&self, article_id: &str, text: &str) -> Result<(), String> {
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+        let reading = reading_time::estimate(text);
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         conn.execute(
-            "UPDATE articles SET image_url = ?1 WHERE id = ?2",
+            "UPDATE articles SET word_count = ?2, reading_minutes = ?3
+             WHERE id = ?1 AND (word_count IS NULL OR word_count < ?2)",
+            params![article_id, reading.word_count, reading.reading_minutes],
+        )
+        .map_err(|e| format!("Update reading time: {e}"))?;
+        Ok(())
+    }
+
+    /// Estimate reading time for up to `batch` articles stored before it was recorded.
+    /// Returns how many were filled; 0 once every article with a description has one.
+    pub fn backfill_reading_time(&self, batch: i64) -> Result<usize, String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let rows: Vec<(String, String)> = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, description FROM articles
+                     WHERE word_count IS NULL AND TRIM(COALESCE(description, '')) != ''
+                     LIMIT ?1",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![batch], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(|e| format!("Backfill reading time: {e}"))?
+                .filter_map(|r| r.ok())
+                .collect();
+            rows
+        };
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        for (id, description) in &rows {
+            let reading = reading_time::estimate(description);
+            tx.execute(
+                "UPDATE articles SET word_count = ?2, reading_minutes = ?3 WHERE id = ?1",
+                params![id, reading.word_count, reading.reading_minutes],
+            )
+            .map_err(|e| format!("Backfill reading time: {e}"))?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(rows.len())
+    }
+
+    /// Which of `ids` were first stored at or after `since`; ids a batch insert ignored
+    /// as duplicates keep their original `fetched_at` and are left out.
+    pub fn fetched_since(&self, ids: &[&str], since: &DateTime<Utc>) -> Result<std::collections::HashSet<String>, String> {
+        let since = since.to_rfc3339();
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut fresh = std::collections::HashSet::new();
+        for chunk in ids.chunks(INSERT_BATCH_SIZE) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let mut stmt = conn
+                .prepare(&format!("SELECT id FROM articles WHERE fetched_at >= ? AND id IN ({placeholders})"))
+                .map_err(|e| e.to_string())?;
+            let values = std::iter::once(since.as_str()).chain(chunk.iter().copied());
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(values), |row| row.get::<_, String>(0))
+                .map_err(|e| format!("Fetched since: {e}"))?;
+            fresh.extend(rows.filter_map(|r| r.ok()));
+        }
+        Ok(fresh)
+    }
+
+    /// Recompute title-similarity groups per category over articles published since
+    /// `since` and store `group_id`/`group_count` on them (see
+    /// `grouping::assign_groups`). Returns how many articles ended up in a group.
+    ///
+    /// The clustering runs without holding the connection lock; only the rows read
+    /// up front are rewritten, so articles stored in between keep their groups.
+    pub fn regroup_articles(&self, since: &DateTime<Utc>, threshold: f64) -> Result<usize, String> {
+        let since = since.to_rfc3339();
+        let rows: Vec<(String, String, String, String)> = {
+            let conn = self.conn.lock().map_err(|e| e.to_string())?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, category, title, published_at FROM articles
+                     WHERE published_at >= ?1",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![since], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                })
+                .map_err(|e| e.to_string())?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+
+        let mut by_category: HashMap<&str, Vec<(&str, &str, &str)>> = HashMap::new();
+        for (id, category, title, published_at) in &rows {
+            by_category
+                .entry(category)
+                .or_default()
+                .push((id, title, published_at));
+        }
+        let mut updates: Vec<(&str, Option<(String, u32)>)> = Vec::with_capacity(rows.len());
+        for items in by_category.values() {
+            let assigned = grouping::assign_groups(items, threshold);
+            updates.extend(items.iter().map(|(id, _, _)| *id).zip(assigned));
+        }
+
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn
+            .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+            .map_err(|e| format!("Regroup: {e}"))?;
+        let mut grouped = 0;
+        for (id, group) in updates {
+            let (group_id, count) = group.unzip();
+            grouped += usize::from(group_id.is_some());
+            tx.execute(
+                "UPDATE articles SET group_id = ?1, group_count = ?2 WHERE id = ?3",
+                params![group_id, count, id],
+            )
+            .map_err(|e| format!("Regroup: {e}"))?;
+        }
+        tx.commit().map_err(|e| format!("Regroup commit: {e}"))?;
+        Ok(grouped)
+    }
+
+    /// `(id, category, title, group_id, group_count)` of articles published since
+    /// `since`, oldest first, for rebuilding the incremental grouping state.
+    #[allow(clippy::type_complexity)]
+    pub fn grouping_window(
+        &self,
+        since: &DateTime<Utc>,
+    ) -> Result<Vec<(String, String, String, Option<String>, Option<u32>)>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, category, title, group_id, group_count FROM articles
+                 WHERE published_at >= ?1
+                 ORDER BY published_at, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![since.to_rfc3339()], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Store the result of incremental grouping: `members` are `(article_id, group_id)`
+    /// for newly grouped articles, `groups` the new size of every group that grew. A
+    /// group's representative (`grp-{id}`) is included even if it was a singleton.
+    pub fn apply_group_changes(&self, members: &[(String, String)], groups: &[(String, u32)]) -> Result<(), String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn
+            .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+            .map_err(|e| format!("Group changes: {e}"))?;
+        for (article_id, group_id) in members {
+            tx.execute(
+                "UPDATE articles SET group_id = ?1 WHERE id = ?2",
+                params![group_id, article_id],
+            )
+            .map_err(|e| format!("Group changes: {e}"))?;
+        }
+        for (group_id, count) in groups {
+            let rep_id = group_id.strip_prefix("grp-").unwrap_or(group_id);
+            tx.execute(
+                "UPDATE articles SET group_id = ?1, group_count = ?2 WHERE group_id = ?1 OR id = ?3",
+                params![group_id, count, rep_id],
+            )
+            .map_err(|e| format!("Group changes: {e}"))?;
+        }
+        tx.commit().map_err(|e| format!("Group changes commit: {e}"))?;
+        Ok(())
+    }
+
+    pub fn update_image_url(&self, article_id: &str, image_url: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE articles SET image_url = ?1 WHERE id = ?2",
             params![image_url, article_id],
         )
         .map_err(|e| format!("Update image: {e}"))?;
         Ok(())
     }
 
+    /// Newest articles first. Flagged dead links are left out unless `include_dead`.
+    #[tracing::instrument(skip(self))]
     pub fn query_articles(
         &self,
         category: Option<&Category>,
-        limit: i64,
-        cursor: Option<&str>,
-    ) -> Result<(Vec<Article>, Option<String>), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        page: &Paginator,
+        include_dead: bool,
+    ) -> Result<Page<Article>, String> {
+        self.query_articles_inner(category, page, false, include_dead, &ArticleFilter::default())
+    }
+
+    /// `query_articles` narrowed by `filter`; the cursor pages within it. With
+    /// `collapse_groups`, each stored group is collapsed to one member, which carries
+    /// the group's `group_count`: the one from the most credible source (see
+    /// `SourceMeta::credibility_rank`), newest on ties. Other members never show up
+    /// on a later page.
+    pub fn query_filtered_articles(
+        &self,
+        category: Option<&Category>,
+        filter: &ArticleFilter,
+        page: &Paginator,
+        collapse_groups: bool,
+        include_dead: bool,
+    ) -> Result<Page<Article>, String> {
+        self.query_articles_inner(category, page, collapse_groups, include_dead, filter)
+    }
 
-        let (cursor_pub, cursor_id) = match cursor {
-            Some(c) => decode_cursor(c).unwrap_or((String::new(), String::new())),
-            None => (String::new(), String::new()),
+    /// `query_articles` (grouped with `collapse_groups`) limited to articles published
+    /// between `from` and `to`, both inclusive. The cursor pages within the range.
+    pub fn query_articles_between(
+        &self,
+        category: Option<&Category>,
+        range: (&DateTime<Utc>, &DateTime<Utc>),
+        page: &Paginator,
+        collapse_groups: bool,
+        include_dead: bool,
+    ) -> Result<Page<Article>, String> {
+        let filter = ArticleFilter {
+            range: Some((*range.0, *range.1)),
+            ..Default::default()
         };
+        self.query_articles_inner(category, page, collapse_groups, include_dead, &filter)
+    }
+
+    /// Articles per UTC day published in `[from, to)`, as ("YYYY-MM-DD", count) in
+    /// date order. Days without articles are omitted; dead links aren't counted.
+    pub fn count_articles_by_day(
+        &self,
+        from: &DateTime<Utc>,
+        to: &DateTime<Utc>,
+        category: Option<&Category>,
+    ) -> Result<Vec<(String, i64)>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT date(published_at) AS day, COUNT(*) FROM articles
+                 WHERE published_at >= ?1 AND published_at < ?2 AND dead_link != 1
+                   AND (?3 IS NULL OR category = ?3)
+                 GROUP BY day ORDER BY day",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(
+                params![from.to_rfc3339(), to.to_rfc3339(), category.map(|c| c.as_str())],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| format!("Count articles by day: {e}"))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    fn query_articles_inner(
+        &self,
+        category: Option<&Category>,
+        page: &Paginator,
+        collapse_groups: bool,
+        include_dead: bool,
+        filter: &ArticleFilter,
+    ) -> Result<Page<Article>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+
+        let (cursor_pub, cursor_id) = page.keyset().unwrap_or_default();
         let has_cursor = !cursor_pub.is_empty();
-        let fetch_limit = limit + 1;
+        let fetch_limit = page.limit + 1;
+
+        let rank = |meta: &str| format!("COALESCE({meta}.credibility_tier, {})", MAX_CREDIBILITY_TIER + 1);
+        let collapse_condition = format!(
+            "(articles.group_id IS NULL OR NOT EXISTS (
+                SELECT 1 FROM articles better
+                LEFT JOIN source_meta bm ON bm.source = better.source
+                WHERE better.group_id = articles.group_id
+                  AND ({better} < {this}
+                       OR ({better} = {this}
+                           AND (better.published_at > articles.published_at
+                                OR (better.published_at = articles.published_at AND better.id > articles.id))))))",
+            better = rank("bm"),
+            this = rank("sm"),
+        );
+        let exclude_condition = exclude_types_condition(":xtypes");
 
         // Build SQL dynamically to avoid borrow issues
         let mut conditions = Vec::new();
@@ -276,6 +1387,18 @@ impl Db {
         if has_cursor {
             conditions.push("(published_at < :cpub OR (published_at = :cpub AND id < :cid))");
         }
+        if filter.range.is_some() {
+            conditions.push("published_at BETWEEN :from AND :to");
+        }
+        if !filter.exclude_types.is_empty() {
+            conditions.push(&exclude_condition);
+        }
+        if !include_dead {
+            conditions.push("dead_link != 1");
+        }
+        if collapse_groups {
+            conditions.push(&collapse_condition);
+        }
 
         let where_clause = if conditions.is_empty() {
             String::new()
@@ -283,13 +1406,21 @@ impl Db {
             format!("WHERE {}", conditions.join(" AND "))
         };
 
+        // Source metadata is only needed to rank group members or filter by type
+        let (columns, join) = if collapse_groups || !filter.exclude_types.is_empty() {
+            (JOINED_ARTICLE_COLUMNS, SOURCE_META_JOIN)
+        } else {
+            (
+                "id, category, title, url, description, image_url, source,
+                    published_at, fetched_at, group_id, group_count, word_count, reading_minutes",
+                "",
+            )
+        };
         let sql = format!(
-            "SELECT id, category, title, url, description, image_url, source,
-                    published_at, fetched_at, group_id, group_count
-             FROM articles {}
+            "SELECT {columns}
+             FROM articles {join} {where_clause}
              ORDER BY published_at DESC, id DESC
-             LIMIT :lim",
-            where_clause
+             LIMIT :lim"
         );
 
         let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
@@ -311,6 +1442,18 @@ impl Db {
             param_values.push(Box::new(cursor_id.clone()));
             idx += 2;
         }
+        if let Some((from, to)) = &filter.range {
+            param_names.push(":from");
+            param_values.push(Box::new(from.to_rfc3339()));
+            param_names.push(":to");
+            param_values.push(Box::new(to.to_rfc3339()));
+            idx += 2;
+        }
+        if !filter.exclude_types.is_empty() {
+            param_names.push(":xtypes");
+            param_values.push(Box::new(exclude_types_param(&filter.exclude_types)));
+            idx += 1;
+        }
         param_names.push(":lim");
         param_values.push(Box::new(fetch_limit));
         let _ = idx;
@@ -324,25 +1467,73 @@ impl Db {
         let rows = stmt
             .query_map(params.as_slice(), row_to_article)
             .map_err(|e| e.to_string())?;
-        let mut articles: Vec<Article> = rows.filter_map(|r| r.ok()).collect();
+        let articles: Vec<Article> = rows.filter_map(|r| r.ok()).collect();
+        Ok(Page::new(articles, page.limit))
+    }
 
-        let next_cursor = if articles.len() as i64 > limit {
-            articles.truncate(limit as usize);
-            articles.last().map(|a| encode_cursor(a))
-        } else {
-            None
+    /// Articles ranked by `ranking`, newest first on ties, optionally only those
+    /// published after `since` and not from `exclude_types` sources. Scores move over
+    /// time, so pages use an offset cursor.
+    pub fn query_ranked_articles(
+        &self,
+        category: Option<&Category>,
+        since: Option<&DateTime<Utc>>,
+        ranking: &Ranking,
+        page: &Paginator,
+        include_dead: bool,
+        exclude_types: &[String],
+    ) -> Result<Page<Article>, String> {
+        let offset = page.offset();
+        let order = match ranking {
+            Ranking::Importance(w) if *w == ImportanceWeights::default() => {
+                format!("compound_score + {} * recency_decay({AGE_HOURS_EXPR})", w.recency)
+            }
+            Ranking::Importance(w) => format!(
+                "COALESCE(ai_importance, 0.5) * {}
+                 + MAX(popularity_score, 0.0) / (MAX(popularity_score, 0.0) + {:?}) * {}
+                 + recency_decay({AGE_HOURS_EXPR}) * {}",
+                w.ai, POPULARITY_HALF_SCORE, w.popularity, w.recency
+            ),
+            Ranking::Popularity => "popularity_score".to_string(),
         };
+        let sql = format!(
+            "SELECT {JOINED_ARTICLE_COLUMNS}
+             FROM articles {SOURCE_META_JOIN}
+             WHERE (?1 IS NULL OR category = ?1) AND (?2 IS NULL OR published_at >= ?2)
+               AND (?5 OR dead_link != 1) AND {excluded}
+             ORDER BY {order} DESC, published_at DESC, id DESC
+             LIMIT ?3 OFFSET ?4",
+            excluded = exclude_types_condition("?6"),
+        );
 
-        Ok((articles, next_cursor))
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let articles: Vec<Article> = stmt
+            .query_map(
+                params![
+                    category.map(|c| c.as_str()),
+                    since.map(|t| t.to_rfc3339()),
+                    page.limit + 1,
+                    offset,
+                    include_dead,
+                    exclude_types_param(exclude_types)
+                ],
+                row_to_article,
+            )
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(Page::at_offset(articles, page.limit, offset))
     }
 
+    /// Articles for the OGP pass, newest first. Degraded images are left alone.
     pub fn articles_without_image(&self, limit: i64) -> Result<Vec<Article>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
             .prepare(
                 "SELECT id, category, title, url, description, image_url, source,
-                        published_at, fetched_at, group_id, group_count
-                 FROM articles WHERE image_url IS NULL
+                        published_at, fetched_at, group_id, group_count, word_count, reading_minutes
+                 FROM articles WHERE image_url IS NULL AND image_degraded_at IS NULL
                  ORDER BY published_at DESC LIMIT ?1",
             )
             .map_err(|e| e.to_string())?;
@@ -370,35 +1561,244 @@ impl Db {
         let mut stmt = conn
             .prepare(
                 "SELECT id, category, title, url, description, image_url, source,
-                        published_at, fetched_at, group_id, group_count
+                        published_at, fetched_at, group_id, group_count, word_count, reading_minutes, canonical_url,
+                        original_url
                  FROM articles WHERE id = ?1",
             )
             .map_err(|e| e.to_string())?;
         let mut rows = stmt
-            .query_map(params![id], row_to_article)
+            .query_map(params![id], |row| {
+                let mut article = row_to_article(row)?;
+                article.canonical_url = row.get(13)?;
+                article.original_url = row.get(14)?;
+                Ok(article)
+            })
             .map_err(|e| e.to_string())?;
-        match rows.next() {
-            Some(Ok(article)) => Ok(Some(article)),
-            Some(Err(e)) => Err(e.to_string()),
-            None => Ok(None),
+        let mut article = match rows.next() {
+            Some(Ok(article)) => article,
+            Some(Err(e)) => return Err(e.to_string()),
+            None => return Ok(None),
+        };
+        drop(rows);
+        drop(stmt);
+        drop(conn);
+        article.tags = self.get_article_tags(id)?;
+        Ok(Some(article))
+    }
+
+    // --- Link Health ---
+
+    /// Up to `limit` (id, url) of articles published since `since` whose link hasn't
+    /// been checked since `checked_before`, most popular first. Articles already flagged
+    /// or un-flagged by an admin are left alone.
+    pub fn link_check_candidates(
+        &self,
+        since: &DateTime<Utc>,
+        checked_before: &DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, url FROM articles
+                 WHERE dead_link = 0 AND published_at >= ?1
+                   AND (link_checked_at IS NULL OR link_checked_at < ?2)
+                 ORDER BY popularity_score DESC, published_at DESC
+                 LIMIT ?3",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![since.to_rfc3339(), checked_before.to_rfc3339(), limit], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(|e| format!("Link check candidates: {e}"))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Record that an article's link was checked, flagging it when `dead`.
+    pub fn record_link_check(&self, id: &str, dead: bool) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE articles SET link_checked_at = ?2, dead_link = CASE WHEN ?3 THEN 1 ELSE dead_link END
+             WHERE id = ?1 AND dead_link != -1",
+            params![id, chrono::Utc::now().to_rfc3339(), dead],
+        )
+        .map_err(|e| format!("Record link check: {e}"))?;
+        Ok(())
+    }
+
+    /// Clear a false-positive dead-link flag; the article is not checked again.
+    /// Returns false if it wasn't flagged.
+    pub fn unflag_dead_link(&self, id: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let n = conn
+            .execute("UPDATE articles SET dead_link = -1 WHERE id = ?1 AND dead_link = 1", params![id])
+            .map_err(|e| format!("Unflag dead link: {e}"))?;
+        Ok(n > 0)
+    }
+
+    pub fn count_dead_links(&self) -> Result<i64, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row("SELECT COUNT(*) FROM articles WHERE dead_link = 1", [], |row| row.get(0))
+            .map_err(|e| format!("Count dead links: {e}"))
+    }
+
+    // --- Short links ---
+
+    /// The article's short code, creating one on first use. One code per article.
+    pub fn get_or_create_shortlink(&self, article_id: &str) -> Result<String, String> {
+        use rusqlite::OptionalExtension;
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        for _ in 0..SHORTLINK_ATTEMPTS {
+            // A clash on short_code inserts nothing and leaves the lookup empty, so a
+            // fresh code is tried; a clash on article_id means the code already exists.
+            conn.execute(
+                "INSERT INTO shortlinks (short_code, article_id, created_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT DO NOTHING",
+                params![new_short_code(), article_id, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| format!("Create shortlink: {e}"))?;
+            let code = conn
+                .query_row(
+                    "SELECT short_code FROM shortlinks WHERE article_id = ?1",
+                    params![article_id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| format!("Get shortlink: {e}"))?;
+            if let Some(code) = code {
+                return Ok(code);
+            }
         }
+        Err("Create shortlink: no free short code".into())
     }
 
-    // --- Search ---
+    /// Article ID a short code points to.
+    pub fn get_shortlink(&self, short_code: &str) -> Result<Option<String>, String> {
+        use rusqlite::OptionalExtension;
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT article_id FROM shortlinks WHERE short_code = ?1",
+            params![short_code],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Get shortlink: {e}"))
+    }
 
-    pub fn search_articles(&self, query: &str, limit: i64) -> Result<Vec<Article>, String> {
-        let search = format!("%{}%", query);
+    pub fn increment_shortlink_clicks(&self, short_code: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE shortlinks SET clicks = clicks + 1 WHERE short_code = ?1",
+            params![short_code],
+        )
+        .map_err(|e| format!("Count shortlink click: {e}"))?;
+        Ok(())
+    }
+
+    /// Short codes of whichever of `article_ids` have one, keyed by article ID.
+    pub fn shortlinks_for_articles(&self, article_ids: &[&str]) -> Result<HashMap<String, String>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut codes = HashMap::new();
+        for chunk in article_ids.chunks(INSERT_BATCH_SIZE) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let mut stmt = conn
+                .prepare(&format!(
+                    "SELECT article_id, short_code FROM shortlinks WHERE article_id IN ({placeholders})"
+                ))
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(chunk.iter()), |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(|e| e.to_string())?;
+            codes.extend(rows.filter_map(|r| r.ok()));
+        }
+        Ok(codes)
+    }
+
+    /// `published_at` of the newest article, None when there are none.
+    pub fn newest_article_published_at(&self) -> Result<Option<String>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row("SELECT MAX(published_at) FROM articles", [], |row| row.get(0))
+            .map_err(|e| format!("Newest article: {e}"))
+    }
+
+    // --- Timeline ---
+
+    /// Articles about the same story as `article_id`, published within `days` days
+    /// either side of it, oldest first (the reference article included). "Same story"
+    /// means same group, same canonical URL, or title similarity >= `TIMELINE_SIMILARITY`.
+    pub fn get_related_timeline(&self, article_id: &str, days: i64) -> Result<Vec<Article>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let reference = conn.query_row(
+            "SELECT title, published_at, group_id, canonical_url FROM articles WHERE id = ?1",
+            params![article_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        );
+        let (title, published_at, group_id, canonical_url) = match reference {
+            Ok(r) => r,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Timeline reference: {e}")),
+        };
+        let published = DateTime::parse_from_rfc3339(&published_at)
+            .map(|d| d.with_timezone(&Utc))
+            .map_err(|e| format!("Timeline reference date: {e}"))?;
+        let from = (published - chrono::Duration::days(days)).to_rfc3339();
+        let to = (published + chrono::Duration::days(days)).to_rfc3339();
+
         let mut stmt = conn
             .prepare(
                 "SELECT id, category, title, url, description, image_url, source,
-                        published_at, fetched_at, group_id, group_count
+                        published_at, fetched_at, group_id, group_count, word_count, reading_minutes, canonical_url
                  FROM articles
-                 WHERE title LIKE ?1 OR description LIKE ?1
-                 ORDER BY published_at DESC
-                 LIMIT ?2",
+                 WHERE published_at >= ?1 AND published_at <= ?2
+                 ORDER BY published_at ASC
+                 LIMIT 5000",
             )
             .map_err(|e| e.to_string())?;
+        let candidates = stmt
+            .query_map(params![from, to], |row| {
+                let mut article = row_to_article(row)?;
+                article.canonical_url = row.get(13)?;
+                Ok(article)
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok());
+
+        let timeline = candidates
+            .filter(|a| {
+                a.id == article_id
+                    || (group_id.is_some() && a.group_id == group_id)
+                    || (canonical_url.is_some() && a.canonical_url == canonical_url)
+                    || news_core::grouping::similarity(&a.title, &title) >= TIMELINE_SIMILARITY
+            })
+            .collect();
+        Ok(timeline)
+    }
+
+    // --- Search ---
+
+    pub fn search_articles(&self, query: &str, limit: i64) -> Result<Vec<Article>, String> {
+        let search = like_pattern(query);
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let sql = format!(
+            "SELECT id, category, title, url, description, image_url, source,
+                    published_at, fetched_at, group_id, group_count, word_count, reading_minutes
+             FROM articles
+             WHERE {}
+             ORDER BY published_at DESC
+             LIMIT ?2",
+            search_condition("articles", "?1")
+        );
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
         let articles = stmt
             .query_map(params![search, limit], row_to_article)
             .map_err(|e| e.to_string())?
@@ -407,87 +1807,602 @@ impl Db {
         Ok(articles)
     }
 
-    // --- Feeds ---
+    // --- Sources ---
+
+    pub fn get_articles_by_source(
+        &self,
+        source: &str,
+        page: &Paginator,
+    ) -> Result<Page<Article>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+
+        let (cursor_pub, cursor_id) = page.keyset().unwrap_or_default();
+        let fetch_limit = page.limit + 1;
+
+        let sql = if cursor_pub.is_empty() {
+            "SELECT id, category, title, url, description, image_url, source,
+                    published_at, fetched_at, group_id, group_count, word_count, reading_minutes
+             FROM articles
+             WHERE source = ?1
+             ORDER BY published_at DESC, id DESC
+             LIMIT ?2"
+        } else {
+            "SELECT id, category, title, url, description, image_url, source,
+                    published_at, fetched_at, group_id, group_count, word_count, reading_minutes
+             FROM articles
+             WHERE source = ?1 AND (published_at < ?3 OR (published_at = ?3 AND id < ?4))
+             ORDER BY published_at DESC, id DESC
+             LIMIT ?2"
+        };
 
-    pub fn get_enabled_feeds(&self) -> Result<Vec<DynamicFeed>, String> {
+        let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+        let rows = if cursor_pub.is_empty() {
+            stmt.query_map(params![source, fetch_limit], row_to_article)
+        } else {
+            stmt.query_map(params![source, fetch_limit, cursor_pub, cursor_id], row_to_article)
+        }
+        .map_err(|e| e.to_string())?;
+        let articles: Vec<Article> = rows.filter_map(|r| r.ok()).collect();
+        Ok(Page::new(articles, page.limit))
+    }
+
+    /// `(total, last_24h, last_7d)` articles stored from `source`; the windows go by
+    /// `fetched_at`, i.e. when the articles were first stored.
+    pub fn source_article_counts(&self, source: &str) -> Result<(i64, i64, i64), String> {
+        let now = Utc::now();
+        let day_ago = (now - chrono::Duration::hours(24)).to_rfc3339();
+        let week_ago = (now - chrono::Duration::days(7)).to_rfc3339();
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "WITH src AS (SELECT fetched_at FROM articles WHERE source = ?1)
+             SELECT COUNT(*),
+                    COUNT(CASE WHEN fetched_at >= ?2 THEN 1 END),
+                    COUNT(CASE WHEN fetched_at >= ?3 THEN 1 END)
+             FROM src",
+            params![source, day_ago, week_ago],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Source article counts: {e}"))
+    }
+
+    /// Distinct sources with (source, article_count, latest_published_at), most recent first.
+    pub fn list_sources(&self) -> Result<Vec<(String, i64, String)>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
-            .prepare("SELECT feed_id, url, source, category, enabled, added_by FROM feeds WHERE enabled = 1")
+            .prepare(
+                "SELECT source, COUNT(*), MAX(published_at) FROM articles
+                 GROUP BY source
+                 ORDER BY MAX(published_at) DESC",
+            )
             .map_err(|e| e.to_string())?;
-        let feeds = stmt
-            .query_map([], |row| {
-                Ok(DynamicFeed {
-                    feed_id: row.get(0)?,
-                    url: row.get(1)?,
-                    source: row.get(2)?,
-                    category: row.get(3)?,
-                    enabled: row.get::<_, i32>(4)? != 0,
-                    added_by: row.get(5)?,
-                })
-            })
+        let sources = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
             .map_err(|e| e.to_string())?
             .filter_map(|r| r.ok())
             .collect();
-        Ok(feeds)
+        Ok(sources)
     }
 
-    pub fn get_all_feeds(&self) -> Result<Vec<DynamicFeed>, String> {
+    // --- Source metadata ---
+
+    pub fn list_source_meta(&self) -> Result<Vec<SourceMeta>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
-            .prepare("SELECT feed_id, url, source, category, enabled, added_by FROM feeds")
+            .prepare("SELECT source, source_type, credibility_tier, homepage_url, logo_url FROM source_meta ORDER BY source")
             .map_err(|e| e.to_string())?;
-        let feeds = stmt
-            .query_map([], |row| {
-                Ok(DynamicFeed {
-                    feed_id: row.get(0)?,
-                    url: row.get(1)?,
-                    source: row.get(2)?,
-                    category: row.get(3)?,
-                    enabled: row.get::<_, i32>(4)? != 0,
-                    added_by: row.get(5)?,
-                })
-            })
+        let rows = stmt
+            .query_map([], row_to_source_meta)
             .map_err(|e| e.to_string())?
             .filter_map(|r| r.ok())
             .collect();
-        Ok(feeds)
+        Ok(rows)
     }
 
-    pub fn put_feed(&self, feed: &DynamicFeed) -> Result<(), String> {
+    /// Metadata keyed by source, for decorating a page of articles without joining
+    /// in the listing queries. The table holds one row per source, so it's read whole.
+    pub fn source_meta_map(&self) -> Result<HashMap<String, SourceMeta>, String> {
+        Ok(self
+            .list_source_meta()?
+            .into_iter()
+            .map(|m| (m.source.clone(), m))
+            .collect())
+    }
+
+    pub fn put_source_meta(&self, meta: &SourceMeta) -> Result<(), String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         conn.execute(
-            "INSERT OR REPLACE INTO feeds (feed_id, url, source, category, enabled, added_by)
+            "INSERT OR REPLACE INTO source_meta
+                (source, source_type, credibility_tier, homepage_url, logo_url, updated_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![
-                feed.feed_id,
-                feed.url,
-                feed.source,
-                feed.category,
-                feed.enabled as i32,
-                feed.added_by,
+                meta.source,
+                meta.source_type,
+                meta.credibility_tier,
+                meta.homepage_url,
+                meta.logo_url,
+                Utc::now().to_rfc3339(),
             ],
         )
-        .map_err(|e| format!("Put feed: {e}"))?;
-        info!(feed_id = %feed.feed_id, source = %feed.source, "Feed saved");
+        .map_err(|e| format!("Put source meta: {e}"))?;
         Ok(())
     }
 
-    pub fn delete_feed(&self, feed_id: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        conn.execute("DELETE FROM feeds WHERE feed_id = ?1", params![feed_id])
-            .map_err(|e| format!("Delete feed: {e}"))?;
-        info!(feed_id, "Feed deleted");
-        Ok(())
+    /// Insert entries for sources that have no row yet, leaving admin edits alone.
+    /// Returns how many were added.
+    pub fn seed_source_meta(&self, entries: &[SourceMeta]) -> Result<usize, String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let now = Utc::now().to_rfc3339();
+        with_busy_retry("Seed source meta", || {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            let mut added = 0;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR IGNORE INTO source_meta
+                        (source, source_type, credibility_tier, homepage_url, logo_url, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                )?;
+                for m in entries {
+                    added += stmt.execute(params![
+                        m.source,
+                        m.source_type,
+                        m.credibility_tier,
+                        m.homepage_url,
+                        m.logo_url,
+                        now
+                    ])?;
+                }
+            }
+            tx.commit()?;
+            Ok(added)
+        })
     }
 
-    pub fn feed_count(&self) -> Result<i64, String> {
+    /// Returns false if `source` had no metadata.
+    pub fn delete_source_meta(&self, source: &str) -> Result<bool, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        conn.query_row("SELECT COUNT(*) FROM feeds", [], |row| row.get(0))
-            .map_err(|e| format!("Feed count: {e}"))
+        let n = conn
+            .execute("DELETE FROM source_meta WHERE source = ?1", params![source])
+            .map_err(|e| format!("Delete source meta: {e}"))?;
+        Ok(n > 0)
+    }
+
+    // --- Tags ---
+
+    /// Replace an article's tags. Tags are trimmed; blanks and duplicates are dropped.
+    pub fn set_article_tags(&self, article_id: &str, tags: &[String]) -> Result<(), String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        with_busy_retry("Set tags", || {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            tx.execute("DELETE FROM article_tags WHERE article_id = ?1", params![article_id])?;
+            {
+                let mut stmt = tx.prepare("INSERT OR IGNORE INTO article_tags (article_id, tag) VALUES (?1, ?2)")?;
+                for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+                    stmt.execute(params![article_id, tag])?;
+                }
+            }
+            tx.commit()
+        })
+    }
+
+    pub fn get_article_tags(&self, article_id: &str) -> Result<Vec<String>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT tag FROM article_tags WHERE article_id = ?1 ORDER BY tag")
+            .map_err(|e| e.to_string())?;
+        let tags = stmt
+            .query_map(params![article_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(tags)
+    }
+
+    /// Tags with article counts, most used first.
+    pub fn list_popular_tags(&self, limit: i64) -> Result<Vec<(String, i64)>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT tag, COUNT(*) AS n FROM article_tags
+                 GROUP BY tag
+                 ORDER BY n DESC, tag ASC
+                 LIMIT ?1",
+            )
+            .map_err(|e| e.to_string())?;
+        let tags = stmt
+            .query_map(params![limit], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(tags)
+    }
+
+    pub fn get_articles_by_tag(
+        &self,
+        tag: &str,
+        page: &Paginator,
+    ) -> Result<Page<Article>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+
+        let (cursor_pub, cursor_id) = page.keyset().unwrap_or_default();
+        let fetch_limit = page.limit + 1;
+
+        let sql = if cursor_pub.is_empty() {
+            "SELECT a.id, a.category, a.title, a.url, a.description, a.image_url, a.source,
+                    a.published_at, a.fetched_at, a.group_id, a.group_count, a.word_count, a.reading_minutes
+             FROM articles a JOIN article_tags t ON t.article_id = a.id
+             WHERE t.tag = ?1
+             ORDER BY a.published_at DESC, a.id DESC
+             LIMIT ?2"
+        } else {
+            "SELECT a.id, a.category, a.title, a.url, a.description, a.image_url, a.source,
+                    a.published_at, a.fetched_at, a.group_id, a.group_count, a.word_count, a.reading_minutes
+             FROM articles a JOIN article_tags t ON t.article_id = a.id
+             WHERE t.tag = ?1 AND (a.published_at < ?3 OR (a.published_at = ?3 AND a.id < ?4))
+             ORDER BY a.published_at DESC, a.id DESC
+             LIMIT ?2"
+        };
+
+        let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+        let rows = if cursor_pub.is_empty() {
+            stmt.query_map(params![tag, fetch_limit], row_to_article)
+        } else {
+            stmt.query_map(params![tag, fetch_limit, cursor_pub, cursor_id], row_to_article)
+        }
+        .map_err(|e| e.to_string())?;
+        let articles: Vec<Article> = rows.filter_map(|r| r.ok()).collect();
+        Ok(Page::new(articles, page.limit))
+    }
+
+    // --- Entities ---
+
+    /// Replace the named entities extracted from `article_id`: `(text, type)` pairs.
+    pub fn set_article_entities(&self, article_id: &str, entities: &[(String, String)]) -> Result<(), String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        with_busy_retry("Set entities", || {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            tx.execute("DELETE FROM article_entities WHERE article_id = ?1", params![article_id])?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR IGNORE INTO article_entities (article_id, entity_text, entity_type) VALUES (?1, ?2, ?3)",
+                )?;
+                for (text, kind) in entities {
+                    let text = text.trim();
+                    if !text.is_empty() {
+                        stmt.execute(params![article_id, text, kind.trim()])?;
+                    }
+                }
+            }
+            tx.commit()
+        })
+    }
+
+    /// Entity texts of `article_id`, in alphabetical order.
+    pub fn get_article_entities(&self, article_id: &str) -> Result<Vec<String>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT entity_text FROM article_entities WHERE article_id = ?1 ORDER BY entity_text")
+            .map_err(|e| e.to_string())?;
+        let entities = stmt
+            .query_map(params![article_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(entities)
+    }
+
+    /// Articles other than `exclude_id` mentioning any of `entities`, with the ones
+    /// they mention. Most shared entities first, then newest.
+    pub fn find_articles_by_entities(
+        &self,
+        entities: &[String],
+        exclude_id: &str,
+        limit: i64,
+    ) -> Result<Vec<(Article, Vec<String>)>, String> {
+        if entities.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let placeholders = (0..entities.len()).map(|i| format!("?{}", i + 3)).collect::<Vec<_>>().join(", ");
+        let sql = format!(
+            "SELECT a.id, a.category, a.title, a.url, a.description, a.image_url, a.source,
+                    a.published_at, a.fetched_at, a.group_id, a.group_count, a.word_count, a.reading_minutes,
+                    m.shared
+             FROM articles a
+             JOIN (SELECT article_id, group_concat(entity_text, char(31)) AS shared, COUNT(*) AS n
+                   FROM article_entities
+                   WHERE entity_text IN ({placeholders}) AND article_id != ?1
+                   GROUP BY article_id) m ON m.article_id = a.id
+             WHERE a.dead_link != 1
+             ORDER BY m.n DESC, a.published_at DESC, a.id DESC
+             LIMIT ?2"
+        );
+        let mut values: Vec<rusqlite::types::Value> = vec![exclude_id.to_string().into(), limit.into()];
+        values.extend(entities.iter().map(|e| e.clone().into()));
+
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let articles = stmt
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                let shared: String = row.get(13)?;
+                let mut shared: Vec<String> = shared.split('\u{1f}').map(str::to_string).collect();
+                shared.sort();
+                Ok((row_to_article(row)?, shared))
+            })
+            .map_err(|e| format!("Articles by entities: {e}"))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(articles)
+    }
+
+    // --- Feeds ---
+
+    pub fn get_all_feeds(&self) -> Result<Vec<DynamicFeed>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT feed_id, url, source, category, enabled, added_by, max_articles_per_fetch, auto_translate,
+                        category_overrides
+                 FROM feeds",
+            )
+            .map_err(|e| e.to_string())?;
+        // A row that doesn't decode is an error, not a missing feed: dropping it would
+        // silently send the fetcher back to feeds.toml.
+        let feeds = stmt
+            .query_map([], row_to_feed)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?;
+        Ok(feeds)
+    }
+
+    pub fn put_feed(&self, feed: &DynamicFeed) -> Result<(), String> {
+        let overrides_json = match feed.category_overrides.as_ref().filter(|o| !o.is_empty()) {
+            Some(overrides) => Some(serde_json::to_string(overrides).map_err(|e| e.to_string())?),
+            None => None,
+        };
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO feeds (feed_id, url, source, category, enabled, added_by, max_articles_per_fetch, auto_translate,
+                                category_overrides)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(feed_id) DO UPDATE SET
+                url = excluded.url, source = excluded.source, category = excluded.category,
+                enabled = excluded.enabled, added_by = excluded.added_by,
+                max_articles_per_fetch = excluded.max_articles_per_fetch,
+                auto_translate = excluded.auto_translate, category_overrides = excluded.category_overrides",
+            params![
+                feed.feed_id,
+                feed.url,
+                feed.source,
+                feed.category,
+                feed.enabled as i32,
+                feed.added_by,
+                feed.max_articles_per_fetch,
+                feed.auto_translate as i32,
+                overrides_json,
+            ],
+        )
+        .map_err(|e| format!("Put feed: {e}"))?;
+        info!(feed_id = %feed.feed_id, source = %feed.source, "Feed saved");
+        Ok(())
+    }
+
+    pub fn delete_feed(&self, feed_id: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM feeds WHERE feed_id = ?1", params![feed_id])
+            .map_err(|e| format!("Delete feed: {e}"))?;
+        conn.execute("DELETE FROM feed_fetch_log WHERE feed_id = ?1", params![feed_id])
+            .map_err(|e| format!("Delete feed log: {e}"))?;
+        info!(feed_id, "Feed deleted");
+        Ok(())
+    }
+
+    /// Record a successful fetch: log it and refresh the feed's freshness columns.
+    pub fn update_feed_stats(
+        &self,
+        feed_id: &str,
+        last_fetched_at: &DateTime<Utc>,
+        item_count: usize,
+        fetch_ms: u64,
+    ) -> Result<(), String> {
+        self.log_feed_fetch(feed_id, last_fetched_at, item_count, fetch_ms, None)
+    }
+
+    /// Record a failed fetch. The feed's freshness columns keep their last success.
+    pub fn log_feed_fetch_error(
+        &self,
+        feed_id: &str,
+        fetched_at: &DateTime<Utc>,
+        fetch_ms: u64,
+        error: &str,
+    ) -> Result<(), String> {
+        self.log_feed_fetch(feed_id, fetched_at, 0, fetch_ms, Some(error))
+    }
+
+    fn log_feed_fetch(
+        &self,
+        feed_id: &str,
+        fetched_at: &DateTime<Utc>,
+        item_count: usize,
+        fetch_ms: u64,
+        error: Option<&str>,
+    ) -> Result<(), String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let fetched_at = fetched_at.to_rfc3339();
+        with_busy_retry("Log feed fetch", || {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            tx.execute(
+                "INSERT INTO feed_fetch_log (feed_id, fetched_at, item_count, fetch_ms, error)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![feed_id, fetched_at, item_count as i64, fetch_ms as i64, error],
+            )?;
+            if error.is_none() {
+                tx.execute(
+                    "UPDATE feeds SET last_fetched_at = ?2, last_item_count = ?3,
+                        avg_fetch_ms = (SELECT CAST(AVG(fetch_ms) AS INTEGER) FROM (
+                            SELECT fetch_ms FROM feed_fetch_log
+                            WHERE feed_id = ?1 AND error IS NULL
+                            ORDER BY log_id DESC LIMIT ?4))
+                     WHERE feed_id = ?1",
+                    params![feed_id, fetched_at, item_count as i64, FEED_STATS_WINDOW],
+                )?;
+            }
+            tx.execute(
+                "DELETE FROM feed_fetch_log WHERE feed_id = ?1 AND log_id NOT IN (
+                    SELECT log_id FROM feed_fetch_log WHERE feed_id = ?1 ORDER BY log_id DESC LIMIT ?2)",
+                params![feed_id, FEED_LOG_KEEP],
+            )?;
+            tx.commit()
+        })
+    }
+
+    /// Freshness stats of every feed that has been fetched, by feed_id.
+    pub fn feed_stats(&self) -> Result<HashMap<String, FeedStats>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT feed_id, last_fetched_at, last_item_count, avg_fetch_ms FROM feeds
+                 WHERE last_fetched_at IS NOT NULL",
+            )
+            .map_err(|e| format!("Feed stats: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    FeedStats {
+                        last_fetched_at: row.get(1)?,
+                        last_item_count: row.get(2)?,
+                        avg_fetch_ms: row.get(3)?,
+                    },
+                ))
+            })
+            .map_err(|e| format!("Feed stats: {e}"))?;
+        rows.collect::<Result<HashMap<_, _>, _>>().map_err(|e| format!("Feed stats: {e}"))
+    }
+
+    /// The newest `limit` fetch records of a feed, newest first.
+    pub fn feed_fetch_history(&self, feed_id: &str, limit: i64) -> Result<Vec<FeedFetchRecord>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT log_id, fetched_at, item_count, fetch_ms, error FROM feed_fetch_log
+                 WHERE feed_id = ?1 ORDER BY log_id DESC LIMIT ?2",
+            )
+            .map_err(|e| format!("Feed history: {e}"))?;
+        let rows = stmt
+            .query_map(params![feed_id, limit], |row| {
+                Ok(FeedFetchRecord {
+                    log_id: row.get(0)?,
+                    fetched_at: row.get(1)?,
+                    item_count: row.get(2)?,
+                    fetch_ms: row.get(3)?,
+                    error: row.get(4)?,
+                })
+            })
+            .map_err(|e| format!("Feed history: {e}"))?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Feed history: {e}"))
+    }
+
+    pub fn feed_count(&self) -> Result<i64, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row("SELECT COUNT(*) FROM feeds", [], |row| row.get(0))
+            .map_err(|e| format!("Feed count: {e}"))
+    }
+
+    /// Whether the main database accepts writes (not opened read-only or
+    /// `query_only`), checked without writing.
+    pub fn probe_writable(&self) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let read_only = conn
+            .is_readonly(rusqlite::DatabaseName::Main)
+            .map_err(|e| format!("Health write probe: {e}"))?;
+        let query_only: bool = conn
+            .query_row("PRAGMA query_only", [], |row| row.get(0))
+            .map_err(|e| format!("Health write probe: {e}"))?;
+        if read_only || query_only {
+            return Err("database is read-only".into());
+        }
+        Ok(())
+    }
+
+    /// Read a row from `table` (a fixed table name, never user input).
+    pub fn probe_table(&self, table: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(&format!("SELECT COUNT(*) FROM (SELECT 1 FROM {table} LIMIT 1)"), [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .map(|_| ())
+        .map_err(|e| format!("Health read probe ({table}): {e}"))
+    }
+
+    // --- Sites ---
+
+    /// Site entries added or overridden at runtime (sites.toml holds the defaults).
+    pub fn list_sites(&self) -> Result<Vec<SiteMeta>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT host, site_id, name, title, description, description_long, url, image, theme_color, lang, keywords FROM sites ORDER BY host")
+            .map_err(|e| e.to_string())?;
+        let sites = stmt
+            .query_map([], row_to_site)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(sites)
+    }
+
+    pub fn get_site(&self, host: &str) -> Result<Option<SiteMeta>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT host, site_id, name, title, description, description_long, url, image, theme_color, lang, keywords FROM sites WHERE host = ?1")
+            .map_err(|e| e.to_string())?;
+        let mut rows = stmt
+            .query_map(params![host], row_to_site)
+            .map_err(|e| e.to_string())?;
+        match rows.next() {
+            Some(Ok(site)) => Ok(Some(site)),
+            Some(Err(e)) => Err(e.to_string()),
+            None => Ok(None),
+        }
+    }
+
+    pub fn put_site(&self, site: &SiteMeta) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO sites
+                (host, site_id, name, title, description, description_long, url, image, theme_color, lang, keywords, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                site.host,
+                site.site_id,
+                site.name,
+                site.title,
+                site.description,
+                site.description_long,
+                site.url,
+                site.image,
+                site.theme_color,
+                site.lang,
+                site.keywords,
+                Utc::now().to_rfc3339(),
+            ],
+        )
+        .map_err(|e| format!("Put site: {e}"))?;
+        info!(host = %site.host, "Site saved");
+        Ok(())
+    }
+
+    /// Returns false if there was no DB entry for `host`.
+    pub fn delete_site(&self, host: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let n = conn
+            .execute("DELETE FROM sites WHERE host = ?1", params![host])
+            .map_err(|e| format!("Delete site: {e}"))?;
+        Ok(n > 0)
     }
 
     // --- Features ---
 
+    #[tracing::instrument(skip(self))]
     pub fn get_feature_flags(&self) -> Result<FeatureFlags, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut flags = FeatureFlags::default();
@@ -522,6 +2437,29 @@ impl Db {
                 "ogp_enrichment" => {
                     flags.ogp_enrichment_enabled = enabled;
                 }
+                "enrichment_research" => flags.enrichment_research_enabled = enabled,
+                "enrichment_image" => flags.enrichment_image_enabled = enabled,
+                "enrichment_video" => flags.enrichment_video_enabled = enabled,
+                "sentiment" => flags.sentiment_enabled = enabled,
+                "importance_weights" if enabled => flags.importance_weights_json = extra,
+                "cors_origins" if enabled => {
+                    flags.cors_origins = extra
+                        .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+                        .and_then(|mut v| serde_json::from_value(v["origins"].take()).ok())
+                        .unwrap_or_default();
+                }
+                "image_degradation" => {
+                    flags.image_degradation = extra
+                        .as_deref()
+                        .and_then(ImageDegradationPolicy::from_json)
+                        .unwrap_or_default();
+                    flags.image_degradation.enabled = enabled;
+                }
+                "ab_tests" if enabled => {
+                    flags.ab_tests = extra
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or_default();
+                }
                 _ => {}
             }
         }
@@ -550,6 +2488,39 @@ impl Db {
         Ok(())
     }
 
+    // --- A/B tests ---
+
+    /// Record which variant a device saw. `assigned_at` only moves when the variant
+    /// changes (e.g. after the weights were edited).
+    pub fn record_ab_assignment(&self, device_id: &str, test_name: &str, variant: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO ab_test_assignment (device_id, test_name, variant, assigned_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(device_id, test_name) DO UPDATE
+                SET variant = excluded.variant, assigned_at = excluded.assigned_at
+                WHERE variant != excluded.variant",
+            params![device_id, test_name, variant, Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| format!("Record A/B assignment: {e}"))?;
+        Ok(())
+    }
+
+    /// Devices per (test, variant), for comparing variants.
+    pub fn ab_assignment_counts(&self) -> Result<Vec<(String, String, i64)>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT test_name, variant, COUNT(*) FROM ab_test_assignment
+                 GROUP BY test_name, variant ORDER BY test_name, variant",
+            )
+            .map_err(|e| format!("A/B counts: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| format!("A/B counts: {e}"))?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("A/B counts: {e}"))
+    }
+
     // --- Categories ---
 
     pub fn category_count(&self) -> Result<i64, String> {
@@ -559,20 +2530,11 @@ impl Db {
     }
 
     pub fn seed_default_categories(&self) -> Result<(), String> {
-        let defaults = [
-            ("general", "総合", "General", 0),
-            ("tech", "テクノロジー", "Technology", 1),
-            ("business", "ビジネス", "Business", 2),
-            ("entertainment", "エンタメ", "Entertainment", 3),
-            ("sports", "スポーツ", "Sports", 4),
-            ("science", "サイエンス", "Science", 5),
-            ("podcast", "ポッドキャスト", "Podcast", 6),
-        ];
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        for (id, ja, en, order) in defaults {
+        for (order, (id, ja, en)) in news_core::models::DEFAULT_CATEGORIES.iter().enumerate() {
             conn.execute(
                 "INSERT OR IGNORE INTO categories (id, label_ja, label_en, sort_order, visible) VALUES (?1, ?2, ?3, ?4, 1)",
-                params![id, ja, en, order],
+                params![id, ja, en, order as i32],
             ).map_err(|e| format!("Seed category: {e}"))?;
         }
         info!("Default categories seeded");
@@ -617,39 +2579,127 @@ impl Db {
             "INSERT OR REPLACE INTO categories (id, label_ja, label_en, sort_order, visible) VALUES (?1, ?2, ?3, ?4, 1)",
             params![id, label_ja, label_en, sort_order],
         ).map_err(|e| format!("Put category: {e}"))?;
+        // A re-added id stops being an alias of whatever replaced it
+        conn.execute("DELETE FROM category_aliases WHERE alias = ?1", params![id])
+            .map_err(|e| format!("Put category: {e}"))?;
         info!(id, label_ja, "Category saved");
         Ok(())
     }
 
-    pub fn rename_category(&self, id: &str, label_ja: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        let affected = conn.execute(
-            "UPDATE categories SET label_ja = ?1 WHERE id = ?2",
-            params![label_ja, id],
+    /// Relabel a category. With a different `new_id` the id changes as well: articles
+    /// and feeds move along in the same transaction and the old id becomes an alias.
+    pub fn rename_category(&self, id: &str, label_ja: &str, new_id: Option<&str>) -> Result<(), String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let Some(new_id) = new_id.filter(|n| *n != id) else {
+            let affected = conn.execute(
+                "UPDATE categories SET label_ja = ?1 WHERE id = ?2",
+                params![label_ja, id],
+            ).map_err(|e| format!("Rename category: {e}"))?;
+            if affected == 0 {
+                return Err(format!("Category not found: {}", id));
+            }
+            info!(id, label_ja, "Category renamed");
+            return Ok(());
+        };
+
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let copied = tx.execute(
+            "INSERT INTO categories (id, label_ja, label_en, sort_order, visible)
+             SELECT ?1, ?2, label_en, sort_order, visible FROM categories WHERE id = ?3",
+            params![new_id, label_ja, id],
         ).map_err(|e| format!("Rename category: {e}"))?;
-        if affected == 0 {
+        if copied == 0 {
             return Err(format!("Category not found: {}", id));
         }
-        info!(id, label_ja, "Category renamed");
+        let (articles, feeds) = move_category(&tx, id, new_id).map_err(|e| format!("Rename category: {e}"))?;
+        tx.execute("DELETE FROM categories WHERE id = ?1", params![id])
+            .map_err(|e| format!("Rename category: {e}"))?;
+        tx.commit().map_err(|e| e.to_string())?;
+        info!(id, new_id, label_ja, articles, feeds, "Category id changed");
         Ok(())
     }
 
-    pub fn delete_category(&self, id: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        conn.execute("DELETE FROM categories WHERE id = ?1", params![id])
+    /// Delete a category. Its articles and feeds move to `reassign_to`, which must be
+    /// given while anything still uses the category; the old id becomes an alias of it.
+    pub fn delete_category(&self, id: &str, reassign_to: Option<&str>) -> Result<(), String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        match reassign_to {
+            Some(target) => {
+                let exists: bool = tx
+                    .query_row("SELECT COUNT(*) FROM categories WHERE id = ?1", params![target], |row| {
+                        row.get::<_, i64>(0)
+                    })
+                    .map_err(|e| e.to_string())?
+                    > 0;
+                if target == id || !exists {
+                    return Err(format!("Cannot reassign to category: {}", target));
+                }
+                let (articles, feeds) =
+                    move_category(&tx, id, target).map_err(|e| format!("Delete category: {e}"))?;
+                info!(id, target, articles, feeds, "Category contents reassigned");
+            }
+            None => {
+                let (articles, feeds): (i64, i64) = tx
+                    .query_row(
+                        "SELECT (SELECT COUNT(*) FROM articles WHERE category = ?1),
+                                (SELECT COUNT(*) FROM feeds WHERE category = ?1)",
+                        params![id],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )
+                    .map_err(|e| e.to_string())?;
+                if articles + feeds > 0 {
+                    return Err(format!(
+                        "Category {} still has {} articles and {} feeds; reassign_to is required",
+                        id, articles, feeds
+                    ));
+                }
+            }
+        }
+        tx.execute("DELETE FROM categories WHERE id = ?1", params![id])
             .map_err(|e| format!("Delete category: {e}"))?;
+        tx.commit().map_err(|e| e.to_string())?;
         info!(id, "Category deleted");
         Ok(())
     }
 
-    pub fn reorder_categories(&self, order: &[String]) -> Result<(), String> {
+    /// The category a former id now points to.
+    pub fn resolve_category_alias(&self, alias: &str) -> Result<Option<String>, String> {
+        use rusqlite::OptionalExtension;
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        for (i, id) in order.iter().enumerate() {
-            conn.execute(
-                "UPDATE categories SET sort_order = ?1 WHERE id = ?2",
-                params![i as i32, id],
-            ).map_err(|e| format!("Reorder category: {e}"))?;
-        }
+        conn.query_row(
+            "SELECT category_id FROM category_aliases WHERE alias = ?1",
+            params![alias],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())
+    }
+
+    /// Articles and feeds still filed under category `id`.
+    pub fn category_usage(&self, id: &str) -> Result<(i64, i64), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT (SELECT COUNT(*) FROM articles WHERE category = ?1),
+                    (SELECT COUNT(*) FROM feeds WHERE category = ?1)",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Category usage: {e}"))
+    }
+
+    pub fn reorder_categories(&self, order: &[String]) -> Result<(), String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        with_busy_retry("Reorder categories", || {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            {
+                let mut stmt = tx.prepare("UPDATE categories SET sort_order = ?1 WHERE id = ?2")?;
+                for (i, id) in order.iter().enumerate() {
+                    stmt.execute(params![i as i32, id])?;
+                }
+            }
+            tx.commit()
+        })?;
         info!(count = order.len(), "Categories reordered");
         Ok(())
     }
@@ -659,10 +2709,23 @@ impl Db {
     pub fn create_change(&self, change: &ChangeRequest) -> Result<(), String> {
         let actions_json =
             serde_json::to_string(&change.actions).map_err(|e| format!("Serialize actions: {e}"))?;
+        let diff_json = change
+            .preview_diff
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| format!("Serialize preview diff: {e}"))?;
+        let validation_json = change
+            .validation
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| format!("Serialize validation: {e}"))?;
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         conn.execute(
-            "INSERT INTO changes (change_id, status, command_text, interpretation, actions_json, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO changes (change_id, status, command_text, interpretation, actions_json, created_at,
+                                  preview_diff_json, validation_json, parent_change_id, version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 change.change_id,
                 change.status.as_str(),
@@ -670,6 +2733,10 @@ impl Db {
                 change.interpretation,
                 actions_json,
                 change.created_at,
+                diff_json,
+                validation_json,
+                change.parent_change_id,
+                change.version,
             ],
         )
         .map_err(|e| format!("Create change: {e}"))?;
@@ -681,42 +2748,31 @@ impl Db {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
             .prepare(
-                "SELECT change_id, status, command_text, interpretation, actions_json, created_at
+                "SELECT change_id, status, command_text, interpretation, actions_json, created_at,
+                        preview_diff_json, validation_json, parent_change_id, version
                  FROM changes WHERE change_id = ?1",
             )
             .map_err(|e| e.to_string())?;
-        let result = stmt
-            .query_row(params![change_id], |row| {
-                let status_str: String = row.get(1)?;
-                let actions_json: String = row.get(4)?;
-                Ok((
-                    row.get::<_, String>(0)?,
-                    status_str,
-                    row.get::<_, String>(2)?,
-                    row.get::<_, String>(3)?,
-                    actions_json,
-                    row.get::<_, String>(5)?,
-                ))
-            })
-            .ok();
+        Ok(stmt.query_row(params![change_id], row_to_change).ok())
+    }
 
-        match result {
-            Some((change_id, status_str, command_text, interpretation, actions_json, created_at)) => {
-                let status = ChangeStatus::from_str(&status_str).unwrap_or(ChangeStatus::Pending);
-                let actions: Vec<AdminAction> =
-                    serde_json::from_str(&actions_json).unwrap_or_default();
-                Ok(Some(ChangeRequest {
-                    change_id,
-                    status,
-                    command_text,
-                    interpretation,
-                    actions,
-                    preview_config: None,
-                    created_at,
-                }))
+    /// `change_id` and the changes it revises, following `parent_change_id`; oldest first.
+    pub fn get_change_chain(&self, change_id: &str) -> Result<Vec<ChangeRequest>, String> {
+        let mut chain = Vec::new();
+        let mut next = Some(change_id.to_string());
+        while let Some(id) = next {
+            // A parent link back into the chain would loop forever
+            if chain.iter().any(|c: &ChangeRequest| c.change_id == id) {
+                break;
             }
-            None => Ok(None),
+            let Some(change) = self.get_change(&id)? else {
+                break;
+            };
+            next = change.parent_change_id.clone();
+            chain.push(change);
         }
+        chain.reverse();
+        Ok(chain)
     }
 
     pub fn update_change_status(
@@ -736,29 +2792,45 @@ impl Db {
 
     // --- Subscriptions ---
 
+    /// Record a subscription from a completed checkout and return its API token. Keyed
+    /// on the Stripe subscription, so a retried checkout event (or one arriving after a
+    /// placeholder from `upsert_subscription_status`) keeps the token already issued
+    /// and `api_token` is only used for a brand-new row.
     pub fn create_subscription(
         &self,
         api_token: &str,
         stripe_customer_id: &str,
         stripe_subscription_id: &str,
         current_period_end: &str,
-    ) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        conn.execute(
-            "INSERT OR REPLACE INTO subscriptions
-                (api_token, stripe_customer_id, stripe_subscription_id, status, current_period_end, created_at)
-             VALUES (?1, ?2, ?3, 'active', ?4, ?5)",
-            params![
-                api_token,
-                stripe_customer_id,
-                stripe_subscription_id,
-                current_period_end,
-                chrono::Utc::now().to_rfc3339(),
-            ],
-        )
-        .map_err(|e| format!("Create subscription: {e}"))?;
+    ) -> Result<String, String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let token = with_busy_retry("Create subscription", || {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            tx.execute(
+                "INSERT INTO subscriptions
+                    (api_token, stripe_customer_id, stripe_subscription_id, status, current_period_end, created_at)
+                 VALUES (?1, ?2, ?3, 'active', ?4, ?5)
+                 ON CONFLICT(stripe_subscription_id) DO UPDATE SET
+                    stripe_customer_id = excluded.stripe_customer_id,
+                    current_period_end = excluded.current_period_end",
+                params![
+                    api_token,
+                    stripe_customer_id,
+                    stripe_subscription_id,
+                    current_period_end,
+                    chrono::Utc::now().to_rfc3339(),
+                ],
+            )?;
+            let token: String = tx.query_row(
+                "SELECT api_token FROM subscriptions WHERE stripe_subscription_id = ?1",
+                params![stripe_subscription_id],
+                |row| row.get(0),
+            )?;
+            tx.commit()?;
+            Ok(token)
+        })?;
         info!(stripe_subscription_id, "Subscription created");
-        Ok(())
+        Ok(token)
     }
 
     pub fn get_subscription_by_token(
@@ -857,6 +2929,77 @@ impl Db {
         Ok(())
     }
 
+    /// Set a subscription's status from a `customer.subscription.*` event. If the event
+    /// beat `checkout.session.completed` here, a placeholder row is created with a
+    /// fresh token, which checkout then adopts via `create_subscription`.
+    pub fn upsert_subscription_status(
+        &self,
+        stripe_subscription_id: &str,
+        stripe_customer_id: &str,
+        status: &str,
+        current_period_end: Option<&str>,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO subscriptions
+                (api_token, stripe_customer_id, stripe_subscription_id, status, current_period_end, created_at)
+             VALUES (?1, ?2, ?3, ?4, COALESCE(?5, ?6), ?6)
+             ON CONFLICT(stripe_subscription_id) DO UPDATE SET
+                status = excluded.status,
+                current_period_end = COALESCE(?5, subscriptions.current_period_end)",
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                stripe_customer_id,
+                stripe_subscription_id,
+                status,
+                current_period_end,
+                now,
+            ],
+        )
+        .map_err(|e| format!("Upsert subscription: {e}"))?;
+        info!(stripe_subscription_id, status, "Subscription status upserted");
+        Ok(())
+    }
+
+    /// Mark a Stripe event as being processed. False if it was already recorded, i.e.
+    /// this delivery is a retry or replay.
+    pub fn claim_webhook_event(&self, event_id: &str, event_type: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO webhook_events (event_id, event_type, processed_at) VALUES (?1, ?2, ?3)",
+                params![event_id, event_type, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| format!("Claim webhook event: {e}"))?;
+        Ok(inserted > 0)
+    }
+
+    /// Forget a claimed event whose processing failed, so Stripe's retry is handled.
+    pub fn release_webhook_event(&self, event_id: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM webhook_events WHERE event_id = ?1", params![event_id])
+            .map_err(|e| format!("Release webhook event: {e}"))?;
+        Ok(())
+    }
+
+    pub fn webhook_event_seen(&self, event_id: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM webhook_events WHERE event_id = ?1)",
+            params![event_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Webhook event lookup: {e}"))
+    }
+
+    pub fn cleanup_webhook_events(&self, days_to_keep: i64) -> Result<usize, String> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(days_to_keep)).to_rfc3339();
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM webhook_events WHERE processed_at < ?1", params![cutoff])
+            .map_err(|e| format!("Cleanup webhook events: {e}"))
+    }
+
     // --- Usage Limits ---
 
     pub fn increment_usage(&self, device_id: &str, feature: &str) -> Result<i64, String> {
@@ -929,42 +3072,15 @@ impl Db {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
             .prepare(
-                "SELECT change_id, status, command_text, interpretation, actions_json, created_at
+                "SELECT change_id, status, command_text, interpretation, actions_json, created_at,
+                        preview_diff_json, validation_json, parent_change_id, version
                  FROM changes ORDER BY created_at DESC LIMIT ?1",
             )
             .map_err(|e| e.to_string())?;
         let changes = stmt
-            .query_map(params![limit], |row| {
-                let status_str: String = row.get(1)?;
-                let actions_json: String = row.get(4)?;
-                Ok((
-                    row.get::<_, String>(0)?,
-                    status_str,
-                    row.get::<_, String>(2)?,
-                    row.get::<_, String>(3)?,
-                    actions_json,
-                    row.get::<_, String>(5)?,
-                ))
-            })
+            .query_map(params![limit], row_to_change)
             .map_err(|e| e.to_string())?
             .filter_map(|r| r.ok())
-            .map(
-                |(change_id, status_str, command_text, interpretation, actions_json, created_at)| {
-                    let status =
-                        ChangeStatus::from_str(&status_str).unwrap_or(ChangeStatus::Pending);
-                    let actions: Vec<AdminAction> =
-                        serde_json::from_str(&actions_json).unwrap_or_default();
-                    ChangeRequest {
-                        change_id,
-                        status,
-                        command_text,
-                        interpretation,
-                        actions,
-                        preview_config: None,
-                        created_at,
-                    }
-                },
-            )
             .collect();
         Ok(changes)
     }
@@ -972,43 +3088,94 @@ impl Db {
     // --- Top Articles per Category (for TTS pre-cache) ---
 
     pub fn top_articles_per_category(&self, per_category: i64) -> Result<Vec<Article>, String> {
+        self.top_articles_per_category_since(per_category, None)
+    }
+
+    /// Like `top_articles_per_category`, limited to articles published in the last
+    /// `hours`.
+    pub fn recent_top_articles_per_category(&self, per_category: i64, hours: i64) -> Result<Vec<Article>, String> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(hours);
+        self.top_articles_per_category_since(per_category, Some(cutoff.to_rfc3339()))
+    }
+
+    fn top_articles_per_category_since(&self, per_category: i64, since: Option<String>) -> Result<Vec<Article>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
             .prepare(
                 "SELECT id, category, title, url, description, image_url, source,
-                        published_at, fetched_at, group_id, group_count
+                        published_at, fetched_at, group_id, group_count, word_count, reading_minutes
                  FROM (
                      SELECT *, ROW_NUMBER() OVER (PARTITION BY category ORDER BY published_at DESC) AS rn
                      FROM articles
-                     WHERE category != 'podcast'
+                     WHERE category != 'podcast' AND (?2 IS NULL OR published_at >= ?2)
                  )
                  WHERE rn <= ?1",
             )
             .map_err(|e| e.to_string())?;
         let articles = stmt
-            .query_map(params![per_category], row_to_article)
+            .query_map(params![per_category, since], row_to_article)
             .map_err(|e| e.to_string())?
             .filter_map(|r| r.ok())
             .collect();
         Ok(articles)
     }
 
+    /// Article counts per category published within the last `hours` (podcasts excluded).
+    pub fn category_article_counts(&self, hours: i64) -> Result<Vec<(String, i64)>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let cutoff = (chrono::Utc::now() - chrono::Duration::hours(hours)).to_rfc3339();
+        let mut stmt = conn
+            .prepare(
+                "SELECT category, COUNT(*) FROM articles
+                 WHERE category != 'podcast' AND published_at >= ?1
+                 GROUP BY category",
+            )
+            .map_err(|e| e.to_string())?;
+        let counts = stmt
+            .query_map(params![cutoff], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(counts)
+    }
+
     // --- AI Cache ---
 
     pub fn get_cache(&self, cache_key: &str) -> Result<Option<String>, String> {
+        self.get_cache_entry(cache_key, None)
+    }
+
+    /// Like `get_cache`, but only returns an entry stored under `endpoint`.
+    pub fn get_cache_for(&self, cache_key: &str, endpoint: &str) -> Result<Option<String>, String> {
+        self.get_cache_entry(cache_key, Some(endpoint))
+    }
+
+    fn get_cache_entry(&self, cache_key: &str, endpoint: Option<&str>) -> Result<Option<String>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().to_rfc3339();
         let mut stmt = conn
             .prepare(
-                "SELECT response_json FROM ai_cache WHERE cache_key = ?1 AND expires_at > ?2",
+                "SELECT response_json FROM ai_cache
+                 WHERE cache_key = ?1 AND expires_at > ?2 AND (?3 IS NULL OR endpoint = ?3)",
             )
             .map_err(|e| e.to_string())?;
         let result: Option<String> = stmt
-            .query_row(params![cache_key, now], |row| row.get(0))
+            .query_row(params![cache_key, now, endpoint], |row| row.get(0))
             .ok();
+        let counter = if result.is_some() { &self.cache_hits } else { &self.cache_misses };
+        counter.fetch_add(1, Ordering::Relaxed);
         Ok(result)
     }
 
+    pub fn stats(&self) -> DbStats {
+        DbStats {
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+            busy_waits: BUSY_WAITS.load(Ordering::Relaxed),
+            busy_timeouts: BUSY_TIMEOUTS.load(Ordering::Relaxed),
+        }
+    }
+
     pub fn set_cache(
         &self,
         cache_key: &str,
@@ -1040,12 +3207,101 @@ impl Db {
         let deleted = conn
             .execute("DELETE FROM ai_cache WHERE expires_at < ?1", params![now])
             .map_err(|e| format!("Cleanup cache: {e}"))?;
-        Ok(deleted)
+        let deleted_content = conn
+            .execute("DELETE FROM content_cache WHERE expires_at < ?1", params![now])
+            .map_err(|e| format!("Cleanup content cache: {e}"))?;
+        Ok(deleted + deleted_content)
+    }
+
+    // --- Content Cache ---
+
+    pub fn get_content_cache(&self, cache_key: &str) -> Result<Option<String>, String> {
+        use rusqlite::OptionalExtension;
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT content FROM content_cache WHERE cache_key = ?1 AND expires_at > ?2",
+            params![cache_key, chrono::Utc::now().to_rfc3339()],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Get content cache: {e}"))
+    }
+
+    pub fn set_content_cache(&self, cache_key: &str, content: &str, ttl_secs: i64) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let expires = chrono::Utc::now() + chrono::Duration::seconds(ttl_secs);
+        conn.execute(
+            "INSERT OR REPLACE INTO content_cache (cache_key, content, expires_at) VALUES (?1, ?2, ?3)",
+            params![cache_key, content, expires.to_rfc3339()],
+        )
+        .map_err(|e| format!("Set content cache: {e}"))?;
+        Ok(())
+    }
+
+    /// VACUUM, then truncate the WAL. Returns the number of free pages reclaimed.
+    pub fn vacuum_and_checkpoint(&self) -> Result<usize, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let free_pages: i64 = conn
+            .query_row("PRAGMA freelist_count", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        conn.execute_batch("VACUUM;")
+            .map_err(|e| format!("Vacuum: {e}"))?;
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+            .map_err(|e| format!("WAL checkpoint: {e}"))?;
+        Ok(free_pages as usize)
+    }
+
+    // --- Maintenance log ---
+
+    pub fn record_maintenance_run(
+        &self,
+        step: &str,
+        duration_ms: i64,
+        result: &Result<usize, String>,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let (rows, error) = match result {
+            Ok(n) => (Some(*n as i64), None),
+            Err(e) => (None, Some(e.as_str())),
+        };
+        conn.execute(
+            "INSERT OR REPLACE INTO maintenance_runs (step, last_run_at, duration_ms, rows_affected, error)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![step, chrono::Utc::now().to_rfc3339(), duration_ms, rows, error],
+        )
+        .map_err(|e| format!("Record maintenance run: {e}"))?;
+        Ok(())
+    }
+
+    /// Latest run of every maintenance step.
+    pub fn maintenance_runs(&self) -> Result<Vec<serde_json::Value>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT step, last_run_at, duration_ms, rows_affected, error
+                 FROM maintenance_runs ORDER BY step",
+            )
+            .map_err(|e| e.to_string())?;
+        let runs = stmt
+            .query_map([], |row| {
+                Ok(serde_json::json!({
+                    "step": row.get::<_, String>(0)?,
+                    "last_run_at": row.get::<_, String>(1)?,
+                    "duration_ms": row.get::<_, i64>(2)?,
+                    "rows_affected": row.get::<_, Option<i64>>(3)?,
+                    "error": row.get::<_, Option<String>>(4)?,
+                }))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(runs)
     }
 
     // --- Users (Google Auth) ---
 
-    /// Upsert a user from Google Sign-In. Returns (auth_token, user_id, is_new).
+    /// Upsert a user from Google Sign-In. Returns (auth_token, auth_token_expires_at,
+    /// user_id, is_new). Signing in again extends the expiry; an expired token is replaced.
     pub fn upsert_user(
         &self,
         google_id: &str,
@@ -1053,53 +3309,68 @@ impl Db {
         name: &str,
         picture_url: Option<&str>,
         device_id: Option<&str>,
-    ) -> Result<(String, String, bool), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        let now = chrono::Utc::now().to_rfc3339();
-
-        // Check if user already exists
-        let existing: Option<(String, String)> = conn
-            .query_row(
-                "SELECT id, auth_token FROM users WHERE google_id = ?1",
-                params![google_id],
-                |row| Ok((row.get(0)?, row.get(1)?)),
-            )
-            .ok();
+    ) -> Result<(String, String, String, bool), String> {
+        use rusqlite::OptionalExtension;
 
-        if let Some((user_id, auth_token)) = existing {
-            // Update existing user
-            conn.execute(
-                "UPDATE users SET email = ?1, name = ?2, picture_url = ?3, device_id = COALESCE(?4, device_id), updated_at = ?5 WHERE id = ?6",
-                params![email, name, picture_url, device_id, now, user_id],
-            )
-            .map_err(|e| format!("Update user: {e}"))?;
-            info!(user_id = %user_id, email = %email, "User updated");
-            Ok((auth_token, user_id, false))
-        } else {
-            // Create new user
-            let user_id = uuid::Uuid::new_v4().to_string();
-            let auth_token = format!("ga_{}", uuid::Uuid::new_v4().to_string().replace('-', ""));
-            conn.execute(
-                "INSERT INTO users (id, email, name, picture_url, google_id, auth_token, device_id, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
-                params![user_id, email, name, picture_url, google_id, auth_token, device_id, now],
-            )
-            .map_err(|e| format!("Insert user: {e}"))?;
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let expires_at = auth_token_expiry();
+
+        // IMMEDIATE so a second connection signing in the same account waits here
+        // instead of also missing the SELECT and failing the google_id UNIQUE insert.
+        let (auth_token, user_id, is_new) = with_busy_retry("Upsert user", || {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            let existing: Option<(String, String, String)> = tx
+                .query_row(
+                    "SELECT id, auth_token, auth_token_expires_at FROM users WHERE google_id = ?1",
+                    params![google_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()?;
+
+            let result = if let Some((user_id, auth_token, old_expires_at)) = existing {
+                let auth_token = if old_expires_at <= now { new_auth_token() } else { auth_token };
+                tx.execute(
+                    "UPDATE users SET email = ?1, name = ?2, picture_url = ?3, device_id = COALESCE(?4, device_id),
+                        auth_token = ?5, auth_token_expires_at = ?6, updated_at = ?7
+                     WHERE id = ?8",
+                    params![email, name, picture_url, device_id, auth_token, expires_at, now, user_id],
+                )?;
+                (auth_token, user_id, false)
+            } else {
+                let user_id = uuid::Uuid::new_v4().to_string();
+                let auth_token = new_auth_token();
+                tx.execute(
+                    "INSERT INTO users (id, email, name, picture_url, google_id, auth_token, device_id, created_at, updated_at, auth_token_expires_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8, ?9)",
+                    params![user_id, email, name, picture_url, google_id, auth_token, device_id, now, expires_at],
+                )?;
+                (auth_token, user_id, true)
+            };
+            tx.commit()?;
+            Ok(result)
+        })?;
+
+        if is_new {
             info!(user_id = %user_id, email = %email, "New user created");
-            Ok((auth_token, user_id, true))
+        } else {
+            info!(user_id = %user_id, email = %email, "User updated");
         }
+        Ok((auth_token, expires_at, user_id, is_new))
     }
 
-    /// Get a user by their auth token. Returns (user_id, email, name, picture_url, device_id, konami_claimed).
+    /// Get a user by their (unexpired) auth token. Returns (user_id, email, name, picture_url, device_id, konami_claimed).
     pub fn get_user_by_auth_token(
         &self,
         auth_token: &str,
     ) -> Result<Option<(String, String, String, Option<String>, Option<String>, bool)>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
         let result = conn
             .query_row(
-                "SELECT id, email, name, picture_url, device_id, konami_claimed FROM users WHERE auth_token = ?1",
-                params![auth_token],
+                "SELECT id, email, name, picture_url, device_id, konami_claimed FROM users
+                 WHERE auth_token = ?1 AND auth_token_expires_at > ?2",
+                params![auth_token, now],
                 |row| {
                     Ok((
                         row.get::<_, String>(0)?,
@@ -1115,6 +3386,23 @@ impl Db {
         Ok(result)
     }
 
+    /// Swap an unexpired auth token for a new one with a fresh expiry.
+    /// Returns (new_token, expires_at), or None if the old token is unknown or expired.
+    pub fn refresh_auth_token(&self, old_token: &str) -> Result<Option<(String, String)>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let new_token = new_auth_token();
+        let expires_at = auth_token_expiry();
+        let updated = conn
+            .execute(
+                "UPDATE users SET auth_token = ?1, auth_token_expires_at = ?2, updated_at = ?3
+                 WHERE auth_token = ?4 AND auth_token_expires_at > ?3",
+                params![new_token, expires_at, now, old_token],
+            )
+            .map_err(|e| format!("Refresh auth token: {e}"))?;
+        Ok((updated > 0).then_some((new_token, expires_at)))
+    }
+
     /// Claim the konami code bonus for a user. Returns true if successfully claimed, false if already used.
     pub fn claim_konami(&self, user_id: &str) -> Result<bool, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
@@ -1131,121 +3419,687 @@ impl Db {
         Ok(affected > 0)
     }
 
-    // --- Enrichment & Popularity ---
+    // --- Account data (export / delete) ---
 
-    /// Increment view count for an article and update popularity score.
-    pub fn increment_view_count(&self, article_id: &str) -> Result<i64, String> {
+    /// Everything stored about a user: the user row, chat sessions (article chats
+    /// included), usage counters and view/click dedup rows for their device, and the
+    /// subscription for `pro_token`.
+    pub fn export_account(
+        &self,
+        user_id: &str,
+        device_id: Option<&str>,
+        pro_token: Option<&str>,
+    ) -> Result<serde_json::Value, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        conn.execute(
-            "UPDATE articles SET view_count = view_count + 1 WHERE id = ?1",
-            params![article_id],
-        )
-        .map_err(|e| format!("Increment view: {e}"))?;
-
-        // Update popularity score: view_count * 0.7 + click_count * 0.3
-        conn.execute(
-            "UPDATE articles SET popularity_score = view_count * 0.7 + click_count * 0.3 WHERE id = ?1",
-            params![article_id],
-        )
-        .map_err(|e| format!("Update popularity: {e}"))?;
 
-        let view_count: i64 = conn
+        let user = conn
             .query_row(
-                "SELECT view_count FROM articles WHERE id = ?1",
-                params![article_id],
-                |row| row.get(0),
+                "SELECT id, email, name, picture_url, device_id, konami_claimed, created_at, updated_at
+                 FROM users WHERE id = ?1",
+                params![user_id],
+                |row| {
+                    Ok(serde_json::json!({
+                        "id": row.get::<_, String>(0)?,
+                        "email": row.get::<_, String>(1)?,
+                        "name": row.get::<_, String>(2)?,
+                        "picture_url": row.get::<_, Option<String>>(3)?,
+                        "device_id": row.get::<_, Option<String>>(4)?,
+                        "konami_claimed": row.get::<_, i32>(5)? != 0,
+                        "created_at": row.get::<_, String>(6)?,
+                        "updated_at": row.get::<_, String>(7)?,
+                    }))
+                },
             )
-            .map_err(|e| format!("Get view count: {e}"))?;
-        Ok(view_count)
-    }
+            .map_err(|e| format!("Export user: {e}"))?;
 
-    /// Increment click count for an article and update popularity score.
-    pub fn increment_click_count(&self, article_id: &str) -> Result<i64, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        conn.execute(
-            "UPDATE articles SET click_count = click_count + 1 WHERE id = ?1",
-            params![article_id],
-        )
-        .map_err(|e| format!("Increment click: {e}"))?;
+        let device = device_id.unwrap_or("");
+        let mut stmt = conn
+            .prepare("SELECT feature, used_date, count FROM usage_limits WHERE device_id = ?1 ORDER BY used_date")
+            .map_err(|e| e.to_string())?;
+        let usage: Vec<serde_json::Value> = stmt
+            .query_map(params![device], |row| {
+                Ok(serde_json::json!({
+                    "feature": row.get::<_, String>(0)?,
+                    "date": row.get::<_, String>(1)?,
+                    "count": row.get::<_, i64>(2)?,
+                }))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
 
-        // Update popularity score
-        conn.execute(
-            "UPDATE articles SET popularity_score = view_count * 0.7 + click_count * 0.3 WHERE id = ?1",
-            params![article_id],
-        )
-        .map_err(|e| format!("Update popularity: {e}"))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT session_id, article_id, messages_json, created_at, updated_at
+                 FROM chat_sessions WHERE device_id = ?1 OR owner IN (?2, ?3) ORDER BY created_at",
+            )
+            .map_err(|e| e.to_string())?;
+        let owners = (format!("user:{}", user_id), format!("device:{}", device));
+        let chat_sessions: Vec<serde_json::Value> = stmt
+            .query_map(params![device, owners.0, owners.1], |row| {
+                let messages: String = row.get(2)?;
+                Ok(serde_json::json!({
+                    "session_id": row.get::<_, String>(0)?,
+                    "article_id": row.get::<_, Option<String>>(1)?,
+                    "messages": serde_json::from_str::<serde_json::Value>(&messages).unwrap_or_default(),
+                    "created_at": row.get::<_, String>(3)?,
+                    "updated_at": row.get::<_, String>(4)?,
+                }))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
 
-        let click_count: i64 = conn
-            .query_row(
-                "SELECT click_count FROM articles WHERE id = ?1",
-                params![article_id],
-                |row| row.get(0),
+        let mut stmt = conn
+            .prepare("SELECT article_id, kind, day, count FROM views_dedup WHERE visitor = ?1 ORDER BY day")
+            .map_err(|e| e.to_string())?;
+        let engagement: Vec<serde_json::Value> = stmt
+            .query_map(params![format!("d:{}", device)], |row| {
+                Ok(serde_json::json!({
+                    "article_id": row.get::<_, String>(0)?,
+                    "kind": row.get::<_, String>(1)?,
+                    "day": row.get::<_, String>(2)?,
+                    "count": row.get::<_, i64>(3)?,
+                }))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT stripe_customer_id, stripe_subscription_id, status, current_period_end, created_at
+                 FROM subscriptions WHERE api_token = ?1",
             )
-            .map_err(|e| format!("Get click count: {e}"))?;
-        Ok(click_count)
-    }
+            .map_err(|e| e.to_string())?;
+        let subscriptions: Vec<serde_json::Value> = stmt
+            .query_map(params![pro_token.unwrap_or("")], |row| {
+                Ok(serde_json::json!({
+                    "stripe_customer_id": row.get::<_, String>(0)?,
+                    "stripe_subscription_id": row.get::<_, String>(1)?,
+                    "status": row.get::<_, String>(2)?,
+                    "current_period_end": row.get::<_, String>(3)?,
+                    "created_at": row.get::<_, String>(4)?,
+                }))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
 
-    /// Get popular articles by percentile range (e.g., top 10-20%).
-    /// Returns articles with popularity_score in the specified percentile range, ordered by score DESC.
-    pub fn get_popular_articles(&self, min_percentile: f64, max_percentile: f64, limit: i64) -> Result<Vec<Article>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        Ok(serde_json::json!({
+            "user": user,
+            "usage": usage,
+            "chat_sessions": chat_sessions,
+            "engagement": engagement,
+            "subscriptions": subscriptions,
+        }))
+    }
 
-        // Get total article count
-        let total: i64 = conn
-            .query_row("SELECT COUNT(*) FROM articles WHERE popularity_score > 0", [], |row| row.get(0))
-            .unwrap_or(0);
+    /// Delete a user's device- and token-keyed rows, one transaction per table.
+    /// Returns (table, rows deleted or error) for each table; the user row itself is
+    /// removed separately by `delete_user` once everything else succeeded.
+    pub fn delete_account_data(
+        &self,
+        user_id: &str,
+        device_id: Option<&str>,
+        pro_token: Option<&str>,
+    ) -> Vec<(&'static str, Result<usize, String>)> {
+        let mut conn = match self.conn.lock() {
+            Ok(c) => c,
+            Err(e) => return vec![("lock", Err(e.to_string()))],
+        };
+        let device = device_id.unwrap_or("");
+        let visitor = format!("d:{}", device);
+        let user_owner = format!("user:{}", user_id);
+        let device_owner = if device.is_empty() { String::new() } else { format!("device:{}", device) };
+        let steps: [(&'static str, &str, &str); 10] = [
+            ("usage_limits", "DELETE FROM usage_limits WHERE device_id = ?1", device),
+            ("chat_sessions", "DELETE FROM chat_sessions WHERE device_id = ?1", device),
+            ("chat_sessions", "DELETE FROM chat_sessions WHERE owner = ?1", &user_owner),
+            ("chat_sessions", "DELETE FROM chat_sessions WHERE owner = ?1", &device_owner),
+            ("saved_searches", "DELETE FROM saved_searches WHERE owner_id = ?1", &user_owner),
+            ("saved_searches", "DELETE FROM saved_searches WHERE owner_id = ?1", &device_owner),
+            ("mute_rules", "DELETE FROM mute_rules WHERE owner_id = ?1", &user_owner),
+            ("mute_rules", "DELETE FROM mute_rules WHERE owner_id = ?1", &device_owner),
+            ("views_dedup", "DELETE FROM views_dedup WHERE visitor = ?1", &visitor),
+            ("subscriptions", "DELETE FROM subscriptions WHERE api_token = ?1", pro_token.unwrap_or("")),
+        ];
 
-        if total == 0 {
-            return Ok(Vec::new());
+        let mut results = Vec::new();
+        for (table, sql, key) in steps {
+            if key.is_empty() || key == "d:" {
+                results.push((table, Ok(0)));
+                continue;
+            }
+            let result = conn
+                .transaction()
+                .and_then(|tx| {
+                    let n = tx.execute(sql, params![key])?;
+                    tx.commit()?;
+                    Ok(n)
+                })
+                .map_err(|e| format!("Delete {table}: {e}"));
+            results.push((table, result));
         }
+        results
+    }
 
-        // Calculate offset and limit based on percentiles
-        let skip = ((100.0 - max_percentile) / 100.0 * total as f64).floor() as i64;
-        let take = (((max_percentile - min_percentile) / 100.0 * total as f64).ceil() as i64).min(limit);
+    /// Delete the user row, which also invalidates their auth token.
+    pub fn delete_user(&self, user_id: &str) -> Result<usize, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let n = conn
+            .execute("DELETE FROM users WHERE id = ?1", params![user_id])
+            .map_err(|e| format!("Delete user: {e}"))?;
+        info!(user_id, "User account deleted");
+        Ok(n)
+    }
+
+    // --- Chat sessions ---
 
+    pub fn get_chat_session(&self, session_id: &str) -> Result<Option<ChatSession>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
             .prepare(
-                "SELECT id, category, title, url, description, image_url, source,
-                        published_at, fetched_at, group_id, group_count
-                 FROM articles
-                 WHERE popularity_score > 0
-                 ORDER BY popularity_score DESC, published_at DESC
-                 LIMIT ?1 OFFSET ?2",
+                "SELECT device_id, owner, article_id, messages_json, created_at, updated_at
+                 FROM chat_sessions WHERE session_id = ?1",
             )
             .map_err(|e| e.to_string())?;
-
-        let articles = stmt
-            .query_map(params![take, skip], row_to_article)
-            .map_err(|e| e.to_string())?
-            .filter_map(|r| r.ok())
-            .collect();
-        Ok(articles)
+        let mut rows = stmt
+            .query_map(params![session_id], |row| {
+                Ok(ChatSession {
+                    device_id: row.get(0)?,
+                    owner: row.get(1)?,
+                    article_id: row.get(2)?,
+                    messages_json: row.get(3)?,
+                    created_at: row.get(4)?,
+                    updated_at: row.get(5)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        match rows.next() {
+            Some(Ok(session)) => Ok(Some(session)),
+            Some(Err(e)) => Err(e.to_string()),
+            None => Ok(None),
+        }
     }
 
-    /// Update enrichment status for an article.
-    pub fn update_enrichment_status(&self, article_id: &str, status: &str) -> Result<(), String> {
+    /// Create or update a session; its device, owner and article are set on creation.
+    pub fn save_chat_session(
+        &self,
+        session_id: &str,
+        device_id: Option<&str>,
+        owner: Option<&str>,
+        article_id: Option<&str>,
+        messages_json: &str,
+    ) -> Result<(), String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().to_rfc3339();
         conn.execute(
-            "UPDATE articles SET enrichment_status = ?1, enriched_at = ?2 WHERE id = ?3",
-            params![status, now, article_id],
+            "INSERT INTO chat_sessions (session_id, device_id, owner, article_id, messages_json, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+             ON CONFLICT(session_id) DO UPDATE SET messages_json = excluded.messages_json, updated_at = excluded.updated_at",
+            params![session_id, device_id, owner, article_id, messages_json, now],
         )
-        .map_err(|e| format!("Update enrichment status: {e}"))?;
+        .map_err(|e| format!("Save chat session: {e}"))?;
         Ok(())
     }
 
-    /// Create an enrichment record.
-    pub fn create_enrichment(
-        &self,
-        enrichment_id: &str,
-        article_id: &str,
-        agent_type: &str,
-        content_type: &str,
-        data_json: &str,
-    ) -> Result<(), String> {
+    /// Sessions of `owner` with activity after `since` (RFC 3339).
+    pub fn count_active_chat_sessions(&self, owner: &str, since: &str) -> Result<i64, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        let now = chrono::Utc::now().to_rfc3339();
-        conn.execute(
+        conn.query_row(
+            "SELECT COUNT(*) FROM chat_sessions WHERE owner = ?1 AND updated_at >= ?2",
+            params![owner, since],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    /// Delete article chats (sessions with an owner) idle for more than `max_age_hours`.
+    pub fn prune_owned_chat_sessions(&self, max_age_hours: i64) -> Result<usize, String> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::hours(max_age_hours)).to_rfc3339();
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM chat_sessions WHERE owner IS NOT NULL AND updated_at < ?1", params![cutoff])
+            .map_err(|e| format!("Prune chat sessions: {e}"))
+    }
+
+    // --- Saved searches ---
+
+    pub fn list_saved_searches(&self, owner: &str) -> Result<Vec<SavedSearch>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT s.id, s.query, s.category_filter, s.created_at, s.last_notified_at,
+                        (SELECT COUNT(*) FROM search_matches m WHERE m.search_id = s.id AND m.seen = 0)
+                 FROM saved_searches s WHERE s.owner_id = ?1 ORDER BY s.created_at",
+            )
+            .map_err(|e| e.to_string())?;
+        let searches = stmt
+            .query_map(params![owner], |row| {
+                Ok(SavedSearch {
+                    id: row.get(0)?,
+                    query: row.get(1)?,
+                    category_filter: row.get(2)?,
+                    created_at: row.get(3)?,
+                    last_notified_at: row.get(4)?,
+                    unseen: row.get(5)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(searches)
+    }
+
+    /// Insert a saved search unless `owner` already has `max`. Returns false when full.
+    pub fn create_saved_search(
+        &self,
+        id: &str,
+        owner: &str,
+        query: &str,
+        category_filter: Option<&str>,
+        max: i64,
+    ) -> Result<bool, String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn
+            .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+            .map_err(|e| e.to_string())?;
+        let count: i64 = tx
+            .query_row("SELECT COUNT(*) FROM saved_searches WHERE owner_id = ?1", params![owner], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if count >= max {
+            return Ok(false);
+        }
+        tx.execute(
+            "INSERT INTO saved_searches (id, owner_id, query, category_filter, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id, owner, query, category_filter, chrono::Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| format!("Save search: {e}"))?;
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    /// Change the query and category of `owner`'s search. Returns false if not found.
+    /// Matches of the old query are kept.
+    pub fn update_saved_search(
+        &self,
+        id: &str,
+        owner: &str,
+        query: &str,
+        category_filter: Option<&str>,
+    ) -> Result<bool, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let n = conn
+            .execute(
+                "UPDATE saved_searches SET query = ?3, category_filter = ?4 WHERE id = ?1 AND owner_id = ?2",
+                params![id, owner, query, category_filter],
+            )
+            .map_err(|e| format!("Update search: {e}"))?;
+        Ok(n > 0)
+    }
+
+    /// Delete `owner`'s search with its matches. Returns false if not found.
+    pub fn delete_saved_search(&self, id: &str, owner: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let n = conn
+            .execute("DELETE FROM saved_searches WHERE id = ?1 AND owner_id = ?2", params![id, owner])
+            .map_err(|e| format!("Delete search: {e}"))?;
+        Ok(n > 0)
+    }
+
+    // --- Mute rules ---
+
+    pub fn list_mute_rules(&self, owner: &str) -> Result<Vec<MuteRule>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT id, pattern, field, created_at FROM mute_rules WHERE owner_id = ?1 ORDER BY created_at")
+            .map_err(|e| e.to_string())?;
+        let rules = stmt
+            .query_map(params![owner], |row| {
+                let field: String = row.get(2)?;
+                Ok(MuteRule {
+                    id: row.get(0)?,
+                    pattern: row.get(1)?,
+                    field: MuteField::parse(&field).unwrap_or_default(),
+                    created_at: row.get(3)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rules)
+    }
+
+    /// Insert a mute rule unless `owner` already has `max`. Returns None when full.
+    pub fn create_mute_rule(
+        &self,
+        owner: &str,
+        pattern: &str,
+        field: MuteField,
+        max: i64,
+    ) -> Result<Option<MuteRule>, String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn
+            .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+            .map_err(|e| e.to_string())?;
+        let count: i64 = tx
+            .query_row("SELECT COUNT(*) FROM mute_rules WHERE owner_id = ?1", params![owner], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if count >= max {
+            return Ok(None);
+        }
+        let rule = MuteRule {
+            id: uuid::Uuid::new_v4().to_string(),
+            pattern: pattern.to_string(),
+            field,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        tx.execute(
+            "INSERT INTO mute_rules (id, owner_id, pattern, field, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![rule.id, owner, rule.pattern, field.as_str(), rule.created_at],
+        )
+        .map_err(|e| format!("Save mute rule: {e}"))?;
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(Some(rule))
+    }
+
+    /// Delete `owner`'s mute rule. Returns false if not found.
+    pub fn delete_mute_rule(&self, id: &str, owner: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let n = conn
+            .execute("DELETE FROM mute_rules WHERE id = ?1 AND owner_id = ?2", params![id, owner])
+            .map_err(|e| format!("Delete mute rule: {e}"))?;
+        Ok(n > 0)
+    }
+
+    /// Record which of `article_ids` match each saved search, skipping articles that were
+    /// already stored before the search was created. Returns the number of new matches.
+    pub fn match_saved_searches(&self, article_ids: &[&str]) -> Result<usize, String> {
+        if article_ids.is_empty() {
+            return Ok(0);
+        }
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut matched = 0;
+        for chunk in article_ids.chunks(INSERT_BATCH_SIZE) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let sql = format!(
+                "INSERT OR IGNORE INTO search_matches (search_id, article_id, matched_at)
+                 SELECT s.id, a.id, ?
+                 FROM saved_searches s JOIN articles a
+                   ON {}
+                  AND (s.category_filter IS NULL OR a.category = s.category_filter)
+                  AND a.fetched_at >= s.created_at
+                 WHERE a.id IN ({placeholders})",
+                search_condition("a", &sql_like_pattern("s.query"))
+            );
+            let values = std::iter::once(now.as_str()).chain(chunk.iter().copied());
+            matched += conn
+                .execute(&sql, rusqlite::params_from_iter(values))
+                .map_err(|e| format!("Match saved searches: {e}"))?;
+        }
+        Ok(matched)
+    }
+
+    /// Unseen matches of `owner`'s search, newest article first, marking them seen.
+    /// None if the search doesn't exist or belongs to someone else.
+    pub fn take_unseen_matches(&self, id: &str, owner: &str, limit: i64) -> Result<Option<Vec<Article>>, String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn
+            .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+            .map_err(|e| e.to_string())?;
+        let owned = tx
+            .query_row(
+                "SELECT 1 FROM saved_searches WHERE id = ?1 AND owner_id = ?2",
+                params![id, owner],
+                |_| Ok(()),
+            )
+            .is_ok();
+        if !owned {
+            return Ok(None);
+        }
+        let articles: Vec<Article> = {
+            let mut stmt = tx
+                .prepare(
+                    "SELECT a.id, a.category, a.title, a.url, a.description, a.image_url, a.source,
+                            a.published_at, a.fetched_at, a.group_id, a.group_count, a.word_count, a.reading_minutes
+                     FROM search_matches m JOIN articles a ON a.id = m.article_id
+                     WHERE m.search_id = ?1 AND m.seen = 0
+                     ORDER BY a.published_at DESC LIMIT ?2",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![id, limit], row_to_article)
+                .map_err(|e| e.to_string())?
+                .filter_map(|r| r.ok())
+                .collect();
+            rows
+        };
+        for article in &articles {
+            tx.execute(
+                "UPDATE search_matches SET seen = 1 WHERE search_id = ?1 AND article_id = ?2",
+                params![id, article.id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(Some(articles))
+    }
+
+    /// Per search of `owner`: matches recorded since the last call (or since creation),
+    /// as `(search_id, query, new, unseen)`. Advances `last_notified_at`.
+    pub fn saved_search_updates(&self, owner: &str) -> Result<Vec<(String, String, i64, i64)>, String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn
+            .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+            .map_err(|e| e.to_string())?;
+        let updates = {
+            let mut stmt = tx
+                .prepare(
+                    "SELECT s.id, s.query,
+                            (SELECT COUNT(*) FROM search_matches m
+                             WHERE m.search_id = s.id AND m.matched_at > COALESCE(s.last_notified_at, '')),
+                            (SELECT COUNT(*) FROM search_matches m WHERE m.search_id = s.id AND m.seen = 0)
+                     FROM saved_searches s WHERE s.owner_id = ?1 ORDER BY s.created_at",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![owner], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+                .map_err(|e| e.to_string())?
+                .filter_map(|r| r.ok())
+                .collect();
+            rows
+        };
+        tx.execute(
+            "UPDATE saved_searches SET last_notified_at = ?2 WHERE owner_id = ?1",
+            params![owner, chrono::Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(updates)
+    }
+
+    // --- Enrichment & Popularity ---
+
+    /// Count a view or click unless the same visitor already counted one for this
+    /// article within `window_minutes`, or has hit `daily_cap` today. `visitor: None`
+    /// (e.g. a bot) never counts. Returns the article's current counter either way.
+    pub fn record_engagement(
+        &self,
+        article_id: &str,
+        kind: &str,
+        visitor: Option<&str>,
+        window_minutes: i64,
+        daily_cap: i64,
+        restore_image_score: f64,
+    ) -> Result<i64, String> {
+        let column = match kind {
+            "view" => "view_count",
+            "click" => "click_count",
+            _ => return Err(format!("Unknown engagement kind: {kind}")),
+        };
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+
+        // The dedup row, the counter bump and the popularity_score derived from it
+        // commit together, so the score never lags behind its counters.
+        with_busy_retry("Record engagement", || {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            let counted = match visitor {
+                Some(visitor) => {
+                    let now = chrono::Utc::now();
+                    let day = now.format("%Y-%m-%d").to_string();
+                    let window_start = (now - chrono::Duration::minutes(window_minutes)).to_rfc3339();
+                    tx.execute(
+                        "INSERT INTO views_dedup (article_id, kind, visitor, day, count, last_at)
+                         VALUES (?1, ?2, ?3, ?4, 1, ?5)
+                         ON CONFLICT(article_id, kind, visitor, day) DO UPDATE
+                            SET count = count + 1, last_at = excluded.last_at
+                            WHERE views_dedup.last_at < ?6 AND views_dedup.count < ?7",
+                        params![article_id, kind, visitor, day, now.to_rfc3339(), window_start, daily_cap],
+                    )? > 0
+                }
+                None => false,
+            };
+
+            if counted {
+                // Separate statements: within one UPDATE, POPULARITY_EXPR would still
+                // read the counter's pre-increment value.
+                tx.execute(
+                    &format!("UPDATE articles SET {column} = {column} + 1 WHERE id = ?1"),
+                    params![article_id],
+                )?;
+                tx.execute(
+                    &format!("UPDATE articles SET popularity_score = {POPULARITY_EXPR} WHERE id = ?1"),
+                    params![article_id],
+                )?;
+                if restore_degraded_image(&tx, article_id, restore_image_score)? {
+                    info!(article_id, "Degraded image restored");
+                }
+                if kind == "view" {
+                    tx.execute(
+                        "INSERT INTO view_events (article_id, viewed_at) VALUES (?1, ?2)",
+                        params![article_id, chrono::Utc::now().to_rfc3339()],
+                    )?;
+                }
+            }
+
+            let count: i64 = tx.query_row(
+                &format!("SELECT {column} FROM articles WHERE id = ?1"),
+                params![article_id],
+                |row| row.get(0),
+            )?;
+            tx.commit()?;
+            Ok(count)
+        })
+    }
+
+    /// Recalculate popularity_score for every article from its stored counters.
+    pub fn recompute_popularity(&self) -> Result<usize, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            &format!("UPDATE articles SET popularity_score = {POPULARITY_EXPR} WHERE popularity_score != {POPULARITY_EXPR}"),
+            [],
+        )
+        .map_err(|e| format!("Recompute popularity: {e}"))
+    }
+
+    pub fn cleanup_views_dedup(&self, days_to_keep: i64) -> Result<usize, String> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(days_to_keep))
+            .format("%Y-%m-%d")
+            .to_string();
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM views_dedup WHERE day < ?1", params![cutoff])
+            .map_err(|e| format!("Cleanup views dedup: {e}"))
+    }
+
+    pub fn cleanup_view_events(&self, days_to_keep: i64) -> Result<usize, String> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(days_to_keep)).to_rfc3339();
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM view_events WHERE viewed_at < ?1", params![cutoff])
+            .map_err(|e| format!("Cleanup view events: {e}"))
+    }
+
+    /// Counted views of the last `days` days per (weekday, hour) slot in UTC, as
+    /// `("%w %H", count)` — "0 00" is Sunday midnight. Empty slots are omitted.
+    pub fn get_hourly_view_counts(&self, days: i64) -> Result<Vec<(String, i64)>, String> {
+        let since = (chrono::Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT strftime('%w %H', viewed_at) AS slot, COUNT(*) FROM view_events
+                 WHERE viewed_at > ?1 GROUP BY slot ORDER BY slot",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Get popular articles by percentile range (e.g., top 10-20%).
+    /// Returns articles with popularity_score in the specified percentile range, ordered by score DESC.
+    pub fn get_popular_articles(&self, min_percentile: f64, max_percentile: f64, limit: i64) -> Result<Vec<Article>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+
+        // Get total article count
+        let total: i64 = conn
+            .query_row("SELECT COUNT(*) FROM articles WHERE popularity_score > 0", [], |row| row.get(0))
+            .unwrap_or(0);
+
+        if total == 0 {
+            return Ok(Vec::new());
+        }
+
+        // Calculate offset and limit based on percentiles
+        let skip = ((100.0 - max_percentile) / 100.0 * total as f64).floor() as i64;
+        let take = (((max_percentile - min_percentile) / 100.0 * total as f64).ceil() as i64).min(limit);
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, category, title, url, description, image_url, source,
+                        published_at, fetched_at, group_id, group_count, word_count, reading_minutes
+                 FROM articles
+                 WHERE popularity_score > 0
+                 ORDER BY popularity_score DESC, published_at DESC
+                 LIMIT ?1 OFFSET ?2",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let articles = stmt
+            .query_map(params![take, skip], row_to_article)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(articles)
+    }
+
+    /// Update enrichment status for an article.
+    pub fn update_enrichment_status(&self, article_id: &str, status: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE articles SET enrichment_status = ?1, enriched_at = ?2 WHERE id = ?3",
+            params![status, now, article_id],
+        )
+        .map_err(|e| format!("Update enrichment status: {e}"))?;
+        Ok(())
+    }
+
+    /// Create an enrichment record.
+    pub fn create_enrichment(
+        &self,
+        enrichment_id: &str,
+        article_id: &str,
+        agent_type: &str,
+        content_type: &str,
+        data_json: &str,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
             "INSERT INTO enrichments (enrichment_id, article_id, agent_type, content_type, data_json, status, created_at)
              VALUES (?1, ?2, ?3, ?4, ?5, 'pending', ?6)",
             params![enrichment_id, article_id, agent_type, content_type, data_json, now],
@@ -1309,39 +4163,84 @@ impl Db {
         Ok(enrichments)
     }
 
-    /// Degrade images for old unpopular articles (older than hours_old, below median popularity).
-    pub fn degrade_old_unpopular_images(&self, hours_old: i64) -> Result<usize, String> {
-        let cutoff = (chrono::Utc::now() - chrono::Duration::hours(hours_old)).to_rfc3339();
+    /// Recent enrichment rows, newest first, optionally filtered by status.
+    pub fn list_enrichments(&self, status: Option<&str>, limit: i64) -> Result<Vec<EnrichmentRecord>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT enrichment_id, article_id, agent_type, status, error_message, created_at, completed_at
+                 FROM enrichments
+                 WHERE ?1 IS NULL OR status = ?1
+                 ORDER BY created_at DESC
+                 LIMIT ?2",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![status, limit], |row| {
+                Ok(EnrichmentRecord {
+                    enrichment_id: row.get(0)?,
+                    article_id: row.get(1)?,
+                    agent_type: row.get(2)?,
+                    status: row.get(3)?,
+                    error_message: row.get(4)?,
+                    created_at: row.get(5)?,
+                    completed_at: row.get(6)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Hide the images of old articles below `policy.percentile` in popularity. The
+    /// URL moves to original_image_url, for `restore_degraded_image`. Articles that
+    /// were restored once are not degraded again.
+    pub fn degrade_old_unpopular_images(&self, policy: &ImageDegradationPolicy) -> Result<usize, String> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::hours(policy.hours_old)).to_rfc3339();
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
 
-        // Get median popularity score for old articles
-        let median_score: f64 = conn
+        // Popularity score at the percentile among old articles
+        let threshold: f64 = conn
             .query_row(
                 "SELECT popularity_score FROM articles
                  WHERE published_at < ?1 AND popularity_score > 0
                  ORDER BY popularity_score
-                 LIMIT 1 OFFSET (SELECT COUNT(*) FROM articles WHERE published_at < ?1 AND popularity_score > 0) / 2",
-                params![cutoff],
+                 LIMIT 1 OFFSET CAST(
+                    (SELECT COUNT(*) FROM articles WHERE published_at < ?1 AND popularity_score > 0) * ?2
+                 AS INTEGER)",
+                params![cutoff, policy.percentile],
                 |row| row.get(0),
             )
             .unwrap_or(0.0);
 
-        // Degrade images for articles below median popularity
         let degraded = conn
             .execute(
                 "UPDATE articles
-                 SET image_url = NULL
+                 SET original_image_url = image_url, image_url = NULL, image_degraded_at = ?3
                  WHERE published_at < ?1
                  AND popularity_score < ?2
                  AND popularity_score > 0
-                 AND image_url IS NOT NULL",
-                params![cutoff, median_score],
+                 AND image_url IS NOT NULL
+                 AND image_restored_at IS NULL",
+                params![cutoff, threshold, chrono::Utc::now().to_rfc3339()],
             )
             .map_err(|e| format!("Degrade images: {}", e))?;
 
         Ok(degraded)
     }
 
+    /// (articles with a degraded image, articles whose image was restored)
+    pub fn image_degradation_counts(&self) -> Result<(i64, i64), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT COUNT(image_degraded_at), COUNT(image_restored_at) FROM articles",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Count degraded images: {e}"))
+    }
+
     /// Delete bottom 80% of articles older than days_old (keep top 20% by popularity).
     pub fn cleanup_old_articles_bottom_80(&self, days_old: i64) -> Result<usize, String> {
         let cutoff = (chrono::Utc::now() - chrono::Duration::days(days_old)).to_rfc3339();
@@ -1378,7 +4277,7 @@ impl Db {
         let mut stmt = conn
             .prepare(
                 "SELECT id, category, title, url, description, image_url, source,
-                        published_at, fetched_at, group_id, group_count
+                        published_at, fetched_at, group_id, group_count, word_count, reading_minutes
                  FROM articles
                  WHERE enrichment_status = 'pending'
                  ORDER BY popularity_score DESC, published_at DESC
@@ -1400,66 +4299,184 @@ impl Db {
         category: Option<&Category>,
         minutes: i64,
         limit: i64,
+        include_dead: bool,
+        exclude_types: &[String],
     ) -> Result<Vec<Article>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let cutoff = (chrono::Utc::now() - chrono::Duration::minutes(minutes))
             .to_rfc3339();
 
-        let sql = if category.is_some() {
-            "SELECT id, category, title, url, description, image_url, source,
-                    published_at, fetched_at, group_id, group_count
-             FROM articles
-             WHERE category = ?1 AND published_at >= ?2
-             ORDER BY published_at DESC
-             LIMIT ?3"
-        } else {
-            "SELECT id, category, title, url, description, image_url, source,
-                    published_at, fetched_at, group_id, group_count
-             FROM articles
-             WHERE published_at >= ?1
+        let sql = format!(
+            "SELECT {JOINED_ARTICLE_COLUMNS}
+             FROM articles {SOURCE_META_JOIN}
+             WHERE (?1 IS NULL OR category = ?1) AND published_at >= ?2 AND (?4 OR dead_link != 1)
+               AND {excluded}
              ORDER BY published_at DESC
-             LIMIT ?2"
-        };
+             LIMIT ?3",
+            excluded = exclude_types_condition("?5"),
+        );
 
-        let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
 
-        let articles = if let Some(cat) = category {
-            stmt.query_map(params![cat.as_str(), cutoff, limit], row_to_article)
-        } else {
-            stmt.query_map(params![cutoff, limit], row_to_article)
-        }
-        .map_err(|e| e.to_string())?
-        .filter_map(|r| r.ok())
-        .collect();
+        let articles = stmt
+            .query_map(
+                params![category.map(|c| c.as_str()), cutoff, limit, include_dead, exclude_types_param(exclude_types)],
+                row_to_article,
+            )
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
 
         Ok(articles)
     }
 
-    // --- AI Analysis ---
+    // --- Query plans ---
 
-    /// Get articles that need AI analysis (not yet analyzed)
-    pub fn get_articles_for_analysis(&self, limit: i64) -> Result<Vec<Article>, String> {
+    /// SQLite's `EXPLAIN QUERY PLAN` for one of `EXPLAIN_QUERIES`, as (sql, steps), or
+    /// None for an unknown name. Parameters are left unbound; they don't change the plan.
+    pub fn explain_query_plan(&self, query_name: &str) -> Result<Option<(String, Vec<QueryPlanStep>)>, String> {
+        let Some(sql) = explain_query_sql(query_name) else {
+            return Ok(None);
+        };
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(&format!("EXPLAIN QUERY PLAN {sql}"))
+            .map_err(|e| format!("Explain {query_name}: {e}"))?;
+        let mut rows = stmt.raw_query();
+        let mut plan = Vec::new();
+        while let Some(row) = rows.next().map_err(|e| format!("Explain {query_name}: {e}"))? {
+            plan.push(QueryPlanStep {
+                id: row.get(0).map_err(|e| e.to_string())?,
+                parent: row.get(1).map_err(|e| e.to_string())?,
+                notused: row.get(2).map_err(|e| e.to_string())?,
+                detail: row.get(3).map_err(|e| e.to_string())?,
+            });
+        }
+        Ok(Some((sql, plan)))
+    }
+
+    /// Indexes on `articles` with their columns and on-disk size (from `dbstat`).
+    pub fn article_indexes(&self) -> Result<Vec<IndexInfo>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
             .prepare(
-                "SELECT id, category, title, url, description, image_url, source,
-                        published_at, fetched_at, group_id, group_count
-                 FROM articles
-                 WHERE analyzed_at IS NULL
-                   AND description IS NOT NULL
-                   AND length(description) > 10
-                 ORDER BY published_at DESC
-                 LIMIT ?1",
+                "SELECT l.name, l.\"unique\", l.origin, l.partial,
+                        (SELECT json_group_array(i.name) FROM pragma_index_info(l.name) i),
+                        (SELECT SUM(pgsize) FROM dbstat WHERE dbstat.name = l.name)
+                 FROM pragma_index_list('articles') l
+                 ORDER BY l.name",
             )
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| format!("Article indexes: {e}"))?;
+        let indexes = stmt
+            .query_map([], |row| {
+                let columns: String = row.get(4)?;
+                Ok(IndexInfo {
+                    name: row.get(0)?,
+                    unique: row.get::<_, i64>(1)? != 0,
+                    origin: row.get(2)?,
+                    partial: row.get::<_, i64>(3)? != 0,
+                    // Expression columns come back as null
+                    columns: serde_json::from_str::<Vec<Option<String>>>(&columns)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|c| c.unwrap_or_else(|| "<expr>".into()))
+                        .collect(),
+                    size_bytes: row.get::<_, Option<i64>>(5)?.unwrap_or(0),
+                })
+            })
+            .map_err(|e| format!("Article indexes: {e}"))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| format!("Article indexes: {e}"))?;
+        Ok(indexes)
+    }
 
-        let articles = stmt
-            .query_map(params![limit], row_to_article)
-            .map_err(|e| e.to_string())?
-            .filter_map(|r| r.ok())
-            .collect();
+    // --- Admin Audit Log ---
 
-        Ok(articles)
+    /// Record an admin operation with its parameters.
+    pub fn record_admin_audit(&self, action: &str, details: &serde_json::Value) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO admin_audit_log (action, details, created_at) VALUES (?1, ?2, ?3)",
+            params![action, details.to_string(), Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| format!("Admin audit: {e}"))?;
+        Ok(())
+    }
+
+    /// Record `action` unless it was already recorded within `cooldown`. The check and
+    /// the insert are one statement, so concurrent callers can't both get through.
+    /// Returns the new entry's id, or None if a recent entry blocked it.
+    pub fn record_admin_audit_unless_recent(
+        &self,
+        action: &str,
+        details: &serde_json::Value,
+        cooldown: chrono::Duration,
+    ) -> Result<Option<i64>, String> {
+        let now = Utc::now();
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let inserted = conn
+            .execute(
+                "INSERT INTO admin_audit_log (action, details, created_at)
+                 SELECT ?1, ?2, ?3
+                 WHERE NOT EXISTS (SELECT 1 FROM admin_audit_log WHERE action = ?1 AND created_at > ?4)",
+                params![action, details.to_string(), now.to_rfc3339(), (now - cooldown).to_rfc3339()],
+            )
+            .map_err(|e| format!("Admin audit: {e}"))?;
+        Ok((inserted > 0).then(|| conn.last_insert_rowid()))
+    }
+
+    /// Replace the details of audit entry `log_id`, e.g. with the outcome of the
+    /// action it claimed.
+    pub fn update_admin_audit_details(&self, log_id: i64, details: &serde_json::Value) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE admin_audit_log SET details = ?2 WHERE log_id = ?1",
+            params![log_id, details.to_string()],
+        )
+        .map_err(|e| format!("Admin audit: {e}"))?;
+        Ok(())
+    }
+
+    /// When `action` was last recorded, if ever.
+    pub fn last_admin_audit_at(&self, action: &str) -> Result<Option<DateTime<Utc>>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let at: Option<String> = conn
+            .query_row(
+                "SELECT MAX(created_at) FROM admin_audit_log WHERE action = ?1",
+                params![action],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Admin audit: {e}"))?;
+        Ok(at
+            .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+            .map(|t| t.with_timezone(&Utc)))
+    }
+
+    // --- AI Analysis ---
+
+    /// Get articles that need AI analysis (not yet analyzed)
+    pub fn get_articles_for_analysis(&self, limit: i64) -> Result<Vec<Article>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, category, title, url, description, image_url, source,
+                        published_at, fetched_at, group_id, group_count, word_count, reading_minutes
+                 FROM articles
+                 WHERE analyzed_at IS NULL
+                   AND description IS NOT NULL
+                   AND length(description) > 10
+                 ORDER BY published_at DESC
+                 LIMIT ?1",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let articles = stmt
+            .query_map(params![limit], row_to_article)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(articles)
     }
 
     /// Update article with AI analysis results
@@ -1521,11 +4538,407 @@ impl Db {
 
         Ok((total, analyzed))
     }
+
+    /// Clear `analyzed_at` of the analyzed articles matching `filter`, so the analyzer
+    /// picks them up again on its next cycle. Returns how many were reset.
+    pub fn reset_analysis_for_articles(&self, filter: &ReanalyzeFilter) -> Result<usize, String> {
+        let mut conditions = vec!["analyzed_at IS NOT NULL".to_string()];
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        match (&filter.article_ids, &filter.before_analyzed_at) {
+            (None, None) => return Err("Reset analysis: no article_ids or before_analyzed_at".into()),
+            (Some(ids), _) if ids.is_empty() => return Ok(0),
+            _ => {}
+        }
+        if let Some(ids) = &filter.article_ids {
+            let placeholders = vec!["?"; ids.len()].join(", ");
+            conditions.push(format!("id IN ({placeholders})"));
+            values.extend(ids.iter().map(|id| id.clone().into()));
+        }
+        if let Some(before) = &filter.before_analyzed_at {
+            conditions.push("analyzed_at < ?".into());
+            values.push(before.to_rfc3339().into());
+        }
+        if let Some(category) = &filter.category {
+            conditions.push("category = ?".into());
+            values.push(category.clone().into());
+        }
+        let limit = filter.limit.unwrap_or(-1);
+        values.push(limit.into());
+        let sql = format!(
+            "UPDATE articles SET analyzed_at = NULL
+             WHERE id IN (SELECT id FROM articles WHERE {} ORDER BY published_at DESC LIMIT ?)",
+            conditions.join(" AND ")
+        );
+
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(&sql, rusqlite::params_from_iter(values))
+            .map_err(|e| format!("Reset analysis: {e}"))
+    }
+
+    /// Keywords from AI analysis; None until the analyzer has processed the article.
+    pub fn get_ai_keywords(&self, article_id: &str) -> Result<Option<Vec<String>>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT ai_keywords FROM articles WHERE id = ?1",
+                params![article_id],
+                |row| row.get(0),
+            )
+            .ok()
+            .flatten();
+        Ok(json
+            .and_then(|j| serde_json::from_str::<Vec<String>>(&j).ok())
+            .filter(|k| !k.is_empty()))
+    }
+
+    /// The analyzer's summary; None until the article has been analyzed.
+    pub fn get_ai_summary(&self, article_id: &str) -> Result<Option<String>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let summary: Option<String> = conn
+            .query_row(
+                "SELECT ai_summary FROM articles WHERE id = ?1",
+                params![article_id],
+                |row| row.get(0),
+            )
+            .ok()
+            .flatten();
+        Ok(summary.filter(|s| !s.trim().is_empty()))
+    }
+
+    /// The analyzer's one-word sentiment; None until the article has been analyzed.
+    pub fn get_ai_sentiment(&self, article_id: &str) -> Result<Option<String>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let sentiment: Option<String> = conn
+            .query_row(
+                "SELECT ai_sentiment FROM articles WHERE id = ?1",
+                params![article_id],
+                |row| row.get(0),
+            )
+            .ok()
+            .flatten();
+        Ok(sentiment.filter(|s| !s.is_empty()))
+    }
+
+    /// Title + description of the newest articles, as a corpus for TF-IDF.
+    pub fn recent_article_texts(&self, limit: i64) -> Result<Vec<String>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT title || ' ' || COALESCE(description, '') FROM articles
+                 ORDER BY published_at DESC LIMIT ?1",
+            )
+            .map_err(|e| e.to_string())?;
+        let texts = stmt
+            .query_map(params![limit], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(texts)
+    }
+
+    /// Up to `limit` other articles from `source` whose title shares one of the top 3
+    /// TF-IDF keywords of the reference article's title, newest first, together with
+    /// those keywords. Empty when the reference is missing or its title has no terms.
+    pub fn find_source_topic_articles(
+        &self,
+        source: &str,
+        reference_article_id: &str,
+        limit: i64,
+    ) -> Result<(Vec<Article>, Vec<String>), String> {
+        let Some(reference) = self.get_article_by_id(reference_article_id)? else {
+            return Ok((Vec::new(), Vec::new()));
+        };
+        let corpus = self.recent_article_texts(KEYWORDS_CORPUS_SIZE)?;
+        let tfidf = news_core::keywords::TfIdf::new(corpus.iter().map(String::as_str));
+        let keywords: Vec<String> = tfidf
+            .top_terms(&reference.title, 3)
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect();
+        if keywords.is_empty() {
+            return Ok((Vec::new(), keywords));
+        }
+
+        let likes = (0..keywords.len())
+            .map(|i| format!(r"title LIKE ?{} ESCAPE '\'", i + 4))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let sql = format!(
+            "SELECT id, category, title, url, description, image_url, source,
+                    published_at, fetched_at, group_id, group_count, word_count, reading_minutes
+             FROM articles
+             WHERE source = ?1 AND id != ?2 AND dead_link != 1 AND ({likes})
+             ORDER BY published_at DESC
+             LIMIT ?3"
+        );
+        let mut values: Vec<rusqlite::types::Value> = vec![
+            source.to_string().into(),
+            reference_article_id.to_string().into(),
+            limit.into(),
+        ];
+        values.extend(keywords.iter().map(|k| like_pattern(k).into()));
+
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let articles = stmt
+            .query_map(rusqlite::params_from_iter(values), row_to_article)
+            .map_err(|e| format!("Source topic articles: {e}"))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok((articles, keywords))
+    }
+
+    /// Store a translation of an article's title and description, replacing any
+    /// earlier one into the same language.
+    pub fn store_translation(
+        &self,
+        article_id: &str,
+        lang: &str,
+        title: &str,
+        description: Option<&str>,
+        source_lang: Option<&str>,
+        model: Option<&str>,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO translations (article_id, lang, title, description, source_lang, created_at, model)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![article_id, lang, title, description, source_lang, Utc::now().to_rfc3339(), model],
+        )
+        .map_err(|e| format!("Store translation: {e}"))?;
+        Ok(())
+    }
+
+    /// Attach the translated body to a stored translation.
+    pub fn set_translation_content(&self, article_id: &str, lang: &str, content: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE translations SET content = ?3 WHERE article_id = ?1 AND lang = ?2",
+            params![article_id, lang, content],
+        )
+        .map_err(|e| format!("Store translation content: {e}"))?;
+        Ok(())
+    }
+
+    /// The stored translation of one article into `lang`.
+    pub fn get_translation(&self, article_id: &str, lang: &str) -> Result<Option<StoredTranslation>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let result = conn.query_row(
+            "SELECT lang, title, description, source_lang, created_at, model, content FROM translations
+             WHERE article_id = ?1 AND lang = ?2",
+            params![article_id, lang],
+            row_to_translation,
+        );
+        match result {
+            Ok(t) => Ok(Some(t)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Get translation: {e}")),
+        }
+    }
+
+    /// Every stored translation of an article, by language code.
+    pub fn list_translations(&self, article_id: &str) -> Result<Vec<StoredTranslation>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT lang, title, description, source_lang, created_at, model, content FROM translations
+                 WHERE article_id = ?1 ORDER BY lang",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![article_id], row_to_translation)
+            .map_err(|e| format!("List translations: {e}"))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Stored `(title, description)` translations into `lang`, by article id.
+    pub fn get_translations(
+        &self,
+        article_ids: &[&str],
+        lang: &str,
+    ) -> Result<HashMap<String, (String, Option<String>)>, String> {
+        if article_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let placeholders = vec!["?"; article_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT article_id, title, description FROM translations
+             WHERE lang = ? AND article_id IN ({placeholders})"
+        );
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let values = std::iter::once(lang).chain(article_ids.iter().copied());
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Newest articles from auto_translate feeds (matched by source name) that have no
+    /// translation into `lang` yet.
+    pub fn articles_needing_translation(&self, lang: &str, limit: i64) -> Result<Vec<Article>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT a.id, a.category, a.title, a.url, a.description, a.image_url, a.source,
+                        a.published_at, a.fetched_at, a.group_id, a.group_count, a.word_count, a.reading_minutes
+                 FROM articles a
+                 WHERE a.source IN (SELECT source FROM feeds WHERE auto_translate = 1 AND enabled = 1)
+                   AND NOT EXISTS (SELECT 1 FROM translations t WHERE t.article_id = a.id AND t.lang = ?1)
+                 ORDER BY a.published_at DESC
+                 LIMIT ?2",
+            )
+            .map_err(|e| e.to_string())?;
+        let articles = stmt
+            .query_map(params![lang, limit], row_to_article)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(articles)
+    }
+
+    /// One page of the bulk article export, oldest first. `after` is the
+    /// (published_at, id) of the last row of the previous page; the lock is only held
+    /// for the page.
+    pub fn export_articles_page(
+        &self,
+        after: Option<&(String, String)>,
+        since: Option<&str>,
+        category: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<serde_json::Value>, String> {
+        let (after_pub, after_id) = match after {
+            Some((p, id)) => (Some(p.as_str()), id.as_str()),
+            None => (None, ""),
+        };
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT a.id, a.category, a.title, a.url, a.description, a.image_url, a.source,
+                        a.published_at, a.fetched_at, a.canonical_url, a.group_id, a.group_count,
+                        a.view_count, a.click_count, a.popularity_score, a.enrichment_status, a.enriched_at,
+                        a.ai_summary, a.ai_keywords, a.ai_sentiment, a.ai_importance, a.ai_category, a.analyzed_at,
+                        (SELECT COUNT(*) FROM enrichments e WHERE e.article_id = a.id),
+                        (SELECT COUNT(*) FROM enrichments e WHERE e.article_id = a.id AND e.status = 'completed'),
+                        a.word_count, a.reading_minutes
+                 FROM articles a
+                 WHERE (?1 IS NULL OR a.published_at > ?1 OR (a.published_at = ?1 AND a.id > ?2))
+                   AND (?3 IS NULL OR a.published_at >= ?3)
+                   AND (?4 IS NULL OR a.category = ?4)
+                 ORDER BY a.published_at, a.id
+                 LIMIT ?5",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![after_pub, after_id, since, category, limit], |row| {
+                let keywords: Option<String> = row.get(18)?;
+                Ok(serde_json::json!({
+                    "id": row.get::<_, String>(0)?,
+                    "category": row.get::<_, String>(1)?,
+                    "title": row.get::<_, String>(2)?,
+                    "url": row.get::<_, String>(3)?,
+                    "description": row.get::<_, Option<String>>(4)?,
+                    "image_url": row.get::<_, Option<String>>(5)?,
+                    "source": row.get::<_, String>(6)?,
+                    "published_at": row.get::<_, String>(7)?,
+                    "fetched_at": row.get::<_, String>(8)?,
+                    "canonical_url": row.get::<_, Option<String>>(9)?,
+                    "group_id": row.get::<_, Option<String>>(10)?,
+                    "group_count": row.get::<_, Option<i64>>(11)?,
+                    "view_count": row.get::<_, i64>(12)?,
+                    "click_count": row.get::<_, i64>(13)?,
+                    "popularity_score": row.get::<_, f64>(14)?,
+                    "enrichment_status": row.get::<_, Option<String>>(15)?,
+                    "enriched_at": row.get::<_, Option<String>>(16)?,
+                    "ai_summary": row.get::<_, Option<String>>(17)?,
+                    "ai_keywords": keywords.and_then(|k| serde_json::from_str::<Vec<String>>(&k).ok()),
+                    "ai_sentiment": row.get::<_, Option<String>>(19)?,
+                    "ai_importance": row.get::<_, Option<f64>>(20)?,
+                    "ai_category": row.get::<_, Option<String>>(21)?,
+                    "analyzed_at": row.get::<_, Option<String>>(22)?,
+                    "enrichment_count": row.get::<_, i64>(23)?,
+                    "enrichment_completed_count": row.get::<_, i64>(24)?,
+                    "word_count": row.get::<_, Option<i64>>(25)?,
+                    "reading_minutes": row.get::<_, Option<i64>>(26)?,
+                }))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// usage_limits totals per day and feature, oldest first.
+    pub fn usage_by_day(&self) -> Result<Vec<serde_json::Value>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT used_date, feature, COUNT(DISTINCT device_id), SUM(count)
+                 FROM usage_limits
+                 GROUP BY used_date, feature
+                 ORDER BY used_date, feature",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(serde_json::json!({
+                    "date": row.get::<_, String>(0)?,
+                    "feature": row.get::<_, String>(1)?,
+                    "devices": row.get::<_, i64>(2)?,
+                    "count": row.get::<_, i64>(3)?,
+                }))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+}
+
+/// Point articles, feeds and aliases at category `to` instead of `from`, and make
+/// `from` an alias of `to`. Returns the articles and feeds moved.
+fn move_category(conn: &Connection, from: &str, to: &str) -> rusqlite::Result<(usize, usize)> {
+    let articles = conn.execute("UPDATE articles SET category = ?2 WHERE category = ?1", params![from, to])?;
+    let feeds = conn.execute("UPDATE feeds SET category = ?2 WHERE category = ?1", params![from, to])?;
+    conn.execute("UPDATE category_aliases SET category_id = ?2 WHERE category_id = ?1", params![from, to])?;
+    // A live category id is never an alias
+    conn.execute("DELETE FROM category_aliases WHERE alias = ?1", params![to])?;
+    conn.execute(
+        "INSERT OR REPLACE INTO category_aliases (alias, category_id) VALUES (?1, ?2)",
+        params![from, to],
+    )?;
+    Ok((articles, feeds))
+}
+
+fn row_to_change(row: &rusqlite::Row) -> rusqlite::Result<ChangeRequest> {
+    let status_str: String = row.get(1)?;
+    let actions_json: String = row.get(4)?;
+    let diff_json: Option<String> = row.get(6)?;
+    let validation_json: Option<String> = row.get(7)?;
+    Ok(ChangeRequest {
+        change_id: row.get(0)?,
+        status: ChangeStatus::from_str(&status_str).unwrap_or(ChangeStatus::Pending),
+        command_text: row.get(2)?,
+        interpretation: row.get(3)?,
+        actions: serde_json::from_str(&actions_json).unwrap_or_default(),
+        preview_config: None,
+        preview_diff: diff_json.and_then(|j| serde_json::from_str(&j).ok()),
+        validation: validation_json.and_then(|j| serde_json::from_str(&j).ok()),
+        parent_change_id: row.get(8)?,
+        version: row.get(9)?,
+        created_at: row.get(5)?,
+    })
 }
 
 fn row_to_article(row: &rusqlite::Row) -> rusqlite::Result<Article> {
     let cat_str: String = row.get(1)?;
-    let category = Category::from_str(&cat_str).unwrap_or(Category::General);
+    let category = Category::from_id(&cat_str);
     let pub_str: String = row.get(7)?;
     let fetch_str: String = row.get(8)?;
     let published_at: DateTime<Utc> = pub_str.parse().unwrap_or_default();
@@ -1543,25 +4956,1117 @@ fn row_to_article(row: &rusqlite::Row) -> rusqlite::Result<Article> {
         fetched_at,
         group_id: row.get(9)?,
         group_count: row.get(10)?,
+        canonical_url: None,
+        tags: Vec::new(),
+        word_count: row.get(11)?,
+        reading_minutes: row.get(12)?,
+        original_url: None,
+    })
+}
+
+/// Reading time of an article from its feed description, if it has one.
+fn description_reading_time(description: Option<&str>) -> Option<ReadingTime> {
+    description.filter(|d| !d.trim().is_empty()).map(reading_time::estimate)
+}
+
+fn row_to_translation(row: &rusqlite::Row) -> rusqlite::Result<StoredTranslation> {
+    Ok(StoredTranslation {
+        language: row.get(0)?,
+        title: row.get(1)?,
+        description: row.get(2)?,
+        source_lang: row.get(3)?,
+        translated_at: row.get(4)?,
+        model: row.get(5)?,
+        content: row.get(6)?,
+    })
+}
+
+/// Lifetime of a Google-auth session token.
+const AUTH_TOKEN_TTL_DAYS: i64 = 30;
+
+fn new_auth_token() -> String {
+    format!("ga_{}", uuid::Uuid::new_v4().to_string().replace('-', ""))
+}
+
+fn auth_token_expiry() -> String {
+    (chrono::Utc::now() + chrono::Duration::days(AUTH_TOKEN_TTL_DAYS)).to_rfc3339()
+}
+
+fn row_to_source_meta(row: &rusqlite::Row) -> rusqlite::Result<SourceMeta> {
+    Ok(SourceMeta {
+        source: row.get(0)?,
+        source_type: row.get(1)?,
+        credibility_tier: row.get(2)?,
+        homepage_url: row.get(3)?,
+        logo_url: row.get(4)?,
     })
 }
 
-fn encode_cursor(article: &Article) -> String {
-    use base64::Engine;
-    let json = serde_json::json!({
-        "p": article.published_at.to_rfc3339(),
-        "i": article.id,
-    });
-    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json.to_string().as_bytes())
+fn row_to_site(row: &rusqlite::Row) -> rusqlite::Result<SiteMeta> {
+    Ok(SiteMeta {
+        host: row.get(0)?,
+        site_id: row.get(1)?,
+        name: row.get(2)?,
+        title: row.get(3)?,
+        description: row.get(4)?,
+        description_long: row.get(5)?,
+        url: row.get(6)?,
+        image: row.get(7)?,
+        theme_color: row.get(8)?,
+        lang: row.get(9)?,
+        keywords: row.get(10)?,
+    })
 }
 
-fn decode_cursor(cursor: &str) -> Option<(String, String)> {
-    use base64::Engine;
-    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
-        .decode(cursor)
-        .ok()?;
-    let v: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
-    let p = v.get("p")?.as_str()?.to_string();
-    let i = v.get("i")?.as_str()?.to_string();
-    Some((p, i))
+fn row_to_feed(row: &rusqlite::Row) -> rusqlite::Result<DynamicFeed> {
+    Ok(DynamicFeed {
+        feed_id: row.get(0)?,
+        url: row.get(1)?,
+        source: row.get(2)?,
+        category: row.get(3)?,
+        enabled: row.get::<_, i32>(4)? != 0,
+        added_by: row.get(5)?,
+        max_articles_per_fetch: row.get(6)?,
+        auto_translate: row.get::<_, i32>(7)? != 0,
+        category_overrides: row
+            .get::<_, Option<String>>(8)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+    })
+}
+
+/// Put back a degraded image once the article's popularity reaches `min_score`.
+/// Articles that never had an image carry no `image_degraded_at` and are skipped.
+fn restore_degraded_image(conn: &Connection, article_id: &str, min_score: f64) -> rusqlite::Result<bool> {
+    let restored = conn.execute(
+        "UPDATE articles
+         SET image_url = original_image_url, original_image_url = NULL,
+             image_degraded_at = NULL, image_restored_at = ?2
+         WHERE id = ?1 AND image_degraded_at IS NOT NULL AND popularity_score >= ?3",
+        params![article_id, Utc::now().to_rfc3339(), min_score],
+    )?;
+    Ok(restored > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn articles(n: usize, prefix: &str) -> Vec<Article> {
+        let now = Utc::now();
+        (0..n)
+            .map(|i| {
+                let url = format!("https://example.com/{prefix}/{i}");
+                Article {
+                    id: news_core::dedup::article_id_from_url(&url),
+                    category: Category::Tech,
+                    title: format!("Article {i}"),
+                    url,
+                    description: Some("description".into()),
+                    image_url: None,
+                    source: "Example".into(),
+                    published_at: now,
+                    fetched_at: now,
+                    group_id: None,
+                    group_count: None,
+                    canonical_url: None,
+                    tags: Vec::new(),
+                    word_count: None,
+                    reading_minutes: None,
+                    original_url: None,
+                }
+            })
+            .collect()
+    }
+
+    fn temp_db(name: &str) -> (Db, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("news-db-{}-{}.db", name, std::process::id()));
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+        (Db::open(path.to_str().unwrap()).unwrap(), path)
+    }
+
+    #[test]
+    fn in_memory_database_builds_the_full_schema() {
+        let db = Db::open(":memory:").unwrap();
+        let batch = articles(3, "m");
+        assert_eq!(db.batch_insert_articles(&batch).unwrap(), 3);
+        assert!(db.get_article_by_id(&batch[0].id).unwrap().is_some());
+        assert!(db.category_count().unwrap() == 0 && db.seed_default_categories().is_ok());
+        assert!(db.get_feature_flags().is_ok());
+    }
+
+    #[test]
+    fn article_tags_replace_list_and_page() {
+        let db = Db::open(":memory:").unwrap();
+        let batch = articles(3, "tags");
+        db.batch_insert_articles(&batch).unwrap();
+        let tags = |t: &[&str]| t.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        db.set_article_tags(&batch[0].id, &tags(&[" rust ", "ai", "", "ai"])).unwrap();
+        assert_eq!(db.get_article_tags(&batch[0].id).unwrap(), ["ai", "rust"]);
+        // Setting again replaces rather than appends
+        db.set_article_tags(&batch[0].id, &tags(&["ai"])).unwrap();
+        assert_eq!(db.get_article_tags(&batch[0].id).unwrap(), ["ai"]);
+        db.set_article_tags(&batch[1].id, &tags(&["ai", "chips"])).unwrap();
+        db.set_article_tags(&batch[2].id, &tags(&["ai"])).unwrap();
+
+        let popular = db.list_popular_tags(10).unwrap();
+        assert_eq!(popular, [("ai".to_string(), 3), ("chips".to_string(), 1)]);
+        assert_eq!(db.list_popular_tags(1).unwrap().len(), 1);
+
+        let Page { items: first, next_cursor, .. } = db.get_articles_by_tag("ai", &Paginator::first(2)).unwrap();
+        assert_eq!(first.len(), 2);
+        let rest = db.get_articles_by_tag("ai", &Paginator::new(2, next_cursor)).unwrap().items;
+        assert_eq!(rest.len(), 1);
+        assert!(rest.iter().all(|a| !first.iter().any(|f| f.id == a.id)));
+        assert!(db.get_articles_by_tag("none", &Paginator::first(10)).unwrap().items.is_empty());
+    }
+
+    #[test]
+    fn batch_insert_counts_new_rows_and_ignores_duplicates() {
+        let (db, _) = temp_db("batch");
+        let batch = articles(250, "a");
+        assert_eq!(db.batch_insert_articles(&batch).unwrap(), 250);
+        // Same ids, plus a tracking-param variant of an existing URL (same canonical_url)
+        let mut again = articles(10, "a");
+        let mut variant = articles(1, "a").remove(0);
+        variant.id = "other-id".into();
+        variant.url.push_str("?utm_source=rss");
+        again.push(variant);
+        again.extend(articles(5, "b"));
+        assert_eq!(db.batch_insert_articles(&again).unwrap(), 5);
+        assert_eq!(db.batch_insert_articles(&[]).unwrap(), 0);
+
+        let stored = db.get_article_by_id(&batch[123].id).unwrap().unwrap();
+        assert_eq!(stored.title, "Article 123");
+    }
+
+    #[test]
+    fn reading_time_is_set_at_insert_raised_by_extraction_and_backfilled() {
+        let (db, _) = temp_db("reading-time");
+        let mut batch = articles(3, "r");
+        batch[0].description = Some("あ".repeat(1200));
+        batch[2].description = None;
+        db.batch_insert_articles(&batch).unwrap();
+
+        let first = db.get_article_by_id(&batch[0].id).unwrap().unwrap();
+        assert_eq!((first.word_count, first.reading_minutes), (Some(1200), Some(2)));
+        assert_eq!(db.get_article_by_id(&batch[2].id).unwrap().unwrap().word_count, None);
+
+        // Extracted body text only ever raises the estimate
+        db.update_reading_time(&batch[0].id, "短い").unwrap();
+        assert_eq!(db.get_article_by_id(&batch[0].id).unwrap().unwrap().word_count, Some(1200));
+        db.update_reading_time(&batch[2].id, &"word ".repeat(450)).unwrap();
+        let third = db.get_article_by_id(&batch[2].id).unwrap().unwrap();
+        assert_eq!((third.word_count, third.reading_minutes), (Some(450), Some(3)));
+
+        // Rows stored before the columns existed
+        db.conn.lock().unwrap().execute_batch("UPDATE articles SET word_count = NULL, reading_minutes = NULL").unwrap();
+        assert_eq!(db.backfill_reading_time(1).unwrap(), 1);
+        // Only the two with a description are picked up
+        assert_eq!(db.backfill_reading_time(500).unwrap(), 1);
+        assert_eq!(db.backfill_reading_time(500).unwrap(), 0);
+        assert_eq!(db.get_article_by_id(&batch[1].id).unwrap().unwrap().reading_minutes, Some(1));
+    }
+
+    #[test]
+    fn regroup_collapses_to_one_representative_per_group() {
+        let (db, _) = temp_db("regroup");
+        let now = Utc::now();
+        let titles = [
+            "東京都で新型コロナウイルスの感染者が100人確認",
+            "日銀が金融政策決定会合で利上げを決定",
+            "東京都で新型コロナウイルスの感染者が150人確認",
+            "東京都で新型コロナウイルスの感染者が200人確認",
+        ];
+        let mut batch = articles(titles.len(), "g");
+        for (i, (a, title)) in batch.iter_mut().zip(titles).enumerate() {
+            a.title = title.to_string();
+            a.published_at = now - chrono::Duration::minutes(i as i64);
+        }
+        db.batch_insert_articles(&batch).unwrap();
+
+        let since = now - chrono::Duration::hours(48);
+        assert_eq!(db.regroup_articles(&since, 0.5).unwrap(), 3);
+        let group_id = db.get_article_by_id(&batch[0].id).unwrap().unwrap().group_id;
+        assert_eq!(group_id, Some(format!("grp-{}", batch[3].id)));
+        // Regrouping the same set keeps the ids
+        db.regroup_articles(&since, 0.5).unwrap();
+        assert_eq!(db.get_article_by_id(&batch[2].id).unwrap().unwrap().group_id, group_id);
+
+        let collapsed = db.query_filtered_articles(None, &ArticleFilter::default(), &Paginator::first(10), true, false).unwrap().items;
+        let ids: Vec<&str> = collapsed.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec![batch[0].id.as_str(), batch[1].id.as_str()]);
+        assert_eq!(collapsed[0].group_count, Some(3));
+
+        // Paging never brings back an older member of a collapsed group
+        let Page { items: first, next_cursor: cursor, .. } = db.query_filtered_articles(None, &ArticleFilter::default(), &Paginator::first(1), true, false).unwrap();
+        let second = db.query_filtered_articles(None, &ArticleFilter::default(), &Paginator::new(10, cursor), true, false).unwrap().items;
+        assert_eq!(first.len() + second.len(), 2);
+    }
+
+    #[test]
+    fn collapse_prefers_credible_sources_and_types_can_be_excluded() {
+        let (db, _) = temp_db("source-meta");
+        let now = Utc::now();
+        let titles = [
+            "東京都で新型コロナウイルスの感染者が100人確認",
+            "東京都で新型コロナウイルスの感染者が150人確認",
+            "日銀が金融政策決定会合で利上げを決定",
+        ];
+        let sources = ["PR Wire", "NHK", "PR Wire"];
+        let mut batch = articles(titles.len(), "sm");
+        for (i, a) in batch.iter_mut().enumerate() {
+            a.title = titles[i].to_string();
+            a.source = sources[i].to_string();
+            a.published_at = now - chrono::Duration::minutes(i as i64);
+        }
+        db.batch_insert_articles(&batch).unwrap();
+        db.regroup_articles(&(now - chrono::Duration::hours(48)), 0.5).unwrap();
+
+        let meta = |source: &str, source_type: &str, tier| SourceMeta {
+            credibility_tier: Some(tier),
+            source_type: source_type.into(),
+            ..SourceMeta::unknown(source)
+        };
+        assert_eq!(
+            db.seed_source_meta(&[meta("NHK", "public_broadcaster", 1), meta("PR Wire", "pr", 3)]).unwrap(),
+            2
+        );
+        // Seeding again leaves admin edits alone
+        db.put_source_meta(&meta("PR Wire", "pr", 2)).unwrap();
+        assert_eq!(db.seed_source_meta(&[meta("PR Wire", "pr", 3)]).unwrap(), 0);
+        assert_eq!(db.source_meta_map().unwrap()["PR Wire"].credibility_tier, Some(2));
+
+        // The older NHK article represents the group over the newer press release
+        let collapsed = db.query_filtered_articles(None, &ArticleFilter::default(), &Paginator::first(10), true, false).unwrap().items;
+        let ids: Vec<&str> = collapsed.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec![batch[1].id.as_str(), batch[2].id.as_str()]);
+
+        let filter = ArticleFilter {
+            exclude_types: vec!["pr".into()],
+            ..Default::default()
+        };
+        let listed = db.query_filtered_articles(None, &filter, &Paginator::first(10), false, false).unwrap().items;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].source, "NHK");
+
+        // Sources without metadata count as "unknown"
+        assert!(db.delete_source_meta("NHK").unwrap());
+        let filter = ArticleFilter {
+            exclude_types: vec!["unknown".into()],
+            ..Default::default()
+        };
+        let listed = db.query_filtered_articles(None, &filter, &Paginator::first(10), false, false).unwrap().items;
+        assert!(listed.iter().all(|a| a.source == "PR Wire"));
+        assert_eq!(listed.len(), 2);
+    }
+
+    #[test]
+    fn feeds_round_trip_through_put_feed() {
+        let db = Db::open(":memory:").unwrap();
+        let feed = DynamicFeed {
+            feed_id: "f1".into(),
+            url: "https://example.com/rss".into(),
+            source: "Example".into(),
+            category: "tech".into(),
+            enabled: true,
+            added_by: Some("admin".into()),
+            max_articles_per_fetch: Some(20),
+            auto_translate: true,
+            category_overrides: None,
+        };
+        db.put_feed(&feed).unwrap();
+        db.put_feed(&DynamicFeed { feed_id: "f2".into(), url: "https://example.com/off".into(), enabled: false, ..feed.clone() })
+            .unwrap();
+
+        let mut all = db.get_all_feeds().unwrap();
+        all.sort_by(|a, b| a.feed_id.cmp(&b.feed_id));
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].feed_id, "f1");
+        assert_eq!(all[0].url, feed.url);
+        assert_eq!(all[0].added_by.as_deref(), Some("admin"));
+        assert_eq!(all[0].max_articles_per_fetch, Some(20));
+        assert!(all[0].auto_translate);
+        assert!(!all[1].enabled);
+
+        // The fetcher only picks up the enabled one
+        let base = news_core::feeds::FeedsConfig { feeds: Vec::new(), warnings: Vec::new() };
+        let merged = news_core::feeds::FeedsConfig::merge(&base, &all);
+        assert_eq!(merged.feeds.len(), 1);
+        assert_eq!(merged.feeds[0].url, feed.url);
+        assert_eq!(merged.feeds[0].max_articles_per_fetch, Some(20));
+    }
+
+    #[test]
+    fn auto_translate_feeds_queue_untranslated_articles() {
+        let (db, _) = temp_db("translate");
+        let batch = articles(2, "t");
+        db.batch_insert_articles(&batch).unwrap();
+        assert!(db.articles_needing_translation("ja", 10).unwrap().is_empty());
+
+        db.put_feed(&DynamicFeed {
+            feed_id: "f1".into(),
+            url: "https://example.com/rss".into(),
+            source: "Example".into(),
+            category: "tech".into(),
+            enabled: true,
+            added_by: None,
+            max_articles_per_fetch: None,
+            auto_translate: true,
+            category_overrides: None,
+        })
+        .unwrap();
+        assert!(db.get_all_feeds().unwrap()[0].auto_translate);
+        assert_eq!(db.articles_needing_translation("ja", 10).unwrap().len(), 2);
+
+        db.store_translation(&batch[0].id, "ja", "記事", Some("説明"), Some("en"), None).unwrap();
+        let pending = db.articles_needing_translation("ja", 10).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, batch[1].id);
+
+        let ids: Vec<&str> = batch.iter().map(|a| a.id.as_str()).collect();
+        let translations = db.get_translations(&ids, "ja").unwrap();
+        assert_eq!(translations.len(), 1);
+        assert_eq!(translations[&batch[0].id].0, "記事");
+        // The article itself keeps its original title
+        assert_eq!(db.get_article_by_id(&batch[0].id).unwrap().unwrap().title, batch[0].title);
+
+        // Further languages sit alongside; storing a language again replaces it
+        db.store_translation(&batch[0].id, "en", "Article", None, Some("ja"), Some("haiku")).unwrap();
+        db.store_translation(&batch[0].id, "ko", "기사", None, Some("ja"), Some("haiku")).unwrap();
+        db.store_translation(&batch[0].id, "en", "The article", None, Some("ja"), Some("sonnet")).unwrap();
+        let all = db.list_translations(&batch[0].id).unwrap();
+        let langs: Vec<&str> = all.iter().map(|t| t.language.as_str()).collect();
+        assert_eq!(langs, ["en", "ja", "ko"]);
+        let en = db.get_translation(&batch[0].id, "en").unwrap().unwrap();
+        assert_eq!((en.title.as_str(), en.model.as_deref()), ("The article", Some("sonnet")));
+        assert!(db.get_translation(&batch[0].id, "zh").unwrap().is_none());
+        assert!(db.list_translations(&batch[1].id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn mute_rules_are_capped_and_scoped_to_their_owner() {
+        let (db, _) = temp_db("mute-rules");
+        let rule = db.create_mute_rule("device:d1", "巨人", MuteField::Title, 2).unwrap().unwrap();
+        db.create_mute_rule("device:d1", "tabloid", MuteField::Source, 2).unwrap().unwrap();
+        assert!(db.create_mute_rule("device:d1", "third", MuteField::Any, 2).unwrap().is_none());
+
+        let rules = db.list_mute_rules("device:d1").unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1].field, MuteField::Source);
+        assert!(db.list_mute_rules("device:d2").unwrap().is_empty());
+
+        assert!(!db.delete_mute_rule(&rule.id, "device:d2").unwrap());
+        assert!(db.delete_mute_rule(&rule.id, "device:d1").unwrap());
+        assert_eq!(db.list_mute_rules("device:d1").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn search_wildcards_in_queries_match_literally() {
+        let db = Db::open(":memory:").unwrap();
+        assert!(db.create_saved_search("s1", "device:d1", "100%", None, 5).unwrap());
+        assert!(db.create_saved_search("s2", "device:d1", "a_b", None, 5).unwrap());
+        let mut batch = articles(3, "w");
+        batch[0].title = "Growth hits 100% in Q3".into();
+        batch[1].title = "1000 new users".into();
+        batch[2].title = "axb released".into();
+        db.batch_insert_articles(&batch).unwrap();
+
+        let titles = |q: &str| -> Vec<String> {
+            db.search_articles(q, 10).unwrap().into_iter().map(|a| a.title).collect()
+        };
+        assert_eq!(titles("100%"), ["Growth hits 100% in Q3"]);
+        assert!(titles("a_b").is_empty());
+        assert!(titles("%").iter().all(|t| t.contains('%')));
+
+        let ids: Vec<&str> = batch.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(db.match_saved_searches(&ids).unwrap(), 1, "only s1 matches, and only once");
+    }
+
+    #[test]
+    fn saved_searches_collect_only_new_matching_articles() {
+        let (db, _) = temp_db("saved-search");
+        let mut old = articles(3, "old");
+        for a in &mut old {
+            a.fetched_at = Utc::now() - chrono::Duration::hours(1);
+        }
+        db.batch_insert_articles(&old).unwrap();
+
+        assert!(db.create_saved_search("s1", "device:d1", "Article 1", None, 2).unwrap());
+        assert!(db.create_saved_search("s2", "device:d1", "Article", Some("business"), 2).unwrap());
+        assert!(!db.create_saved_search("s3", "device:d1", "full", None, 2).unwrap());
+
+        let new = articles(3, "new");
+        db.batch_insert_articles(&new).unwrap();
+        let ids: Vec<&str> = old.iter().chain(&new).map(|a| a.id.as_str()).collect();
+        // Only new/1 matches: old articles predate the search, s2 wants another category
+        assert_eq!(db.match_saved_searches(&ids).unwrap(), 1);
+        assert_eq!(db.match_saved_searches(&ids).unwrap(), 0);
+
+        let updates = db.saved_search_updates("device:d1").unwrap();
+        assert_eq!(updates[0], ("s1".to_string(), "Article 1".to_string(), 1, 1));
+        assert_eq!(db.saved_search_updates("device:d1").unwrap()[0].2, 0);
+
+        assert!(db.take_unseen_matches("s1", "device:other", 10).unwrap().is_none());
+        let matches = db.take_unseen_matches("s1", "device:d1", 10).unwrap().unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].id, new[1].id);
+        assert!(db.take_unseen_matches("s1", "device:d1", 10).unwrap().unwrap().is_empty());
+        assert_eq!(db.list_saved_searches("device:d1").unwrap()[0].unseen, 0);
+
+        assert!(!db.delete_saved_search("s1", "device:other").unwrap());
+        assert!(db.delete_saved_search("s1", "device:d1").unwrap());
+        assert_eq!(db.list_saved_searches("device:d1").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn maintenance_runs_keep_the_latest_outcome_per_step() {
+        let (db, _) = temp_db("maintenance");
+        db.batch_insert_articles(&articles(3, "m")).unwrap();
+        let vacuum = db.vacuum_and_checkpoint();
+        assert!(vacuum.is_ok());
+        db.record_maintenance_run("vacuum", 12, &vacuum).unwrap();
+        db.record_maintenance_run("old_usage", 3, &Ok(5)).unwrap();
+        db.record_maintenance_run("old_usage", 4, &Err("locked".into())).unwrap();
+
+        let runs = db.maintenance_runs().unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0]["step"], "old_usage");
+        assert_eq!(runs[0]["duration_ms"], 4);
+        assert!(runs[0]["rows_affected"].is_null());
+        assert_eq!(runs[0]["error"], "locked");
+        assert_eq!(runs[1]["step"], "vacuum");
+    }
+
+    #[test]
+    fn export_pages_cover_every_article_once() {
+        let (db, _) = temp_db("export");
+        // Same published_at for all rows, so paging has to break ties on id
+        let batch = articles(7, "x");
+        db.batch_insert_articles(&batch).unwrap();
+
+        let mut seen = Vec::new();
+        let mut after: Option<(String, String)> = None;
+        loop {
+            let page = db.export_articles_page(after.as_ref(), None, None, 3).unwrap();
+            let Some(last) = page.last() else { break };
+            after = Some((
+                last["published_at"].as_str().unwrap().to_string(),
+                last["id"].as_str().unwrap().to_string(),
+            ));
+            seen.extend(page.iter().map(|r| r["id"].as_str().unwrap().to_string()));
+        }
+        let mut expected: Vec<String> = batch.iter().map(|a| a.id.clone()).collect();
+        expected.sort();
+        assert_eq!(seen, expected);
+        assert!(db.export_articles_page(None, None, Some("politics"), 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn fetched_since_skips_duplicates_and_older_rows() {
+        let (db, _) = temp_db("fetched-since");
+        let old = articles(3, "old");
+        db.batch_insert_articles(&old).unwrap();
+        let since = Utc::now();
+        // Same ids again with a newer fetched_at: ignored by the insert
+        let mut again = articles(3, "old");
+        again.extend(articles(2, "new"));
+        db.batch_insert_articles(&again).unwrap();
+
+        let ids: Vec<&str> = again.iter().map(|a| a.id.as_str()).collect();
+        let fresh = db.fetched_since(&ids, &since).unwrap();
+        let expected: std::collections::HashSet<String> = again[3..].iter().map(|a| a.id.clone()).collect();
+        assert_eq!(fresh, expected);
+    }
+
+    #[test]
+    fn range_queries_page_within_range_and_count_by_day() {
+        let (db, _) = temp_db("range");
+        let day = |d: u32, h: u32| chrono::NaiveDate::from_ymd_opt(2025, 1, d).unwrap().and_hms_opt(h, 0, 0).unwrap().and_utc();
+        let mut batch = articles(5, "r");
+        let times = [day(1, 9), day(2, 9), day(2, 18), day(3, 9), day(5, 9)];
+        for (a, t) in batch.iter_mut().zip(times) {
+            a.published_at = t;
+        }
+        db.batch_insert_articles(&batch).unwrap();
+
+        let range = (&day(2, 0), &day(3, 23));
+        let Page { items: first, next_cursor: cursor, .. } = db.query_articles_between(None, range, &Paginator::first(2), false, false).unwrap();
+        assert_eq!(first.iter().map(|a| a.published_at).collect::<Vec<_>>(), vec![day(3, 9), day(2, 18)]);
+        let Page { items: second, next_cursor: cursor, .. } = db.query_articles_between(None, range, &Paginator::new(2, cursor), false, false).unwrap();
+        assert_eq!(second.iter().map(|a| a.published_at).collect::<Vec<_>>(), vec![day(2, 9)]);
+        assert!(cursor.is_none());
+
+        let counts = db.count_articles_by_day(&day(1, 0), &day(5, 0), None).unwrap();
+        assert_eq!(
+            counts,
+            vec![("2025-01-01".to_string(), 1), ("2025-01-02".to_string(), 2), ("2025-01-03".to_string(), 1)]
+        );
+        assert!(db.count_articles_by_day(&day(1, 0), &day(5, 0), Some(&Category::Sports)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn dead_links_are_hidden_until_unflagged() {
+        let (db, _) = temp_db("dead-link");
+        let batch = articles(3, "d");
+        db.batch_insert_articles(&batch).unwrap();
+        let dead = &batch[1].id;
+        let hour_ago = Utc::now() - chrono::Duration::hours(1);
+
+        assert_eq!(db.link_check_candidates(&hour_ago, &Utc::now(), 10).unwrap().len(), 3);
+        db.record_link_check(dead, true).unwrap();
+        db.record_link_check(&batch[0].id, false).unwrap();
+        // Checked articles wait for the next day; flagged ones aren't checked again
+        let left = db.link_check_candidates(&hour_ago, &hour_ago, 10).unwrap();
+        assert_eq!(left, vec![(batch[2].id.clone(), batch[2].url.clone())]);
+
+        let listed = db.query_articles(None, &Paginator::first(10), false).unwrap().items;
+        assert!(listed.iter().all(|a| &a.id != dead));
+        assert_eq!(db.query_articles(None, &Paginator::first(10), true).unwrap().items.len(), 3);
+        assert_eq!(db.get_fresh_articles(None, 60, 10, false, &[]).unwrap().len(), 2);
+        assert_eq!(db.count_dead_links().unwrap(), 1);
+
+        assert!(db.unflag_dead_link(dead).unwrap());
+        assert!(!db.unflag_dead_link(dead).unwrap());
+        db.record_link_check(dead, true).unwrap();
+        assert_eq!(db.count_dead_links().unwrap(), 0);
+        assert_eq!(db.query_articles(None, &Paginator::first(10), false).unwrap().items.len(), 3);
+    }
+
+    #[test]
+    fn concurrent_upserts_of_one_google_account_create_one_user() {
+        let (first, path) = temp_db("upsert-race");
+        // Separate connections, so the in-process Mutex doesn't serialize them
+        let dbs: Vec<std::sync::Arc<Db>> = std::iter::once(first)
+            .chain((0..3).map(|_| Db::open(path.to_str().unwrap()).unwrap()))
+            .map(std::sync::Arc::new)
+            .collect();
+
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                let db = std::sync::Arc::clone(&dbs[i % dbs.len()]);
+                std::thread::spawn(move || db.upsert_user("g-race", "race@example.com", "Race", None, None))
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap().unwrap()).collect();
+
+        assert_eq!(results.iter().filter(|r| r.3).count(), 1);
+        assert!(results.iter().all(|r| r.2 == results[0].2));
+        let conn = dbs[0].conn.lock().unwrap();
+        let users: i64 = conn
+            .query_row("SELECT COUNT(*) FROM users WHERE google_id = 'g-race'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(users, 1);
+    }
+
+    #[test]
+    fn concurrent_audit_claims_get_one_slot_per_cooldown() {
+        let (first, path) = temp_db("audit-claim");
+        let dbs: Vec<std::sync::Arc<Db>> = std::iter::once(first)
+            .chain((0..3).map(|_| Db::open(path.to_str().unwrap()).unwrap()))
+            .map(std::sync::Arc::new)
+            .collect();
+        let cooldown = chrono::Duration::minutes(10);
+
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                let db = std::sync::Arc::clone(&dbs[i % dbs.len()]);
+                std::thread::spawn(move || {
+                    db.record_admin_audit_unless_recent("articles.import", &serde_json::json!({}), cooldown)
+                })
+            })
+            .collect();
+        let claims: Vec<_> = handles.into_iter().map(|h| h.join().unwrap().unwrap()).collect();
+        let claimed: Vec<i64> = claims.into_iter().flatten().collect();
+        assert_eq!(claimed.len(), 1);
+
+        let db = &dbs[0];
+        db.update_admin_audit_details(claimed[0], &serde_json::json!({"imported": 3})).unwrap();
+        let details: String = db
+            .conn
+            .lock()
+            .unwrap()
+            .query_row("SELECT details FROM admin_audit_log WHERE log_id = ?1", params![claimed[0]], |row| row.get(0))
+            .unwrap();
+        assert_eq!(details, r#"{"imported":3}"#);
+        // Other actions and an elapsed cooldown aren't blocked
+        assert!(db.record_admin_audit_unless_recent("other", &serde_json::json!({}), cooldown).unwrap().is_some());
+        let none = chrono::Duration::zero();
+        assert!(db.record_admin_audit_unless_recent("articles.import", &serde_json::json!({}), none).unwrap().is_some());
+    }
+
+    #[test]
+    fn engagement_keeps_popularity_in_step_with_counters() {
+        let (db, _) = temp_db("engagement");
+        let batch = articles(1, "e");
+        db.batch_insert_articles(&batch).unwrap();
+        let id = &batch[0].id;
+
+        assert_eq!(db.record_engagement(id, "view", Some("v1"), 30, 10, 10.0).unwrap(), 1);
+        assert_eq!(db.record_engagement(id, "view", Some("v1"), 30, 10, 10.0).unwrap(), 1);
+        assert_eq!(db.record_engagement(id, "click", Some("v2"), 30, 10, 10.0).unwrap(), 1);
+        let conn = db.conn.lock().unwrap();
+        let score: f64 = conn
+            .query_row("SELECT popularity_score FROM articles WHERE id = ?1", params![id], |row| row.get(0))
+            .unwrap();
+        assert!((score - 1.0).abs() < 1e-9, "score {score}");
+    }
+
+    #[test]
+    fn degraded_images_come_back_when_the_article_gets_popular() {
+        let (db, _) = temp_db("degrade");
+        let mut batch = articles(5, "d");
+        for (i, a) in batch.iter_mut().enumerate() {
+            a.published_at = Utc::now() - chrono::Duration::hours(72 + i as i64);
+            if i < 3 {
+                a.image_url = Some(format!("https://img.example.com/{i}.jpg"));
+            }
+        }
+        // Fresh and without an image: ordinary OGP work
+        batch[4].published_at = Utc::now();
+        db.batch_insert_articles(&batch).unwrap();
+        let ids: Vec<&str> = batch.iter().map(|a| a.id.as_str()).collect();
+        {
+            let conn = db.conn.lock().unwrap();
+            for (id, views) in ids.iter().zip([1, 3, 10, 2, 0]) {
+                conn.execute("UPDATE articles SET view_count = ?2 WHERE id = ?1", params![id, views]).unwrap();
+            }
+        }
+        db.recompute_popularity().unwrap();
+        let image = |id: &str| db.get_article_by_id(id).unwrap().unwrap().image_url;
+        let ogp_queue = || db.articles_without_image(10).unwrap().into_iter().map(|a| a.id).collect::<Vec<_>>();
+
+        // Scores 0.7, 1.4, 2.1, 7.0: the median is 2.1, and d3 has no image to lose
+        let policy = ImageDegradationPolicy::default();
+        assert_eq!(db.degrade_old_unpopular_images(&policy).unwrap(), 1);
+        assert_eq!(image(ids[0]), None);
+        assert_eq!(db.image_degradation_counts().unwrap(), (1, 0));
+        assert_eq!(ogp_queue(), [ids[4], ids[3]], "degraded images aren't re-fetched");
+
+        db.record_engagement(ids[0], "view", Some("v1"), 30, 10, 1.0).unwrap();
+        assert_eq!(image(ids[0]).as_deref(), Some("https://img.example.com/0.jpg"));
+        assert_eq!(db.image_degradation_counts().unwrap(), (0, 1));
+
+        // Never had an image: nothing to restore, and not counted as restored
+        db.record_engagement(ids[3], "view", Some("v1"), 30, 10, 1.0).unwrap();
+        assert_eq!(db.image_degradation_counts().unwrap(), (0, 1));
+        assert_eq!(ogp_queue(), [ids[4], ids[3]]);
+
+        // Restored articles stay restored
+        assert_eq!(db.degrade_old_unpopular_images(&ImageDegradationPolicy { percentile: 0.9, ..policy }).unwrap(), 1);
+        assert_eq!(image(ids[0]).as_deref(), Some("https://img.example.com/0.jpg"));
+    }
+
+    #[test]
+    fn hourly_view_counts_group_counted_views_by_weekday_and_hour() {
+        let (db, _) = temp_db("heatmap");
+        let batch = articles(2, "h");
+        db.batch_insert_articles(&batch).unwrap();
+
+        db.record_engagement(&batch[0].id, "view", Some("v1"), 30, 10, 10.0).unwrap();
+        db.record_engagement(&batch[0].id, "view", Some("v1"), 30, 10, 10.0).unwrap(); // deduped
+        db.record_engagement(&batch[1].id, "view", Some("v1"), 30, 10, 10.0).unwrap();
+        db.record_engagement(&batch[1].id, "click", Some("v2"), 30, 10, 10.0).unwrap();
+        {
+            let conn = db.conn.lock().unwrap();
+            // Friday 2024-01-05 08:15 UTC, and one too old for a 30-day window
+            conn.execute(
+                "INSERT INTO view_events (article_id, viewed_at) VALUES ('h0', '2024-01-05T08:15:00+00:00')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let now = chrono::Utc::now().format("%w %H").to_string();
+        assert_eq!(db.get_hourly_view_counts(30).unwrap(), vec![(now, 2)]);
+        assert_eq!(db.cleanup_view_events(90).unwrap(), 1);
+    }
+
+    #[test]
+    fn shortlink_is_stable_per_article_and_counts_clicks() {
+        let (db, _) = temp_db("shortlinks");
+        let code = db.get_or_create_shortlink("a1").unwrap();
+        assert_eq!(code.len(), 6);
+        assert!(code.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_eq!(db.get_or_create_shortlink("a1").unwrap(), code);
+        assert_ne!(db.get_or_create_shortlink("a2").unwrap(), code);
+
+        assert_eq!(db.get_shortlink(&code).unwrap().as_deref(), Some("a1"));
+        assert_eq!(db.get_shortlink("zzzzzz").unwrap(), None);
+        db.increment_shortlink_clicks(&code).unwrap();
+        db.increment_shortlink_clicks(&code).unwrap();
+        let clicks: i64 = db
+            .conn
+            .lock()
+            .unwrap()
+            .query_row("SELECT clicks FROM shortlinks WHERE short_code = ?1", params![code], |row| row.get(0))
+            .unwrap();
+        assert_eq!(clicks, 2);
+
+        let codes = db.shortlinks_for_articles(&["a1", "a3"]).unwrap();
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes["a1"], code);
+    }
+
+    fn subscription_rows(db: &Db) -> Vec<(String, String, String)> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT api_token, stripe_subscription_id, status FROM subscriptions ORDER BY created_at")
+            .unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn replayed_checkout_webhook_keeps_one_subscription_and_its_token() {
+        let (db, _) = temp_db("webhook_replay");
+        let event = serde_json::json!({
+            "id": "evt_checkout_1",
+            "type": "checkout.session.completed",
+            "data": {"object": {"customer": "cus_1", "subscription": "sub_1"}},
+        });
+
+        assert!(crate::stripe::apply_webhook_event(&db, &event, "2030-01-01T00:00:00+00:00").unwrap());
+        let first = subscription_rows(&db);
+        assert!(!crate::stripe::apply_webhook_event(&db, &event, "2030-01-01T00:00:00+00:00").unwrap());
+        assert!(db.webhook_event_seen("evt_checkout_1").unwrap());
+        assert_eq!(subscription_rows(&db), first);
+        assert_eq!(first.len(), 1);
+
+        // Even a redelivery under a new event ID must not mint another token
+        let token = db.create_subscription("other-token", "cus_1", "sub_1", "2030-02-01T00:00:00+00:00").unwrap();
+        assert_eq!(token, first[0].0);
+        assert_eq!(subscription_rows(&db).len(), 1);
+    }
+
+    #[test]
+    fn subscription_update_before_checkout_creates_placeholder_that_checkout_adopts() {
+        let (db, _) = temp_db("webhook_order");
+        let updated = serde_json::json!({
+            "id": "evt_updated_1",
+            "type": "customer.subscription.updated",
+            "data": {"object": {"id": "sub_2", "customer": "cus_2", "status": "active", "current_period_end": 1_900_000_000}},
+        });
+        let checkout = serde_json::json!({
+            "id": "evt_checkout_2",
+            "type": "checkout.session.completed",
+            "data": {"object": {"customer": "cus_2", "subscription": "sub_2"}},
+        });
+
+        crate::stripe::apply_webhook_event(&db, &updated, "").unwrap();
+        let placeholder = subscription_rows(&db);
+        assert_eq!(placeholder.len(), 1);
+        assert_eq!(placeholder[0].2, "active");
+
+        crate::stripe::apply_webhook_event(&db, &checkout, "2030-01-01T00:00:00+00:00").unwrap();
+        crate::stripe::apply_webhook_event(&db, &checkout, "2030-01-01T00:00:00+00:00").unwrap();
+        assert_eq!(subscription_rows(&db), placeholder);
+    }
+
+    #[test]
+    fn ab_tests_flag_and_assignments() {
+        let (db, _) = temp_db("abtests");
+        let tests = r#"{"murmur":{"variants":[{"name":"control","weight":1,"prompt_override":""},{"name":"casual","weight":1,"prompt_override":"もっと砕けて"}]}}"#;
+        db.set_feature_flag("ab_tests", true, Some(tests)).unwrap();
+        let flags = db.get_feature_flags().unwrap();
+        assert_eq!(flags.ab_tests["murmur"].variants.len(), 2);
+        assert_eq!(flags.ab_tests["murmur"].variants[1].prompt(), Some("もっと砕けて"));
+
+        db.set_feature_flag("ab_tests", false, Some(tests)).unwrap();
+        assert!(db.get_feature_flags().unwrap().ab_tests.is_empty());
+
+        db.record_ab_assignment("d1", "murmur", "control").unwrap();
+        db.record_ab_assignment("d1", "murmur", "control").unwrap();
+        db.record_ab_assignment("d2", "murmur", "casual").unwrap();
+        db.record_ab_assignment("d1", "murmur", "casual").unwrap();
+        assert_eq!(db.ab_assignment_counts().unwrap(), vec![("murmur".into(), "casual".into(), 2)]);
+    }
+
+    #[test]
+    fn feed_stats_track_successes_and_survive_edits() {
+        let (db, _) = temp_db("feedstats");
+        let mut feed = DynamicFeed {
+            feed_id: "f1".into(),
+            url: "https://example.com/rss".into(),
+            source: "Example".into(),
+            category: "tech".into(),
+            enabled: true,
+            added_by: None,
+            max_articles_per_fetch: None,
+            auto_translate: false,
+            category_overrides: None,
+        };
+        db.put_feed(&feed).unwrap();
+        assert!(db.feed_stats().unwrap().is_empty());
+
+        let t0 = Utc::now() - chrono::Duration::minutes(20);
+        let t1 = Utc::now() - chrono::Duration::minutes(10);
+        db.update_feed_stats("f1", &t0, 12, 100).unwrap();
+        db.update_feed_stats("f1", &t1, 8, 300).unwrap();
+        db.log_feed_fetch_error("f1", &Utc::now(), 5000, "timeout").unwrap();
+
+        // Editing the feed keeps its stats
+        feed.source = "Renamed".into();
+        db.put_feed(&feed).unwrap();
+        let stats = db.feed_stats().unwrap();
+        assert_eq!(stats["f1"].last_fetched_at.as_deref(), Some(t1.to_rfc3339().as_str()));
+        assert_eq!(stats["f1"].last_item_count, Some(8));
+        assert_eq!(stats["f1"].avg_fetch_ms, Some(200));
+
+        let history = db.feed_fetch_history("f1", 2).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].error.as_deref(), Some("timeout"));
+        assert_eq!(history[1].item_count, 8);
+
+        db.delete_feed("f1").unwrap();
+        assert!(db.feed_fetch_history("f1", 30).unwrap().is_empty());
+    }
+
+    #[test]
+    fn source_topic_articles_match_title_keywords_within_source() {
+        let (db, _) = temp_db("source-topic");
+        let mut batch = articles(6, "topic");
+        let titles = [
+            ("NHK", "Climate summit in Geneva"),
+            ("NHK", "Climate policy debate continues"),
+            ("NHK", "Baseball season ends"),
+            ("Other", "Climate summit draws protests"),
+            ("NHK", "Geneva hosts trade talks"),
+            ("NHK", "Election results announced"),
+        ];
+        for (a, (source, title)) in batch.iter_mut().zip(titles) {
+            a.source = source.into();
+            a.title = title.into();
+        }
+        db.batch_insert_articles(&batch).unwrap();
+
+        let (found, keywords) = db.find_source_topic_articles("NHK", &batch[0].id, 5).unwrap();
+        assert_eq!(keywords.len(), 3);
+        let ids: Vec<&str> = found.iter().map(|a| a.id.as_str()).collect();
+        assert!(ids.contains(&batch[1].id.as_str()));
+        assert!(ids.contains(&batch[4].id.as_str()));
+        assert!(!ids.contains(&batch[0].id.as_str()));
+        assert!(!ids.contains(&batch[2].id.as_str()));
+        assert!(!ids.contains(&batch[3].id.as_str()));
+
+        let (found, keywords) = db.find_source_topic_articles("NHK", "missing", 5).unwrap();
+        assert!(found.is_empty() && keywords.is_empty());
+    }
+
+    #[test]
+    fn reset_analysis_only_touches_matching_analyzed_articles() {
+        let (db, _) = temp_db("reanalyze");
+        let batch = articles(4, "re");
+        db.batch_insert_articles(&batch).unwrap();
+        for a in &batch[..3] {
+            db.update_article_analysis(&a.id, "summary", &["k".into()], "neutral", 0.5, "tech").unwrap();
+        }
+        let analyzed = |db: &Db| db.get_analysis_stats().unwrap().1;
+        assert_eq!(analyzed(&db), 3);
+
+        assert!(db.reset_analysis_for_articles(&ReanalyzeFilter::default()).is_err());
+        let by_id = ReanalyzeFilter {
+            article_ids: Some(vec![batch[0].id.clone(), batch[3].id.clone()]),
+            ..Default::default()
+        };
+        assert_eq!(db.reset_analysis_for_articles(&by_id).unwrap(), 1);
+        assert_eq!(analyzed(&db), 2);
+
+        let past = ReanalyzeFilter {
+            before_analyzed_at: Some(Utc::now() - chrono::Duration::hours(1)),
+            ..Default::default()
+        };
+        assert_eq!(db.reset_analysis_for_articles(&past).unwrap(), 0);
+        let all = ReanalyzeFilter {
+            before_analyzed_at: Some(Utc::now() + chrono::Duration::hours(1)),
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(db.reset_analysis_for_articles(&all).unwrap(), 1);
+        assert_eq!(analyzed(&db), 1);
+    }
+
+    #[test]
+    fn change_chain_follows_revisions_back_to_the_original() {
+        let (db, _) = temp_db("change-chain");
+        let change = |id: &str, parent: Option<&str>, version| ChangeRequest {
+            change_id: id.into(),
+            status: ChangeStatus::Rejected,
+            command_text: format!("command {id}"),
+            interpretation: String::new(),
+            actions: Vec::new(),
+            preview_config: None,
+            preview_diff: None,
+            validation: None,
+            parent_change_id: parent.map(String::from),
+            version,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        db.create_change(&change("c1", None, 1)).unwrap();
+        db.create_change(&change("c2", Some("c1"), 2)).unwrap();
+        db.create_change(&change("c3", Some("c2"), 3)).unwrap();
+        db.create_change(&change("other", None, 1)).unwrap();
+
+        let chain = db.get_change_chain("c3").unwrap();
+        let ids: Vec<_> = chain.iter().map(|c| (c.change_id.as_str(), c.version)).collect();
+        assert_eq!(ids, [("c1", 1), ("c2", 2), ("c3", 3)]);
+        assert_eq!(chain[2].parent_change_id.as_deref(), Some("c2"));
+        assert_eq!(db.get_change_chain("c1").unwrap().len(), 1);
+        assert!(db.get_change_chain("missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn category_id_changes_move_articles_and_leave_aliases() {
+        let (db, _) = temp_db("category-move");
+        db.seed_default_categories().unwrap();
+        db.batch_insert_articles(&articles(3, "c")).unwrap();
+
+        db.rename_category("tech", "IT", Some("it")).unwrap();
+        assert_eq!(db.category_usage("tech").unwrap(), (0, 0));
+        assert_eq!(db.category_usage("it").unwrap(), (3, 0));
+        assert_eq!(db.resolve_category_alias("tech").unwrap().as_deref(), Some("it"));
+        assert!(db.get_categories().unwrap().iter().all(|(id, ..)| id != "tech"));
+
+        assert!(db.delete_category("it", None).is_err());
+        assert!(db.delete_category("it", Some("weather")).is_err());
+        assert_eq!(db.category_usage("it").unwrap(), (3, 0));
+        db.delete_category("it", Some("general")).unwrap();
+        assert_eq!(db.category_usage("general").unwrap(), (3, 0));
+        // Aliases follow their category, so both former ids now land on general
+        assert_eq!(db.resolve_category_alias("tech").unwrap().as_deref(), Some("general"));
+        assert_eq!(db.resolve_category_alias("it").unwrap().as_deref(), Some("general"));
+
+        db.put_category("tech", "テクノロジー", "Technology", 1).unwrap();
+        assert_eq!(db.resolve_category_alias("tech").unwrap(), None);
+    }
+
+    #[test]
+    fn articles_in_admin_created_categories_round_trip() {
+        let db = Db::open(":memory:").unwrap();
+        db.put_category("lifestyle", "ライフスタイル", "Lifestyle", 7).unwrap();
+        let mut batch = articles(2, "life");
+        batch[0].category = Category::from_id("lifestyle");
+        db.batch_insert_articles(&batch).unwrap();
+
+        let stored = db.get_article_by_id(&batch[0].id).unwrap().unwrap();
+        assert_eq!(stored.category, Category::Custom("lifestyle".into()));
+        let page = db.query_articles(Some(&stored.category), &Paginator::first(10), false).unwrap();
+        assert_eq!(page.items.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), [batch[0].id.as_str()]);
+    }
+
+    #[test]
+    fn canonicalized_links_are_recognised_on_the_next_fetch() {
+        let db = Db::open(":memory:").unwrap();
+        let wrapper = "https://t.co/abc";
+        let mut fetched = articles(2, "links");
+        fetched[1].url = wrapper.into();
+        fetched[1].id = news_core::dedup::article_id_from_url(wrapper);
+
+        let mut resolved = fetched[1].clone();
+        resolved.url = "https://example.com/resolved".into();
+        resolved.id = news_core::dedup::article_id_from_url(&resolved.url);
+        resolved.original_url = Some(wrapper.into());
+        db.batch_insert_articles(&[fetched[0].clone(), resolved.clone()]).unwrap();
+        assert_eq!(db.get_article_by_id(&resolved.id).unwrap().unwrap().original_url.as_deref(), Some(wrapper));
+
+        let (stored, replaced) = db.stored_feed_links(&fetched).unwrap();
+        assert_eq!(stored, HashSet::from([fetched[0].id.clone()]));
+        assert_eq!(replaced, HashSet::from([fetched[1].id.clone()]));
+    }
+
+    #[test]
+    fn source_article_counts_window_by_fetch_time() {
+        let db = Db::open(":memory:").unwrap();
+        let mut batch = articles(4, "counts");
+        batch[1].fetched_at = Utc::now() - chrono::Duration::days(2);
+        batch[2].fetched_at = Utc::now() - chrono::Duration::days(30);
+        batch[3].source = "Other".into();
+        db.batch_insert_articles(&batch).unwrap();
+        assert_eq!(db.source_article_counts("Example").unwrap(), (3, 1, 2));
+        assert_eq!(db.source_article_counts("Nobody").unwrap(), (0, 0, 0));
+    }
+
+    #[test]
+    fn articles_sharing_entities_rank_by_overlap() {
+        let db = Db::open(":memory:").unwrap();
+        let a = articles(4, "entities");
+        db.batch_insert_articles(&a).unwrap();
+        let pairs = |names: &[&str]| names.iter().map(|n| (n.to_string(), "organization".to_string())).collect::<Vec<_>>();
+        db.set_article_entities(&a[0].id, &pairs(&["Apple", "Tim Cook"])).unwrap();
+        db.set_article_entities(&a[1].id, &pairs(&["apple"])).unwrap();
+        db.set_article_entities(&a[2].id, &pairs(&["Apple", "Tim Cook", "EU"])).unwrap();
+        db.set_article_entities(&a[3].id, &pairs(&["Toyota", " "])).unwrap();
+        assert_eq!(db.get_article_entities(&a[3].id).unwrap(), ["Toyota"]);
+
+        let entities = db.get_article_entities(&a[0].id).unwrap();
+        let found = db.find_articles_by_entities(&entities, &a[0].id, 10).unwrap();
+        let ids: Vec<&str> = found.iter().map(|(article, _)| article.id.as_str()).collect();
+        assert_eq!(ids, [a[2].id.as_str(), a[1].id.as_str()]);
+        assert_eq!(found[0].1, ["Apple", "Tim Cook"]);
+        assert_eq!(found[1].1, ["apple"]);
+        assert_eq!(db.find_articles_by_entities(&entities, &a[0].id, 1).unwrap().len(), 1);
+        assert!(db.find_articles_by_entities(&[], &a[0].id, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn query_plans_and_indexes_are_reported() {
+        let db = Db::open(":memory:").unwrap();
+        db.batch_insert_articles(&articles(3, "plan")).unwrap();
+        for name in EXPLAIN_QUERIES {
+            let (sql, plan) = db.explain_query_plan(name).unwrap().unwrap();
+            assert!(sql.contains("FROM articles"), "{name}");
+            assert!(!plan.is_empty(), "{name}");
+        }
+        let (_, plan) = db.explain_query_plan("articles_by_category").unwrap().unwrap();
+        assert!(plan.iter().any(|step| step.detail.contains("idx_articles_cat_pub")), "{plan:?}");
+        assert!(db.explain_query_plan("drop_everything").unwrap().is_none());
+
+        let indexes = db.article_indexes().unwrap();
+        let cat_pub = indexes.iter().find(|i| i.name == "idx_articles_cat_pub").unwrap();
+        assert_eq!(cat_pub.columns[0], "category");
+        assert_eq!(cat_pub.origin, "c");
+        assert!(cat_pub.size_bytes > 0);
+    }
+
+    #[test]
+    fn pruning_article_chats_keeps_anonymous_sessions() {
+        let db = Db::open(":memory:").unwrap();
+        db.save_chat_session("anon", Some("device-1"), None, None, "[]").unwrap();
+        db.save_chat_session("owned", None, Some("device:device-1"), Some("a1"), "[]").unwrap();
+
+        // A negative age puts the cutoff in the future, so every owned session is idle
+        assert_eq!(db.prune_owned_chat_sessions(-1).unwrap(), 1);
+        assert!(db.get_chat_session("owned").unwrap().is_none());
+        assert_eq!(db.get_chat_session("anon").unwrap().unwrap().device_id.as_deref(), Some("device-1"));
+    }
+
+    /// Timing comparison behind the numbers in `batch_insert_articles`' doc comment.
+    /// Run with `cargo test --release -- --ignored batch_insert_speedup`.
+    #[test]
+    #[ignore]
+    fn batch_insert_speedup() {
+        let (single, _) = temp_db("single");
+        let (batched, _) = temp_db("batched");
+        let rows = articles(1000, "bench");
+
+        let started = std::time::Instant::now();
+        for a in &rows {
+            assert!(single.insert_article(a).unwrap());
+        }
+        let loop_time = started.elapsed();
+
+        let started = std::time::Instant::now();
+        assert_eq!(batched.batch_insert_articles(&rows).unwrap(), 1000);
+        let batch_time = started.elapsed();
+
+        let ratio = loop_time.as_secs_f64() / batch_time.as_secs_f64();
+        // Measured 6.5–7.7×, below 10× since WAL commits don't fsync; 4× still catches
+        // the batch path regressing to per-row commits while leaving room for noisy
+        // machines
+        assert!(ratio >= 4.0, "batch insert only {ratio:.1}x faster (loop {loop_time:?}, batch {batch_time:?})");
+    }
 }
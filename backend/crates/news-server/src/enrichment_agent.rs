@@ -1,76 +1,170 @@
 use crate::agents::{image_agent, research_agent, video_agent};
 use crate::routes::AppState;
-use std::sync::Arc;
-use tokio::sync::Semaphore;
-use tokio::time::{interval, Duration};
+use crate::supervisor::Heartbeat;
+use news_core::config::FeatureFlags;
+use news_core::enrichment::{CONTENT_TYPE_AI_IMAGE, CONTENT_TYPE_BACKGROUND_INFO, CONTENT_TYPE_YOUTUBE_VIDEOS};
+use news_core::models::Article;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, Semaphore};
+use tokio::time::{interval, Duration, MissedTickBehavior};
 use tracing::{info, warn};
+use uuid::Uuid;
 
-/// Main enrichment agent that runs in a loop.
+/// One of the enrichment sub-agents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentKind {
+    Research,
+    Image,
+    Video,
+}
+
+impl AgentKind {
+    pub const ALL: [AgentKind; 3] = [AgentKind::Research, AgentKind::Image, AgentKind::Video];
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "research" => Some(Self::Research),
+            "image" => Some(Self::Image),
+            "video" => Some(Self::Video),
+            _ => None,
+        }
+    }
+
+    /// Stored as `enrichments.agent_type`; the feature flag is `enrichment_<name>`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Research => "research",
+            Self::Image => "image",
+            Self::Video => "video",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Research => CONTENT_TYPE_BACKGROUND_INFO,
+            Self::Image => CONTENT_TYPE_AI_IMAGE,
+            Self::Video => CONTENT_TYPE_YOUTUBE_VIDEOS,
+        }
+    }
+
+    fn enabled(self, flags: &FeatureFlags) -> bool {
+        match self {
+            Self::Research => flags.enrichment_research_enabled,
+            Self::Image => flags.enrichment_image_enabled,
+            Self::Video => flags.enrichment_video_enabled,
+        }
+    }
+
+    /// Agents whose feature flag is on.
+    pub fn enabled_agents(flags: &FeatureFlags) -> Vec<AgentKind> {
+        Self::ALL.into_iter().filter(|a| a.enabled(flags)).collect()
+    }
+}
+
+/// Articles enriched at the same time, across the queue and the sweep.
+const MAX_CONCURRENT: usize = 3;
+/// Fallback sweep for popular articles the queue missed (cold start, restarts, a
+/// full channel).
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+/// Capacity of the `AppState::enrichment_tx` queue; views never wait on it.
+pub const QUEUE_CAPACITY: usize = 64;
+
+/// Article IDs currently being enriched, so the queue and the sweep don't start the
+/// same article twice.
+type InFlight = Arc<Mutex<HashSet<String>>>;
+
+/// Main enrichment agent.
 ///
-/// This agent:
-/// 1. Runs every 10 minutes
-/// 2. Identifies popular articles (top 10-20% by popularity_score)
-/// 3. Marks them for enrichment
-/// 4. Spawns parallel tasks to enrich articles
-pub async fn run(state: Arc<AppState>) {
+/// Articles arrive on `queue` as soon as their views cross the threshold (see
+/// `routes::handle_article_view`). A sweep every `SWEEP_INTERVAL` also marks the
+/// top 10-20% by popularity_score and picks up pending articles. At most
+/// `MAX_CONCURRENT` articles are enriched at a time.
+#[tracing::instrument(name = "enrichment_agent", skip_all)]
+pub async fn run(state: Arc<AppState>, queue: Arc<tokio::sync::Mutex<mpsc::Receiver<String>>>, heartbeat: Heartbeat) {
     info!("Enrichment agent starting");
 
-    let mut tick = interval(Duration::from_secs(600)); // 10 minutes
+    let mut queue = queue.lock().await;
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT));
+    let in_flight = InFlight::default();
+    let mut sweep = interval(SWEEP_INTERVAL);
+    sweep.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
     loop {
-        tick.tick().await;
-
-        if let Err(e) = run_cycle(&state).await {
-            warn!(error = %e, "Enrichment cycle failed");
+        tokio::select! {
+            biased;
+            Some(article_id) = queue.recv() => {
+                if let Err(e) = enqueue(&state, &semaphore, &in_flight, &article_id) {
+                    warn!(article_id = %article_id, error = %e, "Failed to enqueue article for enrichment");
+                }
+            }
+            _ = sweep.tick() => match run_sweep(&state, &semaphore, &in_flight).await {
+                Ok(()) => heartbeat.beat(),
+                Err(e) => warn!(error = %e, "Enrichment sweep failed"),
+            },
         }
     }
 }
 
-/// Run one enrichment cycle.
-async fn run_cycle(state: &Arc<AppState>) -> Result<(), String> {
-    info!("Starting enrichment cycle");
+/// Start enriching an article from the queue, unless it already has enrichments.
+fn enqueue(state: &Arc<AppState>, semaphore: &Arc<Semaphore>, in_flight: &InFlight, article_id: &str) -> Result<(), String> {
+    let agents = AgentKind::enabled_agents(&state.db.get_feature_flags().unwrap_or_default());
+    if agents.is_empty() || !state.db.get_enrichments(article_id)?.is_empty() {
+        return Ok(());
+    }
+    let Some(article) = state.db.get_article_by_id(article_id)? else {
+        return Ok(());
+    };
+    state.db.update_enrichment_status(article_id, "pending")?;
+    info!(article_id, "Popular article queued for enrichment");
+    spawn_enrichment(state, semaphore, in_flight, article, agents);
+    Ok(())
+}
+
+/// Mark popular articles and start the pending ones that aren't already running.
+async fn run_sweep(state: &Arc<AppState>, semaphore: &Arc<Semaphore>, in_flight: &InFlight) -> Result<(), String> {
+    let flags = state.db.get_feature_flags().unwrap_or_default();
+    let agents = AgentKind::enabled_agents(&flags);
+    if agents.is_empty() {
+        return Ok(());
+    }
 
-    // Step 1: Mark popular articles for enrichment
     mark_popular_articles_for_enrichment(state).await?;
 
-    // Step 2: Get pending enrichment articles
     let pending_articles = state
         .db
         .get_pending_enrichment_articles(20)
         .map_err(|e| format!("Failed to get pending articles: {}", e))?;
-
-    if pending_articles.is_empty() {
-        info!("No articles pending enrichment");
-        return Ok(());
+    if !pending_articles.is_empty() {
+        info!(count = pending_articles.len(), "Found articles to enrich");
     }
-
-    info!(count = pending_articles.len(), "Found articles to enrich");
-
-    // Step 3: Process articles with concurrency limit (max 3 at a time)
-    let semaphore = Arc::new(Semaphore::new(3));
-    let mut tasks = Vec::new();
-
     for article in pending_articles {
-        let state = Arc::clone(state);
-        let permit = Arc::clone(&semaphore);
-
-        let task = tokio::spawn(async move {
-            let _permit = permit.acquire().await.unwrap();
-            process_article(&state, &article).await
-        });
-
-        tasks.push(task);
+        spawn_enrichment(state, semaphore, in_flight, article, agents.clone());
     }
+    Ok(())
+}
 
-    // Wait for all tasks to complete
-    for task in tasks {
-        if let Err(e) = task.await {
-            warn!(error = %e, "Enrichment task failed");
-        }
+/// Enrich `article` in the background once a permit is free. No-op while the same
+/// article is already in flight.
+fn spawn_enrichment(
+    state: &Arc<AppState>,
+    semaphore: &Arc<Semaphore>,
+    in_flight: &InFlight,
+    article: Article,
+    agents: Vec<AgentKind>,
+) {
+    if !in_flight.lock().unwrap_or_else(|e| e.into_inner()).insert(article.id.clone()) {
+        return;
     }
-
-    info!("Enrichment cycle completed");
-    Ok(())
+    let state = Arc::clone(state);
+    let semaphore = Arc::clone(semaphore);
+    let in_flight = Arc::clone(in_flight);
+    tokio::spawn(async move {
+        if let Ok(_permit) = semaphore.acquire().await {
+            process_article(&state, &article, &agents).await;
+        }
+        in_flight.lock().unwrap_or_else(|e| e.into_inner()).remove(&article.id);
+    });
 }
 
 /// Mark popular articles (top 10-20%) for enrichment.
@@ -108,43 +202,80 @@ async fn mark_popular_articles_for_enrichment(state: &Arc<AppState>) -> Result<(
     Ok(())
 }
 
+/// Enrich one article right away (admin trigger), bypassing the popularity check.
+/// Creates the pending enrichment rows up front and returns their ids; the agents run
+/// in the background.
+pub fn enrich_now(
+    state: &Arc<AppState>,
+    article: Article,
+    agents: &[AgentKind],
+) -> Result<Vec<(AgentKind, String)>, String> {
+    state.db.update_enrichment_status(&article.id, "pending")?;
+    let jobs = create_jobs(state, &article.id, agents)?;
+
+    let state = Arc::clone(state);
+    let spawned = jobs.clone();
+    tokio::spawn(async move {
+        run_jobs(&state, &article, &spawned).await;
+    });
+
+    Ok(jobs)
+}
+
+/// Insert a pending enrichment row per agent.
+fn create_jobs(state: &AppState, article_id: &str, agents: &[AgentKind]) -> Result<Vec<(AgentKind, String)>, String> {
+    agents
+        .iter()
+        .map(|&agent| {
+            let enrichment_id = Uuid::new_v4().to_string();
+            state
+                .db
+                .create_enrichment(&enrichment_id, article_id, agent.as_str(), agent.content_type(), "{}")
+                .map_err(|e| format!("Failed to create enrichment: {}", e))?;
+            Ok((agent, enrichment_id))
+        })
+        .collect()
+}
+
 /// Process a single article for enrichment.
-async fn process_article(state: &Arc<AppState>, article: &news_core::models::Article) {
+async fn process_article(state: &Arc<AppState>, article: &Article, agents: &[AgentKind]) {
     info!(article_id = %article.id, title = %article.title, "Processing article");
 
+    match create_jobs(state, &article.id, agents) {
+        Ok(jobs) => run_jobs(state, article, &jobs).await,
+        Err(e) => {
+            warn!(article_id = %article.id, error = %e, "Failed to create enrichment jobs");
+            state.db.update_enrichment_status(&article.id, "failed").ok();
+        }
+    }
+}
+
+/// Run the given agents in parallel and record the article's final enrichment status.
+async fn run_jobs(state: &Arc<AppState>, article: &Article, jobs: &[(AgentKind, String)]) {
     // Update status to processing
     if let Err(e) = state.db.update_enrichment_status(&article.id, "processing") {
         warn!(article_id = %article.id, error = %e, "Failed to update status");
         return;
     }
 
-    // Run all three agents in parallel
-    let (image_result, video_result, research_result) = tokio::join!(
-        image_agent::run(state, article),
-        video_agent::run(state, article),
-        research_agent::run(state, article),
-    );
+    let results = futures::future::join_all(jobs.iter().map(|(agent, enrichment_id)| async move {
+        let result = match agent {
+            AgentKind::Image => image_agent::run(state, article, enrichment_id).await,
+            AgentKind::Video => video_agent::run(state, article, enrichment_id).await,
+            AgentKind::Research => research_agent::run(state, article, enrichment_id).await,
+        };
+        (*agent, result)
+    }))
+    .await;
 
     // Log results
+    let total_count = results.len();
     let mut success_count = 0;
-    let total_count = 3;
-
-    if image_result.is_ok() {
-        success_count += 1;
-    } else if let Err(e) = &image_result {
-        warn!(article_id = %article.id, error = %e, "Image agent failed");
-    }
-
-    if video_result.is_ok() {
-        success_count += 1;
-    } else if let Err(e) = &video_result {
-        warn!(article_id = %article.id, error = %e, "Video agent failed");
-    }
-
-    if research_result.is_ok() {
-        success_count += 1;
-    } else if let Err(e) = &research_result {
-        warn!(article_id = %article.id, error = %e, "Research agent failed");
+    for (agent, result) in &results {
+        match result {
+            Ok(()) => success_count += 1,
+            Err(e) => warn!(article_id = %article.id, agent = agent.as_str(), error = %e, "Enrichment agent failed"),
+        }
     }
 
     // Update final status (partial success is ok)
@@ -0,0 +1,243 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+use tracing::error;
+
+/// Error type for route handlers.
+///
+/// Every variant renders as `{"code": ..., "message": ..., "details": ...}` so the
+/// frontend can branch on `code` and show `message` (user-facing, usually Japanese).
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound(String),
+    Unauthorized(String),
+    RateLimited {
+        feature: String,
+        limit: i64,
+        used: i64,
+        tier: &'static str,
+        message: String,
+    },
+    /// AI features need at least a device ID to meter usage.
+    DeviceIdRequired,
+    /// An anonymous visitor used up today's trial; a device ID or login lifts the limit.
+    TrialExhausted { limit: i64 },
+    /// An operation that may only run once per cooldown was retried too soon.
+    TooManyRequests { retry_after_secs: i64, message: String },
+    Upstream { provider: String, message: String },
+    Timeout { provider: String, message: String },
+    /// A required backend (API key, payment provider, ...) is not configured.
+    Unavailable(String),
+    Conflict(String),
+    /// The request was well-formed but its target couldn't be processed (e.g. a feed
+    /// URL that doesn't fetch or parse).
+    Unprocessable(String),
+    Validation { field: String, message: String },
+    PayloadTooLarge { limit: usize, message: String },
+    /// The request body exceeded the route's byte limit (see `body_limit`).
+    BodyTooLarge { max_bytes: usize },
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn validation(field: &str, message: impl Into<String>) -> Self {
+        ApiError::Validation {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn upstream(provider: &str, message: impl Into<String>) -> Self {
+        ApiError::Upstream {
+            provider: provider.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn timeout(provider: &str, message: impl Into<String>) -> Self {
+        ApiError::Timeout {
+            provider: provider.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::RateLimited { .. } | ApiError::DeviceIdRequired | ApiError::TrialExhausted { .. } => {
+                StatusCode::PAYMENT_REQUIRED
+            }
+            ApiError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Upstream { .. } => StatusCode::BAD_GATEWAY,
+            ApiError::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+            ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Validation { .. } => StatusCode::BAD_REQUEST,
+            ApiError::PayloadTooLarge { .. } | ApiError::BodyTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::RateLimited { .. } => "rate_limit_exceeded",
+            ApiError::DeviceIdRequired => "device_id_required",
+            ApiError::TrialExhausted { .. } => "trial_exhausted",
+            ApiError::TooManyRequests { .. } => "too_many_requests",
+            ApiError::Upstream { .. } => "upstream_error",
+            ApiError::Timeout { .. } => "upstream_timeout",
+            ApiError::Unavailable(_) => "service_unavailable",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Unprocessable(_) => "unprocessable",
+            ApiError::Validation { .. } => "validation_error",
+            ApiError::PayloadTooLarge { .. } | ApiError::BodyTooLarge { .. } => "payload_too_large",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            ApiError::NotFound(m)
+            | ApiError::Unauthorized(m)
+            | ApiError::Unavailable(m)
+            | ApiError::Conflict(m)
+            | ApiError::Unprocessable(m) => m,
+            // The cause is logged, never sent: it is often raw SQLite or upstream text
+            ApiError::Internal(_) => "Internal server error",
+            ApiError::RateLimited { message, .. }
+            | ApiError::TooManyRequests { message, .. }
+            | ApiError::Upstream { message, .. }
+            | ApiError::Timeout { message, .. }
+            | ApiError::Validation { message, .. }
+            | ApiError::PayloadTooLarge { message, .. } => message,
+            ApiError::DeviceIdRequired => "AI機能を利用するにはデバイスIDが必要です。",
+            ApiError::BodyTooLarge { .. } => "リクエストのサイズが上限を超えています。",
+            ApiError::TrialExhausted { .. } => {
+                "本日のお試し回数を使い切りました。デバイスIDを有効にするか、Googleログインで引き続きご利用いただけます。"
+            }
+        }
+    }
+
+    fn details(&self) -> serde_json::Value {
+        match self {
+            ApiError::RateLimited {
+                feature,
+                limit,
+                used,
+                tier,
+                ..
+            } => json!({
+                "feature": feature,
+                "limit": limit,
+                "used": used,
+                "tier": tier,
+                "upgrade_url": "/pro",
+            }),
+            ApiError::DeviceIdRequired => json!({"tier": "anonymous"}),
+            ApiError::TrialExhausted { limit } => json!({"tier": "anonymous", "limit": limit}),
+            ApiError::TooManyRequests { retry_after_secs, .. } => json!({"retry_after_secs": retry_after_secs}),
+            ApiError::Upstream { provider, .. } | ApiError::Timeout { provider, .. } => {
+                json!({"provider": provider})
+            }
+            ApiError::Validation { field, .. } => json!({"field": field}),
+            ApiError::PayloadTooLarge { limit, .. } => json!({"max_chars": limit}),
+            ApiError::BodyTooLarge { max_bytes } => json!({"max_bytes": max_bytes}),
+            _ => serde_json::Value::Null,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code(), self.message())
+    }
+}
+
+/// Db and client helpers return `Result<_, String>`; those are internal failures.
+impl From<String> for ApiError {
+    fn from(e: String) -> Self {
+        ApiError::Internal(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if let ApiError::Internal(ref e) = self {
+            error!(error = %e, "Internal error");
+        }
+        let body = json!({
+            "code": self.code(),
+            "message": self.message(),
+            "details": self.details(),
+        });
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body_json(err: ApiError) -> (StatusCode, serde_json::Value) {
+        let resp = err.into_response();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn rate_limited_envelope() {
+        let (status, body) = body_json(ApiError::RateLimited {
+            feature: "tts".into(),
+            limit: 30,
+            used: 30,
+            tier: "free",
+            message: "本日の利用回数（30回）に達しました。".into(),
+        })
+        .await;
+        assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+        assert_eq!(body["code"], "rate_limit_exceeded");
+        assert_eq!(body["message"], "本日の利用回数（30回）に達しました。");
+        assert_eq!(body["details"]["feature"], "tts");
+        assert_eq!(body["details"]["limit"], 30);
+        assert_eq!(body["details"]["used"], 30);
+        assert_eq!(body["details"]["tier"], "free");
+    }
+
+    #[tokio::test]
+    async fn trial_exhausted_envelope() {
+        let (status, body) = body_json(ApiError::TrialExhausted { limit: 3 }).await;
+        assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+        assert_eq!(body["code"], "trial_exhausted");
+        assert_eq!(body["details"]["tier"], "anonymous");
+        assert_eq!(body["details"]["limit"], 3);
+        assert!(body["details"].get("upgrade_url").is_none());
+    }
+
+    #[tokio::test]
+    async fn not_found_envelope() {
+        let (status, body) = body_json(ApiError::NotFound("記事が見つかりません".into())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "not_found");
+        assert_eq!(body["message"], "記事が見つかりません");
+        assert!(body["details"].is_null());
+        assert_eq!(body.as_object().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn validation_and_internal_envelopes() {
+        let (status, body) = body_json(ApiError::validation("url", "url is required")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["details"]["field"], "url");
+
+        let (status, body) = body_json(ApiError::from("db locked".to_string())).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["code"], "internal_error");
+        assert_eq!(body["message"], "Internal server error");
+    }
+}
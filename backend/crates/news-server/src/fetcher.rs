@@ -1,88 +1,193 @@
 use crate::db::Db;
+use crate::metrics::Metrics;
+use crate::routes::category_exists;
+use crate::supervisor::Heartbeat;
 use chrono::{Duration, Utc};
-use news_core::feeds::{fetch_all_feeds, FeedConfig, FeedsConfig};
+use news_core::feeds::{
+    fetch_feeds_streaming, normalize_feed_url, FeedConfig, FeedFetchReport, FeedsConfig, FetchLimits,
+};
+use news_core::grouping::{group_articles_incremental, GroupState};
+use news_core::links;
+use news_core::models::{is_default_category, Article};
 use news_core::ogp;
-use std::sync::Arc;
+use news_core::polite::PoliteFetcher;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
 use tracing::{info, warn};
 
 const FEEDS_TOML: &str = include_str!("../../../feeds.toml");
+/// Articles published within this window are regrouped after every fetch.
+const GROUPING_WINDOW_HOURS: i64 = 48;
 
-fn fallback_feeds() -> Vec<FeedConfig> {
-    FeedsConfig::from_toml(FEEDS_TOML)
-        .map(|c| c.feeds)
-        .unwrap_or_default()
+/// Incremental grouping state per category, rebuilt by `regroup_recent`. None until
+/// the first rebuild and while grouping is off.
+pub type GroupStates = RwLock<Option<HashMap<String, GroupState>>>;
+
+/// feeds.toml, whose categories may also be admin-created ones in `db`.
+fn static_feeds(db: &Db) -> FeedsConfig {
+    match FeedsConfig::from_toml_with(FEEDS_TOML, |c| is_default_category(c) || category_exists(db, c)) {
+        Ok(config) => {
+            for w in &config.warnings {
+                warn!(index = w.index, url = %w.url, "feeds.toml: {}", w.error);
+            }
+            config
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "feeds.toml failed validation, no static feeds");
+            FeedsConfig {
+                feeds: Vec::new(),
+                warnings: Vec::new(),
+            }
+        }
+    }
 }
 
+/// feeds.toml merged with the feeds from the DB; disabled ones switch off their
+/// feeds.toml counterpart.
 fn load_feeds(db: &Db) -> Vec<FeedConfig> {
-    match db.get_enabled_feeds() {
-        Ok(feeds) if !feeds.is_empty() => {
-            info!(count = feeds.len(), "Loaded feeds from DB");
-            feeds
-                .into_iter()
-                .map(|f| FeedConfig {
-                    url: f.url,
-                    source: f.source,
-                    category: f.category,
-                })
-                .collect()
-        }
-        Ok(_) => {
-            info!("DB feeds empty, using fallback feeds.toml");
-            fallback_feeds()
+    let base = static_feeds(db);
+    match db.get_all_feeds() {
+        Ok(dynamic) => {
+            let merged = FeedsConfig::merge(&base, &dynamic);
+            info!(
+                static_feeds = base.feeds.len(),
+                dynamic_feeds = dynamic.len(),
+                total = merged.feeds.len(),
+                "Merged feeds.toml with DB feeds"
+            );
+            merged.feeds
         }
         Err(e) => {
-            warn!(error = %e, "Failed to read feeds from DB, using fallback");
-            fallback_feeds()
+            warn!(error = %e, "Failed to read DB feeds, using feeds.toml only");
+            base.feeds
         }
     }
 }
 
-pub async fn run(db: Arc<Db>, http_client: reqwest::Client) {
+#[tracing::instrument(name = "fetcher", skip_all)]
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    db: Arc<Db>,
+    http_client: reqwest::Client,
+    metrics: Arc<Metrics>,
+    groups: Arc<GroupStates>,
+    polite: Arc<PoliteFetcher>,
+    fresh_tx: mpsc::Sender<Vec<Article>>,
+    base_url: String,
+    heartbeat: Heartbeat,
+) {
     let mut fetch_interval = tokio::time::interval(std::time::Duration::from_secs(600));
+    // Row cleanup lives in `cleanup_task`; this daily tick only prunes grouping state
     let mut cleanup_interval = tokio::time::interval(std::time::Duration::from_secs(86400));
 
-    // Tick once immediately for cleanup (first tick is instant)
+    // Skip the immediate first tick
     cleanup_interval.tick().await;
 
     loop {
         tokio::select! {
             _ = fetch_interval.tick() => {
-                fetch_cycle(&db, &http_client).await;
+                let started = std::time::Instant::now();
+                fetch_cycle(&db, &http_client, &metrics, &groups, &polite, &fresh_tx, &base_url).await;
+                metrics.fetch_cycle(started);
+                heartbeat.beat();
             }
             _ = cleanup_interval.tick() => {
-                let cutoff = Utc::now() - Duration::days(7);
-                match db.delete_old_articles(&cutoff) {
-                    Ok(n) => info!(deleted = n, "Old articles cleaned up"),
-                    Err(e) => warn!(error = %e, "Failed to clean old articles"),
-                }
-                match db.cleanup_old_usage(7) {
-                    Ok(n) if n > 0 => info!(deleted = n, "Old usage records cleaned up"),
-                    Err(e) => warn!(error = %e, "Failed to clean old usage"),
-                    _ => {}
-                }
-                match db.cleanup_expired_cache() {
-                    Ok(n) if n > 0 => info!(deleted = n, "Expired cache entries cleaned up"),
-                    Err(e) => warn!(error = %e, "Failed to clean expired cache"),
-                    _ => {}
+                // Drops articles that left the window from the grouping state
+                let (db, groups) = (Arc::clone(&db), Arc::clone(&groups));
+                if let Err(e) = tokio::task::spawn_blocking(move || regroup_recent(&db, &groups)).await {
+                    warn!(error = %e, "Regroup task failed");
                 }
             }
         }
     }
 }
 
-async fn fetch_cycle(db: &Db, http_client: &reqwest::Client) {
+/// Hand the articles this cycle actually inserted to the TTS warmup. A full queue
+/// means warmup is behind; those articles wait for the next sweep instead.
+fn publish_fresh(
+    db: &Db,
+    fresh_tx: &mpsc::Sender<Vec<Article>>,
+    articles: &[Article],
+    ids: &[&str],
+    cycle_started: &chrono::DateTime<Utc>,
+) {
+    let fresh_ids = match db.fetched_since(ids, cycle_started) {
+        Ok(fresh_ids) => fresh_ids,
+        Err(e) => {
+            warn!(error = %e, "Failed to look up newly inserted articles");
+            return;
+        }
+    };
+    let fresh: Vec<Article> = articles.iter().filter(|a| fresh_ids.contains(&a.id)).cloned().collect();
+    if !fresh.is_empty() && fresh_tx.try_send(fresh).is_err() {
+        warn!("TTS warmup queue full or closed, fresh articles left to the next sweep");
+    }
+}
+
+async fn fetch_cycle(
+    db: &Arc<Db>,
+    http_client: &reqwest::Client,
+    metrics: &Metrics,
+    groups: &Arc<GroupStates>,
+    polite: &PoliteFetcher,
+    fresh_tx: &mpsc::Sender<Vec<Article>>,
+    base_url: &str,
+) {
     let feeds = load_feeds(db);
 
-    let feeds_config = FeedsConfig { feeds };
-    let articles = fetch_all_feeds(http_client, &feeds_config).await;
-    info!(total_articles = articles.len(), "Fetched all feeds");
+    let cycle_started = Utc::now();
+    // Each feed's articles are stored and grouped as soon as it arrives, so slow
+    // hosts don't hold back the rest. Resolving their links is async, so batches are
+    // handed over a channel to a loop running alongside the fetches.
+    let (batch_tx, mut batch_rx) = mpsc::unbounded_channel::<(String, Vec<Article>)>();
+    let fetch = fetch_feeds_streaming(http_client, &feeds, FetchLimits::default(), move |feed, batch| {
+        let _ = batch_tx.send((feed.source.clone(), batch));
+    });
+    let store = async {
+        let mut articles = Vec::new();
+        let mut inserted = 0;
+        while let Some((source, batch)) = batch_rx.recv().await {
+            let batch = canonicalize_batch(db, polite, batch).await;
+            // The insert holds the DB lock and grouping may fall back to a full
+            // regroup, so both run off the async runtime
+            let (db, groups) = (Arc::clone(db), Arc::clone(groups));
+            match tokio::task::spawn_blocking(move || {
+                let stored = db.batch_insert_articles(&batch);
+                group_new_articles(&db, &groups, &batch);
+                (stored, batch)
+            })
+            .await
+            {
+                Ok((stored, batch)) => {
+                    match stored {
+                        Ok(n) => {
+                            metrics.articles_inserted(n);
+                            inserted += n;
+                        }
+                        Err(e) => warn!(source = %source, error = %e, "Failed to store articles"),
+                    }
+                    articles.extend(batch);
+                }
+                Err(e) => warn!(source = %source, error = %e, "Storing task failed"),
+            }
+        }
+        (articles, inserted)
+    };
+    let (reports, (articles, inserted)) = tokio::join!(fetch, store);
+    info!(total_articles = articles.len(), inserted, "Fetched all feeds");
+    record_feed_stats(db, &reports, &cycle_started);
 
-    match db.insert_articles(&articles) {
-        Ok(inserted) => info!(inserted, "Articles stored"),
-        Err(e) => warn!(error = %e, "Failed to store articles"),
+    let ids: Vec<&str> = articles.iter().map(|a| a.id.as_str()).collect();
+    publish_fresh(db, fresh_tx, &articles, &ids, &cycle_started);
+    match db.match_saved_searches(&ids) {
+        Ok(n) if n > 0 => info!(matches = n, "Saved search matches recorded"),
+        Err(e) => warn!(error = %e, "Failed to match saved searches"),
+        _ => {}
     }
 
-    // OGP enrichment — always run to ensure articles have images
+    // OGP enrichment — always run to ensure articles have images; pages without an
+    // og:image get a generated placeholder card
     let no_image = match db.articles_without_image(50) {
         Ok(a) => a,
         Err(_) => return,
@@ -90,7 +195,13 @@ async fn fetch_cycle(db: &Db, http_client: &reqwest::Client) {
     if !no_image.is_empty() {
         let mut ogp_count = 0;
         for article in &no_image {
-            if let Some(img_url) = ogp::fetch_og_image(http_client, &article.url).await {
+            let placeholder = ogp::generate_placeholder_image_url(
+                &article.title,
+                &article.source,
+                article.category.as_str(),
+                base_url,
+            );
+            if let Some(img_url) = ogp::fetch_og_image(polite, &article.url, Some(placeholder)).await {
                 if db.update_image_url(&article.id, &img_url).is_ok() {
                     ogp_count += 1;
                 }
@@ -101,3 +212,146 @@ async fn fetch_cycle(db: &Db, http_client: &reqwest::Client) {
         }
     }
 }
+
+/// `links::canonicalize_links` for one feed's articles, minus those already stored
+/// under the link theirs resolved to.
+async fn canonicalize_batch(db: &Db, polite: &PoliteFetcher, mut batch: Vec<Article>) -> Vec<Article> {
+    let (stored, replaced) = match db.stored_feed_links(&batch) {
+        Ok(found) => found,
+        Err(e) => {
+            warn!(error = %e, "Failed to look up stored articles, keeping feed links");
+            return batch;
+        }
+    };
+    batch.retain(|a| !replaced.contains(&a.id));
+    links::canonicalize_links(polite, batch, &stored).await
+}
+
+/// Log each fetch against its DB feed row. Feeds only in feeds.toml (not seeded
+/// into the DB) have no row and aren't tracked.
+fn record_feed_stats(db: &Db, reports: &[FeedFetchReport], fetched_at: &chrono::DateTime<Utc>) {
+    let feed_ids: HashMap<String, String> = match db.get_all_feeds() {
+        Ok(feeds) => feeds.into_iter().map(|f| (normalize_feed_url(&f.url), f.feed_id)).collect(),
+        Err(e) => {
+            warn!(error = %e, "Failed to load feeds for fetch stats");
+            return;
+        }
+    };
+    for report in reports {
+        let Some(feed_id) = feed_ids.get(&normalize_feed_url(&report.url)) else {
+            continue;
+        };
+        let fetch_ms = report.elapsed.as_millis() as u64;
+        let result = match &report.error {
+            None => db.update_feed_stats(feed_id, fetched_at, report.item_count, fetch_ms),
+            Some(error) => db.log_feed_fetch_error(feed_id, fetched_at, fetch_ms, error),
+        };
+        if let Err(e) = result {
+            warn!(feed_id = %feed_id, error = %e, "Failed to record feed fetch");
+        }
+    }
+}
+
+/// Recompute the stored article groups over the recent window from scratch, if
+/// grouping is on, and rebuild the incremental state from them. Also called when the
+/// grouping flag or threshold changes.
+pub fn regroup_recent(db: &Db, groups: &GroupStates) {
+    let flags = match db.get_feature_flags() {
+        Ok(flags) if flags.grouping_enabled => flags,
+        Ok(_) => {
+            if let Ok(mut states) = groups.write() {
+                *states = None;
+            }
+            return;
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to read feature flags for grouping");
+            return;
+        }
+    };
+    let since = Utc::now() - Duration::hours(GROUPING_WINDOW_HOURS);
+    match db.regroup_articles(&since, flags.grouping_threshold) {
+        Ok(grouped) => info!(grouped, threshold = flags.grouping_threshold, "Articles regrouped"),
+        Err(e) => {
+            warn!(error = %e, "Failed to regroup articles");
+            return;
+        }
+    }
+
+    let rows = match db.grouping_window(&since) {
+        Ok(rows) => rows,
+        Err(e) => {
+            warn!(error = %e, "Failed to load articles for grouping state");
+            return;
+        }
+    };
+    let mut states: HashMap<String, GroupState> = HashMap::new();
+    for (id, category, title, group_id, group_count) in &rows {
+        let group = group_id.as_deref().map(|g| (g, group_count.unwrap_or(1)));
+        states
+            .entry(category.clone())
+            .or_insert_with(|| GroupState::new(flags.grouping_threshold))
+            .insert(id, title, group);
+    }
+    if let Ok(mut current) = groups.write() {
+        *current = Some(states);
+    }
+}
+
+/// Add freshly fetched articles to the grouping state and store the groups they
+/// joined, comparing each only against existing group representatives. Falls back to
+/// `regroup_recent` when the state hasn't been built or the threshold changed.
+fn group_new_articles(db: &Db, groups: &GroupStates, articles: &[Article]) {
+    let threshold = match db.get_feature_flags() {
+        Ok(flags) if flags.grouping_enabled => flags.grouping_threshold,
+        Ok(_) => return,
+        Err(e) => {
+            warn!(error = %e, "Failed to read feature flags for grouping");
+            return;
+        }
+    };
+    let since = Utc::now() - Duration::hours(GROUPING_WINDOW_HOURS);
+
+    let (members, grown) = {
+        let Ok(mut guard) = groups.write() else { return };
+        let stale = guard
+            .as_ref()
+            .is_none_or(|states| states.values().any(|s| s.threshold() != threshold));
+        if stale {
+            drop(guard);
+            regroup_recent(db, groups);
+            return;
+        }
+        let Some(states) = guard.as_mut() else { return };
+
+        let mut by_category: HashMap<&str, Vec<&Article>> = HashMap::new();
+        for article in articles.iter().filter(|a| a.published_at >= since) {
+            by_category.entry(article.category.as_str()).or_default().push(article);
+        }
+        let mut members = Vec::new();
+        let mut grown = Vec::new();
+        for (category, mut batch) in by_category {
+            batch.sort_by(|a, b| (a.published_at, &a.id).cmp(&(b.published_at, &b.id)));
+            let items: Vec<(&str, &str)> = batch.iter().map(|a| (a.id.as_str(), a.title.as_str())).collect();
+            let state = states
+                .entry(category.to_string())
+                .or_insert_with(|| GroupState::new(threshold));
+            let changes = group_articles_incremental(state, &items);
+            for (i, group_id) in changes.assignments {
+                if state.group_size(&group_id) > 1 {
+                    members.push((items[i].0.to_string(), group_id));
+                }
+            }
+            grown.extend(changes.grown);
+        }
+        (members, grown)
+    };
+
+    if grown.is_empty() {
+        return;
+    }
+    match db.apply_group_changes(&members, &grown) {
+        Ok(()) => info!(grouped = members.len(), groups = grown.len(), "New articles grouped"),
+        Err(e) => warn!(error = %e, "Failed to store article groups"),
+    }
+}
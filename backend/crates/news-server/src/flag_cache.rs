@@ -0,0 +1,77 @@
+//! Feature flags kept in memory for the hot paths that read them on every request
+//! (view and click recording). Admin and MCP toggles invalidate the copy; anything
+//! else that writes flags is picked up once `FLAG_CACHE_TTL` passes.
+
+use crate::db::Db;
+use news_core::config::FeatureFlags;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+pub const FLAG_CACHE_TTL: Duration = Duration::from_secs(60);
+
+pub struct FlagCache {
+    inner: RwLock<Option<(FeatureFlags, Instant)>>,
+    ttl: Duration,
+}
+
+impl FlagCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { inner: RwLock::new(None), ttl }
+    }
+
+    /// The cached flags, reloaded from `db` when missing or older than the TTL. A
+    /// failed read falls back to the defaults and isn't cached.
+    pub fn get(&self, db: &Db) -> FeatureFlags {
+        if let Ok(guard) = self.inner.read() {
+            if let Some((flags, at)) = guard.as_ref() {
+                if at.elapsed() < self.ttl {
+                    return flags.clone();
+                }
+            }
+        }
+        match db.get_feature_flags() {
+            Ok(flags) => {
+                if let Ok(mut guard) = self.inner.write() {
+                    *guard = Some((flags.clone(), Instant::now()));
+                }
+                flags
+            }
+            Err(_) => FeatureFlags::default(),
+        }
+    }
+
+    /// Drop the cached copy after a flag changes.
+    pub fn invalidate(&self) {
+        if let Ok(mut guard) = self.inner.write() {
+            *guard = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_flags_refresh_after_invalidate() {
+        let db = Db::open(":memory:").unwrap();
+        db.set_feature_flag("sentiment", false, None).unwrap();
+        let cache = FlagCache::new(FLAG_CACHE_TTL);
+        assert!(!cache.get(&db).sentiment_enabled);
+
+        db.set_feature_flag("sentiment", true, None).unwrap();
+        assert!(!cache.get(&db).sentiment_enabled, "served from the cache");
+        cache.invalidate();
+        assert!(cache.get(&db).sentiment_enabled);
+    }
+
+    #[test]
+    fn expired_flags_are_reloaded() {
+        let db = Db::open(":memory:").unwrap();
+        db.set_feature_flag("sentiment", false, None).unwrap();
+        let cache = FlagCache::new(Duration::ZERO);
+        assert!(!cache.get(&db).sentiment_enabled);
+        db.set_feature_flag("sentiment", true, None).unwrap();
+        assert!(cache.get(&db).sentiment_enabled);
+    }
+}
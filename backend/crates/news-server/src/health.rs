@@ -0,0 +1,260 @@
+//! Sub-system checks behind `GET /api/health/detailed`. Each check reports ok,
+//! degraded (serving, but something needs attention) or error (not ready).
+
+use crate::metrics::{Metrics, TASKS};
+use crate::routes::AppState;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Last Anthropic probe and when it ran.
+static ANTHROPIC_PROBE: Mutex<Option<(Instant, HealthCheck)>> = Mutex::new(None);
+
+/// Reads slower than this mark the database degraded.
+const SLOW_READ: Duration = Duration::from_millis(500);
+const ANTHROPIC_TIMEOUT: Duration = Duration::from_secs(3);
+/// The endpoint is public, so the upstream probe is reused for this long rather than
+/// sent on every call.
+const ANTHROPIC_PROBE_TTL: Duration = Duration::from_secs(60);
+/// Free space below these marks is degraded / error.
+const DISK_DEGRADED_BYTES: u64 = 500 * 1024 * 1024;
+const DISK_ERROR_BYTES: u64 = 50 * 1024 * 1024;
+
+/// How often each of `TASKS` reports a successful cycle; a task is stale after twice this.
+const TASK_INTERVALS: &[(&str, Duration)] = &[
+    ("fetcher", Duration::from_secs(600)),
+    ("analyzer", Duration::from_secs(600)),
+    ("enrichment", Duration::from_secs(60)),
+    ("tts_cache", Duration::from_secs(900)),
+    ("cleanup", Duration::from_secs(6 * 3600)),
+    ("link_checker", Duration::from_secs(3600)),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Degraded,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub status: Status,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl HealthCheck {
+    fn new(name: impl Into<String>, started: Instant, status: Status, detail: Option<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            latency_ms: started.elapsed().as_millis() as u64,
+            detail,
+        }
+    }
+}
+
+/// The worst status among `checks` (ok when there are none).
+pub fn overall(checks: &[HealthCheck]) -> Status {
+    checks.iter().map(|c| c.status).max().unwrap_or(Status::Ok)
+}
+
+pub async fn run_checks(state: &AppState) -> Vec<HealthCheck> {
+    let mut checks = vec![sqlite_writable(state), sqlite_read(state), ai_cache(state)];
+    checks.extend(background_tasks(&state.metrics, state.start_time.elapsed()));
+    checks.push(anthropic_cached(&state.http_client).await);
+    checks.push(disk_space(&state.database_path));
+    checks
+}
+
+/// Whether the database accepts writes, without writing anything.
+fn sqlite_writable(state: &AppState) -> HealthCheck {
+    let started = Instant::now();
+    match state.db.probe_writable() {
+        Ok(()) => HealthCheck::new("sqlite_writable", started, Status::Ok, None),
+        Err(e) => HealthCheck::new("sqlite_writable", started, Status::Error, Some(e)),
+    }
+}
+
+fn sqlite_read(state: &AppState) -> HealthCheck {
+    let started = Instant::now();
+    match state.db.probe_table("articles") {
+        Ok(()) if started.elapsed() > SLOW_READ => HealthCheck::new(
+            "sqlite_read",
+            started,
+            Status::Degraded,
+            Some(format!("read took over {} ms", SLOW_READ.as_millis())),
+        ),
+        Ok(()) => HealthCheck::new("sqlite_read", started, Status::Ok, None),
+        Err(e) => HealthCheck::new("sqlite_read", started, Status::Error, Some(e)),
+    }
+}
+
+fn ai_cache(state: &AppState) -> HealthCheck {
+    let started = Instant::now();
+    match state.db.probe_table("ai_cache") {
+        Ok(()) => HealthCheck::new("ai_cache", started, Status::Ok, None),
+        Err(e) => HealthCheck::new("ai_cache", started, Status::Error, Some(e)),
+    }
+}
+
+/// Expected interval between successful cycles of a background task.
+pub fn task_interval(task: &str) -> Duration {
+    TASK_INTERVALS
+        .iter()
+        .find(|(name, _)| *name == task)
+        .map_or(Duration::from_secs(3600), |(_, d)| *d)
+}
+
+/// A task that hasn't succeeded within twice its interval is degraded; right after
+/// startup it gets that long to report in.
+pub fn task_staleness(
+    last_success: Option<chrono::DateTime<chrono::Utc>>,
+    uptime: Duration,
+    interval: Duration,
+) -> (Status, String) {
+    let allowed = interval * 2;
+    match last_success {
+        Some(last) => {
+            let age = (chrono::Utc::now() - last).to_std().unwrap_or_default();
+            let status = if age > allowed { Status::Degraded } else { Status::Ok };
+            (status, format!("last success {}s ago", age.as_secs()))
+        }
+        None if uptime > allowed => (Status::Degraded, "no successful run yet".into()),
+        None => (Status::Ok, "waiting for first run".into()),
+    }
+}
+
+/// One check per background task, see `task_staleness`.
+fn background_tasks(metrics: &Metrics, uptime: Duration) -> Vec<HealthCheck> {
+    TASKS
+        .iter()
+        .map(|task| {
+            let started = Instant::now();
+            let (status, detail) =
+                task_staleness(metrics.task_last_success(task), uptime, task_interval(task));
+            HealthCheck::new(format!("task:{task}"), started, status, Some(detail))
+        })
+        .collect()
+}
+
+/// `anthropic`, reused for `ANTHROPIC_PROBE_TTL`.
+async fn anthropic_cached(client: &reqwest::Client) -> HealthCheck {
+    if let Ok(guard) = ANTHROPIC_PROBE.lock() {
+        if let Some((at, check)) = guard.as_ref().filter(|(at, _)| at.elapsed() < ANTHROPIC_PROBE_TTL) {
+            let mut check = check.clone();
+            check.detail = Some(format!("checked {}s ago", at.elapsed().as_secs()));
+            return check;
+        }
+    }
+    let check = anthropic(client).await;
+    if let Ok(mut guard) = ANTHROPIC_PROBE.lock() {
+        *guard = Some((Instant::now(), check.clone()));
+    }
+    check
+}
+
+/// Any HTTP response counts as reachable. AI features degrade without it, but the
+/// site still serves, so failures are never an error.
+async fn anthropic(client: &reqwest::Client) -> HealthCheck {
+    let started = Instant::now();
+    match client
+        .head("https://api.anthropic.com/")
+        .timeout(ANTHROPIC_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(_) => HealthCheck::new("anthropic_api", started, Status::Ok, None),
+        Err(e) => HealthCheck::new("anthropic_api", started, Status::Degraded, Some(e.to_string())),
+    }
+}
+
+fn disk_space(database_path: &str) -> HealthCheck {
+    let started = Instant::now();
+    let dir = std::path::Path::new(database_path)
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(std::path::Path::new("."));
+    match available_bytes(dir) {
+        Ok(free) => {
+            let status = if free < DISK_ERROR_BYTES {
+                Status::Error
+            } else if free < DISK_DEGRADED_BYTES {
+                Status::Degraded
+            } else {
+                Status::Ok
+            };
+            let detail = format!("{} MB free", free / (1024 * 1024));
+            HealthCheck::new("disk_space", started, status, Some(detail))
+        }
+        Err(e) => HealthCheck::new("disk_space", started, Status::Degraded, Some(e)),
+    }
+}
+
+#[cfg(unix)]
+fn available_bytes(dir: &std::path::Path) -> Result<u64, String> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(dir.as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is a valid NUL-terminated string and `stat` a writable statvfs.
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn available_bytes(_dir: &std::path::Path) -> Result<u64, String> {
+    Err("disk space check not supported on this platform".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(status: Status) -> HealthCheck {
+        HealthCheck {
+            name: "c".into(),
+            status,
+            latency_ms: 0,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn overall_status_is_the_worst_check() {
+        assert_eq!(overall(&[]), Status::Ok);
+        assert_eq!(overall(&[check(Status::Ok), check(Status::Degraded)]), Status::Degraded);
+        assert_eq!(
+            overall(&[check(Status::Error), check(Status::Degraded), check(Status::Ok)]),
+            Status::Error
+        );
+    }
+
+    #[test]
+    fn tasks_are_stale_after_twice_their_interval() {
+        let metrics = Metrics::new();
+        metrics.task_ok("fetcher");
+        let checks = background_tasks(&metrics, Duration::from_secs(60));
+        assert_eq!(checks.len(), TASKS.len());
+        assert!(checks.iter().all(|c| c.status == Status::Ok));
+
+        // Long after startup, tasks that never reported are degraded
+        let checks = background_tasks(&metrics, Duration::from_secs(3 * 24 * 3600));
+        let fetcher = checks.iter().find(|c| c.name == "task:fetcher").unwrap();
+        assert_eq!(fetcher.status, Status::Ok);
+        let analyzer = checks.iter().find(|c| c.name == "task:analyzer").unwrap();
+        assert_eq!(analyzer.status, Status::Degraded);
+    }
+
+    #[test]
+    fn disk_space_reports_free_megabytes() {
+        let path = std::env::temp_dir().join("health.db");
+        let check = disk_space(path.to_str().unwrap());
+        assert!(check.detail.unwrap_or_default().contains("MB free") || cfg!(not(unix)));
+    }
+}
@@ -0,0 +1,136 @@
+//! `GET /feed.json`: the latest articles as a JSON Feed 1.1 document
+//! (https://www.jsonfeed.org/version/1.1/). Optional fields an article lacks are
+//! left out of its item rather than sent as null.
+
+use news_core::models::Article;
+use news_core::sites::SiteMeta;
+use serde::Serialize;
+
+pub const VERSION: &str = "https://jsonfeed.org/version/1.1";
+pub const CONTENT_TYPE: &str = "application/feed+json; charset=utf-8";
+
+#[derive(Debug, Serialize)]
+pub struct JsonFeed {
+    pub version: &'static str,
+    pub title: String,
+    pub home_page_url: String,
+    pub feed_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub items: Vec<JsonFeedItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonFeedItem {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    /// The description; the title when there is none, since an item needs content.
+    pub content_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    pub date_published: String,
+    pub authors: Vec<JsonFeedAuthor>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonFeedAuthor {
+    pub name: String,
+}
+
+/// `feed_url` is this document's own URL, query included.
+pub fn build(site: &SiteMeta, title: String, feed_url: String, articles: &[Article]) -> JsonFeed {
+    JsonFeed {
+        version: VERSION,
+        title,
+        home_page_url: site.url.clone(),
+        feed_url,
+        description: non_empty(&site.description),
+        language: non_empty(&site.lang),
+        items: articles.iter().map(item).collect(),
+    }
+}
+
+fn item(article: &Article) -> JsonFeedItem {
+    JsonFeedItem {
+        id: article.id.clone(),
+        url: article.url.clone(),
+        title: article.title.clone(),
+        content_text: article
+            .description
+            .as_deref()
+            .and_then(non_empty)
+            .unwrap_or_else(|| article.title.clone()),
+        image: article.image_url.as_deref().and_then(non_empty),
+        date_published: article.published_at.to_rfc3339(),
+        authors: non_empty(&article.source).map(|name| JsonFeedAuthor { name }).into_iter().collect(),
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    Some(s.trim()).filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use news_core::models::Category;
+
+    fn site() -> SiteMeta {
+        SiteMeta {
+            host: "news.xyz".into(),
+            site_id: "xyz".into(),
+            name: "News.xyz".into(),
+            title: "News.xyz".into(),
+            description: "ニュース".into(),
+            description_long: None,
+            url: "https://news.xyz/".into(),
+            image: String::new(),
+            theme_color: String::new(),
+            lang: "ja".into(),
+            keywords: String::new(),
+        }
+    }
+
+    fn article(description: Option<&str>, image_url: Option<&str>) -> Article {
+        Article {
+            id: "a1".into(),
+            category: Category::Tech,
+            title: "見出し".into(),
+            url: "https://example.com/a".into(),
+            description: description.map(String::from),
+            image_url: image_url.map(String::from),
+            source: "Example".into(),
+            published_at: Utc::now(),
+            fetched_at: Utc::now(),
+            group_id: None,
+            group_count: None,
+            canonical_url: None,
+            tags: Vec::new(),
+            word_count: None,
+            reading_minutes: None,
+            original_url: None,
+        }
+    }
+
+    #[test]
+    fn missing_optional_fields_are_left_out() {
+        let articles = [article(None, None), article(Some("本文の概要"), Some("https://img.example.com/a.jpg"))];
+        let feed = build(&site(), "News.xyz".into(), "https://news.xyz/feed.json".into(), &articles);
+        let json = serde_json::to_value(&feed).unwrap();
+
+        assert_eq!(json["version"], VERSION);
+        assert_eq!(json["home_page_url"], "https://news.xyz/");
+        let bare = json["items"][0].as_object().unwrap();
+        assert!(!bare.contains_key("image"));
+        assert_eq!(bare["content_text"], "見出し");
+        assert_eq!(bare["authors"][0]["name"], "Example");
+
+        let full = &json["items"][1];
+        assert_eq!(full["content_text"], "本文の概要");
+        assert_eq!(full["image"], "https://img.example.com/a.jpg");
+    }
+}
@@ -0,0 +1,67 @@
+//! Dead-link detection: every hour, ask publishers for the status of recent popular
+//! articles' URLs through the polite fetcher and flag 404/410 as `dead_link`, which
+//! hides the article from listings and the sitemap. Hosts we're backed off from are
+//! skipped, and an article is checked at most once a day.
+
+use crate::db::Db;
+use crate::supervisor::Heartbeat;
+use chrono::Utc;
+use news_core::polite::{PoliteError, PoliteFetcher};
+use std::sync::Arc;
+use tokio::time::{interval_at, Duration, Instant};
+use tracing::{info, warn};
+
+const CYCLE_INTERVAL: Duration = Duration::from_secs(3600);
+const INITIAL_DELAY: Duration = Duration::from_secs(300);
+const MAX_CHECKS_PER_CYCLE: i64 = 100;
+/// Only articles published this recently are sampled.
+const RECENT_DAYS: i64 = 3;
+const RECHECK_AFTER_HOURS: i64 = 24;
+
+#[tracing::instrument(name = "link_checker", skip_all)]
+pub async fn run(db: Arc<Db>, polite: Arc<PoliteFetcher>, heartbeat: Heartbeat) {
+    info!("Link checker starting");
+    let mut tick = interval_at(Instant::now() + INITIAL_DELAY, CYCLE_INTERVAL);
+    loop {
+        tick.tick().await;
+        match check_cycle(&db, &polite).await {
+            Ok(()) => heartbeat.beat(),
+            Err(e) => warn!(error = %e, "Link check cycle failed"),
+        }
+    }
+}
+
+async fn check_cycle(db: &Db, polite: &PoliteFetcher) -> Result<(), String> {
+    let now = Utc::now();
+    let candidates = db.link_check_candidates(
+        &(now - chrono::Duration::days(RECENT_DAYS)),
+        &(now - chrono::Duration::hours(RECHECK_AFTER_HOURS)),
+        MAX_CHECKS_PER_CYCLE,
+    )?;
+
+    let (mut alive, mut dead, mut backed_off, mut failed) = (0, 0, 0, 0);
+    for (id, url) in &candidates {
+        match polite.status(url).await {
+            Ok(status) => {
+                let is_dead = matches!(status.as_u16(), 404 | 410);
+                db.record_link_check(id, is_dead)?;
+                if is_dead {
+                    info!(article_id = %id, url = %url, status = status.as_u16(), "Flagged dead link");
+                    dead += 1;
+                } else {
+                    alive += 1;
+                }
+            }
+            // Left unchecked so it comes up again once the host has cooled down
+            Err(PoliteError::BackedOff(_)) => backed_off += 1,
+            // Network errors and robots.txt refusals say nothing about the page
+            Err(_) => {
+                db.record_link_check(id, false)?;
+                failed += 1;
+            }
+        }
+    }
+
+    info!(alive, dead, backed_off, failed, total = candidates.len(), "Link check cycle complete");
+    Ok(())
+}
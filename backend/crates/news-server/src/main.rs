@@ -1,46 +1,79 @@
+mod ab_assignments;
 mod agents;
 mod analyzer;
+mod article_export;
+mod article_import;
+mod audio;
+mod body_limit;
+mod bootstrap;
+mod change_validation;
 mod chatweb;
 mod claude;
+mod cleanup_task;
+mod client_ip;
+mod cors;
 mod db;
-mod degradation_agent;
 mod enrichment_agent;
+mod error;
 mod fetcher;
+mod flag_cache;
+mod health;
+mod json_feed;
+mod link_checker;
 mod mcp;
+mod metrics;
+mod og_image;
+mod reading;
+mod ref_audio;
 mod routes;
+mod static_files;
 mod stripe;
+mod supervisor;
+#[cfg(feature = "otel")]
+mod telemetry;
+mod token_cache;
 mod tts_cache;
+mod tts_chunk;
+mod zip_store;
+
+#[cfg(test)]
+mod api_tests;
 
 use axum::body::Body;
-use axum::extract::Request;
+use axum::extract::DefaultBodyLimit;
 use axum::http::HeaderValue;
-use axum::middleware::{self, Next};
-use axum::response::{IntoResponse, Redirect};
+use axum::middleware;
 use axum::routing::{delete, get, post, put};
 use axum::Router;
 use db::Db;
 use news_core::config::DynamicFeed;
 use news_core::feeds::FeedsConfig;
+use news_core::models::is_default_category;
 use routes::AppState;
 use std::sync::Arc;
+use supervisor::{RestartPolicy, Supervisor, TaskSpec};
 use tower::limit::ConcurrencyLimitLayer;
-use tower_http::compression::CompressionLayer;
-use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::set_header::SetResponseHeaderLayer;
-use tower_http::services::ServeDir;
 use tracing::info;
 
 const FEEDS_TOML: &str = include_str!("../../../feeds.toml");
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt()
-        .with_env_filter(
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let subscriber = tracing_subscriber::registry()
+        .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
         )
-        .init();
+        .with(tracing_subscriber::fmt::layer());
+    #[cfg(feature = "otel")]
+    let subscriber = subscriber.with(telemetry::layer());
+    subscriber.init();
 
+    let start_time = std::time::Instant::now();
     let db_path = std::env::var("DATABASE_PATH").unwrap_or_else(|_| "/data/news.db".into());
     let static_dir = std::env::var("STATIC_DIR").unwrap_or_else(|_| "/app/public".into());
     let api_key = std::env::var("ANTHROPIC_API_KEY").unwrap_or_default();
@@ -67,9 +100,30 @@ async fn main() {
 
     let db = Arc::new(Db::open(&db_path).expect("Failed to open SQLite database"));
 
+    // Fail fast on a broken sites.toml; DB-added sites extend the CORS allowlist
+    info!(sites = routes::SITES.sites.len(), default = %routes::SITES.default_host, "sites.toml loaded");
+    let cors = Arc::new(cors::CorsAllowlist::from_env());
+    cors.reload(&db);
+
+    // Validate feeds.toml up front so mistakes show up in the startup log
+    let known_category = |c: &str| is_default_category(c) || routes::category_exists(&db, c);
+    let feeds_config = match FeedsConfig::from_toml_with(FEEDS_TOML, known_category) {
+        Ok(config) => {
+            for w in &config.warnings {
+                tracing::warn!(index = w.index, url = %w.url, "feeds.toml: {}", w.error);
+            }
+            info!(feeds = config.feeds.len(), warnings = config.warnings.len(), "feeds.toml validated");
+            Some(config)
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "feeds.toml failed validation");
+            None
+        }
+    };
+
     // Seed feeds from feeds.toml if DB is empty
     if db.feed_count().unwrap_or(0) == 0 {
-        if let Ok(config) = FeedsConfig::from_toml(FEEDS_TOML) {
+        if let Some(config) = &feeds_config {
             for (i, feed) in config.feeds.iter().enumerate() {
                 let dynamic = DynamicFeed {
                     feed_id: format!("seed-{}", i),
@@ -78,6 +132,9 @@ async fn main() {
                     category: feed.category.clone(),
                     enabled: true,
                     added_by: Some("seed".into()),
+                    max_articles_per_fetch: feed.max_articles_per_fetch,
+                    auto_translate: false,
+                    category_overrides: None,
                 };
                 let _ = db.put_feed(&dynamic);
             }
@@ -85,6 +142,15 @@ async fn main() {
         }
     }
 
+    // Source metadata declared in feeds.toml; rows edited by admins are kept
+    if let Some(config) = &feeds_config {
+        match db.seed_source_meta(&config.source_meta()) {
+            Ok(0) => {}
+            Ok(added) => info!(added, "Seeded source metadata from feeds.toml"),
+            Err(e) => tracing::warn!(error = %e, "Failed to seed source metadata"),
+        }
+    }
+
     // Seed default categories if table is empty
     if db.category_count().unwrap_or(0) == 0 {
         let _ = db.seed_default_categories();
@@ -103,15 +169,53 @@ async fn main() {
         .build()
         .expect("Failed to build RunPod HTTP client");
 
+    let metrics = Arc::new(metrics::Metrics::new());
+
+    // Background loops run under the supervisor, which restarts them after a panic
+    let tasks = Arc::new(Supervisor::new(Arc::clone(&metrics)));
+    // Tasks listed in NO_RESTART_TASKS (comma-separated) stay down after a crash,
+    // e.g. while one is being debugged
+    let no_restart = std::env::var("NO_RESTART_TASKS").unwrap_or_default();
+    let supervised = |name: &'static str, critical| TaskSpec {
+        name,
+        policy: if no_restart.split(',').any(|t| t.trim() == name) {
+            RestartPolicy::Never
+        } else {
+            RestartPolicy::Always
+        },
+        critical,
+    };
+
     // Spawn background fetcher
     let fetcher_db = Arc::clone(&db);
     let fetcher_client = http_client.clone();
-    tokio::spawn(async move {
-        fetcher::run(fetcher_db, fetcher_client).await;
+    let fetcher_metrics = Arc::clone(&metrics);
+    let group_states: Arc<fetcher::GroupStates> = Arc::default();
+    let fetcher_groups = Arc::clone(&group_states);
+    let polite = Arc::new(news_core::polite::PoliteFetcher::new(http_client.clone()));
+    let fetcher_polite = Arc::clone(&polite);
+    let fetcher_base_url = base_url.clone();
+    // Newly inserted articles, from the fetcher to the TTS warmup
+    let (fresh_tx, fresh_rx) = tokio::sync::mpsc::channel(8);
+    tasks.spawn(supervised("fetcher", true), move |heartbeat| {
+        fetcher::run(
+            Arc::clone(&fetcher_db),
+            fetcher_client.clone(),
+            Arc::clone(&fetcher_metrics),
+            Arc::clone(&fetcher_groups),
+            Arc::clone(&fetcher_polite),
+            fresh_tx.clone(),
+            fetcher_base_url.clone(),
+            heartbeat,
+        )
     });
 
     // NOTE: TTS pre-cache task is spawned after state construction (see below)
 
+    let claude = claude::ClaudeClient::new(http_client.clone(), api_key.clone());
+    // Article IDs whose views crossed the enrichment threshold, to the enrichment agent
+    let (enrichment_tx, enrichment_rx) = tokio::sync::mpsc::channel(enrichment_agent::QUEUE_CAPACITY);
+
     let state = Arc::new(AppState {
         db,
         http_client,
@@ -133,51 +237,224 @@ async fn main() {
         admin_secret,
         base_url,
         google_client_id,
+        metrics,
+        database_path: db_path,
+        static_dir: static_dir.clone(),
+        start_time,
+        group_states,
+        polite,
+        claude,
+        cors: Arc::clone(&cors),
+        tasks: Arc::clone(&tasks),
+        token_cache: token_cache::TokenCache::new(token_cache::TOKEN_CACHE_TTL, token_cache::TOKEN_CACHE_CAPACITY),
+        flag_cache: flag_cache::FlagCache::new(flag_cache::FLAG_CACHE_TTL),
+        ab_assignments: ab_assignments::RecordedAssignments::new(ab_assignments::AB_RECORDED_CAPACITY),
+        og_font: og_image::load_font(),
+        murmurs_in_flight: Arc::default(),
+        enrichment_tx,
     });
 
     // Spawn TTS pre-cache background task
-    tokio::spawn(tts_cache::run(Arc::clone(&state)));
+    let fresh_rx = Arc::new(tokio::sync::Mutex::new(fresh_rx));
+    let task_state = Arc::clone(&state);
+    tasks.spawn(supervised("tts_cache", false), move |heartbeat| {
+        tts_cache::run(
+            Arc::clone(&task_state),
+            Arc::clone(&fresh_rx),
+            tts_cache::WarmConfig::from_env(),
+            heartbeat,
+        )
+    });
 
     // Spawn enrichment agent background task
-    tokio::spawn(enrichment_agent::run(Arc::clone(&state)));
+    let enrichment_rx = Arc::new(tokio::sync::Mutex::new(enrichment_rx));
+    let task_state = Arc::clone(&state);
+    tasks.spawn(supervised("enrichment", false), move |heartbeat| {
+        enrichment_agent::run(Arc::clone(&task_state), Arc::clone(&enrichment_rx), heartbeat)
+    });
 
-    // Spawn degradation agent background task
-    tokio::spawn(degradation_agent::run(Arc::clone(&state)));
+    // Spawn database maintenance (expired rows, old articles, VACUUM)
+    let task_state = Arc::clone(&state);
+    tasks.spawn(supervised("cleanup", true), move |heartbeat| {
+        cleanup_task::run(Arc::clone(&task_state.db), heartbeat)
+    });
+
+    // Spawn dead-link checker (flags articles whose URL now returns 404/410)
+    let task_state = Arc::clone(&state);
+    tasks.spawn(supervised("link_checker", false), move |heartbeat| {
+        link_checker::run(Arc::clone(&task_state.db), Arc::clone(&task_state.polite), heartbeat)
+    });
 
     // Spawn AI analyzer background task (ChatWeb.ai)
-    tokio::spawn(analyzer::run(Arc::clone(&state)));
+    let task_state = Arc::clone(&state);
+    tasks.spawn(supervised("analyzer", false), move |heartbeat| {
+        analyzer::run(Arc::clone(&task_state), heartbeat)
+    });
+
+    let api_routes = api_routes(state);
+
+    let app = static_files::serve(api_routes, &static_dir)
+        .layer(ConcurrencyLimitLayer::new(256))
+        // CORS: sites, ALLOWED_ORIGINS and the cors_origins feature, reloaded on admin changes
+        .layer(cors.layer())
+        // Resolve the client IP before anything reads the forwarding headers
+        .layer(middleware::from_fn(client_ip::normalize))
+        // Security headers
+        .layer(SetResponseHeaderLayer::overriding(
+            axum::http::header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        ))
+        // CSP with the per-request nonce of SSR pages; frame-ancestors replaces X-Frame-Options
+        .layer(SetResponseHeaderLayer::if_not_present(
+            axum::http::header::CONTENT_SECURITY_POLICY,
+            routes::csp_header,
+        ))
+        .layer(SetResponseHeaderLayer::overriding(
+            axum::http::header::REFERRER_POLICY,
+            HeaderValue::from_static("strict-origin-when-cross-origin"),
+        ))
+        .layer(SetResponseHeaderLayer::overriding(
+            axum::http::header::STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_static("max-age=31536000; includeSubDomains"),
+        ));
+    #[cfg(feature = "otel")]
+    let app = app.layer(telemetry::trace_layer());
+
+    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{port}"))
+        .await
+        .expect("Failed to bind");
+
+    info!(port, "Server starting");
+
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .expect("Server error");
+
+    #[cfg(feature = "otel")]
+    telemetry::shutdown();
+}
+
+/// Routes whose handlers check a usage limit, and so can spend an anonymous caller's
+/// trial; only these report `x-trial-remaining`.
+fn trial_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/articles/:id/sentiment", get(routes::handle_article_sentiment))
+        .route("/api/articles/:id/reading-mode", get(routes::handle_article_reading_mode))
+        .route("/api/articles/translate", post(routes::handle_translate))
+        .route("/api/articles/summarize", post(routes::handle_summarize))
+        .route("/api/articles/questions", post(routes::handle_article_questions))
+        .route("/api/articles/ask", post(routes::handle_article_ask))
+        .route("/api/articles/compare", post(routes::handle_compare_articles))
+        .route("/api/articles/:id/compare", post(routes::handle_compare_coverage))
+        .route("/api/articles/chat", post(routes::handle_article_chat))
+        .route("/api/articles/classify", post(routes::handle_article_classify))
+        .route("/api/articles/action-plan", post(routes::handle_action_plan))
+        .route("/api/chat", post(routes::handle_chat))
+        .route("/api/tts/to-reading", post(routes::handle_to_reading))
+        .route("/api/tts/preview", get(routes::handle_tts_preview))
+        .route("/api/tts", post(routes::handle_tts))
+        .route("/api/tts/batch", post(routes::handle_tts_batch))
+        .route("/api/tts/clone", post(routes::handle_tts_clone))
+        .route("/api/tts/preload", post(routes::handle_tts_preload))
+        .route("/api/podcast/generate", post(routes::handle_podcast_generate))
+        .route("/api/murmur/generate", post(routes::handle_murmur_generate))
+        .route("/api/murmur/prefetch", post(routes::handle_murmur_prefetch))
+        .route("/api/murmur/:article_id/audio", post(routes::handle_murmur_audio_generate))
+        .route_layer(middleware::from_fn_with_state(state, routes::anonymous_trial_header))
+}
 
-    let api_routes = Router::new()
+/// Every route with its per-route middleware. The outer layers (static files, CORS,
+/// security headers) are added in `main`.
+fn api_routes(state: Arc<AppState>) -> Router {
+    Router::new()
         .route("/article/:id", get(routes::serve_article_html))
+        .route("/s/:code", get(routes::redirect_shortlink))
+        .route("/og/:file", get(routes::handle_og_card))
         .route("/api/articles", get(routes::get_articles))
+        .route("/api/articles/trending", get(routes::get_trending_articles))
+        .route("/api/articles/calendar", get(routes::get_article_calendar))
         .route("/api/articles/:id", get(routes::get_article_by_id))
+        .route("/api/articles/:id/audio", get(routes::handle_article_audio))
+        .route("/api/articles/:id/audio-transcript", get(routes::handle_article_audio_transcript))
+        .route("/api/articles/:id/audio-waveform", get(routes::handle_article_audio_waveform))
+        .route("/api/articles/:id/share", get(routes::handle_article_share))
+        .route("/api/articles/:id/timeline", get(routes::get_article_timeline))
+        .route("/api/articles/:id/cited-by", get(routes::get_article_cited_by))
+        .route("/api/articles/:id/keywords", get(routes::get_article_keywords))
+        .route("/api/articles/:id/similar-by-source", get(routes::similar_by_source))
+        .route("/api/articles/:id/raw-content", get(routes::handle_article_raw_content))
+        .route("/api/articles/:id/export", get(routes::handle_article_export))
+        .route("/api/articles/:id/translations/list", get(routes::list_article_translations))
         .route("/api/articles/:id/view", post(routes::handle_article_view))
         .route("/api/articles/:id/click", post(routes::handle_article_click))
         .route("/api/articles/:id/enrichments", get(routes::handle_get_enrichments))
         .route("/api/categories", get(routes::get_categories))
+        .route("/api/placeholder-image", get(routes::handle_placeholder_image))
         .route("/api/search", get(routes::handle_search))
+        .route("/api/searches", get(routes::list_saved_searches))
+        .route("/api/searches", post(routes::create_saved_search))
+        .route("/api/searches/updates", get(routes::get_saved_search_updates))
+        .route("/api/searches/:id", put(routes::update_saved_search))
+        .route("/api/searches/:id", delete(routes::delete_saved_search))
+        .route("/api/searches/:id/matches", get(routes::get_saved_search_matches))
+        .route("/api/mutes", get(routes::list_mute_rules))
+        .route("/api/mutes", post(routes::create_mute_rule))
+        .route("/api/mutes/:id", delete(routes::delete_mute_rule))
+        .route("/api/tags", get(routes::list_tags))
+        .route("/api/tags/:tag/articles", get(routes::get_tag_articles))
+        .route("/api/sources", get(routes::list_sources))
+        .route("/api/sources/:source/articles", get(routes::get_source_articles))
         .route("/api/image-proxy", get(routes::handle_image_proxy))
         .route("/health", get(routes::health))
-        .route("/api/articles/summarize", post(routes::handle_summarize))
-        .route("/api/articles/questions", post(routes::handle_article_questions))
-        .route("/api/articles/ask", post(routes::handle_article_ask))
-        .route("/api/articles/classify", post(routes::handle_article_classify))
-        .route("/api/articles/action-plan", post(routes::handle_action_plan))
-        .route("/api/tts/to-reading", post(routes::handle_to_reading))
+        .route("/api/health", get(routes::health))
+        .route("/api/health/detailed", get(routes::health_detailed))
+        .route("/api/digest/daily", get(routes::handle_daily_digest))
+        .route("/api/digest/daily/audio", get(routes::handle_daily_digest_audio))
+        .route("/api/articles/chat/:conversation_id", get(routes::get_article_chat))
         .route("/api/tts/voices", get(routes::handle_tts_voices))
-        .route("/api/tts", post(routes::handle_tts))
-        .route("/api/tts/clone", post(routes::handle_tts_clone))
-        .route("/api/podcast/generate", post(routes::handle_podcast_generate))
-        .route("/api/murmur/generate", post(routes::handle_murmur_generate))
+        .route("/api/tts/preload/status", get(routes::handle_tts_preload_status))
+        .route("/api/tts/cached/:key", get(routes::handle_tts_cached))
+        .route("/api/podcast/rss.xml", get(routes::serve_podcast_rss))
+        .route("/api/murmur/playlist", get(routes::handle_murmur_playlist))
+        .route("/api/murmur/:article_id/audio", get(routes::handle_murmur_audio))
         .route("/api/feed", get(routes::get_feed))
         .route("/api/admin/feeds", get(routes::list_feeds))
         .route("/api/admin/feeds", post(routes::add_feed))
+        .route("/api/admin/feeds/test", post(routes::handle_test_feed))
         .route("/api/admin/feeds/:feed_id", delete(routes::delete_feed))
         .route("/api/admin/feeds/:feed_id", put(routes::update_feed))
+        .route("/api/admin/feeds/:feed_id/history", get(routes::feed_history))
+        .route("/api/admin/feeds/:feed_id/articles", get(routes::feed_articles))
+        .route("/api/admin/feeds/:feed_id/articles/count", get(routes::feed_article_counts))
         .route("/api/admin/categories", post(routes::handle_categories_manage))
         .route("/api/admin/command", post(routes::handle_command))
         .route("/api/admin/features", post(routes::handle_toggle_feature))
+        .route("/api/admin/ab-tests", get(routes::list_ab_tests))
         .route("/api/admin/changes", get(routes::list_changes))
+        .route("/api/admin/system/info", get(routes::get_system_info))
+        .route("/api/admin/maintenance/status", get(routes::get_maintenance_status))
+        .route("/api/admin/db/explain", get(routes::explain_db_query))
+        .route("/api/admin/db/indexes", get(routes::list_db_indexes))
+        .route("/api/admin/tasks", get(routes::list_tasks))
+        .route("/api/admin/tasks/:name/restart", post(routes::restart_task))
+        .route("/api/admin/cache/token-stats", get(routes::token_cache_stats))
+        .route("/api/admin/analytics/heatmap", get(routes::get_view_heatmap))
+        .route("/api/admin/export/articles", get(routes::export_articles))
+        .route("/api/admin/export/usage", get(routes::export_usage))
+        .route("/api/admin/popularity/recompute", post(routes::handle_recompute_popularity))
+        .route("/api/admin/articles/:id/enrich", post(routes::handle_enrich_article))
+        .route("/api/admin/articles/:id/dead-link", delete(routes::unflag_dead_link))
+        .route("/api/admin/articles/reanalyze", post(routes::handle_reanalyze))
+        .route("/api/admin/import/articles", post(routes::handle_import_articles))
+        .route("/api/admin/enrichments", get(routes::handle_list_enrichments))
+        .route("/api/admin/sites", get(routes::list_sites))
+        .route("/api/admin/sites/:host", put(routes::upsert_site))
+        .route("/api/admin/sites/:host", delete(routes::delete_site))
+        .route("/api/admin/cors", get(routes::list_cors_origins))
+        .route("/api/admin/sources", get(routes::list_source_meta))
+        .route("/api/admin/sources/:source", put(routes::upsert_source_meta))
+        .route("/api/admin/sources/:source", delete(routes::delete_source_meta))
         .route(
             "/api/admin/changes/:id/apply",
             post(routes::apply_change),
@@ -186,6 +463,9 @@ async fn main() {
             "/api/admin/changes/:id/reject",
             post(routes::reject_change),
         )
+        .route("/api/admin/changes/:id/diff", get(routes::change_diff))
+        .route("/api/admin/changes/:id/revise", post(routes::revise_change))
+        .route("/api/admin/changes/:id/chain", get(routes::change_chain))
         // Subscription routes
         .route("/api/subscribe", post(routes::handle_subscribe))
         .route("/api/stripe/webhook", post(routes::handle_stripe_webhook))
@@ -195,7 +475,11 @@ async fn main() {
         // Auth routes
         .route("/api/auth/google", post(routes::handle_google_auth))
         .route("/api/auth/konami", post(routes::handle_konami))
+        .route("/api/auth/refresh", post(routes::handle_auth_refresh))
+        .route("/api/account/export", get(routes::handle_account_export))
+        .route("/api/account", delete(routes::handle_account_delete))
         .route("/api/config", get(routes::handle_config))
+        .route("/api/bootstrap", get(routes::handle_bootstrap))
         // Telemetry (vitals + errors from frontend beacon)
         .route("/api/telemetry", post(routes::handle_telemetry))
         // MCP server endpoint
@@ -203,97 +487,20 @@ async fn main() {
         // SEO: server-side rendered index.html with per-domain OGP meta tags
         .route("/", get(routes::serve_index_html))
         .route("/index.html", get(routes::serve_index_html))
-        // SEO: sitemap and robots.txt
+        // SEO: sitemap, robots.txt and JSON Feed
         .route("/robots.txt", get(routes::serve_robots_txt))
         .route("/sitemap.xml", get(routes::serve_sitemap_xml))
-        .with_state(state);
-
-    // CORS: restrict to known origins (same-origin requests + specific domains)
-    let cors = CorsLayer::new()
-        .allow_origin(AllowOrigin::list([
-            "https://news.xyz".parse::<HeaderValue>().unwrap(),
-            "https://news-xyz.fly.dev".parse::<HeaderValue>().unwrap(),
-            "http://localhost:8080".parse::<HeaderValue>().unwrap(),
-        ]))
-        .allow_methods([
-            axum::http::Method::GET,
-            axum::http::Method::POST,
-            axum::http::Method::PUT,
-            axum::http::Method::DELETE,
-            axum::http::Method::OPTIONS,
-        ])
-        .allow_headers([
-            axum::http::header::CONTENT_TYPE,
-            axum::http::header::AUTHORIZATION,
-            axum::http::HeaderName::from_static("x-device-id"),
-            axum::http::HeaderName::from_static("x-admin-secret"),
-        ]);
-
-    let app = api_routes
-        .fallback_service(ServeDir::new(&static_dir).append_index_html_on_directories(true))
-        .layer(middleware::from_fn(set_cache_headers))
-        .layer(ConcurrencyLimitLayer::new(256))
-        .layer(CompressionLayer::new())
-        .layer(cors)
-        // Security headers
-        .layer(SetResponseHeaderLayer::overriding(
-            axum::http::header::X_CONTENT_TYPE_OPTIONS,
-            HeaderValue::from_static("nosniff"),
-        ))
-        .layer(SetResponseHeaderLayer::overriding(
-            axum::http::header::X_FRAME_OPTIONS,
-            HeaderValue::from_static("DENY"),
-        ))
-        .layer(SetResponseHeaderLayer::overriding(
-            axum::http::header::REFERRER_POLICY,
-            HeaderValue::from_static("strict-origin-when-cross-origin"),
-        ))
-        .layer(SetResponseHeaderLayer::overriding(
-            axum::http::header::STRICT_TRANSPORT_SECURITY,
-            HeaderValue::from_static("max-age=31536000; includeSubDomains"),
-        ));
-
-    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{port}"))
-        .await
-        .expect("Failed to bind");
-
-    info!(port, "Server starting");
-
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .expect("Server error");
+        .route("/feed.json", get(routes::serve_json_feed))
+        // Prometheus scrape endpoint
+        .route("/metrics", get(metrics::handle_metrics))
+        .merge(trial_routes(Arc::clone(&state)))
+        // Per-route body limits replace axum's default 2 MB one
+        .layer(DefaultBodyLimit::disable())
+        .layer(middleware::from_fn(body_limit::enforce))
+        .layer(middleware::from_fn_with_state(Arc::clone(&state), metrics::track_http))
+        .with_state(state)
 }
 
-/// Set Cache-Control headers based on URL patterns
-async fn set_cache_headers(req: Request, next: Next) -> impl IntoResponse {
-    let path = req.uri().path().to_owned();
-    let query = req.uri().query().unwrap_or_default().to_owned();
-    let mut res = next.run(req).await;
-    let cache_value = if query.contains("v=") {
-        Some("public, max-age=31536000, immutable")
-    } else if path == "/sw.js" {
-        Some("no-cache")
-    } else if path.ends_with(".html") || path == "/" {
-        Some("no-cache")
-    } else if path.starts_with("/icons/") {
-        Some("public, max-age=604800")
-    } else if path.ends_with(".json") || path == "/robots.txt" || path == "/sitemap.xml" {
-        Some("public, max-age=3600")
-    } else {
-        None
-    };
-    if let Some(val) = cache_value {
-        res.headers_mut().insert(
-            axum::http::header::CACHE_CONTROL,
-            HeaderValue::from_static(val),
-        );
-    }
-    res
-}
-
-
-
 async fn shutdown_signal() {
     tokio::signal::ctrl_c()
         .await
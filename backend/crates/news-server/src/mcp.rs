@@ -1,11 +1,14 @@
 use crate::claude;
+use crate::error::ApiError;
 use crate::routes::AppState;
+use axum::extract::rejection::JsonRejection;
 use axum::extract::State;
 use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use news_core::config::DynamicFeed;
-use news_core::models::Category;
+use news_core::models::{Article, Category};
+use news_core::pagination::{Page, Paginator, DEFAULT_LIMIT};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
@@ -58,27 +61,30 @@ fn error(id: Value, code: i32, message: &str) -> JsonRpcResponse {
 
 pub async fn handle_mcp(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<JsonRpcRequest>,
-) -> Response {
+    body: Result<Json<JsonRpcRequest>, JsonRejection>,
+) -> Result<Response, ApiError> {
+    // Malformed envelopes never reach JSON-RPC dispatch; report them like any other API error
+    let Json(req) = body.map_err(|e| ApiError::validation("body", e.body_text()))?;
     let id = req.id.clone().unwrap_or(Value::Null);
 
     info!(method = %req.method, "MCP request");
 
     let response = match req.method.as_str() {
         "initialize" => handle_initialize(id),
-        "tools/list" => handle_tools_list(id),
+        "tools/list" => handle_tools_list(id, &state),
         "tools/call" => handle_tools_call(id, &req.params, &state).await,
-        "resources/list" => handle_resources_list(id),
+        "resources/list" => handle_resources_list(id, &req.params, &state),
+        "resources/templates/list" => handle_resource_templates_list(id),
         "resources/read" => handle_resources_read(id, &req.params, &state).await,
         "ping" => success(id, json!({})),
         _ => error(id, -32601, &format!("Method not found: {}", req.method)),
     };
 
-    (
+    Ok((
         StatusCode::OK,
         [(header::CONTENT_TYPE, "application/json")],
         Json(response),
-    ).into_response()
+    ).into_response())
 }
 
 // --- initialize ---
@@ -99,7 +105,18 @@ fn handle_initialize(id: Value) -> JsonRpcResponse {
 
 // --- tools/list ---
 
-fn handle_tools_list(id: Value) -> JsonRpcResponse {
+/// Comma-separated category ids for the tool schemas: the categories table, or the
+/// built-in ones if it can't be read or is empty.
+fn category_list(state: &AppState) -> String {
+    let ids: Vec<String> = match state.db.get_categories() {
+        Ok(cats) if !cats.is_empty() => cats.into_iter().map(|(cid, _, _, _, _)| cid).collect(),
+        _ => Category::all().iter().map(|c| c.as_str().to_string()).collect(),
+    };
+    ids.join(", ")
+}
+
+fn handle_tools_list(id: Value, state: &AppState) -> JsonRpcResponse {
+    let categories = category_list(state);
     success(id, json!({
         "tools": [
             {
@@ -108,7 +125,7 @@ fn handle_tools_list(id: Value) -> JsonRpcResponse {
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "category": { "type": "string", "description": "Category filter: general, tech, business, entertainment, sports, science" },
+                        "category": { "type": "string", "description": format!("Category filter: {categories}") },
                         "limit": { "type": "integer", "description": "Number of articles (1-100, default 20)" },
                         "cursor": { "type": "string", "description": "Pagination cursor from previous response" }
                     }
@@ -139,7 +156,9 @@ fn handle_tools_list(id: Value) -> JsonRpcResponse {
                     "properties": {
                         "url": { "type": "string", "description": "RSS feed URL" },
                         "source": { "type": "string", "description": "Source name (e.g. Reuters)" },
-                        "category": { "type": "string", "description": "Category: general, tech, business, entertainment, sports, science" }
+                        "category": { "type": "string", "description": format!("Category: {categories}") },
+                        "max_articles_per_fetch": { "type": "integer", "description": "Optional cap on articles taken per fetch (omit for unlimited)" },
+                        "auto_translate": { "type": "boolean", "description": "Pre-translate this feed's articles into Japanese" }
                     },
                     "required": ["url", "source", "category"]
                 }
@@ -202,11 +221,11 @@ fn handle_tools_list(id: Value) -> JsonRpcResponse {
             },
             {
                 "name": "update_settings",
-                "description": "Update a feature setting (e.g. grouping, ogp_enrichment)",
+                "description": "Update a feature setting (e.g. grouping, ogp_enrichment, enrichment_image)",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "feature": { "type": "string", "description": "Feature name: grouping, ogp_enrichment" },
+                        "feature": { "type": "string", "description": "Feature name: grouping, ogp_enrichment, enrichment_research, enrichment_image, enrichment_video, sentiment" },
                         "enabled": { "type": "boolean", "description": "Enable or disable" }
                     },
                     "required": ["feature", "enabled"]
@@ -239,12 +258,20 @@ async fn handle_tools_call(id: Value, params: &Value, state: &AppState) -> JsonR
 }
 
 fn tool_list_articles(id: Value, args: &Value, state: &AppState) -> JsonRpcResponse {
-    let category = args["category"].as_str().and_then(Category::from_str);
-    let limit = args["limit"].as_i64().unwrap_or(20).min(100).max(1);
-    let cursor = args["cursor"].as_str();
+    let category = match args["category"].as_str().filter(|c| !c.trim().is_empty()) {
+        Some(c) => match crate::routes::lookup_category(&state.db, c) {
+            Some(category) => Some(category),
+            None => return error(id, -32602, &format!("Unknown category: {c}")),
+        },
+        None => None,
+    };
+    let page = Paginator::new(
+        args["limit"].as_i64().unwrap_or(DEFAULT_LIMIT),
+        args["cursor"].as_str().map(str::to_string),
+    );
 
-    match state.db.query_articles(category.as_ref(), limit, cursor) {
-        Ok((articles, next_cursor)) => {
+    match state.db.query_articles(category.as_ref(), &page, false) {
+        Ok(Page { items: articles, next_cursor, .. }) => {
             let items: Vec<Value> = articles.iter().map(|a| json!({
                 "id": a.id,
                 "title": a.title,
@@ -275,8 +302,8 @@ fn tool_search_articles(id: Value, args: &Value, state: &AppState) -> JsonRpcRes
     }
 
     // Fetch recent articles and filter by keyword
-    match state.db.query_articles(None, 200, None) {
-        Ok((articles, _)) => {
+    match state.db.query_articles(None, &Paginator::first(200), false) {
+        Ok(Page { items: articles, .. }) => {
             let query_lower = query.to_lowercase();
             let matched: Vec<Value> = articles.iter()
                 .filter(|a| {
@@ -344,6 +371,9 @@ fn tool_add_feed(id: Value, args: &Value, state: &AppState) -> JsonRpcResponse {
         category: category.to_string(),
         enabled: true,
         added_by: Some("mcp".into()),
+        max_articles_per_fetch: args["max_articles_per_fetch"].as_u64().map(|n| n as u32).filter(|&n| n > 0),
+        auto_translate: args["auto_translate"].as_bool().unwrap_or(false),
+        category_overrides: None,
     };
 
     match state.db.put_feed(&feed) {
@@ -447,18 +477,18 @@ async fn tool_ask_question(id: Value, args: &Value, state: &AppState) -> JsonRpc
         return error(id, -32000, "Anthropic API key not configured");
     }
 
-    match claude::answer_question(
-        &state.http_client,
-        &state.api_key,
+    match state.metrics.claude(claude::answer_question(
+        &state.claude,
+        claude::ModelTier::Quality,
         title,
         description,
         "",
         question,
         "",
         None,
-    ).await {
+    )).await {
         Ok(answer) => success(id, json!({
-            "content": [{ "type": "text", "text": answer }]
+            "content": [{ "type": "text", "text": answer.value }]
         })),
         Err(e) => error(id, -32000, &format!("AI answer failed: {}", e)),
     }
@@ -472,8 +502,8 @@ async fn tool_summarize_news(id: Value, args: &Value, state: &AppState) -> JsonR
         return error(id, -32000, "Anthropic API key not configured");
     }
 
-    let articles = match state.db.query_articles(None, 30, None) {
-        Ok((arts, _)) => arts,
+    let articles = match state.db.query_articles(None, &Paginator::first(30), false) {
+        Ok(page) => page.items,
         Err(e) => return error(id, -32000, &format!("Failed to query articles: {}", e)),
     };
 
@@ -487,10 +517,20 @@ async fn tool_summarize_news(id: Value, args: &Value, state: &AppState) -> JsonR
         .map(|a| (a.title.clone(), a.source.clone()))
         .collect();
 
-    match claude::summarize_articles(&state.http_client, &state.api_key, &pairs, target_chars).await {
-        Ok(summary) => success(id, json!({
-            "content": [{ "type": "text", "text": summary }]
-        })),
+    match state.metrics.claude(claude::summarize_articles(&state.claude, claude::ModelTier::Quality, &pairs, target_chars, None)).await {
+        Ok(summary) => {
+            let (mut text, cited) = claude::resolve_citations(&summary.value, articles.len());
+            if !cited.is_empty() {
+                text.push_str("\n\nSources:");
+                for marker in cited {
+                    let a = &articles[marker - 1];
+                    text.push_str(&format!("\n[{}] {} {}", marker, a.title, a.url));
+                }
+            }
+            success(id, json!({
+                "content": [{ "type": "text", "text": text }]
+            }))
+        }
         Err(e) => error(id, -32000, &format!("Summarization failed: {}", e)),
     }
 }
@@ -514,6 +554,7 @@ fn tool_update_settings(id: Value, args: &Value, state: &AppState) -> JsonRpcRes
 
     match state.db.set_feature_flag(feature, enabled, None) {
         Ok(()) => {
+            state.flag_cache.invalidate();
             let label = if enabled { "enabled" } else { "disabled" };
             info!(feature, enabled, "Feature toggled via MCP");
             success(id, json!({
@@ -526,31 +567,86 @@ fn tool_update_settings(id: Value, args: &Value, state: &AppState) -> JsonRpcRes
 
 // --- resources/list ---
 
-fn handle_resources_list(id: Value) -> JsonRpcResponse {
+/// Articles per resources/list page.
+const RESOURCE_PAGE_SIZE: i64 = DEFAULT_LIMIT;
+/// Longest resource text returned, in bytes; longer renderings are cut with a notice.
+const MAX_RESOURCE_BYTES: usize = 50 * 1024;
+/// MCP's error code for a resource URI that doesn't exist.
+const RESOURCE_NOT_FOUND: i32 = -32002;
+const ARTICLE_URI_PREFIX: &str = "news://article/";
+const CATEGORY_URI_PREFIX: &str = "news://category/";
+
+/// The fixed resources, then recent articles as `news://article/{id}`. Articles page
+/// with MCP's opaque `cursor` / `nextCursor`; the fixed resources lead the first page.
+fn handle_resources_list(id: Value, params: &Value, state: &AppState) -> JsonRpcResponse {
+    let cursor = params["cursor"].as_str().map(str::to_string);
+    let page = Paginator::new(RESOURCE_PAGE_SIZE, cursor.clone());
+    if cursor.is_some() && page.keyset().is_none() {
+        return error(id, -32602, "Invalid cursor");
+    }
+    let Page { items: articles, next_cursor, .. } = match state.db.query_articles(None, &page, false) {
+        Ok(page) => page,
+        Err(e) => return error(id, -32000, &format!("Failed to list articles: {}", e)),
+    };
+
+    let mut resources = if cursor.is_none() { fixed_resources() } else { Vec::new() };
+    resources.extend(articles.iter().map(|a| json!({
+        "uri": format!("{}{}", ARTICLE_URI_PREFIX, a.id),
+        "name": a.title,
+        "description": format!("{} · {}", a.source, a.published_at.to_rfc3339()),
+        "mimeType": "text/plain"
+    })));
+    let mut result = json!({ "resources": resources });
+    if let Some(next) = next_cursor {
+        result["nextCursor"] = next.into();
+    }
+    success(id, result)
+}
+
+fn fixed_resources() -> Vec<Value> {
+    vec![
+        json!({
+            "uri": "news://articles",
+            "name": "Latest Articles",
+            "description": "Most recent news articles across all categories",
+            "mimeType": "application/json"
+        }),
+        json!({
+            "uri": "news://feeds",
+            "name": "Registered Feeds",
+            "description": "All registered RSS/Atom feeds",
+            "mimeType": "application/json"
+        }),
+        json!({
+            "uri": "news://categories",
+            "name": "Categories",
+            "description": "News categories",
+            "mimeType": "application/json"
+        }),
+        json!({
+            "uri": "news://settings",
+            "name": "Settings",
+            "description": "Current server settings and feature flags",
+            "mimeType": "application/json"
+        }),
+    ]
+}
+
+// --- resources/templates/list ---
+
+fn handle_resource_templates_list(id: Value) -> JsonRpcResponse {
     success(id, json!({
-        "resources": [
+        "resourceTemplates": [
             {
-                "uri": "news://articles",
-                "name": "Latest Articles",
-                "description": "Most recent news articles across all categories",
-                "mimeType": "application/json"
+                "uriTemplate": "news://article/{id}",
+                "name": "Article",
+                "description": "An article with its AI summary and, once extracted, its body text",
+                "mimeType": "text/plain"
             },
             {
-                "uri": "news://feeds",
-                "name": "Registered Feeds",
-                "description": "All registered RSS/Atom feeds",
-                "mimeType": "application/json"
-            },
-            {
-                "uri": "news://categories",
-                "name": "Categories",
-                "description": "News categories",
-                "mimeType": "application/json"
-            },
-            {
-                "uri": "news://settings",
-                "name": "Settings",
-                "description": "Current server settings and feature flags",
+                "uriTemplate": "news://category/{category}",
+                "name": "Articles by Category",
+                "description": "Most recent articles in a category, e.g. news://category/tech",
                 "mimeType": "application/json"
             }
         ]
@@ -562,29 +658,27 @@ fn handle_resources_list(id: Value) -> JsonRpcResponse {
 async fn handle_resources_read(id: Value, params: &Value, state: &AppState) -> JsonRpcResponse {
     let uri = params["uri"].as_str().unwrap_or("");
 
-    match uri {
-        "news://articles" => {
-            match state.db.query_articles(None, 30, None) {
-                Ok((articles, _)) => {
-                    let items: Vec<Value> = articles.iter().map(|a| json!({
-                        "id": a.id,
-                        "title": a.title,
-                        "source": a.source,
-                        "category": a.category.as_str(),
-                        "url": a.url,
-                        "published_at": a.published_at.to_rfc3339(),
-                    })).collect();
-                    success(id, json!({
-                        "contents": [{
-                            "uri": uri,
-                            "mimeType": "application/json",
-                            "text": serde_json::to_string_pretty(&items).unwrap_or_default()
-                        }]
-                    }))
-                }
-                Err(e) => error(id, -32000, &format!("Failed to read articles: {}", e)),
+    if let Some(article_id) = uri.strip_prefix(ARTICLE_URI_PREFIX) {
+        return match state.db.get_article_by_id(article_id) {
+            Ok(Some(article)) => {
+                let text = render_article(state, &article);
+                success(id, json!({
+                    "contents": [{ "uri": uri, "mimeType": "text/plain", "text": truncate_resource_text(text) }]
+                }))
             }
-        }
+            Ok(None) => resource_not_found(id, uri),
+            Err(e) => error(id, -32000, &format!("Failed to read article: {}", e)),
+        };
+    }
+    if let Some(name) = uri.strip_prefix(CATEGORY_URI_PREFIX) {
+        return match crate::routes::lookup_category(&state.db, name) {
+            Some(category) => read_article_list(id, uri, Some(&category), state),
+            None => resource_not_found(id, uri),
+        };
+    }
+
+    match uri {
+        "news://articles" => read_article_list(id, uri, None, state),
         "news://feeds" => {
             match state.db.get_all_feeds() {
                 Ok(feeds) => {
@@ -640,6 +734,82 @@ async fn handle_resources_read(id: Value, params: &Value, state: &AppState) -> J
                 Err(e) => error(id, -32000, &format!("Failed to read settings: {}", e)),
             }
         }
-        _ => error(id, -32602, &format!("Unknown resource URI: {}", uri)),
+        _ => resource_not_found(id, uri),
+    }
+}
+
+fn read_article_list(id: Value, uri: &str, category: Option<&Category>, state: &AppState) -> JsonRpcResponse {
+    match state.db.query_articles(category, &Paginator::first(30), false) {
+        Ok(Page { items: articles, .. }) => {
+            let items: Vec<Value> = articles.iter().map(|a| json!({
+                "id": a.id,
+                "uri": format!("{}{}", ARTICLE_URI_PREFIX, a.id),
+                "title": a.title,
+                "source": a.source,
+                "category": a.category.as_str(),
+                "url": a.url,
+                "published_at": a.published_at.to_rfc3339(),
+            })).collect();
+            success(id, json!({
+                "contents": [{
+                    "uri": uri,
+                    "mimeType": "application/json",
+                    "text": serde_json::to_string_pretty(&items).unwrap_or_default()
+                }]
+            }))
+        }
+        Err(e) => error(id, -32000, &format!("Failed to read articles: {}", e)),
+    }
+}
+
+/// Plain-text article: header lines, description, then the AI summary and the
+/// extracted body when they exist. The body is only taken from the cache; reading a
+/// resource never fetches the page.
+fn render_article(state: &AppState, article: &Article) -> String {
+    let mut text = format!(
+        "{}\n{} · {} · {}\n{}\n",
+        article.title,
+        article.source,
+        article.published_at.to_rfc3339(),
+        article.category.as_str(),
+        article.url,
+    );
+    let sections = [
+        ("", article.description.clone()),
+        ("Summary", state.db.get_ai_summary(&article.id).ok().flatten()),
+        ("Content", state.db.get_cache(&crate::routes::article_text_cache_key(&article.id)).ok().flatten()),
+    ];
+    for (heading, body) in sections {
+        let Some(body) = body.filter(|b| !b.trim().is_empty()) else { continue };
+        text.push('\n');
+        if !heading.is_empty() {
+            text.push_str(&format!("## {}\n", heading));
+        }
+        text.push_str(body.trim());
+        text.push('\n');
+    }
+    text
+}
+
+/// `text` cut to `MAX_RESOURCE_BYTES` on a char boundary, with a note saying so.
+fn truncate_resource_text(mut text: String) -> String {
+    if text.len() <= MAX_RESOURCE_BYTES {
+        return text;
+    }
+    let total = text.len();
+    let mut cut = MAX_RESOURCE_BYTES;
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    text.truncate(cut);
+    text.push_str(&format!("\n\n[Truncated: showing {} of {} bytes]\n", cut, total));
+    text
+}
+
+fn resource_not_found(id: Value, uri: &str) -> JsonRpcResponse {
+    let mut response = error(id, RESOURCE_NOT_FOUND, &format!("Resource not found: {}", uri));
+    if let Some(err) = response.error.as_mut() {
+        err.data = Some(json!({ "uri": uri }));
     }
+    response
 }
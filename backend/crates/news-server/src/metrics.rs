@@ -0,0 +1,275 @@
+use crate::routes::AppState;
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use prometheus::{
+    Encoder, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts,
+    Registry, TextEncoder,
+};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Background tasks that report liveness via `task_ok`.
+pub const TASKS: &[&str] = &["fetcher", "analyzer", "enrichment", "tts_cache", "cleanup", "link_checker"];
+
+/// Prometheus metrics shared through `AppState`.
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    articles_inserted: IntCounter,
+    fetch_cycle_duration: Histogram,
+    provider_calls: IntCounterVec,
+    provider_duration: HistogramVec,
+    cache_requests: IntCounterVec,
+    sqlite_busy: IntCounterVec,
+    task_last_success: GaugeVec,
+    tts_warm: IntCounterVec,
+}
+
+/// Outcomes counted by `Metrics::tts_warm`.
+pub const TTS_WARM_OUTCOMES: &[&str] = &["warmed", "skipped_cached", "skipped_budget", "failed"];
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some("news".into()), None).expect("metrics registry");
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route and status"),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let http_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by route"),
+            &["method", "route"],
+        )
+        .unwrap();
+        let articles_inserted =
+            IntCounter::new("articles_inserted_total", "Articles newly stored by the fetcher").unwrap();
+        let fetch_cycle_duration = Histogram::with_opts(
+            HistogramOpts::new("fetch_cycle_duration_seconds", "Duration of one feed fetch cycle")
+                .buckets(vec![5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0]),
+        )
+        .unwrap();
+        let provider_calls = IntCounterVec::new(
+            Opts::new("provider_calls_total", "Outbound AI/TTS calls by provider and outcome"),
+            &["kind", "provider", "outcome"],
+        )
+        .unwrap();
+        let provider_duration = HistogramVec::new(
+            HistogramOpts::new("provider_call_duration_seconds", "Outbound AI/TTS call latency")
+                .buckets(vec![0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 180.0]),
+            &["kind", "provider"],
+        )
+        .unwrap();
+        let cache_requests = IntCounterVec::new(
+            Opts::new("cache_requests_total", "ai_cache lookups by result"),
+            &["result"],
+        )
+        .unwrap();
+        let sqlite_busy = IntCounterVec::new(
+            Opts::new("sqlite_busy_total", "SQLite busy waits and lock timeouts"),
+            &["event"],
+        )
+        .unwrap();
+        let task_last_success = GaugeVec::new(
+            Opts::new(
+                "task_last_success_timestamp_seconds",
+                "Unix time of the last successful background task cycle",
+            ),
+            &["task"],
+        )
+        .unwrap();
+        let tts_warm = IntCounterVec::new(
+            Opts::new("tts_warm_total", "Freshly fetched articles seen by the TTS warmup, by outcome"),
+            &["outcome"],
+        )
+        .unwrap();
+
+        registry.register(Box::new(http_requests.clone())).unwrap();
+        registry.register(Box::new(http_duration.clone())).unwrap();
+        registry.register(Box::new(articles_inserted.clone())).unwrap();
+        registry.register(Box::new(fetch_cycle_duration.clone())).unwrap();
+        registry.register(Box::new(provider_calls.clone())).unwrap();
+        registry.register(Box::new(provider_duration.clone())).unwrap();
+        registry.register(Box::new(cache_requests.clone())).unwrap();
+        registry.register(Box::new(sqlite_busy.clone())).unwrap();
+        registry.register(Box::new(task_last_success.clone())).unwrap();
+        registry.register(Box::new(tts_warm.clone())).unwrap();
+
+        // Export every task at 0 so a task that never succeeds is visible
+        for task in TASKS {
+            task_last_success.with_label_values(&[task]).set(0.0);
+        }
+
+        Self {
+            registry,
+            http_requests,
+            http_duration,
+            articles_inserted,
+            fetch_cycle_duration,
+            provider_calls,
+            provider_duration,
+            cache_requests,
+            sqlite_busy,
+            task_last_success,
+            tts_warm,
+        }
+    }
+
+    pub fn articles_inserted(&self, n: usize) {
+        self.articles_inserted.inc_by(n as u64);
+    }
+
+    pub fn fetch_cycle(&self, started: Instant) {
+        self.fetch_cycle_duration.observe(started.elapsed().as_secs_f64());
+    }
+
+    /// Record a successful cycle of a background task (one of `TASKS`).
+    pub fn task_ok(&self, task: &str) {
+        self.task_last_success
+            .with_label_values(&[task])
+            .set(chrono::Utc::now().timestamp() as f64);
+    }
+
+    /// When `task` last succeeded, or `None` if it hasn't since startup.
+    pub fn task_last_success(&self, task: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+        let secs = self.task_last_success.with_label_values(&[task]).get() as i64;
+        if secs == 0 {
+            return None;
+        }
+        chrono::DateTime::from_timestamp(secs, 0)
+    }
+
+    /// Count fresh articles by TTS warmup outcome (one of `TTS_WARM_OUTCOMES`).
+    pub fn tts_warm(&self, outcome: &str, n: u64) {
+        self.tts_warm.with_label_values(&[outcome]).inc_by(n);
+    }
+
+    /// Totals per TTS warmup outcome since startup.
+    pub fn tts_warm_totals(&self) -> std::collections::BTreeMap<&'static str, u64> {
+        TTS_WARM_OUTCOMES
+            .iter()
+            .map(|o| (*o, self.tts_warm.with_label_values(&[o]).get()))
+            .collect()
+    }
+
+    pub fn provider_call(&self, kind: &str, provider: &str, ok: bool, started: Instant) {
+        let outcome = if ok { "ok" } else { "error" };
+        self.provider_calls
+            .with_label_values(&[kind, provider, outcome])
+            .inc();
+        self.provider_duration
+            .with_label_values(&[kind, provider])
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    /// Await a Claude API call, counting it and its outcome.
+    pub async fn claude<T, E>(&self, call: impl Future<Output = Result<T, E>>) -> Result<T, E> {
+        let started = Instant::now();
+        let result = call.await;
+        self.provider_call("claude", "anthropic", result.is_ok(), started);
+        result
+    }
+
+    /// Render the registry in the Prometheus text format, folding in counters the
+    /// Db keeps itself (cache hits/misses, SQLite busy events).
+    pub fn render(&self, db: &crate::db::Db) -> String {
+        let stats = db.stats();
+        sync_counter(&self.cache_requests.with_label_values(&["hit"]), stats.cache_hits);
+        sync_counter(&self.cache_requests.with_label_values(&["miss"]), stats.cache_misses);
+        sync_counter(&self.sqlite_busy.with_label_values(&["wait"]), stats.busy_waits);
+        sync_counter(&self.sqlite_busy.with_label_values(&["timeout"]), stats.busy_timeouts);
+
+        let mut buf = Vec::new();
+        let _ = TextEncoder::new().encode(&self.registry.gather(), &mut buf);
+        String::from_utf8(buf).unwrap_or_default()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bring a Prometheus counter up to an externally tracked monotonic total.
+fn sync_counter(counter: &IntCounter, total: u64) {
+    let current = counter.get();
+    if total > current {
+        counter.inc_by(total - current);
+    }
+}
+
+/// Middleware: count and time every request by its route template.
+pub async fn track_http(State(state): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "fallback".into());
+    let started = Instant::now();
+    let res = next.run(req).await;
+
+    let status = res.status().as_u16().to_string();
+    state
+        .metrics
+        .http_requests
+        .with_label_values(&[&method, &route, &status])
+        .inc();
+    state
+        .metrics
+        .http_duration
+        .with_label_values(&[&method, &route])
+        .observe(started.elapsed().as_secs_f64());
+    res
+}
+
+/// GET /metrics — Prometheus scrape endpoint. When ADMIN_SECRET is set, it must be
+/// sent as `x-admin-secret` or `Authorization: Bearer <secret>`.
+pub async fn handle_metrics(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    if !state.admin_secret.is_empty() {
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        let admin = headers.get("x-admin-secret").and_then(|v| v.to_str().ok());
+        if bearer != Some(state.admin_secret.as_str()) && admin != Some(state.admin_secret.as_str()) {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+    }
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        state.metrics.render(&state.db),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_counter_only_moves_forward() {
+        let c = IntCounter::new("c", "c").unwrap();
+        sync_counter(&c, 5);
+        sync_counter(&c, 3);
+        assert_eq!(c.get(), 5);
+    }
+
+    #[test]
+    fn render_includes_task_gauges() {
+        let m = Metrics::new();
+        m.task_ok("fetcher");
+        m.articles_inserted(3);
+        let families = m.registry.gather();
+        let names: Vec<&str> = families.iter().map(|f| f.get_name()).collect();
+        assert!(names.contains(&"news_task_last_success_timestamp_seconds"));
+        assert!(names.contains(&"news_articles_inserted_total"));
+        assert_eq!(m.articles_inserted.get(), 3);
+    }
+}
@@ -0,0 +1,390 @@
+//! 1200×630 PNG share cards for `GET /og/:id.png`, used as og:image for articles
+//! without an image of their own: category gradient, source, up to three lines of
+//! title and the site name. Text is drawn with fontdue from the font at
+//! `OG_FONT_PATH`; the Docker image bundles a subset of Noto Sans CJK JP covering
+//! the characters in `og-font-chars.txt`. Without a font, cards aren't rendered and
+//! pages keep the static site image.
+
+use fontdue::{Font, FontSettings};
+use std::sync::Arc;
+use tiny_skia::{
+    Color, FillRule, GradientStop, LinearGradient, Paint, PathBuilder, Pixmap, Point, Rect, SpreadMode,
+    Transform,
+};
+use tracing::{info, warn};
+
+pub const WIDTH: u32 = 1200;
+pub const HEIGHT: u32 = 630;
+const MARGIN: f32 = 80.0;
+const TITLE_SIZE: f32 = 60.0;
+const TITLE_LINE_HEIGHT: f32 = 84.0;
+const TITLE_MAX_LINES: usize = 3;
+
+/// The card font from `OG_FONT_PATH`, if set and readable.
+pub fn load_font() -> Option<Arc<Font>> {
+    let path = std::env::var("OG_FONT_PATH").ok().filter(|p| !p.is_empty())?;
+    let data = match std::fs::read(&path) {
+        Ok(data) => data,
+        Err(e) => {
+            warn!(path = %path, error = %e, "OG card font not readable; share cards disabled");
+            return None;
+        }
+    };
+    match parse_font(&data) {
+        Ok(font) => {
+            info!(path = %path, glyphs = font.glyph_count(), "OG card font loaded");
+            Some(Arc::new(font))
+        }
+        Err(e) => {
+            warn!(path = %path, error = %e, "OG card font invalid; share cards disabled");
+            None
+        }
+    }
+}
+
+/// Parse a font file (the first face of a collection), tuned for title-size text.
+pub fn parse_font(data: &[u8]) -> Result<Font, &'static str> {
+    Font::from_bytes(data, FontSettings { scale: TITLE_SIZE, ..FontSettings::default() })
+}
+
+pub struct Card<'a> {
+    pub title: &'a str,
+    pub source: &'a str,
+    pub site_name: &'a str,
+    /// Gradient from top-left to bottom-right, as `#rrggbb`.
+    pub colors: (&'a str, &'a str),
+}
+
+/// Render `card` as PNG bytes.
+pub fn render(font: &Font, card: &Card) -> Result<Vec<u8>, String> {
+    let mut pixmap = Pixmap::new(WIDTH, HEIGHT).ok_or("pixmap allocation failed")?;
+    let (from, to) = (hex_color(card.colors.0), hex_color(card.colors.1));
+    let gradient = LinearGradient::new(
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(WIDTH as f32, HEIGHT as f32),
+        vec![GradientStop::new(0.0, from), GradientStop::new(1.0, to)],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .ok_or("invalid gradient")?;
+    let paint = Paint {
+        shader: gradient,
+        ..Paint::default()
+    };
+    let full = Rect::from_xywh(0.0, 0.0, WIDTH as f32, HEIGHT as f32).ok_or("invalid rect")?;
+    pixmap.fill_rect(full, &paint, Transform::identity(), None);
+
+    // Source initial in a translucent circle, then the source name
+    let mut circle = Paint::default();
+    circle.set_color(Color::from_rgba8(255, 255, 255, 51));
+    circle.anti_alias = true;
+    if let Some(path) = PathBuilder::from_circle(128.0, 128.0, 56.0) {
+        pixmap.fill_path(&path, &circle, FillRule::Winding, Transform::identity(), None);
+    }
+    let initial: String = card.source.trim().chars().take(1).flat_map(char::to_uppercase).collect();
+    let initial_width = text_width(font, 56.0, &initial);
+    draw_text(&mut pixmap, font, 56.0, 128.0 - initial_width / 2.0, 148.0, &initial, 1.0);
+    let source = fit_line(font, 32.0, card.source.trim(), WIDTH as f32 - 208.0 - MARGIN);
+    draw_text(&mut pixmap, font, 32.0, 208.0, 140.0, &source, 0.85);
+
+    let lines = wrap_lines(card.title.trim(), WIDTH as f32 - 2.0 * MARGIN, TITLE_MAX_LINES, |c| {
+        glyph_advance(font, TITLE_SIZE, c)
+    });
+    for (i, line) in lines.iter().enumerate() {
+        let y = 300.0 + i as f32 * TITLE_LINE_HEIGHT;
+        draw_text(&mut pixmap, font, TITLE_SIZE, MARGIN, y, line, 1.0);
+    }
+
+    draw_text(&mut pixmap, font, 32.0, MARGIN, HEIGHT as f32 - 60.0, card.site_name, 0.85);
+    pixmap.encode_png().map_err(|e| e.to_string())
+}
+
+/// Advance of `c` at `size` px, or None when the font has no glyph for it (emoji in
+/// most CJK fonts), so it's left out instead of drawn as a box.
+fn glyph_advance(font: &Font, size: f32, c: char) -> Option<f32> {
+    if c.is_whitespace() {
+        return Some(font.metrics(' ', size).advance_width);
+    }
+    (font.lookup_glyph_index(c) != 0).then(|| font.metrics(c, size).advance_width)
+}
+
+fn text_width(font: &Font, size: f32, text: &str) -> f32 {
+    text.chars().filter_map(|c| glyph_advance(font, size, c)).sum()
+}
+
+/// `text` on a single line of at most `max_width`, with an ellipsis when cut.
+fn fit_line(font: &Font, size: f32, text: &str, max_width: f32) -> String {
+    wrap_lines(text, max_width, 1, |c| glyph_advance(font, size, c))
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+}
+
+/// Greedy wrap of `text` into at most `max_lines` lines no wider than `max_width`.
+/// Lines break between any two chars (Japanese has no spaces), but back up to the
+/// last space when there is one. Text that doesn't fit ends in "…". Chars `advance`
+/// has no width for are dropped.
+pub fn wrap_lines(
+    text: &str,
+    max_width: f32,
+    max_lines: usize,
+    advance: impl Fn(char) -> Option<f32>,
+) -> Vec<String> {
+    let chars: Vec<(char, f32)> = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .filter_map(|c| advance(c).map(|w| (c, w)))
+        .collect();
+    let mut lines: Vec<Vec<(char, f32)>> = Vec::new();
+    let mut line: Vec<(char, f32)> = Vec::new();
+    let mut width = 0.0;
+    let mut rest = chars.into_iter().peekable();
+    for (c, w) in rest.by_ref() {
+        if line.is_empty() && c == ' ' {
+            continue;
+        }
+        if width + w > max_width && !line.is_empty() {
+            let mut carry = Vec::new();
+            if c != ' ' {
+                if let Some(space) = line.iter().rposition(|(c, _)| *c == ' ').filter(|&i| i > 0) {
+                    carry = line.split_off(space + 1);
+                    line.pop();
+                }
+            }
+            lines.push(std::mem::take(&mut line));
+            if lines.len() == max_lines {
+                // Put the char back so the ellipsis check sees there's more
+                line = vec![(c, w)];
+                break;
+            }
+            line = carry;
+            width = line.iter().map(|(_, w)| w).sum();
+            if c == ' ' {
+                continue;
+            }
+        }
+        line.push((c, w));
+        width += w;
+    }
+    let truncated = lines.len() == max_lines && (!line.is_empty() || rest.peek().is_some());
+    if !truncated && !line.is_empty() {
+        lines.push(line);
+    }
+    if truncated {
+        if let Some(last) = lines.last_mut() {
+            let ellipsis = advance('…').unwrap_or(0.0);
+            while !last.is_empty() && last.iter().map(|(_, w)| w).sum::<f32>() + ellipsis > max_width {
+                last.pop();
+            }
+            while last.last().is_some_and(|(c, _)| *c == ' ') {
+                last.pop();
+            }
+            last.push(('…', ellipsis));
+        }
+    }
+    lines.into_iter().map(|l| l.into_iter().map(|(c, _)| c).collect()).collect()
+}
+
+/// Draw white `text` with its baseline at `y`, blending by glyph coverage × `opacity`.
+fn draw_text(pixmap: &mut Pixmap, font: &Font, size: f32, x: f32, y: f32, text: &str, opacity: f32) {
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let pixels = pixmap.pixels_mut();
+    let mut caret = x;
+    let mut previous = None;
+    for c in text.chars() {
+        let Some(advance) = glyph_advance(font, size, c) else {
+            continue;
+        };
+        if let Some(prev) = previous {
+            caret += font.horizontal_kern(prev, c, size).unwrap_or(0.0);
+        }
+        previous = Some(c);
+        let (metrics, coverage) = font.rasterize(c, size);
+        // Bitmap rows run top-down from the glyph's top edge
+        let left = caret.round() as i32 + metrics.xmin;
+        let top = y.round() as i32 - metrics.ymin - metrics.height as i32;
+        for (i, &value) in coverage.iter().enumerate() {
+            if value == 0 {
+                continue;
+            }
+            let px = left + (i % metrics.width) as i32;
+            let py = top + (i / metrics.width) as i32;
+            if px < 0 || py < 0 || px >= width || py >= height {
+                continue;
+            }
+            let pixel = &mut pixels[(py * width + px) as usize];
+            let alpha = (value as f32 / 255.0 * opacity).clamp(0.0, 1.0);
+            // The background is opaque, so blending toward white is per channel
+            let blend = |channel: u8| (channel as f32 + (255.0 - channel as f32) * alpha).round() as u8;
+            if let Some(blended) =
+                tiny_skia::PremultipliedColorU8::from_rgba(blend(pixel.red()), blend(pixel.green()), blend(pixel.blue()), 255)
+            {
+                *pixel = blended;
+            }
+        }
+        caret += advance;
+    }
+}
+
+fn hex_color(hex: &str) -> Color {
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2).unwrap_or("00"), 16).unwrap_or(0);
+    Color::from_rgba8(channel(1), channel(3), channel(5), 255)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fixed-width metrics: ASCII 1, everything else 2, no glyph for emoji.
+    fn advance(c: char) -> Option<f32> {
+        match c {
+            '😀' => None,
+            c if c.is_ascii() => Some(1.0),
+            _ => Some(2.0),
+        }
+    }
+
+    #[test]
+    fn wraps_on_spaces_or_between_chars() {
+        assert_eq!(wrap_lines("hello brave new world", 11.0, 3, advance), ["hello brave", "new world"]);
+        assert_eq!(wrap_lines("日本語のニュース", 6.0, 3, advance), ["日本語", "のニュ", "ース"]);
+        assert_eq!(wrap_lines("  short  ", 20.0, 3, advance), ["short"]);
+        assert!(wrap_lines("", 20.0, 3, advance).is_empty());
+    }
+
+    #[test]
+    fn long_titles_end_in_an_ellipsis_after_the_last_line() {
+        let lines = wrap_lines(&"あ".repeat(20), 6.0, 3, advance);
+        assert_eq!(lines, ["あああ", "あああ", "ああ…"]);
+        // Exactly three full lines fit without one
+        assert_eq!(wrap_lines(&"あ".repeat(9), 6.0, 3, advance), ["あああ", "あああ", "あああ"]);
+    }
+
+    #[test]
+    fn chars_without_glyphs_are_dropped() {
+        assert_eq!(wrap_lines("速報😀です", 20.0, 3, advance), ["速報です"]);
+    }
+
+    /// A minimal TrueType font in which every char of `chars` is the same filled
+    /// square (0.1–0.9 em wide, 0–0.8 em tall) advancing 1 em.
+    fn box_font(chars: &str) -> Vec<u8> {
+        let be16 = |v: i32| (v as u16).to_be_bytes();
+        let mut codes: Vec<u32> = chars.chars().map(u32::from).filter(|&c| c < 0xFFFF).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        codes.push(0xFFFF);
+
+        let mut head = Vec::new();
+        for v in [1, 0, 0, 0] {
+            head.extend(be16(v)); // version 1.0, fontRevision
+        }
+        head.extend([0; 4]); // checkSumAdjustment
+        head.extend(0x5F0F3CF5u32.to_be_bytes());
+        head.extend(be16(0)); // flags
+        head.extend(be16(1000)); // unitsPerEm
+        head.extend([0; 16]); // created, modified
+        for v in [0, 0, 1000, 1000, 0, 8, 2, 1, 0] {
+            head.extend(be16(v)); // bbox, macStyle, lowestRecPPEM, direction, indexToLocFormat (long), glyphDataFormat
+        }
+
+        let mut hhea = Vec::new();
+        for v in [1, 0, 880, -120, 0, 1000, 0, 0, 1000, 1, 0, 0, 0, 0, 0, 0, 0, 2] {
+            hhea.extend(be16(v));
+        }
+        let maxp = [0x00, 0x00, 0x50, 0x00, 0x00, 0x02].to_vec(); // version 0.5, 2 glyphs
+        let mut hmtx = Vec::new();
+        for v in [500, 0, 1000, 100] {
+            hmtx.extend(be16(v));
+        }
+
+        // Glyph 0 is empty; glyph 1 is one four-point contour, all on-curve
+        let mut square = Vec::new();
+        for v in [1, 100, 0, 900, 800, 3, 0] {
+            square.extend(be16(v));
+        }
+        square.extend([1; 4]);
+        for v in [100, 0, 800, 0, 0, 800, 0, -800] {
+            square.extend(be16(v));
+        }
+        square.resize(square.len().next_multiple_of(4), 0);
+        let mut loca = Vec::new();
+        for v in [0u32, 0, square.len() as u32] {
+            loca.extend(v.to_be_bytes());
+        }
+
+        // cmap format 4: one segment per char, each mapped to glyph 1 through idDelta
+        let seg = codes.len() as i32;
+        let search = 2 * (1 << (31 - (seg as u32).leading_zeros()));
+        let mut sub = Vec::new();
+        for v in [4, 16 + 8 * seg, 0, 2 * seg, search, (search / 2).trailing_zeros() as i32, 2 * seg - search] {
+            sub.extend(be16(v));
+        }
+        codes.iter().for_each(|&c| sub.extend(be16(c as i32)));
+        sub.extend(be16(0));
+        codes.iter().for_each(|&c| sub.extend(be16(c as i32)));
+        codes.iter().for_each(|&c| sub.extend(be16(if c == 0xFFFF { 1 } else { 1 - c as i32 })));
+        codes.iter().for_each(|_| sub.extend(be16(0)));
+        let mut cmap = Vec::new();
+        for v in [0, 1, 3, 1, 0, 12] {
+            cmap.extend(be16(v));
+        }
+        cmap.extend(sub);
+
+        let tables: [(&[u8; 4], Vec<u8>); 7] = [
+            (b"cmap", cmap),
+            (b"glyf", square),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"loca", loca),
+            (b"maxp", maxp),
+        ];
+        let mut font = Vec::new();
+        for v in [1, 0, tables.len() as i32, 64, 2, 48] {
+            font.extend(be16(v));
+        }
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, data) in &tables {
+            font.extend(*tag);
+            font.extend([0; 4]);
+            font.extend((offset as u32).to_be_bytes());
+            font.extend((data.len() as u32).to_be_bytes());
+            offset += data.len().next_multiple_of(4);
+        }
+        for (_, data) in &tables {
+            font.extend(data);
+            font.resize(font.len().next_multiple_of(4), 0);
+        }
+        font
+    }
+
+    #[test]
+    fn renders_a_cjk_title_to_png() {
+        let card = Card {
+            title: "日本銀行が金利を引き上げ",
+            source: "共同通信",
+            site_name: "ニュース",
+            colors: ("#000000", "#000000"),
+        };
+        let font = parse_font(&box_font(&format!("{}{}{}", card.title, card.source, card.site_name))).unwrap();
+        assert_eq!(glyph_advance(&font, TITLE_SIZE, '日'), Some(TITLE_SIZE));
+        assert_eq!(glyph_advance(&font, TITLE_SIZE, 'x'), None);
+
+        let png = render(&font, &card).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+        let pixmap = Pixmap::decode_png(&png).unwrap();
+        let white = |x: u32, y: u32| pixmap.pixel(x, y).is_some_and(|p| p.red() == 255 && p.green() == 255);
+        // Inside the first title glyph (x 86–134, baseline 300, 48 px tall) and between glyphs
+        assert!(white(110, 280));
+        assert!(!white(140, 280));
+        assert!(!white(110, 310), "below the baseline");
+    }
+
+    #[test]
+    fn hex_colors_parse() {
+        assert_eq!(hex_color("#667eea"), Color::from_rgba8(0x66, 0x7e, 0xea, 255));
+        assert_eq!(hex_color("#zz"), Color::from_rgba8(0, 0, 0, 255));
+    }
+}
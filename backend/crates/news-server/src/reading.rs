@@ -0,0 +1,124 @@
+use crate::db::Db;
+use crate::routes::cache_key;
+use crate::tts_chunk;
+use std::future::Future;
+use tracing::{debug, info};
+use unicode_normalization::UnicodeNormalization;
+
+/// How to-reading conversion should prepare text. Voices sharing a profile share
+/// one cached conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingProfile {
+    /// Keep kanji, add readings only for hard proper nouns (ElevenLabs, OpenAI, Venice, ...).
+    KanjiPreserving,
+    /// Translate to Japanese and expand for the RunPod Qwen/CosyVoice models.
+    QwenJapanese,
+}
+
+impl ReadingProfile {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::KanjiPreserving => "kanji-preserving",
+            Self::QwenJapanese => "qwen-japanese",
+        }
+    }
+
+    /// Engine name passed to `claude::convert_to_reading`.
+    pub fn engine(self) -> &'static str {
+        match self {
+            Self::KanjiPreserving => "elevenlabs",
+            Self::QwenJapanese => "qwen-tts",
+        }
+    }
+}
+
+/// Reading profile for a TTS voice_id.
+pub fn reading_profile(voice_id: &str) -> ReadingProfile {
+    match tts_chunk::provider(voice_id) {
+        "qwen-tts" | "qwen-omni" | "cosyvoice" => ReadingProfile::QwenJapanese,
+        _ => ReadingProfile::KanjiPreserving,
+    }
+}
+
+/// NFKC-normalize, trim and collapse runs of whitespace to a single space.
+pub fn normalize_text(text: &str) -> String {
+    let nfkc: String = text.nfkc().collect();
+    nfkc.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// ai_cache key for the reading of `text` under `profile`.
+pub fn reading_cache_key(profile: ReadingProfile, text: &str) -> String {
+    cache_key("to_reading", &format!("{}|{}", profile.as_str(), normalize_text(text)))
+}
+
+/// Cached to-reading conversion. `convert` is only called on a cache miss, with the
+/// profile's engine name; failed conversions are not cached.
+pub async fn cached_reading<F, Fut>(
+    db: &Db,
+    profile: ReadingProfile,
+    text: &str,
+    ttl_secs: i64,
+    convert: F,
+) -> Result<String, String>
+where
+    F: FnOnce(&'static str) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    let key = reading_cache_key(profile, text);
+    if let Ok(Some(reading)) = db.get_cache(&key) {
+        debug!(profile = profile.as_str(), "to_reading cache hit");
+        return Ok(reading);
+    }
+    info!(profile = profile.as_str(), chars = text.chars().count(), "to_reading cache miss");
+    let reading = convert(profile.engine()).await?;
+    let _ = db.set_cache(&key, "to_reading", &reading, ttl_secs);
+    Ok(reading)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn providers_map_to_profiles() {
+        assert_eq!(reading_profile("qwen-tts:Japanese"), ReadingProfile::QwenJapanese);
+        assert_eq!(reading_profile("cosyvoice:longxiaochun"), ReadingProfile::QwenJapanese);
+        assert_eq!(reading_profile("openai:nova"), ReadingProfile::KanjiPreserving);
+        assert_eq!(reading_profile("venice:af_heart"), ReadingProfile::KanjiPreserving);
+        assert_eq!(reading_profile("21m00Tcm4TlvDq8ikWAM"), ReadingProfile::KanjiPreserving);
+    }
+
+    #[test]
+    fn normalization_ignores_whitespace_and_width() {
+        assert_eq!(normalize_text("  ＡＩ　ニュース\n\n速報 "), "AI ニュース 速報");
+        assert_eq!(
+            reading_cache_key(ReadingProfile::KanjiPreserving, "経済  発表"),
+            reading_cache_key(ReadingProfile::KanjiPreserving, "経済 発表\n")
+        );
+        assert_ne!(
+            reading_cache_key(ReadingProfile::KanjiPreserving, "経済"),
+            reading_cache_key(ReadingProfile::QwenJapanese, "経済")
+        );
+    }
+
+    #[tokio::test]
+    async fn voices_in_same_profile_share_one_conversion() {
+        let db = Db::open(":memory:").unwrap();
+        let calls = AtomicUsize::new(0);
+        let convert = |engine: &'static str| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(format!("{engine}:よみ")) }
+        };
+
+        let a = cached_reading(&db, reading_profile("openai:nova"), "AIが発表", 60, convert).await;
+        let b = cached_reading(&db, reading_profile("venice:af_heart"), " AIが発表 ", 60, convert).await;
+        assert_eq!(a.unwrap(), "elevenlabs:よみ");
+        assert_eq!(b.unwrap(), "elevenlabs:よみ");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let c = cached_reading(&db, reading_profile("qwen-tts:Japanese"), "AIが発表", 60, convert).await;
+        assert_eq!(c.unwrap(), "qwen-tts:よみ");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
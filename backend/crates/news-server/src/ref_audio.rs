@@ -0,0 +1,201 @@
+//! Checks on the reference audio of `POST /api/tts/clone`, so a bad upload fails
+//! with a 400 before any RunPod time is spent on it.
+
+use crate::error::ApiError;
+use base64::Engine;
+
+/// Longest reference clip accepted, where the duration can be read from the file.
+pub const MAX_DURATION_SECS: f64 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Wav,
+    Mp3,
+    M4a,
+    /// What browsers' MediaRecorder produces (settings → voice clone recording).
+    Webm,
+    Ogg,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefAudio {
+    pub format: AudioFormat,
+    pub bytes: usize,
+    /// None when the format's duration isn't cheap to read (WebM, Ogg, VBR oddities).
+    pub duration_secs: Option<f64>,
+}
+
+/// Decode and sanity-check base64 reference audio: canonical padding, a known
+/// container, and at most `MAX_DURATION_SECS` long.
+pub fn validate(b64: &str) -> Result<RefAudio, ApiError> {
+    let b64 = b64.trim();
+    if b64.is_empty() {
+        return Err(ApiError::validation("ref_audio", "参照音声が空です"));
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(b64)
+        .map_err(|_| ApiError::validation("ref_audio", "参照音声のbase64が不正です"))?;
+    let format = detect_format(&bytes).ok_or_else(|| {
+        ApiError::validation("ref_audio", "対応していない音声形式です（WAV / MP3 / M4A / WebM / Ogg）")
+    })?;
+    let duration_secs = match format {
+        AudioFormat::Wav => wav_duration(&bytes),
+        AudioFormat::Mp3 => mp3_duration(&bytes),
+        AudioFormat::M4a => m4a_duration(&bytes),
+        AudioFormat::Webm | AudioFormat::Ogg => None,
+    };
+    if duration_secs.is_some_and(|d| d > MAX_DURATION_SECS) {
+        return Err(ApiError::validation(
+            "ref_audio",
+            format!("参照音声は{}秒以内にしてください", MAX_DURATION_SECS),
+        ));
+    }
+    Ok(RefAudio {
+        format,
+        bytes: bytes.len(),
+        duration_secs,
+    })
+}
+
+fn detect_format(bytes: &[u8]) -> Option<AudioFormat> {
+    match bytes {
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some(AudioFormat::Wav),
+        [b'I', b'D', b'3', ..] => Some(AudioFormat::Mp3),
+        [0xFF, b, ..] if b & 0xE0 == 0xE0 => Some(AudioFormat::Mp3),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some(AudioFormat::M4a),
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => Some(AudioFormat::Webm),
+        [b'O', b'g', b'g', b'S', ..] => Some(AudioFormat::Ogg),
+        _ => None,
+    }
+}
+
+fn u32_le(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// data chunk size / byte rate of the fmt chunk.
+fn wav_duration(bytes: &[u8]) -> Option<f64> {
+    let mut pos = 12;
+    let mut byte_rate = None;
+    while pos + 8 <= bytes.len() {
+        let size = u32_le(bytes, pos + 4)? as usize;
+        match &bytes[pos..pos + 4] {
+            b"fmt " => byte_rate = u32_le(bytes, pos + 16).filter(|&r| r > 0),
+            b"data" => {
+                // Streamed WAVs may leave the size at its maximum
+                let size = size.min(bytes.len() - pos - 8);
+                return Some(size as f64 / byte_rate? as f64);
+            }
+            _ => {}
+        }
+        pos = pos.checked_add(8 + size + size % 2)?;
+    }
+    None
+}
+
+/// Sum of the frame durations, so VBR files are measured correctly too.
+fn mp3_duration(bytes: &[u8]) -> Option<f64> {
+    let ms = crate::audio::mp3_duration_ms(bytes);
+    (ms > 0).then(|| ms as f64 / 1000.0)
+}
+
+/// duration / timescale of the movie header (`mvhd`).
+fn m4a_duration(bytes: &[u8]) -> Option<f64> {
+    let at = bytes.windows(4).position(|w| w == b"mvhd")? + 4;
+    let (timescale, duration) = match bytes.get(at)? {
+        0 => (u32_be(bytes, at + 12)?, u32_be(bytes, at + 16)? as u64),
+        1 => {
+            let high = u32_be(bytes, at + 24)? as u64;
+            let low = u32_be(bytes, at + 28)? as u64;
+            (u32_be(bytes, at + 20)?, (high << 32) | low)
+        }
+        _ => return None,
+    };
+    (timescale > 0).then(|| duration as f64 / timescale as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(secs: u32) -> Vec<u8> {
+        let byte_rate: u32 = 16_000 * 2;
+        let data_len = byte_rate * secs;
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data_len).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        out.extend_from_slice(&1u16.to_le_bytes()); // mono
+        out.extend_from_slice(&16_000u32.to_le_bytes());
+        out.extend_from_slice(&byte_rate.to_le_bytes());
+        out.extend_from_slice(&2u16.to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_len.to_le_bytes());
+        out.resize(out.len() + data_len as usize, 0);
+        out
+    }
+
+    fn b64(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn rejected_field(result: Result<RefAudio, ApiError>) -> String {
+        match result {
+            Err(ApiError::Validation { field, .. }) => field,
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn accepts_short_clips_and_reads_their_duration() {
+        let audio = validate(&b64(&wav(5))).unwrap();
+        assert_eq!(audio.format, AudioFormat::Wav);
+        assert_eq!(audio.duration_secs, Some(5.0));
+
+        // 383 frames of 128 kbps MPEG-1 Layer III at 44.1 kHz, 10 s of audio
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0x64];
+        frame.resize(417, 0);
+        let mp3 = frame.repeat(383);
+        let audio = validate(&b64(&mp3)).unwrap();
+        assert_eq!(audio.format, AudioFormat::Mp3);
+        assert!((audio.duration_secs.unwrap() - 10.0).abs() < 0.01);
+
+        // An ID3v2.4 tag with a footer ahead of the first frame
+        let mut tagged = b"ID3\x04\x00\x10\x00\x00\x00\x05".to_vec();
+        tagged.extend_from_slice(&[0; 5]);
+        tagged.extend_from_slice(b"3DI\x04\x00\x10\x00\x00\x00\x05");
+        tagged.extend_from_slice(&mp3);
+        assert!((validate(&b64(&tagged)).unwrap().duration_secs.unwrap() - 10.0).abs() < 0.01);
+
+        let webm = [0x1A, 0x45, 0xDF, 0xA3, 0, 0, 0, 0];
+        assert_eq!(validate(&b64(&webm)).unwrap().duration_secs, None);
+    }
+
+    #[test]
+    fn rejects_malformed_or_long_audio() {
+        assert_eq!(rejected_field(validate("")), "ref_audio");
+        assert_eq!(rejected_field(validate("not base64!")), "ref_audio");
+        // Missing padding
+        assert_eq!(rejected_field(validate(b64(&wav(1)).trim_end_matches('='))), "ref_audio");
+        assert_eq!(rejected_field(validate(&b64(b"<html>not audio</html>"))), "ref_audio");
+        assert_eq!(rejected_field(validate(&b64(&wav(61)))), "ref_audio");
+    }
+
+    #[test]
+    fn m4a_duration_comes_from_mvhd() {
+        let mut m4a = b"\0\0\0\x18ftypM4A \0\0\0\0".to_vec();
+        m4a.extend_from_slice(b"\0\0\0\x6cmoov\0\0\0\x64mvhd");
+        m4a.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        m4a.extend_from_slice(&1000u32.to_be_bytes());
+        m4a.extend_from_slice(&90_000u32.to_be_bytes());
+        assert_eq!(m4a_duration(&m4a), Some(90.0));
+        assert_eq!(rejected_field(validate(&b64(&m4a))), "ref_audio");
+    }
+}
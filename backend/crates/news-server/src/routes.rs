@@ -1,18 +1,40 @@
-use crate::claude;
-use crate::db::Db;
+use crate::article_export::{self, ExportBundle, ExportEnrichment, ExportFormat};
+use crate::article_import::{self, ImportArticlesRequest};
+use crate::audio;
+use crate::bootstrap::{self, ArticlesSection, BootstrapArticle, CategoryEntry, ClientConfig, ClientFeatures, Section, TrialUsage, UsageSummary};
+use crate::change_validation;
+use crate::claude::{self, ModelTier};
+use crate::db::{ArticleFilter, ChatSession, Db, Ranking, ReanalyzeFilter, KEYWORDS_CORPUS_SIZE};
+use crate::enrichment_agent;
+use crate::json_feed;
+use crate::error::ApiError;
+use crate::metrics::Metrics;
+use crate::reading;
 use crate::stripe;
+use crate::tts_chunk;
 use axum::extract::{Path, Query, State};
-use axum::http::{header, HeaderMap, StatusCode};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
-use news_core::changes::{AdminAction, ChangeRequest, ChangeStatus};
-use news_core::config::DynamicFeed;
-use news_core::grouping;
-use news_core::models::{ArticlesResponse, Category, CategoryInfo};
+use futures::StreamExt;
+use news_core::changes::{
+    apply_actions, diff_categories, diff_service_configs, preview_diff, recheck_preview,
+    AdminAction, ChangeRequest, ChangeStatus,
+};
+use news_core::config::{AbVariant, CategoryOverride, DynamicFeed, ImageDegradationPolicy};
+use news_core::balance;
+use news_core::enrichment::EnrichmentPayload;
+use news_core::mute::{MuteField, MuteRule};
+use news_core::pagination::{self, Page, Paginator};
+use news_core::models::{
+    Article, ArticlesResponse, Category, CategoryInfo, SourceMeta, MAX_CREDIBILITY_TIER, SOURCE_TYPES,
+};
+use news_core::sites::{normalize_host, SiteMeta, SitesConfig};
+use news_core::text::truncate_sentence;
 use axum::body::Body;
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 use tracing::{info, warn};
 
@@ -45,10 +67,89 @@ pub struct AppState {
     pub admin_secret: String,
     pub base_url: String,
     pub google_client_id: String,
+    pub metrics: Arc<Metrics>,
+    pub database_path: String,
+    pub static_dir: String,
+    pub start_time: std::time::Instant,
+    /// Incremental article grouping, shared with the fetcher.
+    pub group_states: Arc<crate::fetcher::GroupStates>,
+    /// Client for publisher pages (robots.txt, per-host pacing and back-off).
+    pub polite: Arc<news_core::polite::PoliteFetcher>,
+    /// Claude API client with per-model health tracking and Sonnet → Haiku fallback.
+    pub claude: claude::ClaudeClient,
+    /// Live CORS allowlist; reload after changing sites or the `cors_origins` feature.
+    pub cors: Arc<crate::cors::CorsAllowlist>,
+    pub tasks: Arc<crate::supervisor::Supervisor>,
+    pub token_cache: crate::token_cache::TokenCache,
+    /// Feature flags for per-request reads; invalidate after changing a flag.
+    pub flag_cache: crate::flag_cache::FlagCache,
+    /// A/B assignments already stored, so repeat lookups don't write.
+    pub ab_assignments: crate::ab_assignments::RecordedAssignments,
+    /// Font for `GET /og/:id.png` share cards; None disables them.
+    pub og_font: Option<Arc<fontdue::Font>>,
+    /// Cache keys of murmurs being generated (see `MurmurClaim`).
+    pub murmurs_in_flight: Arc<std::sync::Mutex<std::collections::HashSet<String>>>,
+    /// Article IDs for the enrichment agent, sent when views cross
+    /// `ENRICHMENT_VIEW_THRESHOLD`.
+    pub enrichment_tx: tokio::sync::mpsc::Sender<String>,
+}
+
+#[cfg(test)]
+impl AppState {
+    /// State for handler tests: an in-memory database, placeholder Anthropic key and
+    /// admin secret, no other provider keys, and Claude requests sent to
+    /// `claude_base_url` (a local mock) so nothing reaches the network.
+    pub fn for_tests(claude_base_url: &str) -> Self {
+        let db = Arc::new(Db::open(":memory:").expect("in-memory database"));
+        let http_client = reqwest::Client::new();
+        let metrics = Arc::new(crate::metrics::Metrics::new());
+        Self {
+            db,
+            http_client: http_client.clone(),
+            api_key: "test-key".into(),
+            elevenlabs_api_key: String::new(),
+            openai_api_key: String::new(),
+            cartesia_api_key: String::new(),
+            fish_audio_api_key: String::new(),
+            aimlapi_key: String::new(),
+            venice_api_key: String::new(),
+            runpod_api_key: String::new(),
+            runpod_client: http_client.clone(),
+            cosyvoice_endpoint_id: String::new(),
+            qwen_tts_endpoint_id: String::new(),
+            qwen_omni_endpoint_id: String::new(),
+            stripe_secret_key: String::new(),
+            stripe_webhook_secret: String::new(),
+            stripe_price_id: String::new(),
+            admin_secret: "test-admin-secret".into(),
+            base_url: "https://news.xyz".into(),
+            google_client_id: String::new(),
+            metrics: Arc::clone(&metrics),
+            database_path: ":memory:".into(),
+            static_dir: String::new(),
+            start_time: std::time::Instant::now(),
+            group_states: Arc::default(),
+            polite: Arc::new(news_core::polite::PoliteFetcher::new(http_client)),
+            claude: claude::ClaudeClient::with_endpoint(claude_base_url.to_string(), Duration::from_millis(1)),
+            cors: Arc::new(crate::cors::CorsAllowlist::from_env()),
+            tasks: Arc::new(crate::supervisor::Supervisor::new(metrics)),
+            token_cache: crate::token_cache::TokenCache::new(
+                crate::token_cache::TOKEN_CACHE_TTL,
+                crate::token_cache::TOKEN_CACHE_CAPACITY,
+            ),
+            flag_cache: crate::flag_cache::FlagCache::new(crate::flag_cache::FLAG_CACHE_TTL),
+            ab_assignments: crate::ab_assignments::RecordedAssignments::new(
+                crate::ab_assignments::AB_RECORDED_CAPACITY,
+            ),
+            og_font: None,
+            murmurs_in_flight: Arc::default(),
+            enrichment_tx: tokio::sync::mpsc::channel(1).0,
+        }
+    }
 }
 
-/// Check admin auth. Returns error response if unauthorized.
-fn check_admin_auth(headers: &HeaderMap, state: &AppState) -> Result<(), Response> {
+/// Check admin auth.
+fn check_admin_auth(headers: &HeaderMap, state: &AppState) -> Result<(), ApiError> {
     if state.admin_secret.is_empty() {
         // No secret configured = open (dev mode)
         return Ok(());
@@ -60,82 +161,161 @@ fn check_admin_auth(headers: &HeaderMap, state: &AppState) -> Result<(), Respons
     if provided == state.admin_secret {
         Ok(())
     } else {
-        Err((
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({"error": "管理者認証が必要です"})),
-        )
-            .into_response())
+        Err(ApiError::Unauthorized("管理者認証が必要です".into()))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum UserTier {
-    Anonymous,
-    Free { device_id: String },
-    Authenticated { device_id: String, user_id: String },
+    /// No device ID or login; metered by a salted hash of the client IP.
+    Anonymous { trial_key: Arc<str> },
+    Free { device_id: Arc<str> },
+    Authenticated { device_id: Arc<str>, user_id: Arc<str> },
     Pro,
 }
 
-fn extract_user_tier(headers: &HeaderMap, db: &Db) -> UserTier {
+fn extract_user_tier(headers: &HeaderMap, state: &AppState) -> UserTier {
+    let header_device_id = || {
+        headers
+            .get("x-device-id")
+            .and_then(|v| v.to_str().ok())
+            .filter(|id| !id.is_empty())
+    };
     // Check for Bearer token first (Pro or Google auth)
-    if let Some(auth) = headers.get("authorization") {
-        if let Ok(val) = auth.to_str() {
-            if let Some(token) = val.strip_prefix("Bearer ") {
-                // Check Pro (Stripe) token
-                if let Ok(Some((_, _, status, period_end))) = db.get_subscription_by_token(token) {
-                    if status == "active" {
-                        if let Ok(end) = period_end.parse::<chrono::DateTime<chrono::Utc>>() {
-                            if end > chrono::Utc::now() {
-                                return UserTier::Pro;
-                            }
-                        }
-                    }
-                }
-                // Check Google auth token
-                if let Ok(Some((user_id, _, _, _, device_id_opt, _))) =
-                    db.get_user_by_auth_token(token)
-                {
-                    let device_id = device_id_opt
-                        .or_else(|| {
-                            headers
-                                .get("x-device-id")
-                                .and_then(|v| v.to_str().ok())
-                                .map(|s| s.to_string())
-                        })
-                        .unwrap_or_default();
-                    return UserTier::Authenticated { device_id, user_id };
+    let token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if let Some(token) = token {
+        let resolved = match state.token_cache.get(token) {
+            Some(tier) => Some(tier),
+            None => {
+                let resolved = resolve_bearer_token(&state.db, token);
+                if let Some(tier) = &resolved {
+                    state.token_cache.insert(token, tier.clone());
                 }
+                resolved
             }
+        };
+        match resolved {
+            // A user without a stored device ID is metered by the header's
+            Some(UserTier::Authenticated { device_id, user_id }) if device_id.is_empty() => {
+                return UserTier::Authenticated {
+                    device_id: header_device_id().unwrap_or_default().into(),
+                    user_id,
+                };
+            }
+            Some(tier) => return tier,
+            None => {}
         }
     }
 
     // Check for device ID
-    if let Some(device_id) = headers.get("x-device-id") {
-        if let Ok(id) = device_id.to_str() {
-            if !id.is_empty() {
-                return UserTier::Free {
-                    device_id: id.to_string(),
-                };
-            }
+    if let Some(id) = header_device_id() {
+        return UserTier::Free { device_id: id.into() };
+    }
+
+    UserTier::Anonymous {
+        trial_key: anonymous_trial_key(headers).into(),
+    }
+}
+
+/// Pro for an active, unexpired Stripe subscription token, else the Google user the
+/// token belongs to (expired tokens are not returned); `device_id` is empty when the
+/// user has none stored.
+fn resolve_bearer_token(db: &Db, token: &str) -> Option<UserTier> {
+    if let Ok(Some((_, _, status, period_end))) = db.get_subscription_by_token(token) {
+        let unexpired = period_end
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .is_ok_and(|end| end > chrono::Utc::now());
+        if status == "active" && unexpired {
+            return Some(UserTier::Pro);
         }
     }
+    match db.get_user_by_auth_token(token) {
+        Ok(Some((user_id, _, _, _, device_id, _))) => Some(UserTier::Authenticated {
+            device_id: device_id.unwrap_or_default().into(),
+            user_id: user_id.into(),
+        }),
+        _ => None,
+    }
+}
+
+/// The caller's variant of prompt experiment `test`, recorded for analysis the first
+/// time it's seen. None when the test isn't running or the caller has no device ID
+/// (anonymous visitors).
+fn ab_variant(state: &AppState, tier: &UserTier, test: &str) -> Option<AbVariant> {
+    let device_id = match tier {
+        UserTier::Free { device_id } | UserTier::Authenticated { device_id, .. } if !device_id.is_empty() => device_id,
+        _ => return None,
+    };
+    let flags = state.flag_cache.get(&state.db);
+    let variant = flags.ab_variant(test, device_id)?.clone();
+    if let Err(e) = state.ab_assignments.record(&state.db, device_id, test, &variant.name) {
+        warn!(error = %e, test, "Failed to record A/B assignment");
+    }
+    Some(variant)
+}
+
+/// Cache-key suffix separating a variant's responses from the default prompt's.
+/// Control variants share the default cache.
+fn ab_cache_suffix(variant: Option<&AbVariant>) -> String {
+    match variant {
+        Some(v) if v.prompt().is_some() => format!("|ab:{}", v.name),
+        _ => String::new(),
+    }
+}
+
+/// Uses per day an anonymous visitor gets, shared across every AI feature.
+const ANON_TRIAL_DAILY_LIMIT: i64 = 3;
+/// usage_limits feature name for the shared anonymous allowance.
+const ANON_TRIAL_FEATURE: &str = "anon_trial";
+
+/// Salt for anonymous trial keys, from `ANON_TRIAL_SALT` (change it to rotate every
+/// key). Without it a random salt is used, so trials reset on restart.
+static ANON_TRIAL_SALT: LazyLock<String> = LazyLock::new(|| {
+    std::env::var("ANON_TRIAL_SALT")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| {
+            warn!("ANON_TRIAL_SALT not set; anonymous trials reset on restart");
+            uuid::Uuid::new_v4().to_string()
+        })
+});
 
-    UserTier::Anonymous
+/// Stable per-IP usage key that doesn't store the IP itself.
+fn anonymous_trial_key(headers: &HeaderMap) -> String {
+    format!("anon:{}", cache_key(&ANON_TRIAL_SALT, crate::client_ip::header_ip(headers)))
 }
 
 struct FeatureLimit {
     name: &'static str,
     daily_limit: i64,
+    /// Limit after Google login; None = twice the free limit.
+    authenticated_limit: Option<i64>,
+}
+
+impl FeatureLimit {
+    fn authenticated(&self) -> i64 {
+        self.authenticated_limit.unwrap_or(self.daily_limit * 2)
+    }
 }
 
 const FEATURE_LIMITS: &[FeatureLimit] = &[
-    FeatureLimit { name: "summarize", daily_limit: 20 },
-    FeatureLimit { name: "questions", daily_limit: 20 },
-    FeatureLimit { name: "ask", daily_limit: 20 },
-    FeatureLimit { name: "tts", daily_limit: 30 },
-    FeatureLimit { name: "to_reading", daily_limit: 30 },
-    FeatureLimit { name: "podcast", daily_limit: 10 },
-    FeatureLimit { name: "murmur", daily_limit: 50 },
+    FeatureLimit { name: "summarize", daily_limit: 20, authenticated_limit: None },
+    FeatureLimit { name: "questions", daily_limit: 20, authenticated_limit: None },
+    FeatureLimit { name: "ask", daily_limit: 20, authenticated_limit: None },
+    FeatureLimit { name: "tts", daily_limit: 30, authenticated_limit: None },
+    FeatureLimit { name: "tts_preload", daily_limit: 5, authenticated_limit: None },
+    FeatureLimit { name: "to_reading", daily_limit: 30, authenticated_limit: None },
+    FeatureLimit { name: "podcast", daily_limit: 10, authenticated_limit: None },
+    FeatureLimit { name: "murmur", daily_limit: 50, authenticated_limit: None },
+    FeatureLimit { name: "chat", daily_limit: 30, authenticated_limit: Some(100) },
+    FeatureLimit { name: "translate", daily_limit: 20, authenticated_limit: None },
+    FeatureLimit { name: "sentiment", daily_limit: 15, authenticated_limit: None },
+    FeatureLimit { name: "compare", daily_limit: 10, authenticated_limit: Some(50) },
+    FeatureLimit { name: "reading_mode", daily_limit: 50, authenticated_limit: Some(200) },
+    FeatureLimit { name: "tts_preview", daily_limit: 10, authenticated_limit: None },
 ];
 
 fn get_daily_limit(feature: &str) -> i64 {
@@ -146,31 +326,32 @@ fn get_daily_limit(feature: &str) -> i64 {
         .unwrap_or(5)
 }
 
+fn get_authenticated_limit(feature: &str) -> i64 {
+    FEATURE_LIMITS
+        .iter()
+        .find(|f| f.name == feature)
+        .map(|f| f.authenticated())
+        .unwrap_or(10)
+}
+
 fn check_rate_limit(
     db: &Db,
     tier: &UserTier,
     feature: &str,
-) -> Result<(), Response> {
+) -> Result<(), ApiError> {
     match tier {
         UserTier::Pro => Ok(()),
         UserTier::Authenticated { device_id, .. } => {
-            let base_limit = get_daily_limit(feature);
-            let limit = base_limit * 2;
+            let limit = get_authenticated_limit(feature);
             let used = db.get_usage(device_id, feature).unwrap_or(0);
             if used >= limit {
-                Err((
-                    StatusCode::PAYMENT_REQUIRED,
-                    Json(serde_json::json!({
-                        "error": "rate_limit_exceeded",
-                        "message": format!("本日の利用回数（{}回）に達しました。Proプラン（¥500/月）で無制限にご利用いただけます。", limit),
-                        "feature": feature,
-                        "limit": limit,
-                        "used": used,
-                        "tier": "authenticated",
-                        "upgrade_url": "/pro"
-                    })),
-                )
-                    .into_response())
+                Err(ApiError::RateLimited {
+                    feature: feature.to_string(),
+                    limit,
+                    used,
+                    tier: "authenticated",
+                    message: format!("本日の利用回数（{}回）に達しました。Proプラン（¥500/月）で無制限にご利用いただけます。", limit),
+                })
             } else {
                 Ok(())
             }
@@ -179,46 +360,75 @@ fn check_rate_limit(
             let limit = get_daily_limit(feature);
             let used = db.get_usage(device_id, feature).unwrap_or(0);
             if used >= limit {
-                Err((
-                    StatusCode::PAYMENT_REQUIRED,
-                    Json(serde_json::json!({
-                        "error": "rate_limit_exceeded",
-                        "message": format!("本日の利用回数（{}回）に達しました。Googleログインで制限が2倍に！", limit),
-                        "feature": feature,
-                        "limit": limit,
-                        "used": used,
-                        "tier": "free",
-                        "upgrade_url": "/pro"
-                    })),
-                )
-                    .into_response())
+                Err(ApiError::RateLimited {
+                    feature: feature.to_string(),
+                    limit,
+                    used,
+                    tier: "free",
+                    message: format!("本日の利用回数（{}回）に達しました。Googleログインで制限が2倍に！", limit),
+                })
             } else {
                 Ok(())
             }
         }
-        UserTier::Anonymous => {
-            Err((
-                StatusCode::PAYMENT_REQUIRED,
-                Json(serde_json::json!({
-                    "error": "device_id_required",
-                    "message": "AI機能を利用するにはデバイスIDが必要です。",
-                    "tier": "anonymous"
-                })),
-            )
-                .into_response())
+        UserTier::Anonymous { trial_key } => {
+            let used = db.get_usage(trial_key, ANON_TRIAL_FEATURE).unwrap_or(0);
+            if used >= ANON_TRIAL_DAILY_LIMIT {
+                Err(ApiError::TrialExhausted { limit: ANON_TRIAL_DAILY_LIMIT })
+            } else {
+                Ok(())
+            }
         }
     }
 }
 
+/// Uses of `feature` left today; None for Pro (unlimited).
+fn remaining_usage(db: &Db, tier: &UserTier, feature: &str) -> Option<i64> {
+    let (device_id, feature, limit) = match tier {
+        UserTier::Pro => return None,
+        UserTier::Authenticated { device_id, .. } => (device_id, feature, get_authenticated_limit(feature)),
+        UserTier::Free { device_id } => (device_id, feature, get_daily_limit(feature)),
+        UserTier::Anonymous { trial_key } => (trial_key, ANON_TRIAL_FEATURE, ANON_TRIAL_DAILY_LIMIT),
+    };
+    Some((limit - db.get_usage(device_id, feature).unwrap_or(0)).max(0))
+}
+
 fn increment_usage_if_needed(db: &Db, tier: &UserTier, feature: &str) {
     match tier {
         UserTier::Free { device_id } | UserTier::Authenticated { device_id, .. } => {
             let _ = db.increment_usage(device_id, feature);
         }
-        _ => {}
+        UserTier::Anonymous { trial_key } => {
+            let _ = db.increment_usage(trial_key, ANON_TRIAL_FEATURE);
+        }
+        UserTier::Pro => {}
     }
 }
 
+/// Middleware for the rate-limited AI routes: adds `x-trial-remaining` to anonymous
+/// responses that used a trial or were refused one.
+pub async fn anonymous_trial_header(
+    State(state): State<Arc<AppState>>,
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let identified = ["authorization", "x-device-id"]
+        .iter()
+        .any(|h| req.headers().get(*h).is_some_and(|v| !v.is_empty()));
+    if identified {
+        return next.run(req).await;
+    }
+    let trial_key = anonymous_trial_key(req.headers());
+    let used_before = state.db.get_usage(&trial_key, ANON_TRIAL_FEATURE).unwrap_or(0);
+    let mut res = next.run(req).await;
+    let used = state.db.get_usage(&trial_key, ANON_TRIAL_FEATURE).unwrap_or(0);
+    if used != used_before || res.status() == StatusCode::PAYMENT_REQUIRED {
+        let remaining = (ANON_TRIAL_DAILY_LIMIT - used).max(0);
+        res.headers_mut().insert("x-trial-remaining", HeaderValue::from(remaining));
+    }
+    res
+}
+
 #[derive(Deserialize)]
 pub struct ArticlesQuery {
     pub category: Option<String>,
@@ -226,8 +436,118 @@ pub struct ArticlesQuery {
     pub cursor: Option<String>,
     /// Freshness filter in minutes (e.g., 10 for articles from last 10 minutes)
     pub freshness: Option<i64>,
+    /// Re-order the page so no single source floods it
+    pub balance_sources: Option<bool>,
+    /// "latest" (default) or "importance"
+    pub sort: Option<String>,
+    /// Comma-separated extras; "translations" adds stored translations of the page.
+    pub include: Option<String>,
+    /// Target language for include=translations (default "ja").
+    pub lang: Option<String>,
+    /// Also return articles whose URL was flagged dead (404/410).
+    pub include_dead: Option<bool>,
+    /// Published on or after this RFC 3339 time or YYYY-MM-DD date (UTC).
+    pub from: Option<String>,
+    /// Published on or before this RFC 3339 time or YYYY-MM-DD date (the whole day).
+    pub to: Option<String>,
+    /// Comma-separated source types to leave out, e.g. "pr,blog".
+    pub exclude_types: Option<String>,
+}
+
+/// Longest `from`..`to` span accepted by `GET /api/articles`.
+const MAX_ARTICLE_RANGE_DAYS: i64 = 31;
+
+/// Parse a `from`/`to` value: RFC 3339, or a UTC date meaning that day's first
+/// (`end_of_day = false`) or last instant.
+fn parse_date_param(field: &str, value: &str, end_of_day: bool) -> Result<chrono::DateTime<chrono::Utc>, ApiError> {
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(t.with_timezone(&chrono::Utc));
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| ApiError::validation(field, format!("{field} must be an RFC 3339 time or YYYY-MM-DD")))?;
+    let time = if end_of_day {
+        chrono::NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999)
+    } else {
+        chrono::NaiveTime::from_hms_opt(0, 0, 0)
+    };
+    Ok(date.and_time(time.unwrap_or_default()).and_utc())
+}
+
+type DateRange = (chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>);
+
+/// The `from`/`to` range of an articles query, if either is given. A missing end
+/// is filled in so the span is the longest allowed.
+fn article_range(
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Option<DateRange>, ApiError> {
+    let from = from.filter(|s| !s.is_empty()).map(|s| parse_date_param("from", s, false)).transpose()?;
+    let to = to.filter(|s| !s.is_empty()).map(|s| parse_date_param("to", s, true)).transpose()?;
+    let max_span = chrono::Duration::days(MAX_ARTICLE_RANGE_DAYS);
+    let (from, to) = match (from, to) {
+        (None, None) => return Ok(None),
+        (Some(from), Some(to)) => (from, to),
+        (Some(from), None) => (from, (from + max_span).min(chrono::Utc::now())),
+        (None, Some(to)) => (to - max_span, to),
+    };
+    if from > to {
+        return Err(ApiError::validation("from", "from must not be after to"));
+    }
+    if to - from > max_span {
+        return Err(ApiError::validation(
+            "to",
+            format!("from..to must span at most {MAX_ARTICLE_RANGE_DAYS} days"),
+        ));
+    }
+    Ok(Some((from, to)))
+}
+
+/// Parse `exclude_types`: comma-separated entries of `SOURCE_TYPES`.
+fn parse_source_types(value: Option<&str>) -> Result<Vec<String>, ApiError> {
+    let mut types = Vec::new();
+    for t in value.unwrap_or_default().split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !SOURCE_TYPES.contains(&t) {
+            return Err(ApiError::validation(
+                "exclude_types",
+                format!("unknown source type {t}; expected one of {}", SOURCE_TYPES.join(", ")),
+            ));
+        }
+        if !types.iter().any(|x| x == t) {
+            types.push(t.to_string());
+        }
+    }
+    Ok(types)
+}
+
+/// Set `source_meta` on a serialized article, falling back to type "unknown" for
+/// sources without metadata.
+fn attach_source_meta(article: &mut serde_json::Value, meta: &std::collections::HashMap<String, SourceMeta>) {
+    let Some(source) = article.get("source").and_then(|s| s.as_str()) else {
+        return;
+    };
+    let value = match meta.get(source) {
+        Some(m) => serde_json::to_value(m),
+        None => serde_json::to_value(SourceMeta::unknown(source)),
+    };
+    article["source_meta"] = value.unwrap_or_default();
+}
+
+#[derive(Deserialize)]
+pub struct TrendingQuery {
+    pub category: Option<String>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    /// Look-back window in hours (default 24, max 168)
+    pub hours: Option<i64>,
+    /// "popularity" (default) or "importance"
+    pub sort: Option<String>,
 }
 
+/// `balance_sources=true`: at most this many articles per source...
+const BALANCE_MAX_PER_SOURCE: usize = 3;
+/// ...within this many consecutive results.
+const BALANCE_WINDOW: usize = 30;
+
 #[derive(Deserialize)]
 pub struct CommandRequest {
     pub command: String,
@@ -236,119 +556,296 @@ pub struct CommandRequest {
 #[derive(Deserialize)]
 pub struct SummarizeRequest {
     pub minutes: u32,
+    /// "by_category" for a per-category briefing; anything else is the flat summary.
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// Categories for the briefing (empty = all except podcast).
+    #[serde(default)]
+    pub categories: Vec<String>,
 }
 
 #[derive(Deserialize)]
 pub struct ToggleFeatureRequest {
     pub feature: String,
     pub enabled: bool,
+    /// Feature settings stored as extra_json (e.g. weights for "importance_weights").
+    #[serde(default)]
+    pub extra: Option<serde_json::Value>,
 }
 
 // --- Public API ---
 
 pub async fn get_articles(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Query(params): Query<ArticlesQuery>,
-) -> Response {
-    let category = params.category.as_deref().and_then(Category::from_str);
-    let limit = params.limit.unwrap_or(30).min(100).max(1);
+) -> Result<Response, ApiError> {
+    let category = category_filter(&state.db, params.category.as_deref())?;
+    let limit = Paginator::new(params.limit.unwrap_or(30), None).limit;
+    let mutes = caller_mute_rules(&headers, &state);
+    let fetch_limit = if mutes.is_empty() { limit } else { limit * MUTE_OVERFETCH };
+    let page = Paginator::uncapped(fetch_limit, params.cursor.clone());
+    let grouping_enabled = state.db.get_feature_flags().is_ok_and(|f| f.grouping_enabled);
+    let include_dead = params.include_dead.unwrap_or(false);
+    let range = article_range(params.from.as_deref(), params.to.as_deref())?;
+    if range.is_some() && (params.freshness.is_some() || params.sort.as_deref() == Some("importance")) {
+        return Err(ApiError::validation("from", "from/to cannot be combined with freshness or sort=importance"));
+    }
+    let filter = ArticleFilter {
+        range,
+        exclude_types: parse_source_types(params.exclude_types.as_deref())?,
+    };
+    let source_meta = state.db.source_meta_map().unwrap_or_default();
+    let credibility = |a: &Article| source_meta.get(&a.source).map_or(MAX_CREDIBILITY_TIER + 1, |m| m.credibility_rank());
 
     // Check if freshness filter is requested (e.g., ?freshness=10 for 10 minutes)
-    let result = if let Some(minutes) = params.freshness {
-        state
+    let (result, resume) = if params.sort.as_deref() == Some("importance") {
+        let since = params.freshness.map(|m| chrono::Utc::now() - chrono::Duration::minutes(m));
+        let result = state.db.query_ranked_articles(
+            category.as_ref(),
+            since.as_ref(),
+            &importance_ranking(&state.db),
+            &page,
+            include_dead,
+            &filter.exclude_types,
+        );
+        (result, MuteResume::Offset(page.offset()))
+    } else if !matches!(params.sort.as_deref(), None | Some("latest")) {
+        return Err(ApiError::validation("sort", "sort must be latest or importance"));
+    } else if let Some(minutes) = params.freshness {
+        let result = state
             .db
-            .get_fresh_articles(category.as_ref(), minutes, limit)
-            .map(|articles| (articles, None))
+            .get_fresh_articles(category.as_ref(), minutes, fetch_limit, include_dead, &filter.exclude_types)
+            .map(Page::complete);
+        (result, MuteResume::Unpaged)
+    } else if let (Some((from, to)), true) = (&filter.range, filter.exclude_types.is_empty()) {
+        let result = state.db.query_articles_between(category.as_ref(), (from, to), &page, grouping_enabled, include_dead);
+        (result, MuteResume::Keyset)
     } else {
-        state
-            .db
-            .query_articles(category.as_ref(), limit, params.cursor.as_deref())
+        let result = state.db.query_filtered_articles(
+            category.as_ref(),
+            &filter,
+            &page,
+            grouping_enabled,
+            include_dead,
+        );
+        (result, MuteResume::Keyset)
     };
 
     match result {
-        Ok((mut articles, next_cursor)) => {
-            // Apply grouping if feature is enabled
-            if let Ok(flags) = state.db.get_feature_flags() {
-                if flags.grouping_enabled && articles.len() > 1 {
-                    let titles: Vec<&str> =
-                        articles.iter().map(|a| a.title.as_str()).collect();
-                    let groups =
-                        grouping::group_articles(&titles, flags.grouping_threshold);
-
-                    for group in &groups {
-                        if group.len() > 1 {
-                            let group_id = uuid::Uuid::new_v4().to_string();
-                            let count = group.len() as u32;
-                            for (i, &idx) in group.iter().enumerate() {
-                                articles[idx].group_id = Some(group_id.clone());
-                                if i == 0 {
-                                    articles[idx].group_count = Some(count);
-                                }
-                            }
+        Ok(Page { items: articles, next_cursor, .. }) => {
+            let (mut articles, next_cursor, muted) = apply_mutes(articles, next_cursor, &mutes, limit, resume);
+
+            // Groups are stored by the fetcher; the other sorts collapse within the page,
+            // keeping each group's most credible member (the first one on ties)
+            if grouping_enabled {
+                let mut best = std::collections::HashMap::new();
+                for (i, a) in articles.iter().enumerate() {
+                    if let Some(g) = &a.group_id {
+                        let current = best.entry(g.clone()).or_insert(i);
+                        if credibility(a) < credibility(&articles[*current]) {
+                            *current = i;
                         }
                     }
-
-                    let keep_indices: std::collections::HashSet<usize> = groups
-                        .iter()
-                        .flat_map(|g| {
-                            if g.len() > 1 {
-                                vec![g[0]]
-                            } else {
-                                g.clone()
-                            }
-                        })
-                        .collect();
-
-                    let filtered: Vec<_> = articles
-                        .into_iter()
-                        .enumerate()
-                        .filter(|(i, _)| keep_indices.contains(i))
-                        .map(|(_, a)| a)
-                        .collect();
-                    articles = filtered;
                 }
+                let mut i = 0;
+                articles.retain(|a| {
+                    let keep = a.group_id.as_ref().is_none_or(|g| best[g] == i);
+                    i += 1;
+                    keep
+                });
+            }
+
+            // Re-order within the page only; next_cursor still comes from the DB query
+            if params.balance_sources.unwrap_or(false) {
+                articles = balance_page(articles);
             }
 
+            let include_translations = params
+                .include
+                .as_deref()
+                .is_some_and(|inc| inc.split(',').any(|i| i.trim() == "translations"));
+            let translations = if include_translations {
+                let lang = params.lang.as_deref().unwrap_or(DEFAULT_TRANSLATE_LANG);
+                let ids: Vec<&str> = articles.iter().map(|a| a.id.as_str()).collect();
+                Some(state.db.get_translations(&ids, lang).unwrap_or_default())
+            } else {
+                None
+            };
+
             let body = ArticlesResponse {
                 articles,
                 next_cursor,
             };
-            (
+            let mut json = serde_json::to_value(&body).unwrap_or_default();
+            if let Some(items) = json["articles"].as_array_mut() {
+                for item in items {
+                    attach_source_meta(item, &source_meta);
+                }
+            }
+            if !mutes.is_empty() {
+                json["muted_count"] = muted.into();
+            }
+            if let Some(translations) = translations {
+                let map: serde_json::Map<String, serde_json::Value> = translations
+                    .into_iter()
+                    .map(|(id, (title, description))| {
+                        (id, serde_json::json!({ "title": title, "description": description }))
+                    })
+                    .collect();
+                json["translations"] = serde_json::Value::Object(map);
+            }
+            Ok((
                 StatusCode::OK,
                 [
-                    (header::CACHE_CONTROL, "public, max-age=120"),
+                    (header::CACHE_CONTROL, if mutes.is_empty() { "public, max-age=120" } else { "private, max-age=120" }),
+                    // Mute rules come from these, so a shared cache must key on them
+                    (header::VARY, "Authorization, X-Device-Id"),
                     (header::CONTENT_TYPE, "application/json; charset=utf-8"),
                 ],
-                Json(body),
+                Json(json),
             )
-                .into_response()
+                .into_response())
         }
         Err(e) => {
             tracing::error!(error = %e, "Failed to query articles");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Internal server error"})),
-            )
-                .into_response()
+            Err(ApiError::Internal("Internal server error".into()))
+        }
+    }
+}
+
+/// Re-order a page so no single source floods it (see `balance::balance_order`).
+fn balance_page(articles: Vec<Article>) -> Vec<Article> {
+    if articles.len() <= BALANCE_MAX_PER_SOURCE {
+        return articles;
+    }
+    let sources: Vec<&str> = articles.iter().map(|a| a.source.as_str()).collect();
+    let order = balance::balance_order(&sources, BALANCE_MAX_PER_SOURCE, BALANCE_WINDOW);
+    let mut slots: Vec<Option<Article>> = articles.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+#[derive(Deserialize)]
+pub struct CalendarQuery {
+    pub year: Option<i32>,
+    pub month: Option<u32>,
+    pub category: Option<String>,
+}
+
+/// GET /api/articles/calendar?year=2025&month=01&category= — article counts for every
+/// UTC day of the month, for the archive calendar.
+pub async fn get_article_calendar(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<CalendarQuery>,
+) -> Result<Response, ApiError> {
+    use chrono::Datelike;
+    let today = chrono::Utc::now().date_naive();
+    let year = params.year.unwrap_or(today.year());
+    if !(2000..=2100).contains(&year) {
+        return Err(ApiError::validation("year", "year must be between 2000 and 2100"));
+    }
+    let month = params.month.unwrap_or(today.month());
+    let first = chrono::NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(|| ApiError::validation("month", "month must be between 1 and 12"))?;
+    let category = match params.category.as_deref().filter(|c| !c.is_empty()) {
+        Some(c) => Some(lookup_category(&state.db, c).ok_or_else(|| ApiError::validation("category", "unknown category"))?),
+        None => None,
+    };
+
+    let ckey = cache_key(
+        "calendar",
+        &format!("{year}-{month:02}:{}", category.as_ref().map_or("", |c| c.as_str())),
+    );
+    if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
+        if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
+
+    let next = first.checked_add_months(chrono::Months::new(1)).unwrap_or(first);
+    let counts: std::collections::HashMap<String, i64> = state
+        .db
+        .count_articles_by_day(
+            &first.and_time(chrono::NaiveTime::MIN).and_utc(),
+            &next.and_time(chrono::NaiveTime::MIN).and_utc(),
+            category.as_ref(),
+        )?
+        .into_iter()
+        .collect();
+    let days: Vec<serde_json::Value> = first
+        .iter_days()
+        .take_while(|d| *d < next)
+        .map(|d| {
+            let date = d.format("%Y-%m-%d").to_string();
+            let count = counts.get(&date).copied().unwrap_or(0);
+            serde_json::json!({"date": date, "count": count})
+        })
+        .collect();
+
+    let result = serde_json::json!({
+        "year": year,
+        "month": month,
+        "category": category.as_ref().map(|c| c.as_str()),
+        "total": counts.values().sum::<i64>(),
+        "days": days,
+    });
+    let _ = state.db.set_cache(&ckey, "calendar", &result.to_string(), 3600);
+    Ok((StatusCode::OK, Json(result)).into_response())
+}
+
+/// Importance ranking with the weights from the "importance_weights" feature flag.
+fn importance_ranking(db: &Db) -> Ranking {
+    Ranking::Importance(db.get_feature_flags().map(|f| f.importance_weights()).unwrap_or_default())
+}
+
+/// GET /api/articles/trending — articles from the last `hours`, by popularity or importance.
+pub async fn get_trending_articles(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<TrendingQuery>,
+) -> Result<Response, ApiError> {
+    let category = category_filter(&state.db, params.category.as_deref())?;
+    let page = Paginator::new(params.limit.unwrap_or(30), params.cursor.clone());
+    let since = chrono::Utc::now() - chrono::Duration::hours(params.hours.unwrap_or(24).clamp(1, 168));
+    let ranking = match params.sort.as_deref() {
+        None | Some("popularity") => Ranking::Popularity,
+        Some("importance") => importance_ranking(&state.db),
+        Some(_) => return Err(ApiError::validation("sort", "sort must be popularity or importance")),
+    };
+
+    let Page { items: articles, next_cursor, .. } =
+        state.db.query_ranked_articles(category.as_ref(), Some(&since), &ranking, &page, false, &[])?;
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CACHE_CONTROL, "public, max-age=120"),
+            (header::CONTENT_TYPE, "application/json; charset=utf-8"),
+        ],
+        Json(ArticlesResponse {
+            articles,
+            next_cursor,
+        }),
+    )
+        .into_response())
+}
+
+/// Visible categories in display order.
+fn visible_categories(db: &Db) -> Result<Vec<CategoryEntry>, String> {
+    let cats = db.get_categories()?;
+    Ok(cats
+        .into_iter()
+        .filter(|(_, _, _, _, vis)| *vis)
+        .map(|(id, label_ja, label_en, sort_order, _)| CategoryEntry {
+            id,
+            label: if label_en.is_empty() { label_ja.clone() } else { label_en },
+            label_ja,
+            sort_order,
+        })
+        .collect())
 }
 
 pub async fn get_categories(State(state): State<Arc<AppState>>) -> Response {
-    match state.db.get_categories() {
-        Ok(cats) => {
-            let visible: Vec<serde_json::Value> = cats
-                .into_iter()
-                .filter(|(_, _, _, _, vis)| *vis)
-                .map(|(id, label_ja, label_en, sort_order, _)| {
-                    serde_json::json!({
-                        "id": id,
-                        "label": if label_en.is_empty() { label_ja.clone() } else { label_en },
-                        "label_ja": label_ja,
-                        "sort_order": sort_order,
-                    })
-                })
-                .collect();
+    match visible_categories(&state.db) {
+        Ok(visible) => {
             (
                 StatusCode::OK,
                 [
@@ -374,13 +871,19 @@ pub async fn get_categories(State(state): State<Arc<AppState>>) -> Response {
     }
 }
 
+/// Liveness plus the database. Stale critical background tasks report "degraded"
+/// but keep the 200, so the platform check doesn't pull the machine for them.
 pub async fn health(State(state): State<Arc<AppState>>) -> Response {
     match state.db.feed_count() {
-        Ok(count) => (
-            StatusCode::OK,
-            Json(serde_json::json!({"status": "ok", "feeds": count})),
-        )
-            .into_response(),
+        Ok(count) => {
+            let stale = state.tasks.unhealthy_critical();
+            let status = if stale.is_empty() { "ok" } else { "degraded" };
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({"status": status, "feeds": count, "stale_tasks": stale})),
+            )
+                .into_response()
+        }
         Err(_) => (
             StatusCode::SERVICE_UNAVAILABLE,
             Json(serde_json::json!({"status": "degraded", "error": "database unavailable"})),
@@ -389,83 +892,635 @@ pub async fn health(State(state): State<Arc<AppState>>) -> Response {
     }
 }
 
+/// Per-subsystem readiness. 200 while everything is ok or degraded, 503 once any
+/// check errors. `/health` stays as the cheap liveness probe.
+pub async fn health_detailed(State(state): State<Arc<AppState>>) -> Response {
+    let checks = crate::health::run_checks(&state).await;
+    let status = crate::health::overall(&checks);
+    let code = if status == crate::health::Status::Error {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (
+        code,
+        Json(serde_json::json!({
+            "status": status,
+            "checks": checks,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        })),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ArticleLangQuery {
+    pub lang: Option<String>,
+}
+
+/// GET /api/articles/:id?lang=en — with `lang`, the stored translation into it is
+/// returned as `translation` (null when there is none yet; POST /api/articles/translate
+/// creates it).
 pub async fn get_article_by_id(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-) -> Response {
-    match state.db.get_article_by_id(&id) {
-        Ok(Some(article)) => (
-            StatusCode::OK,
-            [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
-            Json(serde_json::json!({"article": article})),
-        )
-            .into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "Article not found"})),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": e.to_string()})),
-        )
-            .into_response(),
-    }
+    Query(params): Query<ArticleLangQuery>,
+) -> Result<Response, ApiError> {
+    let article = state
+        .db
+        .get_article_by_id(&id)?
+        .ok_or_else(|| ApiError::NotFound("Article not found".into()))?;
+    let mut json = serde_json::json!({"article": article});
+    attach_source_meta(&mut json["article"], &state.db.source_meta_map().unwrap_or_default());
+    if let Some(lang) = params.lang.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+        json["translation"] = serde_json::to_value(state.db.get_translation(&id, lang)?).unwrap_or_default();
+    }
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        Json(json),
+    )
+        .into_response())
 }
 
-pub async fn handle_search(
+#[derive(Deserialize)]
+pub struct TimelineQuery {
+    pub days: Option<i64>,
+}
+
+/// GET /api/articles/:id/timeline?days=7 — how a story developed around this article.
+pub async fn get_article_timeline(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<std::collections::HashMap<String, String>>,
-) -> Response {
-    let q = params.get("q").cloned().unwrap_or_default();
-    if q.is_empty() {
-        return (
-            StatusCode::OK,
-            Json(serde_json::json!({"articles": [], "query": ""})),
-        )
-            .into_response();
-    }
-    let limit = params
-        .get("limit")
-        .and_then(|l| l.parse::<i64>().ok())
-        .unwrap_or(20)
-        .min(100)
-        .max(1);
-    match state.db.search_articles(&q, limit) {
-        Ok(articles) => (
-            StatusCode::OK,
-            [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
-            Json(serde_json::json!({"articles": articles, "query": q})),
-        )
-            .into_response(),
-        Err(e) => {
-            tracing::error!(error = %e, "Failed to search articles");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": e.to_string()})),
-            )
-                .into_response()
+    Path(id): Path<String>,
+    Query(params): Query<TimelineQuery>,
+) -> Result<Response, ApiError> {
+    let days = params.days.unwrap_or(7).clamp(1, 30);
+
+    // Cache check (2 h TTL)
+    let ckey = cache_key("timeline", &format!("{}:{}", id, days));
+    if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
+        if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
-}
 
-pub async fn handle_image_proxy(
-    Query(params): Query<std::collections::HashMap<String, String>>,
-) -> Response {
-    let url = match params.get("url") {
-        Some(u) if !u.is_empty() => u.clone(),
-        _ => {
-            return (StatusCode::BAD_REQUEST, "Missing url param").into_response();
-        }
+    let article = state
+        .db
+        .get_article_by_id(&id)?
+        .ok_or_else(|| ApiError::NotFound("Article not found".into()))?;
+    let timeline = state.db.get_related_timeline(&id, days)?;
+    let span_days = match (timeline.first(), timeline.last()) {
+        (Some(first), Some(last)) => (last.published_at - first.published_at).num_days(),
+        _ => 0,
     };
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .unwrap();
+    let result = serde_json::json!({
+        "article": article,
+        "timeline": timeline,
+        "span_days": span_days,
+    });
+    let _ = state.db.set_cache(&ckey, "timeline", &result.to_string(), 7200);
+    Ok((StatusCode::OK, Json(result)).into_response())
+}
 
-    match client.get(&url).send().await {
-        Ok(resp) if resp.status().is_success() => {
+#[derive(Deserialize)]
+pub struct CitedByQuery {
+    pub limit: Option<i64>,
+}
+
+/// GET /api/articles/:id/cited-by?limit=10 — other articles mentioning the companies,
+/// people or events this one does (entities come from the analyzer). `shared_entities`
+/// lists the article's entities found in at least one of them.
+pub async fn get_article_cited_by(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<CitedByQuery>,
+) -> Result<Response, ApiError> {
+    let limit = params.limit.unwrap_or(10).clamp(1, 50);
+    if state.db.get_article_by_id(&id)?.is_none() {
+        return Err(ApiError::NotFound("Article not found".into()));
+    }
+    let entities = state.db.get_article_entities(&id)?;
+    let matches = state.db.find_articles_by_entities(&entities, &id, limit)?;
+
+    let shared: Vec<&String> = entities
+        .iter()
+        .filter(|e| matches.iter().any(|(_, found)| found.iter().any(|f| f.eq_ignore_ascii_case(e))))
+        .collect();
+    let cited_by: Vec<serde_json::Value> = matches
+        .iter()
+        .map(|(article, found)| {
+            let mut json = serde_json::to_value(article).unwrap_or_default();
+            json["shared_entities"] = serde_json::json!(found);
+            json
+        })
+        .collect();
+    Ok(Json(serde_json::json!({ "cited_by": cited_by, "shared_entities": shared })).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct KeywordsQuery {
+    pub min_score: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Keyword {
+    word: String,
+    score: f64,
+}
+
+/// Keywords for an article, sorted by score: the analyzer's (score 1.0) when present,
+/// otherwise the top 10 TF-IDF terms of title + description (cached 1 h).
+fn article_keywords(db: &Db, article: &Article) -> Result<(Vec<Keyword>, &'static str), String> {
+    if let Some(words) = db.get_ai_keywords(&article.id)? {
+        let keywords = words.into_iter().map(|word| Keyword { word, score: 1.0 }).collect();
+        return Ok((keywords, "ai"));
+    }
+
+    let ckey = cache_key("keywords_tfidf", &article.id);
+    if let Ok(Some(cached)) = db.get_cache(&ckey) {
+        if let Ok(keywords) = serde_json::from_str(&cached) {
+            return Ok((keywords, "tfidf"));
+        }
+    }
+    let corpus = db.recent_article_texts(KEYWORDS_CORPUS_SIZE)?;
+    let tfidf = news_core::keywords::TfIdf::new(corpus.iter().map(String::as_str));
+    let text = format!("{} {}", article.title, article.description.as_deref().unwrap_or(""));
+    let keywords: Vec<Keyword> = tfidf
+        .top_terms(&text, 10)
+        .into_iter()
+        .map(|(word, score)| Keyword { word, score })
+        .collect();
+    if let Ok(json) = serde_json::to_string(&keywords) {
+        let _ = db.set_cache(&ckey, "keywords_tfidf", &json, 3600);
+    }
+    Ok((keywords, "tfidf"))
+}
+
+/// GET /api/articles/:id/keywords?min_score=0.5
+pub async fn get_article_keywords(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<KeywordsQuery>,
+) -> Result<Response, ApiError> {
+    let article = state
+        .db
+        .get_article_by_id(&id)?
+        .ok_or_else(|| ApiError::NotFound("Article not found".into()))?;
+    let (mut keywords, source) = article_keywords(&state.db, &article)?;
+    if let Some(min) = params.min_score {
+        keywords.retain(|k| k.score >= min);
+    }
+    Ok(Json(serde_json::json!({"keywords": keywords, "source": source})).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct SimilarBySourceQuery {
+    pub limit: Option<i64>,
+}
+
+/// GET /api/articles/:id/similar-by-source?limit=5 — other articles from the same
+/// source sharing the title's top TF-IDF keywords (cached 1 h).
+pub async fn similar_by_source(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<SimilarBySourceQuery>,
+) -> Result<Response, ApiError> {
+    let limit = params.limit.unwrap_or(5).clamp(1, 20);
+    let ckey = cache_key("similar_by_source", &format!("{id}|{limit}"));
+    if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
+        if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
+            return Ok((StatusCode::OK, Json(val)).into_response());
+        }
+    }
+    let article = state
+        .db
+        .get_article_by_id(&id)?
+        .ok_or_else(|| ApiError::NotFound("Article not found".into()))?;
+    let (articles, keywords) = state.db.find_source_topic_articles(&article.source, &id, limit)?;
+    let result = serde_json::json!({"source_articles": articles, "query_keywords": keywords});
+    let _ = state.db.set_cache(&ckey, "similar_by_source", &result.to_string(), 3600);
+    Ok(Json(result).into_response())
+}
+
+pub async fn handle_search(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> Result<Response, ApiError> {
+    let q = params.get("q").cloned().unwrap_or_default();
+    if q.is_empty() {
+        return Ok((
+            StatusCode::OK,
+            Json(serde_json::json!({"articles": [], "query": ""})),
+        )
+            .into_response());
+    }
+    let limit = params
+        .get("limit")
+        .and_then(|l| l.parse::<i64>().ok())
+        .unwrap_or(20)
+        .min(100)
+        .max(1);
+    let articles = state.db.search_articles(&q, limit)?;
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        Json(serde_json::json!({"articles": articles, "query": q})),
+    )
+        .into_response())
+}
+
+// --- Saved Searches API ---
+
+/// Saved searches per owner.
+const SAVED_SEARCH_MAX: i64 = 10;
+const SAVED_SEARCH_MAX_QUERY_CHARS: usize = 100;
+const SAVED_SEARCH_MATCHES_LIMIT: i64 = 50;
+
+#[derive(Deserialize)]
+pub struct SavedSearchRequest {
+    pub query: String,
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+/// Trimmed query and validated category filter.
+fn validate_saved_search(db: &Db, body: &SavedSearchRequest) -> Result<(String, Option<String>), ApiError> {
+    let query = body.query.trim();
+    if query.is_empty() {
+        return Err(ApiError::validation("query", "検索キーワードを入力してください"));
+    }
+    if query.chars().count() > SAVED_SEARCH_MAX_QUERY_CHARS {
+        return Err(ApiError::validation(
+            "query",
+            format!("検索キーワードは{}文字以内にしてください", SAVED_SEARCH_MAX_QUERY_CHARS),
+        ));
+    }
+    let category = match body.category.as_deref().filter(|c| !c.is_empty()) {
+        Some(c) => Some(
+            lookup_category(db, c)
+                .ok_or_else(|| ApiError::validation("category", format!("unknown category: {}", c)))?
+                .as_str()
+                .to_string(),
+        ),
+        None => None,
+    };
+    Ok((query.to_string(), category))
+}
+
+/// GET /api/searches — the caller's saved searches with unseen match counts.
+pub async fn list_saved_searches(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    let searches = state.db.list_saved_searches(&owner)?;
+    Ok(Json(serde_json::json!({"searches": searches, "max": SAVED_SEARCH_MAX})).into_response())
+}
+
+/// POST /api/searches — save a search; new articles matching it are collected by the
+/// fetcher from then on.
+pub async fn create_saved_search(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<SavedSearchRequest>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    let (query, category) = validate_saved_search(&state.db, &body)?;
+    let id = uuid::Uuid::new_v4().to_string();
+    if !state.db.create_saved_search(&id, &owner, &query, category.as_deref(), SAVED_SEARCH_MAX)? {
+        return Err(ApiError::validation(
+            "query",
+            format!("保存できる検索は{}件までです", SAVED_SEARCH_MAX),
+        ));
+    }
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({"id": id, "query": query, "category_filter": category})),
+    )
+        .into_response())
+}
+
+/// PUT /api/searches/:id
+pub async fn update_saved_search(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(body): Json<SavedSearchRequest>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    let (query, category) = validate_saved_search(&state.db, &body)?;
+    if !state.db.update_saved_search(&id, &owner, &query, category.as_deref())? {
+        return Err(ApiError::NotFound("保存した検索が見つかりません".into()));
+    }
+    Ok(Json(serde_json::json!({"id": id, "query": query, "category_filter": category})).into_response())
+}
+
+/// DELETE /api/searches/:id
+pub async fn delete_saved_search(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    if !state.db.delete_saved_search(&id, &owner)? {
+        return Err(ApiError::NotFound("保存した検索が見つかりません".into()));
+    }
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// GET /api/searches/:id/matches — unseen matching articles; returning them marks them seen.
+pub async fn get_saved_search_matches(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    let articles = state
+        .db
+        .take_unseen_matches(&id, &owner, SAVED_SEARCH_MATCHES_LIMIT)?
+        .ok_or_else(|| ApiError::NotFound("保存した検索が見つかりません".into()))?;
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(serde_json::json!({"search_id": id, "articles": articles})),
+    )
+        .into_response())
+}
+
+/// GET /api/searches/updates — per saved search, matches found since the previous call.
+/// Cheap enough for the frontend to poll.
+pub async fn get_saved_search_updates(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    let updates: Vec<serde_json::Value> = state
+        .db
+        .saved_search_updates(&owner)?
+        .into_iter()
+        .map(|(id, query, new, unseen)| serde_json::json!({"id": id, "query": query, "new": new, "unseen": unseen}))
+        .collect();
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(serde_json::json!({"searches": updates})),
+    )
+        .into_response())
+}
+
+// --- Mute Rules API ---
+
+/// Mute rules per owner.
+const MUTE_RULES_MAX: i64 = 50;
+const MUTE_PATTERN_MAX_CHARS: usize = 100;
+/// Listings fetch this many times the page size while mute rules apply, so the page
+/// stays full after filtering.
+const MUTE_OVERFETCH: i64 = 2;
+
+#[derive(Deserialize)]
+pub struct MuteRuleRequest {
+    pub pattern: String,
+    #[serde(default)]
+    pub field: Option<String>,
+}
+
+/// The caller's mute rules, or none when the request has no device/auth header.
+fn caller_mute_rules(headers: &HeaderMap, state: &AppState) -> Vec<MuteRule> {
+    if !headers.contains_key("x-device-id") && !headers.contains_key(header::AUTHORIZATION) {
+        return Vec::new();
+    }
+    let tier = extract_user_tier(headers, state);
+    owner_key(&tier, headers)
+        .ok()
+        .and_then(|owner| state.db.list_mute_rules(&owner).ok())
+        .unwrap_or_default()
+}
+
+/// How a listing's cursor resumes after a page that mute rules cut short.
+enum MuteResume {
+    /// (published_at, id) cursor after the last returned article.
+    Keyset,
+    /// Offset cursor; the value is the page's starting offset.
+    Offset(i64),
+    Unpaged,
+}
+
+/// Remove muted articles from a page fetched with `MUTE_OVERFETCH`, keep at most
+/// `limit`, and point the cursor right after the last article actually returned.
+/// Returns (articles, next_cursor, muted count).
+fn apply_mutes(
+    articles: Vec<Article>,
+    next_cursor: Option<String>,
+    rules: &[MuteRule],
+    limit: i64,
+    resume: MuteResume,
+) -> (Vec<Article>, Option<String>, usize) {
+    if rules.is_empty() {
+        return (articles, next_cursor, 0);
+    }
+    let page = news_core::mute::filter_page(articles, rules, limit as usize);
+    let next_cursor = match page.consumed {
+        None => next_cursor,
+        Some(consumed) => match resume {
+            MuteResume::Keyset => page.articles.last().map(pagination::keyset_cursor),
+            MuteResume::Offset(start) => Some(pagination::offset_cursor(start + consumed as i64)),
+            MuteResume::Unpaged => None,
+        },
+    };
+    (page.articles, next_cursor, page.muted)
+}
+
+/// GET /api/mutes — the caller's mute rules.
+pub async fn list_mute_rules(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    let rules = state.db.list_mute_rules(&owner)?;
+    Ok(Json(serde_json::json!({"mutes": rules, "max": MUTE_RULES_MAX})).into_response())
+}
+
+/// POST /api/mutes — hide articles whose title/source (field, default "any") contains
+/// the pattern; `*` is a wildcard.
+pub async fn create_mute_rule(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<MuteRuleRequest>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    let pattern = body.pattern.trim();
+    if pattern.trim_matches('*').trim().is_empty() {
+        return Err(ApiError::validation("pattern", "ミュートするキーワードを入力してください"));
+    }
+    if pattern.chars().count() > MUTE_PATTERN_MAX_CHARS {
+        return Err(ApiError::validation(
+            "pattern",
+            format!("キーワードは{}文字以内にしてください", MUTE_PATTERN_MAX_CHARS),
+        ));
+    }
+    let field = match body.field.as_deref().filter(|f| !f.is_empty()) {
+        Some(f) => MuteField::parse(f)
+            .ok_or_else(|| ApiError::validation("field", "field must be title, source or any"))?,
+        None => MuteField::Any,
+    };
+    let rule = state
+        .db
+        .create_mute_rule(&owner, pattern, field, MUTE_RULES_MAX)?
+        .ok_or_else(|| ApiError::validation("pattern", format!("ミュートできるのは{}件までです", MUTE_RULES_MAX)))?;
+    Ok((StatusCode::CREATED, Json(rule)).into_response())
+}
+
+/// DELETE /api/mutes/:id
+pub async fn delete_mute_rule(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    if !state.db.delete_mute_rule(&id, &owner)? {
+        return Err(ApiError::NotFound("ミュート設定が見つかりません".into()));
+    }
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// --- Sources API ---
+
+#[derive(Deserialize)]
+pub struct SourceArticlesQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+pub async fn list_sources(State(state): State<Arc<AppState>>) -> Result<Response, ApiError> {
+    // Cache check (5 min TTL)
+    let ckey = cache_key("sources", "all");
+    if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
+        if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
+            return Ok((StatusCode::OK, Json(val)).into_response());
+        }
+    }
+
+    let sources: Vec<serde_json::Value> = state
+        .db
+        .list_sources()?
+        .into_iter()
+        .map(|(source, article_count, latest_published_at)| {
+            serde_json::json!({
+                "source": source,
+                "article_count": article_count,
+                "latest_published_at": latest_published_at,
+            })
+        })
+        .collect();
+    let result = serde_json::json!({"sources": sources});
+    let _ = state.db.set_cache(&ckey, "sources", &result.to_string(), 300);
+    Ok((StatusCode::OK, Json(result)).into_response())
+}
+
+pub async fn get_source_articles(
+    State(state): State<Arc<AppState>>,
+    Path(source): Path<String>,
+    Query(params): Query<SourceArticlesQuery>,
+) -> Result<Response, ApiError> {
+    let page = Paginator::new(params.limit.unwrap_or(20), params.cursor.clone());
+    match state.db.get_articles_by_source(&source, &page) {
+        Ok(Page { items: articles, next_cursor, .. }) => Ok((
+            StatusCode::OK,
+            [
+                (header::CACHE_CONTROL, "public, max-age=120"),
+                (header::CONTENT_TYPE, "application/json; charset=utf-8"),
+            ],
+            Json(ArticlesResponse {
+                articles,
+                next_cursor,
+            }),
+        )
+            .into_response()),
+        Err(e) => {
+            tracing::error!(error = %e, source = %source, "Failed to query source articles");
+            Err(ApiError::Internal(e))
+        }
+    }
+}
+
+// --- Tags API ---
+
+#[derive(Deserialize)]
+pub struct TagsQuery {
+    pub limit: Option<i64>,
+}
+
+/// GET /api/tags — most used tags with article counts.
+pub async fn list_tags(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<TagsQuery>,
+) -> Result<Response, ApiError> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let tags: Vec<serde_json::Value> = state
+        .db
+        .list_popular_tags(limit)?
+        .into_iter()
+        .map(|(tag, count)| serde_json::json!({"tag": tag, "count": count}))
+        .collect();
+    Ok((
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(serde_json::json!({"tags": tags})),
+    )
+        .into_response())
+}
+
+/// GET /api/tags/:tag/articles — newest articles carrying a tag.
+pub async fn get_tag_articles(
+    State(state): State<Arc<AppState>>,
+    Path(tag): Path<String>,
+    Query(params): Query<SourceArticlesQuery>,
+) -> Result<Response, ApiError> {
+    let page = Paginator::new(params.limit.unwrap_or(20), params.cursor.clone());
+    let Page { items: articles, next_cursor, .. } = state.db.get_articles_by_tag(&tag, &page)?;
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CACHE_CONTROL, "public, max-age=120"),
+            (header::CONTENT_TYPE, "application/json; charset=utf-8"),
+        ],
+        Json(ArticlesResponse {
+            articles,
+            next_cursor,
+        }),
+    )
+        .into_response())
+}
+
+pub async fn handle_image_proxy(
+    Query(params): Query<std::collections::HashMap<String, String>>,
+    method: Method,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let url = match params.get("url") {
+        Some(u) if !u.is_empty() => u.clone(),
+        _ => {
+            return Err(ApiError::validation("url", "Missing url param"));
+        }
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .unwrap();
+
+    // HEAD is forwarded as HEAD, so the image itself isn't downloaded
+    let upstream = if method == Method::HEAD { client.head(&url) } else { client.get(&url) };
+    match upstream.send().await {
+        Ok(resp) if resp.status().is_success() => {
             let content_type = resp
                 .headers()
                 .get("content-type")
@@ -473,21 +1528,27 @@ pub async fn handle_image_proxy(
                 .unwrap_or("image/jpeg")
                 .to_string();
 
+            if method == Method::HEAD {
+                let mut builder = Response::builder()
+                    .status(StatusCode::OK)
+                    .header(header::CONTENT_TYPE, content_type)
+                    .header(header::CACHE_CONTROL, "public, max-age=86400");
+                if let Some(length) = resp.headers().get(header::CONTENT_LENGTH) {
+                    builder = builder.header(header::CONTENT_LENGTH, length);
+                }
+                return Ok(builder.body(Body::empty()).unwrap());
+            }
             match resp.bytes().await {
                 Ok(bytes) => {
-                    let headers = [
-                        (header::CONTENT_TYPE, content_type),
-                        (
-                            header::CACHE_CONTROL,
-                            "public, max-age=86400".to_string(),
-                        ),
-                    ];
-                    (headers, bytes).into_response()
+                    let mut resp = ranged_response(&method, &headers, &content_type, bytes);
+                    resp.headers_mut()
+                        .insert(header::CACHE_CONTROL, HeaderValue::from_static("public, max-age=86400"));
+                    Ok(resp)
                 }
-                Err(_) => (StatusCode::BAD_GATEWAY, "Failed to read image").into_response(),
+                Err(_) => Err(ApiError::upstream("image", "Failed to read image")),
             }
         }
-        _ => (StatusCode::BAD_GATEWAY, "Failed to fetch image").into_response(),
+        _ => Err(ApiError::upstream("image", "Failed to fetch image")),
     }
 }
 
@@ -495,41 +1556,35 @@ pub async fn handle_summarize(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<SummarizeRequest>,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "summarize") {
-        return resp;
-    }
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "summarize")?;
 
     if state.api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "APIキーが設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
+    }
+
+    if body.mode.as_deref() == Some("by_category") {
+        return summarize_by_category(&state, &tier, &body).await;
     }
 
     let minutes = body.minutes.max(1).min(10);
     let target_chars = (minutes as usize) * 300;
 
-    let articles = match state.db.query_articles(None, 30, None) {
-        Ok((arts, _)) => arts,
+    let articles = match state.db.query_articles(None, &Paginator::first(30), false) {
+        Ok(page) => page.items,
         Err(e) => {
             warn!(error = %e, "Failed to query articles for summary");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "記事の取得に失敗しました"})),
-            )
-                .into_response();
+            return Err(ApiError::Internal("記事の取得に失敗しました".into()));
         }
     };
 
     if articles.is_empty() {
-        return (
+        return Ok((
             StatusCode::OK,
             Json(serde_json::json!({"summary": "現在表示できるニュースがありません。", "article_count": 0})),
         )
-            .into_response();
+            .into_response());
     }
 
     let pairs: Vec<(String, String)> = articles
@@ -540,50 +1595,417 @@ pub async fn handle_summarize(
 
     // Cache check — key based on article titles + minutes
     let titles_hash: String = pairs.iter().map(|(t, _)| t.as_str()).collect::<Vec<_>>().join("|");
-    let ckey = cache_key("summarize", &format!("{}:{}", minutes, titles_hash));
+    let variant = ab_variant(&state, &tier, "summarize");
+    // v2: responses carry `sources`
+    let ckey = cache_key(
+        "summarize:v2",
+        &format!("{}:{}{}", minutes, titles_hash, ab_cache_suffix(variant.as_ref())),
+    );
     if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
         if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
             // Cache hit — don't count against daily limit
-            return (StatusCode::OK, Json(val)).into_response();
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
 
-    match claude::summarize_articles(&state.http_client, &state.api_key, &pairs, target_chars)
+    match state
+        .metrics
+        .claude(claude::summarize_articles(&state.claude, ModelTier::Quality, &pairs, target_chars, variant.as_ref()))
         .await
     {
-        Ok(summary) => {
+        Ok(generated) => {
             increment_usage_if_needed(&state.db, &tier, "summarize");
+            let (summary, cited) = claude::resolve_citations(&generated.value, articles.len());
+            let sources = cited_sources(&cited, &articles.iter().collect::<Vec<_>>());
 
-            // Convert to reading for TTS (generic — caller doesn't know target engine)
-            let reading = claude::convert_to_reading(
-                &state.http_client,
-                &state.api_key,
-                &summary,
-                "generic",
-            )
-            .await
-            .unwrap_or_else(|_| summary.clone());
+            // Convert to reading for TTS (caller doesn't know target engine)
+            let reading = summary_reading(&state, &summary).await;
 
             let resp_json = serde_json::json!({
                 "summary": summary,
                 "summary_reading": reading,
-                "article_count": article_count
+                "sources": sources,
+                "article_count": article_count,
+                "model_used": generated.model_used
             });
 
             // Cache for 3 hours
             let _ = state.db.set_cache(&ckey, "summarize", &resp_json.to_string(), 10800);
 
-            (StatusCode::OK, Json(resp_json)).into_response()
+            Ok((StatusCode::OK, Json(resp_json)).into_response())
         }
         Err(e) => {
             warn!(error = %e, "Summarize failed");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "要約の生成に失敗しました。しばらくしてお試しください。"})),
-            )
-                .into_response()
+            Err(ApiError::upstream("claude", "要約の生成に失敗しました。しばらくしてお試しください。"))
+        }
+    }
+}
+
+/// Articles per category fed into a briefing section.
+const BRIEFING_ARTICLES_PER_CATEGORY: i64 = 5;
+
+/// Briefing mode: one short summary per category (max 2 Claude calls in flight),
+/// plus a combined reading for TTS. Categories with no articles, or whose summary
+/// fails, are left out.
+async fn summarize_by_category(
+    state: &AppState,
+    tier: &UserTier,
+    body: &SummarizeRequest,
+) -> Result<Response, ApiError> {
+    let minutes = body.minutes.clamp(1, 10);
+
+    let mut categories: Vec<Category> = Vec::new();
+    for id in &body.categories {
+        let cat = lookup_category(&state.db, id)
+            .ok_or_else(|| ApiError::validation("categories", format!("不明なカテゴリです: {}", id)))?;
+        if !categories.contains(&cat) {
+            categories.push(cat);
+        }
+    }
+    if categories.is_empty() {
+        categories = match visible_categories(&state.db) {
+            Ok(entries) => entries.iter().map(|c| Category::from_id(&c.id)).collect(),
+            Err(_) => Category::all().to_vec(),
+        };
+        categories.retain(|c| *c != Category::Podcast);
+    }
+
+    let articles = state
+        .db
+        .top_articles_per_category(BRIEFING_ARTICLES_PER_CATEGORY)
+        .map_err(|e| {
+            warn!(error = %e, "Failed to query articles for briefing");
+            ApiError::Internal("記事の取得に失敗しました".into())
+        })?;
+    let groups: Vec<(Category, Vec<&Article>)> = categories
+        .into_iter()
+        .map(|cat| {
+            let arts = articles.iter().filter(|a| a.category == cat).collect::<Vec<_>>();
+            (cat, arts)
+        })
+        .filter(|(_, arts)| !arts.is_empty())
+        .collect();
+
+    if groups.is_empty() {
+        return Ok((
+            StatusCode::OK,
+            Json(serde_json::json!({"sections": [], "summary_reading": "", "article_count": 0})),
+        )
+            .into_response());
+    }
+
+    // Cache check — key based on categories, minutes and article titles
+    let titles_hash: String = groups
+        .iter()
+        .flat_map(|(_, arts)| arts.iter().map(|a| a.title.as_str()))
+        .collect::<Vec<_>>()
+        .join("|");
+    let category_ids: Vec<&str> = groups.iter().map(|(c, _)| c.as_str()).collect();
+    let variant = ab_variant(state, tier, "summarize");
+    // v2: sections carry `sources`
+    let ckey = cache_key(
+        "summarize_by_category:v2",
+        &format!(
+            "{}:{}:{}{}",
+            category_ids.join(","),
+            minutes,
+            titles_hash,
+            ab_cache_suffix(variant.as_ref())
+        ),
+    );
+    if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
+        if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
+
+    let target_chars = ((minutes as usize) * 300 / groups.len()).max(150);
+    let jobs: Vec<_> = groups
+        .iter()
+        .map(|(cat, arts)| briefing_section(state, cat.clone(), arts, target_chars, variant.as_ref()))
+        .collect();
+    let sections: Vec<serde_json::Value> = futures::stream::iter(jobs)
+        .buffered(2)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+
+    if sections.is_empty() {
+        return Err(ApiError::upstream("claude", "要約の生成に失敗しました。しばらくしてお試しください。"));
+    }
+    increment_usage_if_needed(&state.db, tier, "summarize");
+
+    let labels = CategoryInfo::all();
+    let summary_reading = sections
+        .iter()
+        .map(|s| {
+            let id = s["category"].as_str().unwrap_or_default();
+            let label = labels
+                .iter()
+                .find(|c| c.id == id)
+                .map(|c| c.label_ja.as_str())
+                .unwrap_or(id);
+            format!("{}のニュースです。{}", label, s["summary_reading"].as_str().unwrap_or_default())
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let article_count: usize = sections
+        .iter()
+        .map(|s| s["article_ids"].as_array().map_or(0, |a| a.len()))
+        .sum();
+
+    let resp_json = serde_json::json!({
+        "mode": "by_category",
+        "sections": sections,
+        "summary_reading": summary_reading,
+        "article_count": article_count,
+    });
+
+    // Cache for 3 hours
+    let _ = state.db.set_cache(&ckey, "summarize", &resp_json.to_string(), 10800);
+
+    Ok((StatusCode::OK, Json(resp_json)).into_response())
+}
+
+/// Sources for the citation markers of a summary over `articles` (`[1]` = first).
+fn cited_sources(markers: &[usize], articles: &[&Article]) -> Vec<claude::SourceRef> {
+    markers
+        .iter()
+        .filter_map(|&m| {
+            let article = articles.get(m.checked_sub(1)?)?;
+            Some(claude::SourceRef {
+                marker: m,
+                article_id: Some(article.id.clone()),
+                title: article.title.clone(),
+                url: article.url.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Kanji-preserving reading of a summary (cached 24h), citation markers removed; the
+/// bare summary on failure.
+async fn summary_reading(state: &AppState, summary: &str) -> String {
+    let summary = &claude::strip_citations(summary);
+    reading::cached_reading(&state.db, reading::ReadingProfile::KanjiPreserving, summary, 86400, |engine| async move {
+        state.metrics.claude(claude::convert_to_reading(&state.claude, ModelTier::Fast, summary, engine)).await.map(|g| g.value)
+    })
+    .await
+    .unwrap_or_else(|_| summary.to_string())
+}
+
+async fn briefing_section(
+    state: &AppState,
+    cat: Category,
+    articles: &[&Article],
+    target_chars: usize,
+    variant: Option<&AbVariant>,
+) -> Option<serde_json::Value> {
+    let pairs: Vec<(String, String)> = articles.iter().map(|a| (a.title.clone(), a.source.clone())).collect();
+    let summary = match state
+        .metrics
+        .claude(claude::summarize_articles(&state.claude, ModelTier::Quality, &pairs, target_chars, variant))
+        .await
+    {
+        Ok(s) => s.value,
+        Err(e) => {
+            warn!(category = cat.as_str(), error = %e, "Briefing section failed");
+            return None;
+        }
+    };
+    let (summary, cited) = claude::resolve_citations(&summary, articles.len());
+    let reading = summary_reading(state, &summary).await;
+    Some(serde_json::json!({
+        "category": cat.as_str(),
+        "summary": summary,
+        "summary_reading": reading,
+        "sources": cited_sources(&cited, articles),
+        "article_ids": articles.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(),
+    }))
+}
+
+// --- Daily Digest API ---
+
+#[derive(Serialize, Deserialize)]
+pub struct DailyDigest {
+    pub generated_at: String,
+    pub article_count: u32,
+    pub categories: Vec<CategoryDigest>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CategoryDigest {
+    pub category: String,
+    pub top_article: Article,
+    pub headline: String,
+    pub article_count: u32,
+}
+
+const DIGEST_ARTICLES_PER_CATEGORY: i64 = 5;
+/// Only articles from the last day make the digest.
+const DIGEST_WINDOW_HOURS: i64 = 24;
+const DIGEST_CACHE_TTL: i64 = 1800; // 30 min
+
+/// Build (or load from cache) the structured daily digest.
+async fn build_daily_digest(state: &AppState) -> Result<DailyDigest, String> {
+    let ckey = cache_key("digest_daily", "latest");
+    if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
+        if let Ok(digest) = serde_json::from_str::<DailyDigest>(&cached) {
+            return Ok(digest);
+        }
+    }
+
+    let counts: std::collections::HashMap<String, i64> =
+        state.db.category_article_counts(DIGEST_WINDOW_HOURS)?.into_iter().collect();
+    let articles = state.db.recent_top_articles_per_category(DIGEST_ARTICLES_PER_CATEGORY, DIGEST_WINDOW_HOURS)?;
+
+    let mut by_category: std::collections::HashMap<String, Vec<Article>> =
+        std::collections::HashMap::new();
+    for article in articles {
+        by_category
+            .entry(article.category.as_str().to_string())
+            .or_default()
+            .push(article);
+    }
+    for list in by_category.values_mut() {
+        list.sort_by_key(|a| std::cmp::Reverse(a.published_at));
+    }
+
+    let headlines = if state.api_key.is_empty() || by_category.is_empty() {
+        std::collections::HashMap::new()
+    } else {
+        let pairs = by_category
+            .iter()
+            .map(|(cat, list)| {
+                let items = list.iter().map(|a| (a.title.clone(), a.source.clone())).collect();
+                (cat.clone(), items)
+            })
+            .collect();
+        state.metrics.claude(claude::generate_category_headlines(&state.claude, ModelTier::Fast, &pairs))
+            .await
+            .map(|g| g.value)
+            .unwrap_or_else(|e| {
+                warn!(error = %e, "Category headline generation failed, using article titles");
+                std::collections::HashMap::new()
+            })
+    };
+
+    // Follow the admin-defined category order
+    let order: Vec<String> = state
+        .db
+        .get_categories()
+        .map(|cats| {
+            cats.into_iter()
+                .filter(|(_, _, _, _, vis)| *vis)
+                .map(|(id, _, _, _, _)| id)
+                .collect()
+        })
+        .unwrap_or_else(|_| Category::all().iter().map(|c| c.as_str().to_string()).collect());
+
+    let mut categories = Vec::new();
+    for cat in order {
+        let Some(mut list) = by_category.remove(&cat) else {
+            continue;
+        };
+        let top_article = list.remove(0);
+        let headline = headlines
+            .get(&cat)
+            .cloned()
+            .unwrap_or_else(|| top_article.title.clone());
+        let article_count = counts.get(&cat).copied().unwrap_or(0) as u32;
+        categories.push(CategoryDigest {
+            category: cat,
+            top_article,
+            headline,
+            article_count,
+        });
+    }
+
+    let digest = DailyDigest {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        article_count: counts.values().sum::<i64>() as u32,
+        categories,
+    };
+
+    if let Ok(json) = serde_json::to_string(&digest) {
+        let _ = state.db.set_cache(&ckey, "digest_daily", &json, DIGEST_CACHE_TTL);
+    }
+    Ok(digest)
+}
+
+pub async fn handle_daily_digest(State(state): State<Arc<AppState>>) -> Result<Response, ApiError> {
+    match build_daily_digest(&state).await {
+        Ok(digest) => Ok((
+            StatusCode::OK,
+            [(header::CACHE_CONTROL, "public, max-age=300")],
+            Json(digest),
+        )
+            .into_response()),
+        Err(e) => {
+            warn!(error = %e, "Failed to build daily digest");
+            Err(ApiError::upstream("claude", "ダイジェストの生成に失敗しました"))
+        }
+    }
+}
+
+/// Playlist of pre-cached TTS audio for each category's top article.
+/// Segments whose audio isn't cached yet carry `cached: false` so the client
+/// can fall back to `POST /api/tts` with the segment text.
+pub async fn handle_daily_digest_audio(State(state): State<Arc<AppState>>) -> Result<Response, ApiError> {
+    let digest = match build_daily_digest(&state).await {
+        Ok(d) => d,
+        Err(e) => {
+            warn!(error = %e, "Failed to build daily digest for audio");
+            return Err(ApiError::upstream("claude", "ダイジェストの生成に失敗しました"));
+        }
+    };
+
+    let voice_id = crate::tts_cache::DEFAULT_VOICE;
+    let mut cached_count = 0;
+    let segments: Vec<serde_json::Value> = digest
+        .categories
+        .iter()
+        .map(|cd| {
+            let article = &cd.top_article;
+            let text = crate::tts_cache::article_tts_text(article);
+            let audio_key = cache_key("tts_audio", &format!("{}|{}", voice_id, text));
+            let cached = matches!(state.db.get_cache(&audio_key), Ok(Some(_)));
+            if cached {
+                cached_count += 1;
+            }
+            let murmur_key = murmur_cache_key(&article.title, &article.source, None);
+            let murmur = state
+                .db
+                .get_cache(&murmur_key)
+                .ok()
+                .flatten()
+                .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+                .and_then(|v| v["text"].as_str().map(String::from));
+            serde_json::json!({
+                "category": cd.category,
+                "article_id": article.id,
+                "title": article.title,
+                "headline": cd.headline,
+                "murmur": murmur,
+                "text": text,
+                "voice_id": voice_id,
+                "audio_url": format!("/api/tts/cached/{}", audio_key),
+                "cached": cached,
+            })
+        })
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "generated_at": digest.generated_at,
+            "cached_count": cached_count,
+            "segments": segments,
+        })),
+    )
+        .into_response())
 }
 
 // --- Text-to-Reading (hiragana) API ---
@@ -597,42 +2019,28 @@ pub async fn handle_to_reading(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<ToReadingRequest>,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "to_reading") {
-        return resp;
-    }
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "to_reading")?;
 
     if state.api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "APIキーが設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
     }
 
-    let text = if body.text.len() > 5000 {
-        &body.text[..5000]
-    } else {
-        &body.text
-    };
+    let text = truncate_sentence(&body.text, 5000);
 
-    match claude::convert_to_reading(&state.http_client, &state.api_key, text, "generic").await {
+    match state.metrics.claude(claude::convert_to_reading(&state.claude, ModelTier::Fast, text, "generic")).await {
         Ok(reading) => {
             increment_usage_if_needed(&state.db, &tier, "to_reading");
-            (
+            Ok((
                 StatusCode::OK,
-                Json(serde_json::json!({"reading": reading})),
+                Json(serde_json::json!({"reading": reading.value})),
             )
-                .into_response()
+                .into_response())
         }
         Err(e) => {
             warn!(error = %e, "Text to reading conversion failed");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "読み変換に失敗しました。しばらくしてお試しください。"})),
-            )
-                .into_response()
+            Err(ApiError::upstream("claude", "読み変換に失敗しました。しばらくしてお試しください。"))
         }
     }
 }
@@ -660,51 +2068,38 @@ pub async fn handle_podcast_generate(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<PodcastGenerateRequest>,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "podcast") {
-        return resp;
-    }
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "podcast")?;
 
     if state.api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "APIキーが設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
     }
 
     let use_qwen_omni = body.provider.as_deref() == Some("qwen-omni");
 
     if !use_qwen_omni && state.openai_api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "OpenAI APIキーが設定されていません（TTS用）"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("OpenAI APIキーが設定されていません（TTS用）".into()));
     }
 
     if use_qwen_omni && (state.runpod_api_key.is_empty() || state.qwen_omni_endpoint_id.is_empty()) {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "Qwen-Omni endpoint が設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("Qwen-Omni endpoint が設定されていません".into()));
     }
 
     // Cache check
     let url_for_key = body.url.as_deref().unwrap_or("");
-    let ckey = cache_key("podcast", &format!("{}|{}|{}", body.title, body.source, url_for_key));
+    // v2: responses carry `sources`
+    let ckey = cache_key("podcast:v2", &format!("{}|{}|{}", body.title, body.source, url_for_key));
     if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
         if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
-            return (StatusCode::OK, Json(val)).into_response();
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
 
     // Fetch article content if URL provided
     let article_content = if let Some(ref url) = body.url {
         if !url.is_empty() {
-            news_core::ogp::fetch_article_content(&state.http_client, url).await.unwrap_or_default()
+            news_core::ogp::fetch_article_content(&state.polite, url).await.unwrap_or_default()
         } else {
             String::new()
         }
@@ -713,30 +2108,46 @@ pub async fn handle_podcast_generate(
     };
 
     // Generate dialogue script
-    let dialogue = match claude::generate_dialogue_script(
-        &state.http_client,
-        &state.api_key,
+    let dialogue = match state.metrics.claude(claude::generate_dialogue_script(
+        &state.claude,
+        ModelTier::Quality,
         &body.title,
         &body.description,
         &body.source,
         &article_content,
-    )
+    ))
     .await
     {
-        Ok(d) => d,
+        Ok(d) => d.value,
         Err(e) => {
             warn!(error = %e, "Dialogue generation failed");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "対話スクリプトの生成に失敗しました"})),
-            )
-                .into_response();
+            return Err(ApiError::upstream("claude", "対話スクリプトの生成に失敗しました"));
         }
     };
+    // The article is [1]; lines keep their markers, speech drops them
+    let mut cites_article = false;
+    let dialogue: Vec<claude::DialogueLine> = dialogue
+        .into_iter()
+        .map(|line| {
+            let (text, cited) = claude::resolve_citations(&line.text, 1);
+            cites_article |= !cited.is_empty();
+            claude::DialogueLine { text, ..line }
+        })
+        .collect();
+    let sources: Vec<claude::SourceRef> = cites_article
+        .then(|| claude::SourceRef {
+            marker: 1,
+            article_id: body.article_id.clone(),
+            title: body.title.clone(),
+            url: body.url.clone().unwrap_or_default(),
+        })
+        .into_iter()
+        .collect();
 
     // Generate TTS for each line (host=coral, analyst=echo)
     let mut audio_segments = Vec::new();
     for line in &dialogue {
+        let spoken = claude::strip_citations(&line.text);
         if use_qwen_omni {
             // Use Qwen-Omni via RunPod
             let omni_voice = if line.speaker == "host" { "Chelsie" } else { "Ethan" };
@@ -746,7 +2157,7 @@ pub async fn handle_podcast_generate(
                 "あなたはニュース解説の専門家です。落ち着いた知的なトーンで、分析的に語ってください。"
             };
             let input = serde_json::json!({
-                "text": line.text,
+                "text": spoken,
                 "voice": omni_voice,
                 "system_prompt": system_prompt
             });
@@ -755,7 +2166,7 @@ pub async fn handle_podcast_generate(
                     let b64 = output["audio_base64"].as_str().unwrap_or("").to_string();
                     audio_segments.push(AudioSegment {
                         speaker: line.speaker.clone(),
-                        text: line.text.clone(),
+                        text: spoken.clone(),
                         audio_base64: b64,
                     });
                 }
@@ -763,7 +2174,7 @@ pub async fn handle_podcast_generate(
                     warn!(error = %e, speaker = %line.speaker, "Qwen-Omni TTS failed");
                     audio_segments.push(AudioSegment {
                         speaker: line.speaker.clone(),
-                        text: line.text.clone(),
+                        text: spoken.clone(),
                         audio_base64: String::new(),
                     });
                 }
@@ -778,7 +2189,7 @@ pub async fn handle_podcast_generate(
             };
             let tts_body = serde_json::json!({
                 "model": "gpt-4o-mini-tts",
-                "input": line.text,
+                "input": spoken,
                 "voice": voice,
                 "response_format": "mp3",
                 "instructions": tts_instruction
@@ -801,7 +2212,7 @@ pub async fn handle_podcast_generate(
                             );
                             audio_segments.push(AudioSegment {
                                 speaker: line.speaker.clone(),
-                                text: line.text.clone(),
+                                text: spoken.clone(),
                                 audio_base64: b64,
                             });
                         }
@@ -809,7 +2220,7 @@ pub async fn handle_podcast_generate(
                             warn!(error = %e, speaker = %line.speaker, "TTS bytes read failed");
                             audio_segments.push(AudioSegment {
                                 speaker: line.speaker.clone(),
-                                text: line.text.clone(),
+                                text: spoken.clone(),
                                 audio_base64: String::new(),
                             });
                         }
@@ -821,7 +2232,7 @@ pub async fn handle_podcast_generate(
                     warn!(status = %status, body = %err_body, speaker = %line.speaker, "TTS generation failed");
                     audio_segments.push(AudioSegment {
                         speaker: line.speaker.clone(),
-                        text: line.text.clone(),
+                        text: spoken.clone(),
                         audio_base64: String::new(),
                     });
                 }
@@ -829,7 +2240,7 @@ pub async fn handle_podcast_generate(
                     warn!(error = %e, speaker = %line.speaker, "TTS request failed");
                     audio_segments.push(AudioSegment {
                         speaker: line.speaker.clone(),
-                        text: line.text.clone(),
+                        text: spoken.clone(),
                         audio_base64: String::new(),
                     });
                 }
@@ -842,12 +2253,13 @@ pub async fn handle_podcast_generate(
     let resp_json = serde_json::json!({
         "dialogue": dialogue,
         "audio_segments": audio_segments,
+        "sources": sources,
     });
 
     // Cache for 6 hours
     let _ = state.db.set_cache(&ckey, "podcast", &resp_json.to_string(), 21600);
 
-    (StatusCode::OK, Json(resp_json)).into_response()
+    Ok((StatusCode::OK, Json(resp_json)).into_response())
 }
 
 // --- Feed API (for online) ---
@@ -861,38 +2273,48 @@ pub struct FeedQuery {
 
 pub async fn get_feed(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Query(params): Query<FeedQuery>,
-) -> Response {
-    let category = params.category.as_deref().and_then(Category::from_str);
+) -> Result<Response, ApiError> {
+    let category = category_filter(&state.db, params.category.as_deref())?;
     let limit = params.limit.unwrap_or(10).min(20).max(1);
+    let mutes = caller_mute_rules(&headers, &state);
+    let fetch_limit = if mutes.is_empty() { limit } else { limit * MUTE_OVERFETCH };
 
     let result = state
         .db
-        .query_articles(category.as_ref(), limit, params.cursor.as_deref());
+        .query_articles(category.as_ref(), &Paginator::uncapped(fetch_limit, params.cursor.clone()), false);
 
     match result {
-        Ok((articles, next_cursor)) => {
-            let body = serde_json::json!({
+        Ok(Page { items: articles, next_cursor, .. }) => {
+            let (articles, next_cursor, muted) =
+                apply_mutes(articles, next_cursor, &mutes, limit, MuteResume::Keyset);
+            let mut body = serde_json::json!({
                 "articles": articles,
                 "next_cursor": next_cursor,
             });
-            (
+            if !mutes.is_empty() {
+                body["muted_count"] = muted.into();
+            }
+            let cache_control = if mutes.is_empty() {
+                "public, max-age=30, stale-while-revalidate=60"
+            } else {
+                "private, max-age=30"
+            };
+            Ok((
                 StatusCode::OK,
                 [
-                    (header::CACHE_CONTROL, "public, max-age=30, stale-while-revalidate=60"),
+                    (header::CACHE_CONTROL, cache_control),
+                    (header::VARY, "Authorization, X-Device-Id"),
                     (header::CONTENT_TYPE, "application/json; charset=utf-8"),
                 ],
                 Json(body),
             )
-                .into_response()
+                .into_response())
         }
         Err(e) => {
             tracing::error!(error = %e, "Failed to query feed articles");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Internal server error"})),
-            )
-                .into_response()
+            Err(ApiError::Internal("Internal server error".into()))
         }
     }
 }
@@ -905,64 +2327,113 @@ pub struct MurmurGenerateRequest {
     pub description: String,
     pub source: String,
     pub article_id: Option<String>,
+    #[serde(default)]
+    pub style: MurmurStyle,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MurmurStyle {
+    /// One voice musing about the article.
+    #[default]
+    Solo,
+    /// An optimist and a skeptic trading 4–6 short lines.
+    Debate,
 }
 
 pub async fn handle_murmur_generate(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<MurmurGenerateRequest>,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "murmur") {
-        return resp;
-    }
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "murmur")?;
 
     if state.api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "APIキーが設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
     }
 
-    // Cache check (6h TTL)
-    let ckey = cache_key("murmur", &format!("{}|{}", body.title, body.source));
+    let variant = match body.style {
+        MurmurStyle::Solo => ab_variant(&state, &tier, "murmur"),
+        MurmurStyle::Debate => None,
+    };
+    let ckey = match body.style {
+        MurmurStyle::Solo => murmur_cache_key(&body.title, &body.source, variant.as_ref()),
+        MurmurStyle::Debate => murmur_debate_cache_key(&body.title, &body.source),
+    };
     if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
         if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
-            return (StatusCode::OK, Json(val)).into_response();
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
 
+    let result = match body.style {
+        MurmurStyle::Solo => {
+            generate_murmur_entry(&state, &body.title, &body.description, &body.source, variant.as_ref()).await?
+        }
+        MurmurStyle::Debate => generate_murmur_debate_entry(&state, &body.title, &body.description, &body.source).await?,
+    };
+    // One use per murmur, however many lines a debate has
+    increment_usage_if_needed(&state.db, &tier, "murmur");
+    Ok((StatusCode::OK, Json(result)).into_response())
+}
+
+/// Cache key of an article's murmur (`{"text", "audio_base64"}`, 6 h) under `variant`.
+pub(crate) fn murmur_cache_key(title: &str, source: &str, variant: Option<&AbVariant>) -> String {
+    cache_key("murmur", &format!("{}|{}{}", title, source, ab_cache_suffix(variant)))
+}
+
+/// Cache key of an article's debate murmur (`{"lines", "style"}`, 6 h).
+fn murmur_debate_cache_key(title: &str, source: &str) -> String {
+    cache_key("murmur_debate", &format!("{}|{}", title, source))
+}
+
+const MURMUR_TTL: i64 = 6 * 3600;
+
+/// Generate a murmur and its audio, and cache it. `audio_base64` is empty when no
+/// TTS provider is configured or synthesis failed.
+async fn generate_murmur_entry(
+    state: &AppState,
+    title: &str,
+    description: &str,
+    source: &str,
+    variant: Option<&AbVariant>,
+) -> Result<serde_json::Value, ApiError> {
     // Generate murmur text via Claude Haiku
-    let murmur_text = match claude::generate_murmur(
-        &state.http_client,
-        &state.api_key,
-        &body.title,
-        &body.description,
-        &body.source,
-    )
-    .await
+    let murmur_text = match state
+        .metrics
+        .claude(claude::generate_murmur(&state.claude, ModelTier::Fast, title, description, source, variant))
+        .await
     {
-        Ok(t) => t,
+        Ok(t) => t.value,
         Err(e) => {
             warn!(error = %e, "Murmur generation failed");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "つぶやきの生成に失敗しました"})),
-            )
-                .into_response();
+            return Err(ApiError::upstream("claude", "つぶやきの生成に失敗しました"));
         }
     };
 
-    // Generate TTS via Qwen-TTS (Japanese voice) or fallback to OpenAI TTS
-    let audio_base64 = if !state.qwen_tts_endpoint_id.is_empty() && !state.runpod_api_key.is_empty() {
+    let audio_base64 = murmur_audio(state, &murmur_text, "nova").await;
+
+    let result = serde_json::json!({
+        "text": murmur_text,
+        "audio_base64": audio_base64,
+    });
+    let _ = state.db.set_cache(&murmur_cache_key(title, source, variant), "murmur", &result.to_string(), MURMUR_TTL);
+    Ok(result)
+}
+
+/// Base64 audio of a murmur line via Qwen-TTS (Japanese voice) or, failing that,
+/// OpenAI TTS with `openai_voice`. Empty when no provider is configured or synthesis
+/// failed.
+async fn murmur_audio(state: &AppState, text: &str, openai_voice: &str) -> String {
+    if !state.qwen_tts_endpoint_id.is_empty() && !state.runpod_api_key.is_empty() {
         let input = serde_json::json!({
-            "text": murmur_text,
+            "text": text,
             "language": "Japanese",
         });
         match tokio::time::timeout(
             Duration::from_secs(90),
-            runpod_runsync(&state, &state.qwen_tts_endpoint_id, input),
+            runpod_runsync(state, &state.qwen_tts_endpoint_id, input),
         )
         .await
         {
@@ -980,7 +2451,7 @@ pub async fn handle_murmur_generate(
         }
     } else if !state.openai_api_key.is_empty() {
         // Fallback to OpenAI TTS with Japanese voice
-        match tts_openai(&state, &murmur_text, "nova").await {
+        match tts_openai(state, text, openai_voice).await {
             Ok(audio_bytes) => {
                 use base64::{Engine as _, engine::general_purpose};
                 general_purpose::STANDARD.encode(audio_bytes)
@@ -992,19 +2463,320 @@ pub async fn handle_murmur_generate(
         }
     } else {
         String::new()
+    }
+}
+
+/// Voices of each debate persona: (persona, Qwen-Omni voice, OpenAI voice).
+pub(crate) const DEBATE_VOICES: [(&str, &str, &str); 2] = [("optimist", "Chelsie", "nova"), ("skeptic", "Ethan", "echo")];
+
+/// Base64 audio of a debate line in its persona's voice, via Qwen-Omni or, failing
+/// that, OpenAI TTS. Qwen-TTS has a single voice per language, so it isn't used for
+/// debates. Empty when neither provider is configured or synthesis failed.
+async fn debate_line_audio(state: &AppState, persona: &str, text: &str) -> String {
+    use base64::{Engine as _, engine::general_purpose};
+    let (_, omni_voice, openai_voice) = DEBATE_VOICES
+        .iter()
+        .find(|(p, _, _)| *p == persona)
+        .copied()
+        .unwrap_or(DEBATE_VOICES[0]);
+    let audio = if !state.qwen_omni_endpoint_id.is_empty() && !state.runpod_api_key.is_empty() {
+        tokio::time::timeout(Duration::from_secs(90), tts_qwen_omni(state, text, omni_voice))
+            .await
+            .unwrap_or_else(|_| Err("timed out".into()))
+    } else if !state.openai_api_key.is_empty() {
+        tts_openai(state, text, openai_voice).await
+    } else {
+        return String::new();
+    };
+    match audio {
+        Ok(bytes) => general_purpose::STANDARD.encode(bytes),
+        Err(e) => {
+            warn!(error = %e, persona, "Murmur debate TTS failed");
+            String::new()
+        }
+    }
+}
+
+/// Generate a debate murmur with audio for every line, and cache it.
+async fn generate_murmur_debate_entry(
+    state: &AppState,
+    title: &str,
+    description: &str,
+    source: &str,
+) -> Result<serde_json::Value, ApiError> {
+    let lines = match state
+        .metrics
+        .claude(claude::generate_murmur_debate(&state.claude, ModelTier::Fast, title, description, source))
+        .await
+    {
+        Ok(g) => g.value,
+        Err(e) => {
+            warn!(error = %e, "Murmur debate generation failed");
+            return Err(ApiError::upstream("claude", "つぶやきの生成に失敗しました"));
+        }
+    };
+
+    let audio =
+        futures::future::join_all(lines.iter().map(|line| debate_line_audio(state, &line.persona, &line.text))).await;
+    let lines: Vec<serde_json::Value> = lines
+        .into_iter()
+        .zip(audio)
+        .map(|(line, audio_base64)| {
+            serde_json::json!({
+                "persona": line.persona,
+                "text": line.text,
+                "audio_base64": audio_base64,
+            })
+        })
+        .collect();
+    let result = serde_json::json!({"lines": lines, "style": "debate"});
+    let _ = state.db.set_cache(&murmur_debate_cache_key(title, source), "murmur", &result.to_string(), MURMUR_TTL);
+    Ok(result)
+}
+
+/// Recent articles considered per playlist slot.
+const MURMUR_PLAYLIST_CANDIDATES: i64 = 3;
+const MURMUR_PLAYLIST_MAX: i64 = 50;
+const MURMUR_PREFETCH_MAX: usize = 5;
+/// Seconds a client should wait before asking again for a murmur that is being generated.
+const MURMUR_RETRY_AFTER_SECS: u64 = 5;
+
+/// A murmur generation in progress, so concurrent requests for the same cache key
+/// start only one. Dropping it (including on error or cancellation) releases the key.
+struct MurmurClaim {
+    in_flight: Arc<std::sync::Mutex<std::collections::HashSet<String>>>,
+    key: String,
+}
+
+impl MurmurClaim {
+    /// Claim `key`, or None while another request is generating it.
+    fn take(state: &AppState, key: &str) -> Option<Self> {
+        let in_flight = Arc::clone(&state.murmurs_in_flight);
+        if !in_flight.lock().unwrap_or_else(|e| e.into_inner()).insert(key.to_string()) {
+            return None;
+        }
+        Some(Self { in_flight, key: key.to_string() })
+    }
+}
+
+impl Drop for MurmurClaim {
+    fn drop(&mut self) {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.key);
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MurmurPlaylistQuery {
+    pub category: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/murmur/playlist?category=&limit=20 — recent articles to play back to back,
+/// already-cached murmurs first so playback can start at once. Never generates:
+/// uncached items have no text yet, and a POST to their audio_url generates them (see
+/// `handle_murmur_prefetch` to warm them ahead of time).
+pub async fn handle_murmur_playlist(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<MurmurPlaylistQuery>,
+) -> Result<Response, ApiError> {
+    let variant = ab_variant(&state, &extract_user_tier(&headers, &state), "murmur");
+    let category = category_filter(&state.db, params.category.as_deref())?;
+    let limit = params.limit.unwrap_or(20).clamp(1, MURMUR_PLAYLIST_MAX);
+    let articles = state
+        .db
+        .query_articles(category.as_ref(), &Paginator::first(limit * MURMUR_PLAYLIST_CANDIDATES), false)?
+        .items;
+
+    let mut cached = Vec::new();
+    let mut uncached = Vec::new();
+    for article in &articles {
+        if cached.len() as i64 >= limit {
+            break;
+        }
+        let entry = state
+            .db
+            .get_cache(&murmur_cache_key(&article.title, &article.source, variant.as_ref()))
+            .ok()
+            .flatten()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok());
+        match entry {
+            Some(entry) => {
+                let has_audio = entry["audio_base64"].as_str().is_some_and(|a| !a.is_empty());
+                cached.push(serde_json::json!({
+                    "article_id": article.id,
+                    "title": article.title,
+                    "text": entry["text"],
+                    "audio_url": has_audio.then(|| format!("/api/murmur/{}/audio", article.id)),
+                    "cached": true,
+                }));
+            }
+            None => uncached.push(serde_json::json!({
+                "article_id": article.id,
+                "title": article.title,
+                "text": null,
+                "audio_url": format!("/api/murmur/{}/audio", article.id),
+                "cached": false,
+            })),
+        }
+    }
+    let cached_count = cached.len();
+    let items: Vec<serde_json::Value> = cached.into_iter().chain(uncached).take(limit as usize).collect();
+
+    Ok((
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(serde_json::json!({
+            "items": items,
+            "cached": cached_count,
+            "prefetch_url": "/api/murmur/prefetch",
+        })),
+    )
+        .into_response())
+}
+
+/// An article's cached murmur entry under the caller's A/B variant.
+fn cached_murmur(state: &AppState, article: &Article, variant: Option<&AbVariant>) -> Option<serde_json::Value> {
+    state
+        .db
+        .get_cache(&murmur_cache_key(&article.title, &article.source, variant))
+        .ok()
+        .flatten()
+        .and_then(|c| serde_json::from_str(&c).ok())
+}
+
+/// The audio of a murmur entry; 404 when it has none.
+fn murmur_audio_response(entry: &serde_json::Value) -> Result<Response, ApiError> {
+    let audio = entry["audio_base64"].as_str().unwrap_or_default();
+    if audio.is_empty() {
+        return Err(ApiError::NotFound("つぶやきの音声がありません".into()));
+    }
+    let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, audio)
+        .map_err(|e| ApiError::Internal(format!("Cached murmur decode: {e}")))?;
+    Ok(audio_response(axum::body::Bytes::from(bytes)))
+}
+
+/// GET /api/murmur/:article_id/audio — an article's cached murmur audio, served
+/// without touching the caller's allowance. Never generates; POST to the same path
+/// does.
+pub async fn handle_murmur_audio(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+) -> Result<Response, ApiError> {
+    let article = state
+        .db
+        .get_article_by_id(&article_id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+    let variant = ab_variant(&state, &extract_user_tier(&headers, &state), "murmur");
+    let entry = cached_murmur(&state, &article, variant.as_ref())
+        .ok_or_else(|| ApiError::NotFound("つぶやきはまだ生成されていません".into()))?;
+    murmur_audio_response(&entry)
+}
+
+/// POST /api/murmur/:article_id/audio — generate an article's murmur if it isn't
+/// cached (rate-limited like `POST /api/murmur/generate`) and return its audio. While
+/// another request is generating it, answers 202 with Retry-After instead of starting
+/// a second generation.
+pub async fn handle_murmur_audio_generate(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+) -> Result<Response, ApiError> {
+    let article = state
+        .db
+        .get_article_by_id(&article_id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+    let tier = extract_user_tier(&headers, &state);
+    let variant = ab_variant(&state, &tier, "murmur");
+    if let Some(entry) = cached_murmur(&state, &article, variant.as_ref()) {
+        return murmur_audio_response(&entry);
+    }
+
+    let ckey = murmur_cache_key(&article.title, &article.source, variant.as_ref());
+    let Some(_claim) = MurmurClaim::take(&state, &ckey) else {
+        return Ok((
+            StatusCode::ACCEPTED,
+            [(header::RETRY_AFTER, MURMUR_RETRY_AFTER_SECS.to_string())],
+            Json(serde_json::json!({"status": "warming"})),
+        )
+            .into_response());
     };
+    // Another request may have finished between the cache check and the claim
+    if let Some(entry) = cached_murmur(&state, &article, variant.as_ref()) {
+        return murmur_audio_response(&entry);
+    }
 
+    check_rate_limit(&state.db, &tier, "murmur")?;
+    if state.api_key.is_empty() {
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
+    }
+    let description = article.description.as_deref().unwrap_or("");
+    let entry = generate_murmur_entry(&state, &article.title, description, &article.source, variant.as_ref()).await?;
     increment_usage_if_needed(&state.db, &tier, "murmur");
+    murmur_audio_response(&entry)
+}
 
-    let result = serde_json::json!({
-        "text": murmur_text,
-        "audio_base64": audio_base64,
-    });
+#[derive(Deserialize)]
+pub struct MurmurPrefetchRequest {
+    pub article_ids: Vec<String>,
+}
 
-    // Cache for 6 hours
-    let _ = state.db.set_cache(&ckey, "murmur", &result.to_string(), 6 * 3600);
+/// POST /api/murmur/prefetch — generate the murmurs of the next few playlist items
+/// in the background. Each generation started counts against the murmur limit;
+/// cached or already-generating items are free. Reports a status per article.
+pub async fn handle_murmur_prefetch(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<MurmurPrefetchRequest>,
+) -> Result<Response, ApiError> {
+    if body.article_ids.len() > MURMUR_PREFETCH_MAX {
+        return Err(ApiError::validation(
+            "article_ids",
+            format!("一度に指定できるのは{}件までです", MURMUR_PREFETCH_MAX),
+        ));
+    }
+    if state.api_key.is_empty() {
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
+    }
+    let tier = extract_user_tier(&headers, &state);
+    let variant = ab_variant(&state, &tier, "murmur");
+
+    let mut statuses = serde_json::Map::new();
+    for article_id in &body.article_ids {
+        let Some(article) = state.db.get_article_by_id(article_id)? else {
+            statuses.insert(article_id.clone(), "not_found".into());
+            continue;
+        };
+        let ckey = murmur_cache_key(&article.title, &article.source, variant.as_ref());
+        let status = if matches!(state.db.get_cache(&ckey), Ok(Some(_))) {
+            "cached"
+        } else if let Some(claim) = MurmurClaim::take(&state, &ckey) {
+            if check_rate_limit(&state.db, &tier, "murmur").is_err() {
+                "rate_limited"
+            } else {
+                increment_usage_if_needed(&state.db, &tier, "murmur");
+                let bg = Arc::clone(&state);
+                let variant = variant.clone();
+                tokio::spawn(async move {
+                    let _claim = claim;
+                    let description = article.description.as_deref().unwrap_or("");
+                    let generated =
+                        generate_murmur_entry(&bg, &article.title, description, &article.source, variant.as_ref())
+                            .await;
+                    if let Err(e) = generated {
+                        warn!(article_id = %article.id, error = ?e, "Murmur prefetch failed");
+                    }
+                });
+                "warming"
+            }
+        } else {
+            "warming"
+        };
+        statuses.insert(article_id.clone(), status.into());
+    }
 
-    (StatusCode::OK, Json(result)).into_response()
+    Ok((StatusCode::ACCEPTED, Json(serde_json::json!({"items": statuses}))).into_response())
 }
 
 // --- Category Management API ---
@@ -1015,62 +2787,112 @@ pub struct CategoryAction {
     pub id: Option<String>,
     pub label_ja: Option<String>,
     pub order: Option<Vec<String>>,
+    /// remove: where the category's articles and feeds go. Required while it has any.
+    #[serde(default)]
+    pub reassign_to: Option<String>,
+    /// rename: a new id; articles and feeds follow and the old id keeps resolving.
+    #[serde(default)]
+    pub new_id: Option<String>,
+}
+
+/// The category a list endpoint filters on: None without `?category=`, 404 for an id
+/// that doesn't resolve, rather than quietly listing every category.
+fn category_filter(db: &Db, param: Option<&str>) -> Result<Option<Category>, ApiError> {
+    match param.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(c) => lookup_category(db, c)
+            .map(Some)
+            .ok_or_else(|| ApiError::NotFound(format!("カテゴリ「{c}」が見つかりません"))),
+        None => Ok(None),
+    }
+}
+
+/// A `?category=` value: a built-in or admin-created category id, or a former id
+/// (renamed or removed) that `category_aliases` points to a current one, so old links
+/// keep working. Aliases come first: a removed built-in id still parses but no longer
+/// has articles.
+pub(crate) fn lookup_category(db: &Db, name: &str) -> Option<Category> {
+    let name = name.trim().to_lowercase();
+    let id = match db.resolve_category_alias(&name) {
+        Ok(Some(target)) => target,
+        _ => name,
+    };
+    Category::from_str(&id).or_else(|| category_exists(db, &id).then(|| Category::from_id(&id)))
 }
 
 pub async fn handle_categories_manage(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<CategoryAction>,
-) -> Response {
-    if let Err(resp) = check_admin_auth(&headers, &state) { return resp; }
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
     match body.action.as_str() {
         "add" => {
-            let id = match &body.id {
-                Some(id) if !id.is_empty() => id.clone(),
-                _ => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "id is required"}))).into_response(),
+            // Stored lowercase: article categories are matched by `Category::from_id`.
+            let id = match body.id.as_deref().map(|id| id.trim().to_lowercase()) {
+                Some(id) if !id.is_empty() => id,
+                _ => return Err(ApiError::validation("id", "id is required")),
             };
             let label = body.label_ja.clone().unwrap_or_else(|| id.clone());
             let max_order = state.db.get_categories().map(|c| c.len() as i32).unwrap_or(0);
             match state.db.put_category(&id, &label, "", max_order) {
-                Ok(()) => (StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": format!("カテゴリ「{}」を追加しました", label)}))).into_response(),
-                Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
+                Ok(()) => Ok((StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": format!("カテゴリ「{}」を追加しました", label)}))).into_response()),
+                Err(e) => Err(ApiError::Internal(e)),
             }
         }
         "remove" => {
             let id = match &body.id {
                 Some(id) => id.clone(),
-                None => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "id is required"}))).into_response(),
+                None => return Err(ApiError::validation("id", "id is required")),
             };
-            match state.db.delete_category(&id) {
-                Ok(()) => (StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": format!("カテゴリ「{}」を削除しました", id)}))).into_response(),
-                Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
+            let reassign_to = body.reassign_to.as_deref().filter(|t| !t.is_empty());
+            let (articles, feeds) = state.db.category_usage(&id)?;
+            match reassign_to {
+                None if articles + feeds > 0 => {
+                    return Err(ApiError::validation(
+                        "reassign_to",
+                        format!("このカテゴリには記事{}件・フィード{}件があります。移動先を指定してください", articles, feeds),
+                    ))
+                }
+                Some(target) if target == id || !category_exists(&state.db, target) => {
+                    return Err(ApiError::validation("reassign_to", "移動先のカテゴリが見つかりません"))
+                }
+                _ => {}
+            }
+            match state.db.delete_category(&id, reassign_to) {
+                Ok(()) => Ok((StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": format!("カテゴリ「{}」を削除しました", id)}))).into_response()),
+                Err(e) => Err(ApiError::Internal(e)),
             }
         }
         "rename" => {
             let id = match &body.id {
                 Some(id) => id.clone(),
-                None => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "id is required"}))).into_response(),
+                None => return Err(ApiError::validation("id", "id is required")),
             };
             let label = match &body.label_ja {
                 Some(l) => l.clone(),
-                None => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "label_ja is required"}))).into_response(),
+                None => return Err(ApiError::validation("label_ja", "label_ja is required")),
             };
-            match state.db.rename_category(&id, &label) {
-                Ok(()) => (StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": format!("カテゴリを「{}」に変更しました", label)}))).into_response(),
-                Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
+            let new_id = body.new_id.as_deref().map(|n| n.trim().to_lowercase());
+            let new_id = new_id.as_deref().filter(|n| !n.is_empty() && *n != id);
+            if new_id.is_some_and(|n| category_exists(&state.db, n)) {
+                return Err(ApiError::validation("new_id", "そのIDのカテゴリは既に存在します"));
+            }
+            match state.db.rename_category(&id, &label, new_id) {
+                Ok(()) => Ok((StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": format!("カテゴリを「{}」に変更しました", label)}))).into_response()),
+                Err(e) => Err(ApiError::Internal(e)),
             }
         }
         "reorder" => {
             let order = match &body.order {
                 Some(o) => o.clone(),
-                None => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "order is required"}))).into_response(),
+                None => return Err(ApiError::validation("order", "order is required")),
             };
             match state.db.reorder_categories(&order) {
-                Ok(()) => (StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": "カテゴリの並び順を変更しました"}))).into_response(),
-                Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
+                Ok(()) => Ok((StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": "カテゴリの並び順を変更しました"}))).into_response()),
+                Err(e) => Err(ApiError::Internal(e)),
             }
         }
-        _ => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Unknown action"}))).into_response(),
+        _ => Err(ApiError::validation("action", "Unknown action")),
     }
 }
 
@@ -1095,6 +2917,154 @@ pub struct ArticleAskRequest {
     pub custom_prompt: Option<String>,
 }
 
+// --- Translation API ---
+
+pub const DEFAULT_TRANSLATE_LANG: &str = "ja";
+const TRANSLATE_CACHE_TTL: i64 = 7 * 86400;
+const TRANSLATE_MAX_TITLE_CHARS: usize = 500;
+const TRANSLATE_MAX_DESCRIPTION_CHARS: usize = 5000;
+
+#[derive(Deserialize)]
+pub struct TranslateRequest {
+    pub article_id: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub target_lang: Option<String>,
+}
+
+/// POST /api/articles/translate — translate an article (by id, with its extracted body
+/// when available) or a bare title + description. Cached for 7 days per article and
+/// language; cache hits don't count against the "translate" limit.
+pub async fn handle_translate(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<TranslateRequest>,
+) -> Result<Response, ApiError> {
+    let lang = body.target_lang.as_deref().map(str::trim).filter(|l| !l.is_empty()).unwrap_or(DEFAULT_TRANSLATE_LANG);
+    if lang.len() > 10 || !lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(ApiError::validation("target_lang", "target_lang must be a language code like ja or en"));
+    }
+
+    let (title, description, url, ckey) = match body.article_id.as_deref().filter(|id| !id.is_empty()) {
+        Some(id) => {
+            let article = state
+                .db
+                .get_article_by_id(id)?
+                .ok_or_else(|| ApiError::NotFound("Article not found".into()))?;
+            let ckey = cache_key("translate", &format!("{}|{}", id, lang));
+            (article.title, article.description.unwrap_or_default(), Some(article.url), ckey)
+        }
+        None => {
+            let title = body.title.unwrap_or_default();
+            if title.trim().is_empty() {
+                return Err(ApiError::validation("article_id", "article_id or title is required"));
+            }
+            if title.chars().count() > TRANSLATE_MAX_TITLE_CHARS {
+                return Err(ApiError::validation(
+                    "title",
+                    format!("title must be at most {} characters", TRANSLATE_MAX_TITLE_CHARS),
+                ));
+            }
+            let description = body.description.unwrap_or_default();
+            if description.chars().count() > TRANSLATE_MAX_DESCRIPTION_CHARS {
+                return Err(ApiError::validation(
+                    "description",
+                    format!("description must be at most {} characters", TRANSLATE_MAX_DESCRIPTION_CHARS),
+                ));
+            }
+            let ckey = cache_key("translate", &format!("{}|{}|{}", lang, title, description));
+            (title, description, None, ckey)
+        }
+    };
+
+    if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
+        if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
+            return Ok(Json(val).into_response());
+        }
+    }
+    // Stored translations outlive the cache. Those without a body (the auto-translate
+    // agent only does title and description) go through the full translation below.
+    if let Some(id) = body.article_id.as_deref().filter(|id| !id.is_empty()) {
+        if let Some(stored) = state.db.get_translation(id, lang)?.filter(|t| t.content.is_some()) {
+            let val = serde_json::to_value(claude::ArticleTranslation {
+                title: stored.title,
+                description: stored.description.unwrap_or_default(),
+                content: stored.content,
+                detected_source_lang: stored.source_lang.unwrap_or_default(),
+            })
+            .unwrap_or_default();
+            let _ = state.db.set_cache(&ckey, "translate", &val.to_string(), TRANSLATE_CACHE_TTL);
+            return Ok(Json(val).into_response());
+        }
+    }
+
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "translate")?;
+    if state.api_key.is_empty() {
+        return Err(ApiError::Unavailable("翻訳機能は現在利用できません".into()));
+    }
+
+    let content = match &url {
+        Some(url) => news_core::ogp::fetch_article_content(&state.polite, url).await,
+        None => None,
+    };
+    let result = tokio::time::timeout(
+        Duration::from_secs(60),
+        state.metrics.claude(claude::translate_article(
+            &state.claude,
+            ModelTier::Fast,
+            &title,
+            &description,
+            content.as_deref(),
+            lang,
+        )),
+    )
+    .await;
+    let (translation, model) = match result {
+        Ok(Ok(t)) => (t.value, t.model_used),
+        Ok(Err(e)) => {
+            warn!(error = %e, "Translation failed");
+            return Err(ApiError::upstream("claude", "翻訳に失敗しました。しばらくしてお試しください。"));
+        }
+        Err(_) => return Err(ApiError::timeout("claude", "翻訳がタイムアウトしました")),
+    };
+
+    if let Some(id) = body.article_id.as_deref() {
+        let _ = state.db.store_translation(
+            id,
+            lang,
+            &translation.title,
+            Some(translation.description.as_str()),
+            Some(translation.detected_source_lang.as_str()),
+            Some(model),
+        );
+        if let Some(content) = &translation.content {
+            let _ = state.db.set_translation_content(id, lang, content);
+        }
+    }
+    let val = serde_json::to_value(&translation).unwrap_or_default();
+    let _ = state.db.set_cache(&ckey, "translate", &val.to_string(), TRANSLATE_CACHE_TTL);
+    increment_usage_if_needed(&state.db, &tier, "translate");
+    Ok(Json(val).into_response())
+}
+
+/// GET /api/articles/:id/translations/list — every stored translation of an article.
+pub async fn list_article_translations(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    if state.db.get_article_by_id(&id)?.is_none() {
+        return Err(ApiError::NotFound("Article not found".into()));
+    }
+    let translations = state.db.list_translations(&id)?;
+    let available_languages: Vec<&str> = translations.iter().map(|t| t.language.as_str()).collect();
+    Ok(Json(serde_json::json!({
+        "available_languages": available_languages,
+        "translations": translations,
+    }))
+    .into_response())
+}
+
 // --- Feed Management API ---
 
 #[derive(Deserialize)]
@@ -1102,31 +3072,184 @@ pub struct AddFeedRequest {
     pub url: String,
     pub source: String,
     pub category: String,
+    pub max_articles_per_fetch: Option<u32>,
+    #[serde(default)]
+    pub auto_translate: bool,
 }
 
 #[derive(Deserialize)]
 pub struct UpdateFeedRequest {
     pub enabled: Option<bool>,
+    /// 0 clears the quota (unlimited).
+    pub max_articles_per_fetch: Option<u32>,
+    pub auto_translate: Option<bool>,
+    /// Replaces the feed's keyword → category rules; an empty list removes them.
+    pub category_overrides: Option<Vec<CategoryOverride>>,
+}
+
+#[derive(Deserialize)]
+pub struct TestFeedRequest {
+    pub url: String,
+}
+
+const FEED_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+const FEED_TEST_CACHE_TTL: Duration = Duration::from_secs(60);
+const FEED_TEST_PREVIEW_ARTICLES: usize = 5;
+
+type FeedTestResult = Result<serde_json::Value, String>;
+
+/// Recent `handle_test_feed` results by URL, so repeated clicks don't re-fetch.
+static FEED_TEST_CACHE: LazyLock<std::sync::Mutex<std::collections::HashMap<String, (std::time::Instant, FeedTestResult)>>> =
+    LazyLock::new(Default::default);
+
+pub(crate) async fn test_feed(client: &reqwest::Client, url: &str) -> FeedTestResult {
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from))
+        .ok_or_else(|| "URLが不正です".to_string())?;
+    let config = news_core::feeds::FeedConfig {
+        url: url.to_string(),
+        source: host,
+        category: "general".into(),
+        max_articles_per_fetch: None,
+        category_overrides: Vec::new(),
+        source_meta: Default::default(),
+    };
+    let started = std::time::Instant::now();
+    let preview = tokio::time::timeout(FEED_TEST_TIMEOUT, news_core::feeds::fetch_feed_preview(client, &config))
+        .await
+        .map_err(|_| format!("{}秒以内に取得できませんでした", FEED_TEST_TIMEOUT.as_secs()))?
+        .map_err(|e| e.to_string())?;
+    let mut articles = preview.articles;
+    articles.sort_by_key(|a| std::cmp::Reverse(a.published_at));
+    let latest_published_at = articles.first().map(|a| a.published_at.to_rfc3339());
+    articles.truncate(FEED_TEST_PREVIEW_ARTICLES);
+    Ok(serde_json::json!({
+        "articles": articles,
+        "item_count": preview.item_count,
+        "detected_format": preview.detected_format,
+        "detected_type": preview.detected_type,
+        "feed_title": preview.feed_title,
+        "feed_description": preview.feed_description,
+        "latest_published_at": latest_published_at,
+        "fetch_duration_ms": started.elapsed().as_millis() as u64,
+    }))
+}
+
+/// POST /api/admin/feeds/test — fetch a feed without saving it and preview its newest
+/// articles. Fetch or parse failures are 422.
+pub async fn handle_test_feed(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<TestFeedRequest>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let url = body.url.trim();
+    if url.is_empty() {
+        return Err(ApiError::validation("url", "url is required"));
+    }
+
+    let cached = FEED_TEST_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(url).filter(|(at, _)| at.elapsed() < FEED_TEST_CACHE_TTL).map(|(_, r)| r.clone()));
+    let result = match cached {
+        Some(result) => result,
+        None => {
+            let result = test_feed(&state.http_client, url).await;
+            if let Ok(mut cache) = FEED_TEST_CACHE.lock() {
+                cache.retain(|_, (at, _)| at.elapsed() < FEED_TEST_CACHE_TTL);
+                cache.insert(url.to_string(), (std::time::Instant::now(), result.clone()));
+            }
+            result
+        }
+    };
+
+    match result {
+        Ok(preview) => Ok(Json(preview).into_response()),
+        Err(e) => Err(ApiError::Unprocessable(format!("フィードを取得できませんでした: {}", e))),
+    }
 }
 
 pub async fn list_feeds(
     State(state): State<Arc<AppState>>,
-) -> Response {
+) -> Result<Response, ApiError> {
     // Feed list is public (read-only); mutations still require admin auth
-    match state.db.get_all_feeds() {
-        Ok(feeds) => (StatusCode::OK, Json(serde_json::json!({"feeds": feeds}))).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
-    }
+    let feeds = state.db.get_all_feeds().map_err(ApiError::Internal)?;
+    let mut stats = state.db.feed_stats().unwrap_or_default();
+    let feeds: Vec<serde_json::Value> = feeds
+        .into_iter()
+        .map(|feed| {
+            let feed_stats = stats.remove(&feed.feed_id).unwrap_or_default();
+            let mut value = serde_json::to_value(&feed).unwrap_or_default();
+            if let (Some(obj), Ok(serde_json::Value::Object(extra))) =
+                (value.as_object_mut(), serde_json::to_value(feed_stats))
+            {
+                obj.extend(extra);
+            }
+            value
+        })
+        .collect();
+    Ok((StatusCode::OK, Json(serde_json::json!({"feeds": feeds}))).into_response())
+}
+
+const FEED_HISTORY_LIMIT: i64 = 30;
+
+/// GET /api/admin/feeds/:feed_id/history — the feed's last 30 fetches, newest first.
+pub async fn feed_history(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(feed_id): Path<String>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let feed = find_feed(&state.db, &feed_id)?;
+    let history = state.db.feed_fetch_history(&feed.feed_id, FEED_HISTORY_LIMIT)?;
+    Ok(Json(serde_json::json!({"feed_id": feed.feed_id, "source": feed.source, "history": history})).into_response())
+}
+
+fn find_feed(db: &Db, feed_id: &str) -> Result<DynamicFeed, ApiError> {
+    db.get_all_feeds()?
+        .into_iter()
+        .find(|f| f.feed_id == feed_id)
+        .ok_or_else(|| ApiError::NotFound("フィードが見つかりません".into()))
+}
+
+/// GET /api/admin/feeds/:feed_id/articles?limit=20&cursor= — articles stored under the
+/// feed's source name, newest first. Feeds sharing a source name share these.
+pub async fn feed_articles(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(feed_id): Path<String>,
+    Query(params): Query<SourceArticlesQuery>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let feed = find_feed(&state.db, &feed_id)?;
+    let page = Paginator::new(params.limit.unwrap_or(20), params.cursor.clone());
+    let Page { items: articles, next_cursor, .. } = state.db.get_articles_by_source(&feed.source, &page)?;
+    Ok(Json(ArticlesResponse { articles, next_cursor }).into_response())
+}
+
+/// GET /api/admin/feeds/:feed_id/articles/count — how many articles the feed's source
+/// has stored, in total and in the last 24 hours / 7 days.
+pub async fn feed_article_counts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(feed_id): Path<String>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let feed = find_feed(&state.db, &feed_id)?;
+    let (total, last_24h, last_7d) = state.db.source_article_counts(&feed.source)?;
+    Ok(Json(serde_json::json!({"total": total, "last_24h": last_24h, "last_7d": last_7d})).into_response())
 }
 
 pub async fn add_feed(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<AddFeedRequest>,
-) -> Response {
-    if let Err(resp) = check_admin_auth(&headers, &state) { return resp; }
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
     if body.url.is_empty() || body.source.is_empty() || body.category.is_empty() {
-        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "url, source, category are required"}))).into_response();
+        return Err(ApiError::validation("url", "url, source, category are required"));
     }
     let feed_id = format!("feed-{}", uuid::Uuid::new_v4().to_string().split('-').next().unwrap_or("x"));
     let feed = DynamicFeed {
@@ -1136,10 +3259,13 @@ pub async fn add_feed(
         category: body.category,
         enabled: true,
         added_by: Some("settings".into()),
+        max_articles_per_fetch: body.max_articles_per_fetch.filter(|&n| n > 0),
+        auto_translate: body.auto_translate,
+        category_overrides: None,
     };
     match state.db.put_feed(&feed) {
-        Ok(()) => (StatusCode::OK, Json(serde_json::json!({"status": "ok", "feed_id": feed_id, "message": "フィードを追加しました"}))).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
+        Ok(()) => Ok((StatusCode::OK, Json(serde_json::json!({"status": "ok", "feed_id": feed_id, "message": "フィードを追加しました"}))).into_response()),
+        Err(e) => Err(ApiError::Internal(e)),
     }
 }
 
@@ -1147,11 +3273,11 @@ pub async fn delete_feed(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Path(feed_id): Path<String>,
-) -> Response {
-    if let Err(resp) = check_admin_auth(&headers, &state) { return resp; }
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
     match state.db.delete_feed(&feed_id) {
-        Ok(()) => (StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": "フィードを削除しました"}))).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
+        Ok(()) => Ok((StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": "フィードを削除しました"}))).into_response()),
+        Err(e) => Err(ApiError::Internal(e)),
     }
 }
 
@@ -1160,26 +3286,52 @@ pub async fn update_feed(
     headers: HeaderMap,
     Path(feed_id): Path<String>,
     Json(body): Json<UpdateFeedRequest>,
-) -> Response {
-    if let Err(resp) = check_admin_auth(&headers, &state) { return resp; }
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
     let feeds = match state.db.get_all_feeds() {
         Ok(f) => f,
-        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
+        Err(e) => return Err(ApiError::Internal(e)),
     };
     let feed = match feeds.into_iter().find(|f| f.feed_id == feed_id) {
         Some(f) => f,
-        None => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Feed not found"}))).into_response(),
+        None => return Err(ApiError::NotFound("Feed not found".into())),
+    };
+    if let Some(overrides) = &body.category_overrides {
+        news_core::feeds::validate_category_overrides(overrides, |c| lookup_category(&state.db, c).is_some())
+            .map_err(|e| ApiError::validation("category_overrides", e))?;
+    }
+    let max_articles_per_fetch = match body.max_articles_per_fetch {
+        Some(0) => None,
+        Some(n) => Some(n),
+        None => feed.max_articles_per_fetch,
     };
     let updated = DynamicFeed {
         enabled: body.enabled.unwrap_or(feed.enabled),
+        max_articles_per_fetch,
+        auto_translate: body.auto_translate.unwrap_or(feed.auto_translate),
+        category_overrides: match body.category_overrides.clone() {
+            Some(overrides) if overrides.is_empty() => None,
+            Some(overrides) => Some(overrides),
+            None => feed.category_overrides.clone(),
+        },
         ..feed
     };
     match state.db.put_feed(&updated) {
         Ok(()) => {
-            let label = if updated.enabled { "有効" } else { "無効" };
-            (StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": format!("フィードを{}にしました", label)}))).into_response()
+            let message = if body.enabled.is_none() && body.max_articles_per_fetch.is_some() {
+                "フィードの取得上限を更新しました".to_string()
+            } else if body.enabled.is_none() && body.category_overrides.is_some() {
+                "フィードのカテゴリ振り分けルールを更新しました".to_string()
+            } else if body.enabled.is_none() && body.auto_translate.is_some() {
+                let label = if updated.auto_translate { "有効" } else { "無効" };
+                format!("フィードの自動翻訳を{}にしました", label)
+            } else {
+                let label = if updated.enabled { "有効" } else { "無効" };
+                format!("フィードを{}にしました", label)
+            };
+            Ok((StatusCode::OK, Json(serde_json::json!({"status": "ok", "message": message}))).into_response())
         }
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
+        Err(e) => Err(ApiError::Internal(e)),
     }
 }
 
@@ -1187,33 +3339,38 @@ pub async fn handle_article_questions(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<ArticleQuestionsRequest>,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "questions") {
-        return resp;
-    }
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "questions")?;
 
-    if state.api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "APIキーが設定されていません"})),
-        )
-            .into_response();
+    if state.api_key.is_empty() {
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
     }
 
     // Cache check (include URL for cache key)
     let url_for_key = body.url.as_deref().unwrap_or("");
-    let ckey = cache_key("questions", &format!("{}|{}|{}|{}", body.title, body.description, body.source, url_for_key));
+    let variant = ab_variant(&state, &tier, "questions");
+    let ckey = cache_key(
+        "questions",
+        &format!(
+            "{}|{}|{}|{}{}",
+            body.title,
+            body.description,
+            body.source,
+            url_for_key,
+            ab_cache_suffix(variant.as_ref())
+        ),
+    );
     if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
         if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
-            return (StatusCode::OK, Json(val)).into_response();
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
 
     // Fetch article content if URL provided
     let article_content = if let Some(ref url) = body.url {
         if !url.is_empty() {
-            news_core::ogp::fetch_article_content(&state.http_client, url).await.unwrap_or_default()
+            news_core::ogp::fetch_article_content(&state.polite, url).await.unwrap_or_default()
         } else {
             String::new()
         }
@@ -1221,30 +3378,27 @@ pub async fn handle_article_questions(
         String::new()
     };
 
-    match claude::generate_questions(
-        &state.http_client,
-        &state.api_key,
+    match state.metrics.claude(claude::generate_questions(
+        &state.claude,
+        ModelTier::Quality,
         &body.title,
         &body.description,
         &body.source,
         &article_content,
         body.custom_prompt.as_deref(),
-    )
+        variant.as_ref(),
+    ))
     .await
     {
-        Ok(questions) => {
+        Ok(generated) => {
             increment_usage_if_needed(&state.db, &tier, "questions");
-            let resp_json = serde_json::json!({"questions": questions});
+            let resp_json = serde_json::json!({"questions": generated.value, "model_used": generated.model_used});
             let _ = state.db.set_cache(&ckey, "questions", &resp_json.to_string(), 21600); // 6h
-            (StatusCode::OK, Json(resp_json)).into_response()
+            Ok((StatusCode::OK, Json(resp_json)).into_response())
         }
         Err(e) => {
             warn!(error = %e, "Question generation failed");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "質問の生成に失敗しました。しばらくしてお試しください。"})),
-            )
-                .into_response()
+            Err(ApiError::upstream("claude", "質問の生成に失敗しました。しばらくしてお試しください。"))
         }
     }
 }
@@ -1253,18 +3407,12 @@ pub async fn handle_article_ask(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<ArticleAskRequest>,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "ask") {
-        return resp;
-    }
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "ask")?;
 
     if state.api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "APIキーが設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
     }
 
     // Cache check (include URL for cache key)
@@ -1272,14 +3420,14 @@ pub async fn handle_article_ask(
     let ckey = cache_key("ask", &format!("{}|{}|{}|{}|{}", body.title, body.description, body.source, body.question, url_for_key));
     if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
         if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
-            return (StatusCode::OK, Json(val)).into_response();
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
 
     // Fetch article content if URL provided
     let article_content = if let Some(ref url) = body.url {
         if !url.is_empty() {
-            news_core::ogp::fetch_article_content(&state.http_client, url).await.unwrap_or_default()
+            news_core::ogp::fetch_article_content(&state.polite, url).await.unwrap_or_default()
         } else {
             String::new()
         }
@@ -1288,39 +3436,35 @@ pub async fn handle_article_ask(
     };
 
     // Transform question to positive if needed
-    let positive_question = claude::transform_question_to_positive(
-        &state.http_client,
-        &state.api_key,
+    let positive_question = state.metrics.claude(claude::transform_question_to_positive(
+        &state.claude,
+        ModelTier::Fast,
         &body.question,
-    )
+    ))
     .await
     .unwrap_or_else(|_| body.question.clone());
 
-    match claude::answer_question(
-        &state.http_client,
-        &state.api_key,
+    match state.metrics.claude(claude::answer_question(
+        &state.claude,
+        ModelTier::Quality,
         &body.title,
         &body.description,
         &body.source,
         &positive_question,
         &article_content,
         body.custom_prompt.as_deref(),
-    )
+    ))
     .await
     {
-        Ok(answer) => {
+        Ok(generated) => {
             increment_usage_if_needed(&state.db, &tier, "ask");
-            let resp_json = serde_json::json!({"answer": answer});
+            let resp_json = serde_json::json!({"answer": generated.value, "model_used": generated.model_used});
             let _ = state.db.set_cache(&ckey, "ask", &resp_json.to_string(), 21600); // 6h
-            (StatusCode::OK, Json(resp_json)).into_response()
+            Ok((StatusCode::OK, Json(resp_json)).into_response())
         }
         Err(e) => {
             warn!(error = %e, "Answer generation failed");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "回答の生成に失敗しました。しばらくしてお試しください。"})),
-            )
-                .into_response()
+            Err(ApiError::upstream("claude", "回答の生成に失敗しました。しばらくしてお試しください。"))
         }
     }
 }
@@ -1333,6 +3477,9 @@ pub struct ClassifyRequest {
     pub description: String,
     pub source: String,
     pub category: String,
+    /// When given, the returned tags are stored for this article.
+    #[serde(default)]
+    pub article_id: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -1347,75 +3494,587 @@ pub async fn handle_article_classify(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<ClassifyRequest>,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "classify") {
-        return resp;
-    }
-
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "classify")?;
+
+    // Keyword rules stand in when Claude is unconfigured or failing; their results
+    // are neither cached nor stored as tags.
+    let local = || {
+        let classification =
+            claude::classify_article_local(&body.title, &body.description, &body.source, &body.category);
+        let resp_json = serde_json::json!({
+            "category": classification.category,
+            "reasoning": classification.reasoning,
+            "tags": classification.tags,
+            "source": "local"
+        });
+        Ok((StatusCode::OK, Json(resp_json)).into_response())
+    };
     if state.api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "APIキーが設定されていません"})),
-        )
-            .into_response();
+        return local();
     }
 
     // Cache check
     let ckey = cache_key("classify", &format!("{}|{}|{}", body.title, body.source, body.category));
     if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
         if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
-            return (StatusCode::OK, Json(val)).into_response();
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
 
-    match claude::classify_article(
-        &state.http_client,
-        &state.api_key,
+    match state.metrics.claude(claude::classify_article(
+        &state.claude,
+        ModelTier::Fast,
         &body.title,
         &body.description,
         &body.source,
         &body.category,
-    )
+    ))
     .await
     {
-        Ok(classification) => {
+        Ok(generated) => {
             increment_usage_if_needed(&state.db, &tier, "classify");
+            let classification = generated.value;
+            // Stored tags are shared by everyone, so anonymous and device-only
+            // callers only get the classification back
+            let may_tag = matches!(tier, UserTier::Authenticated { .. } | UserTier::Pro)
+                || check_admin_auth(&headers, &state).is_ok();
+            if let Some(article_id) = body.article_id.as_ref().filter(|_| may_tag) {
+                if let Err(e) = state.db.set_article_tags(article_id, &classification.tags) {
+                    warn!(error = %e, article_id = %article_id, "Failed to store article tags");
+                }
+            }
             let resp_json = serde_json::json!({
                 "category": classification.category,
                 "reasoning": classification.reasoning,
                 "tags": classification.tags
             });
             let _ = state.db.set_cache(&ckey, "classify", &resp_json.to_string(), 86400); // 24h
-            (StatusCode::OK, Json(resp_json)).into_response()
+            Ok((StatusCode::OK, Json(resp_json)).into_response())
+        }
+        Err(e) => {
+            warn!(error = %e, "Classification failed, using keyword rules");
+            local()
+        }
+    }
+}
+
+/// GET /api/articles/:id/sentiment — emotions, subjectivity and bias cues for one
+/// article. Once the analyzer has stored `ai_sentiment`, that value is kept as `overall`
+/// (with confidence 0.5) so the detail view agrees with the list badge.
+pub async fn handle_article_sentiment(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+) -> Result<Response, ApiError> {
+    if !state.db.get_feature_flags()?.sentiment_enabled {
+        return Err(ApiError::Unavailable("感情分析は現在無効になっています".into()));
+    }
+    let ckey = cache_key("sentiment", &article_id);
+    if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
+        if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
+            return Ok((StatusCode::OK, Json(val)).into_response());
+        }
+    }
+
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "sentiment")?;
+    if state.api_key.is_empty() {
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
+    }
+
+    let article = state
+        .db
+        .get_article_by_id(&article_id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+    let article_content = news_core::ogp::fetch_article_content(&state.polite, &article.url)
+        .await
+        .unwrap_or_default();
+
+    match state.metrics.claude(claude::analyze_sentiment(
+        &state.claude,
+        ModelTier::Fast,
+        &article.title,
+        article.description.as_deref().unwrap_or(""),
+        &article_content,
+    ))
+    .await
+    {
+        Ok(generated) => {
+            increment_usage_if_needed(&state.db, &tier, "sentiment");
+            let mut analysis = generated.value;
+            if let Some(stored) = state.db.get_ai_sentiment(&article_id)? {
+                analysis.overall = stored;
+                analysis.confidence = 0.5;
+            }
+            let resp_json = serde_json::json!({
+                "article_id": article_id,
+                "overall": analysis.overall,
+                "confidence": analysis.confidence,
+                "emotions": analysis.emotions,
+                "subjectivity": analysis.subjectivity,
+                "bias_indicators": analysis.bias_indicators,
+                "model_used": generated.model_used,
+            });
+            let _ = state.db.set_cache(&ckey, "sentiment", &resp_json.to_string(), 86400); // 24h
+            Ok((StatusCode::OK, Json(resp_json)).into_response())
         }
         Err(e) => {
-            warn!(error = %e, "Classification failed");
+            warn!(error = %e, "Sentiment analysis failed");
+            Err(ApiError::upstream("claude", "感情分析に失敗しました。しばらくしてお試しください。"))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CompareRequest {
+    pub article_id_a: String,
+    pub article_id_b: String,
+}
+
+const COMPARE_TTL: i64 = 12 * 3600;
+
+/// POST /api/articles/compare — agreements, disagreements and framing differences
+/// between two articles. Comparisons are cached per unordered pair.
+pub async fn handle_compare_articles(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<CompareRequest>,
+) -> Result<Response, ApiError> {
+    let (id_a, id_b) = (body.article_id_a.trim(), body.article_id_b.trim());
+    if id_a.is_empty() || id_b.is_empty() {
+        return Err(ApiError::validation("article_id_a", "比較する記事を2本指定してください"));
+    }
+    if id_a == id_b {
+        return Err(ApiError::validation("article_id_b", "異なる記事を指定してください"));
+    }
+    let tier = extract_user_tier(&headers, &state);
+
+    // Compared and cached in id order; swapped back for the caller's order
+    let swapped = id_a > id_b;
+    let (first, second) = if swapped { (id_b, id_a) } else { (id_a, id_b) };
+    let ckey = cache_key("compare", &format!("{}|{}", first, second));
+    let cached = state
+        .db
+        .get_cache(&ckey)
+        .ok()
+        .flatten()
+        .and_then(|c| serde_json::from_str::<(claude::ArticleComparison, String)>(&c).ok());
+
+    let (comparison, model_used) = match cached {
+        Some(hit) => hit,
+        None => {
+            // Cached comparisons stay available past the limit
+            check_rate_limit(&state.db, &tier, "compare")?;
+            if state.api_key.is_empty() {
+                return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
+            }
+            let not_found = || ApiError::NotFound("記事が見つかりません".into());
+            let a = state.db.get_article_by_id(first)?.ok_or_else(not_found)?;
+            let b = state.db.get_article_by_id(second)?.ok_or_else(not_found)?;
+            let (content_a, content_b) =
+                tokio::join!(article_raw_content(&state, &a, false), article_raw_content(&state, &b, false));
+            let (content_a, content_b) = (content_a.unwrap_or_default(), content_b.unwrap_or_default());
+            let generated = state
+                .metrics
+                .claude(claude::compare_articles(
+                    &state.claude,
+                    ModelTier::Quality,
+                    &a,
+                    &content_a,
+                    &b,
+                    &content_b,
+                ))
+                .await
+                .map_err(|e| {
+                    warn!(error = %e, "Article comparison failed");
+                    ApiError::upstream("claude", "記事の比較に失敗しました。しばらくしてお試しください。")
+                })?;
+            increment_usage_if_needed(&state.db, &tier, "compare");
+            let entry = (generated.value, generated.model_used.to_string());
+            if let Ok(json) = serde_json::to_string(&entry) {
+                let _ = state.db.set_cache(&ckey, "compare", &json, COMPARE_TTL);
+            }
+            entry
+        }
+    };
+
+    let comparison = if swapped { comparison.swapped() } else { comparison };
+    let mut resp_json = serde_json::to_value(&comparison).unwrap_or_default();
+    resp_json["article_id_a"] = id_a.into();
+    resp_json["article_id_b"] = id_b.into();
+    resp_json["model_used"] = model_used.into();
+    Ok((StatusCode::OK, Json(resp_json)).into_response())
+}
+
+/// Articles compared by `handle_compare_coverage`, the requested one included.
+const COVERAGE_MAX_MEMBERS: usize = 4;
+/// Days either side of the article searched for other coverage of its story.
+const COVERAGE_DAYS: i64 = 3;
+
+/// The article and up to `COVERAGE_MAX_MEMBERS - 1` other articles about its story
+/// (see `Db::get_related_timeline`), one per source, closest in time first.
+fn coverage_members(state: &AppState, article: Article) -> Result<Vec<Article>, ApiError> {
+    let mut related: Vec<Article> = state
+        .db
+        .get_related_timeline(&article.id, COVERAGE_DAYS)?
+        .into_iter()
+        .filter(|a| a.id != article.id)
+        .collect();
+    related.sort_by_key(|a| (a.published_at - article.published_at).num_seconds().abs());
+
+    let mut members = vec![article];
+    for candidate in related {
+        if members.len() >= COVERAGE_MAX_MEMBERS {
+            break;
+        }
+        if members.iter().all(|m| m.source != candidate.source) {
+            members.push(candidate);
+        }
+    }
+    Ok(members)
+}
+
+/// POST /api/articles/:id/compare — how the sources covering this article's story
+/// differ. Shares the `compare` limit with the two-article comparison, and is
+/// cached per set of member articles.
+pub async fn handle_compare_coverage(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let article = state
+        .db
+        .get_article_by_id(&id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+    let members = coverage_members(&state, article)?;
+    if members.len() < 2 {
+        return Err(ApiError::Conflict("この記事と同じ話題を報じた他のソースの記事がないため、比較できません".into()));
+    }
+    let tier = extract_user_tier(&headers, &state);
+
+    let mut member_ids: Vec<&str> = members.iter().map(|a| a.id.as_str()).collect();
+    member_ids.sort_unstable();
+    let ckey = cache_key("compare_coverage", &member_ids.join("|"));
+    let cached = state
+        .db
+        .get_cache(&ckey)
+        .ok()
+        .flatten()
+        .and_then(|c| serde_json::from_str::<(claude::CoverageComparison, String)>(&c).ok());
+
+    let (comparison, model_used) = match cached {
+        Some(hit) => hit,
+        None => {
+            // Cached comparisons stay available past the limit
+            check_rate_limit(&state.db, &tier, "compare")?;
+            if state.api_key.is_empty() {
+                return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
+            }
+            let contents = futures::future::join_all(members.iter().map(|a| article_raw_content(&state, a, false))).await;
+            let inputs: Vec<(&Article, String)> =
+                members.iter().zip(contents.into_iter().map(Result::unwrap_or_default)).collect();
+            let generated = state
+                .metrics
+                .claude(claude::compare_coverage(&state.claude, ModelTier::Quality, &inputs))
+                .await
+                .map_err(|e| {
+                    warn!(error = %e, article_id = %id, "Coverage comparison failed");
+                    ApiError::upstream("claude", "報道の比較に失敗しました。しばらくしてお試しください。")
+                })?;
+            increment_usage_if_needed(&state.db, &tier, "compare");
+            let entry = (generated.value, generated.model_used.to_string());
+            if let Ok(json) = serde_json::to_string(&entry) {
+                let _ = state.db.set_cache(&ckey, "compare_coverage", &json, COMPARE_TTL);
+            }
+            entry
+        }
+    };
+
+    let mut resp_json = serde_json::to_value(&comparison).unwrap_or_default();
+    resp_json["article_id"] = id.into();
+    resp_json["members"] = members
+        .iter()
+        .map(|a| serde_json::json!({ "id": a.id, "title": a.title, "source": a.source, "url": a.url }))
+        .collect();
+    resp_json["model_used"] = model_used.into();
+    Ok((StatusCode::OK, Json(resp_json)).into_response())
+}
+
+/// Extracted body of `article` as plain text or (`markdown`) Markdown, as served by
+/// `GET /api/articles/:id/raw-content` and cached for 12 h.
+async fn article_raw_content(state: &AppState, article: &Article, markdown: bool) -> Result<String, ApiError> {
+    let format = if markdown { "markdown" } else { "text" };
+    let ckey = cache_key("raw_content", &format!("{}|{}", article.id, format));
+    if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
+        return Ok(cached);
+    }
+    let html = news_core::ogp::fetch_article_html(&state.polite, &article.url)
+        .await
+        .ok_or_else(|| ApiError::upstream("article", "記事ページを取得できませんでした"))?;
+    let text = if markdown {
+        news_core::ogp::html_to_markdown(&html)
+    } else {
+        news_core::ogp::strip_tags(&news_core::ogp::extract_article_text_up_to(&html, usize::MAX))
+    };
+    let text: String = text.chars().take(RAW_CONTENT_MAX_CHARS).collect();
+    if text.trim().is_empty() {
+        return Err(ApiError::Unprocessable("記事本文を抽出できませんでした".into()));
+    }
+    let _ = state.db.set_cache(&ckey, "raw_content", &text, RAW_CONTENT_TTL);
+    let _ = state.db.update_reading_time(&article.id, &text);
+    Ok(text)
+}
+
+/// Cache key of an article's extracted body text.
+pub(crate) fn article_text_cache_key(article_id: &str) -> String {
+    cache_key("raw_content", &format!("{}|text", article_id))
+}
+
+/// Longest body `GET /api/articles/:id/raw-content` returns, in chars.
+const RAW_CONTENT_MAX_CHARS: usize = 50_000;
+/// Downloads per day per Pro token.
+const RAW_CONTENT_DAILY_LIMIT: i64 = 100;
+const RAW_CONTENT_TTL: i64 = 12 * 3600;
+
+#[derive(Deserialize)]
+pub struct RawContentQuery {
+    /// "text" (default) or "markdown"
+    pub format: Option<String>,
+}
+
+/// GET /api/articles/:id/raw-content — the full extracted article text as a download,
+/// for Pro users feeding it into their own tools. `?format=markdown` keeps headings,
+/// list items and links.
+pub async fn handle_article_raw_content(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+    Query(q): Query<RawContentQuery>,
+) -> Result<Response, ApiError> {
+    let markdown = match q.format.as_deref() {
+        None | Some("text") => false,
+        Some("markdown") => true,
+        Some(_) => return Err(ApiError::validation("format", "format は text か markdown を指定してください")),
+    };
+    let tier = extract_user_tier(&headers, &state);
+    let tier_name = match tier {
+        UserTier::Pro => "pro",
+        UserTier::Authenticated { .. } => "authenticated",
+        UserTier::Free { .. } => "free",
+        UserTier::Anonymous { .. } => "anonymous",
+    };
+    if tier_name != "pro" {
+        return Err(ApiError::RateLimited {
+            feature: "raw_content".into(),
+            limit: 0,
+            used: 0,
+            tier: tier_name,
+            message: "記事本文のダウンロードはProプラン限定の機能です。".into(),
+        });
+    }
+    // Pro has no device ID to meter, so count per subscription token (hashed)
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    let usage_key = format!("pro:{}", cache_key("pro_usage", token));
+    let used = state.db.get_usage(&usage_key, "raw_content").unwrap_or(0);
+    if used >= RAW_CONTENT_DAILY_LIMIT {
+        return Err(ApiError::RateLimited {
+            feature: "raw_content".into(),
+            limit: RAW_CONTENT_DAILY_LIMIT,
+            used,
+            tier: "pro",
+            message: format!("本文ダウンロードの本日の上限（{}回）に達しました。", RAW_CONTENT_DAILY_LIMIT),
+        });
+    }
+
+    let article = state
+        .db
+        .get_article_by_id(&article_id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+    let content = article_raw_content(&state, &article, markdown).await?;
+    let _ = state.db.increment_usage(&usage_key, "raw_content");
+
+    let (content_type, ext) = if markdown {
+        ("text/markdown; charset=utf-8", "md")
+    } else {
+        ("text/plain; charset=utf-8", "txt")
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.{}\"", article_id, ext)),
+        ],
+        content,
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    /// "markdown" (default) or "json"
+    pub format: Option<String>,
+}
+
+/// GET /api/articles/:id/export?format=markdown|json — the article as a note to
+/// download. Free callers get its metadata and description; Pro also gets the AI
+/// summary, keywords, enrichments and the extracted body.
+pub async fn handle_article_export(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+    Query(q): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let format = match q.format.as_deref() {
+        None => ExportFormat::Markdown,
+        Some(f) => ExportFormat::parse(f)
+            .ok_or_else(|| ApiError::validation("format", "format は markdown か json を指定してください"))?,
+    };
+    let article = state
+        .db
+        .get_article_by_id(&article_id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+
+    let pro = matches!(extract_user_tier(&headers, &state), UserTier::Pro);
+    let content = match pro {
+        true => article_raw_content(&state, &article, false).await.ok(),
+        false => None,
+    };
+    let mut bundle = ExportBundle {
+        ai_summary: None,
+        ai_keywords: Vec::new(),
+        enrichments: Vec::new(),
+        content,
+        category: article.category.as_str().to_string(),
+        published_at: article.published_at.to_rfc3339(),
+        id: article.id,
+        title: article.title,
+        url: article.url,
+        canonical_url: article.canonical_url,
+        source: article.source,
+        description: article.description,
+        image_url: article.image_url,
+    };
+    if pro {
+        bundle.ai_summary = state.db.get_ai_summary(&bundle.id)?;
+        bundle.ai_keywords = state.db.get_ai_keywords(&bundle.id)?.unwrap_or_default();
+        bundle.enrichments = state
+            .db
+            .get_enrichments(&bundle.id)?
+            .into_iter()
+            .map(|(_, agent_type, content_type, data_json, _)| ExportEnrichment {
+                agent_type,
+                content_type,
+                data: serde_json::from_str(&data_json).unwrap_or(serde_json::Value::String(data_json)),
+            })
+            .collect();
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "分類に失敗しました"})),
-            )
-                .into_response()
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.{}\"", bundle.id, format.extension()),
+            ),
+        ],
+        article_export::render(&bundle, format),
+    )
+        .into_response())
+}
+
+const READING_MODE_TTL: i64 = 6 * 3600;
+
+const READING_MODE_CSS: &str = "body{max-width:42rem;margin:0 auto;padding:1rem 1.25rem 3rem;\
+font:17px/1.8 -apple-system,BlinkMacSystemFont,'Hiragino Sans','Noto Sans JP',sans-serif;color:#222;background:#fff}\
+h1,h2,h3,h4,h5,h6{line-height:1.4}img{max-width:100%;height:auto;display:block;margin:1rem auto}\
+blockquote{margin:1rem 0;padding:.25rem 1rem;border-left:4px solid #ccc;color:#555}\
+a{color:#0a66c2}.source{color:#777;font-size:.9rem}\
+@media (prefers-color-scheme:dark){body{color:#ddd;background:#111}blockquote{color:#aaa;border-color:#444}a{color:#6cb4ff}}";
+
+/// GET /api/articles/:id/reading-mode — the article body as a clean, self-contained
+/// HTML page for the in-app reader. `Accept: application/json` gets
+/// `{url, title, html}` instead.
+pub async fn handle_article_reading_mode(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+) -> Result<Response, ApiError> {
+    let article = state
+        .db
+        .get_article_by_id(&article_id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+
+    let ckey = cache_key("reading_mode", &article.id);
+    let page = match state.db.get_content_cache(&ckey).ok().flatten() {
+        Some(cached) => cached,
+        None => {
+            // Cached pages stay readable past the limit
+            let tier = extract_user_tier(&headers, &state);
+            check_rate_limit(&state.db, &tier, "reading_mode")?;
+            let html = news_core::ogp::fetch_article_html(&state.polite, &article.url)
+                .await
+                .ok_or_else(|| ApiError::upstream("article", "記事ページを取得できませんでした"))?;
+            let body = news_core::ogp::readable_html(&html);
+            if body.trim().is_empty() {
+                return Err(ApiError::Unprocessable("記事本文を抽出できませんでした".into()));
+            }
+            let page = format!(
+                "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n\
+                 <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+                 <base href=\"{url}\">\n<title>{title}</title>\n<style>{css}</style>\n</head>\n<body>\n\
+                 <article>\n<h1>{title}</h1>\n<p class=\"source\"><a href=\"{url}\" rel=\"noopener\" target=\"_blank\">{source}</a></p>\n\
+                 {body}</article>\n</body>\n</html>\n",
+                url = escape_attr(&article.url),
+                title = escape_attr(&article.title),
+                source = escape_attr(&article.source),
+                css = READING_MODE_CSS,
+            );
+            let _ = state.db.set_content_cache(&ckey, &page, READING_MODE_TTL);
+            increment_usage_if_needed(&state.db, &tier, "reading_mode");
+            page
         }
+    };
+
+    let wants_json = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("application/json"));
+    if wants_json {
+        return Ok((
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "url": article.url,
+                "title": article.title,
+                "html": page,
+            })),
+        )
+            .into_response());
     }
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+            (header::VARY, "Accept"),
+        ],
+        page,
+    )
+        .into_response())
 }
 
 pub async fn handle_action_plan(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<ActionPlanRequest>,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "action_plan") {
-        return resp;
-    }
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "action_plan")?;
 
     if state.api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "APIキーが設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
     }
 
     // Cache check
@@ -1423,14 +4082,14 @@ pub async fn handle_action_plan(
     let ckey = cache_key("action_plan", &format!("{}|{}", body.title, url_for_key));
     if let Ok(Some(cached)) = state.db.get_cache(&ckey) {
         if let Ok(val) = serde_json::from_str::<serde_json::Value>(&cached) {
-            return (StatusCode::OK, Json(val)).into_response();
+            return Ok((StatusCode::OK, Json(val)).into_response());
         }
     }
 
     // Fetch article content if URL provided
     let article_content = if let Some(ref url) = body.url {
         if !url.is_empty() {
-            news_core::ogp::fetch_article_content(&state.http_client, url).await.unwrap_or_default()
+            news_core::ogp::fetch_article_content(&state.polite, url).await.unwrap_or_default()
         } else {
             String::new()
         }
@@ -1440,35 +4099,290 @@ pub async fn handle_action_plan(
 
     let classification = body.classification.as_deref().unwrap_or("general");
 
-    match claude::generate_action_plan(
-        &state.http_client,
-        &state.api_key,
+    match state.metrics.claude(claude::generate_action_plan(
+        &state.claude,
+        ModelTier::Quality,
         &body.title,
         &body.description,
         &article_content,
         classification,
-    )
+    ))
     .await
     {
-        Ok(plan) => {
+        Ok(generated) => {
             increment_usage_if_needed(&state.db, &tier, "action_plan");
+            let plan = generated.value;
             let resp_json = serde_json::json!({
                 "summary": plan.summary,
                 "steps": plan.steps,
                 "tools_or_templates": plan.tools_or_templates
             });
             let _ = state.db.set_cache(&ckey, "action_plan", &resp_json.to_string(), 86400); // 24h
-            (StatusCode::OK, Json(resp_json)).into_response()
+            Ok((StatusCode::OK, Json(resp_json)).into_response())
+        }
+        Err(e) => {
+            warn!(error = %e, "Action plan generation failed");
+            Err(ApiError::upstream("claude", "アクションプランの生成に失敗しました"))
+        }
+    }
+}
+
+// --- Chat API (multi-turn Q&A) ---
+
+#[derive(Deserialize)]
+pub struct ChatRequest {
+    /// New turns to append (a single user message when continuing a session).
+    pub messages: Vec<claude::ChatMessage>,
+    pub context_article_id: Option<String>,
+    pub session_id: Option<String>,
+}
+
+/// Turns sent to Claude per request; older history is still stored.
+const CHAT_MAX_HISTORY: usize = 20;
+/// Estimated tokens of history sent to Claude (see `claude::trim_to_token_budget`).
+const CHAT_TOKEN_BUDGET: usize = 6000;
+const CHAT_MAX_MESSAGE_CHARS: usize = 4000;
+
+/// The part of a chat's `history` sent to Claude: its last `CHAT_MAX_HISTORY` turns
+/// within `CHAT_TOKEN_BUDGET`, starting with a user turn.
+fn chat_window(history: &[claude::ChatMessage]) -> &[claude::ChatMessage] {
+    claude::trim_to_token_budget(&history[history.len().saturating_sub(CHAT_MAX_HISTORY)..], CHAT_TOKEN_BUDGET)
+}
+
+pub async fn handle_chat(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<ChatRequest>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "chat")?;
+
+    if state.api_key.is_empty() {
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
+    }
+
+    if body.messages.is_empty() {
+        return Err(ApiError::validation("messages", "メッセージが空です"));
+    }
+    for m in &body.messages {
+        if m.role != "user" && m.role != "assistant" {
+            return Err(ApiError::validation("messages", format!("Invalid role: {}", m.role)));
+        }
+        if m.content.trim().is_empty() || m.content.chars().count() > CHAT_MAX_MESSAGE_CHARS {
+            return Err(ApiError::validation(
+                "messages",
+                format!("メッセージは1〜{}文字で入力してください", CHAT_MAX_MESSAGE_CHARS),
+            ));
+        }
+    }
+    if body.messages.last().map(|m| m.role.as_str()) != Some("user") {
+        return Err(ApiError::validation("messages", "最後のメッセージはuserである必要があります"));
+    }
+
+    let device_id = match &tier {
+        UserTier::Free { device_id } | UserTier::Authenticated { device_id, .. } => Some(device_id.to_string()),
+        _ => headers.get("x-device-id").and_then(|v| v.to_str().ok()).map(|s| s.to_string()),
+    };
+
+    // Continue an existing session or start a new one
+    let (session_id, mut history, article_id) = match &body.session_id {
+        Some(sid) => {
+            // Article chats (sessions with an owner) are continued through their own endpoint
+            let session = state
+                .db
+                .get_chat_session(sid)?
+                .filter(|s| s.owner.is_none() && (s.device_id.is_none() || s.device_id == device_id))
+                .ok_or_else(|| ApiError::NotFound("チャットセッションが見つかりません".into()))?;
+            let history: Vec<claude::ChatMessage> = serde_json::from_str(&session.messages_json).unwrap_or_default();
+            (sid.clone(), history, session.article_id.or_else(|| body.context_article_id.clone()))
+        }
+        None => (uuid::Uuid::new_v4().to_string(), Vec::new(), body.context_article_id.clone()),
+    };
+    history.extend(body.messages);
+
+    let article = match &article_id {
+        Some(id) => state.db.get_article_by_id(id)?,
+        None => None,
+    };
+
+    let answer = match state.metrics.claude(claude::chat(&state.claude, ModelTier::Quality, chat_window(&history), article.as_ref())).await {
+        Ok(a) => a.value,
+        Err(e) => {
+            warn!(error = %e, "Chat failed");
+            return Err(ApiError::upstream("claude", "回答の生成に失敗しました。しばらくしてお試しください。"));
+        }
+    };
+
+    history.push(claude::ChatMessage {
+        role: "assistant".into(),
+        content: answer.clone(),
+    });
+    let messages_json = serde_json::to_string(&history).map_err(|e| e.to_string())?;
+    state.db.save_chat_session(&session_id, device_id.as_deref(), None, article_id.as_deref(), &messages_json)?;
+
+    increment_usage_if_needed(&state.db, &tier, "chat");
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({"session_id": session_id, "answer": answer})),
+    )
+        .into_response())
+}
+
+// --- Article chat (chat sessions owned by a user or device) ---
+
+#[derive(Deserialize)]
+pub struct ArticleChatRequest {
+    pub article_id: String,
+    pub message: String,
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+}
+
+/// User turns allowed per conversation.
+const CONVERSATION_MAX_TURNS: usize = 10;
+/// Conversations per owner with activity in the last `CONVERSATION_TTL_HOURS`.
+const CONVERSATION_MAX_ACTIVE: i64 = 3;
+/// Conversations idle longer than this are expired and pruned by the cleanup sweep.
+pub const CONVERSATION_TTL_HOURS: i64 = 24;
+
+/// Owner key for per-user rows (conversations, saved searches): the Google user, else
+/// the device.
+fn owner_key(tier: &UserTier, headers: &HeaderMap) -> Result<String, ApiError> {
+    match tier {
+        UserTier::Authenticated { user_id, .. } => Ok(format!("user:{}", user_id)),
+        UserTier::Free { device_id } => Ok(format!("device:{}", device_id)),
+        _ => headers
+            .get("x-device-id")
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+            .map(|v| format!("device:{}", v))
+            .ok_or(ApiError::DeviceIdRequired),
+    }
+}
+
+/// Load a conversation if it exists, belongs to `owner` and has not expired.
+fn owned_conversation(db: &Db, id: &str, owner: &str) -> Result<ChatSession, ApiError> {
+    let cutoff = (chrono::Utc::now() - chrono::Duration::hours(CONVERSATION_TTL_HOURS)).to_rfc3339();
+    match db.get_chat_session(id)? {
+        Some(c) if c.owner.as_deref() == Some(owner) && c.updated_at >= cutoff => Ok(c),
+        _ => Err(ApiError::NotFound("会話が見つからないか、有効期限が切れています".into())),
+    }
+}
+
+/// POST /api/articles/chat — ask about an article, continuing `conversation_id` if given.
+/// Each turn counts against the "ask" limit.
+pub async fn handle_article_chat(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<ArticleChatRequest>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "ask")?;
+
+    if state.api_key.is_empty() {
+        return Err(ApiError::Unavailable("APIキーが設定されていません".into()));
+    }
+    let message = body.message.trim();
+    if message.is_empty() || message.chars().count() > CHAT_MAX_MESSAGE_CHARS {
+        return Err(ApiError::validation(
+            "message",
+            format!("メッセージは1〜{}文字で入力してください", CHAT_MAX_MESSAGE_CHARS),
+        ));
+    }
+    let owner = owner_key(&tier, &headers)?;
+
+    let (conversation_id, mut history) = match &body.conversation_id {
+        Some(id) => {
+            let conversation = owned_conversation(&state.db, id, &owner)?;
+            if conversation.article_id.as_deref() != Some(body.article_id.as_str()) {
+                return Err(ApiError::validation("article_id", "会話の記事と一致しません"));
+            }
+            let history: Vec<claude::ChatMessage> =
+                serde_json::from_str(&conversation.messages_json).unwrap_or_default();
+            let turns = history.iter().filter(|m| m.role == "user").count();
+            if turns >= CONVERSATION_MAX_TURNS {
+                return Err(ApiError::Conflict(format!(
+                    "1つの会話は{}往復までです。新しい会話を始めてください",
+                    CONVERSATION_MAX_TURNS
+                )));
+            }
+            (id.clone(), history)
         }
+        None => {
+            let since = (chrono::Utc::now() - chrono::Duration::hours(CONVERSATION_TTL_HOURS)).to_rfc3339();
+            if state.db.count_active_chat_sessions(&owner, &since)? >= CONVERSATION_MAX_ACTIVE {
+                return Err(ApiError::Conflict(format!(
+                    "同時に進められる会話は{}件までです。しばらくしてからお試しください",
+                    CONVERSATION_MAX_ACTIVE
+                )));
+            }
+            (uuid::Uuid::new_v4().to_string(), Vec::new())
+        }
+    };
+
+    let article = state
+        .db
+        .get_article_by_id(&body.article_id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+
+    history.push(claude::ChatMessage {
+        role: "user".into(),
+        content: message.to_string(),
+    });
+    let answer = match state
+        .metrics
+        .claude(claude::chat(&state.claude, ModelTier::Quality, chat_window(&history), Some(&article)))
+        .await
+    {
+        Ok(a) => a.value,
         Err(e) => {
-            warn!(error = %e, "Action plan generation failed");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "アクションプランの生成に失敗しました"})),
-            )
-                .into_response()
+            warn!(error = %e, "Article chat failed");
+            return Err(ApiError::upstream("claude", "回答の生成に失敗しました。しばらくしてお試しください。"));
         }
-    }
+    };
+
+    history.push(claude::ChatMessage {
+        role: "assistant".into(),
+        content: answer.clone(),
+    });
+    let messages_json = serde_json::to_string(&history).map_err(|e| e.to_string())?;
+    state
+        .db
+        .save_chat_session(&conversation_id, None, Some(&owner), Some(&body.article_id), &messages_json)?;
+    increment_usage_if_needed(&state.db, &tier, "ask");
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({"conversation_id": conversation_id, "answer": answer})),
+    )
+        .into_response())
+}
+
+/// GET /api/articles/chat/:conversation_id — transcript of the caller's conversation.
+pub async fn get_article_chat(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(conversation_id): Path<String>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let owner = owner_key(&tier, &headers)?;
+    let conversation = owned_conversation(&state.db, &conversation_id, &owner)?;
+    let messages: Vec<claude::ChatMessage> = serde_json::from_str(&conversation.messages_json).unwrap_or_default();
+    let turns = messages.iter().filter(|m| m.role == "user").count();
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "conversation_id": conversation_id,
+            "article_id": conversation.article_id,
+            "created_at": conversation.created_at,
+            "updated_at": conversation.updated_at,
+            "turns": turns,
+            "max_turns": CONVERSATION_MAX_TURNS,
+            "messages": messages,
+        })),
+    )
+        .into_response())
 }
 
 // --- TTS API (ElevenLabs proxy) ---
@@ -1490,6 +4404,45 @@ pub struct TtsCloneRequest {
 
 fn default_language() -> String { "Japanese".to_string() }
 
+#[derive(Deserialize)]
+pub struct TtsPreloadRequest {
+    pub article_id: String,
+    #[serde(default = "default_preload_voice")]
+    pub voice_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct ArticleAudioQuery {
+    #[serde(default = "default_preload_voice")]
+    pub voice_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct TtsPreloadStatusQuery {
+    pub article_id: String,
+    #[serde(default = "default_preload_voice")]
+    pub voice_id: String,
+}
+
+fn default_preload_voice() -> String { crate::tts_cache::DEFAULT_VOICE.to_string() }
+
+#[derive(Deserialize)]
+pub struct TtsPreviewQuery {
+    pub voice_id: String,
+}
+
+const TTS_AUDIO_TTL: i64 = 21600; // 6h
+/// How long a "warming"/"failed" preload marker lives; longer than the slowest RunPod cold start.
+const TTS_PRELOAD_MARKER_TTL: i64 = 600;
+const TTS_PRELOAD_ESTIMATED_SECS: u64 = 30;
+
+/// Sample read by the voice picker's preview button.
+pub(crate) const TTS_PREVIEW_TEXT: &str = "こんにちは。ニュースエックスワイジーの音声サンプルです。";
+/// Bump when TTS_PREVIEW_TEXT changes so old samples are regenerated.
+const TTS_PREVIEW_REVISION: u32 = 1;
+/// Previews never go stale on their own; effectively permanent.
+pub(crate) const TTS_PREVIEW_TTL: i64 = 10 * 365 * 86400;
+
 #[derive(Serialize)]
 struct VoiceInfo {
     voice_id: String,
@@ -1764,6 +4717,17 @@ pub async fn handle_tts_voices(State(state): State<Arc<AppState>>) -> Response {
         }
     }
 
+    // Generated previews for providers without a native one. RunPod voices are only
+    // listed once the pre-cache task has generated theirs (no cold start on click).
+    for v in voices.iter_mut().filter(|v| v.preview_url.is_none()) {
+        if is_runpod_voice(&v.voice_id)
+            && !matches!(state.db.get_cache(&tts_preview_key(&v.voice_id)), Ok(Some(_)))
+        {
+            continue;
+        }
+        v.preview_url = Some(tts_preview_url(&v.voice_id));
+    }
+
     let available = !voices.is_empty();
 
     // Sort: cloned → recommended → other
@@ -1795,119 +4759,368 @@ pub async fn handle_tts_voices(State(state): State<Arc<AppState>>) -> Response {
         .into_response()
 }
 
+pub(crate) fn is_runpod_voice(voice_id: &str) -> bool {
+    voice_id.starts_with("cosyvoice:")
+        || voice_id.starts_with("qwen-tts:")
+        || voice_id.starts_with("qwen-omni:")
+}
+
+/// Audio cache key of a voice's preview sample.
+pub(crate) fn tts_preview_key(voice_id: &str) -> String {
+    cache_key("tts_preview", &format!("{}|{}", TTS_PREVIEW_REVISION, voice_id))
+}
+
+fn tts_preview_url(voice_id: &str) -> String {
+    let mut url = reqwest::Url::parse("http://localhost/api/tts/preview").expect("static URL");
+    url.query_pairs_mut().append_pair("voice_id", voice_id);
+    format!("{}?{}", url.path(), url.query().unwrap_or_default())
+}
+
+/// Configured RunPod voices, whose previews the pre-cache task generates.
+pub(crate) fn runpod_voice_ids(state: &AppState) -> Vec<String> {
+    if state.runpod_api_key.is_empty() {
+        return Vec::new();
+    }
+    let mut ids = Vec::new();
+    for (prefix, endpoint, voices) in [
+        ("cosyvoice", &state.cosyvoice_endpoint_id, COSYVOICE_VOICES),
+        ("qwen-tts", &state.qwen_tts_endpoint_id, QWEN_TTS_VOICES),
+        ("qwen-omni", &state.qwen_omni_endpoint_id, QWEN_OMNI_VOICES),
+    ] {
+        if !endpoint.is_empty() {
+            ids.extend(voices.iter().map(|(key, _, _)| format!("{}:{}", prefix, key)));
+        }
+    }
+    ids
+}
+
+/// Whether `voice_id` names a voice of a configured provider. Voices from fixed lists
+/// must be on them; ElevenLabs, Cartesia and Fish voices are listed live by the
+/// provider, so only their id's shape is checked.
+fn is_configured_voice(state: &AppState, voice_id: &str) -> bool {
+    let provider_id = |id: &str| {
+        !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    let listed = |voices: &[(&str, &str, bool)], key: &str| voices.iter().any(|(k, _, _)| *k == key);
+    if let Some(name) = voice_id.strip_prefix("openai:") {
+        return !state.openai_api_key.is_empty() && listed(OPENAI_TTS_VOICES, name);
+    }
+    if let Some(name) = voice_id.strip_prefix("venice:") {
+        return !state.venice_api_key.is_empty() && listed(VENICE_TTS_VOICES, name);
+    }
+    if let Some(rest) = voice_id.strip_prefix("aimlapi:") {
+        return !state.aimlapi_key.is_empty()
+            && AIMLAPI_TTS_MODELS.iter().any(|(voice, model, _, _)| rest == format!("{model}:{voice}"));
+    }
+    if is_runpod_voice(voice_id) {
+        return runpod_voice_ids(state).iter().any(|id| id == voice_id);
+    }
+    if let Some(id) = voice_id.strip_prefix("cartesia:") {
+        return !state.cartesia_api_key.is_empty() && provider_id(id);
+    }
+    if let Some(id) = voice_id.strip_prefix("fish:") {
+        return !state.fish_audio_api_key.is_empty() && provider_id(id);
+    }
+    !state.elevenlabs_api_key.is_empty() && provider_id(voice_id)
+}
+
+/// GET /api/tts/preview?voice_id= — short fixed sample for the voice picker. Each
+/// voice is generated once and then served from the cache; only generating one counts
+/// against the caller's "tts_preview" limit.
+pub async fn handle_tts_preview(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<TtsPreviewQuery>,
+    method: Method,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    if q.voice_id.is_empty() {
+        return Err(ApiError::validation("voice_id", "voice_id is required"));
+    }
+    if !is_configured_voice(&state, &q.voice_id) {
+        return Err(ApiError::NotFound("この音声は利用できません".into()));
+    }
+    let ckey = tts_preview_key(&q.voice_id);
+    let cached = match state.db.get_cache(&ckey) {
+        Ok(Some(b64)) => Some(
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &b64)
+                .map(axum::body::Bytes::from)
+                .map_err(|e| ApiError::Internal(format!("Corrupt preview cache: {e}")))?,
+        ),
+        _ => None,
+    };
+    let bytes = match cached {
+        Some(bytes) => {
+            let mut resp = ranged_response(&method, &headers, "audio/mpeg", bytes);
+            resp.headers_mut().insert(header::CACHE_CONTROL, HeaderValue::from_static("public, max-age=86400"));
+            return Ok(resp);
+        }
+        None if is_runpod_voice(&q.voice_id) => {
+            return Err(ApiError::NotFound("この音声のプレビューは準備中です".into()));
+        }
+        None if method == Method::HEAD || headers.contains_key(header::RANGE) => {
+            return Ok(uncached_audio_response(&method));
+        }
+        None => {
+            let tier = extract_user_tier(&headers, &state);
+            check_rate_limit(&state.db, &tier, "tts_preview")?;
+            let bytes = tokio::time::timeout(
+                Duration::from_secs(15),
+                tts_generate(&state, &q.voice_id, TTS_PREVIEW_TEXT),
+            )
+            .await
+            .map_err(|_| ApiError::timeout("tts", "プレビューの生成がタイムアウトしました"))?
+            .map_err(|e| ApiError::upstream("tts", format!("プレビューの生成に失敗しました: {e}")))?;
+            let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
+            let _ = state.db.set_cache(&ckey, "tts_preview", &b64, TTS_PREVIEW_TTL);
+            increment_usage_if_needed(&state.db, &tier, "tts_preview");
+            bytes
+        }
+    };
+    let mut resp = audio_response(bytes);
+    resp.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=86400"),
+    );
+    Ok(resp)
+}
+
 pub async fn handle_tts(
     State(state): State<Arc<AppState>>,
+    method: Method,
     headers: HeaderMap,
     Json(body): Json<TtsRequest>,
-) -> Response {
-    let raw_text = if body.text.len() > 5000 {
-        &body.text[..5000]
-    } else {
-        &body.text
-    };
+) -> Result<Response, ApiError> {
+    let char_count = body.text.chars().count();
+    if char_count > tts_chunk::MAX_TTS_CHARS {
+        return Err(ApiError::PayloadTooLarge {
+            limit: tts_chunk::MAX_TTS_CHARS,
+            message: format!(
+                "テキストが長すぎます（{}文字）。{}文字以内に分割してリクエストしてください。",
+                char_count,
+                tts_chunk::MAX_TTS_CHARS
+            ),
+        });
+    }
+    let raw_text = body.text.as_str();
 
     // --- Audio cache check BEFORE rate limit (cached audio is free) ---
     let audio_ckey = cache_key("tts_audio", &format!("{}|{}", body.voice_id, raw_text));
     if let Ok(Some(cached_b64)) = state.db.get_cache(&audio_ckey) {
         if let Ok(bytes) = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &cached_b64) {
-            return audio_response(axum::body::Bytes::from(bytes));
+            let mut resp = ranged_response(&method, &headers, "audio/mpeg", bytes.into());
+            resp.headers_mut().insert(header::CACHE_CONTROL, HeaderValue::from_static("private, max-age=3600"));
+            return Ok(resp);
         }
     }
+    // A player probing for the length or seeking never starts a generation
+    if headers.contains_key(header::RANGE) {
+        return Ok(uncached_audio_response(&method));
+    }
 
     // Rate limit only applies to uncached (new generation) requests
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "tts") {
-        return resp;
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "tts")?;
+
+    let (audio_bytes, chunks) = render_tts(&state, &body.voice_id, raw_text).await?;
+
+    // Cache audio (base64, TTL 6h) under the full original text, however many chunks it took
+    let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &audio_bytes);
+    let _ = state.db.set_cache(&audio_ckey, "tts_audio", &b64, TTS_AUDIO_TTL);
+
+    increment_usage_if_needed(&state.db, &tier, "tts");
+    let mut resp = audio_response(audio_bytes);
+    resp.headers_mut().insert("x-tts-chunks", HeaderValue::from(chunks));
+    Ok(resp)
+}
+
+#[derive(Deserialize)]
+pub struct TtsBatchItem {
+    pub id: String,
+    pub text: String,
+    pub voice_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct TtsBatchRequest {
+    pub items: Vec<TtsBatchItem>,
+    /// Optional lower cap than the tier's.
+    pub max_items: Option<usize>,
+}
+
+const TTS_BATCH_MAX_ITEMS: usize = 10;
+const TTS_BATCH_MAX_ITEMS_PRO: usize = 30;
+
+/// POST /api/tts/batch — audio for several texts at once, as a ZIP of `{id}.mp3`.
+/// Cached items are free; each generated item counts against the daily `tts` limit.
+/// Items that fail are left out and listed in `x-tts-failed`.
+pub async fn handle_tts_batch(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<TtsBatchRequest>,
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let tier_cap = if matches!(tier, UserTier::Pro) { TTS_BATCH_MAX_ITEMS_PRO } else { TTS_BATCH_MAX_ITEMS };
+    let cap = body.max_items.map_or(tier_cap, |m| m.min(tier_cap));
+    if body.items.is_empty() {
+        return Err(ApiError::validation("items", "items is empty"));
+    }
+    if body.items.len() > cap {
+        return Err(ApiError::validation("items", format!("一度に生成できるのは{}件までです", cap)));
+    }
+    let mut ids = std::collections::HashSet::new();
+    for item in &body.items {
+        let valid_id = !item.id.is_empty()
+            && item.id.len() <= 64
+            && item.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_id || !ids.insert(item.id.as_str()) {
+            return Err(ApiError::validation("items", format!("Invalid or duplicate id: {}", item.id)));
+        }
+        if item.text.chars().count() > tts_chunk::MAX_TTS_CHARS {
+            return Err(ApiError::validation(
+                "items",
+                format!("{}: テキストは{}文字以内にしてください", item.id, tts_chunk::MAX_TTS_CHARS),
+            ));
+        }
     }
 
-    // --- Cached to-reading conversion (TTL 24h) ---
-    let engine = if body.voice_id.starts_with("qwen-tts:") { "qwen-tts" }
-        else if body.voice_id.starts_with("qwen-omni:") { "qwen-omni" }
-        else if body.voice_id.starts_with("cosyvoice:") { "cosyvoice" }
-        else { "elevenlabs" };
-    let reading_ckey = cache_key("to_reading", &format!("{}|{}", engine, raw_text));
-    let text = if let Ok(Some(cached_reading)) = state.db.get_cache(&reading_ckey) {
-        cached_reading
-    } else if !state.api_key.is_empty() {
-        match claude::convert_to_reading(&state.http_client, &state.api_key, raw_text, engine).await {
-            Ok(reading) => {
-                let _ = state.db.set_cache(&reading_ckey, "to_reading", &reading, 86400);
-                reading
+    // Cache hits first; only misses are generated and counted
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut misses = Vec::new();
+    for item in body.items {
+        let ckey = cache_key("tts_audio", &format!("{}|{}", item.voice_id, item.text));
+        let cached = state
+            .db
+            .get_cache(&ckey)
+            .ok()
+            .flatten()
+            .and_then(|b64| base64::Engine::decode(&base64::engine::general_purpose::STANDARD, b64).ok());
+        match cached {
+            Some(bytes) => files.push((item.id, bytes)),
+            None => misses.push((item, ckey)),
+        }
+    }
+    if !misses.is_empty() {
+        check_rate_limit(&state.db, &tier, "tts")?;
+        if let Some(remaining) = remaining_usage(&state.db, &tier, "tts") {
+            if (misses.len() as i64) > remaining {
+                return Err(ApiError::validation(
+                    "items",
+                    format!("本日のTTS残り回数（{}回）を超えています（未キャッシュ{}件）", remaining, misses.len()),
+                ));
             }
-            Err(_) => raw_text.to_string(),
         }
-    } else {
+    }
+
+    let mut jobs = tokio::task::JoinSet::new();
+    for (item, ckey) in misses {
+        let state = Arc::clone(&state);
+        jobs.spawn(async move {
+            let result = render_tts(&state, &item.voice_id, &item.text).await;
+            if let Ok((bytes, _)) = &result {
+                let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes);
+                let _ = state.db.set_cache(&ckey, "tts_audio", &b64, TTS_AUDIO_TTL);
+            }
+            (item.id, result)
+        });
+    }
+    let mut failed = Vec::new();
+    while let Some(joined) = jobs.join_next().await {
+        match joined {
+            Ok((id, Ok((bytes, _)))) => {
+                increment_usage_if_needed(&state.db, &tier, "tts");
+                files.push((id, bytes.to_vec()));
+            }
+            Ok((id, Err(e))) => {
+                warn!(id = %id, error = %e, "Batch TTS item failed");
+                failed.push(id);
+            }
+            Err(e) => warn!(error = %e, "Batch TTS task panicked"),
+        }
+    }
+    if files.is_empty() {
+        return Err(ApiError::upstream("tts", "全てのTTS生成に失敗しました"));
+    }
+    increment_usage_if_needed(&state.db, &tier, "tts_batch");
+
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    let files: Vec<(String, Vec<u8>)> = files.into_iter().map(|(id, bytes)| (format!("{}.mp3", id), bytes)).collect();
+    let mut resp = (
+        [
+            (header::CONTENT_TYPE, "application/zip"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"tts-batch.zip\""),
+        ],
+        crate::zip_store::build(&files),
+    )
+        .into_response();
+    if !failed.is_empty() {
+        failed.sort();
+        if let Ok(v) = HeaderValue::from_str(&failed.join(",")) {
+            resp.headers_mut().insert("x-tts-failed", v);
+        }
+    }
+    Ok(resp)
+}
+
+/// Reading conversion + synthesis with timeout and failover. Does not touch the audio cache.
+/// Returns the audio and the number of chunks it was generated in.
+async fn render_tts(state: &AppState, voice_id: &str, raw_text: &str) -> Result<(axum::body::Bytes, usize), ApiError> {
+    // --- Cached to-reading conversion (TTL 24h), shared by voices of the same profile ---
+    let text = if state.api_key.is_empty() {
         raw_text.to_string()
+    } else {
+        reading::cached_reading(&state.db, reading::reading_profile(voice_id), raw_text, 86400, |engine| async move {
+            state.metrics.claude(claude::convert_to_reading(&state.claude, ModelTier::Fast, raw_text, engine)).await.map(|g| g.value)
+        })
+        .await
+        .unwrap_or_else(|_| raw_text.to_string())
     };
 
     // --- TTS generation with timeout + failover ---
-    let is_runpod = body.voice_id.starts_with("cosyvoice:")
-        || body.voice_id.starts_with("qwen-tts:")
-        || body.voice_id.starts_with("qwen-omni:");
-    let timeout_secs = if is_runpod { 90 } else { 10 };
+    let is_runpod = is_runpod_voice(voice_id);
+    // Chunks are generated sequentially, so the budget scales with the chunk count
+    let chunk_count = tts_chunk::split_chunks(&text, tts_chunk::max_chunk_chars(voice_id)).len().max(1) as u64;
+    let timeout_secs = (if is_runpod { 90 } else { 10 }) * chunk_count;
 
     let primary_result = tokio::time::timeout(
         Duration::from_secs(timeout_secs),
-        tts_generate(&state, &body.voice_id, &text),
+        tts_generate_chunked(state, voice_id, &text),
     ).await;
 
-    let audio_bytes = match primary_result {
-        Ok(Ok(bytes)) => bytes,
+    match primary_result {
+        Ok(Ok(generated)) => Ok(generated),
         Ok(Err(e)) => {
-            warn!(error = %e, voice = %body.voice_id, "Primary TTS failed, trying failover");
+            warn!(error = %e, voice = %voice_id, "Primary TTS failed, trying failover");
             // RunPod providers don't participate in failover (cold start too slow)
             if is_runpod {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({"error": format!("TTS生成に失敗しました: {}", e)})),
-                ).into_response();
-            }
-            match try_failover(&state, &body.voice_id, &text).await {
-                Ok(bytes) => bytes,
-                Err(resp) => return resp,
+                return Err(ApiError::Internal(format!("TTS生成に失敗しました: {}", e)));
             }
+            try_failover(state, voice_id, &text).await
         }
         Err(_) => {
-            warn!(voice = %body.voice_id, timeout_secs, "Primary TTS timed out, trying failover");
+            warn!(voice = %voice_id, timeout_secs, "Primary TTS timed out, trying failover");
             if is_runpod {
-                return (
-                    StatusCode::GATEWAY_TIMEOUT,
-                    Json(serde_json::json!({"error": "TTS生成がタイムアウトしました。GPUのコールドスタート中の可能性があります。しばらくしてお試しください。"})),
-                ).into_response();
-            }
-            match try_failover(&state, &body.voice_id, &text).await {
-                Ok(bytes) => bytes,
-                Err(resp) => return resp,
+                return Err(ApiError::timeout("tts", "TTS生成がタイムアウトしました。GPUのコールドスタート中の可能性があります。しばらくしてお試しください。"));
             }
+            try_failover(state, voice_id, &text).await
         }
-    };
-
-    // Cache audio (base64, TTL 6h)
-    let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &audio_bytes);
-    let _ = state.db.set_cache(&audio_ckey, "tts_audio", &b64, 21600);
-
-    increment_usage_if_needed(&state.db, &tier, "tts");
-    audio_response(audio_bytes)
+    }
 }
 
 pub async fn handle_tts_clone(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<TtsCloneRequest>,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-    if let Err(resp) = check_rate_limit(&state.db, &tier, "tts") {
-        return resp;
-    }
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "tts")?;
+    let ref_audio = crate::ref_audio::validate(&body.ref_audio)?;
 
     if state.qwen_tts_endpoint_id.is_empty() || state.runpod_api_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "Voice clone is not configured"})),
-        ).into_response();
+        return Err(ApiError::Unavailable("Voice clone is not configured".into()));
     }
+    info!(format = ?ref_audio.format, bytes = ref_audio.bytes, secs = ?ref_audio.duration_secs, "Voice clone request");
 
-    let text = if body.text.len() > 5000 { &body.text[..5000] } else { &body.text };
+    let text = truncate_sentence(&body.text, 5000);
 
     let input = serde_json::json!({
         "text": text,
@@ -1921,64 +5134,336 @@ pub async fn handle_tts_clone(
         runpod_runsync(&state, &state.qwen_tts_endpoint_id, input),
     ).await;
 
-    match result {
-        Ok(Ok(output)) => {
-            match decode_runpod_audio(&output) {
-                Ok(bytes) => {
-                    increment_usage_if_needed(&state.db, &tier, "tts");
-                    audio_response(bytes)
-                }
-                Err(e) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({"error": e})),
-                ).into_response(),
-            }
-        }
-        Ok(Err(e)) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": format!("Voice clone failed: {e}")})),
-        ).into_response(),
-        Err(_) => (
-            StatusCode::GATEWAY_TIMEOUT,
-            Json(serde_json::json!({"error": "Voice clone timed out"})),
-        ).into_response(),
-    }
+    match result {
+        Ok(Ok(output)) => {
+            match decode_runpod_audio(&output) {
+                Ok(bytes) => {
+                    increment_usage_if_needed(&state.db, &tier, "tts");
+                    Ok(audio_response(bytes))
+                }
+                Err(e) => Err(ApiError::Internal(e)),
+            }
+        }
+        Ok(Err(e)) => Err(ApiError::Internal(format!("Voice clone failed: {e}"))),
+        Err(_) => Err(ApiError::timeout("tts", "Voice clone timed out")),
+    }
+}
+
+/// Try failover providers with 5s timeout each.
+async fn try_failover(
+    state: &AppState,
+    current_voice_id: &str,
+    text: &str,
+) -> Result<(axum::body::Bytes, usize), ApiError> {
+    let fallbacks = tts_fallback_chain(state, current_voice_id);
+    for (provider_name, fallback_voice) in &fallbacks {
+        let chunk_count = tts_chunk::split_chunks(text, tts_chunk::max_chunk_chars(fallback_voice)).len().max(1) as u64;
+        match tokio::time::timeout(
+            Duration::from_secs(5 * chunk_count),
+            tts_generate_chunked(state, fallback_voice, text),
+        ).await {
+            Ok(Ok(generated)) => {
+                info!(provider = %provider_name, "TTS failover succeeded");
+                return Ok(generated);
+            }
+            Ok(Err(e)) => {
+                warn!(provider = %provider_name, error = %e, "TTS failover failed");
+            }
+            Err(_) => {
+                warn!(provider = %provider_name, "TTS failover timed out ({}s)", 5 * chunk_count);
+            }
+        }
+    }
+    Err(ApiError::upstream("tts", "全TTSプロバイダが失敗しました"))
+}
+
+/// Serve a TTS audio cache entry by key (used by digest playlists).
+pub async fn handle_tts_cached(
+    State(state): State<Arc<AppState>>,
+    Path(key): Path<String>,
+    method: Method,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    if key.len() != 64 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ApiError::validation("key", "Invalid key"));
+    }
+    // Only synthesized audio; other ai_cache entries aren't served here
+    if let Ok(Some(cached_b64)) = state.db.get_cache_for(&key, "tts_audio") {
+        if let Ok(bytes) = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &cached_b64) {
+            let mut resp = ranged_response(&method, &headers, "audio/mpeg", bytes.into());
+            resp.headers_mut().insert(header::CACHE_CONTROL, HeaderValue::from_static("private, max-age=3600"));
+            return Ok(resp);
+        }
+    }
+    Err(ApiError::NotFound("Audio not cached".into()))
+}
+
+/// Resolve an article to the text `handle_tts` would receive for it and the matching
+/// audio cache key, plus the key of the preload status marker.
+fn tts_preload_keys(state: &AppState, article_id: &str, voice_id: &str) -> Result<(String, String, String), ApiError> {
+    let article = state
+        .db
+        .get_article_by_id(article_id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+    let raw_text = crate::tts_cache::article_tts_text(&article);
+    let audio_ckey = cache_key("tts_audio", &format!("{}|{}", voice_id, raw_text));
+    let marker_ckey = cache_key("tts_preload", &audio_ckey);
+    Ok((raw_text, audio_ckey, marker_ckey))
+}
+
+/// Warm the audio cache for one article in the background so a later `/api/tts` call is instant.
+pub async fn handle_tts_preload(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<TtsPreloadRequest>,
+) -> Result<Response, ApiError> {
+    let (raw_text, audio_ckey, marker_ckey) = tts_preload_keys(&state, &body.article_id, &body.voice_id)?;
+
+    if let Ok(Some(_)) = state.db.get_cache(&audio_ckey) {
+        return Ok(Json(serde_json::json!({"status": "cached"})).into_response());
+    }
+
+    let warming = (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({"status": "warming", "estimated_seconds": TTS_PRELOAD_ESTIMATED_SECS})),
+    );
+
+    // Already in flight: don't charge or spawn a second generation
+    if let Ok(Some(marker)) = state.db.get_cache(&marker_ckey) {
+        if marker == "warming" {
+            return Ok(warming.into_response());
+        }
+    }
+
+    let tier = extract_user_tier(&headers, &state);
+    check_rate_limit(&state.db, &tier, "tts_preload")?;
+
+    let _ = state.db.set_cache(&marker_ckey, "tts_preload", "warming", TTS_PRELOAD_MARKER_TTL);
+    increment_usage_if_needed(&state.db, &tier, "tts_preload");
+
+    let bg = Arc::clone(&state);
+    let article_id = body.article_id;
+    let voice_id = body.voice_id;
+    tokio::spawn(async move {
+        match render_tts(&bg, &voice_id, &raw_text).await {
+            Ok((bytes, _)) => {
+                let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
+                let _ = bg.db.set_cache(&audio_ckey, "tts_audio", &b64, TTS_AUDIO_TTL);
+                let _ = bg.db.set_cache(&marker_ckey, "tts_preload", "done", TTS_PRELOAD_MARKER_TTL);
+                info!(article_id = %article_id, voice = %voice_id, "TTS preload: generated audio");
+            }
+            Err(e) => {
+                warn!(article_id = %article_id, voice = %voice_id, error = %e, "TTS preload failed");
+                let _ = bg.db.set_cache(&marker_ckey, "tts_preload", "failed", TTS_PRELOAD_MARKER_TTL);
+            }
+        }
+    });
+
+    Ok(warming.into_response())
+}
+
+/// Poll a preload: `cached`, `warming`, `failed`, or `not_cached`.
+pub async fn handle_tts_preload_status(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<TtsPreloadStatusQuery>,
+) -> Result<Response, ApiError> {
+    let (_, audio_ckey, marker_ckey) = tts_preload_keys(&state, &params.article_id, &params.voice_id)?;
+
+    let status = if let Ok(Some(_)) = state.db.get_cache(&audio_ckey) {
+        "cached"
+    } else {
+        match state.db.get_cache(&marker_ckey) {
+            Ok(Some(m)) if m == "warming" => "warming",
+            Ok(Some(m)) if m == "failed" => "failed",
+            _ => "not_cached",
+        }
+    };
+
+    Ok((
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(serde_json::json!({"status": status})),
+    )
+        .into_response())
+}
+
+/// GET /api/articles/:id/audio?voice_id=... — the article's cached TTS audio, for
+/// `<audio src>` and podcast enclosures. Never generates; uncached audio is a 404 that
+/// points at the preload endpoint. Supports single `Range: bytes=` requests.
+pub async fn handle_article_audio(
+    State(state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Query(params): Query<ArticleAudioQuery>,
+    method: Method,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let (_, audio_ckey, _) = tts_preload_keys(&state, &article_id, &params.voice_id)?;
+    let bytes = match state.db.get_cache(&audio_ckey) {
+        Ok(Some(b64)) => base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &b64)
+            .map_err(|e| ApiError::Internal(format!("Cached audio decode: {e}")))?,
+        _ => {
+            return Ok((
+                StatusCode::NOT_FOUND,
+                [(header::CACHE_CONTROL, "no-store")],
+                Json(serde_json::json!({
+                    "status": "not_cached",
+                    "preload_url": "/api/tts/preload",
+                    "article_id": article_id,
+                    "voice_id": params.voice_id,
+                })),
+            )
+                .into_response());
+        }
+    };
+
+    let mut resp = ranged_response(&method, &headers, "audio/mpeg", bytes.into());
+    resp.headers_mut().insert(header::CACHE_CONTROL, HeaderValue::from_static("public, max-age=3600"));
+    if let Ok(v) =
+        HeaderValue::from_str(&format!("inline; filename=\"article-{}.mp3\"", article_id.replace(['"', '\\'], "")))
+    {
+        resp.headers_mut().insert(header::CONTENT_DISPOSITION, v);
+    }
+    Ok(resp)
+}
+
+/// Data points in an audio waveform.
+const WAVEFORM_SAMPLES: usize = 200;
+
+/// GET /api/articles/:id/audio-waveform?voice_id=... — peaks of the article's cached
+/// audio for drawing a waveform (`audio::mp3_extract_peaks`). Computed once per audio
+/// blob and cached alongside it; uncached audio is a 404 like `handle_article_audio`.
+pub async fn handle_article_audio_waveform(
+    State(state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Query(params): Query<ArticleAudioQuery>,
+) -> Result<Response, ApiError> {
+    let (_, audio_ckey, _) = tts_preload_keys(&state, &article_id, &params.voice_id)?;
+    let waveform_ckey = cache_key("audio_waveform", &audio_ckey);
+    let cached_headers = [(header::CACHE_CONTROL, "public, max-age=3600")];
+    if let Ok(Some(cached)) = state.db.get_cache(&waveform_ckey) {
+        if let Ok(waveform) = serde_json::from_str::<serde_json::Value>(&cached) {
+            return Ok((cached_headers, Json(waveform)).into_response());
+        }
+    }
+    let Ok(Some(b64)) = state.db.get_cache(&audio_ckey) else {
+        return Err(ApiError::NotFound("音声はまだ生成されていません".into()));
+    };
+    let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &b64)
+        .map_err(|e| ApiError::Internal(format!("Cached audio decode: {e}")))?;
+
+    let peaks = audio::mp3_extract_peaks(&bytes, WAVEFORM_SAMPLES);
+    // Two decimals are plenty for drawing and keep the payload small
+    let peaks: Vec<f32> = peaks.iter().map(|p| (p * 100.0).round() / 100.0).collect();
+    let waveform = serde_json::json!({
+        "peaks": peaks,
+        "duration_ms": audio::mp3_duration_ms(&bytes),
+        "sample_count": peaks.len(),
+    });
+    let _ = state.db.set_cache(&waveform_ckey, "audio_waveform", &waveform.to_string(), TTS_AUDIO_TTL);
+    Ok((cached_headers, Json(waveform)).into_response())
+}
+
+/// A stored body answered the way media players expect: HEAD gets only the headers
+/// (with the full Content-Length), a single `Range: bytes=` gets 206 with
+/// `Content-Range`, an unsatisfiable one 416. Callers add caching headers.
+fn ranged_response(method: &Method, headers: &HeaderMap, content_type: &str, bytes: axum::body::Bytes) -> Response {
+    let total = bytes.len();
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes");
+    if method == Method::HEAD {
+        return builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_LENGTH, total)
+            .body(Body::empty())
+            .unwrap();
+    }
+    let range = headers
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| parse_byte_range(v, total));
+    let resp = match range {
+        Some(Some((start, end))) => builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, total))
+            .body(Body::from(bytes.slice(start..=end))),
+        Some(None) => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", total))
+            .body(Body::empty()),
+        None => builder.status(StatusCode::OK).body(Body::from(bytes)),
+    };
+    resp.unwrap()
+}
+
+/// Answer for audio that isn't cached yet, when the request can't be served by
+/// generating it: HEAD learns the type but no length, a range can't be satisfied.
+fn uncached_audio_response(method: &Method) -> Response {
+    let status = if method == Method::HEAD { StatusCode::OK } else { StatusCode::RANGE_NOT_SATISFIABLE };
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "audio/mpeg")
+        .header(header::ACCEPT_RANGES, "none")
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::empty())
+        .unwrap()
+}
+
+/// GET /api/articles/:id/audio-transcript?voice_id=... — the text the article's audio
+/// was synthesized from, for captions and screen readers. `source` is "cached" when it
+/// is the to-reading conversion (hiragana, translation) and "original" when the audio
+/// was generated from the article text as is. Only reads caches, so it is not metered.
+pub async fn handle_article_audio_transcript(
+    State(state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Query(params): Query<ArticleAudioQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let (raw_text, audio_ckey, _) = tts_preload_keys(&state, &article_id, &params.voice_id)?;
+    let profile = reading::reading_profile(&params.voice_id);
+    if let Ok(Some(reading)) = state.db.get_cache(&reading::reading_cache_key(profile, &raw_text)) {
+        return Ok(Json(serde_json::json!({
+            "transcript": reading,
+            "source": "cached",
+            "engine": profile.engine(),
+        })));
+    }
+    // Audio without a cached reading was synthesized from the raw text (no API key, or
+    // the conversion failed).
+    if let Ok(Some(_)) = state.db.get_cache(&audio_ckey) {
+        return Ok(Json(serde_json::json!({
+            "transcript": raw_text,
+            "source": "original",
+            "engine": profile.engine(),
+        })));
+    }
+    Ok(Json(serde_json::json!({"transcript": null, "reason": "not_yet_generated"})))
 }
 
-/// Try failover providers with 5s timeout each. Returns Ok(bytes) or error Response.
-async fn try_failover(
-    state: &AppState,
-    current_voice_id: &str,
-    text: &str,
-) -> Result<axum::body::Bytes, Response> {
-    let fallbacks = tts_fallback_chain(state, current_voice_id);
-    for (provider_name, fallback_voice) in &fallbacks {
-        match tokio::time::timeout(
-            Duration::from_secs(5),
-            tts_generate(state, fallback_voice, text),
-        ).await {
-            Ok(Ok(bytes)) => {
-                info!(provider = %provider_name, "TTS failover succeeded");
-                return Ok(bytes);
-            }
-            Ok(Err(e)) => {
-                warn!(provider = %provider_name, error = %e, "TTS failover failed");
-            }
-            Err(_) => {
-                warn!(provider = %provider_name, "TTS failover timed out (5s)");
-            }
+/// Parse a single `bytes=start-end`, `bytes=start-` or `bytes=-suffix` range into an
+/// inclusive (start, end). `None` means unsatisfiable.
+fn parse_byte_range(value: &str, total: usize) -> Option<(usize, usize)> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') || total == 0 {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let n: usize = suffix.parse().ok()?;
+            (total.saturating_sub(n), total - 1)
         }
-    }
-    Err((
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(serde_json::json!({"error": "全TTSプロバイダが失敗しました"})),
-    ).into_response())
+        (start, "") => (start.parse().ok()?, total - 1),
+        (start, end) => (start.parse().ok()?, end.parse::<usize>().ok()?.min(total - 1)),
+    };
+    (start <= end && start < total).then_some((start, end))
 }
 
+/// Freshly generated (or not range-servable) audio; see `ranged_response` for cached blobs.
 fn audio_response(bytes: axum::body::Bytes) -> Response {
     Response::builder()
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, "audio/mpeg")
+        .header(header::ACCEPT_RANGES, "none")
         .header(header::CACHE_CONTROL, "private, max-age=3600")
         .body(Body::from(bytes))
         .unwrap()
@@ -2010,6 +5495,36 @@ fn tts_fallback_chain(state: &AppState, current_voice_id: &str) -> Vec<(&'static
 
 /// Core TTS generation — returns audio bytes or error string. No HTTP response logic.
 pub(crate) async fn tts_generate(state: &AppState, voice_id: &str, text: &str) -> Result<axum::body::Bytes, String> {
+    tts_generate_chunked(state, voice_id, text).await.map(|(bytes, _)| bytes)
+}
+
+/// Split long text into provider-sized chunks, synthesize them in order and join the MP3s.
+/// Returns the audio and the number of chunks used.
+async fn tts_generate_chunked(state: &AppState, voice_id: &str, text: &str) -> Result<(axum::body::Bytes, usize), String> {
+    let chunks = tts_chunk::split_chunks(text, tts_chunk::max_chunk_chars(voice_id));
+    if chunks.len() <= 1 {
+        return tts_generate_single(state, voice_id, text).await.map(|b| (b, 1));
+    }
+    let mut parts = Vec::with_capacity(chunks.len());
+    for (i, chunk) in chunks.iter().enumerate() {
+        let bytes = tts_generate_single(state, voice_id, chunk)
+            .await
+            .map_err(|e| format!("chunk {}/{}: {}", i + 1, chunks.len(), e))?;
+        parts.push(bytes);
+    }
+    Ok((tts_chunk::concat_mp3(&parts), chunks.len()))
+}
+
+async fn tts_generate_single(state: &AppState, voice_id: &str, text: &str) -> Result<axum::body::Bytes, String> {
+    let started = std::time::Instant::now();
+    let result = tts_dispatch(state, voice_id, text).await;
+    state
+        .metrics
+        .provider_call("tts", tts_chunk::provider(voice_id), result.is_ok(), started);
+    result
+}
+
+async fn tts_dispatch(state: &AppState, voice_id: &str, text: &str) -> Result<axum::body::Bytes, String> {
     if let Some(voice_name) = voice_id.strip_prefix("openai:") {
         return tts_openai(state, text, voice_name).await;
     }
@@ -2402,92 +5917,204 @@ pub async fn handle_toggle_feature(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<ToggleFeatureRequest>,
-) -> Response {
-    if let Err(resp) = check_admin_auth(&headers, &state) { return resp; }
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
     let feature = body.feature.trim();
     if feature.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Empty feature name"})),
-        )
-            .into_response();
+        return Err(ApiError::validation("feature", "Empty feature name"));
+    }
+
+    if feature == "importance_weights" {
+        let valid = body
+            .extra
+            .as_ref()
+            .and_then(|v| news_core::models::ImportanceWeights::from_json(&v.to_string()));
+        if body.enabled && valid.is_none() {
+            return Err(ApiError::validation(
+                "extra",
+                "extra must be {\"ai\", \"popularity\", \"recency\"} with non-negative numbers",
+            ));
+        }
+    }
+    if feature == "image_degradation"
+        && body.extra.as_ref().is_some_and(|v| ImageDegradationPolicy::from_json(&v.to_string()).is_none())
+    {
+        return Err(ApiError::validation(
+            "extra",
+            "extra must be {\"hours_old\": >= 1, \"percentile\": between 0 and 1, \"restore_score\": >= 0}",
+        ));
+    }
+    if feature == "ab_tests" && body.enabled {
+        ab_tests_extra(body.extra.as_ref())?;
+    }
+    let mut extra = body.extra.as_ref().map(|v| v.to_string());
+    if feature == "cors_origins" {
+        let origins = cors_origins_extra(body.extra.as_ref())?;
+        extra = Some(serde_json::json!({ "origins": origins }).to_string());
     }
 
-    match state.db.set_feature_flag(feature, body.enabled, None) {
+    match state.db.set_feature_flag(feature, body.enabled, extra.as_deref()) {
         Ok(()) => {
+            state.flag_cache.invalidate();
+            if feature == "grouping" {
+                spawn_regroup(&state);
+            }
+            if feature == "cors_origins" {
+                state.cors.reload(&state.db);
+            }
             let label = if body.enabled { "有効" } else { "無効" };
             info!(feature, enabled = body.enabled, "Feature toggled");
-            (
+            Ok((
                 StatusCode::OK,
                 Json(serde_json::json!({
                     "status": "ok",
                     "message": format!("{}を{}にしました。", feature, label)
                 })),
             )
-                .into_response()
+                .into_response())
         }
         Err(e) => {
             warn!(error = %e, feature, "Failed to toggle feature");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": format!("Failed to toggle feature: {}", e)})),
+            Err(ApiError::Internal(format!("Failed to toggle feature: {}", e)))
+        }
+    }
+}
+
+/// Normalized origins of a `cors_origins` toggle: extra must be {"origins": [..]}.
+fn cors_origins_extra(extra: Option<&serde_json::Value>) -> Result<Vec<String>, ApiError> {
+    let invalid = || ApiError::validation("extra", "extra must be {\"origins\": [\"https://host\", ...]}");
+    let list = extra
+        .and_then(|v| v.get("origins"))
+        .and_then(|v| v.as_array())
+        .ok_or_else(invalid)?;
+    list.iter()
+        .map(|v| {
+            let raw = v.as_str().ok_or_else(invalid)?;
+            crate::cors::normalize_origin(raw)
+                .ok_or_else(|| ApiError::validation("extra", format!("Invalid origin: {}", raw)))
+        })
+        .collect()
+}
+
+/// Checks an `ab_tests` toggle: extra maps test names to {"variants": [{name, weight,
+/// prompt_override}]}, each with a positively weighted variant and unique names.
+fn ab_tests_extra(extra: Option<&serde_json::Value>) -> Result<(), ApiError> {
+    let tests: std::collections::HashMap<String, news_core::config::AbTest> = extra
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .ok_or_else(|| {
+            ApiError::validation(
+                "extra",
+                "extra must be {\"test\": {\"variants\": [{\"name\", \"weight\", \"prompt_override\"}]}}",
             )
-                .into_response()
+        })?;
+    for (name, test) in &tests {
+        if !test.variants.iter().any(|v| v.weight.is_finite() && v.weight > 0.0) {
+            return Err(ApiError::validation("extra", format!("{}: no variant has a positive weight", name)));
+        }
+        let mut names: Vec<&str> = test.variants.iter().map(|v| v.name.as_str()).collect();
+        names.sort_unstable();
+        if names.windows(2).any(|w| w[0] == w[1]) || names.iter().any(|n| n.trim().is_empty()) {
+            return Err(ApiError::validation("extra", format!("{}: variant names must be unique and non-empty", name)));
         }
     }
+    Ok(())
+}
+
+/// GET /api/admin/ab-tests — running prompt experiments and devices per variant.
+pub async fn list_ab_tests(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let flags = state.db.get_feature_flags()?;
+    let assignments: Vec<serde_json::Value> = state
+        .db
+        .ab_assignment_counts()?
+        .into_iter()
+        .map(|(test, variant, devices)| serde_json::json!({"test": test, "variant": variant, "devices": devices}))
+        .collect();
+    Ok(Json(serde_json::json!({"tests": flags.ab_tests, "assignments": assignments})).into_response())
 }
 
 pub async fn handle_command(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<CommandRequest>,
-) -> Response {
-    if let Err(resp) = check_admin_auth(&headers, &state) { return resp; }
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
     let command = body.command.trim();
     if command.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Empty command"})),
-        )
-            .into_response();
+        return Err(ApiError::validation("command", "Empty command"));
+    }
+    create_change_from_command(&state, command, None).await
+}
+
+/// POST /api/admin/changes/:id/revise — re-interpret a revised command for a rejected
+/// change. The new change links back to it and is one version higher.
+pub async fn revise_change(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(change_id): Path<String>,
+    Json(body): Json<CommandRequest>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let command = body.command.trim();
+    if command.is_empty() {
+        return Err(ApiError::validation("command", "Empty command"));
+    }
+    let parent = state
+        .db
+        .get_change(&change_id)?
+        .ok_or_else(|| ApiError::NotFound("Change not found".into()))?;
+    if parent.status != ChangeStatus::Rejected {
+        return Err(ApiError::validation("status", "Only rejected changes can be revised"));
     }
+    create_change_from_command(&state, command, Some(&parent)).await
+}
 
+/// Interpret `command` and store the result as a change in preview, or answer with
+/// Claude's explanation when it isn't confident. `parent` is the rejected change
+/// being revised.
+async fn create_change_from_command(
+    state: &AppState,
+    command: &str,
+    parent: Option<&ChangeRequest>,
+) -> Result<Response, ApiError> {
+    let context = parent.map(|p| format!("Revising rejected change: {}", p.interpretation));
     let current_config = match state.db.get_service_config() {
         Ok(c) => c,
         Err(e) => {
             warn!(error = %e, "Failed to load service config");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Failed to load config"})),
-            )
-                .into_response();
+            return Err(ApiError::Internal("Failed to load config".into()));
         }
     };
 
-    let interpretation = match claude::interpret_command(
-        &state.http_client,
-        &state.api_key,
+    let interpretation = match state.metrics.claude(claude::interpret_command(
+        &state.claude,
+        ModelTier::Quality,
         command,
         &current_config,
-    )
+        context.as_deref(),
+    ))
     .await
     {
-        Ok(i) => i,
+        Ok(i) => i.value,
         Err(e) => {
             warn!(error = %e, "Claude API interpretation failed");
-            return (
+            return Ok((
                 StatusCode::OK,
                 Json(serde_json::json!({
                     "type": "error",
                     "message": format!("コマンドの解釈に失敗しました: {}", e)
                 })),
             )
-                .into_response();
+                .into_response());
         }
     };
 
     if interpretation.confidence < 0.7 || interpretation.actions.is_empty() {
-        return (
+        return Ok((
             StatusCode::OK,
             Json(serde_json::json!({
                 "type": "info",
@@ -2495,10 +6122,16 @@ pub async fn handle_command(
                 "confidence": interpretation.confidence
             })),
         )
-            .into_response();
+            .into_response());
     }
 
     let change_id = uuid::Uuid::new_v4().to_string();
+    let diff = preview_diff(
+        &current_config,
+        category_labels(&state.db).as_deref(),
+        &interpretation.actions,
+    );
+    let validation = change_validation::validate_actions(state, &interpretation.actions).await;
     let change = ChangeRequest {
         change_id: change_id.clone(),
         status: ChangeStatus::Preview,
@@ -2506,6 +6139,10 @@ pub async fn handle_command(
         interpretation: interpretation.interpretation.clone(),
         actions: interpretation.actions,
         preview_config: Some(current_config),
+        preview_diff: Some(diff),
+        validation: Some(validation),
+        parent_change_id: parent.map(|p| p.change_id.clone()),
+        version: parent.map_or(1, |p| p.version + 1),
         created_at: chrono::Utc::now().to_rfc3339(),
     };
 
@@ -2513,75 +6150,95 @@ pub async fn handle_command(
         warn!(error = %e, "Failed to save change request");
     }
 
-    (
+    Ok((
         StatusCode::OK,
         Json(serde_json::json!({
             "type": "preview",
             "change_id": change_id,
             "interpretation": interpretation.interpretation,
             "confidence": interpretation.confidence,
-            "actions": change.actions
+            "actions": change.actions,
+            "preview_diff": change.preview_diff,
+            "validation": change.validation,
+            "parent_change_id": change.parent_change_id,
+            "version": change.version
         })),
     )
-        .into_response()
+        .into_response())
 }
 
 pub async fn list_changes(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-) -> Response {
-    if let Err(resp) = check_admin_auth(&headers, &state) { return resp; }
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
     match state.db.list_changes(20) {
         Ok(changes) => {
-            (StatusCode::OK, Json(serde_json::json!({"changes": changes}))).into_response()
+            Ok((StatusCode::OK, Json(serde_json::json!({"changes": changes}))).into_response())
         }
         Err(e) => {
             warn!(error = %e, "Failed to list changes");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Failed to list changes"})),
-            )
-                .into_response()
+            Err(ApiError::Internal("Failed to list changes".into()))
         }
     }
 }
 
+#[derive(Deserialize)]
+pub struct ApplyChangeQuery {
+    /// Apply even though some actions failed validation.
+    #[serde(default)]
+    pub force: bool,
+}
+
 pub async fn apply_change(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Path(change_id): Path<String>,
-) -> Response {
-    if let Err(resp) = check_admin_auth(&headers, &state) { return resp; }
+    Query(params): Query<ApplyChangeQuery>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
     let change = match state.db.get_change(&change_id) {
         Ok(Some(c)) => c,
         Ok(None) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "Change not found"})),
-            )
-                .into_response()
+            return Err(ApiError::validation("change_id", "Change not found"))
         }
         Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": e})),
-            )
-                .into_response()
+            return Err(ApiError::Internal(e))
         }
     };
 
     if change.status != ChangeStatus::Preview {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Change is not in preview status"})),
-        )
-            .into_response();
+        return Err(ApiError::validation("status", "Change is not in preview status"));
+    }
+
+    // Nothing is applied while an action failed validation (e.g. a feed URL that
+    // doesn't fetch), unless the admin insists
+    let failed = change.failed_validations();
+    if !failed.is_empty() && !params.force {
+        let actions: Vec<String> = failed.iter().map(|v| v.action.to_string()).collect();
+        return Err(ApiError::validation(
+            "force",
+            format!("検証に失敗したアクションがあります ({})。force=true で強制適用できます", actions.join(", ")),
+        ));
     }
 
+    // Re-diff against the current state; actions whose preconditions no longer hold
+    // are reported as conflicts and skipped
+    let current_config = state.db.get_service_config()?;
+    let checks = recheck_preview(
+        change.preview_diff.as_deref(),
+        preview_diff(&current_config, category_labels(&state.db).as_deref(), &change.actions),
+    );
+
     let mut applied = 0;
     let mut errors = Vec::new();
+    let mut conflicts = Vec::new();
 
-    for action in &change.actions {
+    for (action, check) in change.actions.iter().zip(checks) {
+        if check.conflict.is_some() {
+            conflicts.push(check);
+            continue;
+        }
         match apply_action(&state.db, action) {
             Ok(()) => applied += 1,
             Err(e) => errors.push(format!("{:?}: {}", action, e)),
@@ -2591,41 +6248,537 @@ pub async fn apply_change(
     let _ = state
         .db
         .update_change_status(&change_id, ChangeStatus::Applied);
+    state.flag_cache.invalidate();
+
+    if change.actions.iter().any(|a| {
+        matches!(a, AdminAction::SetGroupingThreshold { .. })
+            || matches!(a, AdminAction::ToggleFeature { feature, .. } if feature == "grouping")
+    }) {
+        spawn_regroup(&state);
+    }
+
+    info!(change_id = %change_id, applied, errors = errors.len(), conflicts = conflicts.len(), "Change applied");
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": "applied",
+            "applied": applied,
+            "errors": errors,
+            "conflicts": conflicts
+        })),
+    )
+        .into_response())
+}
+
+/// GET /api/admin/changes/:id/diff — what applying the change would do to the current
+/// config as a whole, rather than action by action.
+pub async fn change_diff(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(change_id): Path<String>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let change = state
+        .db
+        .get_change(&change_id)?
+        .ok_or_else(|| ApiError::NotFound("Change not found".into()))?;
+
+    let current_config = state.db.get_service_config()?;
+    let categories = category_labels(&state.db);
+    let (after, after_categories) =
+        apply_actions(&current_config, categories.as_deref(), &change.actions);
+    let mut diff = diff_service_configs(&current_config, &after);
+    if let (Some(before), Some(after)) = (&categories, &after_categories) {
+        diff.category_changes = diff_categories(before, after);
+    }
+    let conflicts: Vec<_> = preview_diff(&current_config, categories.as_deref(), &change.actions)
+        .into_iter()
+        .filter(|d| d.conflict.is_some())
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "change_id": change.change_id,
+            "status": change.status,
+            "diff": diff,
+            "conflicts": conflicts
+        })),
+    )
+        .into_response())
+}
+
+/// GET /api/admin/changes/:id/chain — the change and the rejected changes it revises,
+/// oldest first.
+pub async fn change_chain(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(change_id): Path<String>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let chain = state.db.get_change_chain(&change_id)?;
+    if chain.is_empty() {
+        return Err(ApiError::NotFound("Change not found".into()));
+    }
+    Ok(Json(serde_json::json!({"changes": chain})).into_response())
+}
+
+pub async fn reject_change(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(change_id): Path<String>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    match state
+        .db
+        .update_change_status(&change_id, ChangeStatus::Rejected)
+    {
+        Ok(()) => Ok((
+            StatusCode::OK,
+            Json(serde_json::json!({"status": "rejected"})),
+        )
+            .into_response()),
+        Err(e) => Err(ApiError::Internal(e)),
+    }
+}
+
+#[derive(Serialize)]
+pub struct SystemInfo {
+    pub binary_version: String,
+    pub build_timestamp: String,
+    pub git_sha: Option<String>,
+    pub database_path: String,
+    pub static_dir: String,
+    /// Providers whose API key (or client ID) is set; the keys themselves are never returned.
+    pub configured_providers: Vec<String>,
+    pub tokio_worker_threads: usize,
+    pub uptime_seconds: u64,
+    /// Fresh-article TTS warmup counters, for tuning `TTS_WARM_BUDGET`.
+    pub tts_warm: std::collections::BTreeMap<&'static str, u64>,
+    /// Articles currently hidden because their URL returned 404/410.
+    pub dead_links: i64,
+    /// Articles whose image the maintenance degraded, and ones popularity brought back.
+    pub degraded_images: i64,
+    pub restored_images: i64,
+}
+
+/// GET /api/admin/system/info — version, build and runtime metadata.
+pub async fn get_system_info(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let (degraded_images, restored_images) = state.db.image_degradation_counts()?;
+    let providers = [
+        ("anthropic", &state.api_key),
+        ("elevenlabs", &state.elevenlabs_api_key),
+        ("openai", &state.openai_api_key),
+        ("cartesia", &state.cartesia_api_key),
+        ("fish_audio", &state.fish_audio_api_key),
+        ("aimlapi", &state.aimlapi_key),
+        ("venice", &state.venice_api_key),
+        ("runpod", &state.runpod_api_key),
+        ("stripe", &state.stripe_secret_key),
+        ("google", &state.google_client_id),
+    ];
+    let info = SystemInfo {
+        binary_version: env!("CARGO_PKG_VERSION").to_string(),
+        build_timestamp: option_env!("BUILD_TIMESTAMP").unwrap_or("unknown").to_string(),
+        git_sha: option_env!("GIT_SHA").map(String::from),
+        database_path: state.database_path.clone(),
+        static_dir: state.static_dir.clone(),
+        configured_providers: providers
+            .iter()
+            .filter(|(_, key)| !key.is_empty())
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        tokio_worker_threads: tokio::runtime::Handle::current().metrics().num_workers(),
+        uptime_seconds: state.start_time.elapsed().as_secs(),
+        tts_warm: state.metrics.tts_warm_totals(),
+        dead_links: state.db.count_dead_links()?,
+        degraded_images,
+        restored_images,
+    };
+    Ok(Json(info).into_response())
+}
+
+/// GET /api/admin/maintenance/status — last run of each `cleanup_task` step.
+pub async fn get_maintenance_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let steps = state.db.maintenance_runs()?;
+    Ok(Json(serde_json::json!({"steps": steps})).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct ExplainRequest {
+    /// One of `db::EXPLAIN_QUERIES`.
+    pub query_name: Option<String>,
+}
+
+/// GET /api/admin/db/explain?query_name=… — SQLite's plan for one of the common
+/// article queries, to check index use without shell access to the database.
+pub async fn explain_db_query(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(q): Query<ExplainRequest>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let invalid = || {
+        ApiError::validation(
+            "query_name",
+            format!("query_name must be one of: {}", crate::db::EXPLAIN_QUERIES.join(", ")),
+        )
+    };
+    let name = q.query_name.ok_or_else(invalid)?;
+    let (query, plan) = state.db.explain_query_plan(&name)?.ok_or_else(invalid)?;
+    Ok(Json(serde_json::json!({"query_name": name, "query": query, "plan": plan})).into_response())
+}
+
+/// GET /api/admin/db/indexes — indexes on the articles table with their columns and size.
+pub async fn list_db_indexes(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    Ok(Json(serde_json::json!({"table": "articles", "indexes": state.db.article_indexes()?})).into_response())
+}
+
+/// GET /api/admin/tasks — state of each supervised background task.
+pub async fn list_tasks(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    Ok(Json(serde_json::json!({"tasks": state.tasks.statuses()})).into_response())
+}
+
+/// POST /api/admin/tasks/:name/restart — abort and restart a background task.
+pub async fn restart_task(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    if !state.tasks.restart(&name) {
+        return Err(ApiError::NotFound("タスクが見つかりません".into()));
+    }
+    info!(task = %name, "Background task restart requested");
+    if let Err(e) = state.db.record_admin_audit("tasks.restart", &serde_json::json!({"task": name})) {
+        warn!(error = %e, "Failed to record admin audit");
+    }
+    Ok(Json(serde_json::json!({"success": true, "task": name})).into_response())
+}
+
+/// GET /api/admin/cache/token-stats — hit/miss counts of the Bearer token cache.
+pub async fn token_cache_stats(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    Ok(Json(state.token_cache.stats()).into_response())
+}
+
+/// DELETE /api/admin/articles/:id/dead-link — clear a false-positive dead-link flag.
+/// The article is listed again and the link checker leaves it alone from then on.
+pub async fn unflag_dead_link(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    if !state.db.unflag_dead_link(&article_id)? {
+        return Err(ApiError::NotFound("リンク切れとして登録された記事が見つかりません".into()));
+    }
+    info!(article_id = %article_id, "Dead-link flag cleared");
+    Ok(Json(serde_json::json!({"success": true, "article_id": article_id})).into_response())
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ReanalyzeRequest {
+    pub article_ids: Option<Vec<String>>,
+    /// RFC 3339
+    pub before_analyzed_at: Option<String>,
+    pub category: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// POST /api/admin/articles/reanalyze — clear the AI analysis of the selected
+/// articles (e.g. after a prompt update) so the analyzer redoes them.
+pub async fn handle_reanalyze(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<ReanalyzeRequest>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    if body.article_ids.is_none() && body.before_analyzed_at.is_none() {
+        return Err(ApiError::validation(
+            "article_ids",
+            "article_ids か before_analyzed_at のどちらかを指定してください",
+        ));
+    }
+    let before_analyzed_at = match body.before_analyzed_at.as_deref() {
+        Some(t) => Some(
+            chrono::DateTime::parse_from_rfc3339(t)
+                .map_err(|_| ApiError::validation("before_analyzed_at", "RFC 3339形式で指定してください"))?
+                .with_timezone(&chrono::Utc),
+        ),
+        None => None,
+    };
+    if let Some(c) = body.category.as_deref() {
+        if lookup_category(&state.db, c).is_none() {
+            return Err(ApiError::validation("category", "unknown category"));
+        }
+    }
+    if body.limit.is_some_and(|l| l < 1) {
+        return Err(ApiError::validation("limit", "limit は1以上にしてください"));
+    }
+    let filter = ReanalyzeFilter {
+        article_ids: body.article_ids.clone(),
+        before_analyzed_at,
+        category: body.category.clone(),
+        limit: body.limit,
+    };
+    let queued = state.db.reset_analysis_for_articles(&filter)?;
+    let details = serde_json::json!({"request": body, "queued": queued});
+    if let Err(e) = state.db.record_admin_audit("articles.reanalyze", &details) {
+        warn!(error = %e, "Failed to record admin audit");
+    }
+    info!(queued, "Articles queued for reanalysis");
+    Ok(Json(serde_json::json!({"queued": queued})).into_response())
+}
+
+const IMPORT_AUDIT_ACTION: &str = "articles.import";
+/// Minimum time between two imports; there is one admin secret, so this is global.
+const IMPORT_COOLDOWN_MINUTES: i64 = 10;
+
+/// POST /api/admin/import/articles — bulk import `{"articles": [...]}`, sent as the
+/// JSON body or, for large files, as the `file` field of a multipart form.
+pub async fn handle_import_articles(
+    State(state): State<Arc<AppState>>,
+    request: axum::extract::Request,
+) -> Result<Response, ApiError> {
+    check_admin_auth(request.headers(), &state)?;
+    let cooldown = chrono::Duration::minutes(IMPORT_COOLDOWN_MINUTES);
+    let too_soon = |db: &Db| -> Result<ApiError, ApiError> {
+        let last = db.last_admin_audit_at(IMPORT_AUDIT_ACTION)?.unwrap_or_else(chrono::Utc::now);
+        Ok(ApiError::TooManyRequests {
+            retry_after_secs: (last + cooldown - chrono::Utc::now()).num_seconds().max(1),
+            message: format!("記事のインポートは{IMPORT_COOLDOWN_MINUTES}分に1回までです"),
+        })
+    };
+    // Early out before reading a large upload; the claim below is what enforces it
+    if state.db.last_admin_audit_at(IMPORT_AUDIT_ACTION)?.is_some_and(|last| last + cooldown > chrono::Utc::now()) {
+        return Err(too_soon(&state.db)?);
+    }
 
-    info!(change_id = %change_id, applied, errors = errors.len(), "Change applied");
+    let body = read_import_body(request, &state).await?;
+    let (articles, validation_errors) = article_import::prepare(&body.articles, chrono::Utc::now(), |c| {
+        lookup_category(&state.db, c)
+    });
+    let claim = serde_json::json!({"received": body.articles.len()});
+    let Some(log_id) = state.db.record_admin_audit_unless_recent(IMPORT_AUDIT_ACTION, &claim, cooldown)? else {
+        return Err(too_soon(&state.db)?);
+    };
+    let mut imported = 0;
+    for chunk in articles.chunks(article_import::IMPORT_CHUNK) {
+        imported += state.db.batch_insert_articles(chunk)?;
+    }
+    let skipped_duplicates = articles.len() - imported;
+
+    let details = serde_json::json!({
+        "received": body.articles.len(),
+        "imported": imported,
+        "skipped_duplicates": skipped_duplicates,
+        "validation_errors": validation_errors.len(),
+    });
+    if let Err(e) = state.db.update_admin_audit_details(log_id, &details) {
+        warn!(error = %e, "Failed to record admin audit");
+    }
+    info!(imported, skipped_duplicates, invalid = validation_errors.len(), "Articles imported");
+    Ok(Json(serde_json::json!({
+        "imported": imported,
+        "skipped_duplicates": skipped_duplicates,
+        "validation_errors": validation_errors,
+    }))
+    .into_response())
+}
+
+async fn read_import_body(
+    request: axum::extract::Request,
+    state: &Arc<AppState>,
+) -> Result<ImportArticlesRequest, ApiError> {
+    use axum::extract::FromRequest;
+
+    let rejected = |status: StatusCode, text: String| {
+        if status == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::BodyTooLarge { max_bytes: crate::body_limit::limit_for("/api/admin/import/articles") }
+        } else {
+            ApiError::validation("file", text)
+        }
+    };
+    let is_multipart = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("multipart/form-data"));
+    let bytes = if is_multipart {
+        let mut form = axum::extract::Multipart::from_request(request, state)
+            .await
+            .map_err(|e| rejected(e.status(), e.body_text()))?;
+        let mut file = None;
+        while let Some(field) = form.next_field().await.map_err(|e| rejected(e.status(), e.body_text()))? {
+            if field.name() == Some("file") {
+                file = Some(field.bytes().await.map_err(|e| rejected(e.status(), e.body_text()))?);
+                break;
+            }
+        }
+        file.ok_or_else(|| ApiError::validation("file", "file フィールドにJSONファイルを指定してください"))?
+    } else {
+        axum::body::Bytes::from_request(request, state)
+            .await
+            .map_err(|e| rejected(e.status(), e.body_text()))?
+    };
+    serde_json::from_slice(&bytes)
+        .map_err(|e| ApiError::validation("articles", format!("インポートするJSONを解析できません: {e}")))
+}
+
+#[derive(Deserialize)]
+pub struct HeatmapQuery {
+    pub days: Option<i64>,
+}
+
+const WEEKDAY_LABELS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// GET /api/admin/analytics/heatmap?days=30 — counted article views per weekday
+/// (rows, Sunday first) and hour (columns), in UTC, plus the busiest slot.
+pub async fn get_view_heatmap(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<HeatmapQuery>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let days = params.days.unwrap_or(30).clamp(1, 90);
+    let slots = state.db.get_hourly_view_counts(days)?;
+
+    let mut heatmap = [[0i64; 24]; 7];
+    for (slot, count) in &slots {
+        let parsed = slot
+            .split_once(' ')
+            .and_then(|(d, h)| Some((d.parse::<usize>().ok()?, h.parse::<usize>().ok()?)));
+        if let Some((day, hour)) = parsed.filter(|&(d, h)| d < 7 && h < 24) {
+            heatmap[day][hour] += count;
+        }
+    }
+    let peak_slot = (0..7)
+        .flat_map(|d| (0..24).map(move |h| (d, h)))
+        .filter(|&(d, h)| heatmap[d][h] > 0)
+        .max_by_key(|&(d, h)| (heatmap[d][h], std::cmp::Reverse((d, h))))
+        .map(|(d, h)| format!("{} {:02}", WEEKDAY_LABELS[d], h));
+
+    Ok(Json(serde_json::json!({
+        "days": days,
+        "heatmap": heatmap,
+        "labels": {"days": WEEKDAY_LABELS, "hours": (0..24).collect::<Vec<_>>()},
+        "peak_slot": peak_slot,
+    }))
+    .into_response())
+}
+
+/// Rows read per lock acquisition while streaming an export.
+const EXPORT_PAGE_SIZE: i64 = 1000;
+
+#[derive(Deserialize)]
+pub struct ExportArticlesParams {
+    pub since: Option<String>,
+    pub category: Option<String>,
+}
 
+fn ndjson_response(body: Body, filename: &str) -> Response {
     (
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "status": "applied",
-            "applied": applied,
-            "errors": errors
-        })),
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
     )
         .into_response()
 }
 
-pub async fn reject_change(
+fn ndjson_lines(rows: &[serde_json::Value]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for row in rows {
+        if serde_json::to_writer(&mut out, row).is_ok() {
+            out.push(b'\n');
+        }
+    }
+    out
+}
+
+/// GET /api/admin/export/articles?since=&category= — every article as NDJSON, oldest
+/// first, with the ai_* columns and enrichment counts. The body is streamed page by
+/// page so neither memory nor the DB lock scale with the corpus; compression comes
+/// from the CompressionLayer via Accept-Encoding.
+pub async fn export_articles(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-    Path(change_id): Path<String>,
-) -> Response {
-    if let Err(resp) = check_admin_auth(&headers, &state) { return resp; }
-    match state
-        .db
-        .update_change_status(&change_id, ChangeStatus::Rejected)
-    {
-        Ok(()) => (
-            StatusCode::OK,
-            Json(serde_json::json!({"status": "rejected"})),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": e})),
-        )
-            .into_response(),
-    }
+    Query(params): Query<ExportArticlesParams>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let since = match params.since.as_deref().filter(|s| !s.is_empty()) {
+        Some(s) => Some(
+            chrono::DateTime::parse_from_rfc3339(s)
+                .map_err(|_| ApiError::validation("since", "since must be an ISO 8601 timestamp"))?
+                .with_timezone(&chrono::Utc)
+                .to_rfc3339(),
+        ),
+        None => None,
+    };
+    let category = params.category.filter(|c| !c.is_empty());
+
+    let db = Arc::clone(&state.db);
+    let pages = futures::stream::unfold(Some(None::<(String, String)>), move |cursor| {
+        let db = Arc::clone(&db);
+        let since = since.clone();
+        let category = category.clone();
+        async move {
+            let after = cursor?;
+            let page = tokio::task::spawn_blocking(move || {
+                db.export_articles_page(after.as_ref(), since.as_deref(), category.as_deref(), EXPORT_PAGE_SIZE)
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+            match page {
+                Ok(rows) if rows.is_empty() => None,
+                Ok(rows) => {
+                    let next = rows.last().and_then(|last| {
+                        Some((last["published_at"].as_str()?.to_string(), last["id"].as_str()?.to_string()))
+                    });
+                    let next = next.filter(|_| rows.len() as i64 == EXPORT_PAGE_SIZE).map(Some);
+                    Some((Ok(ndjson_lines(&rows)), next))
+                }
+                Err(e) => {
+                    warn!("Article export failed: {}", e);
+                    Some((Err(std::io::Error::other(e)), None))
+                }
+            }
+        }
+    });
+    Ok(ndjson_response(Body::from_stream(pages), "articles.ndjson"))
+}
+
+/// GET /api/admin/export/usage — usage_limits summed per day and feature, as NDJSON.
+pub async fn export_usage(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let rows = state.db.usage_by_day()?;
+    Ok(ndjson_response(Body::from(ndjson_lines(&rows)), "usage.ndjson"))
 }
 
 // --- Subscription API ---
@@ -2638,13 +6791,9 @@ pub struct SubscribeRequest {
 pub async fn handle_subscribe(
     State(state): State<Arc<AppState>>,
     Json(body): Json<SubscribeRequest>,
-) -> Response {
+) -> Result<Response, ApiError> {
     if state.stripe_secret_key.is_empty() || state.stripe_price_id.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "課金機能が設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("課金機能が設定されていません".into()));
     }
 
     let client_ref = body.device_id.unwrap_or_default();
@@ -2661,18 +6810,14 @@ pub async fn handle_subscribe(
     )
     .await
     {
-        Ok(result) => (
+        Ok(result) => Ok((
             StatusCode::OK,
             Json(serde_json::json!({"url": result.session_url})),
         )
-            .into_response(),
+            .into_response()),
         Err(e) => {
             warn!(error = %e, "Failed to create checkout session");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "チェックアウトの作成に失敗しました"})),
-            )
-                .into_response()
+            Err(ApiError::upstream("stripe", "チェックアウトの作成に失敗しました"))
         }
     }
 }
@@ -2681,7 +6826,7 @@ pub async fn handle_stripe_webhook(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     body: axum::body::Bytes,
-) -> Response {
+) -> Result<Response, ApiError> {
     // Verify signature
     let sig = headers
         .get("stripe-signature")
@@ -2690,96 +6835,49 @@ pub async fn handle_stripe_webhook(
 
     if state.stripe_webhook_secret.is_empty() {
         warn!("Stripe webhook secret not configured — rejecting webhook");
-        return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({"error": "Webhook not configured"}))).into_response();
+        return Err(ApiError::Unauthorized("Webhook not configured".into()));
     }
     if let Err(e) = stripe::verify_webhook_signature(&body, sig, &state.stripe_webhook_secret) {
         warn!(error = %e, "Webhook signature verification failed");
-        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid signature"}))).into_response();
+        return Err(ApiError::validation("stripe-signature", "Invalid signature"));
     }
 
     let event: serde_json::Value = match serde_json::from_slice(&body) {
         Ok(v) => v,
         Err(e) => {
             warn!(error = %e, "Failed to parse webhook body");
-            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid JSON"}))).into_response();
+            return Err(ApiError::validation("body", "Invalid JSON"));
         }
     };
 
+    let event_id = event["id"].as_str().unwrap_or("");
     let event_type = event["type"].as_str().unwrap_or("");
-    info!(event_type, "Stripe webhook received");
-
-    match event_type {
-        "checkout.session.completed" => {
-            let session = &event["data"]["object"];
-            let customer_id = session["customer"].as_str().unwrap_or("");
-            let subscription_id = session["subscription"].as_str().unwrap_or("");
-
-            if !customer_id.is_empty() && !subscription_id.is_empty() {
-                // Generate API token
-                let api_token = uuid::Uuid::new_v4().to_string();
-                // Fetch subscription to get period_end
-                let period_end = fetch_subscription_period_end(
-                    &state.http_client,
-                    &state.stripe_secret_key,
-                    subscription_id,
-                )
-                .await
-                .unwrap_or_else(|_| {
-                    (chrono::Utc::now() + chrono::Duration::days(30)).to_rfc3339()
-                });
+    info!(event_id, event_type, "Stripe webhook received");
 
-                if let Err(e) = state.db.create_subscription(
-                    &api_token,
-                    customer_id,
-                    subscription_id,
-                    &period_end,
-                ) {
-                    warn!(error = %e, "Failed to create subscription in DB");
-                }
-                info!(customer_id, subscription_id, "Subscription created via checkout");
-            }
-        }
-        "customer.subscription.updated" => {
-            let sub = &event["data"]["object"];
-            let sub_id = sub["id"].as_str().unwrap_or("");
-            let status = sub["status"].as_str().unwrap_or("");
-            let period_end = sub["current_period_end"].as_i64().map(|ts| {
-                chrono::DateTime::from_timestamp(ts, 0)
-                    .unwrap_or_default()
-                    .to_rfc3339()
-            });
+    // Retries are acknowledged without touching Stripe's API again
+    if !event_id.is_empty() && state.db.webhook_event_seen(event_id)? {
+        info!(event_id, "Stripe webhook already processed");
+        return Ok((StatusCode::OK, Json(serde_json::json!({"received": true, "duplicate": true}))).into_response());
+    }
 
-            if !sub_id.is_empty() {
-                let _ = state.db.update_subscription_status(
-                    sub_id,
-                    status,
-                    period_end.as_deref(),
-                );
-                info!(sub_id, status, "Subscription updated via webhook");
-            }
-        }
-        "customer.subscription.deleted" => {
-            let sub = &event["data"]["object"];
-            let sub_id = sub["id"].as_str().unwrap_or("");
-            if !sub_id.is_empty() {
-                let _ = state.db.update_subscription_status(sub_id, "canceled", None);
-                info!(sub_id, "Subscription canceled via webhook");
+    let mut period_end = (chrono::Utc::now() + chrono::Duration::days(30)).to_rfc3339();
+    if event_type == "checkout.session.completed" {
+        if let Some(sub_id) = event["data"]["object"]["subscription"].as_str() {
+            if let Ok(end) = fetch_subscription_period_end(&state.http_client, &state.stripe_secret_key, sub_id).await {
+                period_end = end;
             }
         }
-        "invoice.payment_failed" => {
-            let invoice = &event["data"]["object"];
-            let sub_id = invoice["subscription"].as_str().unwrap_or("");
-            if !sub_id.is_empty() {
-                let _ = state.db.update_subscription_status(sub_id, "past_due", None);
-                info!(sub_id, "Subscription payment failed");
-            }
-        }
-        _ => {
-            info!(event_type, "Unhandled webhook event type");
-        }
     }
 
-    (StatusCode::OK, Json(serde_json::json!({"received": true}))).into_response()
+    // A failure answers 500 so Stripe retries the event later
+    let applied = stripe::apply_webhook_event(&state.db, &event, &period_end)?;
+    // Events name the subscription, not its token, so drop every cached tier
+    state.token_cache.clear();
+    if !applied {
+        return Ok((StatusCode::OK, Json(serde_json::json!({"received": true, "duplicate": true}))).into_response());
+    }
+
+    Ok((StatusCode::OK, Json(serde_json::json!({"received": true}))).into_response())
 }
 
 async fn fetch_subscription_period_end(
@@ -2845,13 +6943,9 @@ pub async fn handle_subscription_status(
 pub async fn handle_billing_portal(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-) -> Response {
+) -> Result<Response, ApiError> {
     if state.stripe_secret_key.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "課金機能が設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("課金機能が設定されていません".into()));
     }
 
     let token = headers
@@ -2861,23 +6955,23 @@ pub async fn handle_billing_portal(
         .unwrap_or("");
 
     if token.is_empty() {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({"error": "認証トークンが必要です"})),
-        )
-            .into_response();
+        return Err(ApiError::Unauthorized("認証トークンが必要です".into()));
     }
 
-    let customer_id = match state.db.get_subscription_by_token(token) {
-        Ok(Some((cid, _, _, _))) => cid,
+    let (customer_id, status, period_end) = match state.db.get_subscription_by_token(token) {
+        Ok(Some((cid, _, status, period_end))) => (cid, status, period_end),
         _ => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "サブスクリプションが見つかりません"})),
-            )
-                .into_response();
+            return Err(ApiError::NotFound("サブスクリプションが見つかりません".into()));
         }
     };
+    // A canceled subscription whose paid period is over no longer authorizes anything
+    let expired = period_end
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .map(|end| end <= chrono::Utc::now())
+        .unwrap_or(true);
+    if status == "canceled" && expired {
+        return Err(ApiError::Unauthorized("トークンの有効期限が切れています".into()));
+    }
 
     let return_url = format!("{}/", state.base_url);
     match stripe::create_billing_portal_session(
@@ -2888,96 +6982,63 @@ pub async fn handle_billing_portal(
     )
     .await
     {
-        Ok(url) => (
+        Ok(url) => Ok((
             StatusCode::OK,
             Json(serde_json::json!({"url": url})),
         )
-            .into_response(),
+            .into_response()),
         Err(e) => {
             warn!(error = %e, "Failed to create billing portal session");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "ポータルの作成に失敗しました"})),
-            )
-                .into_response()
+            Err(ApiError::upstream("stripe", "ポータルの作成に失敗しました"))
         }
     }
 }
 
+/// Today's usage and limits for `tier`.
+fn usage_summary(db: &Db, tier: &UserTier) -> Result<UsageSummary, String> {
+    let limits = |limit: fn(&FeatureLimit) -> i64| FEATURE_LIMITS.iter().map(|f| (f.name.to_string(), limit(f))).collect();
+    Ok(match tier {
+        UserTier::Pro => UsageSummary {
+            tier: "pro",
+            usage: Default::default(),
+            limits: Default::default(),
+            trial: None,
+        },
+        UserTier::Authenticated { device_id, .. } => UsageSummary {
+            tier: "authenticated",
+            usage: db.get_all_usage(device_id)?.into_iter().collect(),
+            limits: limits(FeatureLimit::authenticated),
+            trial: None,
+        },
+        UserTier::Free { device_id } => UsageSummary {
+            tier: "free",
+            usage: db.get_all_usage(device_id)?.into_iter().collect(),
+            limits: limits(|f| f.daily_limit),
+            trial: None,
+        },
+        UserTier::Anonymous { trial_key } => {
+            let used = db.get_usage(trial_key, ANON_TRIAL_FEATURE)?;
+            UsageSummary {
+                tier: "anonymous",
+                usage: Default::default(),
+                limits: limits(|f| f.daily_limit),
+                trial: Some(TrialUsage {
+                    limit: ANON_TRIAL_DAILY_LIMIT,
+                    used,
+                    remaining: (ANON_TRIAL_DAILY_LIMIT - used).max(0),
+                }),
+            }
+        }
+    })
+}
+
 pub async fn handle_usage(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
-
-    match tier {
-        UserTier::Pro => {
-            (
-                StatusCode::OK,
-                Json(serde_json::json!({
-                    "tier": "pro",
-                    "usage": {},
-                    "limits": {}
-                })),
-            )
-                .into_response()
-        }
-        UserTier::Authenticated { device_id, .. } => {
-            let usage = state.db.get_all_usage(&device_id).unwrap_or_default();
-            let usage_map: serde_json::Map<String, serde_json::Value> = usage
-                .into_iter()
-                .map(|(f, c)| (f, serde_json::json!(c)))
-                .collect();
-            let limits_map: serde_json::Map<String, serde_json::Value> = FEATURE_LIMITS
-                .iter()
-                .map(|f| (f.name.to_string(), serde_json::json!(f.daily_limit * 2)))
-                .collect();
-            (
-                StatusCode::OK,
-                Json(serde_json::json!({
-                    "tier": "authenticated",
-                    "usage": usage_map,
-                    "limits": limits_map
-                })),
-            )
-                .into_response()
-        }
-        UserTier::Free { device_id } => {
-            let usage = state.db.get_all_usage(&device_id).unwrap_or_default();
-            let usage_map: serde_json::Map<String, serde_json::Value> = usage
-                .into_iter()
-                .map(|(f, c)| (f, serde_json::json!(c)))
-                .collect();
-            let limits_map: serde_json::Map<String, serde_json::Value> = FEATURE_LIMITS
-                .iter()
-                .map(|f| (f.name.to_string(), serde_json::json!(f.daily_limit)))
-                .collect();
-            (
-                StatusCode::OK,
-                Json(serde_json::json!({
-                    "tier": "free",
-                    "usage": usage_map,
-                    "limits": limits_map
-                })),
-            )
-                .into_response()
-        }
-        UserTier::Anonymous => {
-            let limits_map: serde_json::Map<String, serde_json::Value> = FEATURE_LIMITS
-                .iter()
-                .map(|f| (f.name.to_string(), serde_json::json!(f.daily_limit)))
-                .collect();
-            (
-                StatusCode::OK,
-                Json(serde_json::json!({
-                    "tier": "anonymous",
-                    "usage": {},
-                    "limits": limits_map
-                })),
-            )
-                .into_response()
-        }
-    }
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
+    let summary = usage_summary(&state.db, &tier)?;
+    Ok((StatusCode::OK, Json(summary)).into_response())
 }
 
 // --- Google Auth endpoint ---
@@ -2991,13 +7052,9 @@ pub struct GoogleAuthRequest {
 pub async fn handle_google_auth(
     State(state): State<Arc<AppState>>,
     Json(body): Json<GoogleAuthRequest>,
-) -> Response {
+) -> Result<Response, ApiError> {
     if state.google_client_id.is_empty() {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": "Google認証は設定されていません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unavailable("Google認証は設定されていません".into()));
     }
 
     // Verify the ID token with Google's tokeninfo endpoint
@@ -3009,30 +7066,18 @@ pub async fn handle_google_auth(
         Ok(r) => r,
         Err(e) => {
             warn!(error = %e, "Google token verification request failed");
-            return (
-                StatusCode::BAD_GATEWAY,
-                Json(serde_json::json!({"error": "Google認証サーバーに接続できません"})),
-            )
-                .into_response();
+            return Err(ApiError::upstream("google", "Google認証サーバーに接続できません"));
         }
     };
 
     if !resp.status().is_success() {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({"error": "無効なGoogleトークンです"})),
-        )
-            .into_response();
+        return Err(ApiError::Unauthorized("無効なGoogleトークンです".into()));
     }
 
     let token_info: serde_json::Value = match resp.json().await {
         Ok(v) => v,
         Err(_) => {
-            return (
-                StatusCode::BAD_GATEWAY,
-                Json(serde_json::json!({"error": "Google認証レスポンスの解析に失敗しました"})),
-            )
-                .into_response();
+            return Err(ApiError::upstream("google", "Google認証レスポンスの解析に失敗しました"));
         }
     };
 
@@ -3040,21 +7085,13 @@ pub async fn handle_google_auth(
     let aud = token_info["aud"].as_str().unwrap_or("");
     if aud != state.google_client_id {
         warn!(expected = %state.google_client_id, got = %aud, "Google token audience mismatch");
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({"error": "トークンのaudience が一致しません"})),
-        )
-            .into_response();
+        return Err(ApiError::Unauthorized("トークンのaudience が一致しません".into()));
     }
 
     let google_id = match token_info["sub"].as_str() {
         Some(s) => s,
         None => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({"error": "Google IDが取得できません"})),
-            )
-                .into_response();
+            return Err(ApiError::Unauthorized("Google IDが取得できません".into()));
         }
     };
 
@@ -3066,12 +7103,15 @@ pub async fn handle_google_auth(
         .db
         .upsert_user(google_id, email, name, picture, body.device_id.as_deref())
     {
-        Ok((auth_token, user_id, is_new)) => {
+        Ok((auth_token, auth_token_expires_at, user_id, is_new)) => {
             info!(user_id = %user_id, email = %email, is_new = %is_new, "Google auth successful");
-            (
+            // The login replaced the user's previous token
+            state.token_cache.invalidate_user(&user_id);
+            Ok((
                 StatusCode::OK,
                 Json(serde_json::json!({
                     "auth_token": auth_token,
+                    "auth_token_expires_at": auth_token_expires_at,
                     "user": {
                         "id": user_id,
                         "email": email,
@@ -3081,52 +7121,196 @@ pub async fn handle_google_auth(
                     "is_new": is_new,
                 })),
             )
-                .into_response()
+                .into_response())
         }
         Err(e) => {
             warn!(error = %e, "Failed to upsert user");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "ユーザー登録に失敗しました"})),
-            )
-                .into_response()
+            Err(ApiError::Internal("ユーザー登録に失敗しました".into()))
         }
     }
 }
 
 // --- Konami endpoint ---
 
+/// POST /api/auth/refresh — exchange a valid Bearer token for a new one (30 days).
+/// The old token stops working immediately.
+pub async fn handle_auth_refresh(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| ApiError::Unauthorized("認証トークンが必要です".into()))?;
+    state.token_cache.invalidate(token);
+    match state.db.refresh_auth_token(token)? {
+        Some((auth_token, auth_token_expires_at)) => Ok((
+            StatusCode::OK,
+            [(header::CACHE_CONTROL, "no-store")],
+            Json(serde_json::json!({
+                "auth_token": auth_token,
+                "auth_token_expires_at": auth_token_expires_at,
+            })),
+        )
+            .into_response()),
+        None => Err(ApiError::Unauthorized(
+            "トークンが無効または期限切れです。再度ログインしてください。".into(),
+        )),
+    }
+}
+
 pub async fn handle_konami(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-) -> Response {
-    let tier = extract_user_tier(&headers, &state.db);
+) -> Result<Response, ApiError> {
+    let tier = extract_user_tier(&headers, &state);
     match tier {
         UserTier::Authenticated { user_id, .. } => {
             match state.db.claim_konami(&user_id) {
-                Ok(true) => (
+                Ok(true) => Ok((
                     StatusCode::OK,
                     Json(serde_json::json!({"success": true, "message": "コナミコマンド発動！1000トークンを獲得しました！"})),
                 )
-                    .into_response(),
-                Ok(false) => (
-                    StatusCode::CONFLICT,
-                    Json(serde_json::json!({"success": false, "message": "コナミコマンドは既に使用済みです"})),
-                )
-                    .into_response(),
-                Err(e) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({"error": e})),
-                )
-                    .into_response(),
+                    .into_response()),
+                Ok(false) => Err(ApiError::Conflict("コナミコマンドは既に使用済みです".into())),
+                Err(e) => Err(ApiError::Internal(e)),
             }
         }
-        _ => (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({"error": "Googleログインが必要です"})),
+        _ => Err(ApiError::Unauthorized("Googleログインが必要です".into())),
+    }
+}
+
+// --- Account (data export / deletion) ---
+
+/// Resolve the Google-auth Bearer token to (user_id, stored device_id).
+fn account_from_headers(headers: &HeaderMap, db: &Db) -> Result<(String, Option<String>), ApiError> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| ApiError::Unauthorized("Googleログインが必要です".into()))?;
+    match db.get_user_by_auth_token(token)? {
+        Some((user_id, _, _, _, device_id, _)) => Ok((user_id, device_id)),
+        None => Err(ApiError::Unauthorized("認証トークンが無効です".into())),
+    }
+}
+
+/// Pro (Stripe) tokens aren't linked to users; the client passes its own in `x-pro-token`.
+fn pro_token_header(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-pro-token")
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// GET /api/account/export — JSON bundle of everything stored about the caller.
+/// ai_cache entries are keyed by content, not by user, so none are attributable.
+pub async fn handle_account_export(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let (user_id, device_id) = account_from_headers(&headers, &state.db)?;
+    let pro_token = pro_token_header(&headers);
+    let mut data = state
+        .db
+        .export_account(&user_id, device_id.as_deref(), pro_token.as_deref())?;
+    data["exported_at"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CACHE_CONTROL, "no-store"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"news-xyz-export.json\""),
+        ],
+        Json(data),
+    )
+        .into_response())
+}
+
+/// DELETE /api/account — cancel any active subscription, delete device/token-keyed
+/// rows, then the user row (which invalidates the auth token). If any step fails the
+/// user row is kept so the request can be retried, and the response lists what failed.
+pub async fn handle_account_delete(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let (user_id, device_id) = account_from_headers(&headers, &state.db)?;
+    let pro_token = pro_token_header(&headers);
+
+    let mut deleted = serde_json::Map::new();
+    let mut failed = serde_json::Map::new();
+
+    // Cancel the Stripe subscription first; keep its row if that fails
+    let mut subscription_canceled = false;
+    let mut keep_subscription = false;
+    if let Some(token) = &pro_token {
+        if let Some((_, sub_id, status, _)) = state.db.get_subscription_by_token(token)? {
+            if status == "active" || status == "past_due" {
+                if state.stripe_secret_key.is_empty() {
+                    keep_subscription = true;
+                    failed.insert("stripe".into(), "Stripe is not configured".into());
+                } else {
+                    match stripe::cancel_subscription(&state.http_client, &state.stripe_secret_key, &sub_id).await {
+                        Ok(()) => subscription_canceled = true,
+                        Err(e) => {
+                            keep_subscription = true;
+                            failed.insert("stripe".into(), e.into());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let token_for_delete = if keep_subscription { None } else { pro_token.as_deref() };
+    state.token_cache.invalidate_user(&user_id);
+    if let Some(token) = token_for_delete {
+        state.token_cache.invalidate(token);
+    }
+    for (table, result) in state.db.delete_account_data(&user_id, device_id.as_deref(), token_for_delete) {
+        match result {
+            Ok(n) => {
+                // A table can be cleared in several steps (e.g. user- and device-owned rows)
+                let prev = deleted.get(table).and_then(|v| v.as_u64()).unwrap_or(0);
+                deleted.insert(table.into(), (prev + n as u64).into());
+            }
+            Err(e) => {
+                failed.insert(table.into(), e.into());
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        warn!(user_id = %user_id, failed = ?failed, "Account deletion partially failed");
+        return Ok((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "code": "partial_failure",
+                "message": "アカウントの一部データを削除できませんでした。再度お試しください。",
+                "details": {
+                    "deleted": deleted,
+                    "failed": failed,
+                    "subscription_canceled": subscription_canceled,
+                },
+            })),
         )
-            .into_response(),
+            .into_response());
     }
+
+    let n = state.db.delete_user(&user_id)?;
+    deleted.insert("users".into(), n.into());
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": "deleted",
+            "deleted": deleted,
+            "subscription_canceled": subscription_canceled,
+            "message": "アカウントとデータを削除しました",
+        })),
+    )
+        .into_response())
 }
 
 // --- Config endpoint (returns Google Client ID for frontend) ---
@@ -3147,6 +7331,133 @@ pub async fn handle_config(
         .into_response()
 }
 
+// --- Bootstrap bundle ---
+
+/// Articles in the bootstrap bundle, as many as `GET /api/articles` returns by default.
+const BOOTSTRAP_ARTICLE_LIMIT: i64 = 30;
+
+#[derive(Deserialize)]
+pub struct BootstrapQuery {
+    pub category: Option<String>,
+}
+
+fn client_config(state: &AppState) -> ClientConfig {
+    let ai = !state.api_key.is_empty();
+    let tts_keys = [
+        &state.elevenlabs_api_key,
+        &state.openai_api_key,
+        &state.cartesia_api_key,
+        &state.fish_audio_api_key,
+        &state.aimlapi_key,
+        &state.venice_api_key,
+        &state.runpod_api_key,
+    ];
+    ClientConfig {
+        google_client_id: state.google_client_id.clone(),
+        features: ClientFeatures {
+            ai,
+            sentiment: ai && state.db.get_feature_flags().is_ok_and(|f| f.sentiment_enabled),
+            tts: tts_keys.iter().any(|k| !k.is_empty()),
+            pro_checkout: !state.stripe_secret_key.is_empty() && !state.stripe_price_id.is_empty(),
+        },
+    }
+}
+
+/// First page for `category` (source-balanced when None), with mute rules and group
+/// collapsing applied as `GET /api/articles` does.
+fn bootstrap_articles(
+    state: &AppState,
+    category: Option<&str>,
+    mutes: &[MuteRule],
+    grouping_enabled: bool,
+) -> Result<ArticlesSection, String> {
+    let category = match category {
+        Some(c) => Some(lookup_category(&state.db, c).ok_or_else(|| format!("unknown category: {c}"))?),
+        None => None,
+    };
+    let limit = BOOTSTRAP_ARTICLE_LIMIT;
+    let fetch_limit = if mutes.is_empty() { limit } else { limit * MUTE_OVERFETCH };
+    let Page { items, next_cursor, .. } = state.db.query_filtered_articles(
+        category.as_ref(),
+        &ArticleFilter::default(),
+        &Paginator::uncapped(fetch_limit, None),
+        grouping_enabled,
+        false,
+    )?;
+    let (mut articles, next_cursor, _) = apply_mutes(items, next_cursor, mutes, limit, MuteResume::Keyset);
+    if category.is_none() {
+        articles = balance_page(articles);
+    }
+    let source_meta = state.db.source_meta_map().unwrap_or_default();
+    Ok(ArticlesSection {
+        category: category.map(|c| c.as_str().to_string()),
+        articles: articles
+            .into_iter()
+            .map(|article| BootstrapArticle {
+                source_meta: source_meta.get(&article.source).cloned().unwrap_or_else(|| SourceMeta::unknown(&article.source)),
+                article,
+            })
+            .collect(),
+        next_cursor,
+    })
+}
+
+/// GET /api/bootstrap?category= — categories, the first page of articles, client config
+/// and, for callers with a device id or token, their usage, in one response (see
+/// `bootstrap`). The ETag covers the newest article, the feature flags and every
+/// caller-specific input, so a matching `If-None-Match` gets a 304 without the
+/// articles being queried.
+pub async fn handle_bootstrap(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<BootstrapQuery>,
+) -> Response {
+    let category = params.category.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    let identified = headers.contains_key("x-device-id") || headers.contains_key(header::AUTHORIZATION);
+    let categories = Section::from(visible_categories(&state.db));
+    let config = client_config(&state);
+    let usage = identified.then(|| Section::from(usage_summary(&state.db, &extract_user_tier(&headers, &state))));
+    let mutes = caller_mute_rules(&headers, &state);
+    let flags = state.db.get_feature_flags().unwrap_or_default();
+
+    let etag = state.db.newest_article_published_at().ok().map(|newest| {
+        let inputs = [
+            serde_json::to_string(&categories),
+            serde_json::to_string(&config),
+            serde_json::to_string(&usage),
+            serde_json::to_string(&mutes),
+            serde_json::to_string(&flags),
+        ]
+        .map(Result::unwrap_or_default);
+        let mut parts = vec![category.unwrap_or("")];
+        parts.extend(inputs.iter().map(String::as_str));
+        bootstrap::etag(newest.as_deref().unwrap_or(""), &parts)
+    });
+    let inm = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok());
+    let cache_headers = |etag: &str| {
+        [
+            (header::CACHE_CONTROL, HeaderValue::from_static("private, no-cache")),
+            (header::ETAG, HeaderValue::from_str(etag).unwrap_or(HeaderValue::from_static(""))),
+        ]
+    };
+    if let (Some(etag), Some(inm)) = (&etag, inm) {
+        if crate::static_files::etag_matches(inm, etag) {
+            return (StatusCode::NOT_MODIFIED, cache_headers(etag)).into_response();
+        }
+    }
+
+    let body = bootstrap::Bootstrap {
+        categories,
+        articles: bootstrap_articles(&state, category, &mutes, flags.grouping_enabled).into(),
+        config,
+        usage,
+    };
+    match etag {
+        Some(etag) => (StatusCode::OK, cache_headers(&etag), Json(body)).into_response(),
+        None => (StatusCode::OK, [(header::CACHE_CONTROL, "private, no-cache")], Json(body)).into_response(),
+    }
+}
+
 // --- Telemetry endpoint (fire-and-forget from sendBeacon) ---
 
 pub async fn handle_telemetry(body: axum::body::Bytes) -> Response {
@@ -3194,6 +7505,9 @@ fn apply_action(db: &Db, action: &AdminAction) -> Result<(), String> {
                 category: category.clone(),
                 enabled: true,
                 added_by: Some("admin-chat".into()),
+                max_articles_per_fetch: None,
+                auto_translate: false,
+                category_overrides: None,
             };
             db.put_feed(&feed)
         }
@@ -3211,52 +7525,242 @@ fn apply_action(db: &Db, action: &AdminAction) -> Result<(), String> {
             let max_order = db.get_categories().map(|cats| cats.len() as i32).unwrap_or(0);
             db.put_category(id, label_ja, "", max_order)
         }
-        AdminAction::RemoveCategory { id } => db.delete_category(id),
-        AdminAction::RenameCategory { id, label_ja } => db.rename_category(id, label_ja),
+        AdminAction::RemoveCategory { id, reassign_to } => db.delete_category(id, reassign_to.as_deref()),
+        AdminAction::RenameCategory { id, label_ja, new_id } => db.rename_category(id, label_ja, new_id.as_deref()),
         AdminAction::ReorderCategories { order } => db.reorder_categories(order),
     }
 }
 
-fn update_feed_enabled(db: &Db, feed_id: &str, enabled: bool) -> Result<(), String> {
-    let feeds = db.get_all_feeds()?;
-    let feed = feeds
-        .into_iter()
-        .find(|f| f.feed_id == feed_id)
-        .ok_or_else(|| format!("Feed not found: {}", feed_id))?;
-    let updated = DynamicFeed { enabled, ..feed };
-    db.put_feed(&updated)
+/// Regroup the recent window in the background after a grouping flag/threshold change.
+fn spawn_regroup(state: &AppState) {
+    let db = Arc::clone(&state.db);
+    let groups = Arc::clone(&state.group_states);
+    tokio::task::spawn_blocking(move || crate::fetcher::regroup_recent(&db, &groups));
+}
+
+/// Whether `id` is in the categories table.
+pub(crate) fn category_exists(db: &Db, id: &str) -> bool {
+    db.get_categories().is_ok_and(|cats| cats.iter().any(|(c, ..)| c == id))
+}
+
+/// Ordered `(id, label_ja)` categories for change previews.
+fn category_labels(db: &Db) -> Option<Vec<(String, String)>> {
+    db.get_categories()
+        .ok()
+        .map(|cats| cats.into_iter().map(|(id, label_ja, ..)| (id, label_ja)).collect())
+}
+
+fn update_feed_enabled(db: &Db, feed_id: &str, enabled: bool) -> Result<(), String> {
+    let feeds = db.get_all_feeds()?;
+    let feed = feeds
+        .into_iter()
+        .find(|f| f.feed_id == feed_id)
+        .ok_or_else(|| format!("Feed not found: {}", feed_id))?;
+    let updated = DynamicFeed { enabled, ..feed };
+    db.put_feed(&updated)
+}
+
+// --- SEO / OGP per-domain ---
+
+const SITES_TOML: &str = include_str!("../../../sites.toml");
+
+/// Sites compiled in from sites.toml; forced (and validated) at startup in main.rs.
+pub static SITES: LazyLock<SitesConfig> =
+    LazyLock::new(|| SitesConfig::from_toml(SITES_TOML).expect("invalid sites.toml"));
+
+/// Resolve the site for the request's Host header: DB override, then sites.toml,
+/// then the default site. Unknown hosts never 404.
+fn detect_site(state: &AppState, headers: &HeaderMap) -> SiteMeta {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .map(normalize_host)
+        .unwrap_or_default();
+    match state.db.get_site(&host) {
+        Ok(Some(site)) => site,
+        _ => SITES.lookup(&host).clone(),
+    }
+}
+
+/// Per-response CSP nonce, set as a response extension by the SSR handlers and turned
+/// into a `Content-Security-Policy` header by the layer in main.rs.
+#[derive(Clone)]
+pub struct CspNonce(pub String);
+
+/// Base64 of 16 random bytes.
+pub fn generate_csp_nonce() -> String {
+    let mut bytes = [0u8; 16];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        bytes = *uuid::Uuid::new_v4().as_bytes();
+    }
+    base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes)
 }
 
-// --- SEO / OGP per-domain ---
+/// CSP for a response carrying a `CspNonce`; other responses get none. Inline styles
+/// stay allowed for the critical CSS in index.html; Google Identity Services loads its
+/// script, stylesheet and iframe from accounts.google.com/gsi/ and calls back to it;
+/// murmurs play as `data:audio/wav` URLs.
+pub fn csp_header<B>(res: &axum::http::Response<B>) -> Option<HeaderValue> {
+    let CspNonce(nonce) = res.extensions().get::<CspNonce>()?;
+    HeaderValue::from_str(&format!(
+        "default-src 'self'; script-src 'self' 'nonce-{nonce}' https://accounts.google.com/gsi/client; \
+         style-src 'self' 'unsafe-inline' https://accounts.google.com/gsi/style; \
+         frame-src https://accounts.google.com/gsi/; img-src * data:; \
+         connect-src 'self' https://api.anthropic.com https://accounts.google.com/gsi/; font-src 'self'; \
+         media-src 'self' blob: data:; frame-ancestors 'none'"
+    ))
+    .ok()
+}
 
-struct SiteMeta {
-    _site_id: &'static str,
-    name: &'static str,
-    title: &'static str,
-    description: &'static str,
-    description_long: &'static str,
-    url: &'static str,
-    image: &'static str,
-    theme_color: &'static str,
-    lang: &'static str,
-    keywords: &'static str,
-}
-
-const SITE_META: SiteMeta = SiteMeta {
-    _site_id: "xyz",
-    name: "news.xyz",
-    title: "news.xyz \u{2014} AI News, Blazing Fast | Built in Rust",
-    description: "The $56,000 domain running the fastest AI news aggregator. 146+ feeds, AI summaries, voice news, 8 themes. Rust-powered. Ad-free.",
-    description_long: "The $56,000 domain running the fastest AI news aggregator. 146+ RSS feeds, AI summaries, Q&A, voice news, podcast generation, 8 themes. Built entirely in Rust. Ad-free.",
-    url: "https://news.xyz/",
-    image: "https://news.xyz/icons/og-xyz.png",
-    theme_color: "#1a1a2e",
-    lang: "en",
-    keywords: "news,AI,artificial intelligence,news aggregator,AI summary,voice news,tech news,breaking news,Rust,56000 dollar domain",
-};
+#[derive(Deserialize)]
+pub struct PlaceholderImageQuery {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub category: String,
+}
+
+/// Gradient of a category's card, matching `.article-img-wrap[data-category]` in base.css.
+fn category_gradient(category: &str) -> (&'static str, &'static str) {
+    match Category::from_str(category) {
+        Some(Category::Business) => ("#2193b0", "#6dd5ed"),
+        Some(Category::Entertainment) => ("#e44d26", "#f16529"),
+        Some(Category::Sports) => ("#11998e", "#38ef7d"),
+        Some(Category::Science) => ("#0f2027", "#2c5364"),
+        Some(Category::General) => ("#434343", "#000000"),
+        Some(Category::Podcast) => ("#7b2ff7", "#c471f5"),
+        Some(Category::Tech | Category::Custom(_)) | None => ("#667eea", "#764ba2"),
+    }
+}
+
+/// Title characters per line of a placeholder card.
+const PLACEHOLDER_LINE_CHARS: usize = 25;
+
+/// GET /api/placeholder-image?title=…&source=…&category=… — 1200×630 SVG card for
+/// articles without an og:image: category gradient, source initial, and the first 50
+/// characters of the title.
+pub async fn handle_placeholder_image(Query(params): Query<PlaceholderImageQuery>) -> Response {
+    let (from, to) = category_gradient(&params.category);
+    let source = params.source.trim();
+    let initial: String = source.chars().next().map(|c| c.to_uppercase().collect()).unwrap_or_default();
+
+    let mut title: Vec<char> = params.title.trim().chars().collect();
+    if title.len() > news_core::ogp::PLACEHOLDER_TITLE_CHARS {
+        title.truncate(news_core::ogp::PLACEHOLDER_TITLE_CHARS - 1);
+        title.push('…');
+    }
+    let lines: String = title
+        .chunks(PLACEHOLDER_LINE_CHARS)
+        .enumerate()
+        .map(|(i, line)| {
+            let text: String = line.iter().collect();
+            format!(r#"<tspan x="80" dy="{}">{}</tspan>"#, if i == 0 { 0 } else { 64 }, escape_attr(&text))
+        })
+        .collect();
+
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="1200" height="630" viewBox="0 0 1200 630">
+<defs><linearGradient id="bg" x1="0" y1="0" x2="1" y2="1"><stop offset="0" stop-color="{from}"/><stop offset="1" stop-color="{to}"/></linearGradient></defs>
+<rect width="1200" height="630" fill="url(#bg)"/>
+<circle cx="128" cy="128" r="56" fill="#ffffff" fill-opacity="0.2"/>
+<text x="128" y="148" text-anchor="middle" font-family="sans-serif" font-size="56" font-weight="bold" fill="#ffffff">{initial}</text>
+<text x="208" y="144" font-family="sans-serif" font-size="32" fill="#ffffff" fill-opacity="0.85">{source}</text>
+<text y="320" font-family="sans-serif" font-size="48" font-weight="bold" fill="#ffffff">{lines}</text>
+</svg>"##,
+        initial = escape_attr(&initial),
+        source = escape_attr(source),
+    );
+    (
+        [
+            (header::CONTENT_TYPE, "image/svg+xml"),
+            (header::CACHE_CONTROL, "public, max-age=604800"),
+        ],
+        svg,
+    )
+        .into_response()
+}
+
+/// Longest a share card may take to render before the static image is served instead.
+const OG_CARD_BUDGET: std::time::Duration = std::time::Duration::from_millis(100);
+const OG_CARD_TTL: i64 = 7 * 86400;
+
+/// og:image for `article`: its own image, else its share card when a card font is
+/// configured, else the site's static image.
+fn article_og_image(state: &AppState, site: &SiteMeta, article: &Article) -> String {
+    match article.image_url.as_deref() {
+        Some(url) if !news_core::ogp::is_placeholder_image(url) => url.to_string(),
+        _ if state.og_font.is_some() => format!("{}/og/{}.png", site.base_url(), article.id),
+        _ => site.image.clone(),
+    }
+}
+
+/// GET /og/:id.png — the article's 1200×630 PNG share card. Falls back to a redirect
+/// to the site's static image when there's no card font, the article is unknown, or
+/// rendering takes longer than `OG_CARD_BUDGET` (it's still cached for next time).
+pub async fn handle_og_card(
+    State(state): State<Arc<AppState>>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let site = detect_site(&state, &headers);
+    let fallback = || (StatusCode::FOUND, [(header::LOCATION, site.image.clone())]).into_response();
+    let Some(article_id) = file.strip_suffix(".png") else {
+        return ApiError::NotFound("Not found".into()).into_response();
+    };
+    let (Some(font), Ok(Some(article))) = (state.og_font.clone(), state.db.get_article_by_id(article_id)) else {
+        return fallback();
+    };
+
+    let png = |bytes: Vec<u8>| {
+        (
+            [
+                (header::CONTENT_TYPE, "image/png"),
+                (header::CACHE_CONTROL, "public, max-age=86400"),
+            ],
+            bytes,
+        )
+            .into_response()
+    };
+    // The title is part of the key so an edited title gets a fresh card
+    let ckey = cache_key("og_card", &format!("{}|{}|{}", article.id, article.title, site.site_id));
+    if let Ok(Some(b64)) = state.db.get_cache(&ckey) {
+        if let Ok(bytes) = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, b64) {
+            return png(bytes);
+        }
+    }
 
-fn detect_site(_host: &str) -> &'static SiteMeta {
-    &SITE_META
+    let db = Arc::clone(&state.db);
+    let site_name = site.name.clone();
+    let render = tokio::task::spawn_blocking(move || {
+        let (from, to) = category_gradient(article.category.as_str());
+        let card = crate::og_image::Card {
+            title: &article.title,
+            source: &article.source,
+            site_name: &site_name,
+            colors: (from, to),
+        };
+        let bytes = crate::og_image::render(&font, &card)?;
+        let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
+        let _ = db.set_cache(&ckey, "og_card", &b64, OG_CARD_TTL);
+        Ok::<_, String>(bytes)
+    });
+    match tokio::time::timeout(OG_CARD_BUDGET, render).await {
+        Ok(Ok(Ok(bytes))) => png(bytes),
+        Ok(Ok(Err(e))) => {
+            warn!(article_id, error = %e, "Share card rendering failed");
+            fallback()
+        }
+        Ok(Err(e)) => {
+            warn!(article_id, error = %e, "Share card rendering panicked");
+            fallback()
+        }
+        Err(_) => {
+            info!(article_id, "Share card over budget, serving the static image");
+            fallback()
+        }
+    }
 }
 
 /// Escape characters that are special inside HTML attribute values.
@@ -3275,45 +7779,47 @@ pub async fn serve_article_html(
     Path(article_id): Path<String>,
     headers: HeaderMap,
 ) -> Response {
-    let host = headers
-        .get("host")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("news.xyz");
+    let site = detect_site(&state, &headers);
+    let nonce = generate_csp_nonce();
+    let article_url = format!("{}/article/{}", site.base_url(), article_id);
 
-    let site = detect_site(host);
-    let article_url = format!("{}/article/{}", site.url.trim_end_matches('/'), article_id);
-
-    let (og_title, og_description, og_image, og_type) = match state.db.get_article_by_id(&article_id) {
+    let (og_title, og_description, og_image, og_type, keywords) = match state.db.get_article_by_id(&article_id) {
         Ok(Some(article)) => {
+            let keywords = match article_keywords(&state.db, &article) {
+                Ok((keywords, _)) if !keywords.is_empty() => keywords
+                    .iter()
+                    .map(|k| k.word.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                _ => site.keywords.clone(),
+            };
             let title = format!("{} | {}", article.title, site.name);
             let description = article
                 .description
                 .as_deref()
-                .unwrap_or(site.description)
+                .unwrap_or(&site.description)
                 .chars()
                 .take(200)
                 .collect::<String>();
-            let image = article
-                .image_url
-                .as_deref()
-                .unwrap_or(site.image)
-                .to_string();
-            (title, description, image, "article")
+            let image = article_og_image(&state, &site, &article);
+            (title, description, image, "article", keywords)
         }
         _ => (
-            site.title.to_string(),
-            site.description.to_string(),
-            site.image.to_string(),
+            site.title.clone(),
+            site.description.clone(),
+            site.image.clone(),
             "website",
+            site.keywords.clone(),
         ),
     };
 
     let head_block = format!(
 r#"<head>
-  <script>document.documentElement.dataset.site='xyz';</script>
+  <script nonce="{nonce}">document.documentElement.dataset.site='{site_id}';</script>
   <meta charset="UTF-8">
   <meta name="viewport" content="width=device-width, initial-scale=1.0">
   <meta name="description" content="{description}">
+  <meta name="keywords" content="{keywords}">
   <meta name="theme-color" content="{theme_color}">
   <meta name="robots" content="index, follow">
   <link rel="canonical" href="{canonical}">
@@ -3331,11 +7837,14 @@ r#"<head>
   <meta name="twitter:description" content="{description}">
   <meta name="twitter:image" content="{image}">
   <title>{title}</title>"#,
+        nonce = nonce,
+        site_id = escape_attr(&site.site_id),
         description = escape_attr(&og_description),
-        theme_color = site.theme_color,
+        keywords = escape_attr(&keywords),
+        theme_color = escape_attr(&site.theme_color),
         canonical = escape_attr(&article_url),
         og_type = og_type,
-        site_name = escape_attr(site.name),
+        site_name = escape_attr(&site.name),
         title = escape_attr(&og_title),
         image = escape_attr(&og_image),
     );
@@ -3351,7 +7860,7 @@ r#"<head>
     let head_start = html_str.find("<head>").unwrap_or(0);
 
     let html = if title_end > head_start {
-        let lang_attr = format!("<html lang=\"{}\">", site.lang);
+        let lang_attr = format!("<html lang=\"{}\">", escape_attr(&site.lang));
         format!(
             "<!DOCTYPE html>\n{}\n{}\n{}",
             lang_attr,
@@ -3366,27 +7875,66 @@ r#"<head>
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
         .header(header::CACHE_CONTROL, "public, max-age=60")
+        .extension(CspNonce(nonce))
         .body(Body::from(html))
         .unwrap()
 }
 
+/// GET /api/articles/:id/share — the article's short URL (created on first request)
+/// and the OGP title/image its preview will show.
+pub async fn handle_article_share(
+    State(state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let site = detect_site(&state, &headers);
+    let article = state
+        .db
+        .get_article_by_id(&article_id)?
+        .ok_or_else(|| ApiError::NotFound("Article not found".into()))?;
+    let code = state.db.get_or_create_shortlink(&article.id)?;
+    Ok(Json(serde_json::json!({
+        "short_url": format!("{}/s/{}", site.base_url(), code),
+        "og_title": format!("{} | {}", article.title, site.name),
+        "og_image": article_og_image(&state, &site, &article),
+    }))
+    .into_response())
+}
+
+/// GET /s/:code — count the click and 301 to the article page, whose SSR tags give
+/// crawlers the preview.
+pub async fn redirect_shortlink(
+    State(state): State<Arc<AppState>>,
+    Path(code): Path<String>,
+) -> Result<Response, ApiError> {
+    let not_found = || ApiError::NotFound("Short link not found".into());
+    if code.is_empty() || code.len() > 16 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(not_found());
+    }
+    let article_id = state.db.get_shortlink(&code)?.ok_or_else(not_found)?;
+    if let Err(e) = state.db.increment_shortlink_clicks(&code) {
+        warn!(error = %e, code, "Failed to count shortlink click");
+    }
+    let location = format!("/article/{}", article_id);
+    Ok((StatusCode::MOVED_PERMANENTLY, [(header::LOCATION, location)]).into_response())
+}
+
 /// Serve index.html with per-domain SEO/OGP meta tags injected server-side.
 /// This is critical because crawlers (Googlebot, Facebook, Twitter) do NOT execute JavaScript.
 /// Instead of fragile string replacements on the original template, we use placeholders.
 const INDEX_HTML_TEMPLATE: &str = include_str!("../../../../frontend/index.html");
 
-pub async fn serve_index_html(headers: HeaderMap) -> Response {
-    let host = headers
-        .get("host")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("news.xyz");
-
-    let site = detect_site(host);
+pub async fn serve_index_html(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Response {
+    let site = detect_site(&state, &headers);
+    let nonce = generate_csp_nonce();
 
     // Build the <head> section with correct meta tags for this domain
     let head_block = format!(
 r#"<head>
-  <script>document.documentElement.dataset.site='xyz';</script>
+  <script nonce="{nonce}">document.documentElement.dataset.site='{site_id}';</script>
   <meta charset="UTF-8">
   <meta name="viewport" content="width=device-width, initial-scale=1.0">
   <meta name="description" content="{description_long}">
@@ -3408,14 +7956,16 @@ r#"<head>
   <meta name="twitter:description" content="{description}">
   <meta name="twitter:image" content="{image}">
   <title>{title}</title>"#,
-        description_long = site.description_long,
-        keywords = site.keywords,
-        theme_color = site.theme_color,
-        url = site.url,
-        name = site.name,
-        title = site.title,
-        description = site.description,
-        image = site.image,
+        nonce = nonce,
+        site_id = escape_attr(&site.site_id),
+        description_long = escape_attr(site.description_long()),
+        keywords = escape_attr(&site.keywords),
+        theme_color = escape_attr(&site.theme_color),
+        url = escape_attr(&site.url),
+        name = escape_attr(&site.name),
+        title = escape_attr(&site.title),
+        description = escape_attr(&site.description),
+        image = escape_attr(&site.image),
     );
 
     // Replace the entire <head> block up to (but not including) the manifest link
@@ -3434,7 +7984,7 @@ r#"<head>
     let head_start = html_str.find("<head>").unwrap_or(0);
 
     let html = if title_end > head_start {
-        let lang_attr = format!("<html lang=\"{}\">", site.lang);
+        let lang_attr = format!("<html lang=\"{}\">", escape_attr(&site.lang));
         format!(
             "<!DOCTYPE html>\n{}\n{}\n{}",
             lang_attr,
@@ -3450,25 +8000,24 @@ r#"<head>
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
         .header(header::CACHE_CONTROL, "public, max-age=300")
+        .extension(CspNonce(nonce))
         .body(Body::from(html))
         .unwrap()
 }
 
 /// Serve /robots.txt with a reference to the sitemap.
-pub async fn serve_robots_txt(headers: HeaderMap) -> Response {
-    let host = headers
-        .get("host")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("news.xyz");
-
-    let site = detect_site(host);
+pub async fn serve_robots_txt(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Response {
+    let site = detect_site(&state, &headers);
 
     let body = format!(
         "User-agent: *\n\
          Allow: /\n\
          \n\
-         Sitemap: {}sitemap.xml\n",
-        site.url
+         Sitemap: {}/sitemap.xml\n",
+        site.base_url()
     );
 
     Response::builder()
@@ -3484,13 +8033,8 @@ pub async fn serve_sitemap_xml(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
 ) -> Response {
-    let host = headers
-        .get("host")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("news.xyz");
-
-    let site = detect_site(host);
-    let base_url = site.url.trim_end_matches('/');
+    let site = detect_site(&state, &headers);
+    let base_url = site.base_url();
 
     let mut xml = String::from(
         "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
@@ -3512,15 +8056,20 @@ pub async fn serve_sitemap_xml(
     }
 
     // Category pages
-    for cat in news_core::models::Category::all() {
+    let categories = visible_categories(&state.db)
+        .map(|cats| cats.into_iter().map(|c| c.id).collect())
+        .unwrap_or_else(|_| Category::all().iter().map(|c| c.as_str().to_string()).collect::<Vec<_>>());
+    for cat in categories {
         xml.push_str(&format!(
             "  <url>\n    <loc>{}/?category={}</loc>\n    <changefreq>hourly</changefreq>\n    <priority>0.8</priority>\n  </url>\n",
-            base_url, cat.as_str()
+            base_url, cat
         ));
     }
 
-    // Recent articles (up to 200 for sitemap coverage)
-    if let Ok((articles, _)) = state.db.query_articles(None, 200, None) {
+    // Recent articles (up to 200 for sitemap coverage), with their short links
+    if let Ok(Page { items: articles, .. }) = state.db.query_articles(None, &Paginator::first(200), false) {
+        let ids: Vec<&str> = articles.iter().map(|a| a.id.as_str()).collect();
+        let short_codes = state.db.shortlinks_for_articles(&ids).unwrap_or_default();
         for article in &articles {
             let lastmod = article.published_at.format("%Y-%m-%dT%H:%M:%S+00:00");
             // Use article ID as the URL fragment/path for the detail view
@@ -3529,9 +8078,21 @@ pub async fn serve_sitemap_xml(
                 "  <url>\n    <loc>{}/#article/{}</loc>\n    <lastmod>{}</lastmod>\n    <changefreq>daily</changefreq>\n    <priority>0.6</priority>\n  </url>\n",
                 base_url, escaped_id, lastmod
             ));
+            if let Some(code) = short_codes.get(&article.id) {
+                xml.push_str(&format!(
+                    "  <url>\n    <loc>{}/s/{}</loc>\n    <lastmod>{}</lastmod>\n    <priority>0.4</priority>\n  </url>\n",
+                    base_url, code, lastmod
+                ));
+            }
         }
     }
 
+    // Podcast feed
+    xml.push_str(&format!(
+        "  <url>\n    <loc>{}/api/podcast/rss.xml</loc>\n    <changefreq>hourly</changefreq>\n    <priority>0.5</priority>\n  </url>\n",
+        base_url
+    ));
+
     xml.push_str("</urlset>\n");
 
     Response::builder()
@@ -3542,6 +8103,308 @@ pub async fn serve_sitemap_xml(
         .unwrap()
 }
 
+#[derive(Deserialize)]
+pub struct PodcastRssQuery {
+    pub category: Option<String>,
+    #[serde(default = "default_preload_voice")]
+    pub voice_id: String,
+}
+
+const PODCAST_RSS_ITEMS: i64 = 20;
+/// Rough TTS speaking rate used for `<itunes:duration>`.
+const PODCAST_CHARS_PER_SEC: usize = 6;
+
+/// GET /api/podcast/rss.xml?category=&voice_id= — RSS 2.0 podcast feed of the latest
+/// articles. Enclosures point at `/api/articles/:id/audio`, so episodes only play once
+/// their audio has been generated (TTS pre-cache or `/api/tts/preload`).
+pub async fn serve_podcast_rss(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<PodcastRssQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let site = detect_site(&state, &headers);
+    let base_url = site.base_url();
+    let category = category_filter(&state.db, params.category.as_deref())?;
+    let articles = state.db.query_articles(category.as_ref(), &Paginator::first(PODCAST_RSS_ITEMS), false)?.items;
+
+    let title = match &category {
+        Some(c) => format!("{} - {}", site.name, c.as_str()),
+        None => site.name.clone(),
+    };
+    let mut feed_url = reqwest::Url::parse(&format!("{}/api/podcast/rss.xml", base_url))
+        .map_err(|e| ApiError::Internal(format!("Podcast feed URL: {e}")))?;
+    if let Some(c) = &category {
+        feed_url.query_pairs_mut().append_pair("category", c.as_str());
+    }
+    feed_url.query_pairs_mut().append_pair("voice_id", &params.voice_id);
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <rss version=\"2.0\" xmlns:itunes=\"http://www.itunes.com/dtds/podcast-1.0.dtd\" \
+         xmlns:atom=\"http://www.w3.org/2005/Atom\">\n<channel>\n",
+    );
+    xml.push_str(&format!(
+        "  <title>{title}</title>\n  <link>{link}/</link>\n  <description>{description}</description>\n  \
+         <language>{lang}</language>\n  <atom:link href=\"{self_url}\" rel=\"self\" type=\"application/rss+xml\"/>\n  \
+         <itunes:author>{author}</itunes:author>\n  <itunes:image href=\"{image}\"/>\n  \
+         <itunes:explicit>false</itunes:explicit>\n  <itunes:category text=\"News\"/>\n",
+        title = escape_attr(&title),
+        link = escape_attr(base_url),
+        description = escape_attr(&site.description),
+        lang = escape_attr(&site.lang),
+        self_url = escape_attr(feed_url.as_str()),
+        author = escape_attr(&site.name),
+        image = escape_attr(&site.image),
+    ));
+    if let Some(latest) = articles.iter().map(|a| a.published_at).max() {
+        xml.push_str(&format!("  <lastBuildDate>{}</lastBuildDate>\n", latest.to_rfc2822()));
+    }
+
+    for article in &articles {
+        let mut audio_url = reqwest::Url::parse(&format!("{}/api/articles/{}/audio", base_url, article.id))
+            .map_err(|e| ApiError::Internal(format!("Podcast enclosure URL: {e}")))?;
+        audio_url.query_pairs_mut().append_pair("voice_id", &params.voice_id);
+        let description = article.description.as_deref().unwrap_or("");
+        let secs = ((article.title.chars().count() + description.chars().count()) / PODCAST_CHARS_PER_SEC).max(1);
+        xml.push_str(&format!(
+            "  <item>\n    <title>{title}</title>\n    <link>{link}</link>\n    \
+             <guid isPermaLink=\"false\">{guid}</guid>\n    <description>{description}</description>\n    \
+             <pubDate>{pub_date}</pubDate>\n    <enclosure url=\"{audio}\" type=\"audio/mpeg\" length=\"0\"/>\n    \
+             <itunes:author>{source}</itunes:author>\n    <itunes:duration>{h:02}:{m:02}:{s:02}</itunes:duration>\n  </item>\n",
+            title = escape_attr(&article.title),
+            link = escape_attr(&article.url),
+            guid = escape_attr(&article.id),
+            description = escape_attr(description),
+            pub_date = article.published_at.to_rfc2822(),
+            audio = escape_attr(audio_url.as_str()),
+            source = escape_attr(&article.source),
+            h = secs / 3600,
+            m = secs / 60 % 60,
+            s = secs % 60,
+        ));
+    }
+    xml.push_str("</channel>\n</rss>\n");
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=600")
+        .body(Body::from(xml))
+        .unwrap())
+}
+
+#[derive(Deserialize)]
+pub struct JsonFeedQuery {
+    pub category: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// GET /feed.json?category=&limit= — JSON Feed 1.1 of the latest articles (default 20,
+/// at most 100). Grouped articles appear once, as on the front page.
+pub async fn serve_json_feed(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<JsonFeedQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let site = detect_site(&state, &headers);
+    let category = match params.category.as_deref().filter(|c| !c.is_empty()) {
+        Some(c) => Some(lookup_category(&state.db, c).ok_or_else(|| ApiError::validation("category", "unknown category"))?),
+        None => None,
+    };
+    let page = Paginator::new(params.limit.unwrap_or(pagination::DEFAULT_LIMIT), None);
+    let grouping = state.db.get_feature_flags().is_ok_and(|f| f.grouping_enabled);
+    let articles =
+        state.db.query_filtered_articles(category.as_ref(), &ArticleFilter::default(), &page, grouping, false)?.items;
+
+    let title = match &category {
+        Some(c) => format!("{} - {}", site.name, c.as_str()),
+        None => site.name.clone(),
+    };
+    let mut feed_url = reqwest::Url::parse(&format!("{}/feed.json", site.base_url()))
+        .map_err(|e| ApiError::Internal(format!("JSON Feed URL: {e}")))?;
+    if let Some(c) = &category {
+        feed_url.query_pairs_mut().append_pair("category", c.as_str());
+    }
+    let feed = json_feed::build(&site, title, feed_url.to_string(), &articles);
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, json_feed::CONTENT_TYPE)
+        .header(header::CACHE_CONTROL, "public, max-age=600")
+        .body(Body::from(serde_json::to_vec(&feed).unwrap_or_default()))
+        .unwrap())
+}
+
+// --- Site Management API ---
+
+/// Fields to set on a site entry. Omitted fields keep the current value; a new host
+/// starts from the default site with `url` = `https://{host}/`.
+#[derive(Deserialize)]
+pub struct UpsertSiteRequest {
+    pub site_id: Option<String>,
+    pub name: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub description_long: Option<String>,
+    pub url: Option<String>,
+    pub image: Option<String>,
+    pub theme_color: Option<String>,
+    pub lang: Option<String>,
+    pub keywords: Option<String>,
+}
+
+/// GET /api/admin/sites — compiled-in sites and DB overrides.
+pub async fn list_sites(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let overrides = state.db.list_sites()?;
+    Ok(Json(serde_json::json!({
+        "default_host": SITES.default_host,
+        "config": SITES.sites,
+        "overrides": overrides,
+    }))
+    .into_response())
+}
+
+/// PUT /api/admin/sites/:host — add a domain or override a sites.toml entry.
+pub async fn upsert_site(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(host): Path<String>,
+    Json(body): Json<UpsertSiteRequest>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let host = normalize_host(&host);
+    let base = match state.db.get_site(&host)? {
+        Some(site) => site,
+        None => match SITES.get(&host) {
+            Some(site) => site.clone(),
+            None => SiteMeta {
+                host: host.clone(),
+                url: format!("https://{}/", host),
+                ..SITES.default_site().clone()
+            },
+        },
+    };
+    let site = SiteMeta {
+        host: host.clone(),
+        site_id: body.site_id.unwrap_or(base.site_id),
+        name: body.name.unwrap_or(base.name),
+        title: body.title.unwrap_or(base.title),
+        description: body.description.unwrap_or(base.description),
+        description_long: body.description_long.or(base.description_long),
+        url: body.url.unwrap_or(base.url),
+        image: body.image.unwrap_or(base.image),
+        theme_color: body.theme_color.unwrap_or(base.theme_color),
+        lang: body.lang.unwrap_or(base.lang),
+        keywords: body.keywords.unwrap_or(base.keywords),
+    };
+    if let Some(error) = site.validate().into_iter().next() {
+        return Err(ApiError::validation("site", error));
+    }
+    state.db.put_site(&site)?;
+    state.cors.reload(&state.db);
+    Ok(Json(serde_json::json!({
+        "status": "ok",
+        "site": site,
+        "message": format!("サイト {} を保存しました", host),
+    }))
+    .into_response())
+}
+
+/// DELETE /api/admin/sites/:host — drop a DB entry; sites.toml entries reappear.
+pub async fn delete_site(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(host): Path<String>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let host = normalize_host(&host);
+    if !state.db.delete_site(&host)? {
+        return Err(ApiError::NotFound(format!("サイト {} は登録されていません", host)));
+    }
+    state.cors.reload(&state.db);
+    Ok(Json(serde_json::json!({"status": "ok", "message": "サイトを削除しました"})).into_response())
+}
+
+/// GET /api/admin/cors — origins currently allowed by CORS.
+pub async fn list_cors_origins(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    Ok(Json(serde_json::json!({ "origins": state.cors.origins() })).into_response())
+}
+
+// --- Source metadata ---
+
+#[derive(Deserialize)]
+pub struct UpsertSourceMetaRequest {
+    #[serde(rename = "type")]
+    pub source_type: Option<String>,
+    pub credibility_tier: Option<u8>,
+    pub homepage_url: Option<String>,
+    pub logo_url: Option<String>,
+}
+
+/// GET /api/admin/sources — metadata of every source that has any.
+pub async fn list_source_meta(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let sources = state.db.list_source_meta()?;
+    Ok(Json(serde_json::json!({ "sources": sources, "types": SOURCE_TYPES })).into_response())
+}
+
+/// PUT /api/admin/sources/:source — set a source's metadata. Omitted fields keep
+/// their current value.
+pub async fn upsert_source_meta(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(source): Path<String>,
+    Json(body): Json<UpsertSourceMetaRequest>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let source = source.trim().to_string();
+    let base = state
+        .db
+        .source_meta_map()?
+        .remove(&source)
+        .unwrap_or_else(|| SourceMeta::unknown(&source));
+    let meta = SourceMeta {
+        source: source.clone(),
+        source_type: body.source_type.unwrap_or(base.source_type),
+        credibility_tier: body.credibility_tier.or(base.credibility_tier),
+        homepage_url: body.homepage_url.filter(|u| !u.is_empty()).or(base.homepage_url),
+        logo_url: body.logo_url.filter(|u| !u.is_empty()).or(base.logo_url),
+    };
+    meta.validate().map_err(|e| ApiError::validation("source", e))?;
+    state.db.put_source_meta(&meta)?;
+    Ok(Json(serde_json::json!({
+        "status": "ok",
+        "source": meta,
+        "message": format!("ソース {} の情報を保存しました", source),
+    }))
+    .into_response())
+}
+
+/// DELETE /api/admin/sources/:source — drop a source's metadata; it reports type
+/// "unknown" until re-added (feeds.toml entries come back on restart).
+pub async fn delete_source_meta(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(source): Path<String>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    if !state.db.delete_source_meta(source.trim())? {
+        return Err(ApiError::NotFound(format!("ソース {} の情報は登録されていません", source)));
+    }
+    Ok(Json(serde_json::json!({"status": "ok", "message": "ソース情報を削除しました"})).into_response())
+}
+
 // --- Enrichment API ---
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -3553,8 +8416,9 @@ pub struct ViewClickResponse {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct EnrichmentData {
     agent_type: String,
+    /// One of the `news_core::enrichment::CONTENT_TYPE_*` values.
     content_type: String,
-    data: serde_json::Value,
+    data: EnrichmentPayload,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -3562,89 +8426,208 @@ pub struct EnrichmentsResponse {
     enrichments: Vec<EnrichmentData>,
 }
 
-/// POST /api/articles/:id/view
-pub async fn handle_article_view(
-    State(state): State<Arc<AppState>>,
-    Path(article_id): Path<String>,
-) -> Response {
-    match state.db.increment_view_count(&article_id) {
+/// A visitor counts at most once per article per window...
+const ENGAGEMENT_DEDUP_MINUTES: i64 = 30;
+/// ...and at most this many times per article per day.
+const ENGAGEMENT_DAILY_CAP: i64 = 3;
+/// The view that queues an article for enrichment right away.
+const ENRICHMENT_VIEW_THRESHOLD: i64 = 5;
+
+const BOT_UA_PATTERNS: &[&str] = &[
+    "bot", "crawl", "spider", "slurp", "facebookexternalhit", "embedly", "preview",
+    "headless", "lighthouse", "pagespeed", "curl/", "wget/", "python-requests", "httpclient",
+    "go-http-client", "okhttp",
+];
+
+fn is_bot_user_agent(headers: &HeaderMap) -> bool {
+    let ua = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_lowercase();
+    ua.is_empty() || BOT_UA_PATTERNS.iter().any(|p| ua.contains(p))
+}
+
+/// Who to dedupe a view/click against: the device ID if sent, otherwise a hash of the
+/// client IP. `None` means the request should not be counted (bots).
+fn engagement_visitor(headers: &HeaderMap) -> Option<String> {
+    if is_bot_user_agent(headers) {
+        return None;
+    }
+    if let Some(id) = headers.get("x-device-id").and_then(|v| v.to_str().ok()) {
+        if !id.is_empty() {
+            return Some(format!("d:{}", id));
+        }
+    }
+    Some(format!("ip:{}", cache_key("visitor", crate::client_ip::header_ip(headers))))
+}
+
+fn record_engagement(state: &AppState, headers: &HeaderMap, article_id: &str, kind: &str) -> Result<Response, ApiError> {
+    let visitor = engagement_visitor(headers);
+    let restore_score = state.flag_cache.get(&state.db).image_degradation.restore_score;
+    match state.db.record_engagement(
+        article_id,
+        kind,
+        visitor.as_deref(),
+        ENGAGEMENT_DEDUP_MINUTES,
+        ENGAGEMENT_DAILY_CAP,
+        restore_score,
+    ) {
         Ok(count) => {
-            // Check if this article should be enriched (top 10-20%)
-            // This is done asynchronously by the enrichment agent
-            (
+            if kind == "view" && count == ENRICHMENT_VIEW_THRESHOLD {
+                // A full queue is fine: the agent's sweep finds popular articles too
+                let _ = state.enrichment_tx.try_send(article_id.to_string());
+            }
+            Ok((
                 StatusCode::OK,
                 Json(ViewClickResponse {
                     success: true,
                     count,
                 }),
             )
-                .into_response()
+                .into_response())
         }
         Err(e) => {
-            warn!(error = %e, article_id, "Failed to increment view count");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Failed to update view count"})),
-            )
-                .into_response()
+            warn!(error = %e, article_id, kind, "Failed to record engagement");
+            Err(ApiError::Internal(format!("Failed to update {} count", kind)))
         }
     }
 }
 
+/// POST /api/articles/:id/view
+pub async fn handle_article_view(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+) -> Result<Response, ApiError> {
+    // The view that crosses ENRICHMENT_VIEW_THRESHOLD queues the article for the
+    // enrichment agent
+    record_engagement(&state, &headers, &article_id, "view")
+}
+
 /// POST /api/articles/:id/click
 pub async fn handle_article_click(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Path(article_id): Path<String>,
-) -> Response {
-    match state.db.increment_click_count(&article_id) {
-        Ok(count) => (
-            StatusCode::OK,
-            Json(ViewClickResponse {
-                success: true,
-                count,
-            }),
-        )
-            .into_response(),
-        Err(e) => {
-            warn!(error = %e, article_id, "Failed to increment click count");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Failed to update click count"})),
-            )
-                .into_response()
-        }
+) -> Result<Response, ApiError> {
+    record_engagement(&state, &headers, &article_id, "click")
+}
+
+/// POST /api/admin/popularity/recompute
+/// Rebuild popularity_score from view/click counters (e.g. after a scoring change).
+pub async fn handle_recompute_popularity(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let updated = state.db.recompute_popularity()?;
+    info!(updated, "Popularity scores recomputed");
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({"status": "ok", "updated": updated})),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+pub struct EnrichNowRequest {
+    /// "research" | "image" | "video"; empty = every agent whose flag is on.
+    #[serde(default)]
+    pub agents: Vec<String>,
+}
+
+/// POST /api/admin/articles/:id/enrich
+pub async fn handle_enrich_article(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+    Json(body): Json<EnrichNowRequest>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+
+    let agents = if body.agents.is_empty() {
+        let flags = state.db.get_feature_flags()?;
+        enrichment_agent::AgentKind::enabled_agents(&flags)
+    } else {
+        body.agents
+            .iter()
+            .map(|name| {
+                enrichment_agent::AgentKind::from_str(name)
+                    .ok_or_else(|| ApiError::validation("agents", format!("Unknown agent: {}", name)))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    if agents.is_empty() {
+        return Err(ApiError::validation("agents", "No enrichment agents enabled"));
     }
+
+    let article = state
+        .db
+        .get_article_by_id(&article_id)?
+        .ok_or_else(|| ApiError::NotFound("記事が見つかりません".into()))?;
+
+    let jobs = enrichment_agent::enrich_now(&state, article, &agents)?;
+    info!(article_id, jobs = jobs.len(), "Manual enrichment queued");
+
+    let enrichments: Vec<serde_json::Value> = jobs
+        .iter()
+        .map(|(agent, id)| serde_json::json!({"agent": agent.as_str(), "enrichment_id": id}))
+        .collect();
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({"status": "queued", "enrichments": enrichments})),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+pub struct EnrichmentListQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/admin/enrichments?status=failed
+pub async fn handle_list_enrichments(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<EnrichmentListQuery>,
+) -> Result<Response, ApiError> {
+    check_admin_auth(&headers, &state)?;
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let enrichments = state.db.list_enrichments(params.status.as_deref(), limit)?;
+    Ok((StatusCode::OK, Json(serde_json::json!({"enrichments": enrichments}))).into_response())
 }
 
 /// GET /api/articles/:id/enrichments
 pub async fn handle_get_enrichments(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
-) -> Response {
+) -> Result<Response, ApiError> {
     match state.db.get_enrichments(&article_id) {
         Ok(rows) => {
             let enrichments: Vec<EnrichmentData> = rows
                 .into_iter()
-                .filter_map(|(_, agent_type, content_type, data_json, _)| {
-                    serde_json::from_str::<serde_json::Value>(&data_json)
-                        .ok()
-                        .map(|data| EnrichmentData {
+                .filter_map(|(enrichment_id, agent_type, content_type, data_json, _)| {
+                    match EnrichmentPayload::parse(&content_type, &data_json) {
+                        Ok(data) => Some(EnrichmentData {
                             agent_type,
                             content_type,
                             data,
-                        })
+                        }),
+                        Err(e) => {
+                            warn!(enrichment_id, article_id, error = %e, "Skipping malformed enrichment");
+                            None
+                        }
+                    }
                 })
                 .collect();
 
-            (StatusCode::OK, Json(EnrichmentsResponse { enrichments })).into_response()
+            Ok((StatusCode::OK, Json(EnrichmentsResponse { enrichments })).into_response())
         }
         Err(e) => {
             warn!(error = %e, article_id, "Failed to get enrichments");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Failed to get enrichments"})),
-            )
-                .into_response()
+            Err(ApiError::Internal("Failed to get enrichments".into()))
         }
     }
 }
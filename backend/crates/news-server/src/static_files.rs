@@ -0,0 +1,251 @@
+use axum::body::Body;
+use axum::extract::Request;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::Router;
+use sha2::{Digest, Sha256};
+use tower_http::compression::CompressionLayer;
+use tower_http::services::ServeDir;
+
+/// Serve the frontend from `static_dir` as the fallback of `router`, with cache headers
+/// and response compression.
+///
+/// The image build is expected to write `foo.js.br` / `foo.js.gz` next to each text
+/// asset (see the Dockerfile). ServeDir sends those as-is when the client accepts
+/// them and falls back to the plain file otherwise; CompressionLayer leaves responses
+/// that already carry a Content-Encoding alone, so only assets without a precompressed
+/// variant (and API responses) are compressed on the fly.
+pub fn serve<S>(router: Router<S>, static_dir: &str) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let files = ServeDir::new(static_dir)
+        .append_index_html_on_directories(true)
+        .precompressed_br()
+        .precompressed_gzip();
+    router
+        .fallback_service(files)
+        .layer(middleware::from_fn(set_cache_headers))
+        .layer(CompressionLayer::new())
+}
+
+/// Whether the file name carries a content hash, e.g. `app.3fa9c2.js` or
+/// `style.5d41402abc4b.min.css`: a dot-separated segment of 6–64 hex digits with at
+/// least one digit, other than the first and the extension.
+pub fn is_fingerprinted(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or("");
+    let parts: Vec<&str> = name.split('.').collect();
+    parts.len() >= 3
+        && parts[1..parts.len() - 1].iter().any(|p| {
+            (6..=64).contains(&p.len())
+                && p.chars().all(|c| c.is_ascii_hexdigit())
+                && p.chars().any(|c| c.is_ascii_digit())
+        })
+}
+
+/// Cache-Control for a request path; None leaves the response as it is.
+pub fn cache_control_for(path: &str) -> Option<&'static str> {
+    if is_fingerprinted(path) {
+        Some("public, max-age=31536000, immutable")
+    } else if path == "/sw.js" || path.ends_with(".html") || path == "/" {
+        Some("no-cache")
+    } else if path.starts_with("/icons/") {
+        Some("public, max-age=604800")
+    } else if path.ends_with(".json") || path == "/robots.txt" || path == "/sitemap.xml" {
+        Some("public, max-age=3600")
+    } else {
+        None
+    }
+}
+
+/// Weak ETag for a file response, derived from the validators ServeDir already sends.
+/// The encoding is part of it since br/gzip/identity bodies differ.
+fn file_etag(headers: &HeaderMap) -> Option<HeaderValue> {
+    let last_modified = headers.get(header::LAST_MODIFIED)?;
+    let mut hasher = Sha256::new();
+    hasher.update(last_modified.as_bytes());
+    for name in [header::CONTENT_LENGTH, header::CONTENT_ENCODING] {
+        hasher.update(b"|");
+        if let Some(v) = headers.get(name) {
+            hasher.update(v.as_bytes());
+        }
+    }
+    let digest = hex::encode(hasher.finalize());
+    HeaderValue::from_str(&format!("W/\"{}\"", &digest[..16])).ok()
+}
+
+/// `If-None-Match` matches `etag` (weak comparison) or is `*`.
+pub(crate) fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let bare = |t: &str| t.trim().trim_start_matches("W/").to_string();
+    if_none_match
+        .split(',')
+        .any(|t| t.trim() == "*" || bare(t) == bare(etag))
+}
+
+/// Set Cache-Control by path, add an ETag to file responses and answer matching
+/// `If-None-Match` with 304. Runs inside CompressionLayer, so it sees precompressed
+/// files with their Content-Encoding and everything else uncompressed.
+async fn set_cache_headers(req: Request, next: Next) -> Response {
+    let path = req.uri().path().to_owned();
+    let if_none_match = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned);
+    let mut res = next.run(req).await;
+
+    if let Some(val) = cache_control_for(&path) {
+        res.headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static(val));
+    }
+    if res.status() != StatusCode::OK || res.headers().contains_key(header::ETAG) {
+        return res;
+    }
+    let Some(etag) = file_etag(res.headers()) else {
+        return res;
+    };
+
+    if let Some(inm) = if_none_match {
+        if etag_matches(&inm, etag.to_str().unwrap_or("")) {
+            let mut not_modified = Response::new(Body::empty());
+            *not_modified.status_mut() = StatusCode::NOT_MODIFIED;
+            for name in [header::CACHE_CONTROL, header::LAST_MODIFIED, header::VARY] {
+                if let Some(v) = res.headers().get(&name) {
+                    not_modified.headers_mut().insert(name, v.clone());
+                }
+            }
+            not_modified.headers_mut().insert(header::ETAG, etag);
+            return not_modified;
+        }
+    }
+    res.headers_mut().insert(header::ETAG, etag);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tower::ServiceExt;
+
+    const JS: &str = "console.log('news.xyz static asset test, long enough to be compressed');\n";
+    const CSS: &str = "body { margin: 0; padding: 0; font-family: system-ui, sans-serif; }\n";
+
+    /// Frontend dir with a fingerprinted JS file (plus its .gz) and a plain CSS file.
+    fn fixture_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("news-static-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("js")).unwrap();
+        std::fs::create_dir_all(dir.join("css")).unwrap();
+        std::fs::write(dir.join("js/app.3fa9c2.js"), JS).unwrap();
+        std::fs::write(dir.join("js/app.3fa9c2.js.gz"), b"precompressed-gzip-bytes").unwrap();
+        let mut css = std::fs::File::create(dir.join("css/style.css")).unwrap();
+        for _ in 0..20 {
+            css.write_all(CSS.as_bytes()).unwrap();
+        }
+        dir
+    }
+
+    async fn get(app: &Router, uri: &str, headers: &[(header::HeaderName, &str)]) -> Response {
+        let mut req = Request::builder().uri(uri);
+        for (name, value) in headers {
+            req = req.header(name, *value);
+        }
+        app.clone().oneshot(req.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    async fn body_bytes(res: Response) -> Vec<u8> {
+        axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap().to_vec()
+    }
+
+    #[test]
+    fn fingerprint_detection() {
+        assert!(is_fingerprinted("/js/app.3fa9c2.js"));
+        assert!(is_fingerprinted("/css/style.5d41402abc4b.min.css"));
+        assert!(!is_fingerprinted("/js/app.js"));
+        assert!(!is_fingerprinted("/js/jquery.min.js"));
+        assert!(!is_fingerprinted("/js/app.decade.js"));
+        assert!(!is_fingerprinted("/3fa9c2.js"));
+        assert!(!is_fingerprinted("/js/app.3fa9.js"));
+    }
+
+    #[test]
+    fn cache_rules() {
+        assert_eq!(cache_control_for("/js/app.3fa9c2.js"), Some("public, max-age=31536000, immutable"));
+        assert_eq!(cache_control_for("/js/app.js"), None);
+        assert_eq!(cache_control_for("/sw.js"), Some("no-cache"));
+        assert_eq!(cache_control_for("/about.html"), Some("no-cache"));
+        assert_eq!(cache_control_for("/icons/icon-192.png"), Some("public, max-age=604800"));
+        assert_eq!(cache_control_for("/manifest.json"), Some("public, max-age=3600"));
+    }
+
+    #[test]
+    fn weak_etag_comparison() {
+        assert!(etag_matches("W/\"abc\"", "W/\"abc\""));
+        assert!(etag_matches("\"x\", W/\"abc\"", "W/\"abc\""));
+        assert!(etag_matches("*", "W/\"abc\""));
+        assert!(!etag_matches("W/\"abd\"", "W/\"abc\""));
+    }
+
+    #[tokio::test]
+    async fn precompressed_file_is_sent_as_is_with_immutable_caching() {
+        let dir = fixture_dir("precompressed");
+        let app: Router = serve(Router::new(), dir.to_str().unwrap());
+
+        let res = get(&app, "/js/app.3fa9c2.js", &[(header::ACCEPT_ENCODING, "gzip")]).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_ENCODING], "gzip");
+        assert_eq!(res.headers()[header::CACHE_CONTROL], "public, max-age=31536000, immutable");
+        assert!(res.headers().contains_key(header::ETAG));
+        assert!(res.headers().contains_key(header::LAST_MODIFIED));
+        // The .gz file itself, not a recompression of it
+        assert_eq!(body_bytes(res).await, b"precompressed-gzip-bytes");
+
+        let res = get(&app, "/js/app.3fa9c2.js", &[]).await;
+        assert!(res.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(body_bytes(res).await, JS.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn plain_file_is_compressed_on_the_fly_without_immutable() {
+        let dir = fixture_dir("dynamic");
+        let app: Router = serve(Router::new(), dir.to_str().unwrap());
+
+        // A version query no longer makes a file immutable
+        let res = get(&app, "/css/style.css?v=36", &[(header::ACCEPT_ENCODING, "gzip")]).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_ENCODING], "gzip");
+        assert!(res.headers().get(header::CACHE_CONTROL).is_none());
+        assert!(res.headers().contains_key(header::ETAG));
+        let body = body_bytes(res).await;
+        assert_eq!(&body[..2], &[0x1f, 0x8b]);
+    }
+
+    #[tokio::test]
+    async fn validators_produce_304_without_a_compressed_body() {
+        let dir = fixture_dir("conditional");
+        let app: Router = serve(Router::new(), dir.to_str().unwrap());
+
+        let first = get(&app, "/css/style.css", &[(header::ACCEPT_ENCODING, "gzip")]).await;
+        let etag = first.headers()[header::ETAG].to_str().unwrap().to_string();
+        let last_modified = first.headers()[header::LAST_MODIFIED].to_str().unwrap().to_string();
+
+        let res = get(
+            &app,
+            "/css/style.css",
+            &[(header::ACCEPT_ENCODING, "gzip"), (header::IF_NONE_MATCH, &etag)],
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers()[header::ETAG], etag.as_str());
+        assert!(res.headers().get(header::CONTENT_ENCODING).is_none());
+        assert!(body_bytes(res).await.is_empty());
+
+        let res = get(&app, "/css/style.css", &[(header::IF_MODIFIED_SINCE, &last_modified)]).await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+        let res = get(&app, "/js/app.3fa9c2.js", &[(header::IF_NONE_MATCH, "W/\"stale\"")]).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::db::Db;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 use tracing::{info, warn};
@@ -95,6 +96,30 @@ pub async fn create_billing_portal_session(
     Ok(url)
 }
 
+/// Cancel a subscription immediately (DELETE /v1/subscriptions/:id).
+pub async fn cancel_subscription(
+    client: &reqwest::Client,
+    secret_key: &str,
+    subscription_id: &str,
+) -> Result<(), String> {
+    let resp = client
+        .delete(format!("https://api.stripe.com/v1/subscriptions/{}", subscription_id))
+        .basic_auth(secret_key, None::<&str>)
+        .send()
+        .await
+        .map_err(|e| format!("Stripe cancel request failed: {e}"))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        warn!(status = %status, body = %body, "Stripe cancel subscription error");
+        return Err(format!("Stripe error: {status}"));
+    }
+
+    info!(subscription_id, "Stripe subscription canceled");
+    Ok(())
+}
+
 pub fn verify_webhook_signature(
     payload: &[u8],
     sig_header: &str,
@@ -144,6 +169,78 @@ pub fn verify_webhook_signature(
     Ok(())
 }
 
+/// Apply a verified webhook event to the subscriptions table, at most once per event
+/// ID. Returns false for a delivery that was already processed. `checkout_period_end`
+/// is the subscription's period end, looked up by the caller for checkout events.
+/// If applying fails the event is released again so Stripe's retry gets another go.
+pub fn apply_webhook_event(
+    db: &Db,
+    event: &serde_json::Value,
+    checkout_period_end: &str,
+) -> Result<bool, String> {
+    let event_id = event["id"].as_str().unwrap_or("");
+    let event_type = event["type"].as_str().unwrap_or("");
+    if !event_id.is_empty() && !db.claim_webhook_event(event_id, event_type)? {
+        info!(event_id, event_type, "Duplicate webhook event skipped");
+        return Ok(false);
+    }
+
+    let result = apply_event(db, event_type, &event["data"]["object"], checkout_period_end);
+    if let Err(e) = &result {
+        warn!(event_id, event_type, error = %e, "Webhook event failed");
+        if !event_id.is_empty() {
+            db.release_webhook_event(event_id)?;
+        }
+    }
+    result.map(|_| true)
+}
+
+fn apply_event(
+    db: &Db,
+    event_type: &str,
+    object: &serde_json::Value,
+    checkout_period_end: &str,
+) -> Result<(), String> {
+    match event_type {
+        "checkout.session.completed" => {
+            let customer_id = object["customer"].as_str().unwrap_or("");
+            let subscription_id = object["subscription"].as_str().unwrap_or("");
+            if !customer_id.is_empty() && !subscription_id.is_empty() {
+                let new_token = uuid::Uuid::new_v4().to_string();
+                db.create_subscription(&new_token, customer_id, subscription_id, checkout_period_end)?;
+                info!(customer_id, subscription_id, "Subscription created via checkout");
+            }
+        }
+        "customer.subscription.updated" | "customer.subscription.deleted" => {
+            let sub_id = object["id"].as_str().unwrap_or("");
+            let customer_id = object["customer"].as_str().unwrap_or("");
+            let status = if event_type == "customer.subscription.deleted" {
+                "canceled"
+            } else {
+                object["status"].as_str().unwrap_or("")
+            };
+            let period_end = object["current_period_end"]
+                .as_i64()
+                .map(|ts| chrono::DateTime::from_timestamp(ts, 0).unwrap_or_default().to_rfc3339());
+            if !sub_id.is_empty() && !status.is_empty() {
+                db.upsert_subscription_status(sub_id, customer_id, status, period_end.as_deref())?;
+                info!(sub_id, status, "Subscription updated via webhook");
+            }
+        }
+        "invoice.payment_failed" => {
+            let sub_id = object["subscription"].as_str().unwrap_or("");
+            if !sub_id.is_empty() {
+                db.update_subscription_status(sub_id, "past_due", None)?;
+                info!(sub_id, "Subscription payment failed");
+            }
+        }
+        _ => {
+            info!(event_type, "Unhandled webhook event type");
+        }
+    }
+    Ok(())
+}
+
 fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     if a.len() != b.len() {
         return false;
@@ -0,0 +1,327 @@
+//! Supervision of the background loops started in `main`. Each loop runs in its own
+//! tokio task; a panic or unexpected exit is logged, kept as the task's `last_error`
+//! and, under `RestartPolicy::Always`, followed by a restart after an exponential
+//! backoff. Loops call `Heartbeat::beat` after every successful cycle, which also
+//! feeds the `task_last_success` gauge behind `/api/health/detailed`.
+
+use crate::health;
+use crate::metrics::Metrics;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio::task::AbortHandle;
+use tracing::{error, info, warn};
+
+/// First restart delay; doubles with every failed run up to `BACKOFF_MAX`.
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartPolicy {
+    /// Restart after a panic or exit.
+    Always,
+    /// Stay stopped until restarted through the admin API.
+    Never,
+}
+
+pub struct TaskSpec {
+    pub name: &'static str,
+    pub policy: RestartPolicy,
+    /// A stale critical task marks `/health` degraded.
+    pub critical: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    pub name: &'static str,
+    pub policy: RestartPolicy,
+    pub critical: bool,
+    pub running: bool,
+    pub restarts: u32,
+    pub last_start: Option<DateTime<Utc>>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub expected_interval_secs: u64,
+    /// No heartbeat within twice the expected interval.
+    pub stale: bool,
+}
+
+#[derive(Default)]
+struct TaskState {
+    running: bool,
+    restarts: u32,
+    last_start: Option<DateTime<Utc>>,
+    last_success: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    abort: Option<AbortHandle>,
+}
+
+struct Task {
+    spec: TaskSpec,
+    state: Arc<Mutex<TaskState>>,
+    restart: Arc<Notify>,
+}
+
+fn lock(state: &Mutex<TaskState>) -> MutexGuard<'_, TaskState> {
+    // The state is plain data; a panic while holding the lock can't leave it torn
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Handed to a supervised loop; `beat` after each successful cycle.
+#[derive(Clone)]
+pub struct Heartbeat {
+    name: &'static str,
+    state: Arc<Mutex<TaskState>>,
+    metrics: Arc<Metrics>,
+}
+
+impl Heartbeat {
+    pub fn beat(&self) {
+        lock(&self.state).last_success = Some(Utc::now());
+        self.metrics.task_ok(self.name);
+    }
+}
+
+pub struct Supervisor {
+    metrics: Arc<Metrics>,
+    tasks: Mutex<BTreeMap<&'static str, Task>>,
+    started: Instant,
+    backoff_base: Duration,
+}
+
+impl Supervisor {
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self::with_backoff(metrics, BACKOFF_BASE)
+    }
+
+    fn with_backoff(metrics: Arc<Metrics>, backoff_base: Duration) -> Self {
+        Self {
+            metrics,
+            tasks: Mutex::default(),
+            started: Instant::now(),
+            backoff_base,
+        }
+    }
+
+    /// Start `make(heartbeat)` as a supervised task. `make` is called again for every
+    /// restart, so it clones whatever the loop needs.
+    pub fn spawn<F, Fut>(&self, spec: TaskSpec, make: F)
+    where
+        F: Fn(Heartbeat) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let state = Arc::new(Mutex::new(TaskState::default()));
+        let restart = Arc::new(Notify::new());
+        let heartbeat = Heartbeat {
+            name: spec.name,
+            state: Arc::clone(&state),
+            metrics: Arc::clone(&self.metrics),
+        };
+        tokio::spawn(supervise(
+            spec.name,
+            spec.policy,
+            self.backoff_base,
+            heartbeat,
+            Arc::clone(&restart),
+            make,
+        ));
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks.insert(spec.name, Task { spec, state, restart });
+    }
+
+    /// Restart `name` now: a running task is aborted and started again, a stopped
+    /// one skips its backoff. False if there is no such task.
+    pub fn restart(&self, name: &str) -> bool {
+        let tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(task) = tasks.get(name) else {
+            return false;
+        };
+        match &lock(&task.state).abort {
+            Some(handle) => handle.abort(),
+            None => task.restart.notify_one(),
+        }
+        true
+    }
+
+    pub fn statuses(&self) -> Vec<TaskStatus> {
+        let uptime = self.started.elapsed();
+        let tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks
+            .values()
+            .map(|task| {
+                let state = lock(&task.state);
+                let interval = health::task_interval(task.spec.name);
+                let (status, _) = health::task_staleness(state.last_success, uptime, interval);
+                TaskStatus {
+                    name: task.spec.name,
+                    policy: task.spec.policy,
+                    critical: task.spec.critical,
+                    running: state.running,
+                    restarts: state.restarts,
+                    last_start: state.last_start,
+                    last_success: state.last_success,
+                    last_error: state.last_error.clone(),
+                    expected_interval_secs: interval.as_secs(),
+                    stale: status != health::Status::Ok,
+                }
+            })
+            .collect()
+    }
+
+    /// Names of critical tasks that are stale or not running.
+    pub fn unhealthy_critical(&self) -> Vec<&'static str> {
+        self.statuses()
+            .into_iter()
+            .filter(|t| t.critical && (t.stale || !t.running))
+            .map(|t| t.name)
+            .collect()
+    }
+}
+
+async fn supervise<F, Fut>(
+    name: &'static str,
+    policy: RestartPolicy,
+    backoff_base: Duration,
+    heartbeat: Heartbeat,
+    restart: Arc<Notify>,
+    make: F,
+) where
+    F: Fn(Heartbeat) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut backoff = backoff_base;
+    loop {
+        let started = Utc::now();
+        let handle = tokio::spawn(make(heartbeat.clone()));
+        {
+            let mut state = lock(&heartbeat.state);
+            state.running = true;
+            state.last_start = Some(started);
+            state.abort = Some(handle.abort_handle());
+        }
+        info!(task = name, "Background task started");
+
+        let error = match handle.await {
+            Ok(()) => "exited".to_string(),
+            Err(e) if e.is_cancelled() => {
+                // Admin restart: start again right away
+                info!(task = name, "Background task restarted by admin");
+                let mut state = lock(&heartbeat.state);
+                state.running = false;
+                state.abort = None;
+                state.restarts += 1;
+                backoff = backoff_base;
+                continue;
+            }
+            Err(e) => format!("panicked: {}", panic_message(e.into_panic())),
+        };
+        error!(task = name, error = %error, "Background task stopped");
+        let beat_since_start = {
+            let mut state = lock(&heartbeat.state);
+            state.running = false;
+            state.abort = None;
+            state.last_error = Some(error);
+            state.last_success.is_some_and(|t| t >= started)
+        };
+        if beat_since_start {
+            backoff = backoff_base;
+        }
+
+        match policy {
+            RestartPolicy::Always => {
+                warn!(task = name, backoff_secs = backoff.as_secs_f64(), "Restarting background task");
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = restart.notified() => {}
+                }
+                backoff = (backoff * 2).min(BACKOFF_MAX);
+            }
+            RestartPolicy::Never => restart.notified().await,
+        }
+        lock(&heartbeat.state).restarts += 1;
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map_or_else(|| "non-string panic payload".into(), |m| m.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    async fn wait_for(supervisor: &Supervisor, done: impl Fn(&TaskStatus) -> bool) -> TaskStatus {
+        for _ in 0..200 {
+            if let Some(status) = supervisor.statuses().into_iter().find(|s| done(s)) {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("task never reached the expected state: {:?}", supervisor.statuses());
+    }
+
+    #[tokio::test]
+    async fn panicking_task_is_restarted_and_reported() {
+        let supervisor = Supervisor::with_backoff(Arc::new(Metrics::new()), Duration::from_millis(20));
+        let runs = Arc::new(AtomicU32::new(0));
+        let task_runs = Arc::clone(&runs);
+        supervisor.spawn(
+            TaskSpec {
+                name: "fetcher",
+                policy: RestartPolicy::Always,
+                critical: true,
+            },
+            move |heartbeat| {
+                let runs = Arc::clone(&task_runs);
+                async move {
+                    if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                        panic!("first run fails");
+                    }
+                    heartbeat.beat();
+                    std::future::pending::<()>().await;
+                }
+            },
+        );
+
+        let status = wait_for(&supervisor, |s| s.running && s.last_success.is_some()).await;
+        assert_eq!(status.restarts, 1);
+        assert!(status.last_error.unwrap().contains("first run fails"));
+        assert!(!status.stale);
+        assert!(supervisor.unhealthy_critical().is_empty());
+
+        assert!(supervisor.restart("fetcher"));
+        wait_for(&supervisor, |s| s.restarts == 2 && s.running).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert!(!supervisor.restart("nope"));
+    }
+
+    #[tokio::test]
+    async fn never_policy_waits_for_a_manual_restart() {
+        let supervisor = Supervisor::with_backoff(Arc::new(Metrics::new()), Duration::from_millis(1));
+        supervisor.spawn(
+            TaskSpec {
+                name: "cleanup",
+                policy: RestartPolicy::Never,
+                critical: true,
+            },
+            |_| async {},
+        );
+        let status = wait_for(&supervisor, |s| !s.running && s.last_error.is_some()).await;
+        assert_eq!(status.restarts, 0);
+        assert_eq!(supervisor.unhealthy_critical(), vec!["cleanup"]);
+
+        assert!(supervisor.restart("cleanup"));
+        wait_for(&supervisor, |s| s.restarts == 1).await;
+    }
+}
@@ -0,0 +1,89 @@
+//! OpenTelemetry trace export (the `otel` feature). Spans are sent over OTLP/gRPC to
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` (a collector, or Jaeger's OTLP port), and incoming
+//! `traceparent` headers make the request span a child of the caller's trace.
+
+use axum::body::Body;
+use axum::http::{HeaderMap, Request};
+use opentelemetry::propagation::Extractor;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use opentelemetry_sdk::Resource;
+use tower_http::classify::{ServerErrorsAsFailures, SharedClassifier};
+use tower_http::trace::TraceLayer;
+use tracing::Span;
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+
+/// The tracing layer that exports spans, or None when `OTEL_EXPORTER_OTLP_ENDPOINT`
+/// is unset or the exporter can't be built. Also installs the global tracer provider
+/// and the W3C trace-context propagator.
+pub fn layer<S>() -> Option<OpenTelemetryLayer<S, Tracer>>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok().filter(|e| !e.is_empty())?;
+    let exporter = match opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            // The subscriber isn't installed yet, so tracing macros would go nowhere
+            eprintln!("OTLP exporter disabled: {e}");
+            return None;
+        }
+    };
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", "news-server")]))
+        .build();
+    let tracer = provider.tracer("news-server");
+    opentelemetry::global::set_tracer_provider(provider);
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Flush buffered spans before exit.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+type MakeSpan = fn(&Request<Body>) -> Span;
+
+/// Request span per HTTP request, parented to the caller's `traceparent` if any.
+pub fn trace_layer() -> TraceLayer<SharedClassifier<ServerErrorsAsFailures>, MakeSpan> {
+    TraceLayer::new_for_http().make_span_with(request_span as MakeSpan)
+}
+
+fn request_span(req: &Request<Body>) -> Span {
+    let peer = req
+        .extensions()
+        .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+        .map(|c| c.0);
+    let client_ip = crate::client_ip::resolve(req.headers(), peer, *crate::client_ip::TRUST_PROXY_HEADERS);
+    let span = tracing::info_span!(
+        "http_request",
+        method = %req.method(),
+        path = %req.uri().path(),
+        client_ip = %client_ip
+    );
+    let parent =
+        opentelemetry::global::get_text_map_propagator(|p| p.extract(&HeaderExtractor(req.headers())));
+    span.set_parent(parent);
+    span
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|k| k.as_str()).collect()
+    }
+}
@@ -0,0 +1,185 @@
+//! What a valid Bearer token resolved to (Pro or a Google user), kept for a few
+//! minutes so `extract_user_tier` doesn't hit SQLite twice on every authenticated
+//! request. Entries are dropped when a token is refreshed, a user logs in again or
+//! deletes their account, and all at once on any Stripe webhook. Invalid tokens are
+//! not cached, so random ones can't fill the cache, and past `capacity` the oldest
+//! entry makes room.
+
+use crate::routes::UserTier;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const TOKEN_CACHE_TTL: Duration = Duration::from_secs(300);
+pub const TOKEN_CACHE_CAPACITY: usize = 10_000;
+
+pub struct TokenCache {
+    inner: Mutex<Entries>,
+    ttl: Duration,
+    capacity: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+struct Entries {
+    tiers: HashMap<String, (UserTier, Instant)>,
+    /// Insertion order. A token inserted again, or invalidated, leaves a stale record
+    /// behind, recognised by its timestamp no longer matching `tiers`.
+    order: VecDeque<(String, Instant)>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenCacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// hits / (hits + misses); 0 before any lookup.
+    pub hit_rate: f64,
+    pub ttl_secs: u64,
+    pub capacity: usize,
+}
+
+impl TokenCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Entries::default()),
+            ttl,
+            capacity: capacity.max(1),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The cached tier for `token`; None on a miss or an expired entry.
+    pub fn get(&self, token: &str) -> Option<UserTier> {
+        let cached = self.inner.lock().ok().and_then(|entries| {
+            entries
+                .tiers
+                .get(token)
+                .filter(|(_, at)| at.elapsed() < self.ttl)
+                .map(|(tier, _)| tier.clone())
+        });
+        let counter = if cached.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
+    pub fn insert(&self, token: &str, tier: UserTier) {
+        let Ok(mut entries) = self.inner.lock() else { return };
+        let now = Instant::now();
+        entries.tiers.insert(token.to_string(), (tier, now));
+        entries.order.push_back((token.to_string(), now));
+        // Every live entry has a record in `order`, so bounding it bounds both
+        while entries.order.len() > self.capacity {
+            let Some((oldest, at)) = entries.order.pop_front() else { break };
+            if entries.tiers.get(&oldest).is_some_and(|(_, current)| *current == at) {
+                entries.tiers.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn invalidate(&self, token: &str) {
+        if let Ok(mut entries) = self.inner.lock() {
+            entries.tiers.remove(token);
+        }
+    }
+
+    /// Drop every token of `user_id`, e.g. after a new login replaced its token.
+    pub fn invalidate_user(&self, user_id: &str) {
+        if let Ok(mut entries) = self.inner.lock() {
+            entries
+                .tiers
+                .retain(|_, (tier, _)| !matches!(tier, UserTier::Authenticated { user_id: u, .. } if &**u == user_id));
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.inner.lock() {
+            entries.tiers.clear();
+            entries.order.clear();
+        }
+    }
+
+    pub fn stats(&self) -> TokenCacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let total = hits + misses;
+        TokenCacheStats {
+            entries: self.inner.lock().map_or(0, |entries| entries.tiers.len()),
+            hits,
+            misses,
+            hit_rate: if total == 0 { 0.0 } else { hits as f64 / total as f64 },
+            ttl_secs: self.ttl.as_secs(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str) -> UserTier {
+        UserTier::Authenticated {
+            device_id: "dev".into(),
+            user_id: id.into(),
+        }
+    }
+
+    #[test]
+    fn entries_expire_and_count_hits() {
+        let cache = TokenCache::new(Duration::from_millis(50), TOKEN_CACHE_CAPACITY);
+        assert!(cache.get("t1").is_none());
+        cache.insert("t1", UserTier::Pro);
+        assert!(matches!(cache.get("t1"), Some(UserTier::Pro)));
+        assert!(matches!(cache.get("t1"), Some(UserTier::Pro)));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(cache.get("t1").is_none());
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 2));
+        assert_eq!(stats.hit_rate, 0.5);
+    }
+
+    #[test]
+    fn capacity_evicts_the_oldest_entry_first() {
+        let cache = TokenCache::new(TOKEN_CACHE_TTL, 3);
+        cache.insert("a", user("a"));
+        cache.insert("b", user("b"));
+        cache.insert("c", user("c"));
+        // Re-inserting `a` refreshes it, so `b` and then `c` go first
+        cache.insert("a", UserTier::Pro);
+        cache.insert("d", user("d"));
+        assert!(cache.get("b").is_none());
+        cache.insert("e", user("e"));
+        assert!(cache.get("c").is_none());
+        assert!(matches!(cache.get("a"), Some(UserTier::Pro)));
+        assert!(cache.get("d").is_some() && cache.get("e").is_some());
+
+        for i in 0..1000 {
+            cache.insert(&format!("flood-{i}"), UserTier::Pro);
+        }
+        assert_eq!(cache.stats().entries, 3);
+    }
+
+    #[test]
+    fn invalidation_by_token_and_user() {
+        let cache = TokenCache::new(TOKEN_CACHE_TTL, TOKEN_CACHE_CAPACITY);
+        cache.insert("a1", user("alice"));
+        cache.insert("a2", user("alice"));
+        cache.insert("b1", user("bob"));
+        cache.insert("pro", UserTier::Pro);
+
+        cache.invalidate_user("alice");
+        assert!(cache.get("a1").is_none() && cache.get("a2").is_none());
+        assert!(cache.get("b1").is_some());
+
+        cache.invalidate("b1");
+        assert!(cache.get("b1").is_none());
+        cache.clear();
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
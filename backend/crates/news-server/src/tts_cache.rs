@@ -1,32 +1,146 @@
 use crate::claude;
-use crate::routes::{cache_key, tts_generate, AppState};
+use crate::reading;
+use crate::routes::{
+    cache_key, runpod_voice_ids, tts_generate, tts_preview_key, AppState, TTS_PREVIEW_TEXT,
+    TTS_PREVIEW_TTL,
+};
+use crate::supervisor::Heartbeat;
+use news_core::models::Article;
+use news_core::text::truncate_bytes_floor;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::mpsc;
 use tracing::{info, warn};
 
-const DEFAULT_VOICE: &str = "qwen-tts:Japanese";
+pub(crate) const DEFAULT_VOICE: &str = "qwen-tts:Japanese";
 const ARTICLES_PER_CATEGORY: i64 = 5;
 const INTER_REQUEST_DELAY: Duration = Duration::from_secs(2);
 const AUDIO_TTL: i64 = 86400; // 24h
 const CYCLE_INTERVAL: Duration = Duration::from_secs(900); // 15 min
 const INITIAL_DELAY: Duration = Duration::from_secs(60); // 1 min warmup
 const TTS_TIMEOUT: Duration = Duration::from_secs(180); // 3 min (RunPod cold start can be slow)
+const DEFAULT_WARM_BUDGET: usize = 5;
 
-pub async fn run(state: Arc<AppState>) {
+/// Which freshly fetched articles get default-voice audio as soon as the fetcher
+/// stores them, instead of waiting for the next sweep. Set by `TTS_WARM_CATEGORIES`
+/// (default "general"), `TTS_WARM_SOURCES` (source names, default none) and
+/// `TTS_WARM_BUDGET` (generations per fetch cycle, default 5); lists are comma-separated.
+pub struct WarmConfig {
+    categories: Vec<String>,
+    sources: Vec<String>,
+    budget: usize,
+}
+
+impl WarmConfig {
+    pub fn from_env() -> Self {
+        let list = |name: &str, default: &str| -> Vec<String> {
+            std::env::var(name)
+                .unwrap_or_else(|_| default.into())
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+        Self {
+            categories: list("TTS_WARM_CATEGORIES", "general"),
+            sources: list("TTS_WARM_SOURCES", ""),
+            budget: std::env::var("TTS_WARM_BUDGET")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_WARM_BUDGET),
+        }
+    }
+
+    fn matches(&self, article: &Article) -> bool {
+        self.categories.iter().any(|c| c == article.category.as_str())
+            || self.sources.iter().any(|s| *s == article.source.to_lowercase())
+    }
+}
+
+/// Text that the pre-cache reads aloud for an article. The audio cache key is
+/// derived from this, so anything that looks up pre-generated audio must use it too.
+pub(crate) fn article_tts_text(article: &Article) -> String {
+    let desc = article.description.as_deref().unwrap_or("");
+    let raw_text = format!("{}。{}", article.title.trim(), desc.trim());
+    // 5000 bytes, on a char boundary; cache keys of shorter texts are unaffected
+    truncate_bytes_floor(&raw_text, 5000).to_string()
+}
+
+/// `fresh_rx` is shared so a restarted run picks up the same channel.
+#[tracing::instrument(name = "tts_cache", skip_all)]
+pub async fn run(
+    state: Arc<AppState>,
+    fresh_rx: Arc<tokio::sync::Mutex<mpsc::Receiver<Vec<Article>>>>,
+    warm: WarmConfig,
+    heartbeat: Heartbeat,
+) {
+    let mut fresh_rx = fresh_rx.lock().await;
     // Short warmup delay, then run first cycle quickly
-    tokio::time::sleep(INITIAL_DELAY).await;
+    let mut sweep = tokio::time::interval_at(tokio::time::Instant::now() + INITIAL_DELAY, CYCLE_INTERVAL);
+    sweep.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
     loop {
-        // Send a warmup request to wake RunPod GPU before the main cycle
-        warmup_runpod(&state).await;
+        tokio::select! {
+            // Fresh articles go ahead of a due sweep
+            biased;
+            Some(fresh) = fresh_rx.recv() => warm_fresh(&state, &warm, fresh).await,
+            _ = sweep.tick() => {
+                // Send a warmup request to wake RunPod GPU before the main cycle
+                warmup_runpod(&state).await;
+                warm_voice_previews(&state).await;
+
+                match run_cycle(&state).await {
+                    Ok(()) => heartbeat.beat(),
+                    Err(e) => warn!(error = %e, "TTS pre-generation cycle failed"),
+                }
+            }
+        }
+    }
+}
+
+/// Generate audio for the fetcher's newly inserted articles that match `warm`, newest
+/// first, up to `warm.budget` generations; the rest are left to the periodic sweep.
+async fn warm_fresh(state: &AppState, warm: &WarmConfig, mut fresh: Vec<Article>) {
+    if state.runpod_api_key.is_empty() || state.qwen_tts_endpoint_id.is_empty() {
+        return;
+    }
+    fresh.retain(|a| warm.matches(a));
+    fresh.sort_by_key(|a| std::cmp::Reverse(a.published_at));
 
-        if let Err(e) = run_cycle(&state).await {
-            warn!(error = %e, "TTS pre-generation cycle failed");
+    let (mut warmed, mut skipped_cached, mut skipped_budget, mut failed) = (0u64, 0u64, 0u64, 0u64);
+    for article in &fresh {
+        let raw_text = article_tts_text(article);
+        let audio_ckey = article_audio_key(&raw_text);
+        if let Ok(Some(_)) = state.db.get_cache(&audio_ckey) {
+            skipped_cached += 1;
+            continue;
+        }
+        if (warmed + failed) as usize >= warm.budget {
+            skipped_budget += 1;
+            continue;
+        }
+        if generate_article_audio(state, article, &raw_text, &audio_ckey).await {
+            warmed += 1;
+        } else {
+            failed += 1;
         }
-        tokio::time::sleep(CYCLE_INTERVAL).await;
+        tokio::time::sleep(INTER_REQUEST_DELAY).await;
+    }
+
+    state.metrics.tts_warm("warmed", warmed);
+    state.metrics.tts_warm("skipped_cached", skipped_cached);
+    state.metrics.tts_warm("skipped_budget", skipped_budget);
+    state.metrics.tts_warm("failed", failed);
+    if !fresh.is_empty() {
+        info!(warmed, skipped_cached, skipped_budget, failed, "TTS warmup of fresh articles complete");
     }
 }
 
+/// Audio cache key of an article's `article_tts_text` read by `DEFAULT_VOICE`.
+fn article_audio_key(raw_text: &str) -> String {
+    cache_key("tts_audio", &format!("{}|{}", DEFAULT_VOICE, raw_text))
+}
+
 /// Send a tiny TTS request to wake RunPod GPU, then wait for it to complete or timeout.
 async fn warmup_runpod(state: &AppState) {
     if state.runpod_api_key.is_empty() || state.qwen_tts_endpoint_id.is_empty() {
@@ -45,6 +159,27 @@ async fn warmup_runpod(state: &AppState) {
     }
 }
 
+/// Generate missing voice-picker previews for RunPod voices, which are never
+/// generated on demand because of cold starts. Runs right after the warmup.
+async fn warm_voice_previews(state: &AppState) {
+    for voice_id in runpod_voice_ids(state) {
+        let ckey = tts_preview_key(&voice_id);
+        if let Ok(Some(_)) = state.db.get_cache(&ckey) {
+            continue;
+        }
+        match tokio::time::timeout(TTS_TIMEOUT, tts_generate(state, &voice_id, TTS_PREVIEW_TEXT)).await {
+            Ok(Ok(bytes)) => {
+                let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
+                let _ = state.db.set_cache(&ckey, "tts_preview", &b64, TTS_PREVIEW_TTL);
+                info!(voice = %voice_id, "TTS pre-cache: generated voice preview");
+            }
+            Ok(Err(e)) => warn!(voice = %voice_id, error = %e, "TTS pre-cache: voice preview failed"),
+            Err(_) => warn!(voice = %voice_id, "TTS pre-cache: voice preview timed out"),
+        }
+        tokio::time::sleep(INTER_REQUEST_DELAY).await;
+    }
+}
+
 async fn run_cycle(state: &AppState) -> Result<(), String> {
     // Check that RunPod TTS is configured
     if state.runpod_api_key.is_empty() || state.qwen_tts_endpoint_id.is_empty() {
@@ -63,65 +198,17 @@ async fn run_cycle(state: &AppState) -> Result<(), String> {
     let mut failed = 0u32;
 
     for article in &articles {
-        let desc = article.description.as_deref().unwrap_or("");
-        let raw_text = format!("{}。{}", article.title.trim(), desc.trim());
-        // Truncate to 5000 chars (same limit as handle_tts)
-        let raw_text = if raw_text.len() > 5000 {
-            &raw_text[..5000]
-        } else {
-            &raw_text
-        };
-
-        // Check audio cache
-        let audio_ckey = cache_key("tts_audio", &format!("{}|{}", DEFAULT_VOICE, raw_text));
+        let raw_text = article_tts_text(article);
+        let audio_ckey = article_audio_key(&raw_text);
         if let Ok(Some(_)) = state.db.get_cache(&audio_ckey) {
             skipped += 1;
             continue;
         }
 
-        // Get or create reading conversion (qwen-tts engine for pre-cache)
-        let reading_ckey = cache_key("to_reading", &format!("qwen-tts|{}", raw_text));
-        let text = if let Ok(Some(cached_reading)) = state.db.get_cache(&reading_ckey) {
-            cached_reading
-        } else if !state.api_key.is_empty() {
-            match claude::convert_to_reading(&state.http_client, &state.api_key, raw_text, "qwen-tts").await {
-                Ok(reading) => {
-                    let _ = state.db.set_cache(&reading_ckey, "to_reading", &reading, AUDIO_TTL);
-                    reading
-                }
-                Err(e) => {
-                    warn!(article_id = %article.id, error = %e, "TTS pre-cache: reading conversion failed, using raw text");
-                    raw_text.to_string()
-                }
-            }
+        if generate_article_audio(state, article, &raw_text, &audio_ckey).await {
+            generated += 1;
         } else {
-            raw_text.to_string()
-        };
-
-        // Generate TTS audio with extended timeout for cold start
-        match tokio::time::timeout(
-            TTS_TIMEOUT,
-            tts_generate(state, DEFAULT_VOICE, &text),
-        )
-        .await
-        {
-            Ok(Ok(bytes)) => {
-                let b64 = base64::Engine::encode(
-                    &base64::engine::general_purpose::STANDARD,
-                    &bytes,
-                );
-                let _ = state.db.set_cache(&audio_ckey, "tts_audio", &b64, AUDIO_TTL);
-                generated += 1;
-                info!(article_id = %article.id, "TTS pre-cache: generated audio");
-            }
-            Ok(Err(e)) => {
-                warn!(article_id = %article.id, error = %e, "TTS pre-cache: generation failed");
-                failed += 1;
-            }
-            Err(_) => {
-                warn!(article_id = %article.id, "TTS pre-cache: generation timed out ({}s)", TTS_TIMEOUT.as_secs());
-                failed += 1;
-            }
+            failed += 1;
         }
 
         // Delay between requests to avoid overloading RunPod
@@ -137,3 +224,51 @@ async fn run_cycle(state: &AppState) -> Result<(), String> {
     );
     Ok(())
 }
+
+/// Convert `raw_text` to a reading and store its default-voice audio under
+/// `audio_ckey`. Returns whether audio was stored; failures are logged.
+async fn generate_article_audio(state: &AppState, article: &Article, raw_text: &str, audio_ckey: &str) -> bool {
+    // Get or create reading conversion (shared with on-demand TTS for the same profile)
+    let text = if !state.api_key.is_empty() {
+        let profile = reading::reading_profile(DEFAULT_VOICE);
+        match reading::cached_reading(&state.db, profile, raw_text, AUDIO_TTL, |engine| async move {
+            state.metrics.claude(claude::convert_to_reading(&state.claude, claude::ModelTier::Fast, raw_text, engine)).await.map(|g| g.value)
+        })
+        .await
+        {
+            Ok(reading) => reading,
+            Err(e) => {
+                warn!(article_id = %article.id, error = %e, "TTS pre-cache: reading conversion failed, using raw text");
+                raw_text.to_string()
+            }
+        }
+    } else {
+        raw_text.to_string()
+    };
+
+    // Generate TTS audio with extended timeout for cold start
+    match tokio::time::timeout(
+        TTS_TIMEOUT,
+        tts_generate(state, DEFAULT_VOICE, &text),
+    )
+    .await
+    {
+        Ok(Ok(bytes)) => {
+            let b64 = base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                &bytes,
+            );
+            let _ = state.db.set_cache(audio_ckey, "tts_audio", &b64, AUDIO_TTL);
+            info!(article_id = %article.id, "TTS pre-cache: generated audio");
+            true
+        }
+        Ok(Err(e)) => {
+            warn!(article_id = %article.id, error = %e, "TTS pre-cache: generation failed");
+            false
+        }
+        Err(_) => {
+            warn!(article_id = %article.id, "TTS pre-cache: generation timed out ({}s)", TTS_TIMEOUT.as_secs());
+            false
+        }
+    }
+}
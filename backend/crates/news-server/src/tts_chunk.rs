@@ -0,0 +1,148 @@
+/// Hard cap on TTS input. Anything longer is rejected rather than truncated.
+pub const MAX_TTS_CHARS: usize = 20_000;
+
+/// TTS provider for a voice_id: its prefix, or "elevenlabs" for bare voice ids.
+pub fn provider(voice_id: &str) -> &str {
+    voice_id.split_once(':').map(|(p, _)| p).unwrap_or("elevenlabs")
+}
+
+/// Largest chunk (in chars) a provider handles well, keyed by voice_id prefix.
+pub fn max_chunk_chars(voice_id: &str) -> usize {
+    match provider(voice_id) {
+        "openai" => 4000,
+        "cartesia" | "fish" | "aimlapi" => 2000,
+        "cosyvoice" | "qwen-tts" | "qwen-omni" => 2000,
+        // ElevenLabs (no prefix), Venice
+        _ => 1500,
+    }
+}
+
+/// Split text into chunks of at most `max_chars` chars, breaking after 。！？!? or a
+/// newline. A single sentence longer than `max_chars` is split on a char boundary.
+pub fn split_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for sentence in sentences(text) {
+        let len = sentence.chars().count();
+        if current_len + len > max_chars && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if len > max_chars {
+            let chars: Vec<char> = sentence.chars().collect();
+            for piece in chars.chunks(max_chars) {
+                chunks.push(piece.iter().collect());
+            }
+            continue;
+        }
+        current.push_str(sentence);
+        current_len += len;
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks.retain(|c| !c.trim().is_empty());
+    chunks
+}
+
+fn sentences(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if matches!(c, '。' | '！' | '？' | '!' | '?' | '\n') {
+            let end = i + c.len_utf8();
+            out.push(&text[start..end]);
+            start = end;
+        }
+    }
+    if start < text.len() {
+        out.push(&text[start..]);
+    }
+    out
+}
+
+/// Concatenate MP3 chunks, dropping the ID3v2 header from every chunk but the first
+/// and the ID3v1 trailer from every chunk but the last.
+pub fn concat_mp3(chunks: &[axum::body::Bytes]) -> axum::body::Bytes {
+    if chunks.len() == 1 {
+        return chunks[0].clone();
+    }
+    let mut out = Vec::with_capacity(chunks.iter().map(|c| c.len()).sum());
+    let last = chunks.len() - 1;
+    for (i, chunk) in chunks.iter().enumerate() {
+        let mut data: &[u8] = chunk;
+        if i > 0 {
+            data = &data[id3v2_len(data).min(data.len())..];
+        }
+        if i < last && data.len() >= 128 && &data[data.len() - 128..data.len() - 125] == b"TAG" {
+            data = &data[..data.len() - 128];
+        }
+        out.extend_from_slice(data);
+    }
+    axum::body::Bytes::from(out)
+}
+
+/// Length of a leading ID3v2 tag (header + body + optional footer), or 0.
+pub(crate) fn id3v2_len(data: &[u8]) -> usize {
+    if data.len() < 10 || &data[..3] != b"ID3" {
+        return 0;
+    }
+    // Tag size is a 28-bit syncsafe integer (7 bits per byte)
+    let size = data[6..10]
+        .iter()
+        .fold(0usize, |acc, b| (acc << 7) | (*b as usize & 0x7f));
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    10 + size + footer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+
+    #[test]
+    fn short_text_is_one_chunk() {
+        assert_eq!(split_chunks("こんにちは。今日のニュースです。", 1500).len(), 1);
+    }
+
+    #[test]
+    fn splits_on_sentence_boundaries() {
+        let sentence = "あ".repeat(99) + "。";
+        let text = sentence.repeat(40); // 4000 chars
+        let chunks = split_chunks(&text, 1500);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 1500 && c.ends_with('。')));
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn overlong_sentence_is_hard_split() {
+        let text = "い".repeat(3500);
+        let chunks = split_chunks(&text, 1500);
+        assert_eq!(chunks.iter().map(|c| c.chars().count()).collect::<Vec<_>>(), vec![1500, 1500, 500]);
+    }
+
+    #[test]
+    fn provider_limits() {
+        assert_eq!(max_chunk_chars("21m00Tcm4TlvDq8ikWAM"), 1500);
+        assert_eq!(max_chunk_chars("openai:nova"), 4000);
+        assert_eq!(max_chunk_chars("venice:af_sky"), 1500);
+    }
+
+    #[test]
+    fn strips_id3_from_later_chunks() {
+        let mut first = b"ID3\x04\x00\x00\x00\x00\x00\x02ab".to_vec();
+        first.extend_from_slice(&[0xff, 0xfb, 1]);
+        let mut second = b"ID3\x04\x00\x00\x00\x00\x00\x03xyz".to_vec();
+        second.extend_from_slice(&[0xff, 0xfb, 2]);
+
+        let out = concat_mp3(&[Bytes::from(first.clone()), Bytes::from(second)]);
+        let mut expected = first;
+        expected.extend_from_slice(&[0xff, 0xfb, 2]);
+        assert_eq!(out.as_ref(), expected.as_slice());
+    }
+}
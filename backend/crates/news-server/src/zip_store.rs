@@ -0,0 +1,123 @@
+/// Build an in-memory ZIP archive with every file stored uncompressed (method 0).
+/// Audio is already compressed, so deflating it would only cost CPU. No ZIP64, so the
+/// archive has to stay under 4 GiB and 65 535 entries.
+pub fn build(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    // Bit 11: file names are UTF-8
+    const FLAGS: u16 = 0x0800;
+    // 1980-01-01 00:00 in DOS format
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = (1 << 5) | 1;
+
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in files {
+        let crc = crc32fast::hash(data);
+        let offset = out.len() as u32;
+
+        put32(&mut out, 0x0403_4b50);
+        put16(&mut out, 20);
+        put16(&mut out, FLAGS);
+        put16(&mut out, 0);
+        put16(&mut out, DOS_TIME);
+        put16(&mut out, DOS_DATE);
+        put32(&mut out, crc);
+        put32(&mut out, data.len() as u32);
+        put32(&mut out, data.len() as u32);
+        put16(&mut out, name.len() as u16);
+        put16(&mut out, 0);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        put32(&mut central, 0x0201_4b50);
+        put16(&mut central, 20);
+        put16(&mut central, 20);
+        put16(&mut central, FLAGS);
+        put16(&mut central, 0);
+        put16(&mut central, DOS_TIME);
+        put16(&mut central, DOS_DATE);
+        put32(&mut central, crc);
+        put32(&mut central, data.len() as u32);
+        put32(&mut central, data.len() as u32);
+        put16(&mut central, name.len() as u16);
+        // Extra field, comment, disk number, internal and external attributes
+        put16(&mut central, 0);
+        put16(&mut central, 0);
+        put16(&mut central, 0);
+        put16(&mut central, 0);
+        put32(&mut central, 0);
+        put32(&mut central, offset);
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    let central_size = central.len() as u32;
+    out.extend_from_slice(&central);
+    put32(&mut out, 0x0605_4b50);
+    put16(&mut out, 0);
+    put16(&mut out, 0);
+    put16(&mut out, files.len() as u16);
+    put16(&mut out, files.len() as u16);
+    put32(&mut out, central_size);
+    put32(&mut out, central_offset);
+    put16(&mut out, 0);
+    out
+}
+
+fn put16(out: &mut Vec<u8>, v: u16) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+fn put32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(b: &[u8], i: usize) -> u16 {
+        u16::from_le_bytes([b[i], b[i + 1]])
+    }
+
+    fn u32_at(b: &[u8], i: usize) -> u32 {
+        u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]])
+    }
+
+    #[test]
+    fn archive_layout_is_readable() {
+        let files = vec![
+            ("a.mp3".to_string(), b"hello".to_vec()),
+            ("b.mp3".to_string(), vec![0xff; 300]),
+        ];
+        let zip = build(&files);
+
+        // End of central directory record is the last 22 bytes
+        let eocd = zip.len() - 22;
+        assert_eq!(u32_at(&zip, eocd), 0x0605_4b50);
+        assert_eq!(u16_at(&zip, eocd + 10), 2);
+        let central_offset = u32_at(&zip, eocd + 16) as usize;
+
+        // Walk the central directory to each local header and its data
+        let mut pos = central_offset;
+        for (name, data) in &files {
+            assert_eq!(u32_at(&zip, pos), 0x0201_4b50);
+            assert_eq!(u32_at(&zip, pos + 16), crc32fast::hash(data));
+            let name_len = u16_at(&zip, pos + 28) as usize;
+            assert_eq!(&zip[pos + 46..pos + 46 + name_len], name.as_bytes());
+            let local = u32_at(&zip, pos + 42) as usize;
+            assert_eq!(u32_at(&zip, local), 0x0403_4b50);
+            let data_start = local + 30 + name_len;
+            assert_eq!(&zip[data_start..data_start + data.len()], data.as_slice());
+            pos += 46 + name_len;
+        }
+        assert_eq!(pos, eocd);
+        assert_eq!(crc32fast::hash(b"hello"), 0x3610_a686);
+    }
+
+    #[test]
+    fn empty_archive_is_just_the_end_record() {
+        let zip = build(&[]);
+        assert_eq!(zip.len(), 22);
+        assert_eq!(u32_at(&zip, 0), 0x0605_4b50);
+    }
+}
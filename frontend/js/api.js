@@ -93,7 +93,7 @@ const Api = (() => {
     if (res.status === 402) {
       const data = await res.json().catch(() => ({}));
       if (typeof Subscription !== 'undefined') {
-        Subscription.showUpgradePrompt(feature, data.limit || '?', data.tier || 'free');
+        Subscription.showUpgradePrompt(feature, data.details?.limit || '?', data.details?.tier || 'free');
       }
       throw new Error(data.message || 'rate_limit_exceeded');
     }
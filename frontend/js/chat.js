@@ -223,8 +223,8 @@ const Chat = (() => {
     try {
       const data = await Api.summarizeArticles(minutes);
       removeThinking();
-      if (data.error) {
-        addMessage(`エラー: ${data.error}`, 'bot');
+      if (data.code) {
+        addMessage(`エラー: ${data.message}`, 'bot');
         return;
       }
       addMessage(data.summary, 'bot');
@@ -92,9 +92,9 @@ const GoogleAuth = (() => {
 
       if (!res.ok) {
         const err = await res.json().catch(() => ({}));
-        console.error('Google auth failed:', err.error);
+        console.error('Google auth failed:', err.message);
         if (typeof Chat !== 'undefined') {
-          Chat.addMessage('Googleログインに失敗しました: ' + (err.error || ''), 'bot');
+          Chat.addMessage('Googleログインに失敗しました: ' + (err.message || ''), 'bot');
         }
         return;
       }
@@ -182,7 +182,7 @@
         loadFeeds();
       } else {
         const data = await res.json().catch(() => ({}));
-        toast(data.error || 'Failed to add feed');
+        toast(data.message || 'Failed to add feed');
       }
     } catch(e) { toast('Error: ' + e.message); }
   });
@@ -470,7 +470,7 @@
     fileInput.addEventListener('change', e => {
       const file = e.target.files[0];
       if (!file) return;
-      if (file.size > 5 * 1024 * 1024) { toast('ファイルが大きすぎます (5MB以下)'); return; }
+      if (file.size > 2 * 1024 * 1024) { toast('ファイルが大きすぎます (2MB以下)'); return; }
       processAudioBlob(file);
       e.target.value = '';
     });